@@ -4,6 +4,7 @@ use anyhow::anyhow;
 use fj_host::Parameters;
 use fj_kernel::algorithms::Tolerance;
 use fj_math::Scalar;
+use fj_operations::shape_processor::CaptureLevel;
 
 /// Fornjot - Experimental CAD System
 #[derive(clap::Parser)]
@@ -16,6 +17,10 @@ pub struct Args {
     #[clap(short, long)]
     pub export: Option<PathBuf>,
 
+    /// Export the model, even if its mesh is not watertight
+    #[clap(long)]
+    pub allow_open: bool,
+
     /// Parameters for the model, each in the form `key=value`
     #[clap(short, long, parse(try_from_str = parse_parameters))]
     pub parameters: Option<Parameters>,
@@ -23,6 +28,16 @@ pub struct Args {
     /// Model deviation tolerance
     #[clap[short, long, parse(try_from_str = parse_tolerance)]]
     pub tolerance: Option<Tolerance>,
+
+    /// How much debug info to capture: `off`, `summary`, `sampled=N` (every
+    /// Nth check), or `full`
+    #[clap(long, parse(try_from_str = parse_capture_level))]
+    pub debug_capture: Option<CaptureLevel>,
+
+    /// Print an estimate of the memory used by each stage of processing the
+    /// model
+    #[clap(long)]
+    pub memory_report: bool,
 }
 
 impl Args {
@@ -65,3 +80,24 @@ fn parse_tolerance(input: &str) -> anyhow::Result<Tolerance> {
 
     Ok(tolerance)
 }
+
+fn parse_capture_level(input: &str) -> anyhow::Result<CaptureLevel> {
+    let level = match input.trim() {
+        "off" => CaptureLevel::Off,
+        "summary" => CaptureLevel::Summary,
+        "full" => CaptureLevel::Full,
+        input => {
+            let every_nth = input
+                .strip_prefix("sampled=")
+                .ok_or_else(|| anyhow!("Unknown debug capture level: {input}"))?
+                .parse()?;
+            if every_nth == 0 {
+                return Err(anyhow!("Sampling interval must be positive"));
+            }
+
+            CaptureLevel::SampledDetail { every_nth }
+        }
+    };
+
+    Ok(level)
+}
@@ -15,14 +15,17 @@
 mod args;
 mod config;
 
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
 use anyhow::{anyhow, Context as _};
-use fj_export::export;
+use fj_export::{export_with_options, ExportOptions};
 use fj_host::{Model, Parameters};
-use fj_operations::shape_processor::ShapeProcessor;
+use fj_kernel::algorithms::EffortBudget;
+use fj_operations::shape_processor::{
+    memory_table, DebugConfig, ShapeProcessor,
+};
 use fj_window::run::run;
-use tracing_subscriber::fmt::format;
+use tracing_subscriber::fmt::{format, format::FmtSpan};
 use tracing_subscriber::EnvFilter;
 
 use crate::{args::Args, config::Config};
@@ -33,11 +36,15 @@ fn main() -> anyhow::Result<()> {
     //
     // It would be better to fail, if `RUST_LOG` is erroneous, but I don't know
     // how to distinguish between that and the "not defined" case.
+    //
+    // If the `tracing` feature is enabled, the evaluation of the model is
+    // instrumented with spans. Their durations are logged when they close.
     tracing_subscriber::fmt()
         .with_env_filter(
             EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| EnvFilter::new("WARN")),
         )
+        .with_span_events(FmtSpan::CLOSE)
         .event_format(format().pretty())
         .init();
 
@@ -57,19 +64,47 @@ fn main() -> anyhow::Result<()> {
         .with_context(|| format!("Failed to load model: {}", path.display()))?;
     let parameters = args.parameters.unwrap_or_else(Parameters::empty);
 
+    let mut debug_config = DebugConfig::default();
+    if let Some(level) = args.debug_capture {
+        debug_config.level = level;
+    }
+
     let shape_processor = ShapeProcessor {
         tolerance: args.tolerance,
+        debug_config,
+        ..ShapeProcessor::default()
     };
 
     if let Some(path) = args.export {
         let shape = model.load_once(&parameters)?;
         let shape = shape_processor.process(&shape)?;
 
-        export(&shape.mesh, &path)?;
+        if args.memory_report {
+            println!("{}", memory_table(&shape.memory));
+        }
+
+        let options = ExportOptions {
+            allow_open: args.allow_open,
+            materials: shape.materials,
+            ..ExportOptions::default()
+        };
+        export_with_options(&shape.mesh, &path, &options)?;
 
         return Ok(());
     }
 
+    // The viewer needs to stay responsive, even if the tolerance is too fine
+    // for the model. Export always uses the requested tolerance.
+    let shape_processor = ShapeProcessor {
+        effort_budget: EffortBudget {
+            max_points_per_face: Some(100_000),
+            max_total_triangles: Some(2_000_000),
+            deadline: None,
+        },
+        time_budget: Some(Duration::from_secs(5)),
+        ..shape_processor
+    };
+
     let watcher = model.load_and_watch(parameters)?;
     run(watcher, shape_processor)?;
 
@@ -0,0 +1,117 @@
+use std::{fs::File, io::Write, path::Path};
+
+use fj_interop::{material::Materials, mesh::Mesh};
+use fj_math::{Point, Scalar};
+
+use crate::{group_by_material, Error};
+
+const FLOAT: u32 = 5126;
+const ARRAY_BUFFER: u32 = 34962;
+
+/// Export a mesh to glTF, with one primitive per material
+///
+/// The file is written as JSON, with the binary data embedded. Every material
+/// is exported as a named glTF material. Material metadata ends up in the
+/// material's `extras`.
+pub fn export_gltf(
+    mesh: &Mesh<Point<3>>,
+    materials: &Materials,
+    path: &Path,
+) -> Result<(), Error> {
+    let mut buffer = Vec::new();
+    let mut buffer_views = Vec::new();
+    let mut accessors = Vec::new();
+    let mut primitives = Vec::new();
+    let mut gltf_materials = Vec::new();
+
+    for (i, (material, triangles)) in
+        group_by_material(mesh, materials).into_iter().enumerate()
+    {
+        let points: Vec<_> = triangles
+            .iter()
+            .flat_map(|triangle| triangle.points)
+            .collect();
+
+        let byte_offset = buffer.len();
+        for point in &points {
+            for component in point.coords.components {
+                buffer.extend_from_slice(&component.into_f32().to_le_bytes());
+            }
+        }
+
+        buffer_views.push(serde_json::json!({
+            "buffer": 0,
+            "byteOffset": byte_offset,
+            "byteLength": buffer.len() - byte_offset,
+            "target": ARRAY_BUFFER,
+        }));
+
+        // glTF requires the bounds of position accessors.
+        let [min, max] = bounds(&points);
+        accessors.push(serde_json::json!({
+            "bufferView": i,
+            "componentType": FLOAT,
+            "count": points.len(),
+            "type": "VEC3",
+            "min": min,
+            "max": max,
+        }));
+
+        primitives.push(serde_json::json!({
+            "attributes": { "POSITION": i },
+            "material": i,
+        }));
+
+        let material = &materials.materials()[material];
+        let [r, g, b, a] = material.display_color.map(|c| f32::from(c) / 255.);
+        gltf_materials.push(serde_json::json!({
+            "name": material.name,
+            "pbrMetallicRoughness": {
+                "baseColorFactor": [r, g, b, a],
+            },
+            "alphaMode": if a < 1. { "BLEND" } else { "OPAQUE" },
+            "doubleSided": mesh.is_double_sided(),
+            "extras": material.metadata,
+        }));
+    }
+
+    let gltf = serde_json::json!({
+        "asset": { "version": "2.0", "generator": "Fornjot" },
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [{ "mesh": 0 }],
+        "meshes": [{ "primitives": primitives }],
+        "materials": gltf_materials,
+        "accessors": accessors,
+        "bufferViews": buffer_views,
+        "buffers": [{
+            "byteLength": buffer.len(),
+            "uri": format!(
+                "data:application/octet-stream;base64,{}",
+                base64::encode(&buffer)
+            ),
+        }],
+    });
+
+    let mut file = File::create(path)?;
+    serde_json::to_writer(&mut file, &gltf)?;
+    file.flush()?;
+
+    Ok(())
+}
+
+fn bounds(points: &[Point<3>]) -> [[f32; 3]; 2] {
+    let mut min = [Scalar::MAX; 3];
+    let mut max = [-Scalar::MAX; 3];
+
+    for point in points {
+        for (i, &component) in point.coords.components.iter().enumerate() {
+            if component < min[i] {
+                min[i] = component;
+            }
+            max[i] = max[i].max(component);
+        }
+    }
+
+    [min, max].map(|bound| bound.map(|component| component.into_f32()))
+}
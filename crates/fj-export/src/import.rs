@@ -0,0 +1,653 @@
+use std::{
+    io::{self, BufRead, BufReader, Read},
+    ops::ControlFlow,
+};
+
+use thiserror::Error;
+
+use fj_interop::mesh::{Color, Mesh};
+use fj_kernel::objects::Face;
+use fj_math::{Aabb, Point, Scalar, Triangle};
+
+/// The size of the header of a binary STL file, including the triangle count
+const HEADER_SIZE: usize = 84;
+
+/// The size of a single triangle in a binary STL file
+const TRIANGLE_SIZE: usize = 50;
+
+/// The number of bytes at the start of a file, that its format is detected by
+const PEEK_SIZE: usize = 512;
+
+/// The color of imported triangles
+///
+/// STL files don't have a standard way to specify colors.
+const COLOR: Color = [255, 0, 0, 255];
+
+/// Stream the triangles of an STL file to a visitor
+///
+/// Both binary and ASCII STL files are supported. The file is parsed
+/// incrementally, one triangle at a time, so memory use is independent of the
+/// size of the file. The visitor can stop the import early, by returning
+/// [`ControlFlow::Break`].
+///
+/// ASCII files start with `solid`, but so do the headers of many binary files.
+/// A file is only parsed as ASCII, if its start consists of text, and contains
+/// a `facet` or `endsolid` keyword.
+///
+/// Degenerate triangles, which don't span any area, are not passed to the
+/// visitor. They are counted in the returned [`StlStats`].
+///
+/// The reader is buffered internally. There is no need to wrap it into a
+/// [`BufReader`].
+pub fn stream_stl(
+    reader: impl Read,
+    visitor: impl FnMut(Triangle<3>) -> ControlFlow<()>,
+) -> Result<StlStats, ImportError> {
+    let mut reader = BufReader::new(reader);
+
+    match detect_format(&mut reader)? {
+        Format::Binary => {
+            let num_triangles = read_header(&mut reader)?;
+            stream_binary(&mut reader, num_triangles, visitor)
+        }
+        Format::Ascii => stream_ascii(&mut reader, visitor),
+    }
+}
+
+fn stream_binary(
+    reader: &mut impl BufRead,
+    num_triangles: u32,
+    mut visitor: impl FnMut(Triangle<3>) -> ControlFlow<()>,
+) -> Result<StlStats, ImportError> {
+    let mut stats = StlStats::default();
+
+    let mut buffer = [0; TRIANGLE_SIZE];
+    for _ in 0..num_triangles {
+        if let Err(err) = reader.read_exact(&mut buffer) {
+            if err.kind() == io::ErrorKind::UnexpectedEof {
+                return Err(ImportError::Truncated {
+                    expected: num_triangles,
+                    read: stats.triangles,
+                });
+            }
+            return Err(err.into());
+        }
+        stats.triangles += 1;
+
+        // The first three values are the normal, which is ignored. The
+        // orientation of the triangle is defined by the order of its points.
+        let mut coords = [Scalar::ZERO; 9];
+        for (i, coord) in coords.iter_mut().enumerate() {
+            let start = 12 + 4 * i;
+            let value = f32::from_le_bytes([
+                buffer[start],
+                buffer[start + 1],
+                buffer[start + 2],
+                buffer[start + 3],
+            ]);
+            if !value.is_finite() {
+                return Err(ImportError::Malformed {
+                    line: stats.triangles as usize,
+                    reason: "invalid vertex coordinate".into(),
+                });
+            }
+            *coord = Scalar::from_f64(value.into());
+        }
+        let points = [0, 3, 6]
+            .map(|i| Point::from([coords[i], coords[i + 1], coords[i + 2]]));
+
+        if stats.visit(points, &mut visitor).is_break() {
+            break;
+        }
+    }
+
+    Ok(stats)
+}
+
+fn stream_ascii(
+    reader: &mut impl BufRead,
+    mut visitor: impl FnMut(Triangle<3>) -> ControlFlow<()>,
+) -> Result<StlStats, ImportError> {
+    let mut stats = StlStats::default();
+
+    let mut line = Vec::new();
+    let mut line_number = 0;
+
+    // The vertices of the current facet, if within one
+    let mut facet: Option<Vec<Point<3>>> = None;
+
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        line_number += 1;
+
+        let malformed = |reason: &str| ImportError::Malformed {
+            line: line_number,
+            reason: reason.into(),
+        };
+
+        let text = std::str::from_utf8(&line)
+            .map_err(|_| malformed("line is not valid text"))?;
+        let mut tokens = text.split_whitespace();
+
+        let keyword = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+
+        match (keyword, &mut facet) {
+            ("solid" | "endsolid", None) => {}
+            ("facet", None) => facet = Some(Vec::with_capacity(3)),
+            ("outer" | "endloop", Some(_)) => {}
+            ("vertex", Some(vertices)) => {
+                if vertices.len() == 3 {
+                    return Err(malformed("facet has more than 3 vertices"));
+                }
+
+                let mut coords = [Scalar::ZERO; 3];
+                for coord in &mut coords {
+                    let value = tokens
+                        .next()
+                        .and_then(|token| token.parse::<f64>().ok())
+                        .filter(|value| value.is_finite())
+                        .ok_or_else(|| {
+                            malformed("invalid vertex coordinate")
+                        })?;
+                    *coord = Scalar::from_f64(value);
+                }
+
+                vertices.push(Point::from(coords));
+            }
+            ("endfacet", Some(vertices)) => {
+                let points: [Point<3>; 3] = vertices
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| malformed("facet has less than 3 vertices"))?;
+                facet = None;
+
+                stats.triangles += 1;
+                if stats.visit(points, &mut visitor).is_break() {
+                    return Ok(stats);
+                }
+            }
+            (keyword, _) => {
+                return Err(malformed(&format!("unexpected `{keyword}`")));
+            }
+        }
+    }
+
+    if facet.is_some() {
+        return Err(ImportError::Malformed {
+            line: line_number,
+            reason: "file ended within a facet".into(),
+        });
+    }
+
+    Ok(stats)
+}
+
+/// Import all triangles of an STL file into a mesh
+///
+/// See [`stream_stl`] for details.
+pub fn import_stl(reader: impl Read) -> Result<Mesh<Point<3>>, ImportError> {
+    let mut mesh = Mesh::new();
+
+    stream_stl(reader, |triangle| {
+        mesh.push_triangle(triangle.points(), COLOR);
+        ControlFlow::Continue(())
+    })?;
+
+    Ok(mesh)
+}
+
+/// Import all triangles of an STL file into a single face
+///
+/// The face is defined by triangles, which have the default color. See
+/// [`stream_stl`] for details.
+pub fn import_stl_face(reader: impl Read) -> Result<Face, ImportError> {
+    let mut triangles = Vec::new();
+
+    stream_stl(reader, |triangle| {
+        triangles.push((triangle, COLOR));
+        ControlFlow::Continue(())
+    })?;
+
+    Ok(Face::from_triangles(triangles))
+}
+
+/// Import a reduced number of triangles from an STL file
+///
+/// Keeps every n-th triangle, such that the resulting mesh has at most
+/// `target_triangles` triangles. This doesn't preserve the surface of the
+/// mesh, but it is a cheap way to preview very large files.
+///
+/// Only binary files specify the number of triangles up front. ASCII files are
+/// imported completely.
+///
+/// See [`stream_stl`] for details.
+pub fn import_stl_decimated(
+    reader: impl Read,
+    target_triangles: usize,
+) -> Result<Mesh<Point<3>>, ImportError> {
+    let mut mesh = Mesh::new();
+    if target_triangles == 0 {
+        return Ok(mesh);
+    }
+
+    let mut reader = BufReader::new(reader);
+
+    // The number of triangles is required to determine which ones to keep, so
+    // read the header first.
+    let num_triangles = match detect_format(&mut reader)? {
+        Format::Binary => Some(read_header(&mut reader)?),
+        Format::Ascii => None,
+    };
+    let stride = match num_triangles {
+        Some(num_triangles) => {
+            (num_triangles as usize + target_triangles - 1) / target_triangles
+        }
+        None => 1,
+    };
+    let stride = stride.max(1);
+
+    let mut index = 0;
+    let visitor = |triangle: Triangle<3>| {
+        if index % stride == 0 {
+            mesh.push_triangle(triangle.points(), COLOR);
+        }
+        index += 1;
+
+        ControlFlow::Continue(())
+    };
+
+    match num_triangles {
+        Some(num_triangles) => {
+            stream_binary(&mut reader, num_triangles, visitor)?
+        }
+        None => stream_ascii(&mut reader, visitor)?,
+    };
+
+    Ok(mesh)
+}
+
+/// Compute the bounding box of a binary STL file
+///
+/// Returns `None`, if the file doesn't contain any non-degenerate triangles.
+/// See [`stream_stl`] for details.
+pub fn stl_aabb(reader: impl Read) -> Result<Option<Aabb<3>>, ImportError> {
+    let stats = stream_stl(reader, |_| ControlFlow::Continue(()))?;
+    Ok(stats.aabb)
+}
+
+/// Statistics about the triangles read by [`stream_stl`]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct StlStats {
+    /// The number of triangles read, including degenerate ones
+    pub triangles: u32,
+
+    /// The number of degenerate triangles, which were not passed on
+    pub degenerate: u32,
+
+    /// The bounding box of all non-degenerate triangles
+    ///
+    /// `None`, if there were no such triangles.
+    pub aabb: Option<Aabb<3>>,
+}
+
+impl StlStats {
+    /// Pass a triangle on to a visitor, unless it is degenerate
+    fn visit(
+        &mut self,
+        points: [Point<3>; 3],
+        visitor: &mut impl FnMut(Triangle<3>) -> ControlFlow<()>,
+    ) -> ControlFlow<()> {
+        if is_degenerate(points) {
+            self.degenerate += 1;
+            return ControlFlow::Continue(());
+        }
+
+        self.include(points);
+        visitor(Triangle::from_points(points))
+    }
+
+    fn include(&mut self, points: [Point<3>; 3]) {
+        for point in points {
+            self.aabb = Some(match self.aabb {
+                Some(aabb) => aabb.include_point(&point),
+                None => Aabb {
+                    min: point,
+                    max: point,
+                },
+            });
+        }
+    }
+}
+
+fn is_degenerate([a, b, c]: [Point<3>; 3]) -> bool {
+    (b - a).cross(&(c - a)).magnitude() == Scalar::ZERO
+}
+
+/// The format of an STL file
+enum Format {
+    Binary,
+    Ascii,
+}
+
+/// Detect the format of an STL file, without consuming any of it
+fn detect_format(reader: &mut impl BufRead) -> io::Result<Format> {
+    let start = reader.fill_buf()?;
+    let start = &start[..start.len().min(PEEK_SIZE)];
+
+    let is_text = start
+        .iter()
+        .all(|byte| byte.is_ascii_graphic() || byte.is_ascii_whitespace());
+    let contains = |keyword: &[u8]| {
+        start.windows(keyword.len()).any(|window| window == keyword)
+    };
+
+    let text = match start.iter().position(|byte| !byte.is_ascii_whitespace()) {
+        Some(offset) => &start[offset..],
+        None => &[],
+    };
+    if text.starts_with(b"solid")
+        && is_text
+        && (contains(b"facet") || contains(b"endsolid"))
+    {
+        Ok(Format::Ascii)
+    } else {
+        Ok(Format::Binary)
+    }
+}
+
+/// Read the header of a binary STL file, returning the number of triangles
+fn read_header(reader: &mut impl Read) -> io::Result<u32> {
+    let mut header = [0; HEADER_SIZE];
+    reader.read_exact(&mut header)?;
+
+    Ok(u32::from_le_bytes([
+        header[80], header[81], header[82], header[83],
+    ]))
+}
+
+/// An error that can occur while importing
+#[derive(Debug, Error)]
+pub enum ImportError {
+    /// I/O error whilst importing from file
+    #[error("I/O error whilst importing from file")]
+    Io(#[from] io::Error),
+
+    /// The file doesn't follow the format
+    #[error("line {line}: {reason}")]
+    Malformed {
+        /// The number of the line that caused the error, starting at 1
+        ///
+        /// Binary files don't have lines. For those, this is the number of the
+        /// triangle that caused the error.
+        line: usize,
+
+        /// What is wrong with the line
+        reason: String,
+    },
+
+    /// The file ended before all triangles were read
+    #[error("file ended after {read} of {expected} triangles")]
+    Truncated {
+        /// The number of triangles specified in the header
+        expected: u32,
+
+        /// The number of triangles that were actually read
+        read: u32,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Read, ops::ControlFlow};
+
+    use fj_math::{Point, Triangle};
+
+    use super::{
+        import_stl, import_stl_decimated, import_stl_face, stream_stl,
+        ImportError, HEADER_SIZE, TRIANGLE_SIZE,
+    };
+
+    #[test]
+    fn large_file() -> Result<(), ImportError> {
+        let num_triangles = 1_000_000;
+
+        // Only keep the most recent triangles, to show that nothing else
+        // needs to be kept around.
+        let mut recent = [None; 16];
+        let mut index = 0;
+
+        let stats = stream_stl(GeneratedStl::new(num_triangles), |triangle| {
+            recent[index % recent.len()] = Some(triangle);
+            index += 1;
+            ControlFlow::Continue(())
+        })?;
+
+        assert_eq!(stats.triangles, num_triangles);
+        assert_eq!(stats.degenerate, 0);
+        assert_eq!(index, num_triangles as usize);
+
+        let aabb = stats.aabb.expect("Expected bounding box");
+        assert_eq!(aabb.min, Point::from([0., 0., 0.]));
+        assert_eq!(aabb.max, Point::from([num_triangles as f64, 1., 1.]));
+
+        let last = num_triangles as usize - 1;
+        assert_eq!(
+            recent[last % recent.len()],
+            Some(GeneratedStl::triangle(last))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn early_termination() -> Result<(), ImportError> {
+        let mut reader = GeneratedStl::new(1_000_000);
+
+        let mut visited = 0;
+        let stats = stream_stl(&mut reader, |_| {
+            visited += 1;
+            if visited == 10 {
+                return ControlFlow::Break(());
+            }
+            ControlFlow::Continue(())
+        })?;
+
+        assert_eq!(stats.triangles, 10);
+        assert_eq!(visited, 10);
+
+        // Only the internal buffer was filled beyond the triangles read.
+        assert!(reader.position < HEADER_SIZE + TRIANGLE_SIZE * 10 + 8 * 1024);
+
+        Ok(())
+    }
+
+    #[test]
+    fn decimated() -> Result<(), ImportError> {
+        let mesh = import_stl_decimated(GeneratedStl::new(1000), 100)?;
+        assert_eq!(mesh.triangles().count(), 100);
+
+        Ok(())
+    }
+
+    #[test]
+    fn ascii() -> Result<(), ImportError> {
+        let file = "\
+            solid tetrahedron
+              facet normal 0 0 -1
+                outer loop
+                  vertex 0 0 0
+                  vertex 0 1 0
+                  vertex 1 0 0
+                endloop
+              endfacet
+              facet normal 0 0 0
+                outer loop
+                  vertex 0 0 0
+                  vertex 1 0 0
+                  vertex 2 0 0
+                endloop
+              endfacet
+              facet normal 1 1 1
+                outer loop
+                  vertex 1 0 0
+                  vertex 0 1 0
+                  vertex 0 0 1.5e0
+                endloop
+              endfacet
+            endsolid tetrahedron
+        ";
+
+        let mut triangles = Vec::new();
+        let stats = stream_stl(file.as_bytes(), |triangle| {
+            triangles.push(triangle);
+            ControlFlow::Continue(())
+        })?;
+
+        assert_eq!(stats.triangles, 3);
+        assert_eq!(stats.degenerate, 1);
+        assert_eq!(
+            triangles,
+            [
+                Triangle::from_points([
+                    [0., 0., 0.],
+                    [0., 1., 0.],
+                    [1., 0., 0.]
+                ]),
+                Triangle::from_points([
+                    [1., 0., 0.],
+                    [0., 1., 0.],
+                    [0., 0., 1.5]
+                ]),
+            ]
+        );
+
+        let face = import_stl_face(file.as_bytes())?;
+        assert_eq!(face.triangles().map(Vec::len), Some(2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn binary_with_solid_header() -> Result<(), ImportError> {
+        let mut file = Vec::new();
+        GeneratedStl::new(3).read_to_end(&mut file)?;
+        file[..18].copy_from_slice(b"solid facet export");
+
+        let mesh = import_stl(file.as_slice())?;
+        assert_eq!(mesh.triangles().count(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_ascii() {
+        let missing_vertex = "\
+            solid broken
+              facet normal 0 0 1
+                outer loop
+                  vertex 0 0 0
+                  vertex 1 0 0
+                endloop
+              endfacet
+            endsolid broken
+        ";
+        let invalid_coordinate = "\
+            solid broken
+              facet normal 0 0 1
+                outer loop
+                  vertex 0 0 zero
+        ";
+        let unterminated = "\
+            solid broken
+              facet normal 0 0 1
+                outer loop
+        ";
+
+        for (file, expected) in [
+            (missing_vertex, 7),
+            (invalid_coordinate, 4),
+            (unterminated, 4),
+        ] {
+            let result = import_stl(file.as_bytes());
+            assert!(matches!(
+                result,
+                Err(ImportError::Malformed { line, .. }) if line == expected
+            ));
+        }
+    }
+
+    /// A binary STL file that is generated while it is read
+    ///
+    /// Triangle `i` is located at `x = i`, which makes the bounding box of the
+    /// whole file easy to predict.
+    struct GeneratedStl {
+        num_triangles: u32,
+        position: usize,
+    }
+
+    impl GeneratedStl {
+        fn new(num_triangles: u32) -> Self {
+            Self {
+                num_triangles,
+                position: 0,
+            }
+        }
+
+        fn points(index: usize) -> [[f64; 3]; 3] {
+            let x = index as f64;
+            [[x, 0., 0.], [x + 1., 0., 0.], [x, 1., 1.]]
+        }
+
+        fn triangle(index: usize) -> Triangle<3> {
+            Triangle::from_points(Self::points(index))
+        }
+
+        fn byte(&self, position: usize) -> u8 {
+            if position < HEADER_SIZE {
+                return match position {
+                    80..=83 => self.num_triangles.to_le_bytes()[position - 80],
+                    _ => 0,
+                };
+            }
+
+            let position = position - HEADER_SIZE;
+            let [index, offset] =
+                [position / TRIANGLE_SIZE, position % TRIANGLE_SIZE];
+
+            // Normal and attribute byte count are left empty.
+            if !(12..48).contains(&offset) {
+                return 0;
+            }
+
+            let offset = offset - 12;
+            let component = Self::points(index)[offset / 12][offset % 12 / 4];
+
+            (component as f32).to_le_bytes()[offset % 4]
+        }
+    }
+
+    impl Read for GeneratedStl {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = HEADER_SIZE + TRIANGLE_SIZE * self.num_triangles as usize;
+
+            let mut n = 0;
+            for byte in buf {
+                if self.position >= len {
+                    break;
+                }
+
+                *byte = self.byte(self.position);
+                self.position += 1;
+                n += 1;
+            }
+
+            Ok(n)
+        }
+    }
+}
@@ -8,89 +8,188 @@
 //! users that just want to create CAD models.
 //!
 //! The purpose of this library is to export Fornjot models to external file
-//! formats.
+//! formats, and to import meshes from them. Sketches can also be exported to
+//! SVG.
 //!
 //! [Fornjot]: https://www.fornjot.app/
 
 #![warn(missing_docs)]
 
-use std::{fs::File, path::Path};
+mod gltf;
+mod import;
+mod obj;
+mod plan;
+mod ply;
+mod svg;
+mod three_mf;
+
+pub use self::{
+    gltf::export_gltf,
+    import::{
+        import_stl, import_stl_decimated, import_stl_face, stl_aabb,
+        stream_stl, ImportError, StlStats,
+    },
+    obj::export_obj,
+    plan::{
+        plan, CheckKind, CheckOutcome, ExportCheck, ExportFormat, ExportPlan,
+        SizeEstimate,
+    },
+    ply::{export_ply, PlyFormat},
+    svg::{write_svg, SvgError, SvgOptions},
+    three_mf::{export_3mf_with_colors, export_3mf_with_materials},
+};
+
+use std::{collections::BTreeMap, fs::File, path::Path};
 
 use thiserror::Error;
 
-use fj_interop::mesh::Mesh;
-use fj_math::{Point, Triangle};
+use fj_interop::{
+    material::Materials,
+    mesh::{Mesh, Triangle as MeshTriangle},
+};
+use fj_math::{Point, Scalar};
 
 /// Export the provided mesh to the file at the given path.
 ///
 /// This function will create a file if it does not exist, and will truncate it if it does.
 ///
-/// Currently 3MF & STL file types are supported. The case insensitive file extension of
-/// the provided path is used to switch between supported types.
+/// Currently 3MF, glTF, OBJ, PLY & STL file types are supported. The case insensitive file
+/// extension of the provided path is used to switch between supported types.
+///
+/// Meshes that are not watertight are refused. Use [`export_with_options`] to
+/// export them anyway.
 pub fn export(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
-    match path.extension() {
-        Some(extension) if extension.to_ascii_uppercase() == "3MF" => {
-            export_3mf(mesh, path)
-        }
-        Some(extension) if extension.to_ascii_uppercase() == "STL" => {
-            export_stl(mesh, path)
+    export_with_options(mesh, path, &ExportOptions::default())
+}
+
+/// Export the provided mesh to the file at the given path, using the given
+/// options
+///
+/// See [`export`] for details.
+pub fn export_with_options(
+    mesh: &Mesh<Point<3>>,
+    path: &Path,
+    options: &ExportOptions,
+) -> Result<(), Error> {
+    let format = ExportFormat::from_path(path)?;
+    export_planned(&plan(mesh, format, options), path)
+}
+
+/// Write a planned export to the file at the given path
+///
+/// The file is written in the format of the plan, regardless of the extension
+/// of the path. Plans with failed checks are refused, with the error that
+/// [`export_with_options`] would return for them.
+///
+/// See [`plan`] for details.
+pub fn export_planned(plan: &ExportPlan, path: &Path) -> Result<(), Error> {
+    let mesh = plan.mesh();
+    let _span = fj_interop::span!(
+        INFO,
+        "export",
+        path = %path.display(),
+        triangles = mesh.triangle_count(),
+    );
+
+    if let Some(error) = plan.error() {
+        return Err(error);
+    }
+
+    let options = &plan.options;
+    match plan.format {
+        ExportFormat::ThreeMf => {
+            if options.materials.has_assignments() {
+                export_3mf_with_materials(mesh, &options.materials, path)
+            } else {
+                export_3mf_with_colors(mesh, path)
+            }
         }
-        Some(extension) => Err(Error::InvalidExtension(
-            extension.to_string_lossy().into_owned(),
-        )),
-        None => Err(Error::NoExtension),
+        ExportFormat::Gltf => export_gltf(mesh, &options.materials, path),
+        ExportFormat::Obj => export_obj(mesh, path),
+        ExportFormat::Ply => export_ply(mesh, path, options.ply_format),
+        ExportFormat::Stl => export_stl(mesh, path),
     }
 }
 
-fn export_3mf(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
-    let vertices = mesh.vertices().map(|vertex| vertex.into()).collect();
+/// Options for [`export_with_options`]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExportOptions {
+    /// Export meshes that are not watertight
+    ///
+    /// This is required to export parts of a shape, like a selection of its
+    /// faces.
+    pub allow_open: bool,
 
-    let indices: Vec<_> = mesh.indices().collect();
-    let triangles = indices
-        .chunks(3)
-        .map(|triangle| {
-            [
-                triangle[0] as usize,
-                triangle[1] as usize,
-                triangle[2] as usize,
-            ]
-        })
-        .collect();
+    /// The materials of the mesh
+    ///
+    /// 3MF and glTF files contain one object or primitive per material. OBJ
+    /// and STL files don't support them, so they are ignored there. 3MF files
+    /// of meshes without assigned materials, and OBJ files, keep the colors of
+    /// the triangles instead.
+    pub materials: Materials,
 
-    let mesh = threemf::TriangleMesh {
-        vertices,
-        triangles,
-    };
+    /// The distance within which vertices are merged
+    ///
+    /// Equal vertices are always shared between triangles. If this is set,
+    /// vertices that are at most this far apart are merged too, before the
+    /// mesh is checked and exported. See [`Mesh::deduplicated`].
+    pub merge_distance: Option<Scalar>,
 
-    threemf::write(path, &mesh)?;
+    /// The encoding of PLY files
+    ///
+    /// Binary by default.
+    pub ply_format: PlyFormat,
+}
 
-    Ok(())
+/// Count the edges that are not shared by exactly two triangles
+fn count_open_edges(mesh: &Mesh<Point<3>>) -> usize {
+    let mut edges = BTreeMap::new();
+    for triangle in mesh.triangles() {
+        let [a, b, c] = triangle.points;
+        for [a, b] in [[a, b], [b, c], [c, a]] {
+            let edge = if a < b { [a, b] } else { [b, a] };
+            *edges.entry(edge).or_insert(0) += 1;
+        }
+    }
+
+    edges.values().filter(|&&uses| uses != 2).count()
+}
+
+/// Group the triangles of a mesh by material
+///
+/// Returns the index of each material that has triangles, in order, along with
+/// those triangles.
+fn group_by_material(
+    mesh: &Mesh<Point<3>>,
+    materials: &Materials,
+) -> Vec<(usize, Vec<MeshTriangle>)> {
+    let mut groups = BTreeMap::new();
+    for triangle in mesh.triangles() {
+        groups
+            .entry(materials.material_of(&triangle))
+            .or_insert_with(Vec::new)
+            .push(triangle);
+    }
+
+    groups.into_iter().collect()
 }
 
 fn export_stl(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
-    let points = mesh
+    let triangles = mesh
         .triangles()
-        .map(|triangle| triangle.points)
-        .collect::<Vec<_>>();
+        .map(|triangle| {
+            let [v1, v2, v3] = triangle
+                .points
+                .map(|point| point.coords.components.map(|s| s.into_f32()));
+            let normal = triangle.normal.components.map(|s| s.into_f32());
 
-    let vertices = points.iter().map(|points| {
-        points.map(|point| point.coords.components.map(|s| s.into_f32()))
-    });
-
-    let normals = points
-        .iter()
-        .map(|&points| points.into())
-        .map(|triangle: Triangle<3>| triangle.normal())
-        .map(|vector| vector.components.map(|s| s.into_f32()));
-
-    let triangles = vertices
-        .zip(normals)
-        .map(|([v1, v2, v3], normal)| stl::Triangle {
-            normal,
-            v1,
-            v2,
-            v3,
-            attr_byte_count: 0,
+            stl::Triangle {
+                normal,
+                v1,
+                v2,
+                v3,
+                attr_byte_count: 0,
+            }
         })
         .collect::<Vec<_>>();
 
@@ -127,11 +226,490 @@ pub enum Error {
     #[error("I/O error whilst exporting to file")]
     Io(#[from] std::io::Error),
 
+    /// The mesh is not watertight
+    #[error("mesh is not watertight ({0} open edges)")]
+    OpenMesh(usize),
+
     /// Maximum triangle count exceeded
     #[error("maximum triangle count exceeded")]
     InvalidTriangleCount,
 
-    /// Threemf error whilst exporting to 3MF file
-    #[error("threemf error whilst exporting to 3MF file")]
-    ThreeMF(#[from] threemf::Error),
+    /// Error writing the 3MF package
+    #[error("error writing the 3MF package")]
+    Zip(#[from] zip::result::ZipError),
+
+    /// Error whilst exporting to glTF file
+    #[error("error whilst exporting to glTF file")]
+    Json(#[from] serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, io::Read};
+
+    use fj_interop::{
+        debug::DebugInfo,
+        material::{Material, Materials},
+        mesh::Mesh,
+    };
+    use fj_kernel::{
+        algorithms::{triangulate, ApproxStore, ErrorBudget, Tolerance},
+        objects::Solid,
+    };
+    use fj_math::{Point, Scalar, Vector};
+
+    use xml::reader::{EventReader, XmlEvent};
+
+    use super::{
+        export_planned, export_with_options, plan, CheckKind, CheckOutcome,
+        Error, ExportFormat, ExportOptions, PlyFormat, SizeEstimate,
+    };
+
+    #[test]
+    fn open_mesh() -> Result<(), Error> {
+        // The top face of a cube, without the rest of it
+        let [a, b, c, d] = [[0., 0.], [1., 0.], [1., 1.], [0., 1.]]
+            .map(|[x, y]| Point::from([x, y, 1.]));
+
+        let mut mesh = Mesh::new();
+        mesh.push_triangle([a, b, c], [255, 0, 0, 255]);
+        mesh.push_triangle([a, c, d], [255, 0, 0, 255]);
+
+        let path = std::env::temp_dir().join("fj-export-open-mesh.stl");
+
+        let result =
+            export_with_options(&mesh, &path, &ExportOptions::default());
+        assert!(matches!(result, Err(Error::OpenMesh(4))));
+
+        let options = ExportOptions {
+            allow_open: true,
+            ..ExportOptions::default()
+        };
+        export_with_options(&mesh, &path, &options)?;
+        assert!(path.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn plan_open_mesh() {
+        let [a, b, c, d] = [[0., 0.], [1., 0.], [1., 1.], [0., 1.]]
+            .map(|[x, y]| Point::from([x, y, 1.]));
+
+        let mut mesh = Mesh::new();
+        mesh.push_triangle([a, b, c], [255, 0, 0, 255]);
+        mesh.push_triangle([a, c, d], [255, 0, 0, 255]);
+
+        let plan = plan(&mesh, ExportFormat::Stl, &ExportOptions::default());
+        assert!(!plan.is_valid());
+
+        let check = plan.failed_check().expect("Watertightness check failed");
+        assert_eq!(check.kind, CheckKind::Watertight);
+        assert!(check.detail.contains("4 open edges"));
+
+        // Nothing is written for plans that fail.
+        let path = std::env::temp_dir().join("fj-export-plan-open-mesh.stl");
+        let _ = std::fs::remove_file(&path);
+        assert!(matches!(
+            export_planned(&plan, &path),
+            Err(Error::OpenMesh(4))
+        ));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn plan_size() -> Result<(), Error> {
+        let mesh = cube();
+
+        for (format, extension) in
+            [(ExportFormat::Stl, "stl"), (ExportFormat::Ply, "ply")]
+        {
+            let plan = plan(&mesh, format, &ExportOptions::default());
+            assert!(plan.is_valid());
+
+            let path = std::env::temp_dir()
+                .join(format!("fj-export-plan-size.{extension}"));
+            export_planned(&plan, &path)?;
+
+            let size = std::fs::metadata(&path)?.len();
+            assert_eq!(plan.estimated_size, SizeEstimate::Exact(size));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn plan_3mf_colorless() {
+        // All triangles of the cube have the same color.
+        let mesh = cube();
+        let plan =
+            plan(&mesh, ExportFormat::ThreeMf, &ExportOptions::default());
+
+        assert!(plan.is_valid());
+        assert_eq!(plan.warnings().count(), 0);
+        assert!(plan
+            .checks
+            .iter()
+            .all(|check| check.kind != CheckKind::Colors));
+
+        let units = plan
+            .checks
+            .iter()
+            .find(|check| check.kind == CheckKind::Units)
+            .expect("Units are checked");
+        assert_eq!(units.outcome, CheckOutcome::Passed);
+    }
+
+    #[test]
+    fn merge_distance() -> Result<(), Error> {
+        // A cube whose top face was approximated slightly differently from
+        // the rest, so it doesn't share their vertices.
+        let offset = Vector::from([0., 0., 1e-10]);
+        let mut mesh = Mesh::new();
+        for triangle in cube().triangles() {
+            let points = if triangle.face == Some(1) {
+                triangle.points.map(|point| point + offset)
+            } else {
+                triangle.points
+            };
+            mesh.push_triangle(points, triangle.color);
+        }
+
+        let path = std::env::temp_dir().join("fj-export-merge-distance.3mf");
+
+        let result =
+            export_with_options(&mesh, &path, &ExportOptions::default());
+        assert!(matches!(result, Err(Error::OpenMesh(_))));
+
+        let options = ExportOptions {
+            merge_distance: Some(Scalar::from(1e-9)),
+            ..ExportOptions::default()
+        };
+        export_with_options(&mesh, &path, &options)?;
+        assert!(path.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn materials_3mf() -> Result<(), Error> {
+        let mesh = cube();
+
+        // The bottom and top face are soft, the rest is rigid.
+        let mut materials = Materials::default();
+        let rigid = materials.push(Material::new("PLA-black", [0, 0, 0, 255]));
+        let soft =
+            materials.push(Material::new("TPU-clear", [255, 255, 255, 128]));
+        for face in 0..6 {
+            let material = if face < 2 { soft } else { rigid };
+            materials.assign(face, material);
+        }
+
+        let path = std::env::temp_dir().join("fj-export-materials.3mf");
+        let options = ExportOptions {
+            materials,
+            ..ExportOptions::default()
+        };
+        export_with_options(&mesh, &path, &options)?;
+
+        let mut model = String::new();
+        zip::ZipArchive::new(File::open(&path)?)?
+            .by_name("3D/3dmodel.model")?
+            .read_to_string(&mut model)?;
+
+        let base_materials: Vec<_> = model
+            .split("<base ")
+            .skip(1)
+            .map(|base| attribute(base, "name"))
+            .collect();
+        assert_eq!(base_materials, ["PLA-black", "TPU-clear"]);
+
+        // One object per material, referring to the material by index.
+        let objects: Vec<_> = model
+            .split("<object ")
+            .skip(1)
+            .map(|object| {
+                let pindex = attribute(object, "pindex");
+                (pindex, object.matches("<triangle ").count())
+            })
+            .collect();
+        assert_eq!(objects, [("0", 8), ("1", 4)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn colors_3mf() -> Result<(), Error> {
+        // The bottom and top face are blue, the rest is red.
+        let [red, blue] = [[255, 0, 0, 255], [0, 0, 255, 255]];
+        let mut mesh = Mesh::new();
+        for triangle in cube().triangles() {
+            let face = triangle.face.expect("Cube triangles have faces");
+            let color = if face < 2 { blue } else { red };
+            mesh.push_face_triangle(triangle.points, color, face);
+        }
+
+        let path = std::env::temp_dir().join("fj-export-colors.3mf");
+        export_with_options(&mesh, &path, &ExportOptions::default())?;
+
+        let mut package = zip::ZipArchive::new(File::open(&path)?)?;
+        for name in ["[Content_Types].xml", "_rels/.rels"] {
+            package.by_name(name)?;
+        }
+        let model = package.by_name("3D/3dmodel.model")?;
+
+        let mut colors = Vec::new();
+        let mut triangles = Vec::new();
+        for event in EventReader::new(model) {
+            let event = event.expect("Model is not valid XML");
+            if let XmlEvent::StartElement {
+                name, attributes, ..
+            } = event
+            {
+                let attribute = |name: &str| {
+                    attributes
+                        .iter()
+                        .find(|attribute| attribute.name.local_name == name)
+                        .map(|attribute| attribute.value.clone())
+                };
+
+                match name.local_name.as_str() {
+                    "color" => {
+                        assert_eq!(
+                            name.namespace.as_deref(),
+                            Some(
+                                "http://schemas.microsoft.com/\
+                                3dmanufacturing/material/2015/02"
+                            )
+                        );
+                        colors.push(attribute("color"));
+                    }
+                    "triangle" => {
+                        assert_eq!(attribute("pid").as_deref(), Some("1"));
+                        triangles.push(attribute("p1"));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // Each color appears once, in the order of first use.
+        let colors: Vec<_> = colors.into_iter().flatten().collect();
+        assert_eq!(colors, ["#0000FFFF", "#FF0000FF"]);
+
+        let expected: Vec<_> = (0..12)
+            .map(|i| Some(if i < 4 { "0" } else { "1" }.to_string()))
+            .collect();
+        assert_eq!(triangles, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn obj() -> Result<(), Error> {
+        let mesh = cube();
+
+        let path = std::env::temp_dir().join("fj-export-cube.obj");
+        export_with_options(&mesh, &path, &ExportOptions::default())?;
+
+        let obj = std::fs::read_to_string(&path)?;
+        let records = |kind: &str| -> Vec<Vec<String>> {
+            obj.lines()
+                .filter_map(|line| line.strip_prefix(kind))
+                .filter_map(|line| line.strip_prefix(' '))
+                .map(|line| line.split(' ').map(str::to_owned).collect())
+                .collect()
+        };
+
+        // Vertices and normals are shared, faces are not.
+        let vertices = records("v");
+        let normals = records("vn");
+        let faces = records("f");
+        assert_eq!(vertices.len(), 8);
+        assert_eq!(normals.len(), 6);
+        assert_eq!(faces.len(), 12);
+
+        for face in &faces {
+            assert_eq!(face.len(), 3);
+            for corner in face {
+                let indices: Vec<usize> = corner
+                    .split("//")
+                    .map(|index| index.parse().expect("Index is a number"))
+                    .collect();
+                let [v, vn] = [indices[0], indices[1]];
+                assert!((1..=vertices.len()).contains(&v));
+                assert!((1..=normals.len()).contains(&vn));
+            }
+        }
+
+        assert!(obj.contains("mtllib fj-export-cube.mtl"));
+        assert!(obj.contains("usemtl color_FF0000FF"));
+        let mtl = std::fs::read_to_string(path.with_extension("mtl"))?;
+        assert!(mtl.contains("newmtl color_FF0000FF"));
+        assert!(mtl.contains("Kd 1 0 0"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn ply() -> Result<(), Error> {
+        let mesh = cube();
+
+        for format in [PlyFormat::Ascii, PlyFormat::BinaryLittleEndian] {
+            let path =
+                std::env::temp_dir().join(format!("fj-export-{format:?}.ply"));
+            let options = ExportOptions {
+                ply_format: format,
+                ..ExportOptions::default()
+            };
+            export_with_options(&mesh, &path, &options)?;
+
+            let data = std::fs::read(&path)?;
+            let end = b"end_header\n";
+            let header_len = data
+                .windows(end.len())
+                .position(|window| window == end)
+                .expect("Header is terminated")
+                + end.len();
+            let header = std::str::from_utf8(&data[..header_len])
+                .expect("Header is text");
+            let body = &data[header_len..];
+
+            let count = |element: &str| -> usize {
+                header
+                    .lines()
+                    .find_map(|line| {
+                        line.strip_prefix(&format!("element {element} "))
+                    })
+                    .expect("Element is declared")
+                    .parse()
+                    .expect("Count is a number")
+            };
+
+            // Each face of the cube has its own 4 vertices, as the normals
+            // differ between faces.
+            let [vertices, faces] = [count("vertex"), count("face")];
+            assert_eq!(vertices, 24);
+            assert_eq!(faces, 12);
+            assert_eq!(header.matches("property ").count(), 11);
+
+            match format {
+                PlyFormat::Ascii => {
+                    assert!(header.contains("format ascii 1.0"));
+
+                    let body = std::str::from_utf8(body).expect("Body is text");
+                    let lines: Vec<_> = body.lines().collect();
+                    assert_eq!(lines.len(), vertices + faces);
+                    for line in &lines[vertices..] {
+                        let indices: Vec<usize> = line
+                            .split(' ')
+                            .map(|index| index.parse().expect("Is a number"))
+                            .collect();
+                        assert_eq!(indices[0], 3);
+                        assert!(indices[1..].iter().all(|&i| i < vertices));
+                    }
+                }
+                PlyFormat::BinaryLittleEndian => {
+                    assert!(header.contains("format binary_little_endian 1.0"));
+
+                    // 6 floats and 4 bytes per vertex, a count and 3 indices
+                    // per face.
+                    let vertex_size = 6 * 4 + 4;
+                    let face_size = 1 + 3 * 4;
+                    assert_eq!(
+                        body.len(),
+                        vertices * vertex_size + faces * face_size
+                    );
+
+                    for face in body[vertices * vertex_size..].chunks(face_size)
+                    {
+                        assert_eq!(face[0], 3);
+                        for index in face[1..].chunks(4) {
+                            let index = u32::from_le_bytes(
+                                index.try_into().expect("Index has 4 bytes"),
+                            );
+                            assert!((index as usize) < vertices);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn reproducible() -> Result<(), Error> {
+        let faces: Vec<_> = Solid::cube_from_edge_length(1.)
+            .into_faces()
+            .into_iter()
+            .collect();
+
+        // The same faces, passed to the solid in different orders
+        let mut shuffled = faces.clone();
+        shuffled.reverse();
+        shuffled.rotate_left(2);
+        let solids = [faces, shuffled].map(Solid::from_faces);
+        assert_eq!(solids[0], solids[1]);
+
+        let mut files = Vec::new();
+        for (i, solid) in solids.into_iter().enumerate() {
+            let tolerance =
+                Tolerance::from_scalar(0.001).expect("Tolerance is valid");
+            let mesh = triangulate(
+                solid.into_faces().into_iter().collect(),
+                tolerance,
+                &ErrorBudget::new(),
+                &mut ApproxStore::new(),
+                &mut DebugInfo::new(),
+            )
+            .expect("Cube can be triangulated");
+
+            let path = std::env::temp_dir()
+                .join(format!("fj-export-reproducible-{i}.ply"));
+            export_with_options(&mesh, &path, &ExportOptions::default())?;
+            files.push(std::fs::read(path)?);
+        }
+        assert_eq!(files[0], files[1]);
+
+        Ok(())
+    }
+
+    /// A unit cube, with the triangles of each face labeled with its index
+    fn cube() -> Mesh<Point<3>> {
+        let corner = |index: usize| {
+            Point::from([0, 1, 2].map(|axis| ((index >> axis) & 1) as f64))
+        };
+
+        // Every face is defined by the axis it is perpendicular to and the
+        // side of the cube it's on. The corners are ordered counter-clockwise,
+        // when looking at the face from the outside.
+        let faces = [
+            [0, 2, 3, 1],
+            [4, 5, 7, 6],
+            [0, 1, 5, 4],
+            [2, 6, 7, 3],
+            [0, 4, 6, 2],
+            [1, 3, 7, 5],
+        ];
+
+        let mut mesh = Mesh::new();
+        for (face, [a, b, c, d]) in faces.into_iter().enumerate() {
+            let [a, b, c, d] = [a, b, c, d].map(corner);
+            mesh.push_face_triangle([a, b, c], [255, 0, 0, 255], face);
+            mesh.push_face_triangle([a, c, d], [255, 0, 0, 255], face);
+        }
+
+        mesh
+    }
+
+    fn attribute<'r>(element: &'r str, name: &str) -> &'r str {
+        let start = element
+            .find(&format!(r#"{name}=""#))
+            .expect("Attribute not found")
+            + name.len()
+            + 2;
+        let len = element[start..].find('"').expect("Unterminated attribute");
+        &element[start..start + len]
+    }
 }
@@ -0,0 +1,121 @@
+use std::{collections::HashMap, fmt::Write as _, fs, path::Path};
+
+use fj_interop::mesh::{
+    Color, Mesh, Triangle as MeshTriangle, DEFAULT_CREASE_ANGLE,
+};
+use fj_math::{Point, Scalar, Vector};
+
+use crate::Error;
+
+/// Export a mesh to Wavefront OBJ, with its colors in a companion MTL file
+///
+/// The MTL file is written next to the OBJ file, with the same name and the
+/// extension `mtl`. It contains one material per distinct color of the
+/// triangles, and the faces of the OBJ file are grouped by material.
+///
+/// Equal vertices and normals are written only once, and shared by the faces
+/// that refer to them. The normals are computed per vertex (see
+/// [`Mesh::vertex_normals`]), so faces that meet smoothly are shaded smoothly.
+/// Numbers are always written with `.` as decimal separator, as Rust's
+/// formatting doesn't depend on the locale.
+pub fn export_obj(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
+    let mtl_path = path.with_extension("mtl");
+    let mtl_name = mtl_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let groups = group_by_color(mesh);
+
+    fs::write(path, obj(mesh, &groups, &mtl_name))?;
+    fs::write(mtl_path, mtl(&groups))?;
+
+    Ok(())
+}
+
+/// Group the triangles of a mesh by color, in the order of first use
+///
+/// Each triangle is accompanied by the normals at its points.
+fn group_by_color(mesh: &Mesh<Point<3>>) -> Vec<(Color, Vec<ObjTriangle>)> {
+    let mut groups: Vec<(Color, Vec<ObjTriangle>)> = Vec::new();
+    let mut indices_by_color = HashMap::new();
+
+    let normals = mesh.vertex_normals(Scalar::from_f64(DEFAULT_CREASE_ANGLE));
+    for (triangle, normals) in mesh.triangles().zip(normals) {
+        let index =
+            *indices_by_color.entry(triangle.color).or_insert_with(|| {
+                groups.push((triangle.color, Vec::new()));
+                groups.len() - 1
+            });
+        groups[index].1.push((triangle, normals));
+    }
+
+    groups
+}
+
+type ObjTriangle = (MeshTriangle, [Vector<3>; 3]);
+
+fn obj(
+    mesh: &Mesh<Point<3>>,
+    groups: &[(Color, Vec<ObjTriangle>)],
+    mtl_name: &str,
+) -> String {
+    // OBJ indices start at 1.
+    let vertex_indices: HashMap<_, _> = mesh
+        .vertices()
+        .enumerate()
+        .map(|(index, vertex)| (vertex, index + 1))
+        .collect();
+
+    let mut normals = Vec::new();
+    let mut normal_indices = HashMap::new();
+    let mut normal_index = |normal: Vector<3>| {
+        *normal_indices.entry(normal).or_insert_with(|| {
+            normals.push(normal);
+            normals.len()
+        })
+    };
+
+    // Writing to a `String` can't fail, so the results are ignored.
+    let mut faces = String::new();
+    for (color, triangles) in groups {
+        let _ = writeln!(faces, "usemtl {}", material_name(*color));
+        for (triangle, vertex_normals) in triangles {
+            let [a, b, c] = triangle.points.map(|point| vertex_indices[&point]);
+            let [na, nb, nc] = vertex_normals.map(&mut normal_index);
+            let _ = writeln!(faces, "f {a}//{na} {b}//{nb} {c}//{nc}");
+        }
+    }
+
+    let mut obj = String::new();
+    obj.push_str("# Exported by Fornjot\n");
+    let _ = writeln!(obj, "mtllib {mtl_name}");
+    for vertex in mesh.vertices() {
+        let _ = writeln!(obj, "v {} {} {}", vertex.x, vertex.y, vertex.z);
+    }
+    for normal in normals {
+        let _ = writeln!(obj, "vn {} {} {}", normal.x, normal.y, normal.z);
+    }
+    obj.push_str(&faces);
+
+    obj
+}
+
+fn mtl(groups: &[(Color, Vec<ObjTriangle>)]) -> String {
+    let mut mtl = String::new();
+    mtl.push_str("# Exported by Fornjot\n");
+
+    for &(color, _) in groups {
+        let [r, g, b, a] = color.map(|component| f64::from(component) / 255.);
+
+        let _ = writeln!(mtl, "newmtl {}", material_name(color));
+        let _ = writeln!(mtl, "Kd {r} {g} {b}");
+        let _ = writeln!(mtl, "d {a}");
+    }
+
+    mtl
+}
+
+fn material_name([r, g, b, a]: Color) -> String {
+    format!("color_{r:02X}{g:02X}{b:02X}{a:02X}")
+}
@@ -0,0 +1,480 @@
+use std::{collections::HashSet, path::Path};
+
+use fj_interop::mesh::{Mesh, DEFAULT_CREASE_ANGLE};
+use fj_math::{Point, Scalar};
+
+use crate::{count_open_edges, ply, Error, ExportOptions, PlyFormat};
+
+/// Plan the export of a mesh, without writing anything
+///
+/// Resolves the configuration of the exporter, performs the checks that an
+/// export would perform, and estimates the size of the file. The returned
+/// plan can be written with [`export_planned`], which writes exactly what has
+/// been checked here.
+///
+/// The mesh is prepared as it would be for the export, which means vertices
+/// are merged, if [`ExportOptions::merge_distance`] is set. Apart from that,
+/// planning only needs as much memory as the exporter itself.
+///
+/// [`export_planned`]: crate::export_planned
+pub fn plan<'r>(
+    mesh: &'r Mesh<Point<3>>,
+    format: ExportFormat,
+    options: &ExportOptions,
+) -> ExportPlan<'r> {
+    let deduplicated = options
+        .merge_distance
+        .map(|epsilon| mesh.deduplicated(epsilon));
+    let prepared = deduplicated.as_ref().unwrap_or(mesh);
+
+    let open_edges = count_open_edges(prepared);
+    let checks = [
+        Some(check_watertight(open_edges, options.allow_open)),
+        check_element_count(prepared, format),
+        check_colors(prepared, format, options),
+        check_materials(format, options),
+        Some(check_units(format)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    let estimated_size = estimate_size(prepared, format, options);
+
+    ExportPlan {
+        format,
+        options: options.clone(),
+        checks,
+        estimated_size,
+        open_edges,
+        mesh,
+        deduplicated,
+    }
+}
+
+/// The plan of an export, as returned by [`plan`]
+pub struct ExportPlan<'r> {
+    /// The format of the file
+    pub format: ExportFormat,
+
+    /// The options the exporter is configured with
+    pub options: ExportOptions,
+
+    /// The checks that have been performed, in order
+    ///
+    /// Checks that don't apply to the mesh or format are left out. Colors are
+    /// only checked, if the triangles have more than one color, for example.
+    pub checks: Vec<ExportCheck>,
+
+    /// The estimated size of the file
+    pub estimated_size: SizeEstimate,
+
+    open_edges: usize,
+    mesh: &'r Mesh<Point<3>>,
+    deduplicated: Option<Mesh<Point<3>>>,
+}
+
+impl ExportPlan<'_> {
+    /// Indicate whether the export would succeed
+    ///
+    /// Only I/O errors can still occur, if this returns `true`.
+    pub fn is_valid(&self) -> bool {
+        self.failed_check().is_none()
+    }
+
+    /// Access the first check that failed, if any
+    pub fn failed_check(&self) -> Option<&ExportCheck> {
+        self.checks
+            .iter()
+            .find(|check| check.outcome == CheckOutcome::Failed)
+    }
+
+    /// Iterate over the warnings that the export would emit
+    pub fn warnings(&self) -> impl Iterator<Item = &str> + '_ {
+        self.checks
+            .iter()
+            .filter(|check| check.outcome == CheckOutcome::Warning)
+            .map(|check| check.detail.as_str())
+    }
+
+    /// Access the mesh that would be written
+    ///
+    /// This is the mesh passed to [`plan`], with its vertices merged, if
+    /// [`ExportOptions::merge_distance`] is set.
+    pub fn mesh(&self) -> &Mesh<Point<3>> {
+        self.deduplicated.as_ref().unwrap_or(self.mesh)
+    }
+
+    /// The error that writing this plan results in, if a check failed
+    pub(crate) fn error(&self) -> Option<Error> {
+        let error = match self.failed_check()?.kind {
+            CheckKind::Watertight => Error::OpenMesh(self.open_edges),
+            CheckKind::ElementCount => Error::InvalidTriangleCount,
+            CheckKind::Colors | CheckKind::Materials | CheckKind::Units => {
+                unreachable!("Check can't fail")
+            }
+        };
+
+        Some(error)
+    }
+}
+
+/// A file format that meshes can be exported to
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExportFormat {
+    /// 3D Manufacturing Format
+    ThreeMf,
+
+    /// glTF, as JSON with embedded binary data
+    Gltf,
+
+    /// Wavefront OBJ, with a companion MTL file
+    Obj,
+
+    /// Polygon File Format
+    Ply,
+
+    /// Binary STL
+    Stl,
+}
+
+impl ExportFormat {
+    /// Determine the format from the extension of a path
+    ///
+    /// The extension is case-insensitive.
+    pub fn from_path(path: &Path) -> Result<Self, Error> {
+        let extension = path.extension().ok_or(Error::NoExtension)?;
+
+        let format = match extension.to_ascii_uppercase().to_str() {
+            Some("3MF") => Self::ThreeMf,
+            Some("GLTF") => Self::Gltf,
+            Some("OBJ") => Self::Obj,
+            Some("PLY") => Self::Ply,
+            Some("STL") => Self::Stl,
+            _ => {
+                return Err(Error::InvalidExtension(
+                    extension.to_string_lossy().into_owned(),
+                ))
+            }
+        };
+
+        Ok(format)
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::ThreeMf => "3MF",
+            Self::Gltf => "glTF",
+            Self::Obj => "OBJ",
+            Self::Ply => "PLY",
+            Self::Stl => "STL",
+        }
+    }
+}
+
+/// A check performed while planning an export
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExportCheck {
+    /// What has been checked
+    pub kind: CheckKind,
+
+    /// The outcome of the check
+    pub outcome: CheckOutcome,
+
+    /// A description of the outcome
+    pub detail: String,
+}
+
+/// What an [`ExportCheck`] checks
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CheckKind {
+    /// The mesh is watertight, or open meshes are allowed
+    Watertight,
+
+    /// The number of triangles and vertices fits into the format
+    ElementCount,
+
+    /// The colors of the triangles are kept by the format
+    Colors,
+
+    /// The assigned materials are kept by the format
+    Materials,
+
+    /// The format records the unit of the coordinates
+    Units,
+}
+
+/// The outcome of an [`ExportCheck`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CheckOutcome {
+    /// The check passed
+    Passed,
+
+    /// The export would succeed, but lose information or emit a warning
+    Warning,
+
+    /// The export would fail
+    Failed,
+}
+
+/// The estimated size of an exported file, in bytes
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SizeEstimate {
+    /// The size is known exactly
+    ///
+    /// This is the case for binary formats, whose size follows from the
+    /// number of triangles and vertices.
+    Exact(u64),
+
+    /// The size is approximated
+    ///
+    /// This is the case for text formats, whose size depends on how the
+    /// numbers are written, and for compressed formats. For 3MF files, the
+    /// size of the uncompressed package is estimated.
+    Approximate(u64),
+}
+
+impl SizeEstimate {
+    /// Access the estimated number of bytes
+    pub fn bytes(&self) -> u64 {
+        match self {
+            Self::Exact(bytes) | Self::Approximate(bytes) => *bytes,
+        }
+    }
+}
+
+fn check_watertight(open_edges: usize, allow_open: bool) -> ExportCheck {
+    let (outcome, detail) = match (open_edges, allow_open) {
+        (0, _) => (CheckOutcome::Passed, "mesh is watertight".to_string()),
+        (n, true) => (
+            CheckOutcome::Warning,
+            format!("mesh is not watertight ({n} open edges)"),
+        ),
+        (n, false) => (
+            CheckOutcome::Failed,
+            format!(
+                "mesh is not watertight ({n} open edges), and open meshes \
+                are not allowed"
+            ),
+        ),
+    };
+
+    ExportCheck {
+        kind: CheckKind::Watertight,
+        outcome,
+        detail,
+    }
+}
+
+fn check_element_count(
+    mesh: &Mesh<Point<3>>,
+    format: ExportFormat,
+) -> Option<ExportCheck> {
+    // STL files store the number of triangles as `u32`, binary PLY files the
+    // vertex indices.
+    let (elements, count) = match format {
+        ExportFormat::Stl => ("triangles", mesh.triangle_count()),
+        ExportFormat::Ply => ("vertices", ply::elements(mesh).0.len()),
+        _ => return None,
+    };
+
+    let (outcome, detail) = if u32::try_from(count).is_ok() {
+        (CheckOutcome::Passed, format!("{count} {elements}"))
+    } else {
+        (
+            CheckOutcome::Failed,
+            format!("{count} {elements} exceed the maximum of {}", u32::MAX),
+        )
+    };
+
+    Some(ExportCheck {
+        kind: CheckKind::ElementCount,
+        outcome,
+        detail,
+    })
+}
+
+fn check_colors(
+    mesh: &Mesh<Point<3>>,
+    format: ExportFormat,
+    options: &ExportOptions,
+) -> Option<ExportCheck> {
+    // A mesh of a single color doesn't carry any information in its colors.
+    let colors: HashSet<_> =
+        mesh.triangles().map(|triangle| triangle.color).collect();
+    if colors.len() < 2 {
+        return None;
+    }
+
+    let replaced_by_materials = match format {
+        ExportFormat::ThreeMf => options.materials.has_assignments(),
+        ExportFormat::Gltf | ExportFormat::Stl => true,
+        ExportFormat::Obj | ExportFormat::Ply => false,
+    };
+
+    let (outcome, detail) = if replaced_by_materials {
+        (
+            CheckOutcome::Warning,
+            format!(
+                "the {} colors of the triangles are not written to {}",
+                colors.len(),
+                format.name()
+            ),
+        )
+    } else {
+        (
+            CheckOutcome::Passed,
+            format!("{} colors are written", colors.len()),
+        )
+    };
+
+    Some(ExportCheck {
+        kind: CheckKind::Colors,
+        outcome,
+        detail,
+    })
+}
+
+fn check_materials(
+    format: ExportFormat,
+    options: &ExportOptions,
+) -> Option<ExportCheck> {
+    if !options.materials.has_assignments() {
+        return None;
+    }
+
+    let (outcome, detail) = match format {
+        ExportFormat::ThreeMf | ExportFormat::Gltf => (
+            CheckOutcome::Passed,
+            "assigned materials are written".to_string(),
+        ),
+        ExportFormat::Obj | ExportFormat::Ply | ExportFormat::Stl => (
+            CheckOutcome::Warning,
+            format!("assigned materials are not written to {}", format.name()),
+        ),
+    };
+
+    Some(ExportCheck {
+        kind: CheckKind::Materials,
+        outcome,
+        detail,
+    })
+}
+
+fn check_units(format: ExportFormat) -> ExportCheck {
+    let (outcome, detail) = match format {
+        ExportFormat::ThreeMf => (
+            CheckOutcome::Passed,
+            "coordinates are marked as millimeters".to_string(),
+        ),
+        ExportFormat::Gltf => (
+            CheckOutcome::Warning,
+            "glTF coordinates are meters by definition, but are written in \
+            millimeters"
+                .to_string(),
+        ),
+        ExportFormat::Obj | ExportFormat::Ply | ExportFormat::Stl => (
+            CheckOutcome::Warning,
+            format!(
+                "{} files don't record units; coordinates are written in \
+                millimeters",
+                format.name()
+            ),
+        ),
+    };
+
+    ExportCheck {
+        kind: CheckKind::Units,
+        outcome,
+        detail,
+    }
+}
+
+fn estimate_size(
+    mesh: &Mesh<Point<3>>,
+    format: ExportFormat,
+    options: &ExportOptions,
+) -> SizeEstimate {
+    let triangles = mesh.triangle_count() as u64;
+    let vertices = mesh.vertices().count() as u64;
+
+    // The numbers of bytes per line or element of text formats are rough
+    // averages, for coordinates written with their full precision.
+    match format {
+        ExportFormat::Stl => {
+            // An 80 byte header and the number of triangles, then a normal,
+            // three points, and an attribute byte count per triangle.
+            SizeEstimate::Exact(80 + 4 + triangles * (4 * 12 + 2))
+        }
+        ExportFormat::Ply => {
+            let (ply_vertices, faces) = ply::elements(mesh);
+            let header = ply::header(
+                options.ply_format,
+                ply_vertices.len(),
+                faces.len(),
+            )
+            .len() as u64;
+            let [ply_vertices, faces] =
+                [ply_vertices.len(), faces.len()].map(|n| n as u64);
+
+            match options.ply_format {
+                PlyFormat::BinaryLittleEndian => {
+                    // 6 floats and 4 bytes per vertex, a count and 3 indices
+                    // per face.
+                    SizeEstimate::Exact(
+                        header
+                            + ply_vertices * (6 * 4 + 4)
+                            + faces * (1 + 3 * 4),
+                    )
+                }
+                PlyFormat::Ascii => {
+                    let face_line = 2 + 3 * (digits(ply_vertices) + 1);
+                    SizeEstimate::Approximate(
+                        header + ply_vertices * 100 + faces * face_line,
+                    )
+                }
+            }
+        }
+        ExportFormat::Obj => {
+            let normals = mesh
+                .vertex_normals(Scalar::from_f64(DEFAULT_CREASE_ANGLE))
+                .into_iter()
+                .flatten()
+                .collect::<HashSet<_>>()
+                .len() as u64;
+            let colors = mesh
+                .triangles()
+                .map(|triangle| triangle.color)
+                .collect::<HashSet<_>>()
+                .len() as u64;
+
+            let face_line =
+                2 + 3 * (digits(vertices) + 2 + digits(normals) + 1);
+            let obj = 64
+                + (vertices + normals) * 60
+                + triangles * face_line
+                + colors * 27;
+            let mtl = 22 + colors * 80;
+
+            SizeEstimate::Approximate(obj + mtl)
+        }
+        ExportFormat::Gltf => {
+            // Three points per triangle, encoded as Base64, and the JSON
+            // around it.
+            let buffer = triangles * 3 * 12;
+            let materials = options.materials.materials().len() as u64;
+            SizeEstimate::Approximate(
+                (buffer + 2) / 3 * 4 + 400 + materials * 500,
+            )
+        }
+        ExportFormat::ThreeMf => {
+            // The XML of the model, and the other parts of the package.
+            SizeEstimate::Approximate(1500 + vertices * 70 + triangles * 60)
+        }
+    }
+}
+
+/// The number of decimal digits of a number
+fn digits(n: u64) -> u64 {
+    n.to_string().len() as u64
+}
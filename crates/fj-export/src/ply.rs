@@ -0,0 +1,155 @@
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use fj_interop::mesh::{Color, Mesh, DEFAULT_CREASE_ANGLE};
+use fj_math::{Point, Scalar, Vector};
+
+use crate::Error;
+
+/// Export a mesh to PLY
+///
+/// Each vertex has a position, a normal, and an RGBA color. Triangles that
+/// share a vertex only share it in the file, if they also have the same
+/// normal and color there. The normals are computed per vertex (see
+/// [`Mesh::vertex_normals`]), so vertices are shared within the faces of the
+/// shape, and across edges where faces meet smoothly, but not across sharp
+/// edges. Faces are lists of vertex indices.
+///
+/// The file is written as it is generated, without holding all of it in
+/// memory.
+pub fn export_ply(
+    mesh: &Mesh<Point<3>>,
+    path: &Path,
+    format: PlyFormat,
+) -> Result<(), Error> {
+    let (vertices, faces) = elements(mesh);
+
+    // Binary files store vertex indices as `u32`.
+    u32::try_from(vertices.len()).map_err(|_| Error::InvalidTriangleCount)?;
+
+    let mut file = BufWriter::new(File::create(path)?);
+    file.write_all(header(format, vertices.len(), faces.len()).as_bytes())?;
+
+    match format {
+        PlyFormat::Ascii => {
+            for vertex in &vertices {
+                let [x, y, z] = vertex.point.coords.components;
+                let [nx, ny, nz] = vertex.normal.components;
+                let [r, g, b, a] = vertex.color;
+                writeln!(file, "{x} {y} {z} {nx} {ny} {nz} {r} {g} {b} {a}")?;
+            }
+            for [a, b, c] in faces {
+                writeln!(file, "3 {a} {b} {c}")?;
+            }
+        }
+        PlyFormat::BinaryLittleEndian => {
+            for vertex in &vertices {
+                let components = vertex
+                    .point
+                    .coords
+                    .components
+                    .into_iter()
+                    .chain(vertex.normal.components);
+                for component in components {
+                    file.write_all(&component.into_f32().to_le_bytes())?;
+                }
+                file.write_all(&vertex.color)?;
+            }
+            for face in faces {
+                file.write_all(&[3])?;
+                for index in face {
+                    file.write_all(&(index as u32).to_le_bytes())?;
+                }
+            }
+        }
+    }
+
+    file.flush()?;
+
+    Ok(())
+}
+
+/// The vertices of the PLY file of a mesh, and its faces as vertex indices
+pub(crate) fn elements(
+    mesh: &Mesh<Point<3>>,
+) -> (Vec<PlyVertex>, Vec<[usize; 3]>) {
+    let mut vertices = Vec::new();
+    let mut indices_by_vertex = HashMap::new();
+    let normals = mesh.vertex_normals(Scalar::from_f64(DEFAULT_CREASE_ANGLE));
+    let faces = mesh
+        .triangles()
+        .zip(normals)
+        .map(|(triangle, normals)| {
+            [0, 1, 2].map(|i| {
+                let vertex = PlyVertex {
+                    point: triangle.points[i],
+                    normal: normals[i],
+                    color: triangle.color,
+                };
+                *indices_by_vertex.entry(vertex).or_insert_with(|| {
+                    vertices.push(vertex);
+                    vertices.len() - 1
+                })
+            })
+        })
+        .collect();
+
+    (vertices, faces)
+}
+
+/// The header of a PLY file with the given number of vertices and faces
+pub(crate) fn header(
+    format: PlyFormat,
+    vertices: usize,
+    faces: usize,
+) -> String {
+    // Writing to a `String` can't fail, so the results are ignored.
+    let mut header = String::new();
+    header.push_str("ply\n");
+    let _ = writeln!(header, "format {} 1.0", format.name());
+    header.push_str("comment Exported by Fornjot\n");
+    let _ = writeln!(header, "element vertex {vertices}");
+    for property in ["x", "y", "z", "nx", "ny", "nz"] {
+        let _ = writeln!(header, "property float {property}");
+    }
+    for property in ["red", "green", "blue", "alpha"] {
+        let _ = writeln!(header, "property uchar {property}");
+    }
+    let _ = writeln!(header, "element face {faces}");
+    header.push_str("property list uchar uint vertex_indices\n");
+    header.push_str("end_header\n");
+
+    header
+}
+
+/// The encoding of a PLY file
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum PlyFormat {
+    /// Human-readable text
+    Ascii,
+
+    /// Compact binary data, in little-endian byte order
+    #[default]
+    BinaryLittleEndian,
+}
+
+impl PlyFormat {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Ascii => "ascii",
+            Self::BinaryLittleEndian => "binary_little_endian",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub(crate) struct PlyVertex {
+    point: Point<3>,
+    normal: Vector<3>,
+    color: Color,
+}
@@ -0,0 +1,659 @@
+//! Export of sketches to SVG
+//!
+//! Intended for documentation, and for previewing sketches that are going to
+//! be laser cut.
+
+use std::{
+    f64::consts::PI,
+    fmt::Write as _,
+    io::{self, Write},
+};
+
+use fj_kernel::{
+    algorithms::{CycleApprox, Tolerance},
+    objects::{Curve, Cycle, Edge, Sketch, Surface},
+};
+use fj_math::{Circle, Point, Scalar};
+use thiserror::Error;
+
+/// Write a sketch to the provided writer, as an SVG document
+///
+/// Every face of the sketch is written as one `<path>` element, with a
+/// subpath for each of its cycles. Exteriors are wound counter-clockwise and
+/// interiors clockwise, in model coordinates. The paths use the `evenodd` fill
+/// rule, so holes stay empty.
+///
+/// Lines are written as line commands, and circles and circular arcs as arc
+/// commands. Any other curve is approximated by a polyline, within
+/// [`SvgOptions::tolerance`].
+///
+/// The faces of the sketch must lie in planes that are parallel to the
+/// xy-plane, which is where sketches are created. Their z-coordinate is
+/// ignored.
+pub fn write_svg(
+    sketch: &Sketch,
+    mut writer: impl Write,
+    options: SvgOptions,
+) -> Result<(), SvgError> {
+    options.validate()?;
+
+    let mut paths = Vec::new();
+    for face in sketch.faces() {
+        if face.triangles().is_some() {
+            return Err(SvgError::TriangleRepresentation);
+        }
+        if !is_parallel_to_xy_plane(face.surface()) {
+            return Err(SvgError::NotInXyPlane);
+        }
+
+        let mut subpaths = Vec::new();
+        for (cycle, is_exterior) in face
+            .exteriors()
+            .map(|cycle| (cycle, true))
+            .chain(face.interiors().map(|cycle| (cycle, false)))
+        {
+            let subpath = match Subpath::from_cycle(cycle, options.tolerance)? {
+                Some(subpath) => subpath,
+                None => continue,
+            };
+
+            let is_ccw = subpath.signed_area() > 0.;
+            subpaths.push(if is_ccw == is_exterior {
+                subpath
+            } else {
+                subpath.reverse()
+            });
+        }
+
+        if !subpaths.is_empty() {
+            paths.push(subpaths);
+        }
+    }
+
+    let mut bounds = Bounds::default();
+    for subpath in paths.iter().flatten() {
+        subpath.extend_bounds(&mut bounds);
+    }
+    let [min, max] = bounds.0.ok_or(SvgError::EmptySketch)?;
+
+    let [width, height] = [0, 1].map(|i| max[i] - min[i] + options.margin * 2.);
+    let min_x = min[0] - options.margin;
+    let min_y = if options.flip_y { -max[1] } else { min[1] } - options.margin;
+
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" "#,
+            r#"width="{}mm" height="{}mm" viewBox="{} {} {} {}">"#,
+        ),
+        number(width * options.mm_per_unit),
+        number(height * options.mm_per_unit),
+        number(min_x),
+        number(min_y),
+        number(width),
+        number(height),
+    )?;
+    writeln!(
+        writer,
+        r#"<g fill="{}" stroke="{}" stroke-width="{}">"#,
+        escape(&options.fill),
+        escape(&options.stroke),
+        number(options.stroke_width / options.mm_per_unit),
+    )?;
+
+    for subpaths in paths {
+        let mut data = String::new();
+        for subpath in subpaths {
+            subpath.write_data(&mut data, options.flip_y);
+        }
+
+        writeln!(writer, r#"<path d="{}" fill-rule="evenodd"/>"#, data)?;
+    }
+
+    writeln!(writer, "</g>")?;
+    writeln!(writer, "</svg>")?;
+
+    Ok(())
+}
+
+/// Options for [`write_svg`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgOptions {
+    /// The paint of the outlines, as an SVG paint value
+    ///
+    /// Use `"none"` to leave out the outlines.
+    pub stroke: String,
+
+    /// The width of the outlines, in millimeters
+    pub stroke_width: f64,
+
+    /// The paint of the faces, as an SVG paint value
+    ///
+    /// Use `"none"` to only draw the outlines.
+    pub fill: String,
+
+    /// The empty space around the sketch, in model units
+    pub margin: f64,
+
+    /// Whether to flip the y-axis
+    ///
+    /// The y-axis of SVG points down, while the y-axis of the model points
+    /// up. With this enabled, the sketch looks like it does in the viewer.
+    pub flip_y: bool,
+
+    /// The length of a model unit, in millimeters
+    pub mm_per_unit: f64,
+
+    /// The tolerance of curves that are approximated by polylines
+    pub tolerance: Tolerance,
+}
+
+impl SvgOptions {
+    fn validate(&self) -> Result<(), SvgError> {
+        if !(self.mm_per_unit.is_finite() && self.mm_per_unit > 0.) {
+            return Err(SvgError::InvalidOption(format!(
+                "millimeters per unit must be positive, not {}",
+                self.mm_per_unit
+            )));
+        }
+        if !(self.margin.is_finite() && self.margin >= 0.) {
+            return Err(SvgError::InvalidOption(format!(
+                "margin must not be negative, not {}",
+                self.margin
+            )));
+        }
+        if !(self.stroke_width.is_finite() && self.stroke_width >= 0.) {
+            return Err(SvgError::InvalidOption(format!(
+                "stroke width must not be negative, not {}",
+                self.stroke_width
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            stroke: "black".into(),
+            stroke_width: 0.1,
+            fill: "none".into(),
+            margin: 1.,
+            flip_y: true,
+            mm_per_unit: 1.,
+            tolerance: Tolerance::from_scalar(0.01)
+                .expect("Default tolerance is positive"),
+        }
+    }
+}
+
+/// An error that can occur while writing a sketch to SVG
+#[derive(Debug, Error)]
+pub enum SvgError {
+    /// I/O error whilst writing the SVG document
+    #[error("I/O error whilst writing the SVG document")]
+    Io(#[from] io::Error),
+
+    /// An option is out of range
+    #[error("invalid option: {0}")]
+    InvalidOption(String),
+
+    /// The sketch has nothing to draw
+    #[error("sketch has no edges")]
+    EmptySketch,
+
+    /// A face doesn't lie in a plane that is parallel to the xy-plane
+    #[error("face is not parallel to the xy-plane")]
+    NotInXyPlane,
+
+    /// A face is only defined by triangles, not by its boundary
+    #[error("face is defined by triangles, which can't be written to SVG")]
+    TriangleRepresentation,
+
+    /// A line edge is not bounded by vertices
+    #[error("line edge has no vertices")]
+    UnboundedLine,
+}
+
+/// A closed subpath of a `<path>` element, in model coordinates
+struct Subpath {
+    start: [f64; 2],
+    segments: Vec<Segment>,
+}
+
+impl Subpath {
+    /// Convert a cycle into a subpath
+    ///
+    /// Returns `None`, if the cycle has no edges.
+    fn from_cycle(
+        cycle: &Cycle,
+        tolerance: Tolerance,
+    ) -> Result<Option<Self>, SvgError> {
+        let mut subpath: Option<Self> = None;
+
+        for edge in cycle.edges() {
+            let (start, segments) = edge_segments(edge, tolerance)?;
+            subpath
+                .get_or_insert_with(|| Self {
+                    start,
+                    segments: Vec::new(),
+                })
+                .segments
+                .extend(segments);
+        }
+
+        Ok(subpath)
+    }
+
+    /// Compute the signed area that the subpath encloses
+    ///
+    /// Positive, if the subpath is counter-clockwise.
+    fn signed_area(&self) -> f64 {
+        let mut area = 0.;
+
+        let mut from = self.start;
+        for segment in &self.segments {
+            let to = segment.to();
+            area += (from[0] * to[1] - to[0] * from[1]) / 2.;
+
+            // An arc adds the circular segment between it and its chord.
+            if let Segment::Arc { radius, sweep, .. } = *segment {
+                area += radius * radius / 2. * (sweep - sweep.sin());
+            }
+
+            from = to;
+        }
+        area += (from[0] * self.start[1] - self.start[0] * from[1]) / 2.;
+
+        area
+    }
+
+    fn reverse(self) -> Self {
+        let mut points = vec![self.start];
+        points.extend(self.segments.iter().map(Segment::to));
+
+        let start = points[points.len() - 1];
+        let segments = self
+            .segments
+            .into_iter()
+            .enumerate()
+            .rev()
+            .map(|(i, segment)| segment.reverse(points[i]))
+            .collect();
+
+        Self { start, segments }
+    }
+
+    fn extend_bounds(&self, bounds: &mut Bounds) {
+        bounds.include(self.start);
+
+        let mut from = self.start;
+        for segment in &self.segments {
+            segment.extend_bounds(from, bounds);
+            from = segment.to();
+        }
+    }
+
+    fn write_data(&self, data: &mut String, flip_y: bool) {
+        let point = |[x, y]: [f64; 2]| {
+            let y = if flip_y { -y } else { y };
+            format!("{} {}", number(x), number(y))
+        };
+
+        if !data.is_empty() {
+            data.push(' ');
+        }
+        let _ = write!(data, "M {}", point(self.start));
+
+        for segment in &self.segments {
+            match *segment {
+                Segment::Line { to } => {
+                    let _ = write!(data, " L {}", point(to));
+                }
+                Segment::Arc {
+                    radius, sweep, to, ..
+                } => {
+                    let large_arc = sweep.abs() > PI;
+
+                    // The positive angle direction is counter-clockwise in
+                    // the model, but flipping the y-axis reverses it.
+                    let positive = (sweep > 0.) != flip_y;
+
+                    let _ = write!(
+                        data,
+                        " A {r} {r} 0 {} {} {}",
+                        large_arc as u8,
+                        positive as u8,
+                        point(to),
+                        r = number(radius),
+                    );
+                }
+            }
+        }
+
+        data.push_str(" Z");
+    }
+}
+
+/// A segment of a subpath, which starts where the previous one ended
+#[derive(Clone, Copy)]
+enum Segment {
+    Line {
+        to: [f64; 2],
+    },
+    Arc {
+        center: [f64; 2],
+        radius: f64,
+
+        /// The angle of the arc, positive if counter-clockwise
+        sweep: f64,
+
+        to: [f64; 2],
+    },
+}
+
+impl Segment {
+    fn to(&self) -> [f64; 2] {
+        match *self {
+            Self::Line { to } => to,
+            Self::Arc { to, .. } => to,
+        }
+    }
+
+    /// Reverse the segment, given the point it started from
+    fn reverse(self, from: [f64; 2]) -> Self {
+        match self {
+            Self::Line { .. } => Self::Line { to: from },
+            Self::Arc {
+                center,
+                radius,
+                sweep,
+                ..
+            } => Self::Arc {
+                center,
+                radius,
+                sweep: -sweep,
+                to: from,
+            },
+        }
+    }
+
+    fn extend_bounds(&self, from: [f64; 2], bounds: &mut Bounds) {
+        bounds.include(self.to());
+
+        if let Self::Arc {
+            center,
+            radius,
+            sweep,
+            ..
+        } = *self
+        {
+            // Besides its end points, an arc can only extend the bounds at
+            // the points where it is parallel to an axis.
+            let start = (from[1] - center[1]).atan2(from[0] - center[0]);
+            for i in 0..4 {
+                let angle = PI / 2. * i as f64;
+                let distance = if sweep > 0. {
+                    (angle - start).rem_euclid(PI * 2.)
+                } else {
+                    (start - angle).rem_euclid(PI * 2.)
+                };
+
+                if distance <= sweep.abs() {
+                    let (sin, cos) = angle.sin_cos();
+                    bounds.include([
+                        center[0] + radius * cos,
+                        center[1] + radius * sin,
+                    ]);
+                }
+            }
+        }
+    }
+}
+
+/// Convert an edge into its start point and the segments that follow it
+fn edge_segments(
+    edge: &Edge,
+    tolerance: Tolerance,
+) -> Result<([f64; 2], Vec<Segment>), SvgError> {
+    let vertices = edge.vertices().get();
+
+    match edge.curve().global_form() {
+        Curve::Line(_) => {
+            let [a, b] = vertices.ok_or(SvgError::UnboundedLine)?;
+            let [a, b] = [a, b].map(|vertex| xy(vertex.global().position()));
+
+            Ok((a, vec![Segment::Line { to: b }]))
+        }
+        Curve::Circle(circle) if is_circle_in_xy_plane(circle) => {
+            let [start, end] = match vertices {
+                Some(vertices) => vertices.map(|vertex| vertex.position().t),
+                None => [0., PI * 2.].map(Scalar::from_f64),
+            };
+            let [start, end] = [start, end].map(|t| t.into_f64());
+
+            let center = xy(circle.center);
+            let radius = circle.a.magnitude().into_f64();
+
+            // The direction in which the circle coordinates run, in the
+            // xy-plane.
+            let direction = (circle.a.x * circle.b.y - circle.a.y * circle.b.x)
+                .into_f64()
+                .signum();
+
+            let from = match vertices {
+                Some([a, _]) => xy(a.global().position()),
+                None => xy(circle.point_from_circle_coords([start])),
+            };
+            let to = match vertices {
+                Some([_, b]) => xy(b.global().position()),
+                None => from,
+            };
+
+            // An arc command can't describe a full circle, so longer arcs
+            // are split in half.
+            let sweep = (end - start) * direction;
+            let segments = if sweep.abs() > PI {
+                let middle = (start + end) / 2.;
+                let middle = xy(circle.point_from_circle_coords([middle]));
+
+                vec![
+                    Segment::Arc {
+                        center,
+                        radius,
+                        sweep: sweep / 2.,
+                        to: middle,
+                    },
+                    Segment::Arc {
+                        center,
+                        radius,
+                        sweep: sweep / 2.,
+                        to,
+                    },
+                ]
+            } else {
+                vec![Segment::Arc {
+                    center,
+                    radius,
+                    sweep,
+                    to,
+                }]
+            };
+
+            Ok((from, segments))
+        }
+        _ => {
+            let cycle = Cycle {
+                edges: vec![edge.clone()],
+            };
+            let approx = CycleApprox::new(&cycle, tolerance);
+
+            let mut points =
+                approx.points.iter().map(|point| xy(*point.global_form()));
+            let start =
+                points.next().expect("Approximation of curve has no points");
+            let segments = points.map(|to| Segment::Line { to }).collect();
+
+            Ok((start, segments))
+        }
+    }
+}
+
+fn is_parallel_to_xy_plane(surface: &Surface) -> bool {
+    let Surface::SweptCurve(surface) = surface;
+    let normal = surface.normal_at([0., 0.]);
+
+    matches!(surface.curve, Curve::Line(_))
+        && normal.x.into_f64().abs() <= EPSILON
+        && normal.y.into_f64().abs() <= EPSILON
+}
+
+fn is_circle_in_xy_plane(circle: &Circle<3>) -> bool {
+    let radius = circle.a.magnitude().into_f64();
+    let epsilon = EPSILON * radius;
+
+    circle.a.z.into_f64().abs() <= epsilon
+        && circle.b.z.into_f64().abs() <= epsilon
+        && (circle.b.magnitude().into_f64() - radius).abs() <= epsilon
+}
+
+/// The tolerance of the checks that decide how geometry is written
+const EPSILON: f64 = 1e-9;
+
+fn xy(point: Point<3>) -> [f64; 2] {
+    [point.x.into_f64(), point.y.into_f64()]
+}
+
+/// An axis-aligned bounding box, which is empty until a point is included
+#[derive(Default)]
+struct Bounds(Option<[[f64; 2]; 2]>);
+
+impl Bounds {
+    fn include(&mut self, point: [f64; 2]) {
+        let [min, max] = self.0.get_or_insert([point, point]);
+        *min = [min[0].min(point[0]), min[1].min(point[1])];
+        *max = [max[0].max(point[0]), max[1].max(point[1])];
+    }
+}
+
+/// Format a number for the SVG document
+fn number(value: f64) -> f64 {
+    // Values that are zero, up to floating point noise, are written as zero.
+    // That also avoids writing negative zero.
+    if value.abs() < 1e-12 {
+        0.
+    } else {
+        value
+    }
+}
+
+/// Escape a string for use in an attribute value
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_kernel::objects::{Cycle, Edge, Face, Sketch, Surface};
+    use fj_math::Scalar;
+    use xml::reader::{EventReader, XmlEvent};
+
+    use super::{write_svg, SvgError, SvgOptions};
+
+    #[test]
+    fn square_with_circular_hole() -> Result<(), SvgError> {
+        let svg = square_with_hole(SvgOptions::default())?;
+        let elements = parse(&svg);
+
+        let view_box = attribute(&elements, "svg", "viewBox");
+        assert_eq!(view_box, "-3 -3 6 6");
+
+        let data = attribute(&elements, "path", "d");
+        assert_eq!(data.matches('A').count(), 2);
+        assert_eq!(data.matches('L').count(), 4);
+        assert_eq!(data.matches('M').count(), 2);
+
+        // The exterior is counter-clockwise in the model, the hole
+        // clockwise. Flipping the y-axis turns this around, which is what the
+        // sweep flag of the arcs reflects.
+        assert!(data.contains("A 1 1 0 0 1"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn unit_mapping() -> Result<(), SvgError> {
+        let svg = square_with_hole(SvgOptions {
+            margin: 0.,
+            mm_per_unit: 25.4,
+            flip_y: false,
+            ..SvgOptions::default()
+        })?;
+        let elements = parse(&svg);
+
+        assert_eq!(attribute(&elements, "svg", "width"), "101.6mm");
+        assert_eq!(attribute(&elements, "svg", "height"), "101.6mm");
+        assert_eq!(attribute(&elements, "svg", "viewBox"), "-2 -2 4 4");
+
+        Ok(())
+    }
+
+    fn square_with_hole(options: SvgOptions) -> Result<String, SvgError> {
+        let surface = Surface::xy_plane();
+        let exterior = Cycle::polygon_from_points(
+            &surface,
+            [[-2., -2.], [2., -2.], [2., 2.], [-2., 2.]],
+        );
+        let interior = Cycle {
+            edges: vec![Edge::circle_from_radius(Scalar::ONE)],
+        };
+        let face = Face::new(surface, [exterior], [interior], [255, 0, 0, 255]);
+        let sketch = Sketch::from_faces([face]);
+
+        let mut svg = Vec::new();
+        write_svg(&sketch, &mut svg, options)?;
+
+        Ok(String::from_utf8(svg).expect("SVG is not valid UTF-8"))
+    }
+
+    /// Parse the document, returning the name and attributes of each element
+    fn parse(svg: &str) -> Vec<(String, Vec<(String, String)>)> {
+        EventReader::new(svg.as_bytes())
+            .into_iter()
+            .filter_map(|event| match event.expect("SVG is not valid XML") {
+                XmlEvent::StartElement {
+                    name, attributes, ..
+                } => Some((
+                    name.local_name,
+                    attributes
+                        .into_iter()
+                        .map(|a| (a.name.local_name, a.value))
+                        .collect(),
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn attribute<'r>(
+        elements: &'r [(String, Vec<(String, String)>)],
+        element: &str,
+        name: &str,
+    ) -> &'r str {
+        let (_, attributes) = elements
+            .iter()
+            .find(|(n, _)| n == element)
+            .expect("Element not found");
+        attributes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+            .expect("Attribute not found")
+    }
+}
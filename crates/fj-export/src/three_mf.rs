@@ -0,0 +1,226 @@
+use std::{
+    collections::HashMap, fmt::Write as _, fs::File, io::Write, path::Path,
+};
+
+use fj_interop::{
+    material::{Material, Materials},
+    mesh::{Color, Mesh, Triangle as MeshTriangle},
+};
+use fj_math::Point;
+
+use crate::{group_by_material, Error};
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="model" ContentType="application/vnd.ms-package.3dmanufacturing-3dmodel+xml"/></Types>
+"#;
+
+const RELATIONSHIPS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Target="/3D/3dmodel.model" Id="rel0" Type="http://schemas.microsoft.com/3dmanufacturing/2013/01/3dmodel"/></Relationships>
+"#;
+
+/// The namespace of the 3MF materials and properties extension
+const MATERIAL_NAMESPACE: &str =
+    "http://schemas.microsoft.com/3dmanufacturing/material/2015/02";
+
+/// Export a mesh to 3MF, with one object per material
+///
+/// The materials are written as base materials. Each object refers to the
+/// base material of its triangles, so slicers can assign them to extruders.
+pub fn export_3mf_with_materials(
+    mesh: &Mesh<Point<3>>,
+    materials: &Materials,
+    path: &Path,
+) -> Result<(), Error> {
+    write_package(&model_xml(mesh, materials), path)
+}
+
+/// Export a mesh to 3MF, keeping the colors of its triangles
+///
+/// The mesh is written as a single object. Its colors are written as a color
+/// group of the materials extension, with each distinct color appearing once.
+/// Each triangle refers to its color in the group.
+pub fn export_3mf_with_colors(
+    mesh: &Mesh<Point<3>>,
+    path: &Path,
+) -> Result<(), Error> {
+    write_package(&colors_model_xml(mesh), path)
+}
+
+fn write_package(model: &str, path: &Path) -> Result<(), Error> {
+    let mut package = zip::ZipWriter::new(File::create(path)?);
+    let options = zip::write::FileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    for (name, content) in [
+        ("[Content_Types].xml", CONTENT_TYPES),
+        ("_rels/.rels", RELATIONSHIPS),
+        ("3D/3dmodel.model", model),
+    ] {
+        package.start_file(name, options)?;
+        package.write_all(content.as_bytes())?;
+    }
+
+    package.finish()?;
+
+    Ok(())
+}
+
+fn model_xml(mesh: &Mesh<Point<3>>, materials: &Materials) -> String {
+    let groups = group_by_material(mesh, materials);
+
+    // The base materials get ID `1`, objects are numbered after that. Writing
+    // to a `String` can't fail, so the results are ignored.
+    let mut xml = String::new();
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push('\n');
+    xml.push_str(
+        r#"<model unit="millimeter" xml:lang="en-US" xmlns="http://schemas.microsoft.com/3dmanufacturing/core/2015/02">"#,
+    );
+    xml.push_str("<resources>");
+
+    xml.push_str(r#"<basematerials id="1">"#);
+    for (material, _) in &groups {
+        let Material {
+            name,
+            display_color,
+            ..
+        } = &materials.materials()[*material];
+
+        let _ = write!(
+            xml,
+            r#"<base name="{}" displaycolor="{}"/>"#,
+            escape(name),
+            hex_color(*display_color),
+        );
+    }
+    xml.push_str("</basematerials>");
+
+    for (pindex, (_, triangles)) in groups.iter().enumerate() {
+        let id = pindex + 2;
+        let (vertices, triangles) = index_vertices(triangles);
+
+        let _ = write!(
+            xml,
+            r#"<object id="{id}" type="model" pid="1" pindex="{pindex}"><mesh>"#,
+        );
+        write_vertices(&mut xml, &vertices);
+        xml.push_str("<triangles>");
+        for [v1, v2, v3] in triangles {
+            let _ = write!(xml, r#"<triangle v1="{v1}" v2="{v2}" v3="{v3}"/>"#);
+        }
+        xml.push_str("</triangles></mesh></object>");
+    }
+
+    xml.push_str("</resources><build>");
+    for pindex in 0..groups.len() {
+        let _ = write!(xml, r#"<item objectid="{}"/>"#, pindex + 2);
+    }
+    xml.push_str("</build></model>\n");
+
+    xml
+}
+
+fn colors_model_xml(mesh: &Mesh<Point<3>>) -> String {
+    let triangles: Vec<_> = mesh.triangles().collect();
+
+    // Each distinct color is written once, in the order of first use.
+    let mut colors = Vec::new();
+    let mut indices_by_color = HashMap::new();
+    let color_indices: Vec<_> = triangles
+        .iter()
+        .map(|triangle| {
+            *indices_by_color.entry(triangle.color).or_insert_with(|| {
+                colors.push(triangle.color);
+                colors.len() - 1
+            })
+        })
+        .collect();
+
+    let (vertices, triangles) = index_vertices(&triangles);
+
+    // The color group gets ID `1`, the object ID `2`. Writing to a `String`
+    // can't fail, so the results are ignored.
+    let mut xml = String::new();
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push('\n');
+    let _ = write!(
+        xml,
+        r#"<model unit="millimeter" xml:lang="en-US" xmlns="http://schemas.microsoft.com/3dmanufacturing/core/2015/02" xmlns:m="{MATERIAL_NAMESPACE}">"#,
+    );
+    xml.push_str("<resources>");
+
+    // A color group must not be empty, which it would be for an empty mesh.
+    if colors.is_empty() {
+        xml.push_str(r#"<object id="2" type="model"><mesh>"#);
+    } else {
+        xml.push_str(r#"<m:colorgroup id="1">"#);
+        for color in colors {
+            let _ = write!(xml, r#"<m:color color="{}"/>"#, hex_color(color));
+        }
+        xml.push_str("</m:colorgroup>");
+
+        xml.push_str(
+            r#"<object id="2" type="model" pid="1" pindex="0"><mesh>"#,
+        );
+    }
+
+    write_vertices(&mut xml, &vertices);
+    xml.push_str("<triangles>");
+    for ([v1, v2, v3], p1) in triangles.into_iter().zip(color_indices) {
+        let _ = write!(
+            xml,
+            r#"<triangle v1="{v1}" v2="{v2}" v3="{v3}" pid="1" p1="{p1}"/>"#
+        );
+    }
+    xml.push_str("</triangles></mesh></object>");
+
+    xml.push_str(r#"</resources><build><item objectid="2"/></build>"#);
+    xml.push_str("</model>\n");
+
+    xml
+}
+
+/// Assign indices to the vertices of triangles, sharing equal vertices
+fn index_vertices(
+    triangles: &[MeshTriangle],
+) -> (Vec<Point<3>>, Vec<[usize; 3]>) {
+    let mut vertices = Vec::new();
+    let mut indices_by_vertex = HashMap::new();
+
+    let triangles = triangles
+        .iter()
+        .map(|triangle| {
+            triangle.points.map(|point| {
+                *indices_by_vertex.entry(point).or_insert_with(|| {
+                    vertices.push(point);
+                    vertices.len() - 1
+                })
+            })
+        })
+        .collect();
+
+    (vertices, triangles)
+}
+
+fn write_vertices(xml: &mut String, vertices: &[Point<3>]) {
+    xml.push_str("<vertices>");
+    for vertex in vertices {
+        let _ = write!(
+            xml,
+            r#"<vertex x="{}" y="{}" z="{}"/>"#,
+            vertex.x, vertex.y, vertex.z,
+        );
+    }
+    xml.push_str("</vertices>");
+}
+
+fn hex_color([r, g, b, a]: Color) -> String {
+    format!("#{r:02X}{g:02X}{b:02X}{a:02X}")
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,10 @@
+use std::env;
+
+fn main() {
+    // The C API test compiles a C program, which requires knowing the target.
+    // It's only available to build scripts, so pass it on.
+    for var in ["TARGET", "HOST"] {
+        let value = env::var(var).expect("Cargo didn't set variable");
+        println!("cargo:rustc-env=FJ_FFI_{var}={value}");
+    }
+}
@@ -0,0 +1,71 @@
+/*
+ * Fornjot C API
+ *
+ * See the documentation of the `fj-ffi` crate for details. In short:
+ *
+ * - Shapes and meshes are referred to by handles, which must be freed exactly
+ *   once, using `fj_shape_free` and `fj_mesh_free`. Using a handle after it was
+ *   freed results in `FJ_INVALID_HANDLE`.
+ * - Functions that can fail return a status. On failure, out parameters are
+ *   left untouched, and `fj_last_error_message` describes the error.
+ * - All functions can be called from any thread. Buffers returned by the mesh
+ *   accessors stay valid until the mesh is freed.
+ */
+
+#ifndef FJ_H
+#define FJ_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef uint64_t FjHandle;
+
+typedef enum FjStatus {
+    FJ_OK = 0,
+    FJ_NULL_POINTER = 1,
+    FJ_INVALID_HANDLE = 2,
+    FJ_INVALID_ARGUMENT = 3,
+    FJ_PROCESSING = 4,
+    FJ_EXPORT = 5,
+    FJ_PANIC = 6,
+} FjStatus;
+
+/* Errors */
+
+const char *fj_last_error_message(void);
+
+/* Shapes */
+
+FjStatus fj_shape_sketch(const double *points, size_t count, FjHandle *out);
+FjStatus fj_shape_cube(double edge_length, FjHandle *out);
+FjStatus fj_shape_sweep(FjHandle sketch, double x, double y, double z,
+                        FjHandle *out);
+FjStatus fj_shape_transform(FjHandle shape, double axis_x, double axis_y,
+                            double axis_z, double angle, double offset_x,
+                            double offset_y, double offset_z, FjHandle *out);
+FjStatus fj_shape_free(FjHandle shape);
+
+/* Meshes */
+
+FjStatus fj_evaluate(FjHandle shape, double tolerance, FjHandle *out_mesh);
+FjStatus fj_mesh_positions(FjHandle mesh, const double **out_ptr,
+                           size_t *out_len);
+FjStatus fj_mesh_indices(FjHandle mesh, const uint32_t **out_ptr,
+                         size_t *out_len);
+FjStatus fj_mesh_colors(FjHandle mesh, const uint8_t **out_ptr,
+                        size_t *out_len);
+FjStatus fj_mesh_free(FjHandle mesh);
+
+/* Export */
+
+FjStatus fj_export_stl(FjHandle mesh, const char *path);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* FJ_H */
@@ -0,0 +1,92 @@
+//! Handles to objects that are owned by the library
+
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use parking_lot::Mutex;
+
+use crate::status::{Error, FjStatus};
+
+/// A handle to an object that is owned by the library
+///
+/// Handles are plain numbers, not pointers. They are never reused, so using a
+/// handle after its object was freed is detected, instead of accessing freed
+/// memory. `0` is never a valid handle.
+pub type FjHandle = u64;
+
+// Shared by all registries, so a handle of one kind of object is never a valid
+// handle of another kind.
+static NEXT_HANDLE: AtomicU64 = AtomicU64::new(1);
+
+/// The objects of one kind, by their handles
+///
+/// The registry can be accessed from any thread, which is why its objects must
+/// be `Send`. They don't need to be `Sync`, as they are only ever accessed
+/// while the registry is locked.
+pub struct Registry<T> {
+    objects: Mutex<Option<HashMap<FjHandle, T>>>,
+    kind: &'static str,
+}
+
+impl<T: Send> Registry<T> {
+    /// Create an empty registry
+    ///
+    /// `kind` names the kind of object in error messages.
+    pub const fn new(kind: &'static str) -> Self {
+        Self {
+            objects: Mutex::new(None),
+            kind,
+        }
+    }
+
+    /// Take ownership of an object and return a new handle to it
+    pub fn insert(&self, object: T) -> FjHandle {
+        let handle = NEXT_HANDLE.fetch_add(1, Ordering::Relaxed);
+        self.objects
+            .lock()
+            .get_or_insert_with(HashMap::new)
+            .insert(handle, object);
+        handle
+    }
+
+    /// Access the object behind a handle
+    ///
+    /// The registry stays locked while `f` runs.
+    pub fn with<R>(
+        &self,
+        handle: FjHandle,
+        f: impl FnOnce(&T) -> R,
+    ) -> Result<R, Error> {
+        let objects = self.objects.lock();
+        let object = objects
+            .as_ref()
+            .and_then(|objects| objects.get(&handle))
+            .ok_or_else(|| self.invalid(handle))?;
+
+        Ok(f(object))
+    }
+
+    /// Drop the object behind a handle
+    pub fn remove(&self, handle: FjHandle) -> Result<(), Error> {
+        let object = self
+            .objects
+            .lock()
+            .as_mut()
+            .and_then(|objects| objects.remove(&handle))
+            .ok_or_else(|| self.invalid(handle))?;
+
+        // Dropping the object might take a while. Don't hold the lock for it.
+        drop(object);
+
+        Ok(())
+    }
+
+    fn invalid(&self, handle: FjHandle) -> Error {
+        Error::new(
+            FjStatus::InvalidHandle,
+            format!("{handle} is not a valid {} handle", self.kind),
+        )
+    }
+}
@@ -0,0 +1,649 @@
+//! # Fornjot C API
+//!
+//! This library is part of the [Fornjot] ecosystem. Fornjot is an open-source,
+//! code-first CAD application; and collection of libraries that make up the CAD
+//! application, but can be used independently.
+//!
+//! This library exposes a minimal C ABI over the Fornjot operations pipeline,
+//! so the kernel can be driven from hosts that aren't written in Rust. The
+//! declarations for C are in `include/fj.h`.
+//!
+//! ## Handles
+//!
+//! Shapes and meshes are owned by the library. The host refers to them through
+//! opaque handles, and frees them explicitly, using [`fj_shape_free`] and
+//! [`fj_mesh_free`]. Handles are never reused. Passing a handle that was freed,
+//! or never created, results in [`FjStatus::InvalidHandle`].
+//!
+//! Creating a shape from other shapes doesn't consume them. Each handle needs to
+//! be freed exactly once.
+//!
+//! ## Errors
+//!
+//! Every function that can fail returns an [`FjStatus`]. Results are written to
+//! out parameters, which are left untouched on failure. Details about the most
+//! recent error are available from [`fj_last_error_message`].
+//!
+//! ## Thread safety
+//!
+//! All functions can be called from any thread, and handles can be passed
+//! between threads. Access to the objects behind handles is synchronized
+//! internally. The only exception are the buffers returned by the mesh
+//! accessors, like [`fj_mesh_positions`]: They stay valid, until the mesh is
+//! freed, and the host must make sure that no thread reads them anymore when
+//! that happens.
+//!
+//! Error messages are recorded per thread.
+//!
+//! [Fornjot]: https://www.fornjot.app/
+
+#![warn(missing_docs)]
+
+mod handle;
+mod status;
+
+pub use self::{
+    handle::FjHandle,
+    status::{fj_last_error_message, FjStatus},
+};
+
+use std::{ffi::CStr, os::raw::c_char, path::Path};
+
+use fj::syntax::{Sketch as _, Sweep as _};
+use fj_interop::mesh::Mesh;
+use fj_kernel::algorithms::Tolerance;
+use fj_math::{Point, Scalar};
+use fj_operations::shape_processor::ShapeProcessor;
+
+use self::{
+    handle::Registry,
+    status::{run, Error},
+};
+
+// The objects in these registries must be `Send`, or they won't compile. This
+// is what makes it safe to use handles from any thread.
+static SHAPES: Registry<fj::Shape> = Registry::new("shape");
+static MESHES: Registry<MeshData> = Registry::new("mesh");
+
+/// A mesh, along with the flat buffers that the accessors return
+struct MeshData {
+    mesh: Mesh<Point<3>>,
+    positions: Vec<f64>,
+    indices: Vec<u32>,
+    colors: Vec<u8>,
+}
+
+impl MeshData {
+    fn new(mesh: Mesh<Point<3>>) -> Self {
+        let positions = mesh
+            .vertices()
+            .flat_map(|vertex| vertex.coords.components)
+            .map(Scalar::into_f64)
+            .collect();
+        let indices = mesh.indices().collect();
+        let colors = mesh
+            .triangles()
+            .flat_map(|triangle| triangle.color)
+            .collect();
+
+        Self {
+            mesh,
+            positions,
+            indices,
+            colors,
+        }
+    }
+}
+
+/// Create a sketch from a polygon
+///
+/// `points` contains `count` points, as consecutive pairs of x and y
+/// coordinates. At least 3 points are required.
+///
+/// # Safety
+///
+/// `points` must point to `count * 2` readable `double` values. `out` must
+/// point to a writable handle.
+#[no_mangle]
+pub unsafe extern "C" fn fj_shape_sketch(
+    points: *const f64,
+    count: usize,
+    out: *mut FjHandle,
+) -> FjStatus {
+    run(|| {
+        let out = out_param(out, "out")?;
+        if points.is_null() {
+            return Err(Error::null_pointer("points"));
+        }
+        if count < 3 {
+            return Err(Error::invalid_argument(format!(
+                "A sketch needs at least 3 points, got {count}"
+            )));
+        }
+
+        let coords = std::slice::from_raw_parts(points, count * 2);
+        if !coords.iter().all(|coord| coord.is_finite()) {
+            return Err(Error::invalid_argument(
+                "Sketch points must be finite",
+            ));
+        }
+
+        let points: Vec<_> = coords
+            .chunks_exact(2)
+            .map(|point| [point[0], point[1]])
+            .collect();
+
+        *out = SHAPES.insert(points.sketch().into());
+        Ok(())
+    })
+}
+
+/// Create a cube with the given edge length
+///
+/// The cube is centered on the z-axis, with its bottom face in the xy-plane.
+///
+/// # Safety
+///
+/// `out` must point to a writable handle.
+#[no_mangle]
+pub unsafe extern "C" fn fj_shape_cube(
+    edge_length: f64,
+    out: *mut FjHandle,
+) -> FjStatus {
+    run(|| {
+        let out = out_param(out, "out")?;
+        if !(edge_length.is_finite() && edge_length > 0.) {
+            return Err(Error::invalid_argument(format!(
+                "Edge length must be positive, got {edge_length}"
+            )));
+        }
+
+        let half = edge_length / 2.;
+        let square =
+            [[-half, -half], [half, -half], [half, half], [-half, half]];
+        let cube = square.sketch().sweep([0., 0., edge_length]);
+
+        *out = SHAPES.insert(cube.into());
+        Ok(())
+    })
+}
+
+/// Sweep a 2-dimensional shape along a straight path
+///
+/// `sketch` must refer to a 2-dimensional shape, like one created by
+/// [`fj_shape_sketch`].
+///
+/// # Safety
+///
+/// `out` must point to a writable handle.
+#[no_mangle]
+pub unsafe extern "C" fn fj_shape_sweep(
+    sketch: FjHandle,
+    x: f64,
+    y: f64,
+    z: f64,
+    out: *mut FjHandle,
+) -> FjStatus {
+    run(|| {
+        let out = out_param(out, "out")?;
+        let path = finite_vector([x, y, z], "Sweep path")?;
+
+        let sweep = SHAPES.with(sketch, |shape| match shape {
+            fj::Shape::Shape2d(shape) => Ok(shape.sweep(path)),
+            _ => Err(Error::invalid_argument(
+                "Only 2-dimensional shapes can be swept",
+            )),
+        })??;
+
+        *out = SHAPES.insert(sweep.into());
+        Ok(())
+    })
+}
+
+/// Rotate a shape around an axis through the origin, then translate it
+///
+/// The angle is given in radians. The axis doesn't need to be normalized, but
+/// must not be zero, unless the angle is.
+///
+/// # Safety
+///
+/// `out` must point to a writable handle.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn fj_shape_transform(
+    shape: FjHandle,
+    axis_x: f64,
+    axis_y: f64,
+    axis_z: f64,
+    angle: f64,
+    offset_x: f64,
+    offset_y: f64,
+    offset_z: f64,
+    out: *mut FjHandle,
+) -> FjStatus {
+    run(|| {
+        let out = out_param(out, "out")?;
+        let axis = finite_vector([axis_x, axis_y, axis_z], "Rotation axis")?;
+        let offset = finite_vector([offset_x, offset_y, offset_z], "Offset")?;
+        if !angle.is_finite() {
+            return Err(Error::invalid_argument("Angle must be finite"));
+        }
+        if angle != 0. && axis == [0.; 3] {
+            return Err(Error::invalid_argument("Rotation axis is zero"));
+        }
+
+        let shape = SHAPES.with(shape, Clone::clone)?;
+        let transform = fj::Transform {
+            shape,
+            axis,
+            angle: fj::Angle::from_rad(angle),
+            offset,
+        };
+
+        *out = SHAPES.insert(transform.into());
+        Ok(())
+    })
+}
+
+/// Free a shape
+///
+/// Shapes that were created from this one are not affected.
+#[no_mangle]
+pub extern "C" fn fj_shape_free(shape: FjHandle) -> FjStatus {
+    run(|| SHAPES.remove(shape))
+}
+
+/// Process a shape into a triangle mesh
+///
+/// The mesh approximates the shape within `tolerance`. A tolerance of `0`
+/// selects a tolerance based on the size of the shape.
+///
+/// # Safety
+///
+/// `out_mesh` must point to a writable handle.
+#[no_mangle]
+pub unsafe extern "C" fn fj_evaluate(
+    shape: FjHandle,
+    tolerance: f64,
+    out_mesh: *mut FjHandle,
+) -> FjStatus {
+    run(|| {
+        let out_mesh = out_param(out_mesh, "out_mesh")?;
+        if !(tolerance.is_finite() && tolerance >= 0.) {
+            return Err(Error::invalid_argument(format!(
+                "Tolerance must not be negative, got {tolerance}"
+            )));
+        }
+
+        let tolerance = if tolerance == 0. {
+            None
+        } else {
+            let tolerance = Tolerance::from_scalar(tolerance)
+                .map_err(|err| Error::invalid_argument(err.to_string()))?;
+            Some(tolerance)
+        };
+
+        // Processing can take a while. Don't block access to other shapes
+        // while it's going on.
+        let shape = SHAPES.with(shape, Clone::clone)?;
+
+        let processor = ShapeProcessor {
+            tolerance,
+            ..ShapeProcessor::default()
+        };
+        let processed = processor.process(&shape).map_err(|err| {
+            Error::new(FjStatus::Processing, error_chain(&err))
+        })?;
+
+        *out_mesh = MESHES.insert(MeshData::new(processed.mesh));
+        Ok(())
+    })
+}
+
+/// Access the vertex positions of a mesh
+///
+/// Writes a pointer to the positions to `out_ptr`, and the number of values
+/// to `out_len`. Each vertex has 3 consecutive values, its x, y, and z
+/// coordinates. The buffer stays valid, until the mesh is freed.
+///
+/// # Safety
+///
+/// `out_ptr` and `out_len` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn fj_mesh_positions(
+    mesh: FjHandle,
+    out_ptr: *mut *const f64,
+    out_len: *mut usize,
+) -> FjStatus {
+    mesh_buffer(mesh, out_ptr, out_len, |mesh| &mesh.positions)
+}
+
+/// Access the vertex indices of a mesh
+///
+/// Writes a pointer to the indices to `out_ptr`, and the number of indices to
+/// `out_len`. Each triangle has 3 consecutive indices. The buffer stays valid,
+/// until the mesh is freed.
+///
+/// # Safety
+///
+/// `out_ptr` and `out_len` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn fj_mesh_indices(
+    mesh: FjHandle,
+    out_ptr: *mut *const u32,
+    out_len: *mut usize,
+) -> FjStatus {
+    mesh_buffer(mesh, out_ptr, out_len, |mesh| &mesh.indices)
+}
+
+/// Access the triangle colors of a mesh
+///
+/// Writes a pointer to the colors to `out_ptr`, and the number of values to
+/// `out_len`. Each triangle has 4 consecutive values, its red, green, blue,
+/// and alpha components. The buffer stays valid, until the mesh is freed.
+///
+/// # Safety
+///
+/// `out_ptr` and `out_len` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn fj_mesh_colors(
+    mesh: FjHandle,
+    out_ptr: *mut *const u8,
+    out_len: *mut usize,
+) -> FjStatus {
+    mesh_buffer(mesh, out_ptr, out_len, |mesh| &mesh.colors)
+}
+
+/// Free a mesh
+///
+/// Invalidates all buffers returned by the mesh accessors.
+#[no_mangle]
+pub extern "C" fn fj_mesh_free(mesh: FjHandle) -> FjStatus {
+    run(|| MESHES.remove(mesh))
+}
+
+/// Export a mesh to an STL file
+///
+/// `path` is a null-terminated, UTF-8 encoded path, which must have the `stl`
+/// extension. Meshes that are not watertight are refused.
+///
+/// # Safety
+///
+/// `path` must point to a null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn fj_export_stl(
+    mesh: FjHandle,
+    path: *const c_char,
+) -> FjStatus {
+    run(|| {
+        if path.is_null() {
+            return Err(Error::null_pointer("path"));
+        }
+        let path = CStr::from_ptr(path)
+            .to_str()
+            .map_err(|_| Error::invalid_argument("Path is not UTF-8"))?;
+        let path = Path::new(path);
+
+        let is_stl = path
+            .extension()
+            .map(|extension| extension.eq_ignore_ascii_case("stl"))
+            .unwrap_or(false);
+        if !is_stl {
+            return Err(Error::invalid_argument(format!(
+                "Expected path with `stl` extension, got `{}`",
+                path.display()
+            )));
+        }
+
+        MESHES
+            .with(mesh, |mesh| fj_export::export(&mesh.mesh, path))?
+            .map_err(|err| Error::new(FjStatus::Export, error_chain(&err)))
+    })
+}
+
+/// Check an out parameter, and convert it into a reference
+unsafe fn out_param<'r, T>(
+    out: *mut T,
+    name: &str,
+) -> Result<&'r mut T, Error> {
+    out.as_mut().ok_or_else(|| Error::null_pointer(name))
+}
+
+fn finite_vector(vector: [f64; 3], name: &str) -> Result<[f64; 3], Error> {
+    if vector.iter().all(|component| component.is_finite()) {
+        Ok(vector)
+    } else {
+        Err(Error::invalid_argument(format!("{name} must be finite")))
+    }
+}
+
+unsafe fn mesh_buffer<T>(
+    mesh: FjHandle,
+    out_ptr: *mut *const T,
+    out_len: *mut usize,
+    buffer: impl FnOnce(&MeshData) -> &Vec<T>,
+) -> FjStatus {
+    run(|| {
+        let out_ptr = out_param(out_ptr, "out_ptr")?;
+        let out_len = out_param(out_len, "out_len")?;
+
+        // The buffer isn't modified until the mesh is dropped, so the pointer
+        // stays valid after the registry is unlocked.
+        let (ptr, len) = MESHES.with(mesh, |mesh| {
+            let buffer = buffer(mesh);
+            (buffer.as_ptr(), buffer.len())
+        })?;
+
+        *out_ptr = ptr;
+        *out_len = len;
+        Ok(())
+    })
+}
+
+/// Format an error, including all of its sources
+fn error_chain(err: &dyn std::error::Error) -> String {
+    let mut message = err.to_string();
+
+    let mut source = err.source();
+    while let Some(err) = source {
+        message.push_str(": ");
+        message.push_str(&err.to_string());
+        source = err.source();
+    }
+
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ffi::CStr, ptr};
+
+    use super::{
+        fj_evaluate, fj_last_error_message, fj_mesh_free, fj_mesh_indices,
+        fj_mesh_positions, fj_shape_cube, fj_shape_free, fj_shape_sketch,
+        fj_shape_sweep, fj_shape_transform, FjHandle, FjStatus,
+    };
+
+    #[test]
+    fn evaluate_cube() {
+        unsafe {
+            let cube = cube(2.);
+
+            let mut mesh = 0;
+            assert_eq!(fj_evaluate(cube, 0., &mut mesh), FjStatus::Ok);
+
+            let mut positions = ptr::null();
+            let mut positions_len = 0;
+            assert_eq!(
+                fj_mesh_positions(mesh, &mut positions, &mut positions_len),
+                FjStatus::Ok
+            );
+            let positions =
+                std::slice::from_raw_parts(positions, positions_len);
+            assert_eq!(positions.len(), 8 * 3);
+            assert!(positions.iter().all(|&coord| coord.abs() <= 2.));
+
+            let mut indices = ptr::null();
+            let mut indices_len = 0;
+            assert_eq!(
+                fj_mesh_indices(mesh, &mut indices, &mut indices_len),
+                FjStatus::Ok
+            );
+            assert_eq!(indices_len, 12 * 3);
+
+            assert_eq!(fj_mesh_free(mesh), FjStatus::Ok);
+            assert_eq!(fj_shape_free(cube), FjStatus::Ok);
+        }
+    }
+
+    #[test]
+    fn freed_handles_are_invalid() {
+        unsafe {
+            let cube = cube(1.);
+            let mut mesh = 0;
+            assert_eq!(fj_evaluate(cube, 0., &mut mesh), FjStatus::Ok);
+
+            assert_eq!(fj_shape_free(cube), FjStatus::Ok);
+            assert_eq!(fj_shape_free(cube), FjStatus::InvalidHandle);
+            assert!(last_error().contains("not a valid shape handle"));
+
+            let mut other = 0;
+            assert_eq!(
+                fj_evaluate(cube, 0., &mut other),
+                FjStatus::InvalidHandle
+            );
+            assert_eq!(other, 0);
+
+            // The mesh outlives the shape it was created from.
+            let mut ptr = ptr::null();
+            let mut len = 0;
+            assert_eq!(
+                fj_mesh_positions(mesh, &mut ptr, &mut len),
+                FjStatus::Ok
+            );
+
+            assert_eq!(fj_mesh_free(mesh), FjStatus::Ok);
+            assert_eq!(
+                fj_mesh_positions(mesh, &mut ptr, &mut len),
+                FjStatus::InvalidHandle
+            );
+            assert_eq!(fj_mesh_free(mesh), FjStatus::InvalidHandle);
+        }
+    }
+
+    #[test]
+    fn handles_of_other_kinds_are_invalid() {
+        unsafe {
+            let cube = cube(1.);
+            let mut mesh = 0;
+            assert_eq!(fj_evaluate(cube, 0., &mut mesh), FjStatus::Ok);
+
+            assert_eq!(fj_mesh_free(cube), FjStatus::InvalidHandle);
+            assert_eq!(fj_shape_free(mesh), FjStatus::InvalidHandle);
+            assert_eq!(fj_shape_free(0), FjStatus::InvalidHandle);
+
+            assert_eq!(fj_mesh_free(mesh), FjStatus::Ok);
+            assert_eq!(fj_shape_free(cube), FjStatus::Ok);
+        }
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        unsafe {
+            let mut out = 0;
+            assert_eq!(fj_shape_cube(-1., &mut out), FjStatus::InvalidArgument);
+            assert_eq!(
+                fj_shape_cube(f64::NAN, &mut out),
+                FjStatus::InvalidArgument
+            );
+            assert_eq!(
+                fj_shape_cube(1., ptr::null_mut()),
+                FjStatus::NullPointer
+            );
+            assert_eq!(
+                fj_shape_sketch(ptr::null(), 3, &mut out),
+                FjStatus::NullPointer
+            );
+
+            let points = [0., 0., 1., 0.];
+            assert_eq!(
+                fj_shape_sketch(points.as_ptr(), 2, &mut out),
+                FjStatus::InvalidArgument
+            );
+
+            // Only 2-dimensional shapes can be swept.
+            let cube = cube(1.);
+            assert_eq!(
+                fj_shape_sweep(cube, 0., 0., 1., &mut out),
+                FjStatus::InvalidArgument
+            );
+            assert_eq!(
+                fj_shape_transform(cube, 0., 0., 0., 1., 0., 0., 0., &mut out),
+                FjStatus::InvalidArgument
+            );
+            assert_eq!(out, 0);
+
+            let mut mesh = 0;
+            assert_eq!(
+                fj_evaluate(cube, -1., &mut mesh),
+                FjStatus::InvalidArgument
+            );
+            assert_eq!(fj_shape_free(cube), FjStatus::Ok);
+        }
+    }
+
+    #[test]
+    fn processing_errors() {
+        unsafe {
+            // The path of the sweep lies within the sketch plane.
+            let points = [0., 0., 1., 0., 0., 1.];
+            let mut sketch = 0;
+            assert_eq!(
+                fj_shape_sketch(points.as_ptr(), 3, &mut sketch),
+                FjStatus::Ok
+            );
+            let mut sweep = 0;
+            assert_eq!(
+                fj_shape_sweep(sketch, 1., 0., 0., &mut sweep),
+                FjStatus::Ok
+            );
+
+            let mut mesh = 0;
+            assert_eq!(
+                fj_evaluate(sweep, 0.01, &mut mesh),
+                FjStatus::Processing
+            );
+            assert!(last_error().contains("parallel to sketch"));
+
+            assert_eq!(fj_shape_free(sweep), FjStatus::Ok);
+            assert_eq!(fj_shape_free(sketch), FjStatus::Ok);
+        }
+    }
+
+    #[test]
+    fn handles_are_usable_from_other_threads() {
+        let cube = unsafe { cube(1.) };
+
+        let mesh = std::thread::spawn(move || unsafe {
+            let mut mesh = 0;
+            assert_eq!(fj_evaluate(cube, 0., &mut mesh), FjStatus::Ok);
+            mesh
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(fj_mesh_free(mesh), FjStatus::Ok);
+        assert_eq!(fj_shape_free(cube), FjStatus::Ok);
+    }
+
+    unsafe fn cube(edge_length: f64) -> FjHandle {
+        let mut cube = 0;
+        assert_eq!(fj_shape_cube(edge_length, &mut cube), FjStatus::Ok);
+        cube
+    }
+
+    fn last_error() -> String {
+        let message = unsafe { CStr::from_ptr(fj_last_error_message()) };
+        message.to_string_lossy().into_owned()
+    }
+}
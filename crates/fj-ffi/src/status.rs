@@ -0,0 +1,118 @@
+//! Status codes and error messages
+
+use std::{
+    cell::RefCell,
+    ffi::CString,
+    os::raw::c_char,
+    panic::{self, AssertUnwindSafe},
+};
+
+/// The result of a call into the C API
+///
+/// Every function that can fail returns one of these. If it isn't
+/// [`FjStatus::Ok`], [`fj_last_error_message`] describes what went wrong.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub enum FjStatus {
+    /// The call succeeded
+    Ok = 0,
+
+    /// A pointer argument was null
+    NullPointer = 1,
+
+    /// A handle doesn't refer to a live object
+    ///
+    /// Either the handle was never valid, or its object was already freed.
+    InvalidHandle = 2,
+
+    /// An argument was out of range, or of the wrong kind
+    InvalidArgument = 3,
+
+    /// Processing a shape into a mesh failed
+    Processing = 4,
+
+    /// Exporting a mesh failed
+    Export = 5,
+
+    /// The kernel panicked
+    ///
+    /// This indicates a bug in Fornjot. The objects that were involved in the
+    /// call might be in an unusable state.
+    Panic = 6,
+}
+
+/// An error that is reported through the C API
+#[derive(Debug)]
+pub struct Error {
+    status: FjStatus,
+    message: String,
+}
+
+impl Error {
+    /// Create an error from a status and a message
+    pub fn new(status: FjStatus, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    /// Create an error for a null pointer argument
+    pub fn null_pointer(argument: &str) -> Self {
+        Self::new(
+            FjStatus::NullPointer,
+            format!("Argument `{argument}` is null"),
+        )
+    }
+
+    /// Create an error for an argument that is out of range
+    pub fn invalid_argument(message: impl Into<String>) -> Self {
+        Self::new(FjStatus::InvalidArgument, message)
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+/// Run the body of an API function
+///
+/// Converts the result into a status and records the error message, if any.
+/// Panics are caught, as unwinding into the host is undefined behavior.
+pub fn run(f: impl FnOnce() -> Result<(), Error>) -> FjStatus {
+    let result =
+        panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+
+            Err(Error::new(
+                FjStatus::Panic,
+                format!("Fornjot panicked: {message}"),
+            ))
+        });
+
+    match result {
+        Ok(()) => FjStatus::Ok,
+        Err(err) => {
+            // Messages come from Rust strings, which may contain null bytes.
+            let message = CString::new(err.message.replace('\0', " "))
+                .unwrap_or_default();
+            LAST_ERROR.with(|last| *last.borrow_mut() = message);
+
+            err.status
+        }
+    }
+}
+
+/// Access the message of the most recent error on the calling thread
+///
+/// Returns an empty string, if no call on this thread has failed yet. The
+/// returned string is owned by the library. It stays valid until the next call
+/// into the API fails on the same thread, and must not be freed.
+#[no_mangle]
+pub extern "C" fn fj_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ptr())
+}
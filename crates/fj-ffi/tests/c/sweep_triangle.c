@@ -0,0 +1,68 @@
+/*
+ * Builds a swept triangle through the C API and exports it to the STL file
+ * given as the first argument.
+ */
+
+#include <stdio.h>
+
+#include "fj.h"
+
+#define CHECK(call)                                                            \
+    do {                                                                       \
+        FjStatus status = (call);                                              \
+        if (status != FJ_OK) {                                                 \
+            fprintf(stderr, "%s failed (%d): %s\n", #call, (int)status,        \
+                    fj_last_error_message());                                  \
+            return 1;                                                          \
+        }                                                                      \
+    } while (0)
+
+int main(int argc, char **argv) {
+    if (argc != 2) {
+        fprintf(stderr, "Usage: %s <path.stl>\n", argv[0]);
+        return 2;
+    }
+
+    const double points[] = {0.0, 0.0, 1.0, 0.0, 0.0, 1.0};
+
+    FjHandle sketch = 0;
+    FjHandle sweep = 0;
+    FjHandle mesh = 0;
+    CHECK(fj_shape_sketch(points, 3, &sketch));
+    CHECK(fj_shape_sweep(sketch, 0.0, 0.0, 1.0, &sweep));
+    CHECK(fj_evaluate(sweep, 0.0, &mesh));
+
+    const double *positions = NULL;
+    const uint32_t *indices = NULL;
+    size_t positions_len = 0;
+    size_t indices_len = 0;
+    CHECK(fj_mesh_positions(mesh, &positions, &positions_len));
+    CHECK(fj_mesh_indices(mesh, &indices, &indices_len));
+
+    /* 2 triangles for top and bottom, 2 for each of the 3 sides */
+    if (positions_len != 6 * 3 || indices_len != 8 * 3) {
+        fprintf(stderr, "Unexpected mesh: %zu positions, %zu indices\n",
+                positions_len, indices_len);
+        return 1;
+    }
+    for (size_t i = 0; i < indices_len; i++) {
+        if (indices[i] >= positions_len / 3) {
+            fprintf(stderr, "Index out of range: %u\n", indices[i]);
+            return 1;
+        }
+    }
+
+    CHECK(fj_export_stl(mesh, argv[1]));
+
+    CHECK(fj_mesh_free(mesh));
+    CHECK(fj_shape_free(sweep));
+    CHECK(fj_shape_free(sketch));
+
+    /* Freed handles are detected. */
+    if (fj_shape_free(sketch) != FJ_INVALID_HANDLE) {
+        fprintf(stderr, "Double free was not detected\n");
+        return 1;
+    }
+
+    return 0;
+}
@@ -0,0 +1,53 @@
+//! Compiles and runs a C program against the library
+//!
+//! The library is built as a `cdylib` alongside the test executable, so the C
+//! program can link against it like any other host would.
+
+#![cfg(unix)]
+
+use std::{env, fs::File, path::Path, process::Command};
+
+#[test]
+fn sweep_triangle() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let out_dir = env::temp_dir().join("fj-ffi-c-api");
+    std::fs::create_dir_all(&out_dir).unwrap();
+
+    // The test executable is in `target/<profile>/deps`. Cargo places the
+    // library there too, and copies it to the parent directory.
+    let exe = env::current_exe().unwrap();
+    let deps_dir = exe.parent().unwrap();
+    let lib_dirs = [deps_dir, deps_dir.parent().unwrap()];
+
+    let compiler = cc::Build::new()
+        .cargo_metadata(false)
+        .target(env!("FJ_FFI_TARGET"))
+        .host(env!("FJ_FFI_HOST"))
+        .opt_level(0)
+        .get_compiler();
+
+    let program = out_dir.join("sweep_triangle");
+    let mut command = compiler.to_command();
+    command
+        .arg(manifest_dir.join("tests/c/sweep_triangle.c"))
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg("-o")
+        .arg(&program);
+    for dir in lib_dirs {
+        command
+            .arg(format!("-L{}", dir.display()))
+            .arg(format!("-Wl,-rpath,{}", dir.display()));
+    }
+    command.arg("-lfj_ffi");
+
+    let status = command.status().unwrap();
+    assert!(status.success(), "Failed to compile C program");
+
+    let stl = out_dir.join("sweep_triangle.stl");
+    let status = Command::new(&program).arg(&stl).status().unwrap();
+    assert!(status.success(), "C program failed");
+
+    let mesh = fj_export::import_stl(File::open(&stl).unwrap()).unwrap();
+    assert_eq!(mesh.triangles().count(), 8);
+}
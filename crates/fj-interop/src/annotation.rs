@@ -0,0 +1,47 @@
+//! Annotations of a processed shape
+//!
+//! Annotations are resolved against the geometry of a shape while processing
+//! it. The types in here carry the result, positioned in model space, so they
+//! can be displayed or exported alongside the triangle mesh.
+
+use fj_math::{Point, Scalar};
+
+use crate::{gizmo::LineSet, mesh::Color};
+
+/// The color of annotation leader lines
+pub const ANNOTATION_COLOR: Color = [0, 0, 0, 255];
+
+/// A resolved annotation
+#[derive(Clone, Debug, PartialEq)]
+pub struct Annotation {
+    /// The kind of annotation, including its measured value
+    pub kind: AnnotationKind,
+
+    /// The text that is displayed for the annotation
+    pub text: String,
+
+    /// The position of the text
+    pub position: Point<3>,
+
+    /// The leader lines that connect the text to the annotated geometry
+    pub leaders: LineSet,
+}
+
+/// The kind of a resolved annotation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AnnotationKind {
+    /// The distance between two vertices
+    LinearDimension {
+        /// The distance, as measured on the processed shape
+        length: Scalar,
+    },
+
+    /// The radius of a circular edge
+    RadiusDimension {
+        /// The radius, as measured on the processed shape
+        radius: Scalar,
+    },
+
+    /// A note at a point
+    Note,
+}
@@ -4,13 +4,45 @@
 //! ecosystem. The types in here aren't very useful in themselves, but they
 //! define an interface that other crates use to communicate between each other.
 
+use std::{collections::VecDeque, mem::size_of};
+
 use fj_math::{Point, Segment};
 
+use crate::mesh_quality::MeshQualityReport;
+
+/// The default value of [`DebugConfig::memory_limit`]
+pub const DEFAULT_MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+
 /// Debug info from the CAD kernel that can be visualized
+///
+/// How much is recorded depends on the [`DebugConfig`]. Since detailed records
+/// might be sampled or dropped, code that uses them must not assume that it
+/// sees every check that was performed. The counters tell how much is missing.
+///
+/// If [`DebugConfig::mesh_quality`] is enabled, triangulation also records a
+/// [`MeshQualityReport`], regardless of the capture level.
+///
+/// Algorithms that record debug info in a hot loop should record into a
+/// [local buffer](DebugInfo::local), and [merge](DebugInfo::merge) it once
+/// they're done. Local buffers don't share any state with the instance they
+/// were created from.
 #[derive(Default)]
 pub struct DebugInfo {
-    /// Rays being used during face triangulation
-    pub triangle_edge_checks: Vec<TriangleEdgeCheck>,
+    config: DebugConfig,
+
+    triangle_edge_checks: VecDeque<TriangleEdgeCheck>,
+    memory_usage: usize,
+
+    num_triangle_edge_checks: usize,
+    num_dropped: usize,
+    num_sections: usize,
+
+    mesh_quality: MeshQualityReport,
+
+    /// The number of checks that were performed before this buffer was created
+    ///
+    /// Used to continue the sampling pattern in local buffers.
+    sample_offset: usize,
 }
 
 impl DebugInfo {
@@ -19,16 +51,212 @@ impl DebugInfo {
         Self::default()
     }
 
+    /// Construct an empty instance of `DebugInfo` with the given configuration
+    pub fn with_config(config: DebugConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// Access the configuration
+    pub fn config(&self) -> DebugConfig {
+        self.config
+    }
+
+    /// Construct an empty local buffer, to be merged into this instance later
+    ///
+    /// The buffer has the same configuration as this instance, and continues
+    /// its sampling pattern.
+    pub fn local(&self) -> Self {
+        Self {
+            config: self.config,
+            sample_offset: self.sample_offset + self.num_triangle_edge_checks,
+            ..Self::default()
+        }
+    }
+
+    /// Merge a local buffer into this instance
+    ///
+    /// Records of `other` are considered newer than those of this instance.
+    /// If the memory limit is exceeded, the oldest records are dropped.
+    pub fn merge(&mut self, other: DebugInfo) {
+        self.num_triangle_edge_checks += other.num_triangle_edge_checks;
+        self.num_dropped += other.num_dropped;
+        self.num_sections += other.num_sections;
+        self.memory_usage += other.memory_usage;
+        self.triangle_edge_checks.extend(other.triangle_edge_checks);
+        self.mesh_quality.merge(other.mesh_quality);
+
+        self.enforce_memory_limit();
+    }
+
+    /// Start recording a check to determine if a triangle edge is within a face
+    ///
+    /// Returns `Some`, if the check should be recorded in detail. In that case,
+    /// the caller is expected to fill in the hits and pass the record to
+    /// [`DebugInfo::push_triangle_edge_check`]. `origin` is only called, if the
+    /// check is recorded in detail.
+    pub fn begin_triangle_edge_check(
+        &mut self,
+        origin: impl FnOnce() -> Point<3>,
+    ) -> Option<TriangleEdgeCheck> {
+        let index = self.sample_offset + self.num_triangle_edge_checks;
+
+        let capture = match self.config.level {
+            CaptureLevel::Off => return None,
+            CaptureLevel::Summary => false,
+            CaptureLevel::SampledDetail { every_nth } => {
+                index % every_nth.max(1) == 0
+            }
+            CaptureLevel::Full => true,
+        };
+
+        self.num_triangle_edge_checks += 1;
+
+        capture.then(|| TriangleEdgeCheck::new(origin()))
+    }
+
+    /// Add a detailed record of a triangle edge check
+    ///
+    /// If the memory limit is exceeded, the oldest records are dropped.
+    pub fn push_triangle_edge_check(&mut self, mut check: TriangleEdgeCheck) {
+        check.hits.shrink_to_fit();
+
+        self.memory_usage += check.memory_usage();
+        self.triangle_edge_checks.push_back(check);
+
+        self.enforce_memory_limit();
+    }
+
+    /// Access the detailed records of triangle edge checks, oldest first
+    pub fn triangle_edge_checks(
+        &self,
+    ) -> impl Iterator<Item = &TriangleEdgeCheck> + '_ {
+        self.triangle_edge_checks.iter()
+    }
+
+    /// Return the number of triangle edge checks that were performed
+    ///
+    /// This includes checks that were not recorded in detail, or whose records
+    /// were dropped. Checks are not counted, if capturing is off.
+    pub fn num_triangle_edge_checks(&self) -> usize {
+        self.num_triangle_edge_checks
+    }
+
+    /// Return the number of records that were dropped to stay within the
+    /// memory limit
+    pub fn num_dropped(&self) -> usize {
+        self.num_dropped
+    }
+
+    /// Record that a section of the shape was evaluated
+    pub fn record_section(&mut self) {
+        self.num_sections += 1;
+    }
+
+    /// Return the number of sections that were evaluated
+    ///
+    /// Sections are counted regardless of the capture level.
+    pub fn num_sections(&self) -> usize {
+        self.num_sections
+    }
+
+    /// Access the quality report of the triangulated mesh
+    ///
+    /// Empty, unless [`DebugConfig::mesh_quality`] is enabled.
+    pub fn mesh_quality(&self) -> &MeshQualityReport {
+        &self.mesh_quality
+    }
+
+    /// Access the quality report mutably, if it is enabled
+    ///
+    /// Returns `None`, unless [`DebugConfig::mesh_quality`] is enabled.
+    pub fn mesh_quality_mut(&mut self) -> Option<&mut MeshQualityReport> {
+        self.config.mesh_quality.then_some(&mut self.mesh_quality)
+    }
+
+    /// Return the estimated memory used by the detailed records, in bytes
+    pub fn memory_usage(&self) -> usize {
+        self.memory_usage
+    }
+
     /// Clear all information within this instance
     ///
-    /// The resulting instance is the same, as if created by [`DebugInfo::new`],
-    /// but calling `clear` might be more efficient in regard to heap
-    /// allocations.
+    /// The resulting instance is the same, as if created by
+    /// [`DebugInfo::with_config`] with the same configuration, but calling
+    /// `clear` might be more efficient in regard to heap allocations.
     pub fn clear(&mut self) {
         self.triangle_edge_checks.clear();
+        self.memory_usage = 0;
+        self.num_triangle_edge_checks = 0;
+        self.num_dropped = 0;
+        self.num_sections = 0;
+        self.sample_offset = 0;
+        self.mesh_quality = MeshQualityReport::new();
+    }
+
+    fn enforce_memory_limit(&mut self) {
+        while self.memory_usage > self.config.memory_limit {
+            match self.triangle_edge_checks.pop_front() {
+                Some(check) => {
+                    self.memory_usage -= check.memory_usage();
+                    self.num_dropped += 1;
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+/// Configures how much debug info is recorded
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DebugConfig {
+    /// The level of detail that is captured
+    pub level: CaptureLevel,
+
+    /// The maximum memory used by detailed records, in bytes
+    ///
+    /// This is an estimate, based on the size of the records. If it is
+    /// exceeded, the oldest records are dropped.
+    pub memory_limit: usize,
+
+    /// Whether to compute a [`MeshQualityReport`] during triangulation
+    ///
+    /// Disabled by default, as computing it costs time.
+    pub mesh_quality: bool,
+}
+
+impl Default for DebugConfig {
+    fn default() -> Self {
+        Self {
+            level: CaptureLevel::default(),
+            memory_limit: DEFAULT_MEMORY_LIMIT,
+            mesh_quality: false,
+        }
     }
 }
 
+/// The level of detail that debug info is captured with
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CaptureLevel {
+    /// Don't capture anything
+    Off,
+
+    /// Only count the checks that are performed
+    Summary,
+
+    /// Count all checks, but only record every nth check in detail
+    SampledDetail {
+        /// Determines which checks are recorded in detail
+        every_nth: usize,
+    },
+
+    /// Record every check in detail
+    #[default]
+    Full,
+}
+
 /// Record of a check to determine if a triangle edge is within a face
 pub struct TriangleEdgeCheck {
     /// The origin of the ray used to perform the check
@@ -46,4 +274,101 @@ impl TriangleEdgeCheck {
             hits: Vec::new(),
         }
     }
+
+    fn memory_usage(&self) -> usize {
+        size_of::<Self>() + self.hits.capacity() * size_of::<Segment<3>>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Segment};
+
+    use super::{CaptureLevel, DebugConfig, DebugInfo, TriangleEdgeCheck};
+
+    fn perform_checks(debug_info: &mut DebugInfo, n: usize) {
+        for i in 0..n {
+            let origin = Point::from([i as f64, 0., 0.]);
+            if let Some(mut check) =
+                debug_info.begin_triangle_edge_check(|| origin)
+            {
+                check
+                    .hits
+                    .push(Segment::from_points([origin, [0., 1., 0.].into()]));
+                debug_info.push_triangle_edge_check(check);
+            }
+        }
+    }
+
+    #[test]
+    fn capture_levels() {
+        let record = |level| {
+            let mut debug_info = DebugInfo::with_config(DebugConfig {
+                level,
+                ..DebugConfig::default()
+            });
+            perform_checks(&mut debug_info, 100);
+
+            (
+                debug_info.num_triangle_edge_checks(),
+                debug_info.triangle_edge_checks().count(),
+            )
+        };
+
+        assert_eq!(record(CaptureLevel::Off), (0, 0));
+        assert_eq!(record(CaptureLevel::Summary), (100, 0));
+        assert_eq!(
+            record(CaptureLevel::SampledDetail { every_nth: 10 }),
+            (100, 10)
+        );
+        assert_eq!(record(CaptureLevel::Full), (100, 100));
+    }
+
+    #[test]
+    fn local_buffers_continue_sampling() {
+        let mut debug_info = DebugInfo::with_config(DebugConfig {
+            level: CaptureLevel::SampledDetail { every_nth: 10 },
+            ..DebugConfig::default()
+        });
+
+        // Many small buffers must not each record their first check.
+        for _ in 0..20 {
+            let mut local = debug_info.local();
+            perform_checks(&mut local, 5);
+            debug_info.merge(local);
+        }
+
+        assert_eq!(debug_info.num_triangle_edge_checks(), 100);
+        assert_eq!(debug_info.triangle_edge_checks().count(), 10);
+    }
+
+    #[test]
+    fn memory_limit_drops_oldest() {
+        let record_size = {
+            let mut check = TriangleEdgeCheck::new(Point::origin());
+            check
+                .hits
+                .push(Segment::from_points([[0., 0., 0.], [0., 1., 0.]]));
+            check.hits.shrink_to_fit();
+            check.memory_usage()
+        };
+
+        let mut debug_info = DebugInfo::with_config(DebugConfig {
+            level: CaptureLevel::Full,
+            memory_limit: record_size * 10,
+            ..DebugConfig::default()
+        });
+        perform_checks(&mut debug_info, 100);
+
+        assert!(debug_info.memory_usage() <= record_size * 10);
+        assert_eq!(debug_info.triangle_edge_checks().count(), 10);
+        assert_eq!(debug_info.num_dropped(), 90);
+
+        // The newest records are kept.
+        let first = debug_info
+            .triangle_edge_checks()
+            .next()
+            .expect("Expected records");
+        assert_eq!(first.origin, Point::from([90., 0., 0.]));
+    }
 }
@@ -0,0 +1,260 @@
+//! Line geometry for orientation cues
+//!
+//! Defines line geometry, like coordinate axes or a ground grid, that helps
+//! users orient themselves when looking at a model. The geometry is provided
+//! as plain data, so it can be rendered by any line pipeline.
+
+use fj_math::{Aabb, Point, Scalar, Transform};
+
+use crate::mesh::Color;
+
+/// The color of the x axis
+pub const X_AXIS_COLOR: Color = [255, 0, 0, 255];
+
+/// The color of the y axis
+pub const Y_AXIS_COLOR: Color = [0, 255, 0, 255];
+
+/// The color of the z axis
+pub const Z_AXIS_COLOR: Color = [0, 0, 255, 255];
+
+/// The color of grid lines
+pub const GRID_COLOR: Color = [128, 128, 128, 255];
+
+/// The color of bounding box wireframes
+pub const AABB_COLOR: Color = [255, 255, 255, 255];
+
+/// A set of lines
+///
+/// Every line is defined by two positions and has a single color.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LineSet {
+    positions: Vec<Point<3>>,
+    colors: Vec<Color>,
+}
+
+impl LineSet {
+    /// Construct an empty instance of `LineSet`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a line to the set
+    pub fn push_line(&mut self, line: [impl Into<Point<3>>; 2], color: Color) {
+        self.positions.extend(line.map(Into::into));
+        self.colors.push(color);
+    }
+
+    /// Add all lines from another set to this one
+    pub fn extend(&mut self, other: &LineSet) {
+        self.positions.extend_from_slice(&other.positions);
+        self.colors.extend_from_slice(&other.colors);
+    }
+
+    /// Access the positions of the lines
+    ///
+    /// Contains two positions per line, ready to be used as a line list.
+    pub fn positions(&self) -> &[Point<3>] {
+        &self.positions
+    }
+
+    /// Access the colors of the lines
+    ///
+    /// Contains one color per line.
+    pub fn colors(&self) -> &[Color] {
+        &self.colors
+    }
+
+    /// Iterate over the lines and their colors
+    pub fn lines(&self) -> impl Iterator<Item = ([Point<3>; 2], Color)> + '_ {
+        self.positions
+            .chunks_exact(2)
+            .zip(&self.colors)
+            .map(|(line, &color)| ([line[0], line[1]], color))
+    }
+
+    /// Return the number of lines in the set
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    /// Indicate whether the set contains no lines
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /// Transform all lines in the set
+    pub fn transform(&self, transform: &Transform) -> Self {
+        let positions = self
+            .positions
+            .iter()
+            .map(|position| transform.transform_point(position))
+            .collect();
+
+        Self {
+            positions,
+            colors: self.colors.clone(),
+        }
+    }
+}
+
+/// Create lines for the coordinate axes
+///
+/// The lines start at the origin and have the provided length. They are
+/// colored red, green, and blue, for the x, y, and z axis respectively.
+pub fn axes(length: impl Into<Scalar>) -> LineSet {
+    let length = length.into();
+    let origin = Point::origin();
+
+    let mut lines = LineSet::new();
+    lines.push_line(
+        [origin, Point::from([length, Scalar::ZERO, Scalar::ZERO])],
+        X_AXIS_COLOR,
+    );
+    lines.push_line(
+        [origin, Point::from([Scalar::ZERO, length, Scalar::ZERO])],
+        Y_AXIS_COLOR,
+    );
+    lines.push_line(
+        [origin, Point::from([Scalar::ZERO, Scalar::ZERO, length])],
+        Z_AXIS_COLOR,
+    );
+
+    lines
+}
+
+/// Create lines for a grid in the xy-plane
+///
+/// The grid is centered on the origin and covers a square with a side length
+/// of `extent`. Grid lines are placed `spacing` apart, starting at the edge of
+/// the square.
+///
+/// # Panics
+///
+/// Panics, if `spacing` is not positive.
+pub fn grid(extent: impl Into<Scalar>, spacing: impl Into<Scalar>) -> LineSet {
+    let extent = extent.into();
+    let spacing = spacing.into();
+
+    assert!(spacing > Scalar::ZERO, "Grid spacing must be positive");
+
+    let half = extent / 2.;
+    let num_lines = (extent / spacing).into_f64().floor() as usize + 1;
+
+    let mut lines = LineSet::new();
+    for i in 0..num_lines {
+        let offset = -half + spacing * i as f64;
+
+        lines.push_line(
+            [
+                Point::from([offset, -half, Scalar::ZERO]),
+                Point::from([offset, half, Scalar::ZERO]),
+            ],
+            GRID_COLOR,
+        );
+        lines.push_line(
+            [
+                Point::from([-half, offset, Scalar::ZERO]),
+                Point::from([half, offset, Scalar::ZERO]),
+            ],
+            GRID_COLOR,
+        );
+    }
+
+    lines
+}
+
+/// Create lines for the edges of an axis-aligned bounding box
+pub fn aabb_wireframe(aabb: &Aabb<3>) -> LineSet {
+    // Bit `i` of a corner's index selects the maximum for axis `i`.
+    let corner = |index: usize| {
+        Point::from([0, 1, 2].map(|axis| {
+            if index & (1 << axis) == 0 {
+                aabb.min.coords.components[axis]
+            } else {
+                aabb.max.coords.components[axis]
+            }
+        }))
+    };
+
+    let mut lines = LineSet::new();
+    for index in 0..8 {
+        for axis in 0..3 {
+            if index & (1 << axis) == 0 {
+                lines.push_line(
+                    [corner(index), corner(index | 1 << axis)],
+                    AABB_COLOR,
+                );
+            }
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Aabb, Point, Transform, Vector};
+
+    use super::{aabb_wireframe, grid, LineSet, GRID_COLOR};
+
+    #[test]
+    fn grid_line_count() {
+        let lines = grid(10., 1.);
+
+        assert_eq!(lines.len(), 22);
+        assert_eq!(lines.positions().len(), 44);
+        assert!(lines.colors().iter().all(|&color| color == GRID_COLOR));
+    }
+
+    #[test]
+    fn aabb_wireframe_edges() {
+        let aabb = Aabb {
+            min: Point::from([-1., -2., -3.]),
+            max: Point::from([1., 2., 3.]),
+        };
+
+        let lines = aabb_wireframe(&aabb);
+        assert_eq!(lines.len(), 12);
+
+        for ([a, b], _) in lines.lines() {
+            // Every line connects two corners of the box, along one axis.
+            for point in [a, b] {
+                assert!(aabb.vertices().contains(&point));
+            }
+
+            let differences = (b - a)
+                .components
+                .into_iter()
+                .filter(|component| component.into_f64() != 0.)
+                .count();
+            assert_eq!(differences, 1);
+        }
+
+        // Every corner is the endpoint of three edges.
+        for vertex in aabb.vertices() {
+            let count = lines
+                .positions()
+                .iter()
+                .filter(|&&position| position == vertex)
+                .count();
+            assert_eq!(count, 3);
+        }
+    }
+
+    #[test]
+    fn transform_translation() {
+        let mut lines = LineSet::new();
+        lines.push_line([[0., 0., 0.], [1., 2., 3.]], [255, 0, 0, 255]);
+        lines.push_line([[-1., 0.5, 2.], [4., -3., 0.25]], [0, 255, 0, 255]);
+
+        let offset = Vector::from([1., 2., 3.]);
+        let transformed = lines.transform(&Transform::translation(offset));
+
+        assert_eq!(transformed.colors(), lines.colors());
+        for (&original, &moved) in
+            lines.positions().iter().zip(transformed.positions())
+        {
+            assert_eq!(moved, original + offset);
+        }
+    }
+}
@@ -0,0 +1,1071 @@
+//! A half-edge representation of triangle meshes
+//!
+//! [`Mesh`] stores a flat list of triangles, which is all that's needed to
+//! render or export it. Algorithms that process a mesh often need to know how
+//! its triangles are connected, for example which triangles surround a vertex.
+//! [`HalfEdgeMesh`] provides that, and allows changing the mesh without
+//! breaking its connectivity.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use fj_math::{Point, Scalar, Vector};
+
+use crate::mesh::{Color, Mesh, Triangle};
+
+/// A triangle mesh that knows how its triangles are connected
+///
+/// Every triangle consists of three half-edges, which run along its edges, in
+/// the direction of its winding. The half-edges of the triangle with index `t`
+/// have the indices `3 * t`, `3 * t + 1`, and `3 * t + 2`. Half-edge `3 * t`
+/// starts at the first vertex of the triangle.
+///
+/// Where two triangles share an edge, and traverse it in opposite directions,
+/// their half-edges are each other's opposite. Half-edges without an opposite
+/// are on the boundary of the mesh. This includes the half-edges of edges
+/// that are shared by more than two triangles, or by triangles that traverse
+/// them in the same direction.
+///
+/// Removed triangles and vertices keep their indices, so indices stay valid
+/// while the mesh is being changed.
+#[derive(Clone, Debug)]
+pub struct HalfEdgeMesh {
+    points: Vec<Point<3>>,
+    outgoing: Vec<Option<usize>>,
+    num_triangles_of: Vec<usize>,
+
+    triangles: Vec<Option<TriangleData>>,
+    opposites: Vec<Option<usize>>,
+
+    double_sided: bool,
+    degraded_faces: Vec<(usize, Scalar)>,
+}
+
+impl HalfEdgeMesh {
+    /// Build a half-edge mesh from a triangle mesh
+    ///
+    /// Vertices and triangles keep the indices they have in `mesh`. Triangles
+    /// that use a vertex more than once have no proper edges, and are treated
+    /// as removed.
+    pub fn from_mesh(mesh: &Mesh<Point<3>>) -> Self {
+        let points: Vec<_> = mesh.vertices().collect();
+        let indices: Vec<_> =
+            mesh.indices().map(|index| index as usize).collect();
+
+        let triangles: Vec<_> = mesh
+            .triangles()
+            .enumerate()
+            .map(|(i, triangle)| {
+                let vertices = [0, 1, 2].map(|k| indices[3 * i + k]);
+
+                let [a, b, c] = vertices;
+                if a == b || b == c || c == a {
+                    return None;
+                }
+
+                Some(TriangleData {
+                    vertices,
+                    color: triangle.color,
+                    face: triangle.face,
+                    normal: triangle.normal,
+                    surface_coords: mesh.surface_coords(i),
+                })
+            })
+            .collect();
+
+        let mut half_edge_mesh = Self {
+            outgoing: vec![None; points.len()],
+            num_triangles_of: vec![0; points.len()],
+            points,
+            opposites: vec![None; triangles.len() * 3],
+            triangles,
+            double_sided: mesh.is_double_sided(),
+            degraded_faces: mesh.degraded_faces().collect(),
+        };
+
+        let mut half_edges: HashMap<[usize; 2], Vec<usize>> = HashMap::new();
+        for half_edge in half_edge_mesh.half_edges() {
+            let edge = half_edge_mesh.vertices_of(half_edge);
+            half_edges.entry(edge).or_default().push(half_edge);
+        }
+
+        for (&[a, b], uses) in &half_edges {
+            let reverse_uses = half_edges.get(&[b, a]).map(Vec::as_slice);
+            if let (&[half_edge], Some(&[opposite])) =
+                (uses.as_slice(), reverse_uses)
+            {
+                half_edge_mesh.opposites[half_edge] = Some(opposite);
+            }
+        }
+
+        let half_edges: Vec<_> = half_edge_mesh.half_edges().collect();
+        for half_edge in half_edges {
+            let vertex = half_edge_mesh.origin(half_edge);
+            half_edge_mesh.num_triangles_of[vertex] += 1;
+
+            // Boundary vertices must start from a boundary half-edge, or not
+            // all of their triangles can be reached.
+            let is_boundary = half_edge_mesh.opposites[half_edge].is_none();
+            if half_edge_mesh.outgoing[vertex].is_none() || is_boundary {
+                half_edge_mesh.outgoing[vertex] = Some(half_edge);
+            }
+        }
+
+        half_edge_mesh
+    }
+
+    /// Convert the half-edge mesh back into a triangle mesh
+    ///
+    /// Triangles are added in the order of their indices. They keep their
+    /// color and face. Triangles that haven't been changed also keep their
+    /// normal and surface coordinates, so a half-edge mesh that was built from
+    /// a triangle mesh and not changed converts back to the same mesh.
+    pub fn to_mesh(&self) -> Mesh<Point<3>> {
+        let mut mesh = Mesh::new();
+        mesh.set_double_sided(self.double_sided);
+        for &(face, tolerance) in &self.degraded_faces {
+            mesh.mark_degraded(face, tolerance);
+        }
+
+        for triangle in self.triangles.iter().flatten() {
+            let points = triangle.vertices.map(|vertex| self.points[vertex]);
+
+            match (triangle.face, triangle.surface_coords) {
+                (Some(face), Some(surface_coords)) => mesh
+                    .push_face_triangle_with_normal(
+                        points,
+                        triangle.color,
+                        face,
+                        surface_coords,
+                        triangle.normal,
+                    ),
+                (Some(face), None) => {
+                    mesh.push_face_triangle(points, triangle.color, face)
+                }
+                (None, _) => mesh.push_triangle(points, triangle.color),
+            }
+        }
+
+        mesh
+    }
+
+    /// Iterate over the vertices that are part of any triangle
+    pub fn vertices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.num_triangles_of
+            .iter()
+            .enumerate()
+            .filter(|(_, &num_triangles)| num_triangles > 0)
+            .map(|(vertex, _)| vertex)
+    }
+
+    /// Iterate over the triangles that have not been removed
+    pub fn triangles(&self) -> impl Iterator<Item = usize> + '_ {
+        self.triangles
+            .iter()
+            .enumerate()
+            .filter(|(_, triangle)| triangle.is_some())
+            .map(|(index, _)| index)
+    }
+
+    /// Iterate over the half-edges of all triangles
+    pub fn half_edges(&self) -> impl Iterator<Item = usize> + '_ {
+        self.triangles()
+            .flat_map(|triangle| [0, 1, 2].map(|k| 3 * triangle + k))
+    }
+
+    /// Iterate over the edges of the mesh
+    ///
+    /// Yields one half-edge per edge. For edges between two triangles, that's
+    /// the one with the lower index.
+    pub fn edges(&self) -> impl Iterator<Item = usize> + '_ {
+        self.half_edges().filter(|&half_edge| {
+            self.opposites[half_edge]
+                .map(|opposite| half_edge < opposite)
+                .unwrap_or(true)
+        })
+    }
+
+    /// Access the position of a vertex
+    pub fn point(&self, vertex: usize) -> Point<3> {
+        self.points[vertex]
+    }
+
+    /// Move a vertex
+    ///
+    /// The triangles around the vertex lose their surface coordinates, and
+    /// their normals are computed from their points.
+    pub fn set_point(&mut self, vertex: usize, point: Point<3>) {
+        self.points[vertex] = point;
+
+        for triangle in self.triangles_around(vertex) {
+            self.update_triangle(triangle);
+        }
+    }
+
+    /// Access a triangle
+    ///
+    /// Returns `None`, if there is no triangle with this index, or if it has
+    /// been removed.
+    pub fn triangle(&self, index: usize) -> Option<Triangle> {
+        let triangle = self.triangles.get(index)?.as_ref()?;
+
+        Some(Triangle {
+            points: triangle.vertices.map(|vertex| self.points[vertex]),
+            color: triangle.color,
+            face: triangle.face,
+            normal: triangle.normal,
+        })
+    }
+
+    /// Access the vertices of a triangle
+    ///
+    /// Returns `None`, if there is no triangle with this index, or if it has
+    /// been removed.
+    pub fn triangle_vertices(&self, index: usize) -> Option<[usize; 3]> {
+        let triangle = self.triangles.get(index)?.as_ref()?;
+        Some(triangle.vertices)
+    }
+
+    /// Access the vertex that a half-edge starts at
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the triangle of the half-edge has been removed.
+    pub fn origin(&self, half_edge: usize) -> usize {
+        self.vertices_of(half_edge)[0]
+    }
+
+    /// Access the vertex that a half-edge ends at
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the triangle of the half-edge has been removed.
+    pub fn target(&self, half_edge: usize) -> usize {
+        self.vertices_of(half_edge)[1]
+    }
+
+    /// Access the next half-edge in the same triangle
+    pub fn next(&self, half_edge: usize) -> usize {
+        half_edge - half_edge % 3 + (half_edge + 1) % 3
+    }
+
+    /// Access the previous half-edge in the same triangle
+    pub fn prev(&self, half_edge: usize) -> usize {
+        half_edge - half_edge % 3 + (half_edge + 2) % 3
+    }
+
+    /// Access the half-edge that runs along the same edge in the opposite
+    /// direction
+    ///
+    /// Returns `None`, if the half-edge is on the boundary of the mesh.
+    pub fn opposite(&self, half_edge: usize) -> Option<usize> {
+        self.opposites.get(half_edge).copied().flatten()
+    }
+
+    /// Find the half-edge that runs from one vertex to another
+    pub fn half_edge(&self, from: usize, to: usize) -> Option<usize> {
+        self.outgoing(from)
+            .into_iter()
+            .find(|&half_edge| self.target(half_edge) == to)
+    }
+
+    /// Access the half-edges that start at a vertex
+    ///
+    /// They are ordered around the vertex, following the winding of its
+    /// triangles. For vertices on the boundary, the first half-edge is on the
+    /// boundary.
+    ///
+    /// Only one fan of triangles is found around vertices that connect
+    /// multiple fans. See [`HalfEdgeMesh::is_manifold_vertex`].
+    pub fn outgoing(&self, vertex: usize) -> Vec<usize> {
+        let start = match self.outgoing.get(vertex).copied().flatten() {
+            Some(start) => start,
+            None => return Vec::new(),
+        };
+
+        let mut half_edges = vec![start];
+        let mut current = start;
+
+        while half_edges.len() < self.num_triangles_of[vertex] {
+            current = match self.opposites[self.prev(current)] {
+                Some(next) if next != start => next,
+                _ => break,
+            };
+            half_edges.push(current);
+        }
+
+        half_edges
+    }
+
+    /// Access the triangles around a vertex
+    ///
+    /// They are in the same order as the half-edges that are returned by
+    /// [`HalfEdgeMesh::outgoing`].
+    pub fn triangles_around(&self, vertex: usize) -> Vec<usize> {
+        self.outgoing(vertex)
+            .into_iter()
+            .map(|half_edge| half_edge / 3)
+            .collect()
+    }
+
+    /// Access the vertices that share an edge with a vertex
+    ///
+    /// They are ordered around the vertex, like the half-edges that are
+    /// returned by [`HalfEdgeMesh::outgoing`].
+    pub fn one_ring(&self, vertex: usize) -> Vec<usize> {
+        let outgoing = self.outgoing(vertex);
+
+        let mut ring: Vec<_> = outgoing
+            .iter()
+            .map(|&half_edge| self.target(half_edge))
+            .collect();
+
+        // If the triangles don't close around the vertex, the last triangle
+        // has one more neighbor.
+        if let Some(&last) = outgoing.last() {
+            let incoming = self.prev(last);
+            if self.opposites[incoming].is_none() {
+                ring.push(self.origin(incoming));
+            }
+        }
+
+        ring
+    }
+
+    /// Determine whether a half-edge is on the boundary of the mesh
+    pub fn is_boundary_edge(&self, half_edge: usize) -> bool {
+        self.opposite(half_edge).is_none()
+    }
+
+    /// Determine whether a vertex is on the boundary of the mesh
+    pub fn is_boundary_vertex(&self, vertex: usize) -> bool {
+        let incoming = self
+            .outgoing(vertex)
+            .last()
+            .map(|&half_edge| self.prev(half_edge));
+
+        match (self.outgoing.get(vertex).copied().flatten(), incoming) {
+            (Some(outgoing), Some(incoming)) => {
+                self.is_boundary_edge(outgoing)
+                    || self.is_boundary_edge(incoming)
+            }
+            _ => false,
+        }
+    }
+
+    /// Determine whether the triangles around a vertex form a single fan
+    ///
+    /// Only then can all of them be reached, by going from one triangle to the
+    /// next one around the vertex.
+    pub fn is_manifold_vertex(&self, vertex: usize) -> bool {
+        self.outgoing(vertex).len() == self.num_triangles_of[vertex]
+    }
+
+    /// Find the closed loops of boundary edges
+    ///
+    /// Each loop is returned as the vertices that its half-edges start at, in
+    /// the direction of the half-edges. Chains of boundary edges that don't
+    /// form a closed loop are ignored.
+    pub fn boundary_loops(&self) -> Vec<Vec<usize>> {
+        let mut visited = HashSet::new();
+        let mut loops = Vec::new();
+
+        let boundary: Vec<_> = self
+            .half_edges()
+            .filter(|&half_edge| self.is_boundary_edge(half_edge))
+            .collect();
+
+        for start in boundary {
+            if visited.contains(&start) {
+                continue;
+            }
+
+            let mut vertices = Vec::new();
+            let mut current = Some(start);
+
+            let is_closed = loop {
+                let half_edge = match current {
+                    Some(half_edge) => half_edge,
+                    None => break false,
+                };
+                if !visited.insert(half_edge) {
+                    break half_edge == start;
+                }
+
+                vertices.push(self.origin(half_edge));
+                current = self.next_boundary_edge(half_edge);
+            };
+
+            if is_closed {
+                loops.push(vertices);
+            }
+        }
+
+        loops
+    }
+
+    /// Collapse an edge, by merging the vertex it starts at into the one it
+    /// ends at
+    ///
+    /// The triangles on both sides of the edge are removed. The triangles
+    /// around the merged vertex are connected to the remaining vertex instead.
+    /// Returns the remaining vertex.
+    ///
+    /// Returns `None`, and leaves the mesh unchanged, if the collapse would
+    /// make the mesh non-manifold, or fold triangles onto each other.
+    pub fn collapse_edge(&mut self, half_edge: usize) -> Option<usize> {
+        self.triangle_vertices(half_edge / 3)?;
+
+        let [u, v] = self.vertices_of(half_edge);
+        if !self.is_manifold_vertex(u) || !self.is_manifold_vertex(v) {
+            return None;
+        }
+
+        let opposite = self.opposites[half_edge];
+        let removed: Vec<_> =
+            [Some(half_edge), opposite].into_iter().flatten().collect();
+
+        // The only vertices that `u` and `v` may have in common are the ones
+        // of the removed triangles. Otherwise, the mesh would be pinched.
+        let opposite_vertices: BTreeSet<_> = removed
+            .iter()
+            .map(|&half_edge| self.origin(self.prev(half_edge)))
+            .collect();
+        let ring_of_u: BTreeSet<_> = self.one_ring(u).into_iter().collect();
+        let ring_of_v: BTreeSet<_> = self.one_ring(v).into_iter().collect();
+        let common: BTreeSet<_> =
+            ring_of_u.intersection(&ring_of_v).copied().collect();
+        if common != opposite_vertices {
+            return None;
+        }
+
+        // An edge through the inside of the mesh, between two boundary
+        // vertices, would pinch it too.
+        if opposite.is_some()
+            && self.is_boundary_vertex(u)
+            && self.is_boundary_vertex(v)
+        {
+            return None;
+        }
+
+        // Vertices that lose a neighbor need enough of them left, or their
+        // triangles fold onto each other.
+        for &vertex in &opposite_vertices {
+            let min_neighbors = if self.is_boundary_vertex(vertex) {
+                2
+            } else {
+                3
+            };
+            if self.one_ring(vertex).len() - 1 < min_neighbors {
+                return None;
+            }
+        }
+
+        // All checks passed. We can perform the collapse now.
+
+        let moved: Vec<_> = self
+            .triangles_around(u)
+            .into_iter()
+            .filter(|&triangle| {
+                removed.iter().all(|&half_edge| half_edge / 3 != triangle)
+            })
+            .collect();
+
+        let mut neighbors = Vec::new();
+        for &half_edge in &removed {
+            let a = self.opposites[self.next(half_edge)];
+            let b = self.opposites[self.prev(half_edge)];
+            self.link(a, b);
+            neighbors.extend([a, b].into_iter().flatten().map(|h| h / 3));
+        }
+
+        for &half_edge in &removed {
+            self.remove_triangle(half_edge / 3);
+        }
+
+        for &triangle in &moved {
+            let data = self.triangles[triangle]
+                .as_mut()
+                .expect("Triangle around vertex has been removed");
+            for vertex in &mut data.vertices {
+                if *vertex == u {
+                    *vertex = v;
+                }
+            }
+
+            self.num_triangles_of[u] -= 1;
+            self.num_triangles_of[v] += 1;
+            self.update_triangle(triangle);
+        }
+
+        let candidates: Vec<_> = moved.into_iter().chain(neighbors).collect();
+        self.repair_outgoing(u, &candidates);
+        self.repair_outgoing(v, &candidates);
+        for vertex in opposite_vertices {
+            self.repair_outgoing(vertex, &candidates);
+        }
+
+        Some(v)
+    }
+
+    /// Flip an edge between two triangles
+    ///
+    /// The edge is replaced by one that connects the two vertices opposite of
+    /// it. Returns the half-edge of the new edge that is part of the triangle
+    /// of `half_edge`.
+    ///
+    /// Returns `None`, and leaves the mesh unchanged, if the edge is on the
+    /// boundary, if the new edge already exists, or if the vertices of the
+    /// edge would be left with too few neighbors.
+    pub fn flip_edge(&mut self, half_edge: usize) -> Option<usize> {
+        self.triangle_vertices(half_edge / 3)?;
+        let opposite = self.opposites[half_edge]?;
+
+        let [v, w] = self.vertices_of(half_edge);
+        let a = self.origin(self.prev(half_edge));
+        let b = self.origin(self.prev(opposite));
+
+        if a == b || self.one_ring(a).contains(&b) {
+            return None;
+        }
+        for vertex in [v, w] {
+            let min_neighbors = if self.is_boundary_vertex(vertex) {
+                2
+            } else {
+                3
+            };
+            if self.one_ring(vertex).len() - 1 < min_neighbors {
+                return None;
+            }
+        }
+
+        let [wa, av] = [self.next(half_edge), self.prev(half_edge)]
+            .map(|half_edge| self.opposites[half_edge]);
+        let [vb, bw] = [self.next(opposite), self.prev(opposite)]
+            .map(|half_edge| self.opposites[half_edge]);
+
+        let [t0, t1] = [half_edge / 3, opposite / 3];
+        for (triangle, vertices) in [(t0, [a, v, b]), (t1, [b, w, a])] {
+            self.triangles[triangle]
+                .as_mut()
+                .expect("Triangle was checked before")
+                .vertices = vertices;
+            self.update_triangle(triangle);
+        }
+
+        self.link(Some(3 * t0), av);
+        self.link(Some(3 * t0 + 1), vb);
+        self.link(Some(3 * t0 + 2), Some(3 * t1 + 2));
+        self.link(Some(3 * t1), bw);
+        self.link(Some(3 * t1 + 1), wa);
+
+        self.num_triangles_of[v] -= 1;
+        self.num_triangles_of[w] -= 1;
+        self.num_triangles_of[a] += 1;
+        self.num_triangles_of[b] += 1;
+
+        for vertex in [v, w, a, b] {
+            self.repair_outgoing(vertex, &[t0, t1]);
+        }
+
+        Some(3 * t0 + 2)
+    }
+
+    /// Split a vertex in two
+    ///
+    /// This is the reverse of [`HalfEdgeMesh::collapse_edge`]. `from` and `to`
+    /// must be different half-edges that start at the vertex. The triangles
+    /// around the vertex, from the triangle of `from` up to, but not including,
+    /// the triangle of `to`, are moved to a new vertex at `point`. Two new
+    /// triangles fill the gap between the vertices.
+    ///
+    /// Returns the new vertex, or `None`, if the vertex can't be split this
+    /// way. That is the case, if the half-edges are not part of the same fan
+    /// of triangles around the vertex, or if `to` can't be reached from `from`
+    /// going around the vertex in the direction of [`HalfEdgeMesh::outgoing`].
+    pub fn split_vertex(
+        &mut self,
+        from: usize,
+        to: usize,
+        point: Point<3>,
+    ) -> Option<usize> {
+        self.triangle_vertices(from / 3)?;
+        self.triangle_vertices(to / 3)?;
+
+        let v = self.origin(from);
+        if self.origin(to) != v || from == to || !self.is_manifold_vertex(v) {
+            return None;
+        }
+
+        let mut fan = self.outgoing(v);
+        let position_of_from = fan.iter().position(|&h| h == from)?;
+        if !self.is_boundary_vertex(v) {
+            fan.rotate_left(position_of_from);
+        } else {
+            fan.drain(..position_of_from);
+        }
+        let position_of_to = fan.iter().position(|&h| h == to)?;
+        let moved: Vec<_> =
+            fan[..position_of_to].iter().map(|&h| h / 3).collect();
+
+        let [a, b] = [from, to].map(|half_edge| self.target(half_edge));
+        let into_a = self.opposites[from];
+        let into_w = self.prev(fan[position_of_to - 1]);
+
+        let w = self.points.len();
+        self.points.push(point);
+        self.outgoing.push(None);
+        self.num_triangles_of.push(0);
+
+        for &triangle in &moved {
+            let data = self.triangles[triangle]
+                .as_mut()
+                .expect("Triangle around vertex has been removed");
+            for vertex in &mut data.vertices {
+                if *vertex == v {
+                    *vertex = w;
+                }
+            }
+
+            self.num_triangles_of[v] -= 1;
+            self.num_triangles_of[w] += 1;
+            self.update_triangle(triangle);
+        }
+
+        let t1 = self.add_triangle([a, w, v], from / 3);
+        let t2 = self.add_triangle([b, v, w], to / 3);
+
+        self.link(Some(3 * t1), Some(from));
+        self.link(Some(3 * t1 + 1), Some(3 * t2 + 1));
+        self.link(Some(3 * t1 + 2), into_a);
+        self.link(Some(3 * t2), Some(to));
+        self.link(Some(3 * t2 + 2), Some(into_w));
+
+        let candidates: Vec<_> = moved
+            .into_iter()
+            .chain([t1, t2, from / 3, to / 3])
+            .collect();
+        for vertex in [v, w, a, b] {
+            self.repair_outgoing(vertex, &candidates);
+        }
+
+        Some(w)
+    }
+
+    /// Check that the connectivity of the mesh is consistent
+    ///
+    /// This is the case for every mesh that was built from a manifold triangle
+    /// mesh, and stays the case, when the mesh is changed.
+    pub fn check_integrity(&self) -> Result<(), IntegrityError> {
+        let mut half_edges = HashSet::new();
+        let mut num_triangles_of = vec![0; self.points.len()];
+
+        for triangle in self.triangles() {
+            let [a, b, c] = self.triangles[triangle]
+                .as_ref()
+                .expect("Iterating over existing triangles")
+                .vertices;
+            if a == b || b == c || c == a {
+                return Err(IntegrityError::DegenerateTriangle(triangle));
+            }
+
+            for vertex in [a, b, c] {
+                num_triangles_of[vertex] += 1;
+            }
+        }
+
+        for half_edge in self.half_edges() {
+            if !half_edges.insert(self.vertices_of(half_edge)) {
+                return Err(IntegrityError::DuplicateHalfEdge(half_edge));
+            }
+        }
+
+        for half_edge in self.half_edges() {
+            let [a, b] = self.vertices_of(half_edge);
+
+            let is_consistent = match self.opposites[half_edge] {
+                Some(opposite) => {
+                    self.triangle_vertices(opposite / 3).is_some()
+                        && self.opposites[opposite] == Some(half_edge)
+                        && self.vertices_of(opposite) == [b, a]
+                }
+                None => !half_edges.contains(&[b, a]),
+            };
+            if !is_consistent {
+                return Err(IntegrityError::InconsistentOpposite(half_edge));
+            }
+        }
+
+        for (vertex, &num_triangles) in num_triangles_of.iter().enumerate() {
+            let outgoing = self.outgoing[vertex];
+
+            let is_valid = match outgoing {
+                Some(half_edge) => {
+                    self.triangle_vertices(half_edge / 3).is_some()
+                        && self.origin(half_edge) == vertex
+                }
+                None => num_triangles == 0,
+            };
+            if !is_valid || self.num_triangles_of[vertex] != num_triangles {
+                return Err(IntegrityError::InvalidOutgoing(vertex));
+            }
+
+            if !self.is_manifold_vertex(vertex) {
+                return Err(IntegrityError::NonManifoldVertex(vertex));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn vertices_of(&self, half_edge: usize) -> [usize; 2] {
+        let vertices = self
+            .triangle_vertices(half_edge / 3)
+            .expect("Half-edge of removed triangle");
+
+        let k = half_edge % 3;
+        [vertices[k], vertices[(k + 1) % 3]]
+    }
+
+    /// Find the boundary half-edge that continues from the given one
+    fn next_boundary_edge(&self, half_edge: usize) -> Option<usize> {
+        let start = self.next(half_edge);
+        let vertex = self.origin(start);
+
+        let mut current = start;
+        for _ in 0..self.num_triangles_of[vertex] {
+            current = match self.opposites[current] {
+                Some(opposite) => self.next(opposite),
+                None => return Some(current),
+            };
+        }
+
+        None
+    }
+
+    fn link(&mut self, a: Option<usize>, b: Option<usize>) {
+        if let Some(a) = a {
+            self.opposites[a] = b;
+        }
+        if let Some(b) = b {
+            self.opposites[b] = a;
+        }
+    }
+
+    /// Add a triangle, with the color and face of another one
+    fn add_triangle(&mut self, vertices: [usize; 3], like: usize) -> usize {
+        let like = self.triangles[like]
+            .as_ref()
+            .expect("Triangle to copy has been removed");
+
+        let triangle = TriangleData {
+            vertices,
+            color: like.color,
+            face: like.face,
+            normal: Vector::from([0., 0., 0.]),
+            surface_coords: None,
+        };
+
+        let index = self.triangles.len();
+        self.triangles.push(Some(triangle));
+        self.opposites.extend([None; 3]);
+
+        for vertex in vertices {
+            self.num_triangles_of[vertex] += 1;
+        }
+        self.update_triangle(index);
+
+        index
+    }
+
+    fn remove_triangle(&mut self, triangle: usize) {
+        let data = self.triangles[triangle]
+            .take()
+            .expect("Triangle has already been removed");
+
+        for vertex in data.vertices {
+            self.num_triangles_of[vertex] -= 1;
+        }
+        for k in 0..3 {
+            self.opposites[3 * triangle + k] = None;
+        }
+    }
+
+    /// Update the geometry of a triangle, after its points have changed
+    fn update_triangle(&mut self, triangle: usize) {
+        let [a, b, c] = self.triangles[triangle]
+            .as_ref()
+            .expect("Triangle has been removed")
+            .vertices
+            .map(|vertex| self.points[vertex]);
+
+        let normal = (b - a).cross(&(c - a));
+        let normal = if normal.magnitude() == Scalar::ZERO {
+            normal
+        } else {
+            normal.normalize()
+        };
+
+        let data = self.triangles[triangle]
+            .as_mut()
+            .expect("Triangle has been removed");
+        data.normal = normal;
+        data.surface_coords = None;
+    }
+
+    /// Find a valid outgoing half-edge for a vertex
+    ///
+    /// Looks for one in the given triangles first. Vertices on the boundary
+    /// start from a boundary half-edge, so all of their triangles can be
+    /// reached.
+    fn repair_outgoing(&mut self, vertex: usize, triangles: &[usize]) {
+        if self.num_triangles_of[vertex] == 0 {
+            self.outgoing[vertex] = None;
+            return;
+        }
+
+        let starts_at_vertex =
+            |half_edge: &usize| self.origin(*half_edge) == vertex;
+        let candidate = triangles
+            .iter()
+            .filter(|&&triangle| self.triangles[triangle].is_some())
+            .flat_map(|&triangle| [0, 1, 2].map(|k| 3 * triangle + k))
+            .find(starts_at_vertex)
+            .or_else(|| self.half_edges().find(starts_at_vertex));
+        let start = match candidate {
+            Some(half_edge) => half_edge,
+            None => {
+                self.outgoing[vertex] = None;
+                return;
+            }
+        };
+
+        let mut outgoing = start;
+        for _ in 0..self.num_triangles_of[vertex] {
+            match self.opposites[outgoing] {
+                Some(opposite) => {
+                    outgoing = self.next(opposite);
+                    if outgoing == start {
+                        break;
+                    }
+                }
+                None => break,
+            }
+        }
+
+        self.outgoing[vertex] = Some(outgoing);
+    }
+}
+
+/// An inconsistency in the connectivity of a [`HalfEdgeMesh`]
+///
+/// Returned by [`HalfEdgeMesh::check_integrity`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum IntegrityError {
+    /// A triangle uses a vertex more than once
+    DegenerateTriangle(usize),
+
+    /// Two triangles contain the same half-edge
+    DuplicateHalfEdge(usize),
+
+    /// A half-edge doesn't match its opposite, or lacks one
+    InconsistentOpposite(usize),
+
+    /// The outgoing half-edge or triangle count of a vertex is wrong
+    InvalidOutgoing(usize),
+
+    /// The triangles around a vertex don't form a single fan
+    NonManifoldVertex(usize),
+}
+
+#[derive(Clone, Debug)]
+struct TriangleData {
+    vertices: [usize; 3],
+    color: Color,
+    face: Option<usize>,
+    normal: Vector<3>,
+    surface_coords: Option<[Point<2>; 3]>,
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::mesh::Mesh;
+
+    use super::{HalfEdgeMesh, IntegrityError};
+
+    #[test]
+    fn cube_round_trip() {
+        let cube = cube();
+
+        let half_edge_mesh = HalfEdgeMesh::from_mesh(&cube);
+        assert_eq!(half_edge_mesh.check_integrity(), Ok(()));
+        assert_eq!(half_edge_mesh.edges().count(), 18);
+        assert!(half_edge_mesh.boundary_loops().is_empty());
+
+        let round_trip = half_edge_mesh.to_mesh();
+        assert!(round_trip.vertices().eq(cube.vertices()));
+        assert!(round_trip.indices().eq(cube.indices()));
+        assert!(round_trip.triangles().eq(cube.triangles()));
+    }
+
+    #[test]
+    fn one_ring() {
+        let grid = HalfEdgeMesh::from_mesh(&grid());
+
+        let num_neighbors = |x, y| {
+            let vertex = vertex_at(&grid, x, y);
+            (grid.one_ring(vertex).len(), grid.is_boundary_vertex(vertex))
+        };
+
+        assert_eq!(num_neighbors(1., 1.), (6, false));
+        assert_eq!(num_neighbors(2., 0.), (4, true));
+        assert_eq!(num_neighbors(0., 0.), (3, true));
+        assert_eq!(num_neighbors(3., 0.), (2, true));
+
+        // The neighbors are in order around the vertex.
+        let ring: Vec<_> = grid
+            .one_ring(vertex_at(&grid, 1., 1.))
+            .into_iter()
+            .map(|vertex| grid.point(vertex))
+            .collect();
+        for (i, &a) in ring.iter().enumerate() {
+            let b = ring[(i + 1) % ring.len()];
+            assert!((b - a).magnitude() < Scalar::from(1.5));
+        }
+
+        let loops = grid.boundary_loops();
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].len(), 12);
+    }
+
+    #[test]
+    fn collapse_edge() {
+        let mut grid = HalfEdgeMesh::from_mesh(&grid());
+        let num_triangles = grid.triangles().count();
+
+        let [u, v] = [[1., 1.], [2., 1.]].map(|[x, y]| vertex_at(&grid, x, y));
+        let half_edge = grid.half_edge(u, v).expect("Edge exists");
+        assert_eq!(grid.collapse_edge(half_edge), Some(v));
+
+        assert_eq!(grid.check_integrity(), Ok(()));
+        assert_eq!(grid.triangles().count(), num_triangles - 2);
+        assert!(grid.one_ring(u).is_empty());
+        assert_eq!(grid.one_ring(v).len(), 8);
+
+        let mesh = grid.to_mesh();
+        assert_eq!(mesh.triangles().count(), num_triangles - 2);
+
+        // Collapsing an edge through the inside of the mesh, between two
+        // boundary vertices, would pinch it.
+        let [u, v] = [[2., 0.], [3., 1.]].map(|[x, y]| vertex_at(&grid, x, y));
+        let half_edge = grid.half_edge(u, v).expect("Edge exists");
+        assert_eq!(grid.collapse_edge(half_edge), None);
+        assert_eq!(grid.check_integrity(), Ok(()));
+    }
+
+    #[test]
+    fn flip_edge() {
+        let mut grid = HalfEdgeMesh::from_mesh(&grid());
+
+        let [u, v] = [[1., 1.], [2., 2.]].map(|[x, y]| vertex_at(&grid, x, y));
+        let half_edge = grid.half_edge(u, v).expect("Edge exists");
+        let flipped = grid.flip_edge(half_edge).expect("Edge can be flipped");
+
+        assert_eq!(grid.check_integrity(), Ok(()));
+        assert_eq!(grid.half_edge(u, v), None);
+        let [a, b] = [grid.origin(flipped), grid.target(flipped)];
+        let mut flipped_edge = [a, b].map(|vertex| grid.point(vertex));
+        flipped_edge.sort();
+        assert_eq!(flipped_edge, [[1., 2., 0.], [2., 1., 0.]].map(Point::from));
+
+        // Boundary edges can't be flipped.
+        let boundary = grid
+            .half_edges()
+            .find(|&half_edge| grid.is_boundary_edge(half_edge))
+            .expect("Grid has a boundary");
+        assert_eq!(grid.flip_edge(boundary), None);
+    }
+
+    #[test]
+    fn split_vertex() {
+        let mut grid = HalfEdgeMesh::from_mesh(&grid());
+        let num_triangles = grid.triangles().count();
+
+        let vertex = vertex_at(&grid, 1., 1.);
+        let outgoing = grid.outgoing(vertex);
+        let new_vertex = grid
+            .split_vertex(outgoing[0], outgoing[3], [1.1, 1.1, 0.].into())
+            .expect("Vertex can be split");
+
+        assert_eq!(grid.check_integrity(), Ok(()));
+        assert_eq!(grid.triangles().count(), num_triangles + 2);
+        assert_eq!(grid.one_ring(vertex).len(), 5);
+        assert_eq!(grid.one_ring(new_vertex).len(), 5);
+
+        // Collapsing the new edge undoes the split.
+        let half_edge =
+            grid.half_edge(new_vertex, vertex).expect("Edge exists");
+        assert_eq!(grid.collapse_edge(half_edge), Some(vertex));
+        assert_eq!(grid.check_integrity(), Ok(()));
+        assert_eq!(grid.triangles().count(), num_triangles);
+        assert_eq!(grid.one_ring(vertex).len(), 6);
+    }
+
+    #[test]
+    fn non_manifold_edge() {
+        let mut mesh = cube();
+        let [a, b] = [[0., 0., 0.], [1., 0., 0.]].map(Point::from);
+        mesh.push_triangle([a, b, Point::from([0.5, -1., 0.])], [0, 0, 0, 255]);
+
+        let half_edge_mesh = HalfEdgeMesh::from_mesh(&mesh);
+        assert!(matches!(
+            half_edge_mesh.check_integrity(),
+            Err(IntegrityError::DuplicateHalfEdge(_))
+        ));
+    }
+
+    fn cube() -> Mesh<Point<3>> {
+        let quads = [
+            [[0., 0., 0.], [0., 1., 0.], [1., 1., 0.], [1., 0., 0.]],
+            [[0., 0., 1.], [1., 0., 1.], [1., 1., 1.], [0., 1., 1.]],
+            [[0., 0., 0.], [1., 0., 0.], [1., 0., 1.], [0., 0., 1.]],
+            [[0., 1., 0.], [0., 1., 1.], [1., 1., 1.], [1., 1., 0.]],
+            [[0., 0., 0.], [0., 0., 1.], [0., 1., 1.], [0., 1., 0.]],
+            [[1., 0., 0.], [1., 1., 0.], [1., 1., 1.], [1., 0., 1.]],
+        ];
+
+        let mut mesh = Mesh::new();
+        for (face, quad) in quads.into_iter().enumerate() {
+            let [a, b, c, d] = quad.map(Point::from);
+            mesh.push_face_triangle([a, b, c], [255, 0, 0, 255], face);
+            mesh.push_face_triangle([a, c, d], [255, 0, 0, 255], face);
+        }
+
+        mesh
+    }
+
+    /// A flat grid of 3 by 3 squares, each split along the same diagonal
+    fn grid() -> Mesh<Point<3>> {
+        let mut mesh = Mesh::new();
+
+        for x in 0..3 {
+            for y in 0..3 {
+                let [a, b, c, d] =
+                    [[x, y], [x + 1, y], [x + 1, y + 1], [x, y + 1]]
+                        .map(|[x, y]| Point::from([x as f64, y as f64, 0.]));
+                mesh.push_triangle([a, b, c], [255, 0, 0, 255]);
+                mesh.push_triangle([a, c, d], [255, 0, 0, 255]);
+            }
+        }
+
+        mesh
+    }
+
+    fn vertex_at(mesh: &HalfEdgeMesh, x: f64, y: f64) -> usize {
+        let point = Point::from([x, y, 0.]);
+        mesh.vertices()
+            .find(|&vertex| mesh.point(vertex) == point)
+            .expect("No vertex at point")
+    }
+}
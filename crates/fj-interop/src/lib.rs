@@ -14,6 +14,17 @@
 
 #![warn(missing_docs)]
 
+pub mod annotation;
 pub mod debug;
+pub mod gizmo;
+pub mod half_edge;
+pub mod material;
+pub mod memory;
 pub mod mesh;
+pub mod mesh_quality;
+pub mod part;
+pub mod physical_summary;
 pub mod processed_shape;
+pub mod section;
+pub mod trace;
+pub mod uv_atlas;
@@ -0,0 +1,119 @@
+//! Materials of a processed shape
+//!
+//! Materials are assigned to the faces of a shape. Since the triangles of a
+//! [`Mesh`] record the face they approximate, this is enough to find the
+//! material of each triangle. Exporters use this to group triangles by
+//! material.
+//!
+//! [`Mesh`]: crate::mesh::Mesh
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::mesh::{Color, Triangle};
+
+/// The name of the material of faces that have no material assigned
+pub const DEFAULT_MATERIAL_NAME: &str = "default";
+
+/// A named material
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Material {
+    /// The name of the material
+    pub name: String,
+
+    /// The color that is used to display faces of this material
+    pub display_color: Color,
+
+    /// Additional information about the material
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl Material {
+    /// Create a material without metadata
+    pub fn new(name: impl Into<String>, display_color: Color) -> Self {
+        Self {
+            name: name.into(),
+            display_color,
+            metadata: BTreeMap::new(),
+        }
+    }
+}
+
+/// The materials of a shape, and the faces they are assigned to
+///
+/// The material at index `0` is the default material. It is used for all
+/// faces that have no other material assigned.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Materials {
+    materials: Vec<Material>,
+    by_face: HashMap<usize, usize>,
+}
+
+impl Materials {
+    /// Create an instance of `Materials` that only contains the default
+    /// material
+    pub fn new(default: Material) -> Self {
+        Self {
+            materials: vec![default],
+            by_face: HashMap::new(),
+        }
+    }
+
+    /// Add a material
+    ///
+    /// Returns the index of the material.
+    pub fn push(&mut self, material: Material) -> usize {
+        self.materials.push(material);
+        self.materials.len() - 1
+    }
+
+    /// Assign the material at index `material` to the face at index `face`
+    ///
+    /// # Panics
+    ///
+    /// Panics, if there is no material at index `material`.
+    pub fn assign(&mut self, face: usize, material: usize) {
+        assert!(material < self.materials.len(), "Invalid material index");
+        self.by_face.insert(face, material);
+    }
+
+    /// Access all materials
+    ///
+    /// The default material comes first.
+    pub fn materials(&self) -> &[Material] {
+        &self.materials
+    }
+
+    /// Find the index of the material with the given name
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.materials
+            .iter()
+            .position(|material| material.name == name)
+    }
+
+    /// Indicate whether any face has a material assigned
+    pub fn has_assignments(&self) -> bool {
+        !self.by_face.is_empty()
+    }
+
+    /// Return the index of the material of the face at index `face`
+    pub fn material_of_face(&self, face: usize) -> usize {
+        self.by_face.get(&face).copied().unwrap_or(0)
+    }
+
+    /// Return the index of the material of a triangle
+    ///
+    /// Triangles that don't record the face they approximate use the default
+    /// material.
+    pub fn material_of(&self, triangle: &Triangle) -> usize {
+        triangle
+            .face
+            .map(|face| self.material_of_face(face))
+            .unwrap_or(0)
+    }
+}
+
+impl Default for Materials {
+    fn default() -> Self {
+        Self::new(Material::new(DEFAULT_MATERIAL_NAME, [255, 0, 0, 255]))
+    }
+}
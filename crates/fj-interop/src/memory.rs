@@ -0,0 +1,164 @@
+//! Estimates of the memory used while processing a shape
+//!
+//! Used to find out which stage of an evaluation needs the most memory, when
+//! processing a large model. See [`MemoryReport`].
+//!
+//! The estimates are computed from the number of elements in each structure,
+//! not measured by the allocator. They include the buffers that hold most of
+//! the data, like the points of approximations and meshes, but not the
+//! overhead of the allocator or of unused capacity.
+
+use std::{collections::BTreeMap, fmt};
+
+/// Estimated memory usage, by kind of data
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MemoryReport {
+    kinds: BTreeMap<&'static str, MemoryUsage>,
+}
+
+impl MemoryReport {
+    /// Construct an empty report
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add elements of a kind of data to the report
+    ///
+    /// If the kind is already part of the report, the count and size are added
+    /// to it.
+    pub fn add(&mut self, kind: &'static str, count: usize, bytes: usize) {
+        let usage = self.kinds.entry(kind).or_default();
+        usage.count += count;
+        usage.bytes += bytes;
+    }
+
+    /// Merge another report into this one
+    pub fn merge(&mut self, other: &MemoryReport) {
+        for (&kind, usage) in &other.kinds {
+            self.add(kind, usage.count, usage.bytes);
+        }
+    }
+
+    /// Access the usage of a kind of data
+    ///
+    /// Returns `None`, if the kind is not part of the report.
+    pub fn get(&self, kind: &str) -> Option<MemoryUsage> {
+        self.kinds.get(kind).copied()
+    }
+
+    /// Iterate over all kinds of data, ordered by name
+    pub fn kinds(
+        &self,
+    ) -> impl Iterator<Item = (&'static str, MemoryUsage)> + '_ {
+        self.kinds.iter().map(|(&kind, &usage)| (kind, usage))
+    }
+
+    /// The estimated size of all data in the report, in bytes
+    pub fn total_bytes(&self) -> usize {
+        self.kinds.values().map(|usage| usage.bytes).sum()
+    }
+}
+
+impl fmt::Display for MemoryReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (kind, usage) in self.kinds() {
+            writeln!(
+                f,
+                "{kind:<24} {:>10} {:>14} bytes",
+                usage.count, usage.bytes
+            )?;
+        }
+        write!(
+            f,
+            "{:<24} {:>10} {:>14} bytes",
+            "total",
+            "",
+            self.total_bytes()
+        )
+    }
+}
+
+/// Estimated memory usage of one kind of data
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct MemoryUsage {
+    /// The number of elements
+    pub count: usize,
+
+    /// The estimated size of all elements, in bytes
+    pub bytes: usize,
+}
+
+/// A stage of processing a shape, after which memory usage is estimated
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum MemoryStage {
+    /// The boundary representation of the shape has been computed
+    Brep,
+
+    /// The faces of the shape have been approximated
+    Approximation,
+
+    /// The triangle mesh of the shape has been created
+    Mesh,
+
+    /// The sections of the shape have been evaluated
+    Sections,
+}
+
+impl MemoryStage {
+    /// All stages, in the order in which they happen
+    pub const ALL: [Self; 4] =
+        [Self::Brep, Self::Approximation, Self::Mesh, Self::Sections];
+
+    /// The name of the stage
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Brep => "brep",
+            Self::Approximation => "approximation",
+            Self::Mesh => "mesh",
+            Self::Sections => "sections",
+        }
+    }
+}
+
+/// The estimated memory usage after a stage of processing a shape
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MemorySnapshot {
+    /// The stage after which the estimate was made
+    pub stage: MemoryStage,
+
+    /// The data that was produced by the stage
+    pub report: MemoryReport,
+}
+
+/// Format memory snapshots as a table, one section per stage
+pub fn memory_table(snapshots: &[MemorySnapshot]) -> String {
+    snapshots
+        .iter()
+        .map(|snapshot| {
+            format!("[{}]\n{}", snapshot.stage.name(), snapshot.report)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MemoryReport;
+
+    #[test]
+    fn merge() {
+        let mut a = MemoryReport::new();
+        a.add("points", 2, 48);
+
+        let mut b = MemoryReport::new();
+        b.add("points", 1, 24);
+        b.add("indices", 3, 12);
+
+        a.merge(&b);
+
+        assert_eq!(a.get("points").map(|usage| usage.count), Some(3));
+        assert_eq!(a.get("points").map(|usage| usage.bytes), Some(72));
+        assert_eq!(a.total_bytes(), 84);
+        assert!(a.get("triangles").is_none());
+    }
+}
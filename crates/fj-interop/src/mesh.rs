@@ -1,8 +1,14 @@
 //! A triangle mesh
 
-use std::{collections::HashMap, hash::Hash};
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+    mem::size_of,
+};
 
-use fj_math::Point;
+use fj_math::{Point, Scalar, Transform, Vector};
+
+use crate::memory::MemoryReport;
 
 /// A triangle mesh
 pub struct Mesh<V> {
@@ -11,6 +17,10 @@ pub struct Mesh<V> {
 
     indices_by_vertex: HashMap<V, Index>,
     triangles: Vec<Triangle>,
+    surface_coords: Vec<Option<[Point<2>; 3]>>,
+
+    double_sided: bool,
+    degraded_faces: BTreeMap<usize, Scalar>,
 }
 
 impl<V> Mesh<V>
@@ -23,6 +33,9 @@ where
     }
 
     /// Add a vertex to the mesh
+    ///
+    /// Vertices that are equal to one that was added before are not stored
+    /// again. Their index refers to the existing vertex instead.
     pub fn push_vertex(&mut self, vertex: V) {
         let index =
             *self.indices_by_vertex.entry(vertex).or_insert_with(|| {
@@ -37,7 +50,9 @@ where
     /// Determine whether the mesh contains the provided triangle
     ///
     /// Returns true, if a triangle with any combination of the provided points
-    /// is part of the mesh.
+    /// is part of the mesh. The points must match exactly. See
+    /// [`Mesh::contains_triangle_approx`] for a comparison that tolerates
+    /// small differences, and respects the winding of the triangle.
     pub fn contains_triangle(&self, points: [impl Into<Point<3>>; 3]) -> bool {
         let triangle = fj_math::Triangle::from_points(points).normalize();
 
@@ -51,6 +66,56 @@ where
         false
     }
 
+    /// Determine whether the mesh contains a triangle close to the provided one
+    ///
+    /// Returns true, if a triangle of the mesh has the provided points in the
+    /// same cyclic order, starting at any of them. Each coordinate of each
+    /// point may differ by up to `epsilon`. A triangle with the same points but
+    /// the opposite winding doesn't match, as it faces the other way.
+    pub fn contains_triangle_approx(
+        &self,
+        points: [impl Into<Point<3>>; 3],
+        epsilon: impl Into<Scalar>,
+    ) -> bool {
+        let points = points.map(Into::into);
+        let epsilon = epsilon.into();
+
+        let is_close = |a: Point<3>, b: Point<3>| {
+            (a - b)
+                .components
+                .iter()
+                .all(|component| component.abs() <= epsilon)
+        };
+
+        self.triangles.iter().any(|triangle| {
+            (0..3).any(|offset| {
+                (0..3).all(|i| {
+                    is_close(triangle.points[(i + offset) % 3], points[i])
+                })
+            })
+        })
+    }
+
+    /// Find a triangle that contains a point, or is close to it
+    ///
+    /// Returns the first triangle, in the order of [`Mesh::triangles`], whose
+    /// distance from `point` is at most `epsilon`. Returns `None`, if there is
+    /// no such triangle.
+    pub fn find_triangle_near(
+        &self,
+        point: impl Into<Point<3>>,
+        epsilon: impl Into<Scalar>,
+    ) -> Option<Triangle> {
+        let point = point.into();
+        let epsilon = epsilon.into();
+
+        self.triangles.iter().copied().find(|triangle| {
+            fj_math::Triangle::from_points(triangle.points)
+                .distance_to_point(point)
+                <= epsilon
+        })
+    }
+
     /// Access the vertices of the mesh
     pub fn vertices(&self) -> impl Iterator<Item = V> + '_ {
         self.vertices.iter().copied()
@@ -62,20 +127,547 @@ where
     }
 
     /// Access the triangles of the mesh
+    ///
+    /// Each triangle comes with its color, the face it approximates, and its
+    /// normal (see [`Triangle`]). Triangles are yielded in the order they were
+    /// added. Their number is available from [`Mesh::triangle_count`].
     pub fn triangles(&self) -> impl Iterator<Item = Triangle> + '_ {
         self.triangles.iter().copied()
     }
+
+    /// Access the triangle at the given index
+    ///
+    /// Triangles are indexed in the order of [`Mesh::triangles`].
+    pub fn triangle(&self, index: usize) -> Option<Triangle> {
+        self.triangles.get(index).copied()
+    }
+
+    /// Access the surface coordinates of the points of a triangle
+    ///
+    /// These are the exact coordinates of the triangle's points, in the surface
+    /// of the face that the triangle approximates (see [`Triangle::face`]). The
+    /// surface can be evaluated at any point within the triangle, to get back
+    /// from the mesh to the exact shape.
+    ///
+    /// Returns `None`, if the index is out of bounds, or if the surface
+    /// coordinates of the triangle are not known.
+    pub fn surface_coords(&self, index: usize) -> Option<[Point<2>; 3]> {
+        self.surface_coords.get(index).copied().flatten()
+    }
+
+    /// Indicate whether both sides of the mesh's triangles should be rendered
+    ///
+    /// This is the case for meshes that approximate open surfaces, like
+    /// sketches, which can be seen from both sides. Exporters are free to
+    /// ignore this.
+    pub fn is_double_sided(&self) -> bool {
+        self.double_sided
+    }
+
+    /// Define whether both sides of the mesh's triangles should be rendered
+    ///
+    /// See [`Mesh::is_double_sided`].
+    pub fn set_double_sided(&mut self, double_sided: bool) {
+        self.double_sided = double_sided;
+    }
+
+    /// Access the number of triangles in the mesh
+    pub fn triangle_count(&self) -> usize {
+        self.triangles.len()
+    }
+
+    /// Estimate the memory used by the mesh
+    ///
+    /// Reports the vertices, including the map that deduplicates them, the
+    /// indices, and the attributes of the triangles, like their colors and
+    /// surface coordinates.
+    pub fn memory_report(&self) -> MemoryReport {
+        let mut report = MemoryReport::new();
+
+        let vertex = size_of::<V>();
+        let index = size_of::<Index>();
+
+        report.add(
+            "mesh vertices",
+            self.vertices.len(),
+            self.vertices.len() * vertex
+                + self.indices_by_vertex.len() * (vertex + index),
+        );
+        report.add(
+            "mesh indices",
+            self.indices.len(),
+            self.indices.len() * index,
+        );
+        report.add(
+            "mesh attributes",
+            self.triangles.len(),
+            self.triangles.len() * size_of::<Triangle>()
+                + self.surface_coords.len()
+                    * size_of::<Option<[Point<2>; 3]>>()
+                + self.degraded_faces.len()
+                    * (size_of::<usize>() + size_of::<Scalar>()),
+        );
+
+        report
+    }
+
+    /// Access the faces that were approximated with a coarser tolerance
+    ///
+    /// Yields the index of each face (see [`Triangle::face`]), together with
+    /// the tolerance that was actually achieved. This happens, if
+    /// approximating the face with the requested tolerance would have taken
+    /// too much effort.
+    pub fn degraded_faces(&self) -> impl Iterator<Item = (usize, Scalar)> + '_ {
+        self.degraded_faces
+            .iter()
+            .map(|(&face, &tolerance)| (face, tolerance))
+    }
+
+    /// Determine whether a face was approximated with a coarser tolerance
+    ///
+    /// See [`Mesh::degraded_faces`].
+    pub fn is_degraded(&self, face: usize) -> bool {
+        self.degraded_faces.contains_key(&face)
+    }
+
+    /// Record that a face was approximated with a coarser tolerance
+    ///
+    /// See [`Mesh::degraded_faces`].
+    pub fn mark_degraded(&mut self, face: usize, achieved_tolerance: Scalar) {
+        self.degraded_faces.insert(face, achieved_tolerance);
+    }
 }
 
 impl Mesh<Point<3>> {
     /// Add a triangle to the mesh
     pub fn push_triangle(&mut self, points: [Point<3>; 3], color: Color) {
+        self.push_triangle_inner(points, color, None, None, None);
+    }
+
+    /// Add a triangle to the mesh, recording the face it approximates
+    ///
+    /// `face` is an index that identifies the face within the shape. See
+    /// [`Triangle::face`].
+    pub fn push_face_triangle(
+        &mut self,
+        points: [Point<3>; 3],
+        color: Color,
+        face: usize,
+    ) {
+        self.push_triangle_inner(points, color, Some(face), None, None);
+    }
+
+    /// Add a triangle to the mesh, recording where it is within its face
+    ///
+    /// Like [`Mesh::push_face_triangle`], but also records the surface
+    /// coordinates of the triangle's points. See [`Mesh::surface_coords`].
+    pub fn push_face_triangle_with_coords(
+        &mut self,
+        points: [Point<3>; 3],
+        color: Color,
+        face: usize,
+        surface_coords: [Point<2>; 3],
+    ) {
+        self.push_triangle_inner(
+            points,
+            color,
+            Some(face),
+            Some(surface_coords),
+            None,
+        );
+    }
+
+    /// Add a triangle to the mesh, recording the normal of its face
+    ///
+    /// Like [`Mesh::push_face_triangle_with_coords`], but also records the
+    /// outward normal of the face at the triangle, instead of computing it from
+    /// the triangle's points. See [`Triangle::normal`].
+    pub fn push_face_triangle_with_normal(
+        &mut self,
+        points: [Point<3>; 3],
+        color: Color,
+        face: usize,
+        surface_coords: [Point<2>; 3],
+        normal: Vector<3>,
+    ) {
+        self.push_triangle_inner(
+            points,
+            color,
+            Some(face),
+            Some(surface_coords),
+            Some(normal),
+        );
+    }
+
+    /// Compute the normals at the points of each triangle, for smooth shading
+    ///
+    /// Returns the normals in the order of [`Mesh::triangles`]. Within a face
+    /// (see [`Triangle::face`]), the normal at a point is the average of the
+    /// normals of the face's triangles there.
+    ///
+    /// Two faces that meet at an edge share their normals along it, if the
+    /// edge is smooth. That is the case, if the normals of both faces differ
+    /// by at most `crease_angle` (in radians) at every point along the edge,
+    /// like where a fillet meets a plane. Otherwise, the edge is sharp, and
+    /// each face keeps its own normals there, like at the edges of a box.
+    /// Triangles without a face are treated as faces of their own.
+    pub fn vertex_normals(&self, crease_angle: Scalar) -> Vec<[Vector<3>; 3]> {
+        let patches: Vec<_> = self
+            .triangles
+            .iter()
+            .enumerate()
+            .map(|(i, triangle)| {
+                triangle.face.map_or(Patch::Triangle(i), Patch::Face)
+            })
+            .collect();
+
+        let mut normals = HashMap::new();
+        let mut patches_by_edge: HashMap<_, Vec<_>> = HashMap::new();
+        for (triangle, &patch) in self.triangles.iter().zip(&patches) {
+            for point in triangle.points {
+                let normal = normals
+                    .entry((point, patch))
+                    .or_insert_with(|| Vector::from([0., 0., 0.]));
+                *normal = *normal + triangle.normal;
+            }
+
+            let [a, b, c] = triangle.points;
+            for mut edge in [[a, b], [b, c], [c, a]] {
+                edge.sort();
+                let patches = patches_by_edge.entry(edge).or_default();
+                if !patches.contains(&patch) {
+                    patches.push(patch);
+                }
+            }
+        }
+        for normal in normals.values_mut() {
+            *normal = normalize(*normal);
+        }
+
+        // The points along the edge between each pair of adjacent faces
+        let mut edges: HashMap<_, Vec<Point<3>>> = HashMap::new();
+        for (points, patches) in &patches_by_edge {
+            for (i, &a) in patches.iter().enumerate() {
+                for &b in &patches[i + 1..] {
+                    let points_of_edge = edges.entry((a.min(b), a.max(b)));
+                    points_of_edge.or_default().extend(points);
+                }
+            }
+        }
+
+        let min_cos = crease_angle.cos();
+        let mut smooth_edges: HashMap<_, Vec<_>> = HashMap::new();
+        for ((a, b), points) in edges {
+            let is_smooth = points.iter().all(|&point| {
+                normals[&(point, a)].dot(&normals[&(point, b)]) >= min_cos
+            });
+
+            if is_smooth {
+                for point in points {
+                    smooth_edges.entry(point).or_default().push((a, b));
+                }
+            }
+        }
+
+        // All faces that are connected by smooth edges at a point share the
+        // same normal there.
+        let mut shared = HashMap::new();
+        self.triangles
+            .iter()
+            .zip(&patches)
+            .map(|(triangle, &patch)| {
+                triangle.points.map(|point| {
+                    *shared.entry((point, patch)).or_insert_with(|| {
+                        let edges = smooth_edges
+                            .get(&point)
+                            .map(Vec::as_slice)
+                            .unwrap_or_default();
+
+                        let sum = smooth_group(patch, edges)
+                            .into_iter()
+                            .fold(Vector::from([0., 0., 0.]), |sum, patch| {
+                                sum + normals[&(point, patch)]
+                            });
+                        normalize(sum)
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Create the vertices for rendering the mesh with smooth shading
+    ///
+    /// Pairs each point of each triangle with its normal, as computed by
+    /// [`Mesh::vertex_normals`]. Equal pairs are only stored once, which means
+    /// vertices are shared across smooth edges, and split along sharp ones.
+    pub fn shading_vertices(
+        &self,
+        crease_angle: Scalar,
+    ) -> Mesh<(Point<3>, Vector<3>)> {
+        let mut mesh = Mesh::new();
+
+        for (triangle, normals) in
+            self.triangles.iter().zip(self.vertex_normals(crease_angle))
+        {
+            for (point, normal) in triangle.points.into_iter().zip(normals) {
+                mesh.push_vertex((point, normal));
+            }
+        }
+
+        mesh
+    }
+
+    /// Create a transformed copy of the mesh
+    ///
+    /// If the transform mirrors the mesh, the winding of each triangle is
+    /// flipped, so triangles that faced outward still do. Colors, faces, and
+    /// surface coordinates of the triangles are preserved, as is their order.
+    /// Normals are transformed along with the triangles.
+    pub fn transformed(&self, transform: &Transform) -> Self {
+        let is_mirror = transform.determinant() < Scalar::ZERO;
+        let normal_transform = transform.inverse().transpose();
+
+        let mut mesh = Self::new();
+        mesh.set_double_sided(self.double_sided);
+        mesh.degraded_faces = self.degraded_faces.clone();
+
+        for (triangle, surface_coords) in
+            self.triangles.iter().zip(&self.surface_coords)
+        {
+            let [a, b, c] = triangle
+                .points
+                .map(|point| transform.transform_point(&point));
+            let points = if is_mirror { [a, c, b] } else { [a, b, c] };
+            let surface_coords = surface_coords.map(|[a, b, c]| {
+                if is_mirror {
+                    [a, c, b]
+                } else {
+                    [a, b, c]
+                }
+            });
+
+            let normal = normal_transform.transform_vector(&triangle.normal);
+
+            mesh.push_triangle_inner(
+                points,
+                triangle.color,
+                triangle.face,
+                surface_coords,
+                Some(normalize(normal)),
+            );
+        }
+
+        mesh
+    }
+
+    /// Create a copy of the mesh, with vertices merged that are close together
+    ///
+    /// Equal vertices are always shared (see [`Mesh::push_vertex`]). This also
+    /// merges vertices that are at most `epsilon` apart, which might happen,
+    /// where the approximations of adjacent faces meet. Each vertex is replaced
+    /// by the first one within `epsilon`, in the order of the triangles.
+    ///
+    /// Vertices are only merged, if the triangles they belong to have the same
+    /// color. Triangles that collapse, because two of their vertices were
+    /// merged, are removed.
+    pub fn deduplicated(&self, epsilon: Scalar) -> Self {
+        let mut mesh = Self::new();
+        mesh.set_double_sided(self.double_sided);
+        mesh.degraded_faces = self.degraded_faces.clone();
+
+        // Vertices are looked up in a grid of cells that are `epsilon` wide,
+        // so only the neighboring cells need to be searched.
+        let mut merged: HashMap<(Color, [i64; 3]), Vec<Point<3>>> =
+            HashMap::new();
+        let mut merge = |point: Point<3>, color: Color| {
+            if epsilon <= Scalar::ZERO {
+                return point;
+            }
+
+            let cell = point.coords.components.map(|coord| {
+                (coord.into_f64() / epsilon.into_f64()).floor() as i64
+            });
+
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    for dz in -1..=1 {
+                        let [x, y, z] = cell;
+                        let neighbor = merged
+                            .get(&(color, [x + dx, y + dy, z + dz]))
+                            .and_then(|vertices| {
+                                vertices.iter().find(|&&vertex| {
+                                    (vertex - point).magnitude() <= epsilon
+                                })
+                            });
+
+                        if let Some(&vertex) = neighbor {
+                            return vertex;
+                        }
+                    }
+                }
+            }
+
+            merged.entry((color, cell)).or_default().push(point);
+            point
+        };
+
+        for (triangle, &surface_coords) in
+            self.triangles.iter().zip(&self.surface_coords)
+        {
+            let [a, b, c] =
+                triangle.points.map(|point| merge(point, triangle.color));
+            if a == b || b == c || c == a {
+                continue;
+            }
+
+            mesh.push_triangle_inner(
+                [a, b, c],
+                triangle.color,
+                triangle.face,
+                surface_coords,
+                Some(triangle.normal),
+            );
+        }
+
+        mesh
+    }
+
+    /// Create a copy of the mesh, with its triangles colored for debugging
+    ///
+    /// Each triangle gets the color that `coloring` assigns to it. Everything
+    /// else is preserved. As this only needs the faces and normals of the
+    /// triangles, the coloring can be switched without triangulating the shape
+    /// again.
+    pub fn with_face_coloring(&self, coloring: FaceColoring) -> Self {
+        let mut mesh = Self::new();
+        mesh.set_double_sided(self.double_sided);
+        mesh.degraded_faces = self.degraded_faces.clone();
+
+        for (triangle, &surface_coords) in
+            self.triangles.iter().zip(&self.surface_coords)
+        {
+            mesh.push_triangle_inner(
+                triangle.points,
+                coloring.color(triangle),
+                triangle.face,
+                surface_coords,
+                Some(triangle.normal),
+            );
+        }
+
+        mesh
+    }
+
+    /// Transform the mesh
+    ///
+    /// See [`Mesh::transformed`].
+    pub fn transform(&mut self, transform: &Transform) {
+        *self = self.transformed(transform);
+    }
+
+    /// Concatenate multiple meshes into one
+    ///
+    /// The triangles of each mesh are added in order, so they form a contiguous
+    /// range within the merged mesh. Their faces are preserved. Vertices that
+    /// are shared between meshes are only stored once. The merged mesh is
+    /// double-sided, if any of the meshes is. Degraded faces are preserved
+    /// too, which assumes the meshes use the same face indices.
+    pub fn merged(meshes: impl IntoIterator<Item = Self>) -> Self {
+        let mut merged = Self::new();
+
+        for mesh in meshes {
+            merged.double_sided |= mesh.double_sided;
+            merged.degraded_faces.extend(mesh.degraded_faces);
+
+            for (triangle, surface_coords) in
+                mesh.triangles.into_iter().zip(mesh.surface_coords)
+            {
+                merged.push_triangle_inner(
+                    triangle.points,
+                    triangle.color,
+                    triangle.face,
+                    surface_coords,
+                    Some(triangle.normal),
+                );
+            }
+        }
+
+        merged
+    }
+
+    fn push_triangle_inner(
+        &mut self,
+        points: [Point<3>; 3],
+        color: Color,
+        face: Option<usize>,
+        surface_coords: Option<[Point<2>; 3]>,
+        normal: Option<Vector<3>>,
+    ) {
         for point in points {
             self.push_vertex(point);
         }
 
-        self.triangles.push(Triangle { points, color });
+        let normal = normal.unwrap_or_else(|| {
+            let [a, b, c] = points;
+            normalize((b - a).cross(&(c - a)))
+        });
+
+        self.triangles.push(Triangle {
+            points,
+            color,
+            face,
+            normal,
+        });
+        self.surface_coords.push(surface_coords);
+    }
+}
+
+/// A part of a mesh that is shaded smoothly, unless it meets itself at an edge
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+enum Patch {
+    /// The triangles of a face
+    Face(usize),
+
+    /// A triangle without a face, by its index
+    Triangle(usize),
+}
+
+/// The patches that are connected to a patch by smooth edges, at a point
+///
+/// `edges` are the smooth edges at the point. The result is sorted, so it's the
+/// same for all patches in it.
+fn smooth_group(patch: Patch, edges: &[(Patch, Patch)]) -> Vec<Patch> {
+    let mut group = vec![patch];
+
+    let mut i = 0;
+    while i < group.len() {
+        let current = group[i];
+
+        for &(a, b) in edges {
+            let other = match current {
+                current if current == a => b,
+                current if current == b => a,
+                _ => continue,
+            };
+            if !group.contains(&other) {
+                group.push(other);
+            }
+        }
+
+        i += 1;
+    }
+
+    group.sort();
+    group
+}
+
+/// Normalize a vector, unless it has zero length
+fn normalize(vector: Vector<3>) -> Vector<3> {
+    if vector.magnitude() == Scalar::ZERO {
+        return vector;
     }
+
+    vector.normalize()
 }
 
 // This needs to be a manual implementation. Deriving `Default` would require
@@ -87,6 +679,9 @@ impl<V> Default for Mesh<V> {
             indices: Default::default(),
             indices_by_vertex: Default::default(),
             triangles: Default::default(),
+            surface_coords: Default::default(),
+            double_sided: false,
+            degraded_faces: BTreeMap::new(),
         }
     }
 }
@@ -96,7 +691,8 @@ pub type Index = u32;
 
 /// A triangle
 ///
-/// Extension of [`fj_math::Triangle`] that also includes a color.
+/// Extension of [`fj_math::Triangle`] that also includes a color, the face it
+/// approximates, and its normal.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Triangle {
     /// The points of the triangle
@@ -104,7 +700,446 @@ pub struct Triangle {
 
     /// The color of the triangle
     pub color: Color,
+
+    /// The face that the triangle approximates, if known
+    ///
+    /// Triangles that approximate the same face share the same index. This
+    /// can be used to find the triangle edges that correspond to the edges of
+    /// the original shape.
+    pub face: Option<usize>,
+
+    /// The outward normal of the triangle
+    ///
+    /// For triangles that approximate a curved face, this is the normal of the
+    /// face at the triangle, which can differ from the normal of the triangle
+    /// itself. This is also correct for thin triangles, whose points don't
+    /// define their normal precisely. If no other normal is known, it is
+    /// computed from the triangle's points, and is zero, if they don't span
+    /// any area.
+    pub normal: Vector<3>,
 }
 
 /// RGBA color
 pub type Color = [u8; 4];
+
+/// The crease angle that exporters and the viewer pass to
+/// [`Mesh::vertex_normals`], in radians
+///
+/// Faces that meet at a smaller angle are shaded as one smooth surface.
+pub const DEFAULT_CREASE_ANGLE: f64 = std::f64::consts::FRAC_PI_6;
+
+/// How the triangles of a mesh are colored, to tell faces apart
+///
+/// See [`Mesh::with_face_coloring`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FaceColoring {
+    /// Keep the colors of the faces
+    #[default]
+    Original,
+
+    /// Give each face a distinct color, derived from its index
+    ///
+    /// The index of a face stays the same, as long as the topology of the
+    /// shape does, so faces keep their colors while parameters change.
+    /// Triangles that don't record their face keep their color.
+    Distinct,
+
+    /// Color triangles by the octant that their normal points into
+    ///
+    /// Each component of the color is bright, if the respective component of
+    /// the normal is positive or zero, and dark, if it is negative.
+    ByOrientation,
+}
+
+impl FaceColoring {
+    /// The color of a triangle
+    pub fn color(&self, triangle: &Triangle) -> Color {
+        match self {
+            Self::Original => triangle.color,
+            Self::Distinct => match triangle.face {
+                Some(face) => distinct_color(face),
+                None => triangle.color,
+            },
+            Self::ByOrientation => {
+                let [r, g, b] = triangle.normal.components.map(|component| {
+                    if component >= Scalar::ZERO {
+                        224
+                    } else {
+                        64
+                    }
+                });
+                [r, g, b, 255]
+            }
+        }
+    }
+
+    /// The coloring that follows this one, cycling through all of them
+    pub fn next(self) -> Self {
+        match self {
+            Self::Original => Self::Distinct,
+            Self::Distinct => Self::ByOrientation,
+            Self::ByOrientation => Self::Original,
+        }
+    }
+}
+
+/// A color that differs from the colors of the neighboring indices
+///
+/// The hues advance by the golden ratio, which spreads them evenly around the
+/// color wheel, no matter how many there are.
+fn distinct_color(index: usize) -> Color {
+    const GOLDEN_RATIO_CONJUGATE: f64 = 0.618_033_988_749_895;
+    const SATURATION: f64 = 0.65;
+    const VALUE: f64 = 0.95;
+
+    let hue = (index as f64 * GOLDEN_RATIO_CONJUGATE).fract() * 6.;
+    let sector = hue.floor();
+    let f = hue - sector;
+
+    let p = VALUE * (1. - SATURATION);
+    let q = VALUE * (1. - SATURATION * f);
+    let t = VALUE * (1. - SATURATION * (1. - f));
+
+    let [r, g, b] = match sector as u8 {
+        0 => [VALUE, t, p],
+        1 => [q, VALUE, p],
+        2 => [p, VALUE, t],
+        3 => [p, q, VALUE],
+        4 => [t, p, VALUE],
+        _ => [VALUE, p, q],
+    };
+    let [r, g, b] = [r, g, b].map(|component| (component * 255.).round() as u8);
+
+    [r, g, b, 255]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::size_of;
+
+    use fj_math::{Point, Scalar, Transform, Vector};
+
+    use super::{FaceColoring, Mesh, Triangle, DEFAULT_CREASE_ANGLE};
+
+    #[test]
+    fn mirror_flips_winding() {
+        let cube = cube();
+        assert_volume(&cube, 1.);
+
+        let mirrored = cube.transformed(&Transform::scale([-1., 1., 1.]));
+        assert_volume(&mirrored, 1.);
+
+        let mut moved = cube;
+        moved.transform(&Transform::translation([1., 2., 3.]));
+        assert_volume(&moved, 1.);
+        assert!(moved.vertices().all(|point| point.z >= Scalar::from(3.)));
+    }
+
+    #[test]
+    fn surface_coords_follow_points() {
+        let mut mesh = Mesh::new();
+        let points =
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]].map(Point::from);
+        let coords = [[0., 0.], [1., 0.], [0., 1.]].map(Point::from);
+        mesh.push_face_triangle_with_coords(points, [0, 0, 0, 255], 0, coords);
+        mesh.push_face_triangle(points, [0, 0, 0, 255], 1);
+
+        let mirrored = mesh.transformed(&Transform::scale([-1., 1., 1.]));
+        let [a, b, c] = coords;
+        assert_eq!(mirrored.surface_coords(0), Some([a, c, b]));
+        assert_eq!(mirrored.surface_coords(1), None);
+        assert_eq!(mirrored.surface_coords(2), None);
+
+        let merged = Mesh::merged([mesh.deduplicated(Scalar::ZERO), mirrored]);
+        assert_eq!(merged.surface_coords(0), Some(coords));
+        assert_eq!(merged.surface_coords(2), Some([a, c, b]));
+    }
+
+    #[test]
+    fn normals_follow_transform() {
+        let mut mesh = Mesh::new();
+        let points =
+            [[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]].map(Point::from);
+        let coords = [[0., 0.], [1., 0.], [0., 1.]].map(Point::from);
+        let normal = Vector::from([1., 1., 1.]).normalize();
+        mesh.push_face_triangle_with_normal(
+            points,
+            [0, 0, 0, 255],
+            0,
+            coords,
+            normal,
+        );
+        mesh.push_triangle(points, [0, 0, 0, 255]);
+
+        // Without a known normal, it's computed from the points.
+        let normals: Vec<_> =
+            mesh.triangles().map(|triangle| triangle.normal).collect();
+        assert!((normals[0] - normals[1]).magnitude() < Scalar::from(1e-12));
+
+        // Normals stay perpendicular to the triangles, even if those are
+        // scaled non-uniformly or mirrored.
+        let transform = Transform::scale([-2., 1., 1.]);
+        for triangle in mesh.transformed(&transform).triangles() {
+            let [a, b, c] = triangle.points;
+            let expected = (b - a).cross(&(c - a)).normalize();
+            assert!(
+                (triangle.normal - expected).magnitude() < Scalar::from(1e-12)
+            );
+        }
+    }
+
+    #[test]
+    fn merge_preserves_faces() {
+        let a = cube();
+        let b = cube().transformed(&Transform::translation([2., 0., 0.]));
+
+        let merged = Mesh::merged([a, b]);
+
+        assert_eq!(merged.triangles().count(), 24);
+        assert_eq!(merged.vertices().count(), 16);
+        assert_volume(&merged, 2.);
+
+        // The triangles of each mesh form a contiguous range, with the faces
+        // of the original mesh.
+        for (i, triangle) in merged.triangles().enumerate() {
+            let is_b = triangle
+                .points
+                .iter()
+                .all(|point| point.x >= Scalar::from(2.));
+            assert_eq!(is_b, i >= 12);
+            assert_eq!(triangle.face, Some(i % 12 / 2));
+        }
+    }
+
+    #[test]
+    fn deduplicated() {
+        let red = [255, 0, 0, 255];
+        let blue = [0, 0, 255, 255];
+
+        // Two triangles that share an edge, except for a tiny offset.
+        let offset = Vector::from([1e-10, 0., 0.]);
+        let [a, b, c, d] =
+            [[0., 0., 0.], [1., 0., 0.], [1., 1., 0.], [0., 1., 0.]]
+                .map(Point::from);
+
+        let mesh = |color| {
+            let mut mesh = Mesh::new();
+            mesh.push_triangle([a, b, c], red);
+            mesh.push_triangle([a + offset, c + offset, d], color);
+            mesh
+        };
+
+        let same_color = mesh(red);
+        assert_eq!(same_color.vertices().count(), 6);
+        assert_eq!(same_color.deduplicated(Scalar::ZERO).vertices().count(), 6);
+
+        let deduplicated = same_color.deduplicated(Scalar::from(1e-9));
+        assert_eq!(deduplicated.vertices().count(), 4);
+        assert_eq!(deduplicated.indices().count(), 6);
+        assert!(deduplicated.contains_triangle([a, c, d]));
+
+        // Vertices of triangles with different colors are not merged.
+        let different_colors = mesh(blue).deduplicated(Scalar::from(1e-9));
+        assert_eq!(different_colors.vertices().count(), 6);
+
+        // Triangles that collapse are removed.
+        let collapsed = same_color.deduplicated(Scalar::from(2.));
+        assert_eq!(collapsed.triangles().count(), 0);
+    }
+
+    #[test]
+    fn face_coloring() {
+        let cube = cube();
+
+        let distinct = cube.with_face_coloring(FaceColoring::Distinct);
+        let mut colors: Vec<_> = distinct
+            .triangles()
+            .map(|triangle| triangle.color)
+            .collect();
+        colors.sort();
+        colors.dedup();
+        assert_eq!(colors.len(), 6);
+
+        // The bottom face points along -Z, the top face along +Z.
+        let oriented = cube.with_face_coloring(FaceColoring::ByOrientation);
+        let [bottom, top] = [0, 2]
+            .map(|i| oriented.triangle(i).expect("Triangle exists").color);
+        assert_ne!(bottom, top);
+
+        let original = distinct.with_face_coloring(FaceColoring::Original);
+        assert!(original.triangles().eq(distinct.triangles()));
+    }
+
+    #[test]
+    fn shading_vertices() {
+        let crease_angle = Scalar::from_f64(DEFAULT_CREASE_ANGLE);
+
+        // The edges of a cube are sharp. Each corner has three vertices, one
+        // for each face.
+        let cube = cube().shading_vertices(crease_angle);
+        assert_eq!(cube.vertices().count(), 24);
+
+        // A plane that continues into a slightly curved face, like a fillet.
+        // The normals of the faces agree along the seam at `x = 0`.
+        let tilted = Vector::from([-0.1, 0., 1.]).normalize();
+        let mut mesh = Mesh::new();
+        for (face, quad, normal) in [
+            (
+                0,
+                [[-1., 0.], [0., 0.], [0., 1.], [-1., 1.]],
+                Vector::unit_z(),
+            ),
+            (1, [[0., 0.], [1., 0.], [1., 1.], [0., 1.]], tilted),
+        ] {
+            let [a, b, c, d] = quad.map(|[x, y]| {
+                // Bend the second face upward, along its normal.
+                let z = if face == 1 { x * 0.1 } else { 0. };
+                Point::from([x, y, z])
+            });
+            let coords = [[0., 0.]; 3].map(Point::from);
+            for points in [[a, b, c], [a, c, d]] {
+                mesh.push_face_triangle_with_normal(
+                    points,
+                    [255, 0, 0, 255],
+                    face,
+                    coords,
+                    normal,
+                );
+            }
+        }
+
+        // The two points on the seam are shared by both faces.
+        let smooth = mesh.shading_vertices(crease_angle);
+        assert_eq!(smooth.vertices().count(), 6);
+
+        // There's no shading seam: both faces have the same normals there.
+        let normals = mesh.vertex_normals(crease_angle);
+        let seam = Point::from([0., 0., 0.]);
+        let normals_at_seam: Vec<_> = mesh
+            .triangles()
+            .zip(&normals)
+            .flat_map(|(triangle, normals)| {
+                triangle.points.into_iter().zip(*normals)
+            })
+            .filter(|&(point, _)| point == seam)
+            .map(|(_, normal)| normal)
+            .collect();
+        assert_eq!(normals_at_seam.len(), 3);
+        assert!(normals_at_seam
+            .iter()
+            .all(|&normal| normal == normals_at_seam[0]));
+
+        // With a smaller crease angle, the seam is sharp.
+        let sharp = mesh.shading_vertices(Scalar::from_f64(0.01));
+        assert_eq!(sharp.vertices().count(), 8);
+    }
+
+    #[test]
+    fn contains_triangle_approx() {
+        let [a, b, c] =
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]].map(Point::from);
+
+        let mut mesh = Mesh::new();
+        mesh.push_triangle([a, b, c], [255, 0, 0, 255]);
+
+        // Any starting point matches, but only with the same winding.
+        assert!(mesh.contains_triangle_approx([a, b, c], 0.));
+        assert!(mesh.contains_triangle_approx([b, c, a], 0.));
+        assert!(mesh.contains_triangle_approx([c, a, b], 0.));
+        assert!(!mesh.contains_triangle_approx([a, c, b], 0.));
+        assert!(!mesh.contains_triangle_approx([c, b, a], 0.));
+
+        let offset = Vector::from([0., 0., 1e-6]);
+        let moved = [a + offset, b, c];
+        assert!(!mesh.contains_triangle_approx(moved, 0.9e-6));
+        assert!(mesh.contains_triangle_approx(moved, 1.1e-6));
+    }
+
+    #[test]
+    fn find_triangle_near() {
+        let mut mesh = Mesh::new();
+        mesh.push_triangle(
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]].map(Point::from),
+            [255, 0, 0, 255],
+        );
+        mesh.push_triangle(
+            [[0., 0., 1.], [1., 0., 1.], [0., 1., 1.]].map(Point::from),
+            [0, 0, 255, 255],
+        );
+
+        let found = mesh.find_triangle_near([0.25, 0.25, 1.], 0.);
+        assert_eq!(
+            found.map(|triangle| triangle.color),
+            Some([0, 0, 255, 255])
+        );
+
+        let point = [0.25, 0.25, 1e-6];
+        assert!(mesh.find_triangle_near(point, 0.9e-6).is_none());
+        let found = mesh.find_triangle_near(point, 1.1e-6);
+        assert_eq!(
+            found.map(|triangle| triangle.color),
+            Some([255, 0, 0, 255])
+        );
+    }
+
+    #[test]
+    fn memory_report() {
+        let report = cube().memory_report();
+
+        // 8 distinct vertices of 3 `f64`s, each also stored as a key of the
+        // deduplication map, next to its `u32` index.
+        let vertices = report.get("mesh vertices").expect("Vertices reported");
+        assert_eq!(vertices.count, 8);
+        assert_eq!(vertices.bytes, 8 * 24 + 8 * (24 + 4));
+
+        let indices = report.get("mesh indices").expect("Indices reported");
+        assert_eq!(indices.count, 36);
+        assert_eq!(indices.bytes, 36 * 4);
+
+        let attributes =
+            report.get("mesh attributes").expect("Attributes reported");
+        assert_eq!(attributes.count, 12);
+        assert_eq!(
+            attributes.bytes,
+            12 * size_of::<Triangle>()
+                + 12 * size_of::<Option<[Point<2>; 3]>>()
+        );
+
+        assert_eq!(
+            report.total_bytes(),
+            vertices.bytes + indices.bytes + attributes.bytes
+        );
+    }
+
+    fn cube() -> Mesh<Point<3>> {
+        let quads = [
+            [[0., 0., 0.], [0., 1., 0.], [1., 1., 0.], [1., 0., 0.]],
+            [[0., 0., 1.], [1., 0., 1.], [1., 1., 1.], [0., 1., 1.]],
+            [[0., 0., 0.], [1., 0., 0.], [1., 0., 1.], [0., 0., 1.]],
+            [[0., 1., 0.], [0., 1., 1.], [1., 1., 1.], [1., 1., 0.]],
+            [[0., 0., 0.], [0., 0., 1.], [0., 1., 1.], [0., 1., 0.]],
+            [[1., 0., 0.], [1., 1., 0.], [1., 1., 1.], [1., 0., 1.]],
+        ];
+
+        let mut mesh = Mesh::new();
+        for (face, quad) in quads.into_iter().enumerate() {
+            let [a, b, c, d] = quad.map(Point::from);
+            mesh.push_face_triangle([a, b, c], [255, 0, 0, 255], face);
+            mesh.push_face_triangle([a, c, d], [255, 0, 0, 255], face);
+        }
+
+        mesh
+    }
+
+    /// Assert the signed volume of a closed mesh
+    ///
+    /// The volume is only positive, if the triangles face outward.
+    fn assert_volume(mesh: &Mesh<Point<3>>, expected: f64) {
+        let volume = mesh.triangles().fold(Scalar::ZERO, |volume, triangle| {
+            let [a, b, c] = triangle.points.map(|point| point.coords);
+            volume + a.dot(&b.cross(&c)) / 6.
+        });
+
+        assert!((volume - Scalar::from(expected)).abs() < Scalar::from(1e-12));
+    }
+}
@@ -0,0 +1,379 @@
+//! Quality metrics for triangle meshes
+//!
+//! Used to quantify the output of triangulation, for example when tuning how
+//! faces are tessellated. See [`MeshQualityReport`].
+
+use std::collections::BTreeMap;
+
+use fj_math::{Point, Scalar};
+
+/// The minimum angle of a triangle, below which it is considered poor
+///
+/// In degrees. See [`QualityStats::num_poor`].
+pub const POOR_MIN_ANGLE: f64 = 10.;
+
+/// The upper bounds of the buckets of the aspect ratio histogram
+///
+/// See [`QualityStats::aspect_ratio_histogram`]. The last bucket, which is not
+/// listed here, has no upper bound.
+pub const ASPECT_RATIO_BUCKETS: [f64; 5] = [1.5, 2., 3., 5., 10.];
+
+/// Quality metrics of the triangles of a mesh, per face and aggregated
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MeshQualityReport {
+    faces: BTreeMap<usize, QualityStats>,
+    total: QualityStats,
+}
+
+impl MeshQualityReport {
+    /// Construct an empty report
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a triangle of a face to the report
+    ///
+    /// `is_boundary` tells whether the triangle is constrained by the boundary
+    /// of the face, which means that at least one of its edges connects two
+    /// points of the boundary.
+    pub fn push_triangle(
+        &mut self,
+        face: usize,
+        points: [Point<3>; 3],
+        is_boundary: bool,
+    ) {
+        let quality = TriangleQuality::new(points);
+
+        self.faces
+            .entry(face)
+            .or_default()
+            .push(&quality, is_boundary);
+        self.total.push(&quality, is_boundary);
+    }
+
+    /// Merge another report into this one
+    ///
+    /// Statistics of faces with the same index are combined.
+    pub fn merge(&mut self, other: MeshQualityReport) {
+        for (face, stats) in other.faces {
+            self.faces.entry(face).or_default().merge(&stats);
+        }
+        self.total.merge(&other.total);
+    }
+
+    /// Access the statistics of a face
+    ///
+    /// Returns `None`, if no triangles of that face have been added.
+    pub fn face(&self, face: usize) -> Option<&QualityStats> {
+        self.faces.get(&face)
+    }
+
+    /// Iterate over the statistics of all faces, ordered by face index
+    pub fn faces(&self) -> impl Iterator<Item = (usize, &QualityStats)> + '_ {
+        self.faces.iter().map(|(&face, stats)| (face, stats))
+    }
+
+    /// Access the statistics of all triangles
+    pub fn total(&self) -> &QualityStats {
+        &self.total
+    }
+
+    /// Indicate whether the report contains any triangles
+    pub fn is_empty(&self) -> bool {
+        self.total.num_triangles == 0
+    }
+}
+
+/// Quality statistics of a group of triangles
+///
+/// Angles are in degrees.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QualityStats {
+    num_triangles: usize,
+    num_boundary: usize,
+    num_poor: usize,
+    num_poor_interior: usize,
+
+    min_angle: f64,
+    max_angle: f64,
+    sum_min_angles: f64,
+
+    min_edge_length: f64,
+    max_edge_length: f64,
+    sum_edge_lengths: f64,
+
+    aspect_ratio_histogram: [usize; ASPECT_RATIO_BUCKETS.len() + 1],
+}
+
+impl QualityStats {
+    /// The number of triangles
+    pub fn num_triangles(&self) -> usize {
+        self.num_triangles
+    }
+
+    /// The number of triangles that are constrained by the face boundary
+    pub fn num_boundary(&self) -> usize {
+        self.num_boundary
+    }
+
+    /// The number of triangles that are not constrained by the face boundary
+    pub fn num_interior(&self) -> usize {
+        self.num_triangles - self.num_boundary
+    }
+
+    /// The ratio of boundary-constrained triangles to interior ones
+    ///
+    /// Returns `None`, if there are no interior triangles.
+    pub fn boundary_ratio(&self) -> Option<f64> {
+        let num_interior = self.num_interior();
+        (num_interior > 0)
+            .then_some(self.num_boundary as f64 / num_interior as f64)
+    }
+
+    /// The number of triangles with an angle below [`POOR_MIN_ANGLE`]
+    pub fn num_poor(&self) -> usize {
+        self.num_poor
+    }
+
+    /// The number of poor triangles that are not constrained by the boundary
+    ///
+    /// Poor triangles at the boundary are often unavoidable, for example at
+    /// sharp corners of a face. Poor triangles in the interior are not.
+    pub fn num_poor_interior(&self) -> usize {
+        self.num_poor_interior
+    }
+
+    /// The smallest angle of any triangle
+    ///
+    /// Returns `None`, if there are no triangles.
+    pub fn min_angle(&self) -> Option<f64> {
+        self.if_not_empty(self.min_angle)
+    }
+
+    /// The largest angle of any triangle
+    ///
+    /// Returns `None`, if there are no triangles.
+    pub fn max_angle(&self) -> Option<f64> {
+        self.if_not_empty(self.max_angle)
+    }
+
+    /// The mean of the smallest angle of each triangle
+    ///
+    /// The mean of all angles is always 60°, so this is the more useful value.
+    /// Returns `None`, if there are no triangles.
+    pub fn mean_min_angle(&self) -> Option<f64> {
+        self.if_not_empty(self.sum_min_angles / self.num_triangles as f64)
+    }
+
+    /// The length of the shortest triangle edge
+    ///
+    /// Returns `None`, if there are no triangles.
+    pub fn min_edge_length(&self) -> Option<f64> {
+        self.if_not_empty(self.min_edge_length)
+    }
+
+    /// The length of the longest triangle edge
+    ///
+    /// Returns `None`, if there are no triangles.
+    pub fn max_edge_length(&self) -> Option<f64> {
+        self.if_not_empty(self.max_edge_length)
+    }
+
+    /// The mean length of the triangle edges
+    ///
+    /// Edges shared by triangles are counted once per triangle. Returns
+    /// `None`, if there are no triangles.
+    pub fn mean_edge_length(&self) -> Option<f64> {
+        self.if_not_empty(
+            self.sum_edge_lengths / (self.num_triangles * 3) as f64,
+        )
+    }
+
+    /// The number of triangles per aspect ratio bucket
+    ///
+    /// The aspect ratio is normalized, so an equilateral triangle has an aspect
+    /// ratio of 1. Degenerate triangles have an infinite aspect ratio. Bucket
+    /// `i` counts the triangles with an aspect ratio below
+    /// `ASPECT_RATIO_BUCKETS[i]`, that don't fit into a previous bucket. The
+    /// last bucket counts all remaining triangles.
+    pub fn aspect_ratio_histogram(
+        &self,
+    ) -> [usize; ASPECT_RATIO_BUCKETS.len() + 1] {
+        self.aspect_ratio_histogram
+    }
+
+    fn push(&mut self, quality: &TriangleQuality, is_boundary: bool) {
+        let is_poor = quality.min_angle < POOR_MIN_ANGLE;
+
+        self.num_triangles += 1;
+        self.num_boundary += usize::from(is_boundary);
+        self.num_poor += usize::from(is_poor);
+        self.num_poor_interior += usize::from(is_poor && !is_boundary);
+
+        self.min_angle = self.min_angle.min(quality.min_angle);
+        self.max_angle = self.max_angle.max(quality.max_angle);
+        self.sum_min_angles += quality.min_angle;
+
+        self.min_edge_length = self.min_edge_length.min(quality.min_edge);
+        self.max_edge_length = self.max_edge_length.max(quality.max_edge);
+        self.sum_edge_lengths += quality.sum_edges;
+
+        let bucket = ASPECT_RATIO_BUCKETS
+            .iter()
+            .position(|&bound| quality.aspect_ratio < bound)
+            .unwrap_or(ASPECT_RATIO_BUCKETS.len());
+        self.aspect_ratio_histogram[bucket] += 1;
+    }
+
+    fn merge(&mut self, other: &QualityStats) {
+        self.num_triangles += other.num_triangles;
+        self.num_boundary += other.num_boundary;
+        self.num_poor += other.num_poor;
+        self.num_poor_interior += other.num_poor_interior;
+
+        self.min_angle = self.min_angle.min(other.min_angle);
+        self.max_angle = self.max_angle.max(other.max_angle);
+        self.sum_min_angles += other.sum_min_angles;
+
+        self.min_edge_length = self.min_edge_length.min(other.min_edge_length);
+        self.max_edge_length = self.max_edge_length.max(other.max_edge_length);
+        self.sum_edge_lengths += other.sum_edge_lengths;
+
+        for (a, b) in self
+            .aspect_ratio_histogram
+            .iter_mut()
+            .zip(other.aspect_ratio_histogram)
+        {
+            *a += b;
+        }
+    }
+
+    fn if_not_empty(&self, value: f64) -> Option<f64> {
+        (self.num_triangles > 0).then_some(value)
+    }
+}
+
+impl Default for QualityStats {
+    fn default() -> Self {
+        Self {
+            num_triangles: 0,
+            num_boundary: 0,
+            num_poor: 0,
+            num_poor_interior: 0,
+            min_angle: f64::INFINITY,
+            max_angle: f64::NEG_INFINITY,
+            sum_min_angles: 0.,
+            min_edge_length: f64::INFINITY,
+            max_edge_length: f64::NEG_INFINITY,
+            sum_edge_lengths: 0.,
+            aspect_ratio_histogram: [0; ASPECT_RATIO_BUCKETS.len() + 1],
+        }
+    }
+}
+
+struct TriangleQuality {
+    min_angle: f64,
+    max_angle: f64,
+    min_edge: f64,
+    max_edge: f64,
+    sum_edges: f64,
+    aspect_ratio: f64,
+}
+
+impl TriangleQuality {
+    fn new(points: [Point<3>; 3]) -> Self {
+        let [a, b, c] = points;
+        let edges = [b - a, c - b, a - c];
+        let lengths = edges.map(|edge| edge.magnitude().into_f64());
+
+        // The angle at each point is between the edge arriving at it and the
+        // edge leaving it.
+        let angles = [(2, 0), (0, 1), (1, 2)].map(|(arriving, leaving)| {
+            let arriving = -edges[arriving];
+            let leaving = edges[leaving];
+
+            let length = arriving.magnitude() * leaving.magnitude();
+            if length == Scalar::ZERO {
+                return 0.;
+            }
+
+            let cos = (arriving.dot(&leaving) / length).into_f64();
+            cos.clamp(-1., 1.).acos().to_degrees()
+        });
+
+        let min_edge = lengths.into_iter().fold(f64::INFINITY, f64::min);
+        let max_edge = lengths.into_iter().fold(0., f64::max);
+        let sum_edges: f64 = lengths.into_iter().sum();
+
+        // Normalized, so an equilateral triangle has an aspect ratio of 1.
+        let area = edges[0].cross(&-edges[2]).magnitude().into_f64() / 2.;
+        let aspect_ratio = if area > 0. {
+            max_edge * sum_edges / (4. * 3f64.sqrt() * area)
+        } else {
+            f64::INFINITY
+        };
+
+        Self {
+            min_angle: angles.into_iter().fold(f64::INFINITY, f64::min),
+            max_angle: angles.into_iter().fold(0., f64::max),
+            min_edge,
+            max_edge,
+            sum_edges,
+            aspect_ratio,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Point;
+
+    use super::MeshQualityReport;
+
+    #[test]
+    fn equilateral_triangle() {
+        let mut report = MeshQualityReport::new();
+        report.push_triangle(
+            0,
+            [
+                Point::from([0., 0., 0.]),
+                Point::from([1., 0., 0.]),
+                Point::from([0.5, 3f64.sqrt() / 2., 0.]),
+            ],
+            false,
+        );
+
+        let stats = report.total();
+        let is_close = |a: Option<f64>, b: f64| (a.unwrap() - b).abs() < 1e-9;
+
+        assert!(is_close(stats.min_angle(), 60.));
+        assert!(is_close(stats.max_angle(), 60.));
+        assert!(is_close(stats.mean_edge_length(), 1.));
+        assert_eq!(stats.aspect_ratio_histogram(), [1, 0, 0, 0, 0, 0]);
+        assert_eq!(stats.num_interior(), 1);
+    }
+
+    #[test]
+    fn merge() {
+        let triangle = [
+            Point::from([0., 0., 0.]),
+            Point::from([1., 0., 0.]),
+            Point::from([0., 0.1, 0.]),
+        ];
+
+        let mut a = MeshQualityReport::new();
+        a.push_triangle(0, triangle, true);
+
+        let mut b = MeshQualityReport::new();
+        b.push_triangle(0, triangle, false);
+        b.push_triangle(1, triangle, false);
+
+        a.merge(b);
+
+        assert_eq!(a.face(0).map(|stats| stats.num_triangles()), Some(2));
+        assert_eq!(a.face(1).map(|stats| stats.num_triangles()), Some(1));
+        assert_eq!(a.total().num_triangles(), 3);
+        assert_eq!(a.total().num_poor(), 3);
+        assert_eq!(a.total().num_poor_interior(), 2);
+    }
+}
@@ -0,0 +1,35 @@
+//! Parts of a processed shape
+//!
+//! Parts are labeled sub-shapes of a model, that can be suppressed. All parts
+//! of a shape are listed after processing it, including the suppressed ones,
+//! so the host application can present them and change their state for the
+//! next evaluation.
+
+/// A part of a processed shape
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartInfo {
+    /// The path to the part, starting from the processed shape
+    ///
+    /// Made up of the same field names that identify the location of an error.
+    pub path: Vec<String>,
+
+    /// The label of the part, if it has one
+    pub label: Option<String>,
+
+    /// The state that the part was evaluated with
+    pub state: PartState,
+}
+
+/// Determines how a part is evaluated
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum PartState {
+    /// The part was evaluated as usual
+    #[default]
+    Active,
+
+    /// The part was skipped entirely
+    Suppressed,
+
+    /// The part was displayed, but isn't included in the physical properties
+    DisplayOnly,
+}
@@ -0,0 +1,347 @@
+//! Physical properties of a shape, and how they change between evaluations
+
+use std::fmt;
+
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use crate::mesh::Mesh;
+
+/// Physical properties of a shape
+///
+/// Computed from the triangle mesh that approximates the shape. For shapes
+/// that only consist of planar faces, the mesh is exact, and so are these
+/// values. For curved shapes, they are as accurate as the mesh.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PhysicalSummary {
+    /// The volume enclosed by the shape
+    ///
+    /// This is only meaningful, if the mesh is closed.
+    pub volume: Scalar,
+
+    /// The area of the shape's surface
+    pub surface_area: Scalar,
+
+    /// The axis-aligned bounding box of the mesh
+    pub aabb: Aabb<3>,
+
+    /// The center of mass of the shape, assuming uniform density
+    ///
+    /// If the shape has no volume (for example a sketch), this is the center
+    /// of its surface instead.
+    pub centroid: Point<3>,
+
+    /// The tolerance that the mesh was created with
+    ///
+    /// Used by [`PhysicalSummary::diff`] to tell actual changes apart from
+    /// differences in the approximation.
+    pub tolerance: Scalar,
+}
+
+impl PhysicalSummary {
+    /// Compute the physical summary of a mesh
+    ///
+    /// `tolerance` is the tolerance that the mesh was created with.
+    pub fn from_mesh(mesh: &Mesh<Point<3>>, tolerance: Scalar) -> Self {
+        let mut volume = Scalar::ZERO;
+        let mut surface_area = Scalar::ZERO;
+        let mut volume_moment = Vector::from([0., 0., 0.]);
+        let mut area_moment = Vector::from([0., 0., 0.]);
+
+        for triangle in mesh.triangles() {
+            let [a, b, c] = triangle.points.map(|point| point.coords);
+
+            // The signed volume of the tetrahedron that the triangle forms
+            // with the origin. Summed up, these add up to the enclosed volume.
+            let tetrahedron = a.dot(&b.cross(&c)) / 6.;
+            volume += tetrahedron;
+            volume_moment = volume_moment + (a + b + c) * (tetrahedron / 4.);
+
+            let area = (b - a).cross(&(c - a)).magnitude() / 2.;
+            surface_area += area;
+            area_moment = area_moment + (a + b + c) * (area / 3.);
+        }
+
+        let aabb = if mesh.vertices().next().is_some() {
+            Aabb::<3>::from_points(mesh.vertices())
+        } else {
+            Aabb {
+                min: Point::origin(),
+                max: Point::origin(),
+            }
+        };
+
+        let centroid = if volume.abs() > Scalar::ZERO {
+            volume_moment / volume
+        } else if surface_area > Scalar::ZERO {
+            area_moment / surface_area
+        } else {
+            aabb.center().coords
+        };
+
+        Self {
+            volume,
+            surface_area,
+            aabb,
+            centroid: Point { coords: centroid },
+            tolerance,
+        }
+    }
+
+    /// Compare this summary to that of a previous evaluation
+    ///
+    /// Changes that could be caused by approximating the shape are reported
+    /// as unchanged. The thresholds for that are derived from the larger of
+    /// both summaries' tolerances. Use [`PhysicalSummary::diff_with_threshold`]
+    /// to define the threshold explicitly.
+    pub fn diff(&self, previous: &Self) -> SummaryDiff {
+        self.diff_with_threshold(
+            previous,
+            self.tolerance.max(previous.tolerance),
+        )
+    }
+
+    /// Compare this summary to that of a previous evaluation
+    ///
+    /// `threshold` is a length. Lengths that change by less than that are
+    /// reported as unchanged. For areas and volumes, the threshold is scaled
+    /// by the size and surface area of the shape, respectively.
+    pub fn diff_with_threshold(
+        &self,
+        previous: &Self,
+        threshold: Scalar,
+    ) -> SummaryDiff {
+        let size = self.aabb.size();
+        let previous_size = previous.aabb.size();
+
+        let diagonal = size.magnitude().max(previous_size.magnitude());
+        let surface_area = self.surface_area.max(previous.surface_area);
+
+        SummaryDiff {
+            volume: QuantityChange::new(
+                previous.volume,
+                self.volume,
+                threshold * surface_area,
+            ),
+            surface_area: QuantityChange::new(
+                previous.surface_area,
+                self.surface_area,
+                threshold * diagonal,
+            ),
+            size: [0, 1, 2].map(|i| {
+                QuantityChange::new(
+                    previous_size.components[i],
+                    size.components[i],
+                    threshold,
+                )
+            }),
+            centroid_offset: QuantityChange::new(
+                Scalar::ZERO,
+                (self.centroid - previous.centroid).magnitude(),
+                threshold,
+            ),
+        }
+    }
+}
+
+/// The changes between two [`PhysicalSummary`]s
+///
+/// Returned by [`PhysicalSummary::diff`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SummaryDiff {
+    /// The change of the volume
+    pub volume: QuantityChange,
+
+    /// The change of the surface area
+    pub surface_area: QuantityChange,
+
+    /// The changes of the size of the bounding box, along each axis
+    pub size: [QuantityChange; 3],
+
+    /// The distance that the centroid moved
+    ///
+    /// The previous value of this quantity is always zero.
+    pub centroid_offset: QuantityChange,
+}
+
+impl SummaryDiff {
+    /// Indicate whether all quantities are unchanged
+    pub fn is_unchanged(&self) -> bool {
+        self.quantities()
+            .iter()
+            .all(|(_, change)| change.is_unchanged())
+    }
+
+    fn quantities(&self) -> [(&'static str, QuantityChange); 6] {
+        let [x, y, z] = self.size;
+
+        [
+            ("volume", self.volume),
+            ("surface area", self.surface_area),
+            ("size x", x),
+            ("size y", y),
+            ("size z", z),
+            ("centroid offset", self.centroid_offset),
+        ]
+    }
+}
+
+impl fmt::Display for SummaryDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_unchanged() {
+            return write!(f, "unchanged");
+        }
+
+        let mut first = true;
+        for (name, change) in self.quantities() {
+            if change.is_unchanged() {
+                continue;
+            }
+
+            if !first {
+                write!(f, ", ")?;
+            }
+            first = false;
+
+            match change.relative {
+                Some(relative) => {
+                    write!(f, "{name} {:+.1}%", relative.into_f64() * 100.)?
+                }
+                None => write!(f, "{name} {:+}", change.absolute)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The change of a single quantity
+///
+/// See [`SummaryDiff`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QuantityChange {
+    /// The previous value
+    pub previous: Scalar,
+
+    /// The current value
+    pub current: Scalar,
+
+    /// The absolute change, or zero, if the quantity is unchanged
+    pub absolute: Scalar,
+
+    /// The change relative to the previous value
+    ///
+    /// `None`, if the previous value was zero, or if the quantity is
+    /// unchanged.
+    pub relative: Option<Scalar>,
+}
+
+impl QuantityChange {
+    fn new(previous: Scalar, current: Scalar, threshold: Scalar) -> Self {
+        let absolute = current - previous;
+
+        if absolute.abs() <= threshold {
+            return Self {
+                previous,
+                current,
+                absolute: Scalar::ZERO,
+                relative: None,
+            };
+        }
+
+        let relative = if previous == Scalar::ZERO {
+            None
+        } else {
+            Some(absolute / previous.abs())
+        };
+
+        Self {
+            previous,
+            current,
+            absolute,
+            relative,
+        }
+    }
+
+    /// Indicate whether the quantity is unchanged
+    pub fn is_unchanged(&self) -> bool {
+        self.absolute == Scalar::ZERO
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar, Vector};
+
+    use crate::mesh::Mesh;
+
+    use super::PhysicalSummary;
+
+    #[test]
+    fn cube() {
+        let tolerance = Scalar::from_f64(0.001);
+
+        let summary = PhysicalSummary::from_mesh(&cube_mesh(1., 0.), tolerance);
+        assert!(is_close(summary.volume, 1.));
+        assert!(is_close(summary.surface_area, 6.));
+        for coord in summary.centroid.coords.components {
+            assert!(is_close(coord, 0.5));
+        }
+
+        let bigger = PhysicalSummary::from_mesh(&cube_mesh(1.1, 0.), tolerance);
+        let diff = bigger.diff(&summary);
+
+        let relative = diff.volume.relative.expect("Volume should change");
+        assert!((relative.into_f64() - 0.331).abs() < 0.001);
+        assert!(!diff.is_unchanged());
+        assert!(diff.to_string().contains("volume +33.1%"));
+    }
+
+    #[test]
+    fn jitter_is_unchanged() {
+        let tolerance = Scalar::from_f64(0.001);
+
+        let summary = PhysicalSummary::from_mesh(&cube_mesh(1., 0.), tolerance);
+        let jittered =
+            PhysicalSummary::from_mesh(&cube_mesh(1., 1e-12), tolerance);
+
+        let diff = jittered.diff(&summary);
+        assert!(diff.is_unchanged());
+        assert_eq!(diff.to_string(), "unchanged");
+    }
+
+    fn is_close(value: Scalar, expected: f64) -> bool {
+        (value.into_f64() - expected).abs() < 1e-12
+    }
+
+    /// A cube with the given edge length and a consistently oriented mesh
+    ///
+    /// All vertices are moved by `jitter`, along a different direction each.
+    fn cube_mesh(edge: f64, jitter: f64) -> Mesh<Point<3>> {
+        let mut i = 0.;
+        let mut p = |x: f64, y: f64, z: f64| {
+            i += 1.;
+            Point::from([x, y, z].map(|c| c * edge))
+                + Vector::from([jitter * i, -jitter, jitter / i])
+        };
+
+        let [a, b, c, d] =
+            [p(0., 0., 0.), p(1., 0., 0.), p(1., 1., 0.), p(0., 1., 0.)];
+        let [e, f, g, h] =
+            [p(0., 0., 1.), p(1., 0., 1.), p(1., 1., 1.), p(0., 1., 1.)];
+
+        let mut mesh = Mesh::new();
+        for [a, b, c, d] in [
+            [a, d, c, b],
+            [e, f, g, h],
+            [a, b, f, e],
+            [b, c, g, f],
+            [c, d, h, g],
+            [d, a, e, h],
+        ] {
+            mesh.push_triangle([a, b, c], [255, 0, 0, 255]);
+            mesh.push_triangle([a, c, d], [255, 0, 0, 255]);
+        }
+
+        mesh
+    }
+}
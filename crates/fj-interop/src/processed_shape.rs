@@ -1,8 +1,14 @@
 //! A processed shape
 
-use fj_math::{Aabb, Point};
+use std::collections::BTreeMap;
 
-use crate::{debug::DebugInfo, mesh::Mesh};
+use fj_math::{Aabb, Point, Scalar};
+
+use crate::{
+    annotation::Annotation, debug::DebugInfo, material::Materials,
+    memory::MemorySnapshot, mesh::Mesh, part::PartInfo,
+    physical_summary::PhysicalSummary, section::Section,
+};
 
 /// A processed shape
 pub struct ProcessedShape {
@@ -12,6 +18,44 @@ pub struct ProcessedShape {
     /// The triangle mesh that approximates the original shape
     pub mesh: Mesh<Point<3>>,
 
+    /// The worst-case positional error accumulated while computing the shape
+    ///
+    /// This doesn't include the error introduced by approximating the shape
+    /// for the triangle mesh, which is bounded by the tolerance.
+    pub accumulated_error: Scalar,
+
+    /// Physical properties of the shape, computed from the triangle mesh
+    ///
+    /// Display-only parts are not included.
+    pub summary: PhysicalSummary,
+
+    /// Issues with the shape that didn't prevent processing it
+    ///
+    /// These don't make the shape invalid, but are probably not intended.
+    pub warnings: Vec<String>,
+
+    /// The annotations of the shape, resolved against its geometry
+    pub annotations: Vec<Annotation>,
+
+    /// The materials of the shape, and the faces they are assigned to
+    pub materials: Materials,
+
+    /// All parts of the shape, including suppressed ones
+    pub parts: Vec<PartInfo>,
+
+    /// The cross-sections of the shape, by label
+    ///
+    /// All sections that the shape defines are listed, even if their
+    /// evaluation was skipped.
+    pub sections: BTreeMap<String, Section>,
+
     /// The debug info generated while processing the shape
     pub debug_info: DebugInfo,
+
+    /// The estimated memory usage after each stage of processing the shape
+    ///
+    /// Contains one snapshot per [`MemoryStage`], in the order of the stages.
+    ///
+    /// [`MemoryStage`]: crate::memory::MemoryStage
+    pub memory: Vec<MemorySnapshot>,
 }
@@ -0,0 +1,43 @@
+//! Cross-sections of a processed shape
+//!
+//! Sections are defined in the model, as named planes. They are evaluated
+//! along with the shape, so the host application can switch between the full
+//! shape and any of its sections, without processing the shape again.
+
+use fj_math::{Point, Vector};
+
+use crate::mesh::Mesh;
+
+/// A named cross-section of a processed shape
+pub struct Section {
+    /// A point on the plane that the shape is cut with
+    pub origin: Point<3>,
+
+    /// The normal of the plane, pointing to the side that is removed
+    pub normal: Vector<3>,
+
+    /// The result of cutting the shape
+    ///
+    /// `None`, if the evaluation of sections was skipped.
+    pub view: Option<SectionView>,
+}
+
+/// The result of cutting a shape with the plane of a section
+pub struct SectionView {
+    /// The regions of the cross-section
+    pub faces: Vec<SectionFace>,
+
+    /// The part of the shape's mesh below the plane, closed at the cut
+    pub mesh: Mesh<Point<3>>,
+}
+
+/// A region of a cross-section
+#[derive(Clone, Debug, PartialEq)]
+pub struct SectionFace {
+    /// The outline of the region, counter-clockwise when viewed along the
+    /// normal of the section
+    pub exterior: Vec<Point<3>>,
+
+    /// The holes in the region
+    pub interiors: Vec<Vec<Point<3>>>,
+}
@@ -0,0 +1,46 @@
+//! Optional instrumentation with the `tracing` crate
+//!
+//! The macros defined here are shared by the crates that can be instrumented.
+//! Spans are only created, if the `tracing` feature of the crate that uses the
+//! macros is enabled. That crate must depend on `tracing` for that feature.
+//! Otherwise, the macros expand to nothing that has a cost at runtime, and the
+//! values of the span fields are not evaluated.
+
+/// Enter a span at the given level, if the `tracing` feature is enabled
+///
+/// Takes the same arguments as `tracing::span!`, except that the level is
+/// given as the name of a `tracing::Level` constant. Returns a guard that
+/// exits the span, when dropped. Use [`record!`] to record the fields of the
+/// span that are only known later.
+#[macro_export]
+macro_rules! span {
+    ($level:ident, $name:expr $(, $($fields:tt)*)?) => {{
+        #[cfg(feature = "tracing")]
+        let span = tracing::span!(
+            tracing::Level::$level,
+            $name
+            $(, $($fields)*)?
+        )
+        .entered();
+
+        #[cfg(not(feature = "tracing"))]
+        let span = $crate::trace::Disabled;
+
+        span
+    }};
+}
+
+/// Record the value of a field of a span entered by [`span!`]
+#[macro_export]
+macro_rules! record {
+    ($span:expr, $field:literal, $value:expr) => {{
+        #[cfg(feature = "tracing")]
+        $span.record($field, &$value);
+
+        #[cfg(not(feature = "tracing"))]
+        let _ = &$span;
+    }};
+}
+
+/// The span guard, if the `tracing` feature is disabled
+pub struct Disabled;
@@ -0,0 +1,345 @@
+//! Texture coordinates for a whole mesh, in a single texture atlas
+//!
+//! See [`pack_uv_atlas`].
+
+use std::collections::BTreeMap;
+
+use fj_math::{Point, Scalar, Vector};
+
+use crate::mesh::{Mesh, Triangle};
+
+/// The factor by which the charts shrink, each time they don't fit
+const RESCALE_FACTOR: f64 = 0.95;
+
+/// Pack the faces of a mesh into a single texture atlas
+///
+/// Each face of the mesh (see [`Triangle::face`]) becomes a chart: its
+/// triangles are projected into the plane that best fits them. Triangles that
+/// don't belong to a face become a chart each. All charts are scaled by the
+/// same factor, so their size in the atlas is proportional to their size in
+/// the model, and the texel density is roughly uniform.
+///
+/// The bounding rectangles of the charts are packed into shelves that span the
+/// atlas, which covers `[0, 1]` in both directions. `padding` is kept between
+/// charts, and between charts and the border of the atlas. It is a fraction of
+/// the atlas size; for `n` texels at a resolution of `r` texels, pass `n / r`.
+/// If the charts don't fit, they are scaled down uniformly, until they do.
+///
+/// Charts of curved faces overlap themselves, as they are not unwrapped.
+pub fn pack_uv_atlas(mesh: &Mesh<Point<3>>, padding: f32) -> UvAtlas {
+    let triangles: Vec<_> = mesh.triangles().collect();
+    let padding = f64::from(padding);
+
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    let mut ungrouped = Vec::new();
+    for (i, triangle) in triangles.iter().enumerate() {
+        match triangle.face {
+            Some(face) => groups.entry(face).or_default().push(i),
+            None => ungrouped.push((None, vec![i])),
+        }
+    }
+
+    let charts: Vec<_> = groups
+        .into_iter()
+        .map(|(face, group)| (Some(face), group))
+        .chain(ungrouped)
+        .map(|(face, group)| Chart::new(face, group, &triangles))
+        .collect();
+
+    let total_area: f64 =
+        charts.iter().map(|chart| chart.width * chart.height).sum();
+    let mut scale = if total_area > 0. {
+        total_area.sqrt().recip()
+    } else {
+        1.
+    };
+
+    let positions = loop {
+        if let Some(positions) = pack_shelves(&charts, scale, padding) {
+            break positions;
+        }
+        scale *= RESCALE_FACTOR;
+    };
+
+    let mut uvs = vec![[[0.; 2]; 3]; triangles.len()];
+    let mut atlas_charts = Vec::new();
+
+    for (chart, [x, y]) in charts.into_iter().zip(positions) {
+        for (&triangle, points) in chart.triangles.iter().zip(&chart.points) {
+            uvs[triangle] = points
+                .map(|[u, v]| [(x + u * scale) as f32, (y + v * scale) as f32]);
+        }
+
+        atlas_charts.push(AtlasChart {
+            face: chart.face,
+            min: [x as f32, y as f32],
+            max: [
+                (x + chart.width * scale) as f32,
+                (y + chart.height * scale) as f32,
+            ],
+            area: chart.area,
+        });
+    }
+
+    UvAtlas {
+        uvs,
+        charts: atlas_charts,
+    }
+}
+
+/// Texture coordinates for a mesh, packed into a single atlas
+///
+/// Created by [`pack_uv_atlas`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UvAtlas {
+    uvs: Vec<[[f32; 2]; 3]>,
+    charts: Vec<AtlasChart>,
+}
+
+impl UvAtlas {
+    /// Access the texture coordinates of each triangle
+    ///
+    /// The triangles are in the order of [`Mesh::triangles`].
+    pub fn uvs(&self) -> &[[[f32; 2]; 3]] {
+        &self.uvs
+    }
+
+    /// Access the charts, and where they are in the atlas
+    pub fn charts(&self) -> &[AtlasChart] {
+        &self.charts
+    }
+}
+
+/// A chart in a texture atlas
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AtlasChart {
+    /// The face that the chart belongs to, if any
+    pub face: Option<usize>,
+
+    /// The minimum corner of the chart's rectangle in the atlas
+    pub min: [f32; 2],
+
+    /// The maximum corner of the chart's rectangle in the atlas
+    pub max: [f32; 2],
+
+    /// The surface area of the chart's triangles in the model
+    pub area: f64,
+}
+
+struct Chart {
+    face: Option<usize>,
+    triangles: Vec<usize>,
+
+    /// The projected points of each triangle, relative to the bounding box
+    points: Vec<[[f64; 2]; 3]>,
+
+    width: f64,
+    height: f64,
+    area: f64,
+}
+
+impl Chart {
+    fn new(
+        face: Option<usize>,
+        triangles: Vec<usize>,
+        all_triangles: &[Triangle],
+    ) -> Self {
+        let normals: Vec<_> = triangles
+            .iter()
+            .map(|&i| {
+                let [a, b, c] = all_triangles[i].points;
+                (b - a).cross(&(c - a))
+            })
+            .collect();
+
+        let area = normals
+            .iter()
+            .map(|normal| normal.magnitude().into_f64() / 2.)
+            .sum();
+
+        // For curved faces, the normals might cancel each other out. Then
+        // any of them is as good as the others.
+        let sum = normals
+            .iter()
+            .fold(Vector::from([0., 0., 0.]), |sum, &normal| sum + normal);
+        let normal = if sum.magnitude() > Scalar::from_f64(1e-12) {
+            sum.normalize()
+        } else {
+            normals
+                .first()
+                .map_or(Vector::unit_z(), |normal| normal.normalize())
+        };
+        let [u, v] = plane_axes(normal);
+
+        let mut points: Vec<_> = triangles
+            .iter()
+            .map(|&i| {
+                all_triangles[i].points.map(|point| {
+                    let point = point.coords;
+                    [u.dot(&point).into_f64(), v.dot(&point).into_f64()]
+                })
+            })
+            .collect();
+
+        let mut min = [f64::INFINITY; 2];
+        let mut max = [f64::NEG_INFINITY; 2];
+        for &[x, y] in points.iter().flatten() {
+            min = [min[0].min(x), min[1].min(y)];
+            max = [max[0].max(x), max[1].max(y)];
+        }
+        for point in points.iter_mut().flatten() {
+            *point = [point[0] - min[0], point[1] - min[1]];
+        }
+
+        Self {
+            face,
+            triangles,
+            points,
+            width: (max[0] - min[0]).max(0.),
+            height: (max[1] - min[1]).max(0.),
+            area,
+        }
+    }
+}
+
+/// Two orthonormal axes of the plane with the given normal
+fn plane_axes(normal: Vector<3>) -> [Vector<3>; 2] {
+    // Crossing with the coordinate axis that is least aligned with the normal
+    // is well-conditioned, and keeps the axes of axis-aligned faces
+    // axis-aligned.
+    let [x, y, z] = normal.components.map(Scalar::abs);
+    let reference = if x <= y && x <= z {
+        Vector::unit_x()
+    } else if y <= z {
+        Vector::unit_y()
+    } else {
+        Vector::unit_z()
+    };
+
+    let u = reference.cross(&normal).normalize();
+    let v = normal.cross(&u);
+
+    [u, v]
+}
+
+/// Pack the charts into shelves, from the tallest to the shortest
+///
+/// Returns the position of each chart, or `None`, if they don't fit.
+fn pack_shelves(
+    charts: &[Chart],
+    scale: f64,
+    padding: f64,
+) -> Option<Vec<[f64; 2]>> {
+    let mut order: Vec<_> = (0..charts.len()).collect();
+    order.sort_by(|&a, &b| charts[b].height.total_cmp(&charts[a].height));
+
+    let mut positions = vec![[0.; 2]; charts.len()];
+
+    let mut x = padding;
+    let mut y = padding;
+    let mut shelf_height: f64 = 0.;
+
+    for i in order {
+        let [width, height] =
+            [charts[i].width, charts[i].height].map(|size| size * scale);
+
+        if x + width + padding > 1. {
+            x = padding;
+            y += shelf_height + padding;
+            shelf_height = 0.;
+        }
+        if x + width + padding > 1. || y + height + padding > 1. {
+            return None;
+        }
+
+        positions[i] = [x, y];
+        x += width + padding;
+        shelf_height = shelf_height.max(height);
+    }
+
+    Some(positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Point;
+
+    use crate::mesh::Mesh;
+
+    use super::pack_uv_atlas;
+
+    #[test]
+    fn cuboid() {
+        let padding = 0.01;
+
+        let mesh = cuboid_mesh([2., 1., 1.]);
+        let atlas = pack_uv_atlas(&mesh, padding);
+
+        let charts = atlas.charts();
+        assert_eq!(charts.len(), 6);
+
+        let area = |[min, max]: [[f32; 2]; 2]| {
+            f64::from(max[0] - min[0]) * f64::from(max[1] - min[1])
+        };
+
+        for (i, a) in charts.iter().enumerate() {
+            for b in &charts[i + 1..] {
+                let is_apart = (0..2).any(|axis| {
+                    a.max[axis] + padding <= b.min[axis] + 1e-6
+                        || b.max[axis] + padding <= a.min[axis] + 1e-6
+                });
+                assert!(is_apart, "Charts overlap: {a:?}, {b:?}");
+            }
+        }
+
+        let covered: f64 = charts
+            .iter()
+            .map(|chart| area([chart.min, chart.max]))
+            .sum();
+        assert!(covered > 0.4 && covered < 1., "Coverage is {covered}");
+
+        let total_area: f64 = charts.iter().map(|chart| chart.area).sum();
+        for chart in charts {
+            let fraction = area([chart.min, chart.max]) / covered;
+            let expected = chart.area / total_area;
+            assert!((fraction / expected - 1.).abs() < 0.1);
+        }
+
+        // Each triangle is in the rectangle of its chart.
+        for (triangle, uvs) in mesh.triangles().zip(atlas.uvs()) {
+            let chart = charts
+                .iter()
+                .find(|chart| chart.face == triangle.face)
+                .unwrap();
+            for uv in uvs {
+                for ((uv, min), max) in uv.iter().zip(chart.min).zip(chart.max)
+                {
+                    assert!(*uv >= min - 1e-6);
+                    assert!(*uv <= max + 1e-6);
+                }
+            }
+        }
+    }
+
+    fn cuboid_mesh([x, y, z]: [f64; 3]) -> Mesh<Point<3>> {
+        let corner = |[i, j, k]: [f64; 3]| Point::from([i * x, j * y, k * z]);
+
+        let faces = [
+            [[0., 0., 0.], [0., 1., 0.], [1., 1., 0.], [1., 0., 0.]],
+            [[0., 0., 1.], [1., 0., 1.], [1., 1., 1.], [0., 1., 1.]],
+            [[0., 0., 0.], [1., 0., 0.], [1., 0., 1.], [0., 0., 1.]],
+            [[0., 1., 0.], [0., 1., 1.], [1., 1., 1.], [1., 1., 0.]],
+            [[0., 0., 0.], [0., 0., 1.], [0., 1., 1.], [0., 1., 0.]],
+            [[1., 0., 0.], [1., 1., 0.], [1., 1., 1.], [1., 0., 1.]],
+        ];
+
+        let mut mesh = Mesh::new();
+        for (face, [a, b, c, d]) in faces.into_iter().enumerate() {
+            let [a, b, c, d] = [a, b, c, d].map(corner);
+            mesh.push_face_triangle([a, b, c], [255, 0, 0, 255], face);
+            mesh.push_face_triangle([a, c, d], [255, 0, 0, 255], face);
+        }
+
+        mesh
+    }
+}
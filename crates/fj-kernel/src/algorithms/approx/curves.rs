@@ -1,6 +1,6 @@
 use std::cmp::max;
 
-use fj_math::{Circle, Point, Scalar};
+use fj_math::{Circle, Ellipse, Point, Polyline, Scalar};
 
 use crate::{local::Local, objects::Curve};
 
@@ -11,15 +11,8 @@ use super::Tolerance;
 /// `tolerance` defines how far the approximation is allowed to deviate from the
 /// actual edge.
 ///
-/// # Implementation Note
-///
-/// This only works as it is, because edges are severely limited and don't
-/// define which section of the curve they inhabit. Once they do that, we need
-/// an `approximate_between(a, b)` method instead, where `a` and `b` are the
-/// vertices that bound the edge on the curve.
-///
-/// The `approximate_between` methods of the curves then need to make sure to
-/// only return points in between those vertices, not the vertices themselves.
+/// This approximates the whole curve. Use [`approx_curve_between`] for edges
+/// that only cover a section of it.
 pub fn approx_curve(
     curve: &Curve<3>,
     tolerance: Tolerance,
@@ -27,7 +20,77 @@ pub fn approx_curve(
 ) {
     match curve {
         Curve::Circle(curve) => approx_circle(curve, tolerance, out),
+        Curve::Ellipse(curve) => approx_ellipse(curve, tolerance, out),
         Curve::Line(_) => {}
+        Curve::Polyline(curve) => approx_polyline(curve, out),
+    }
+}
+
+/// Compute an approximation of the section of a curve between two points
+///
+/// `range` holds the curve coordinates of the vertices that bound the section,
+/// in ascending order. Only points strictly between them are returned, as the
+/// vertices themselves are added by [`approx_edge`].
+///
+/// Circles and ellipses are periodic, so a section of them can start anywhere,
+/// including at negative curve coordinates, as is the case for reversed arcs.
+/// Their points are shifted by whole turns into the range, if possible.
+///
+/// [`approx_edge`]: super::approx_edge
+pub fn approx_curve_between(
+    curve: &Curve<3>,
+    range: [Point<1>; 2],
+    tolerance: Tolerance,
+    out: &mut Vec<Local<Point<1>>>,
+) {
+    let [min, max] = range.map(|point| point.t);
+
+    let period = match curve {
+        Curve::Circle(_) | Curve::Ellipse(_) => Some(Scalar::PI * 2.),
+        Curve::Line(_) | Curve::Polyline(_) => None,
+    };
+
+    let mut points = Vec::new();
+    approx_curve(curve, tolerance, &mut points);
+
+    let mut points: Vec<_> = points
+        .into_iter()
+        .map(|point| {
+            let t = point.local_form().t;
+            let t = match period {
+                Some(period) => {
+                    let turns = ((t - min) / period).into_f64().floor();
+                    t - period * turns
+                }
+                None => t,
+            };
+
+            Local::new([t], *point.global_form())
+        })
+        .filter(|point: &Local<Point<1>>| {
+            let t = point.local_form().t;
+            min < t && t < max
+        })
+        .collect();
+    points.sort_by_key(|point| point.local_form().t);
+
+    out.extend(points);
+}
+
+/// Approximate the polyline
+///
+/// A polyline is its own approximation, so this just returns its points,
+/// regardless of the tolerance. The first and last point are left out, as
+/// they are the vertices of the edge, unless the polyline is closed. Then the
+/// edge has no vertices, and only the last point (which repeats the first) is
+/// left out.
+pub fn approx_polyline(polyline: &Polyline<3>, out: &mut Vec<Local<Point<1>>>) {
+    let points = polyline.points().iter().zip(polyline.knots());
+    let num_points = polyline.points().len();
+
+    let skip = if polyline.is_closed() { 0 } else { 1 };
+    for (&point, &knot) in points.skip(skip).take(num_points - 1 - skip) {
+        out.push(Local::new([knot], point));
     }
 }
 
@@ -50,14 +113,229 @@ pub fn approx_circle(
 
     let n = number_of_vertices_for_circle(tolerance, radius);
 
+    // The points in the second half of the circle mirror those in the first
+    // half. They are computed from the same sines and cosines, so a reversed
+    // circle is approximated by exactly the same points, regardless of
+    // floating point accuracy.
+    let step = Scalar::PI * 2. / n as f64;
     for i in 0..n {
-        let angle = Scalar::PI * 2. / n as f64 * i as f64;
-        let point = circle.point_from_circle_coords([angle]);
+        let mirrored = i.min(n - i);
+        let (mut sin, cos) = (step * mirrored as f64).sin_cos();
+        if mirrored != i {
+            sin = -sin;
+        }
+
+        let angle = step * i as f64;
+        let point = circle.center + circle.a * cos + circle.b * sin;
         out.push(Local::new([angle], point));
     }
 }
 
-fn number_of_vertices_for_circle(tolerance: Tolerance, radius: Scalar) -> u64 {
+/// Approximate the ellipse
+///
+/// `tolerance` specifies how much the approximation is allowed to deviate
+/// from the ellipse.
+///
+/// Unlike a circle, an ellipse is approximated with steps of varying length.
+/// Each step is chosen such that an arc with the smallest radius of curvature
+/// along the step would stay within the tolerance. This is more conservative
+/// than required, but places more points where the ellipse curves sharply.
+///
+/// The steps are chosen for one quarter of the ellipse, and mirrored to the
+/// others. That way, an ellipse with one of its axes reversed is approximated
+/// with the same points, which keeps the approximations of faces that share
+/// an elliptic edge in opposite directions in sync.
+pub fn approx_ellipse(
+    ellipse: &Ellipse<3>,
+    tolerance: Tolerance,
+    out: &mut Vec<Local<Point<1>>>,
+) {
+    let quarter = Scalar::PI / 2.;
+
+    let mut steps = Vec::new();
+    let mut t = Scalar::ZERO;
+
+    while t < quarter {
+        steps.push(t);
+
+        // The radius of curvature changes along the step, so the step is
+        // refined until it holds for its midpoint and end too.
+        let mut step = step_for_radius(ellipse, t, tolerance, quarter);
+        for _ in 0..8 {
+            let refined = [t + step / 2., t + step]
+                .into_iter()
+                .map(|s| step_for_radius(ellipse, s, tolerance, quarter))
+                .fold(step, |a, b| if b < a { b } else { a });
+
+            if refined >= step {
+                break;
+            }
+            step = refined;
+        }
+
+        t += step;
+    }
+
+    // The last step is cut short, to end exactly at the end of the quarter.
+    // The other quarters mirror the first one. Their points are computed from
+    // the same sines and cosines, with flipped signs, so they are exact mirror
+    // images, regardless of floating point accuracy.
+    let mut quarter_points: Vec<_> = steps
+        .into_iter()
+        .map(|t| {
+            let (sin, cos) = t.sin_cos();
+            (t, sin, cos)
+        })
+        .collect();
+    quarter_points.push((quarter, Scalar::ONE, Scalar::ZERO));
+
+    let mut half = quarter_points.clone();
+    half.extend(
+        quarter_points
+            .iter()
+            .rev()
+            .skip(1)
+            .map(|&(t, sin, cos)| (Scalar::PI - t, sin, -cos)),
+    );
+
+    let second_half = half[1..half.len() - 1]
+        .iter()
+        .map(|&(t, sin, cos)| (Scalar::PI + t, -sin, -cos));
+    let points = half.iter().copied().chain(second_half);
+
+    out.extend(points.map(|(t, sin, cos)| {
+        let point = ellipse.center + ellipse.a * cos + ellipse.b * sin;
+        Local::new([t], point)
+    }));
+}
+
+fn step_for_radius(
+    ellipse: &Ellipse<3>,
+    t: Scalar,
+    tolerance: Tolerance,
+    max_step: Scalar,
+) -> Scalar {
+    let radius = ellipse.radius_of_curvature([t]);
+    let speed = ellipse.tangent_at([t]).magnitude();
+
+    // The deviation of a chord from an arc is `r * (1 - cos(angle / 2))`.
+    let ratio = tolerance.inner() / radius;
+    if ratio >= Scalar::ONE {
+        return max_step;
+    }
+    let angle = (Scalar::ONE - ratio).acos() * 2.;
+
+    let step = radius * angle / speed;
+    if step < max_step {
+        step
+    } else {
+        max_step
+    }
+}
+
+/// Refine an approximation of a curve to a finer tolerance
+///
+/// `points` is an approximation of a section of the curve, as produced by
+/// [`approx_curve_between`] or [`approx_curve`], including the vertices that
+/// bound the section. All of them are kept, and points are only inserted
+/// between those whose segment deviates from the curve by more than
+/// `tolerance`. The result is a superset of `points`, in the same order.
+///
+/// The deviation of a circle's segment follows from its radius. For ellipses,
+/// the midpoint of each segment is evaluated. `evaluations` is incremented by
+/// the number of points on the curve that had to be computed.
+pub fn refine_curve_approx(
+    curve: &Curve<3>,
+    points: &[Local<Point<1>>],
+    tolerance: Tolerance,
+    evaluations: &mut u64,
+) -> Vec<Local<Point<1>>> {
+    // The segments of lines and polylines don't deviate from them.
+    let period = match curve {
+        Curve::Circle(_) | Curve::Ellipse(_) => Scalar::PI * 2.,
+        Curve::Line(_) | Curve::Polyline(_) => return points.to_vec(),
+    };
+
+    let mut refined = Vec::new();
+
+    for segment in points.windows(2) {
+        let [a, b] = [segment[0], segment[1]];
+        refined.push(a);
+
+        // The last point of a closed curve repeats the first one, so the
+        // segment that closes the curve wraps around.
+        let t = a.local_form().t;
+        let mut step = b.local_form().t - t;
+        if step <= Scalar::ZERO {
+            step += period;
+        }
+
+        let num_steps = match curve {
+            Curve::Circle(circle) => {
+                let radius = circle.a.magnitude();
+                let steps_per_turn =
+                    number_of_vertices_for_circle(tolerance, radius);
+                let num_steps = step / period * steps_per_turn as f64;
+
+                // Allow for rounding errors in the curve coordinates, which
+                // would otherwise split segments that are just short enough.
+                (num_steps - Scalar::from_f64(1e-9)).ceil().into_u64()
+            }
+            _ => {
+                let midpoint = curve.point_from_curve_coords([t + step / 2.]);
+                *evaluations += 1;
+
+                // The deviation of a chord from a curve grows with the square
+                // of its length.
+                let chord = *a.global_form()
+                    + (*b.global_form() - *a.global_form()) / 2.;
+                let deviation = (midpoint - chord).magnitude();
+                (deviation / tolerance.inner()).sqrt().ceil().into_u64()
+            }
+        };
+
+        for i in 1..num_steps {
+            let t = t + step / num_steps as f64 * i as f64;
+            refined.push(Local::new([t], curve.point_from_curve_coords([t])));
+            *evaluations += 1;
+        }
+    }
+    refined.extend(points.last().copied());
+
+    refined
+}
+
+/// Estimate the number of points that [`approx_curve`] produces
+///
+/// This is exact for circles, lines, and polylines. For ellipses, it's the
+/// number of points for a circle with the larger semi-axis as its radius,
+/// which is an upper bound, within reason.
+pub(crate) fn estimate_num_points(
+    curve: &Curve<3>,
+    tolerance: Tolerance,
+) -> u64 {
+    match curve {
+        Curve::Circle(curve) => {
+            number_of_vertices_for_circle(tolerance, curve.a.magnitude())
+        }
+        Curve::Ellipse(curve) => {
+            let radius = curve.a.magnitude().max(curve.b.magnitude());
+            number_of_vertices_for_circle(tolerance, radius)
+        }
+        Curve::Line(_) => 0,
+        Curve::Polyline(curve) => curve.points().len() as u64,
+    }
+}
+
+pub(crate) fn number_of_vertices_for_circle(
+    tolerance: Tolerance,
+    radius: Scalar,
+) -> u64 {
+    // A triangle is the coarsest approximation there is.
+    if tolerance.inner() >= radius {
+        return 3;
+    }
+
     let n = (Scalar::PI / (Scalar::ONE - (tolerance.inner() / radius)).acos())
         .ceil()
         .into_u64();
@@ -1,8 +1,17 @@
-use fj_math::{Point, Segment};
+use std::cmp::Ordering;
 
-use crate::{local::Local, objects::Cycle};
+use fj_math::{Point, Scalar, Segment, Winding};
 
-use super::{curves::approx_curve, edges::approx_edge, Tolerance};
+use crate::{
+    local::Local,
+    objects::{Cycle, Edge},
+};
+
+use super::{
+    curves::{approx_curve, approx_curve_between},
+    edges::approx_edge,
+    Tolerance,
+};
 
 /// An approximation of a [`Cycle`]
 #[derive(Debug, Eq, PartialEq, Hash)]
@@ -17,17 +26,26 @@ impl CycleApprox {
     /// `tolerance` defines how far the approximation is allowed to deviate from
     /// the actual face.
     pub fn new(cycle: &Cycle, tolerance: Tolerance) -> Self {
-        let mut points = Vec::new();
+        let edge_points = cycle
+            .edges
+            .iter()
+            .map(|edge| approx_edge_points(edge, tolerance))
+            .collect();
 
-        for edge in &cycle.edges {
-            let mut edge_points = Vec::new();
-            approx_curve(
-                edge.curve().global_form(),
-                tolerance,
-                &mut edge_points,
-            );
-            approx_edge(*edge.vertices(), &mut edge_points);
+        Self::from_edge_points(cycle, edge_points)
+    }
+
+    /// Create the approximation of a cycle from approximations of its edges
+    ///
+    /// `edge_points` holds the approximation of each edge of the cycle, in
+    /// curve coordinates, as produced by [`approx_edge_points`].
+    pub(crate) fn from_edge_points(
+        cycle: &Cycle,
+        edge_points: Vec<Vec<Local<Point<1>>>>,
+    ) -> Self {
+        let mut points = Vec::new();
 
+        for (edge, edge_points) in cycle.edges.iter().zip(edge_points) {
             points.extend(edge_points.into_iter().map(|point| {
                 let local = edge
                     .curve()
@@ -41,9 +59,39 @@ impl CycleApprox {
         // could lead to subtly different surface coordinates.
         points.dedup_by(|a, b| a.global_form() == b.global_form());
 
+        // For the same reason, the point that closes the cycle could differ
+        // from the one that starts it. Both must be the same point, or the face
+        // ends up with two distinct, but very close, points.
+        if let Some(&first) = points.first() {
+            if let Some(last) = points.last_mut() {
+                if last.global_form() == first.global_form() {
+                    *last = first;
+                }
+            }
+        }
+
         Self { points }
     }
 
+    /// Determine the winding direction of the cycle, in surface coordinates
+    ///
+    /// Returns `None`, if the approximation doesn't enclose any area.
+    pub fn winding(&self) -> Option<Winding> {
+        // Twice the signed area of the polygon, using the shoelace formula.
+        let mut area = Scalar::ZERO;
+        for (i, a) in self.points.iter().enumerate() {
+            let b = self.points[(i + 1) % self.points.len()];
+            let [a, b] = [a, &b].map(|point| point.local_form());
+            area += a.u * b.v - b.u * a.v;
+        }
+
+        match area.cmp(&Scalar::ZERO) {
+            Ordering::Greater => Some(Winding::Ccw),
+            Ordering::Less => Some(Winding::Cw),
+            Ordering::Equal => None,
+        }
+    }
+
     /// Construct the segments that approximate the cycle
     pub fn segments(&self) -> Vec<Segment<3>> {
         let mut segments = Vec::new();
@@ -60,3 +108,28 @@ impl CycleApprox {
         segments
     }
 }
+
+/// Compute the approximation of an edge, in curve coordinates
+///
+/// Includes the vertices of the edge. If the edge has no vertices, the first
+/// point is repeated at the end, to close it.
+pub(crate) fn approx_edge_points(
+    edge: &Edge,
+    tolerance: Tolerance,
+) -> Vec<Local<Point<1>>> {
+    let mut points = Vec::new();
+    match edge.vertices().get() {
+        Some(vertices) => approx_curve_between(
+            edge.curve().global_form(),
+            vertices.map(|vertex| vertex.position()),
+            tolerance,
+            &mut points,
+        ),
+        None => {
+            approx_curve(edge.curve().global_form(), tolerance, &mut points)
+        }
+    }
+    approx_edge(*edge.vertices(), &mut points);
+
+    points
+}
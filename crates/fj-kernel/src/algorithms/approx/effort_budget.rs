@@ -0,0 +1,66 @@
+use std::time::Instant;
+
+/// Limits the effort that is spent on approximating a shape
+///
+/// Some shapes need a huge number of points to be approximated within the
+/// requested tolerance, for example a circle with a large radius and a tiny
+/// tolerance. Triangulating them could take minutes.
+///
+/// If a limit is reached, faces are approximated with a coarser tolerance
+/// instead, which is recorded in the resulting mesh (see
+/// [`Mesh::degraded_faces`]). This means the result is always complete, if
+/// partially coarse.
+///
+/// The default budget has no limits. Code that must meet the requested
+/// tolerance, like export, must not set any.
+///
+/// [`Mesh::degraded_faces`]: fj_interop::mesh::Mesh::degraded_faces
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct EffortBudget {
+    /// The maximum number of points that approximate a single face
+    ///
+    /// Faces that would need more points are approximated with the finest
+    /// tolerance that stays within this limit, as far as it can be estimated.
+    pub max_points_per_face: Option<usize>,
+
+    /// The maximum number of triangles in the mesh
+    ///
+    /// Once the mesh has reached this number, any remaining faces are
+    /// approximated as coarsely as possible.
+    pub max_total_triangles: Option<usize>,
+
+    /// The point in time at which approximation should be finished
+    ///
+    /// Once it has passed, any remaining faces are approximated as coarsely
+    /// as possible. The face that is being approximated at that point is
+    /// still finished normally.
+    pub deadline: Option<Instant>,
+}
+
+impl EffortBudget {
+    /// Construct an instance of `EffortBudget` that has no limits
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// Determine whether the budget has no limits
+    pub fn is_unlimited(&self) -> bool {
+        *self == Self::unlimited()
+    }
+
+    /// Determine whether approximation has run out of budget
+    ///
+    /// `num_triangles` is the number of triangles created so far.
+    pub fn is_exhausted(&self, num_triangles: usize) -> bool {
+        let triangles_exhausted = self
+            .max_total_triangles
+            .map(|max| num_triangles >= max)
+            .unwrap_or(false);
+        let time_exhausted = self
+            .deadline
+            .map(|deadline| Instant::now() >= deadline)
+            .unwrap_or(false);
+
+        triangles_exhausted || time_exhausted
+    }
+}
@@ -0,0 +1,142 @@
+use fj_math::Scalar;
+
+use super::Tolerance;
+
+/// Positional error accumulated while computing a shape
+///
+/// Operations like transforms, booleans, or healing can introduce small
+/// positional errors into a shape. Each of them might stay within the tolerance
+/// locally, but if several of them are chained, the final approximation of the
+/// shape can end up violating the tolerance that was originally requested.
+///
+/// Operations record the worst-case error they introduce into the error
+/// budget, which is carried alongside the shape as it's computed. Before
+/// approximating the shape, the accumulated error is subtracted from the
+/// requested tolerance (see [`ErrorBudget::remaining_tolerance`]), so the
+/// result still meets the original bound.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ErrorBudget {
+    contributions: Vec<ErrorContribution>,
+}
+
+impl ErrorBudget {
+    /// Construct an empty instance of `ErrorBudget`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the worst-case error introduced by an operation
+    ///
+    /// `stage` names the operation, for use in error messages.
+    pub fn record(&mut self, stage: &'static str, error: impl Into<Scalar>) {
+        let error = error.into();
+
+        self.contributions.push(ErrorContribution { stage, error });
+    }
+
+    /// Access the errors recorded so far, in order of recording
+    pub fn contributions(&self) -> impl Iterator<Item = &ErrorContribution> {
+        self.contributions.iter()
+    }
+
+    /// Compute the accumulated worst-case error
+    pub fn total(&self) -> Scalar {
+        self.contributions
+            .iter()
+            .fold(Scalar::ZERO, |total, contribution| {
+                total + contribution.error
+            })
+    }
+
+    /// Compute the tolerance that is left after subtracting the budget
+    ///
+    /// Returns an error, if the accumulated error already exhausts the
+    /// tolerance. The error names the stage at which that happened.
+    pub fn remaining_tolerance(
+        &self,
+        tolerance: Tolerance,
+    ) -> Result<Tolerance, ErrorBudgetExceeded> {
+        let mut total = Scalar::ZERO;
+
+        for contribution in &self.contributions {
+            total += contribution.error;
+
+            if total >= tolerance.inner() {
+                return Err(ErrorBudgetExceeded {
+                    stage: contribution.stage,
+                    accumulated_error: total,
+                    tolerance,
+                });
+            }
+        }
+
+        // Can't fail, as we just checked that `total` is less than the
+        // tolerance.
+        Ok(Tolerance::from_scalar(tolerance.inner() - total)
+            .expect("Remaining tolerance must be larger than zero"))
+    }
+}
+
+/// The worst-case error introduced by an operation
+///
+/// See [`ErrorBudget`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ErrorContribution {
+    /// The name of the operation that introduced the error
+    pub stage: &'static str,
+
+    /// The worst-case positional error introduced by the operation
+    pub error: Scalar,
+}
+
+/// Error budget exceeds the requested tolerance
+///
+/// Returned by [`ErrorBudget::remaining_tolerance`].
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "Error budget exceeded by stage `{stage}`: accumulated error \
+    ({accumulated_error}) exhausts tolerance ({tolerance:?})"
+)]
+pub struct ErrorBudgetExceeded {
+    /// The stage that exhausted the error budget
+    pub stage: &'static str,
+
+    /// The error accumulated up to and including that stage
+    pub accumulated_error: Scalar,
+
+    /// The tolerance that was exhausted
+    pub tolerance: Tolerance,
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Scalar;
+
+    use crate::algorithms::Tolerance;
+
+    use super::ErrorBudget;
+
+    #[test]
+    fn remaining_tolerance() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(1.)?;
+
+        let mut budget = ErrorBudget::new();
+        assert_eq!(budget.remaining_tolerance(tolerance)?, tolerance);
+
+        budget.record("transform", 0.25);
+        budget.record("healing", 0.25);
+        assert_eq!(budget.total(), Scalar::from_f64(0.5));
+        assert_eq!(
+            budget.remaining_tolerance(tolerance)?,
+            Tolerance::from_scalar(0.5)?,
+        );
+
+        budget.record("boolean", 0.5);
+        let err = budget
+            .remaining_tolerance(tolerance)
+            .expect_err("Budget should be exhausted");
+        assert_eq!(err.stage, "boolean");
+
+        Ok(())
+    }
+}
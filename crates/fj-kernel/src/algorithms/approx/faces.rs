@@ -1,10 +1,13 @@
 use std::collections::HashSet;
 
-use fj_math::Point;
+use fj_math::{Point, Scalar};
 
 use crate::{local::Local, objects::Face};
 
-use super::{CycleApprox, Tolerance};
+use super::{
+    curves::estimate_num_points as estimate_num_points_for_curve,
+    seam::cut_at_seam, CycleApprox, Tolerance,
+};
 
 /// An approximation of a [`Face`]
 #[derive(Debug, PartialEq)]
@@ -41,22 +44,41 @@ impl FaceApprox {
         // would need to provide its own approximation, as the edges that bound
         // it have nothing to do with its curvature.
 
-        let mut points = HashSet::new();
-        let mut exteriors = Vec::new();
-        let mut interiors = HashSet::new();
+        Self::from_cycles(
+            face,
+            face.exteriors()
+                .map(|cycle| CycleApprox::new(cycle, tolerance))
+                .collect(),
+            face.interiors()
+                .map(|cycle| CycleApprox::new(cycle, tolerance))
+                .collect(),
+        )
+    }
 
-        for cycle in face.exteriors() {
-            let cycle = CycleApprox::new(cycle, tolerance);
+    /// Create the approximation of a face from approximations of its cycles
+    ///
+    /// If the face is on a periodic surface, its cycles are cut open along the
+    /// seam (see [`Face::seam`]), so they bound the face in a single strip of
+    /// surface coordinates.
+    pub(crate) fn from_cycles(
+        face: &Face,
+        exteriors: Vec<CycleApprox>,
+        interiors: Vec<CycleApprox>,
+    ) -> Self {
+        let surface = face.surface();
+        let (mut exteriors, interiors) = match (surface.period(), face.seam()) {
+            (Some(period), Some(seam)) => {
+                cut_at_seam(surface, seam, period, exteriors, interiors)
+            }
+            _ => (exteriors, interiors),
+        };
 
-            points.extend(cycle.points.iter().copied());
-            exteriors.push(cycle);
-        }
-        for cycle in face.interiors() {
-            let cycle = CycleApprox::new(cycle, tolerance);
-
-            points.extend(cycle.points.iter().copied());
-            interiors.insert(cycle);
-        }
+        let points = exteriors
+            .iter()
+            .chain(&interiors)
+            .flat_map(|cycle| cycle.points.iter().copied())
+            .collect();
+        let interiors = interiors.into_iter().collect();
 
         // Only polygons with exactly one exterior cycle are supported.
         //
@@ -76,6 +98,78 @@ impl FaceApprox {
             interiors,
         }
     }
+
+    /// Compute the approximation of a face, limiting the number of points
+    ///
+    /// If approximating the face within `tolerance` would take more than
+    /// `max_points` points, the face is approximated with a coarser tolerance
+    /// instead. See [`FaceApprox::tolerance_for_max_points`].
+    ///
+    /// Returns the approximation and the tolerance that was actually used.
+    pub fn with_max_points(
+        face: &Face,
+        tolerance: Tolerance,
+        max_points: usize,
+    ) -> (Self, Tolerance) {
+        let tolerance =
+            Self::tolerance_for_max_points(face, tolerance, max_points);
+        (Self::new(face, tolerance), tolerance)
+    }
+
+    /// Find a tolerance that approximates a face with at most `max_points`
+    ///
+    /// Returns `tolerance`, if that is already sufficient. Otherwise, the
+    /// tolerance is coarsened until the estimated number of points fits. The
+    /// estimate is exact for circles, lines, and polylines, but conservative
+    /// for ellipses.
+    ///
+    /// Some faces can't be approximated with that few points, for example if
+    /// they are bounded by polylines. Then the tolerance is coarsened as far as
+    /// doing so reduces the number of points.
+    pub fn tolerance_for_max_points(
+        face: &Face,
+        tolerance: Tolerance,
+        max_points: usize,
+    ) -> Tolerance {
+        // The fewest points the face can be approximated with, no matter the
+        // tolerance.
+        let min_points = estimate_num_points(face, Scalar::MAX.into());
+        let max_points = (max_points as u64).max(min_points);
+
+        let mut tolerance = tolerance;
+
+        // The loop is bounded, to be safe. Each iteration coarsens the
+        // tolerance by at least half, which is plenty for any sane model.
+        for _ in 0..128 {
+            let num_points = estimate_num_points(face, tolerance);
+            if num_points <= max_points {
+                break;
+            }
+
+            // The number of points for a circle is roughly proportional to
+            // the inverse square root of the tolerance.
+            let ratio = num_points as f64 / max_points.max(1) as f64;
+            let factor = (ratio * ratio).max(1.5);
+
+            tolerance = Tolerance::from_scalar(tolerance.inner() * factor)
+                .expect("Coarsening a valid tolerance keeps it valid");
+        }
+
+        tolerance
+    }
+}
+
+/// Estimate the number of points that [`FaceApprox::new`] produces
+fn estimate_num_points(face: &Face, tolerance: Tolerance) -> u64 {
+    face.all_cycles()
+        .flat_map(|cycle| &cycle.edges)
+        .map(|edge| {
+            // Each edge shares its vertices with its neighbors, so only one of
+            // them is counted.
+            estimate_num_points_for_curve(edge.curve().global_form(), tolerance)
+                + 1
+        })
+        .sum()
 }
 
 #[cfg(test)]
@@ -1,11 +1,28 @@
 mod curves;
 mod cycles;
 mod edges;
+mod effort_budget;
+mod error_budget;
 mod faces;
+mod seam;
+mod store;
 mod tolerance;
 
+pub(crate) use self::{
+    curves::{
+        approx_curve, approx_curve_between,
+        estimate_num_points as estimate_num_points_for_curve,
+        number_of_vertices_for_circle, refine_curve_approx,
+    },
+    cycles::approx_edge_points,
+    edges::approx_edge,
+};
+
 pub use self::{
     cycles::CycleApprox,
+    effort_budget::EffortBudget,
+    error_budget::{ErrorBudget, ErrorBudgetExceeded, ErrorContribution},
     faces::FaceApprox,
+    store::{ApproxStore, ApproxStoreStats},
     tolerance::{InvalidTolerance, Tolerance},
 };
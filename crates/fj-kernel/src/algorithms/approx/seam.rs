@@ -0,0 +1,546 @@
+use std::collections::BTreeMap;
+
+use fj_math::{Point, Scalar};
+
+use crate::{local::Local, objects::Surface};
+
+use super::CycleApprox;
+
+/// Points that are closer to the seam than this, are considered to be on it
+///
+/// This is a distance in surface coordinates. It absorbs the floating point
+/// error of curve approximations, whose points are computed from angles that
+/// are supposed to, but don't quite, hit the seam.
+const SEAM_SNAP_DISTANCE: f64 = 1e-9;
+
+/// Cut the cycles of a face on a periodic surface open along its seam
+///
+/// The result covers the strip of surface coordinates, whose u-coordinates
+/// are between `seam` and `seam + period`. Cycles that cross the seam are split
+/// where they do. The points where that happens are duplicated, once at each
+/// side of the strip, sharing their global form. The pieces are then joined
+/// along the sides of the strip into new cycles, which the face can be
+/// triangulated from as if it were planar.
+///
+/// Cycles that don't cross the seam are only moved into the strip. If cycles
+/// cross the seam, the largest of the new cycles becomes the exterior, unless
+/// the exterior doesn't cross the seam. If no cycle wraps around the surface,
+/// the face isn't cut at all. Each cycle is only moved into the strip, starting
+/// at its first point, and might stick out of it.
+///
+/// Returns the cycles unchanged, if they can't be joined, which happens for
+/// cycles that are inconsistently oriented.
+pub fn cut_at_seam(
+    surface: &Surface,
+    seam: Scalar,
+    period: Scalar,
+    exteriors: Vec<CycleApprox>,
+    interiors: Vec<CycleApprox>,
+) -> (Vec<CycleApprox>, Vec<CycleApprox>) {
+    let strip = Strip { seam, period };
+
+    // Faces that don't wrap around the surface don't need to be cut open.
+    // Keeping them in one piece keeps their surface coordinates continuous.
+    if !exteriors
+        .iter()
+        .chain(&interiors)
+        .any(|cycle| strip.wraps(cycle))
+    {
+        let move_into_strip = |cycle: CycleApprox| {
+            strip.move_into_strip(unwrap(&cycle.points, period))
+        };
+        return (
+            exteriors.into_iter().map(move_into_strip).collect(),
+            interiors.into_iter().map(move_into_strip).collect(),
+        );
+    }
+
+    let mut closed_exteriors = Vec::new();
+    let mut closed_interiors = Vec::new();
+    let mut chains = Vec::new();
+    let mut exterior_crosses = false;
+
+    for (cycle, is_exterior) in exteriors
+        .iter()
+        .map(|cycle| (cycle, true))
+        .chain(interiors.iter().map(|cycle| (cycle, false)))
+    {
+        match strip.cut(surface, cycle) {
+            Cut::Closed(cycle) if is_exterior => closed_exteriors.push(cycle),
+            Cut::Closed(cycle) => closed_interiors.push(cycle),
+            Cut::Chains(cycle_chains) => {
+                exterior_crosses |= is_exterior;
+                chains.extend(cycle_chains);
+            }
+        }
+    }
+
+    if chains.is_empty() {
+        return (closed_exteriors, closed_interiors);
+    }
+
+    let mut joined = match strip.join(chains) {
+        Some(joined) => joined,
+        None => return (exteriors, interiors),
+    };
+    joined.sort_by(|a, b| area(b).total_cmp(&area(a)));
+
+    if exterior_crosses {
+        let exterior = joined.remove(0);
+        closed_exteriors.push(exterior);
+    }
+    closed_interiors.extend(joined);
+
+    (closed_exteriors, closed_interiors)
+}
+
+/// The strip of surface coordinates that a periodic surface is unrolled into
+struct Strip {
+    seam: Scalar,
+    period: Scalar,
+}
+
+impl Strip {
+    /// The number of periods between the seam and the u-coordinate
+    fn sheet(&self, u: Scalar) -> f64 {
+        ((u - self.seam) / self.period).into_f64().floor()
+    }
+
+    /// The u-coordinate of the seam closest to the given one, if it's close
+    fn snap(&self, u: Scalar) -> Option<Scalar> {
+        let sheet = ((u - self.seam) / self.period).into_f64().round();
+        let boundary = self.seam + self.period * sheet;
+
+        ((u - boundary).abs() < Scalar::from_f64(SEAM_SNAP_DISTANCE))
+            .then_some(boundary)
+    }
+
+    /// Determine whether a cycle wraps around the surface
+    fn wraps(&self, cycle: &CycleApprox) -> bool {
+        let points = unwrap(&cycle.points, self.period);
+        match (points.first(), points.last()) {
+            (Some(first), Some(last)) => {
+                let turns =
+                    (last.local_form().u - first.local_form().u) / self.period;
+                turns.round() != Scalar::ZERO
+            }
+            _ => false,
+        }
+    }
+
+    /// Split a cycle into chains that start and end at the seam
+    fn cut(&self, surface: &Surface, cycle: &CycleApprox) -> Cut {
+        let points = unwrap(&cycle.points, self.period);
+
+        // Move points on the seam onto it exactly, and add points where the
+        // cycle crosses it.
+        let mut marked: Vec<(Local<Point<2>>, bool)> = Vec::new();
+        for &point in &points {
+            let local = *point.local_form();
+            let snapped = self.snap(local.u);
+
+            if let Some(&(previous, false)) = marked.last() {
+                let previous = *previous.local_form();
+                let [sheet_a, sheet_b] =
+                    [previous.u, local.u].map(|u| self.sheet(u));
+
+                if snapped.is_none() && sheet_a != sheet_b {
+                    let u = self.seam + self.period * sheet_a.max(sheet_b);
+                    let v = previous.v
+                        + (local.v - previous.v)
+                            * ((u - previous.u) / (local.u - previous.u));
+
+                    let global = surface.point_from_surface_coords([u, v]);
+                    marked.push((Local::new([u, v], global), true));
+                }
+            }
+
+            match snapped {
+                Some(u) => marked.push((
+                    Local::new([u, local.v], *point.global_form()),
+                    true,
+                )),
+                None => marked.push((point, false)),
+            }
+        }
+
+        // The last point repeats the first one, but might be a whole number of
+        // periods away from it.
+        marked.pop();
+        let [first, last] = [points[0], points[points.len() - 1]]
+            .map(|point| point.local_form().u);
+        let shift = self.period * ((last - first) / self.period).round();
+
+        let start = match marked.iter().position(|&(_, on_seam)| on_seam) {
+            Some(start) => start,
+            None => {
+                let mut points: Vec<_> =
+                    marked.into_iter().map(|(point, _)| point).collect();
+                points.push(points[0]);
+                return Cut::Closed(self.move_into_strip(points));
+            }
+        };
+
+        // Start at the seam, and go around the cycle once.
+        let wrapped = marked[..=start].iter().map(|&(point, on_seam)| {
+            let local = *point.local_form();
+            let point: Local<Point<2>> =
+                Local::new([local.u + shift, local.v], *point.global_form());
+            (point, on_seam)
+        });
+        let around = marked[start..].iter().copied().chain(wrapped);
+
+        let mut chains = Vec::new();
+        let mut chain = Vec::new();
+        for (point, on_seam) in around {
+            chain.push(point);
+            if on_seam && chain.len() > 1 {
+                chains.push(self.move_chain_into_strip(chain));
+                chain = vec![point];
+            }
+        }
+
+        Cut::Chains(chains)
+    }
+
+    /// Move a cycle that doesn't cross the seam into the strip
+    fn move_into_strip(&self, points: Vec<Local<Point<2>>>) -> CycleApprox {
+        let sheet = self.sheet(points[0].local_form().u);
+        let points = points
+            .into_iter()
+            .map(|point| {
+                let local = *point.local_form();
+                Local::new(
+                    [local.u - self.period * sheet, local.v],
+                    *point.global_form(),
+                )
+            })
+            .collect();
+
+        CycleApprox { points }
+    }
+
+    /// Move a chain between two points on the seam into the strip
+    ///
+    /// Its first and last point end up exactly on the sides of the strip.
+    fn move_chain_into_strip(&self, points: Vec<Local<Point<2>>>) -> Chain {
+        let [a, b] = [points[0], points[1]].map(|point| point.local_form().u);
+        let sheet = self.sheet((a + b) / 2.);
+
+        let last = points.len() - 1;
+        let points = points
+            .into_iter()
+            .enumerate()
+            .map(|(i, point)| {
+                let local = *point.local_form();
+                let mut u = local.u - self.period * sheet;
+                if i == 0 || i == last {
+                    u = if (u - self.seam).abs()
+                        < (u - self.seam - self.period).abs()
+                    {
+                        self.seam
+                    } else {
+                        self.seam + self.period
+                    };
+                }
+
+                Local::new([u, local.v], *point.global_form())
+            })
+            .collect();
+
+        Chain { points }
+    }
+
+    /// Join chains into cycles, along the sides of the strip
+    ///
+    /// Where chains end at a side of the strip, the face is on one side of
+    /// them. Sorted along the side, the ends of the chains bound the sections
+    /// of the side that are part of the face. So the end of each chain is
+    /// joined to the start of its neighbor along the side.
+    fn join(&self, chains: Vec<Chain>) -> Option<Vec<CycleApprox>> {
+        // The ends of the chains on each side of the strip, by v-coordinate
+        let mut sides: BTreeMap<bool, Vec<(Scalar, usize, bool)>> =
+            BTreeMap::new();
+        for (index, chain) in chains.iter().enumerate() {
+            for (point, is_start) in [
+                (chain.points[0], true),
+                (chain.points[chain.points.len() - 1], false),
+            ] {
+                let local = point.local_form();
+                let is_right = local.u != self.seam;
+                sides
+                    .entry(is_right)
+                    .or_default()
+                    .push((local.v, index, is_start));
+            }
+        }
+
+        let mut next = BTreeMap::new();
+        for ends in sides.values_mut() {
+            ends.sort_by(|a, b| a.0.cmp(&b.0));
+            if ends.len() % 2 != 0 {
+                return None;
+            }
+
+            for pair in ends.chunks(2) {
+                match [(pair[0].1, pair[0].2), (pair[1].1, pair[1].2)] {
+                    [(end, false), (start, true)]
+                    | [(start, true), (end, false)] => {
+                        next.insert(end, start);
+                    }
+                    _ => return None,
+                }
+            }
+        }
+
+        let mut cycles = Vec::new();
+        let mut is_joined = vec![false; chains.len()];
+        for first in 0..chains.len() {
+            if is_joined[first] {
+                continue;
+            }
+
+            let mut points = Vec::new();
+            let mut index = first;
+            loop {
+                is_joined[index] = true;
+                points.extend(chains[index].points.iter().copied());
+
+                index = next[&index];
+                if index == first {
+                    break;
+                }
+                if is_joined[index] {
+                    return None;
+                }
+            }
+
+            points.dedup();
+            if points.first() != points.last() {
+                points.push(points[0]);
+            }
+            cycles.push(CycleApprox { points });
+        }
+
+        Some(cycles)
+    }
+}
+
+/// A cycle that has been cut at the seam
+enum Cut {
+    /// The cycle doesn't touch the seam, and has been moved into the strip
+    Closed(CycleApprox),
+
+    /// The pieces of the cycle between the points where it meets the seam
+    Chains(Vec<Chain>),
+}
+
+/// A piece of a cycle, that starts and ends at the seam
+struct Chain {
+    points: Vec<Local<Point<2>>>,
+}
+
+/// Remove the jumps from the u-coordinates of a cycle
+///
+/// Periodic curves might jump back by a period, when they wrap around. The
+/// u-coordinates are shifted by periods, so consecutive points are never more
+/// than half a period apart.
+fn unwrap(points: &[Local<Point<2>>], period: Scalar) -> Vec<Local<Point<2>>> {
+    let mut unwrapped: Vec<Local<Point<2>>> = Vec::new();
+
+    for &point in points {
+        let local = *point.local_form();
+        let u = match unwrapped.last() {
+            Some(previous) => {
+                let turns = ((previous.local_form().u - local.u) / period)
+                    .into_f64()
+                    .round();
+                local.u + period * turns
+            }
+            None => local.u,
+        };
+
+        unwrapped.push(Local::new([u, local.v], *point.global_form()));
+    }
+
+    unwrapped
+}
+
+/// The absolute area of a cycle, in surface coordinates
+fn area(cycle: &CycleApprox) -> f64 {
+    let mut area = Scalar::ZERO;
+    for (i, a) in cycle.points.iter().enumerate() {
+        let b = cycle.points[(i + 1) % cycle.points.len()];
+        let [a, b] = [a, &b].map(|point| point.local_form());
+        area += a.u * b.v - b.u * a.v;
+    }
+
+    area.abs().into_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use fj_interop::{debug::DebugInfo, mesh::Mesh};
+    use fj_math::{Circle, Line, Point, Scalar, Vector};
+
+    use crate::{
+        algorithms::{
+            approx::number_of_vertices_for_circle, triangulate, ApproxStore,
+            ErrorBudget, FaceApprox, Tolerance,
+        },
+        local::Local,
+        objects::{
+            Curve, Cycle, Edge, Face, Surface, SweptCurve, VerticesOfEdge,
+        },
+    };
+
+    #[test]
+    fn barrel_is_watertight() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.02)?;
+        let n = number_of_vertices_for_circle(tolerance, Scalar::ONE);
+
+        let mesh = triangulate_barrel(barrel(), tolerance)?;
+        assert_eq!(mesh.triangle_count(), 2 * n as usize);
+        assert_closed_except_rims(&mesh);
+
+        Ok(())
+    }
+
+    #[test]
+    fn rotated_seam() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.02)?;
+
+        // The approximation of the circles has to be symmetric under the
+        // rotation, for the meshes to be congruent.
+        let n = number_of_vertices_for_circle(tolerance, Scalar::ONE);
+        assert_eq!(n % 4, 0);
+
+        let mesh = triangulate_barrel(barrel(), tolerance)?;
+        let rotated =
+            triangulate_barrel(barrel().with_seam(Scalar::PI / 2.), tolerance)?;
+        assert_closed_except_rims(&rotated);
+
+        assert_eq!(mesh.triangle_count(), rotated.triangle_count());
+        for vertex in mesh.vertices() {
+            let vertex = Point::from([-vertex.y, vertex.x, vertex.z]);
+            assert!(rotated.vertices().any(|other| {
+                (other - vertex).magnitude() < Scalar::from_f64(1e-12)
+            }));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn uvs_are_continuous_except_at_seam() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.02)?;
+        let n = number_of_vertices_for_circle(tolerance, Scalar::ONE);
+        let step = Scalar::PI * 2. / n as f64;
+
+        let seam = Scalar::PI / 2.;
+        let face = barrel().with_seam(seam);
+        let approx = FaceApprox::new(&face, tolerance);
+
+        assert!(approx.interiors.is_empty());
+        let points = &approx.exterior.points;
+        assert_eq!(points.first(), points.last());
+
+        let sides = [seam, seam + Scalar::PI * 2.];
+        for pair in points.windows(2) {
+            let [a, b] = [pair[0], pair[1]].map(|point| *point.local_form());
+
+            let is_continuous = (b.u - a.u).abs() <= step * 1.001;
+            let is_at_seam = a.u == b.u && sides.contains(&a.u);
+            assert!(is_continuous || is_at_seam, "Jump from {a:?} to {b:?}");
+        }
+
+        // Points on the seam are duplicated, once on each side.
+        let mut by_global: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for point in &points[1..] {
+            by_global
+                .entry(*point.global_form())
+                .or_default()
+                .push(point.local_form().u);
+        }
+        for mut u in by_global.into_values() {
+            u.sort();
+            match u.as_slice() {
+                [u] => assert!(sides[0] < *u && *u < sides[1]),
+                us => assert_eq!(us, sides),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The barrel of a cylinder, whose rims are a single circle each
+    fn barrel() -> Face {
+        let circle = |z: f64, b: Vector<3>| Circle {
+            center: Point::from([0., 0., z]),
+            a: Vector::unit_x(),
+            b,
+        };
+        let surface = Surface::SweptCurve(SweptCurve {
+            curve: Curve::Circle(circle(0., Vector::unit_y())),
+            path: Vector::unit_z(),
+        });
+
+        // The bottom rim runs along the u-axis, the top rim back against it,
+        // so the face points outward.
+        let rim = |v: f64, direction: Vector<2>, global: Circle<3>| {
+            let local = Curve::Line(Line {
+                origin: Point::from([0., v]),
+                direction,
+            });
+            Cycle {
+                edges: vec![Edge::new(
+                    Local::new(local, Curve::Circle(global)),
+                    VerticesOfEdge::none(),
+                )],
+            }
+        };
+        let bottom = rim(0., Vector::unit_u(), circle(0., Vector::unit_y()));
+        let top = rim(1., -Vector::unit_u(), circle(1., -Vector::unit_y()));
+
+        Face::new(surface, [bottom], [top], [255, 0, 0, 255])
+    }
+
+    fn triangulate_barrel(
+        face: Face,
+        tolerance: Tolerance,
+    ) -> anyhow::Result<Mesh<Point<3>>> {
+        let mesh = triangulate(
+            vec![face],
+            tolerance,
+            &ErrorBudget::new(),
+            &mut ApproxStore::new(),
+            &mut DebugInfo::new(),
+        )?;
+        Ok(mesh)
+    }
+
+    /// Check that only the edges of the rims are used by a single triangle
+    ///
+    /// All other edges, including those along the seam, must be shared by two
+    /// triangles, in opposite directions.
+    fn assert_closed_except_rims(mesh: &Mesh<Point<3>>) {
+        let mut edges = BTreeMap::new();
+        for triangle in mesh.triangles() {
+            let [a, b, c] = triangle.points;
+            for edge in [[a, b], [b, c], [c, a]] {
+                *edges.entry(edge).or_insert(0) += 1;
+            }
+        }
+
+        for (&[a, b], &count) in &edges {
+            assert_eq!(count, 1);
+
+            let is_rim = a.z == b.z;
+            if !is_rim {
+                assert_eq!(edges.get(&[b, a]), Some(&1), "Open edge {a:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use std::{collections::HashMap, iter, mem::size_of, sync::Arc};
+
+use fj_interop::memory::MemoryReport;
+use fj_math::Point;
+
+use crate::{local::Local, objects::Face};
+
+use super::{FaceApprox, Tolerance};
+
+/// Memoizes face approximations within an evaluation
+///
+/// Several algorithms might need the approximation of the same face. Instead
+/// of computing it each time, they can get it from a shared `ApproxStore`,
+/// which computes every approximation only once.
+///
+/// Approximations are keyed by the face and the tolerance. Faces are immutable,
+/// so a stored approximation never goes stale.
+#[derive(Debug, Default)]
+pub struct ApproxStore {
+    faces: HashMap<(Face, Tolerance), Arc<FaceApprox>>,
+    stats: ApproxStoreStats,
+}
+
+impl ApproxStore {
+    /// Construct an empty instance of `ApproxStore`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the approximation of a face, computing it if necessary
+    ///
+    /// See [`FaceApprox::new`].
+    pub fn face_approx(
+        &mut self,
+        face: &Face,
+        tolerance: Tolerance,
+    ) -> Arc<FaceApprox> {
+        let key = (face.clone(), tolerance);
+
+        if let Some(approx) = self.faces.get(&key) {
+            self.stats.hits += 1;
+            return approx.clone();
+        }
+
+        self.stats.misses += 1;
+
+        let approx = Arc::new(FaceApprox::new(face, tolerance));
+        self.faces.insert(key, approx.clone());
+
+        approx
+    }
+
+    /// Access statistics about the use of this store
+    pub fn stats(&self) -> ApproxStoreStats {
+        self.stats
+    }
+
+    /// Estimate the memory used by the stored approximations
+    ///
+    /// Includes the points of each approximation and of its cycles, as well
+    /// as the face that it is stored for. The objects that the face refers to
+    /// are not included.
+    pub fn memory_report(&self) -> MemoryReport {
+        let point = size_of::<Local<Point<2>>>();
+
+        let mut report = MemoryReport::new();
+        for approx in self.faces.values() {
+            let cycles = iter::once(&approx.exterior).chain(&approx.interiors);
+            let points = approx.points.len()
+                + cycles.map(|cycle| cycle.points.len()).sum::<usize>();
+
+            report.add(
+                "face approximations",
+                1,
+                size_of::<(Face, Tolerance)>()
+                    + size_of::<FaceApprox>()
+                    + points * point,
+            );
+        }
+
+        report
+    }
+}
+
+/// Statistics about the use of an [`ApproxStore`]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ApproxStoreStats {
+    /// The number of approximations that were returned from the store
+    pub hits: u64,
+
+    /// The number of approximations that had to be computed
+    pub misses: u64,
+}
@@ -0,0 +1,364 @@
+use fj_math::{Point, Scalar, Vector};
+
+/// A boolean operation on two regions
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum Operation {
+    /// The region covered by either operand
+    Union,
+
+    /// The region covered by both operands
+    Intersection,
+
+    /// The region covered by the first operand, but not the second
+    Difference,
+}
+
+/// A polygon that bounds a region on the outside, and its holes
+pub(super) type Polygon = (Vec<Point<2>>, Vec<Vec<Point<2>>>);
+
+/// Combine two regions in the same 2D coordinate system
+///
+/// Returns the polygons that bound the resulting region. Exterior polygons
+/// wind counter-clockwise, holes clockwise. Points that are closer than
+/// `epsilon` are considered the same.
+pub(super) fn combine(
+    a: &Region,
+    b: &Region,
+    operation: Operation,
+    epsilon: Scalar,
+) -> Vec<Polygon> {
+    let mut segments = Vec::new();
+    for (this, other, is_a) in [(a, b, true), (b, a, false)] {
+        for [p, q] in this.split_segments(other, epsilon) {
+            let midpoint = p + (q - p) / 2.;
+            let position =
+                match other.shared_direction(midpoint, q - p, epsilon) {
+                    Some(true) => Position::SharedSameDirection,
+                    Some(false) => Position::SharedOppositeDirection,
+                    None if other.winding_number(midpoint) == 0 => {
+                        Position::Outside
+                    }
+                    None => Position::Inside,
+                };
+
+            // Edges that both regions share are only added once, from `a`.
+            use Position::*;
+            let segment = match (operation, is_a, position) {
+                (Operation::Union, true, Outside | SharedSameDirection)
+                | (Operation::Union, false, Outside)
+                | (
+                    Operation::Intersection,
+                    true,
+                    Inside | SharedSameDirection,
+                )
+                | (Operation::Intersection, false, Inside)
+                | (Operation::Difference, true, Outside)
+                | (Operation::Difference, true, SharedOppositeDirection) => {
+                    Some([p, q])
+                }
+                // The part of `b` that is inside of `a` bounds the result, but
+                // from the other side.
+                (Operation::Difference, false, Inside) => Some([q, p]),
+                _ => None,
+            };
+
+            segments.extend(segment);
+        }
+    }
+
+    let mut polygons: Vec<Polygon> = Vec::new();
+    let mut holes = Vec::new();
+    for cycle in chain(segments, epsilon) {
+        if signed_area(&cycle) > Scalar::ZERO {
+            polygons.push((cycle, Vec::new()));
+        } else {
+            holes.push(cycle);
+        }
+    }
+
+    for hole in holes {
+        // Each hole belongs to the smallest exterior that contains it.
+        let containing = polygons
+            .iter_mut()
+            .filter(|(exterior, _)| {
+                hole.iter().any(|&point| contains(exterior, point, epsilon))
+            })
+            .min_by_key(|(exterior, _)| signed_area(exterior));
+
+        if let Some((_, interiors)) = containing {
+            interiors.push(hole);
+        }
+    }
+
+    polygons
+}
+
+/// The area of the region bounded by polygons
+pub(super) fn area(polygons: &[Polygon]) -> Scalar {
+    polygons
+        .iter()
+        .flat_map(|(exterior, interiors)| {
+            [exterior].into_iter().chain(interiors)
+        })
+        .fold(Scalar::ZERO, |area, polygon| area + signed_area(polygon))
+}
+
+enum Position {
+    Inside,
+    Outside,
+    SharedSameDirection,
+    SharedOppositeDirection,
+}
+
+/// A region in surface coordinates, bounded by polygons
+///
+/// Exterior polygons wind counter-clockwise, interior ones clockwise.
+pub(super) struct Region {
+    polygons: Vec<Vec<Point<2>>>,
+}
+
+impl Region {
+    /// Create a region from the polygons that bound it
+    ///
+    /// The first `num_exteriors` polygons bound the region on the outside, the
+    /// others are holes. They are reoriented as required.
+    pub(super) fn new(
+        polygons: Vec<Vec<Point<2>>>,
+        num_exteriors: usize,
+    ) -> Self {
+        let polygons = polygons
+            .into_iter()
+            .enumerate()
+            .map(|(i, mut polygon)| {
+                let is_exterior = i < num_exteriors;
+                if (signed_area(&polygon) > Scalar::ZERO) != is_exterior {
+                    polygon.reverse();
+                }
+                polygon
+            })
+            .collect();
+
+        Self { polygons }
+    }
+
+    /// Create a region from polygons that are already oriented
+    ///
+    /// Exterior polygons must wind counter-clockwise, holes clockwise.
+    pub(super) fn from_oriented(polygons: Vec<Vec<Point<2>>>) -> Self {
+        Self { polygons }
+    }
+
+    /// Indicate whether the region is empty
+    pub(super) fn is_empty(&self) -> bool {
+        self.polygons.is_empty()
+    }
+
+    /// The area of the region
+    pub(super) fn area(&self) -> Scalar {
+        self.polygons
+            .iter()
+            .fold(Scalar::ZERO, |area, polygon| area + signed_area(polygon))
+    }
+
+    fn segments(&self) -> impl Iterator<Item = [Point<2>; 2]> + '_ {
+        self.polygons.iter().flat_map(|polygon| {
+            polygon
+                .iter()
+                .zip(polygon.iter().cycle().skip(1))
+                .map(|(&a, &b)| [a, b])
+        })
+    }
+
+    /// Split the segments of this region where the other region's cross them
+    fn split_segments(
+        &self,
+        other: &Region,
+        epsilon: Scalar,
+    ) -> Vec<[Point<2>; 2]> {
+        let mut result = Vec::new();
+
+        for [p, q] in self.segments() {
+            let r = q - p;
+            let length = r.magnitude();
+            if length <= epsilon {
+                continue;
+            }
+
+            let mut splits = Vec::new();
+            for [a, b] in other.segments() {
+                // Vertices of the other region that lie on the segment. This
+                // covers segments that overlap, or touch each other.
+                for point in [a, b] {
+                    let t = (point - p).dot(&r) / (length * length);
+                    if cross(r, point - p).abs() / length < epsilon {
+                        splits.push(t);
+                    }
+                }
+
+                // Proper crossings.
+                let s = b - a;
+                let denom = cross(r, s);
+                if denom == Scalar::ZERO {
+                    continue;
+                }
+                let t = cross(a - p, s) / denom;
+                let u = cross(a - p, r) / denom;
+                if u >= Scalar::ZERO && u <= Scalar::ONE {
+                    splits.push(t);
+                }
+            }
+
+            let min = epsilon / length;
+            splits.retain(|&t| t > min && t < Scalar::ONE - min);
+            splits.sort();
+
+            let mut start = p;
+            for t in splits {
+                let point = p + r * t;
+                if (point - start).magnitude() > epsilon {
+                    result.push([start, point]);
+                    start = point;
+                }
+            }
+            result.push([start, q]);
+        }
+
+        result
+    }
+
+    /// Determine whether a segment lies on the boundary of the region
+    ///
+    /// Returns `Some(true)`, if it runs in the same direction as the boundary,
+    /// `Some(false)`, if it runs in the opposite direction, and `None`, if it
+    /// doesn't lie on the boundary.
+    fn shared_direction(
+        &self,
+        point: Point<2>,
+        direction: Vector<2>,
+        epsilon: Scalar,
+    ) -> Option<bool> {
+        self.segments().find_map(|[a, b]| {
+            let ab = b - a;
+            let length = ab.magnitude();
+            if length <= epsilon {
+                return None;
+            }
+
+            let t = (point - a).dot(&ab) / (length * length);
+
+            let is_on_segment = t > Scalar::ZERO
+                && t < Scalar::ONE
+                && cross(ab, point - a).abs() / length < epsilon;
+
+            is_on_segment.then_some(ab.dot(&direction) > Scalar::ZERO)
+        })
+    }
+
+    fn winding_number(&self, point: Point<2>) -> i32 {
+        self.segments()
+            .map(|[a, b]| {
+                let side = cross(b - a, point - a);
+                if a.v <= point.v && b.v > point.v && side > Scalar::ZERO {
+                    1
+                } else if a.v > point.v && b.v <= point.v && side < Scalar::ZERO
+                {
+                    -1
+                } else {
+                    0
+                }
+            })
+            .sum()
+    }
+}
+
+/// Chain segments into cycles
+///
+/// Points that are closer than `epsilon` are considered the same.
+pub(super) fn chain(
+    segments: Vec<[Point<2>; 2]>,
+    epsilon: Scalar,
+) -> Vec<Vec<Point<2>>> {
+    let mut vertices: Vec<Point<2>> = Vec::new();
+    let mut index_of = |point: Point<2>| match vertices
+        .iter()
+        .position(|&vertex| (vertex - point).magnitude() < epsilon)
+    {
+        Some(index) => index,
+        None => {
+            vertices.push(point);
+            vertices.len() - 1
+        }
+    };
+
+    let mut remaining: Vec<[usize; 2]> = segments
+        .into_iter()
+        .map(|[a, b]| [index_of(a), index_of(b)])
+        .filter(|[a, b]| a != b)
+        .collect();
+
+    let mut cycles = Vec::new();
+    while let Some([start, mut next]) = remaining.pop() {
+        let mut cycle = vec![start];
+        while next != start {
+            cycle.push(next);
+            match remaining.iter().position(|&[a, _]| a == next) {
+                Some(index) => next = remaining.swap_remove(index)[1],
+                None => break,
+            }
+        }
+
+        let cycle: Vec<_> = cycle.into_iter().map(|k| vertices[k]).collect();
+        let cycle = without_collinear(cycle, epsilon);
+        if cycle.len() >= 3 {
+            cycles.push(cycle);
+        }
+    }
+
+    cycles
+}
+
+fn without_collinear(
+    mut cycle: Vec<Point<2>>,
+    epsilon: Scalar,
+) -> Vec<Point<2>> {
+    let mut i = 0;
+    while i < cycle.len() && cycle.len() >= 3 {
+        let n = cycle.len();
+        let [a, b, c] = [cycle[(i + n - 1) % n], cycle[i], cycle[(i + 1) % n]];
+
+        let ac = c - a;
+        let distance = cross(ac, b - a).abs() / ac.magnitude();
+        if distance < epsilon && (b - a).dot(&(c - b)) > Scalar::ZERO {
+            cycle.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+
+    cycle
+}
+
+/// Determine whether a point is inside, or on the boundary of, a polygon
+fn contains(polygon: &[Point<2>], point: Point<2>, epsilon: Scalar) -> bool {
+    let region = Region {
+        polygons: vec![polygon.to_vec()],
+    };
+
+    region.winding_number(point) != 0
+        || region
+            .shared_direction(point, Vector::from([1., 0.]), epsilon)
+            .is_some()
+}
+
+/// The signed area of a polygon
+pub(super) fn signed_area(polygon: &[Point<2>]) -> Scalar {
+    let n = polygon.len();
+    (0..n)
+        .map(|i| cross(polygon[i].coords, polygon[(i + 1) % n].coords))
+        .fold(Scalar::ZERO, |sum, area| sum + area)
+        / 2.
+}
+
+fn cross(a: Vector<2>, b: Vector<2>) -> Scalar {
+    a.u * b.v - a.v * b.u
+}
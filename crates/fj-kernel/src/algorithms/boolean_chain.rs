@@ -0,0 +1,262 @@
+use fj_math::{Point, Vector};
+
+use crate::{
+    iter::ObjectIters,
+    objects::{Curve, Face, Sketch, Surface},
+};
+
+use super::{reverse::reverse_cycle, IntersectionTolerances};
+
+/// A chain of boolean operations on a sketch
+///
+/// Collects all shapes that are subtracted from a base, then subtracts them in
+/// a single step, when [`BooleanChain::apply`] is called. Compared to
+/// subtracting one shape after the other, this avoids building (and
+/// validating) all of the intermediate sketches.
+///
+/// # Implementation Note
+///
+/// Like the rest of the 2D boolean support, this assumes that all shapes are
+/// located in the same surface, and that the subtracted shapes are fully
+/// contained within the base, without overlapping each other:
+/// <https://github.com/hannobraun/Fornjot/issues/92>
+#[derive(Clone, Debug)]
+pub struct BooleanChain {
+    base: Sketch,
+    tools: Vec<Sketch>,
+}
+
+impl BooleanChain {
+    /// Start a chain of boolean operations on `base`
+    pub fn new(base: Sketch) -> Self {
+        Self {
+            base,
+            tools: Vec::new(),
+        }
+    }
+
+    /// Subtract the given sketches from the base
+    pub fn subtract_all(
+        mut self,
+        tools: impl IntoIterator<Item = Sketch>,
+    ) -> Self {
+        self.tools.extend(tools);
+        self
+    }
+
+    /// Apply the operations and return the resulting sketch
+    ///
+    /// The result consists of a single face, in the surface of the base. It
+    /// has the color of the base's first face.
+    ///
+    /// Returns an error, if any face of the base or the subtracted sketches is
+    /// not in the same surface as the first face of the base. Surfaces are
+    /// considered the same, if they are parametrized the same way, within
+    /// [`IntersectionTolerances::coincidence_distance`].
+    pub fn apply(
+        self,
+        tolerances: &IntersectionTolerances,
+    ) -> Result<Sketch, BooleanError> {
+        let first = match self.base.face_iter().next() {
+            Some(face) => face,
+            // If there's no face to subtract from, the result is empty.
+            None => return Ok(Sketch::from_faces([])),
+        };
+
+        let surface = first.surface();
+
+        let mut exteriors = Vec::new();
+        let mut interiors = Vec::new();
+
+        for face in self.base.face_iter() {
+            if !is_same_surface(face.surface(), surface, tolerances) {
+                return Err(BooleanError::DifferentSurfaces);
+            }
+
+            exteriors.extend(face.exteriors().cloned());
+            interiors.extend(face.interiors().cloned());
+        }
+
+        for face in self.tools.iter().flat_map(|tool| tool.face_iter()) {
+            if !is_same_surface(face.surface(), surface, tolerances) {
+                return Err(BooleanError::DifferentSurfaces);
+            }
+
+            interiors.extend(face.exteriors().cloned().map(reverse_cycle));
+        }
+
+        Ok(Sketch::from_faces([Face::new(
+            surface.clone(),
+            exteriors,
+            interiors,
+            first.color(),
+        )]))
+    }
+}
+
+/// Determine whether two surfaces are the same, within the tolerances
+///
+/// The cycles of faces are defined in surface coordinates, so it's not enough
+/// for the surfaces to coincide. Their parametrization must match too. This
+/// is checked by comparing the points at the surface coordinates `[0, 0]`,
+/// `[1, 0]`, and `[0, 1]`.
+pub(super) fn is_same_surface(
+    a: &Surface,
+    b: &Surface,
+    tolerances: &IntersectionTolerances,
+) -> bool {
+    if a == b {
+        return true;
+    }
+
+    let Surface::SweptCurve(a) = a;
+    let Surface::SweptCurve(b) = b;
+
+    let (a_line, b_line) = match (&a.curve, &b.curve) {
+        (Curve::Line(a_line), Curve::Line(b_line)) => (a_line, b_line),
+        // Only planes are compared within tolerances.
+        _ => return false,
+    };
+
+    let points = |origin: Point<3>, u: Vector<3>, v: Vector<3>| {
+        [origin, origin + u, origin + v]
+    };
+    let a = points(a_line.origin, a_line.direction, a.path);
+    let b = points(b_line.origin, b_line.direction, b.path);
+
+    a.iter()
+        .zip(b.iter())
+        .all(|(a, b)| (*a - *b).magnitude() <= tolerances.coincidence_distance)
+}
+
+/// An error that can occur while applying a [`BooleanChain`], or computing a
+/// [`union_coplanar`]
+///
+/// [`union_coplanar`]: super::union_coplanar
+#[derive(Debug, thiserror::Error)]
+pub enum BooleanError {
+    /// The faces of the operands are not all in the same surface
+    #[error("Trying to combine faces with different surfaces")]
+    DifferentSurfaces,
+
+    /// The surfaces of the operands intersect
+    ///
+    /// Only faces in the same or parallel surfaces are supported so far.
+    #[error("Combining faces with intersecting surfaces is not supported")]
+    IntersectingSurfaces,
+
+    /// An operand uses triangle representation
+    #[error("Faces in triangle representation can't be combined")]
+    TriangleRepresentation,
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Aabb, Transform, Vector};
+
+    use crate::{
+        algorithms::{
+            equivalent_translation, IntersectionTolerances, Tolerance,
+            TransformObject,
+        },
+        iter::ObjectIters,
+        objects::{Face, Sketch, Surface},
+    };
+
+    use super::{BooleanChain, BooleanError};
+
+    #[test]
+    fn plate_with_holes() -> anyhow::Result<()> {
+        let tolerances = IntersectionTolerances::default();
+        let holes: Vec<_> = (0..8).map(|i| hole(i as f64 * 2. + 1.)).collect();
+
+        let chained = BooleanChain::new(plate())
+            .subtract_all(holes.clone())
+            .apply(&tolerances)?;
+        let folded = holes.into_iter().try_fold(plate(), |sketch, hole| {
+            BooleanChain::new(sketch)
+                .subtract_all([hole])
+                .apply(&tolerances)
+        })?;
+
+        assert_eq!(chained.face_iter().count(), 1);
+        assert_eq!(
+            chained
+                .face_iter()
+                .map(|face| face.interiors().count())
+                .sum::<usize>(),
+            8
+        );
+
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        assert_eq!(
+            equivalent_translation(&chained, &folded, tolerance),
+            Some(Vector::from([0., 0., 0.]))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn scale_invariance() -> anyhow::Result<()> {
+        // The holes are in a surface that is offset from the plate's by a
+        // tiny distance, relative to the size of the plate.
+        let plate_with_holes = |scale: f64, tolerances: Option<_>| {
+            let scaling = Transform::scale([scale, scale, scale]);
+            let offset = Transform::translation([0., 0., 1e-13 * scale]);
+
+            let holes = (0..8).map(|i| {
+                hole(i as f64 * 2. + 1.)
+                    .transform(&scaling)
+                    .transform(&offset)
+            });
+
+            let tolerances = tolerances.unwrap_or_else(|| {
+                IntersectionTolerances::relative_to(&Aabb::<3>::from_points([
+                    [0., 0., 0.],
+                    [17. * scale, 3. * scale, 0.],
+                ]))
+            });
+
+            BooleanChain::new(plate().transform(&scaling))
+                .subtract_all(holes)
+                .apply(&tolerances)
+        };
+        let num_interiors = |sketch: Sketch| {
+            sketch
+                .face_iter()
+                .map(|face| face.interiors().count())
+                .sum::<usize>()
+        };
+
+        assert_eq!(num_interiors(plate_with_holes(1., None)?), 8);
+        assert_eq!(num_interiors(plate_with_holes(1e4, None)?), 8);
+
+        // Absolute tolerances only suit one scale.
+        let absolute = Some(IntersectionTolerances::default());
+        assert_eq!(num_interiors(plate_with_holes(1., absolute)?), 8);
+        assert!(matches!(
+            plate_with_holes(1e4, absolute),
+            Err(BooleanError::DifferentSurfaces)
+        ));
+
+        Ok(())
+    }
+
+    fn plate() -> Sketch {
+        Sketch::from_faces([Face::builder(Surface::xy_plane())
+            .with_exterior_polygon([[0., 0.], [17., 0.], [17., 3.], [0., 3.]])
+            .build()])
+    }
+
+    fn hole(x: f64) -> Sketch {
+        Sketch::from_faces([Face::builder(Surface::xy_plane())
+            .with_exterior_polygon([
+                [x, 1.],
+                [x + 1., 1.],
+                [x + 1., 2.],
+                [x, 2.],
+            ])
+            .build()])
+    }
+}
@@ -0,0 +1,581 @@
+use std::collections::BTreeMap;
+
+use fj_math::{Point, Scalar, Vector};
+
+use crate::objects::{Face, Solid};
+
+use super::{
+    edge_blend::{
+        adjacent_faces, edge_key, face_vertices, max_sizes, polygon_face,
+        select_straight_edges, EdgeVanishes, EndCut, FeasibilityReport,
+        PlanarFace, SelectionError, Setbacks, VertexStar,
+    },
+    EdgeRef, EdgeSelector,
+};
+
+/// Bevel edges of a solid
+///
+/// Replaces each selected edge with a flat face, that cuts off the edge at a
+/// distance of `distance` from it, measured within both faces that meet at the
+/// edge. For faces that meet at a right angle, that is a 45° bevel.
+///
+/// See [`chamfer_asymmetric`] for details.
+pub fn chamfer(
+    solid: Solid,
+    edges: &EdgeSelector,
+    distance: impl Into<Scalar>,
+) -> Result<Solid, ChamferError> {
+    let distance = distance.into();
+    chamfer_asymmetric(solid, edges, [distance, distance])
+}
+
+/// Bevel edges of a solid, by different distances on either side
+///
+/// Like [`chamfer`], but the edge is cut off at `distances[0]` from it within
+/// the face that the edge was selected from (see [`EdgeRef::face`]), and at
+/// `distances[1]` within the other face. For edges that are selected by
+/// [`EdgeSelector::All`], that is the first face of the solid, that the edge
+/// is part of.
+///
+/// Only straight, convex edges between two planar faces can be chamfered. At
+/// each end of a chamfered edge, either
+///
+/// - no other chamfered edge ends there, and the corner of the third face at
+///   the vertex is cut off by a straight edge.
+/// - three chamfered edges meet at a vertex of three faces, like at the corner
+///   of a cube. The corner is cut off by a triangular face.
+///
+/// Other vertices result in [`ChamferError::UnsupportedVertex`]. If the faces
+/// are too small for the distances, so trimming them would leave edges of zero
+/// or negative length, [`ChamferError::Infeasible`] is returned, listing the
+/// largest distance that each offending edge allows (see
+/// [`max_chamfer_distance`]). For asymmetric chamfers, the report refers to
+/// the larger of the two distances, with the ratio between them kept.
+///
+/// Solids with faces that are made of triangles can't be chamfered. They result
+/// in [`ChamferError::UnsupportedFace`].
+pub fn chamfer_asymmetric(
+    solid: Solid,
+    edges: &EdgeSelector,
+    distances: [Scalar; 2],
+) -> Result<Solid, ChamferError> {
+    for distance in distances {
+        if distance <= Scalar::ZERO {
+            return Err(ChamferError::InvalidDistance(distance));
+        }
+    }
+
+    let distance = distances[0].max(distances[1]);
+    let Chamfers {
+        faces,
+        planar_faces,
+        vertices,
+        chamfered,
+        trimmed_vertices,
+        end_cuts,
+        maxima,
+    } = Chamfers::new(solid, edges, distances)?;
+
+    if let Some(report) = FeasibilityReport::check(distance, &maxima) {
+        return Err(ChamferError::Infeasible(report));
+    }
+
+    let mut result = Vec::new();
+    for (index, (face, planar_face)) in
+        faces.iter().zip(&planar_faces).enumerate()
+    {
+        let is_trimmed = planar_face.as_ref().map_or(false, |planar_face| {
+            planar_face.exterior.iter().any(|vertex| {
+                trimmed_vertices.contains_key(&(index, *vertex))
+                    || end_cuts
+                        .get(vertex)
+                        .map_or(false, |cut| cut.face == index)
+            })
+        });
+
+        match planar_face {
+            Some(planar_face) if is_trimmed => {
+                let face = planar_face
+                    .trim(index, face.color(), &trimmed_vertices, &end_cuts)
+                    .map_err(|EdgeVanishes { length }| {
+                        ChamferError::DistanceTooLarge { distance, length }
+                    })?;
+                result.push(face);
+            }
+            _ => result.push(face.clone()),
+        }
+    }
+
+    let outward = |indices: &[usize]| {
+        indices
+            .iter()
+            .map(|&index| {
+                planar_faces[index]
+                    .as_ref()
+                    .expect("Faces at chamfer are planar")
+                    .normal
+            })
+            .fold(Vector::from([0., 0., 0.]), |sum, normal| sum + normal)
+    };
+
+    for &([start, end], [a, b]) in &chamfered {
+        let points = vec![
+            trimmed_vertices[&(a, start)],
+            trimmed_vertices[&(a, end)],
+            trimmed_vertices[&(b, end)],
+            trimmed_vertices[&(b, start)],
+        ];
+        result.push(polygon_face(points, outward(&[a, b]), faces[a].color()));
+    }
+
+    for (&vertex, kind) in &vertices {
+        if let VertexKind::Corner { faces: corner } = kind {
+            let points = corner
+                .iter()
+                .map(|&index| trimmed_vertices[&(index, vertex)])
+                .collect();
+            result.push(polygon_face(
+                points,
+                outward(corner),
+                faces[corner[0]].color(),
+            ));
+        }
+    }
+
+    Ok(Solid::from_faces(result))
+}
+
+/// Compute the largest distance that each selected edge can be chamfered by
+///
+/// The faces that meet at an edge limit the distance, as do other chamfers
+/// that trim the same faces. For example, chamfers at both ends of an edge can
+/// each take up to half of it. A chamfer is feasible, if its distance is
+/// smaller than the maximum of every selected edge.
+///
+/// Edges that don't limit the distance at all get [`Scalar::MAX`]. Returns the
+/// same errors as [`chamfer`] for selections that can't be chamfered by any
+/// distance.
+pub fn max_chamfer_distance(
+    solid: &Solid,
+    edges: &EdgeSelector,
+) -> Result<Vec<(EdgeRef, Scalar)>, ChamferError> {
+    let chamfers =
+        Chamfers::new(solid.clone(), edges, [Scalar::ONE, Scalar::ONE])?;
+    Ok(chamfers.maxima)
+}
+
+/// The chamfers of the selected edges, before any faces are trimmed
+struct Chamfers {
+    faces: Vec<Face>,
+    planar_faces: Vec<Option<PlanarFace>>,
+    vertices: BTreeMap<Point<3>, VertexKind>,
+
+    /// The chamfered edges, with the faces that meet there
+    chamfered: Vec<([Point<3>; 2], [usize; 2])>,
+
+    trimmed_vertices: BTreeMap<(usize, Point<3>), Point<3>>,
+    end_cuts: BTreeMap<Point<3>, EndCut>,
+
+    /// The largest of the distances that each selected edge allows
+    maxima: Vec<(EdgeRef, Scalar)>,
+}
+
+impl Chamfers {
+    fn new(
+        solid: Solid,
+        edges: &EdgeSelector,
+        distances: [Scalar; 2],
+    ) -> Result<Self, ChamferError> {
+        let scale = distances[0].max(distances[1]);
+
+        let selected = select_straight_edges(&solid, edges)?;
+
+        // Reorienting the faces might reverse them, so the faces that edges
+        // were selected from are recognized by their vertices.
+        let selected_from: BTreeMap<_, _> = selected
+            .iter()
+            .map(|(&key, edge_ref)| {
+                let face = solid
+                    .faces()
+                    .nth(edge_ref.face)
+                    .expect("Selected edge has been resolved");
+                (key, face_vertices(face))
+            })
+            .collect();
+
+        let faces: Vec<_> = solid
+            .reoriented_outward()
+            .into_faces()
+            .into_iter()
+            .collect();
+        let planar_faces: Vec<_> =
+            faces.iter().map(PlanarFace::from_face).collect();
+
+        let mut vertices = BTreeMap::new();
+        for &[a, b] in selected.keys() {
+            for vertex in [a, b] {
+                if vertices.contains_key(&vertex) {
+                    continue;
+                }
+                let kind =
+                    VertexKind::new(vertex, &selected, &faces, &planar_faces)?;
+                vertices.insert(vertex, kind);
+            }
+        }
+
+        // The distance by which each chamfered edge is offset into its faces
+        let mut offsets = BTreeMap::new();
+        let mut chamfered = Vec::new();
+        for (&key, &edge_ref) in &selected {
+            let mut adjacent = adjacent_faces(key, &planar_faces)
+                .ok_or(ChamferError::UnsupportedEdge(edge_ref))?;
+            if face_vertices(&faces[adjacent[1]]) == selected_from[&key] {
+                adjacent.reverse();
+            }
+
+            let [face_a, face_b] = adjacent.map(|index| {
+                planar_faces[index]
+                    .as_ref()
+                    .expect("Faces of chamfered edge are planar")
+            });
+            if (face_b.centroid() - key[0]).dot(&face_a.normal) >= Scalar::ZERO
+            {
+                return Err(ChamferError::ConcaveEdge(edge_ref));
+            }
+
+            for (&face, &distance) in adjacent.iter().zip(&distances) {
+                offsets.insert((face, key), distance);
+            }
+            chamfered.push((key, adjacent));
+        }
+
+        // Where faces are trimmed, their vertices move to where the chamfers
+        // cut through them, in proportion to the distances.
+        let mut trimmed_vertices = BTreeMap::new();
+        let mut setbacks = Setbacks::new();
+        for &(face, key) in offsets.keys() {
+            let planar_face = planar_faces[face]
+                .as_ref()
+                .expect("Faces of chamfered edge are planar");
+            for vertex in key {
+                let point = offset_vertex(planar_face, face, vertex, &offsets);
+                trimmed_vertices.insert((face, vertex), point);
+                setbacks
+                    .entry((face, vertex))
+                    .or_default()
+                    .push(((point - vertex) / scale, key));
+            }
+        }
+
+        let mut end_cuts = BTreeMap::new();
+        for &(key, adjacent) in &chamfered {
+            for vertex in key {
+                if let VertexKind::End { face } = vertices[&vertex] {
+                    // The end face loses its corner, along both of its edges.
+                    for index in adjacent {
+                        let point = trimmed_vertices[&(index, vertex)];
+                        setbacks
+                            .entry((face, vertex))
+                            .or_default()
+                            .push(((point - vertex) / scale, key));
+                    }
+
+                    end_cuts.insert(
+                        vertex,
+                        EndCut {
+                            face,
+                            points: adjacent.map(|index| {
+                                trimmed_vertices[&(index, vertex)]
+                            }),
+                            arc: None,
+                        },
+                    );
+                }
+            }
+        }
+
+        let maxima = max_sizes(&planar_faces, &setbacks);
+        let maxima = selected
+            .iter()
+            .map(|(key, &edge_ref)| {
+                (edge_ref, maxima.get(key).copied().unwrap_or(Scalar::MAX))
+            })
+            .collect();
+
+        Ok(Self {
+            faces,
+            planar_faces,
+            vertices,
+            chamfered,
+            trimmed_vertices,
+            end_cuts,
+            maxima,
+        })
+    }
+}
+
+/// Error chamfering edges of a solid
+#[derive(Debug, thiserror::Error)]
+pub enum ChamferError {
+    /// A distance is not positive
+    #[error("Chamfer distance must be positive, but is {0}")]
+    InvalidDistance(Scalar),
+
+    /// A selected edge doesn't exist
+    #[error("Edge {0:?} doesn't refer to an edge of the solid")]
+    EdgeNotFound(EdgeRef),
+
+    /// A selected edge is not a straight edge between two planar faces
+    #[error("Can only chamfer straight edges between two planar faces: {0:?}")]
+    UnsupportedEdge(EdgeRef),
+
+    /// A face of the solid is made of triangles
+    #[error("Can only chamfer solids with faces bounded by edges: face {0}")]
+    UnsupportedFace(usize),
+
+    /// A selected edge is concave
+    #[error("Can only chamfer convex edges: {0:?}")]
+    ConcaveEdge(EdgeRef),
+
+    /// Chamfers end at a vertex in a way that is not supported
+    #[error(
+        "Can't chamfer at vertex {0:?}: Chamfers can only end at a vertex of \
+        three faces, alone or in threes"
+    )]
+    UnsupportedVertex(Point<3>),
+
+    /// The distance is too large for some of the selected edges
+    #[error(
+        "Chamfer distance {} is too large; the largest feasible distance is {}",
+        .0.requested,
+        .0.max()
+    )]
+    Infeasible(FeasibilityReport),
+
+    /// Trimming a face would make one of its edges vanish
+    #[error(
+        "Chamfer distance {distance} is too large for an edge of length \
+        {length}"
+    )]
+    DistanceTooLarge {
+        /// The larger of the distances of the chamfer
+        distance: Scalar,
+
+        /// The length of the edge that would vanish
+        length: Scalar,
+    },
+}
+
+impl From<SelectionError> for ChamferError {
+    fn from(err: SelectionError) -> Self {
+        match err {
+            SelectionError::EdgeNotFound(edge_ref) => {
+                Self::EdgeNotFound(edge_ref)
+            }
+            SelectionError::UnsupportedEdge(edge_ref) => {
+                Self::UnsupportedEdge(edge_ref)
+            }
+            SelectionError::TriangleFace(index) => Self::UnsupportedFace(index),
+        }
+    }
+}
+
+/// How a vertex at the end of a chamfered edge is treated
+#[derive(Clone, Copy)]
+enum VertexKind {
+    /// One chamfer ends at the given face, whose corner is cut off
+    End { face: usize },
+
+    /// Three chamfers meet at a corner, which is cut off by a triangle
+    Corner { faces: [usize; 3] },
+}
+
+impl VertexKind {
+    fn new(
+        vertex: Point<3>,
+        selected: &BTreeMap<[Point<3>; 2], EdgeRef>,
+        faces: &[Face],
+        planar_faces: &[Option<PlanarFace>],
+    ) -> Result<Self, ChamferError> {
+        let star = VertexStar::new(vertex, selected, faces, planar_faces)
+            .ok_or(ChamferError::UnsupportedVertex(vertex))?;
+
+        match (star.faces.as_slice(), star.selected.as_slice()) {
+            (&[a, b, c], &[edge]) => {
+                let end_faces: Vec<_> = [a, b, c]
+                    .into_iter()
+                    .filter(|&index| {
+                        !planar_faces[index]
+                            .as_ref()
+                            .map_or(false, |face| face.has_edge(edge))
+                    })
+                    .collect();
+
+                match end_faces.as_slice() {
+                    &[face] => Ok(Self::End { face }),
+                    _ => Err(ChamferError::UnsupportedVertex(vertex)),
+                }
+            }
+            (&[a, b, c], selected_edges) if selected_edges.len() == 3 => {
+                Ok(Self::Corner { faces: [a, b, c] })
+            }
+            _ => Err(ChamferError::UnsupportedVertex(vertex)),
+        }
+    }
+}
+
+/// Move a vertex of a face, to where the offset edges of the face meet
+///
+/// Each of the two edges of the face at the vertex is offset into the face, by
+/// the distance in `offsets`, or not at all, if it isn't chamfered.
+fn offset_vertex(
+    face: &PlanarFace,
+    index: usize,
+    vertex: Point<3>,
+    offsets: &BTreeMap<(usize, [Point<3>; 2]), Scalar>,
+) -> Point<3> {
+    let [previous, next] = face
+        .neighbors(vertex)
+        .expect("Vertex of chamfered edge is part of face");
+    let offset = |neighbor: Point<3>| {
+        offsets
+            .get(&(index, edge_key([vertex, neighbor])))
+            .copied()
+            .unwrap_or(Scalar::ZERO)
+    };
+
+    // Moving along one edge changes the distance from the other one by the
+    // sine of the angle between them.
+    let [to_previous, to_next] =
+        [previous, next].map(|neighbor| (neighbor - vertex).normalize());
+    let sin = to_previous.cross(&to_next).magnitude();
+
+    vertex
+        + to_previous * (offset(next) / sin)
+        + to_next * (offset(previous) / sin)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::{mesh::Mesh, physical_summary::PhysicalSummary};
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        algorithms::{test_helpers::closed_mesh, EdgeSelector, Tolerance},
+        objects::Solid,
+        validation::{validate, ValidationConfig},
+    };
+
+    use super::{
+        chamfer, chamfer_asymmetric, max_chamfer_distance, ChamferError,
+    };
+
+    #[test]
+    fn all_edges() -> anyhow::Result<()> {
+        let solid = Solid::cube_from_edge_length(2.);
+
+        let d = 0.5;
+        let result = chamfer(solid, &EdgeSelector::All, d)?;
+
+        // The original faces, a bevel per edge, a triangle per corner.
+        assert_eq!(result.faces().count(), 6 + 12 + 8);
+
+        let result = validate(result, &ValidationConfig::default())?;
+
+        // Each face contributes its area times its distance from the center.
+        let expected = (6. * (2. - 2. * d) * (2. - 2. * d)
+            + 12. * d * (2. - 2. * d) * (2. - d)
+            + 8. * d * d / 2. * (3. - 2. * d))
+            / 3.;
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        let volume =
+            volume(closed_mesh(result.into_inner(), tolerance)?, tolerance);
+        assert!((volume - expected).abs() < 1e-9);
+
+        Ok(())
+    }
+
+    #[test]
+    fn asymmetric() -> anyhow::Result<()> {
+        let solid = Solid::cube_from_edge_length(2.);
+
+        // The edge at the top right, which runs along the y-axis.
+        let edges = EdgeSelector::matching(&solid, |points| {
+            points
+                .iter()
+                .all(|point| point.x == Scalar::ONE && point.z == Scalar::ONE)
+        });
+
+        let distances = [0.5, 1.].map(Scalar::from_f64);
+        let result = chamfer_asymmetric(solid, &edges, distances)?;
+        assert_eq!(result.faces().count(), 7);
+
+        let result = validate(result, &ValidationConfig::default())?;
+
+        let removed = 0.5 * 1. / 2. * 2.;
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        let volume =
+            volume(closed_mesh(result.into_inner(), tolerance)?, tolerance);
+        assert!((volume - (8. - removed)).abs() < 1e-9);
+
+        Ok(())
+    }
+
+    #[test]
+    fn distance_too_large() {
+        let solid = Solid::cube_from_edge_length(2.);
+
+        // Chamfers on both ends of an edge of length 2 leave nothing of it.
+        assert!(matches!(
+            chamfer(solid, &EdgeSelector::All, 1.),
+            Err(ChamferError::Infeasible(_))
+        ));
+    }
+
+    #[test]
+    fn max_distance() -> anyhow::Result<()> {
+        let solid = Solid::cube_from_edge_length(10.);
+
+        let maxima = max_chamfer_distance(&solid, &EdgeSelector::All)?;
+        assert_eq!(maxima.len(), 12);
+        assert!(maxima.iter().all(|&(_, max)| is_close(max, 5.)));
+
+        let report = match chamfer(solid, &EdgeSelector::All, 6.) {
+            Err(ChamferError::Infeasible(report)) => report,
+            _ => panic!("Expected distance to be infeasible"),
+        };
+        assert_eq!(report.requested, Scalar::from_f64(6.));
+        assert!(is_close(report.max(), 5.));
+
+        Ok(())
+    }
+
+    #[test]
+    fn two_chamfers_at_vertex() {
+        let solid = Solid::cube_from_edge_length(2.);
+
+        // Two edges of the top face, that meet at a corner.
+        let edges = EdgeSelector::matching(&solid, |points| {
+            points.iter().all(|point| {
+                point.z == Scalar::ONE
+                    && (point.x == Scalar::ONE || point.y == Scalar::ONE)
+            })
+        });
+
+        assert!(matches!(
+            chamfer(solid, &edges, 0.5),
+            Err(ChamferError::UnsupportedVertex(vertex))
+                if vertex == Point::from([1., 1., 1.])
+        ));
+    }
+
+    fn volume(mesh: Mesh<Point<3>>, tolerance: Tolerance) -> f64 {
+        PhysicalSummary::from_mesh(&mesh, tolerance.inner())
+            .volume
+            .into_f64()
+    }
+
+    fn is_close(a: Scalar, b: f64) -> bool {
+        (a - Scalar::from_f64(b)).abs() < Scalar::from_f64(1e-6)
+    }
+}
@@ -0,0 +1,646 @@
+use fj_math::{Circle, Point, Scalar, Triangle, Vector};
+
+use crate::{
+    local::Local,
+    objects::{Curve, Cycle, Edge, Face, Solid, Surface, VerticesOfEdge},
+};
+
+use super::{
+    reverse::reverse_cycle,
+    reverse_face,
+    split::{contains, face_points, Plane},
+    CycleApprox, Handedness, Tolerance, TransformObject,
+};
+
+/// The maximum distance of a cap face from the cut plane
+const ON_PLANE_MAX_DISTANCE: f64 = 1e-9;
+
+/// The number of candidate positions along each axis of the cut face
+const PLACEMENT_RESOLUTION: usize = 32;
+
+/// Defines the connectors that [`add_connectors`] adds to split parts
+#[derive(Clone, Copy, Debug)]
+pub struct ConnectorSpec {
+    /// The cross-section of the connectors
+    pub shape: ConnectorShape,
+
+    /// The number of connectors
+    pub count: usize,
+
+    /// How far the connectors protrude from the cut face
+    pub length: Scalar,
+
+    /// The gap between a connector and the hole it fits into
+    ///
+    /// The holes are larger than the connectors by this amount on every side,
+    /// and deeper by the same amount.
+    pub clearance: Scalar,
+
+    /// The minimum distance between the holes and the boundary of the cut face
+    ///
+    /// Also applies between neighboring holes.
+    pub margin: Scalar,
+}
+
+/// The cross-section of a connector
+#[derive(Clone, Copy, Debug)]
+pub enum ConnectorShape {
+    /// A cylindrical pin
+    Pin {
+        /// The radius of the pin
+        radius: Scalar,
+    },
+
+    /// A rectangular key
+    ///
+    /// The key is aligned with the coordinate system of the cut plane. Its
+    /// width runs along the first surface coordinate, its length along the
+    /// second.
+    Key {
+        /// The width of the key
+        width: Scalar,
+
+        /// The length of the key
+        length: Scalar,
+    },
+}
+
+impl ConnectorShape {
+    /// Enlarge the shape by `offset` on every side
+    fn grow(self, offset: Scalar) -> Self {
+        match self {
+            Self::Pin { radius } => Self::Pin {
+                radius: radius + offset,
+            },
+            Self::Key { width, length } => Self::Key {
+                width: width + offset * 2.,
+                length: length + offset * 2.,
+            },
+        }
+    }
+
+    /// The radius of the smallest circle around the center that holds the shape
+    fn extent(self) -> Scalar {
+        match self {
+            Self::Pin { radius } => radius,
+            Self::Key { width, length } => {
+                (width * width + length * length).sqrt() / 2.
+            }
+        }
+    }
+}
+
+/// Add alignment connectors to the two parts of a split solid
+///
+/// Pins are added to the cut face of `half_a`, and matching holes to the cut
+/// face of `half_b`, at the same positions. Both parts stay closed. The holes
+/// are larger than the pins, as defined by the [`ConnectorSpec`].
+///
+/// The connectors are distributed over the cut face automatically. They keep
+/// a distance to its boundary (including any holes in it), and to each other.
+/// If the cut face doesn't have enough room, an error is returned.
+///
+/// `cut_plane` is the plane that the solid was split along, and `tolerance` is
+/// used to approximate the connectors and the cut faces.
+///
+/// # Implementation Note
+///
+/// There is no support for boolean operations on solids yet. Instead of
+/// uniting the pins with `half_a` and subtracting the holes from `half_b`, the
+/// connectors are built directly: The cut faces get new interior cycles, which
+/// are closed by the walls and ends of the connectors. This requires the cut
+/// faces to be faces in the cut plane, as created by [`split_solid`].
+///
+/// [`split_solid`]: super::split_solid
+pub fn add_connectors(
+    half_a: Solid,
+    half_b: Solid,
+    cut_plane: &Surface,
+    spec: ConnectorSpec,
+    tolerance: Tolerance,
+) -> Result<(Solid, Solid), ConnectorError> {
+    let plane = Plane::new(cut_plane).map_err(|_| ConnectorError::NotAPlane)?;
+
+    // The connectors point from `half_a` into `half_b`.
+    let direction = face_points_off_plane(&half_a, &plane, tolerance)
+        .map(|distance| {
+            if distance < Scalar::ZERO {
+                plane.normal
+            } else {
+                -plane.normal
+            }
+        })
+        .ok_or(ConnectorError::NoCutFace)?;
+
+    let (mut caps_a, mut faces_a) = partition_caps(half_a, &plane, tolerance);
+    let (mut caps_b, mut faces_b) = partition_caps(half_b, &plane, tolerance);
+    if caps_a.is_empty() || caps_b.is_empty() {
+        return Err(ConnectorError::NoCutFace);
+    }
+
+    let hole_shape = spec.shape.grow(spec.clearance);
+    let positions = place_connectors(
+        &caps_a,
+        &plane,
+        hole_shape.extent(),
+        spec,
+        tolerance,
+    )?;
+
+    for position in positions {
+        let center = plane.origin + plane.u * position.u + plane.v * position.v;
+
+        for (caps, faces, shape, length, is_pin) in [
+            (&mut caps_a, &mut faces_a, spec.shape, spec.length, true),
+            (
+                &mut caps_b,
+                &mut faces_b,
+                hole_shape,
+                spec.length + spec.clearance,
+                false,
+            ),
+        ] {
+            let index = caps
+                .iter()
+                .position(|cap| {
+                    cap_loops(cap, &plane, tolerance)
+                        .iter()
+                        .filter(|points| contains(points, position))
+                        .count()
+                        % 2
+                        == 1
+                })
+                .ok_or(ConnectorError::NoCutFace)?;
+
+            let connector = Connector {
+                center,
+                shape,
+                path: direction * length,
+                is_pin,
+            };
+            caps[index] =
+                connector.build(&caps[index], &plane, tolerance, faces);
+        }
+    }
+
+    faces_a.extend(caps_a);
+    faces_b.extend(caps_b);
+
+    Ok((Solid::from_faces(faces_a), Solid::from_faces(faces_b)))
+}
+
+/// Error adding connectors to split parts
+#[derive(Debug, thiserror::Error)]
+pub enum ConnectorError {
+    /// The cut surface is not a plane
+    #[error("Connectors can only be added to solids split along planes")]
+    NotAPlane,
+
+    /// One of the parts has no face in the cut plane
+    #[error("Parts have no matching faces in the cut plane")]
+    NoCutFace,
+
+    /// The cut faces don't have room for the requested number of connectors
+    #[error("Only found room for {placed} of {requested} connectors")]
+    NotEnoughRoom {
+        /// The number of connectors that would fit
+        placed: usize,
+
+        /// The number of connectors that were requested
+        requested: usize,
+    },
+}
+
+/// A single connector, either a pin or the hole that it fits into
+struct Connector {
+    center: Point<3>,
+    shape: ConnectorShape,
+    path: Vector<3>,
+    is_pin: bool,
+}
+
+impl Connector {
+    /// Add the connector to a cut face
+    ///
+    /// Returns the cut face with an added interior cycle. The walls and end
+    /// face of the connector are added to `faces`.
+    fn build(
+        &self,
+        cap: &Face,
+        plane: &Plane,
+        tolerance: Tolerance,
+        faces: &mut Vec<Face>,
+    ) -> Face {
+        let surface = cap.surface();
+
+        let mut profile = self.profile(surface, plane);
+
+        // Interior cycles wind the other way than exterior ones.
+        let handedness = Handedness::of_face(cap, tolerance)
+            .expect("Caps use boundary representation");
+        let winding = CycleApprox::new(&profile, tolerance).winding();
+        if winding == Some(handedness.exterior_winding()) {
+            profile = reverse_cycle(profile);
+        }
+
+        // Pins point away from the cap, holes into the solid behind it.
+        let outward = if self.is_pin { self.path } else { -self.path };
+
+        let mut walls = Vec::new();
+        for segment in CycleApprox::new(&profile, tolerance).segments() {
+            let [a, b] = segment.points();
+            let [c, d] = [b + self.path, a + self.path];
+
+            let normal = (b - a).cross(&self.path);
+            let away_from_center =
+                (a - self.center).dot(&normal) > Scalar::ZERO;
+
+            let [a, b, c, d] = if away_from_center == self.is_pin {
+                [a, b, c, d]
+            } else {
+                [b, a, d, c]
+            };
+
+            walls.push((Triangle::from_points([a, b, c]), cap.color()));
+            walls.push((Triangle::from_points([a, c, d]), cap.color()));
+        }
+        faces.push(Face::from_triangles(walls));
+
+        let end =
+            Face::new(surface.clone(), [profile.clone()], [], cap.color())
+                .translate(self.path);
+        let end = if face_normal(&end, tolerance).dot(&outward) > Scalar::ZERO {
+            end
+        } else {
+            reverse_face(&end)
+        };
+        faces.push(end);
+
+        Face::new(
+            surface.clone(),
+            cap.exteriors().cloned(),
+            cap.interiors().cloned().chain([profile]),
+            cap.color(),
+        )
+    }
+
+    /// Build the cycle that outlines the connector in the cap's surface
+    fn profile(&self, surface: &Surface, plane: &Plane) -> Cycle {
+        match self.shape {
+            ConnectorShape::Pin { radius } => {
+                let global = Circle {
+                    center: self.center,
+                    a: plane.u * radius,
+                    b: plane.v * radius,
+                };
+
+                let center = surface_coords(surface, global.center);
+                let local = Circle {
+                    center,
+                    a: surface_coords(surface, global.center + global.a)
+                        - center,
+                    b: surface_coords(surface, global.center + global.b)
+                        - center,
+                };
+
+                let edge = Edge::new(
+                    Local::new(Curve::Circle(local), Curve::Circle(global)),
+                    VerticesOfEdge::none(),
+                );
+
+                Cycle { edges: vec![edge] }
+            }
+            ConnectorShape::Key { width, length } => {
+                let u = plane.u * (width / 2.);
+                let v = plane.v * (length / 2.);
+
+                let points = [
+                    self.center - u - v,
+                    self.center + u - v,
+                    self.center + u + v,
+                    self.center - u + v,
+                ]
+                .map(|point| surface_coords(surface, point));
+
+                Cycle::polygon_from_points(surface, points)
+            }
+        }
+    }
+}
+
+/// Find positions for the connectors, in the coordinates of the cut plane
+///
+/// Candidates are taken from a grid over the cut faces. Of those that have
+/// enough room around them, the positions are picked one after the other,
+/// always taking the one that is farthest from the ones already picked.
+fn place_connectors(
+    caps: &[Face],
+    plane: &Plane,
+    extent: Scalar,
+    spec: ConnectorSpec,
+    tolerance: Tolerance,
+) -> Result<Vec<Point<2>>, ConnectorError> {
+    let loops: Vec<_> = caps
+        .iter()
+        .map(|cap| cap_loops(cap, plane, tolerance))
+        .collect();
+
+    let points = loops.iter().flatten().flatten();
+    let min_u = points.clone().map(|point| point.u).min();
+    let max_u = points.clone().map(|point| point.u).max();
+    let min_v = points.clone().map(|point| point.v).min();
+    let max_v = points.map(|point| point.v).max();
+
+    let (min, max) = match (min_u, max_u, min_v, max_v) {
+        (Some(min_u), Some(max_u), Some(min_v), Some(max_v)) => {
+            (Point::from([min_u, min_v]), Point::from([max_u, max_v]))
+        }
+        _ => return Err(ConnectorError::NoCutFace),
+    };
+
+    let room = extent + spec.margin;
+
+    let mut candidates = Vec::new();
+    for i in 0..PLACEMENT_RESOLUTION {
+        for j in 0..PLACEMENT_RESOLUTION {
+            let [s, t] =
+                [i, j].map(|k| (k as f64 + 0.5) / PLACEMENT_RESOLUTION as f64);
+            let candidate = Point::from([
+                min.u + (max.u - min.u) * s,
+                min.v + (max.v - min.v) * t,
+            ]);
+
+            let is_inside = loops.iter().any(|cap| {
+                cap.iter()
+                    .filter(|points| contains(points, candidate))
+                    .count()
+                    % 2
+                    == 1
+            });
+            let has_room = loops.iter().flatten().all(|points| {
+                points.windows(2).all(|segment| {
+                    distance_to_segment(candidate, [segment[0], segment[1]])
+                        >= room
+                })
+            });
+
+            if is_inside && has_room {
+                candidates.push(candidate);
+            }
+        }
+    }
+
+    // Start with the candidate that is farthest from the center of all, which
+    // spreads out the connectors as much as possible.
+    let center = candidates
+        .iter()
+        .fold(Vector::from([0., 0.]), |sum, candidate| {
+            sum + candidate.coords
+        })
+        / candidates.len().max(1) as f64;
+
+    let mut positions: Vec<Point<2>> = Vec::new();
+    while positions.len() < spec.count {
+        let next = candidates
+            .iter()
+            .map(|&candidate| {
+                let distance = positions
+                    .iter()
+                    .map(|&position| (candidate - position).magnitude())
+                    .min()
+                    .unwrap_or_else(|| (candidate.coords - center).magnitude());
+                (distance, candidate)
+            })
+            .filter(|&(distance, _)| {
+                positions.is_empty() || distance >= room * 2.
+            })
+            .max_by_key(|&(distance, _)| distance);
+
+        match next {
+            Some((_, position)) => positions.push(position),
+            None => {
+                return Err(ConnectorError::NotEnoughRoom {
+                    placed: positions.len(),
+                    requested: spec.count,
+                })
+            }
+        }
+    }
+
+    Ok(positions)
+}
+
+/// Find the distance of a point off the plane, in any face of the solid
+fn face_points_off_plane(
+    solid: &Solid,
+    plane: &Plane,
+    tolerance: Tolerance,
+) -> Option<Scalar> {
+    solid
+        .faces()
+        .flat_map(|face| face_points(face, tolerance))
+        .map(|point| plane.distance(point))
+        .find(|distance| {
+            distance.abs() > Scalar::from_f64(ON_PLANE_MAX_DISTANCE)
+        })
+}
+
+/// Separate the faces of a solid that lie in the cut plane from the others
+fn partition_caps(
+    solid: Solid,
+    plane: &Plane,
+    tolerance: Tolerance,
+) -> (Vec<Face>, Vec<Face>) {
+    solid.into_faces().into_iter().partition(|face| {
+        face.triangles().is_none()
+            && face_points(face, tolerance).iter().all(|&point| {
+                plane.distance(point).abs()
+                    <= Scalar::from_f64(ON_PLANE_MAX_DISTANCE)
+            })
+    })
+}
+
+/// The cycles of a cut face, as closed polygons in the cut plane
+fn cap_loops(
+    cap: &Face,
+    plane: &Plane,
+    tolerance: Tolerance,
+) -> Vec<Vec<Point<2>>> {
+    cap.all_cycles()
+        .map(|cycle| {
+            CycleApprox::new(cycle, tolerance)
+                .points
+                .into_iter()
+                .map(|point| plane.surface_coords(*point.global_form()))
+                .collect()
+        })
+        .collect()
+}
+
+/// The outward normal of a planar face
+fn face_normal(face: &Face, tolerance: Tolerance) -> Vector<3> {
+    let normal = face.surface().normal_at([0., 0.]);
+
+    match Handedness::of_face(face, tolerance) {
+        Some(handedness) => handedness.outward(normal),
+        None => normal,
+    }
+}
+
+/// Convert a point into the coordinates of a planar surface
+///
+/// Doesn't require the surface to be normalized.
+fn surface_coords(surface: &Surface, point: Point<3>) -> Point<2> {
+    let origin = surface.point_from_surface_coords([0., 0.]);
+    let u = surface.vector_from_surface_coords([1., 0.]);
+    let v = surface.vector_from_surface_coords([0., 1.]);
+
+    // Solve `point - origin = u * a + v * b` using the normal equations.
+    let offset = point - origin;
+    let [uu, uv, vv] = [u.dot(&u), u.dot(&v), v.dot(&v)];
+    let [pu, pv] = [offset.dot(&u), offset.dot(&v)];
+    let determinant = uu * vv - uv * uv;
+
+    Point::from([
+        (pu * vv - pv * uv) / determinant,
+        (pv * uu - pu * uv) / determinant,
+    ])
+}
+
+fn distance_to_segment(point: Point<2>, [a, b]: [Point<2>; 2]) -> Scalar {
+    let ab = b - a;
+    let length_squared = ab.dot(&ab);
+    if length_squared == Scalar::ZERO {
+        return (point - a).magnitude();
+    }
+
+    let t = ((point - a).dot(&ab) / length_squared)
+        .max(Scalar::ZERO)
+        .min(Scalar::ONE);
+    (point - (a + ab * t)).magnitude()
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        algorithms::{
+            split_solid, sweep, test_helpers::closed_mesh, CycleApprox,
+            Tolerance, TransformObject,
+        },
+        iter::ObjectIters,
+        objects::{Cycle, Face, Sketch, Surface},
+    };
+
+    use super::{add_connectors, ConnectorShape, ConnectorSpec};
+
+    #[test]
+    fn pins_and_holes() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let surface = Surface::xy_plane();
+        let face = Face::new(
+            surface.clone(),
+            [Cycle::polygon_from_points(
+                &surface,
+                [[0., 0.], [2., 0.], [2., 1.], [0., 1.]],
+            )],
+            [],
+            [255, 0, 0, 255],
+        );
+        let block = sweep(
+            Sketch::from_faces([face]),
+            [0., 0., 1.],
+            tolerance,
+            [255, 0, 0, 255],
+        )?;
+
+        let plane = Surface::xy_plane().translate([0., 0., 0.5]);
+        let (below, above) = split_solid(block, &plane, tolerance)?;
+
+        let radius = Scalar::from_f64(0.1);
+        let clearance = Scalar::from_f64(0.02);
+        let spec = ConnectorSpec {
+            shape: ConnectorShape::Pin { radius },
+            count: 2,
+            length: Scalar::from_f64(0.2),
+            clearance,
+            margin: Scalar::from_f64(0.05),
+        };
+        let (below, above) =
+            add_connectors(below, above, &plane, spec, tolerance)?;
+
+        let holes = [&below, &above].map(|half| {
+            let caps: Vec<_> = half
+                .face_iter()
+                .filter(|face| face.triangles().is_none())
+                .filter(|face| {
+                    face.all_cycles()
+                        .flat_map(|cycle| {
+                            CycleApprox::new(cycle, tolerance).points
+                        })
+                        .all(|point| is_close(point.global_form().z, 0.5))
+                })
+                .collect();
+            assert_eq!(caps.len(), 1);
+
+            caps[0]
+                .interiors()
+                .map(|interior| circle(interior, tolerance))
+                .collect::<Vec<_>>()
+        });
+
+        let [pins, holes] = holes;
+        assert_eq!(pins.len(), 2);
+        assert_eq!(holes.len(), 2);
+
+        for (center, pin_radius) in pins {
+            let (_, hole_radius) = holes
+                .iter()
+                .copied()
+                .find(|(hole, _)| is_close((*hole - center).magnitude(), 0.))
+                .expect("No hole at the position of the pin");
+
+            assert!(is_close(pin_radius, radius.into_f64()));
+            assert!(is_close(hole_radius - pin_radius, clearance.into_f64()));
+        }
+
+        // The pins protrude from the lower half.
+        let max = closed_mesh(below, tolerance)?
+            .vertices()
+            .map(|point| point.z)
+            .max();
+        assert!(is_close(max.unwrap_or(Scalar::ZERO), 0.7));
+
+        closed_mesh(above, tolerance)?;
+
+        Ok(())
+    }
+
+    /// Determine center and radius of a circular cycle from its approximation
+    fn circle(cycle: &Cycle, tolerance: Tolerance) -> (Point<3>, Scalar) {
+        let points: Vec<_> = CycleApprox::new(cycle, tolerance)
+            .points
+            .into_iter()
+            .map(|point| *point.global_form())
+            .collect();
+
+        let center = Point {
+            coords: points
+                .iter()
+                .skip(1)
+                .fold(Point::<3>::origin().coords, |sum, point| {
+                    sum + point.coords
+                })
+                / (points.len() - 1) as f64,
+        };
+        let radius = (points[0] - center).magnitude();
+
+        (center, radius)
+    }
+
+    fn is_close(value: Scalar, expected: f64) -> bool {
+        (value.into_f64() - expected).abs() < 1e-9
+    }
+}
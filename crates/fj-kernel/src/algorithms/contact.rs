@@ -0,0 +1,518 @@
+use std::fmt;
+
+use fj_interop::debug::DebugInfo;
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use crate::{
+    iter::ObjectIters,
+    objects::{Face, Solid, Surface},
+};
+
+use super::{
+    split::{face_points, Plane},
+    triangulate, ApproxStore, ErrorBudget, Handedness, Tolerance,
+};
+
+/// Find the regions where two solids touch
+///
+/// See [`contact_report`]. Pairs of faces that might touch, but can't be
+/// analyzed, are skipped silently.
+pub fn contact_patches(
+    a: &Solid,
+    b: &Solid,
+    max_gap: Scalar,
+    tolerance: Tolerance,
+) -> Vec<ContactPatch> {
+    contact_report(a, b, max_gap, tolerance).patches
+}
+
+/// Find the regions where two solids touch, reporting skipped face pairs
+///
+/// Two faces are in contact, if they are planar, point in opposite directions,
+/// and their planes are at most `max_gap` apart. Their normals may deviate from
+/// being antiparallel by the angle at which one face would depart from the
+/// other by `max_gap` over its extent.
+///
+/// The contact patch is the region where the two faces overlap, after
+/// projecting the face of `b` into the plane of the face of `a`. It is computed
+/// from their triangulations, within `tolerance`.
+///
+/// Only planar contact is supported. Pairs of faces whose bounding boxes are
+/// within `max_gap` of each other, but that aren't both planar, are listed in
+/// [`ContactReport::warnings`].
+pub fn contact_report(
+    a: &Solid,
+    b: &Solid,
+    max_gap: Scalar,
+    tolerance: Tolerance,
+) -> ContactReport {
+    let mut approx_store = ApproxStore::new();
+    let mut report = ContactReport::default();
+
+    let faces_b: Vec<_> = b
+        .face_iter()
+        .map(|face| ContactFace::new(face, tolerance, &mut approx_store))
+        .collect();
+
+    for (index_a, face_a) in a.face_iter().enumerate() {
+        let face_a = ContactFace::new(face_a, tolerance, &mut approx_store);
+
+        for (index_b, face_b) in faces_b.iter().enumerate() {
+            let faces = [index_a, index_b];
+
+            if !face_a.aabb_within(face_b, max_gap) {
+                continue;
+            }
+
+            let (planar_a, planar_b) = match (&face_a.planar, &face_b.planar) {
+                (Some(a), Some(b)) => (a, b),
+                _ => {
+                    report.warnings.push(ContactWarning { faces });
+                    continue;
+                }
+            };
+
+            if let Some(patch) =
+                planar_a.contact_with(planar_b, faces, max_gap, tolerance)
+            {
+                report.patches.push(patch);
+            }
+        }
+    }
+
+    report
+}
+
+/// The result of [`contact_report`]
+#[derive(Clone, Debug, Default)]
+pub struct ContactReport {
+    /// The regions where the solids touch
+    pub patches: Vec<ContactPatch>,
+
+    /// Pairs of faces that might touch, but weren't analyzed
+    pub warnings: Vec<ContactWarning>,
+}
+
+/// A region where two solids touch
+#[derive(Clone, Debug)]
+pub struct ContactPatch {
+    /// The indices of the faces that touch
+    ///
+    /// The first index refers to a face of the first solid, the second to a
+    /// face of the second solid. Faces are indexed in the order they are
+    /// iterated over.
+    pub faces: [usize; 2],
+
+    /// The plane that the region is located in
+    ///
+    /// This is the plane of the face of the first solid, with an isometric
+    /// parameterization.
+    pub surface: Surface,
+
+    /// The cycles that bound the region, in coordinates of `surface`
+    ///
+    /// Exterior cycles are counter-clockwise, interior cycles clockwise. The
+    /// last point of each cycle is not repeated.
+    pub cycles: Vec<Vec<Point<2>>>,
+
+    /// The area of the region
+    pub area: Scalar,
+}
+
+/// A pair of faces that might touch, but wasn't analyzed
+///
+/// Returned by [`contact_report`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ContactWarning {
+    /// The indices of the faces, as in [`ContactPatch::faces`]
+    pub faces: [usize; 2],
+}
+
+impl fmt::Display for ContactWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let [a, b] = self.faces;
+        write!(
+            f,
+            "Skipped contact between face {a} of the first solid and face {b} \
+            of the second, as only planar contact is supported"
+        )
+    }
+}
+
+struct ContactFace {
+    aabb: Aabb<3>,
+    planar: Option<PlanarFace>,
+}
+
+impl ContactFace {
+    fn new(
+        face: &Face,
+        tolerance: Tolerance,
+        approx_store: &mut ApproxStore,
+    ) -> Self {
+        let aabb = Aabb::<3>::from_points(face_points(face, tolerance));
+        let planar = PlanarFace::new(face, tolerance, approx_store);
+
+        Self { aabb, planar }
+    }
+
+    fn aabb_within(&self, other: &Self, distance: Scalar) -> bool {
+        (0..3).all(|i| {
+            let [a, b] = [&self.aabb, &other.aabb];
+            a.min.coords.components[i] - distance <= b.max.coords.components[i]
+                && b.min.coords.components[i] - distance
+                    <= a.max.coords.components[i]
+        })
+    }
+}
+
+struct PlanarFace {
+    plane: Plane,
+    outward: Vector<3>,
+    extent: Scalar,
+    triangles: Vec<[Point<3>; 3]>,
+}
+
+impl PlanarFace {
+    fn new(
+        face: &Face,
+        tolerance: Tolerance,
+        approx_store: &mut ApproxStore,
+    ) -> Option<Self> {
+        let handedness = Handedness::of_face(face, tolerance)?;
+        let plane = Plane::new(face.surface()).ok()?;
+        let outward = handedness.outward(plane.normal);
+
+        let mesh = triangulate(
+            vec![face.clone()],
+            tolerance,
+            &ErrorBudget::new(),
+            approx_store,
+            &mut DebugInfo::new(),
+        )
+        .ok()?;
+        let triangles: Vec<_> =
+            mesh.triangles().map(|triangle| triangle.points).collect();
+        if triangles.is_empty() {
+            return None;
+        }
+
+        let extent =
+            Aabb::<3>::from_points(triangles.iter().flatten().copied())
+                .size()
+                .magnitude();
+
+        Some(Self {
+            plane,
+            outward,
+            extent,
+            triangles,
+        })
+    }
+
+    fn contact_with(
+        &self,
+        other: &Self,
+        faces: [usize; 2],
+        max_gap: Scalar,
+        tolerance: Tolerance,
+    ) -> Option<ContactPatch> {
+        // Over its extent, the other face must not depart from this one by
+        // more than the gap.
+        let max_gap = max_gap.max(tolerance.inner());
+        let min_alignment = if max_gap >= self.extent {
+            Scalar::ZERO
+        } else {
+            let ratio = max_gap / self.extent;
+            (Scalar::ONE - ratio * ratio).sqrt()
+        };
+        if -self.outward.dot(&other.outward) < min_alignment {
+            return None;
+        }
+
+        let gap = other
+            .triangles
+            .iter()
+            .flatten()
+            .map(|&point| self.plane.distance(point).abs())
+            .fold(Scalar::ZERO, Scalar::max);
+        if gap > max_gap {
+            return None;
+        }
+
+        let project = |triangles: &[[Point<3>; 3]]| -> Vec<[Point<2>; 3]> {
+            triangles
+                .iter()
+                .map(|triangle| {
+                    let [a, b, c] =
+                        triangle.map(|point| self.plane.surface_coords(point));
+                    if signed_area(&[a, b, c]) < Scalar::ZERO {
+                        [a, c, b]
+                    } else {
+                        [a, b, c]
+                    }
+                })
+                .collect()
+        };
+        let triangles_a = project(&self.triangles);
+        let triangles_b = project(&other.triangles);
+
+        // Pieces that are thinner than this are artifacts of faces that only
+        // touch along an edge.
+        let min_area = tolerance.inner() * tolerance.inner();
+
+        let mut pieces = Vec::new();
+        for a in &triangles_a {
+            for b in &triangles_b {
+                let piece = clip(a, b);
+                if piece.len() >= 3 && signed_area(&piece) > min_area {
+                    pieces.push(piece);
+                }
+            }
+        }
+
+        if pieces.is_empty() {
+            return None;
+        }
+
+        let area = pieces
+            .iter()
+            .map(|piece| signed_area(piece))
+            .fold(Scalar::ZERO, |sum, area| sum + area);
+        let cycles = boundary(&pieces, tolerance.inner() * 1e-3);
+
+        Some(ContactPatch {
+            faces,
+            surface: self.plane.surface.clone(),
+            cycles,
+            area,
+        })
+    }
+}
+
+/// Clip a counter-clockwise polygon by a counter-clockwise triangle
+///
+/// Uses the Sutherland-Hodgman algorithm, which works, as the triangle is
+/// convex.
+fn clip(polygon: &[Point<2>], triangle: &[Point<2>; 3]) -> Vec<Point<2>> {
+    let mut result = polygon.to_vec();
+
+    for i in 0..3 {
+        let [a, b] = [triangle[i], triangle[(i + 1) % 3]];
+        let side = |point: Point<2>| cross(b - a, point - a);
+
+        let input = result;
+        result = Vec::new();
+
+        for j in 0..input.len() {
+            let [p, q] = [input[j], input[(j + 1) % input.len()]];
+            let [side_p, side_q] = [side(p), side(q)];
+
+            if side_p >= Scalar::ZERO {
+                result.push(p);
+            }
+            if (side_p >= Scalar::ZERO) != (side_q >= Scalar::ZERO) {
+                let t = side_p / (side_p - side_q);
+                result.push(p + (q - p) * t);
+            }
+        }
+
+        if result.is_empty() {
+            break;
+        }
+    }
+
+    result
+}
+
+/// Compute the cycles that bound the union of non-overlapping polygons
+///
+/// Edges that are shared by two polygons run in opposite directions and cancel
+/// out. Vertices of a polygon can lie on the edges of its neighbors, so edges
+/// are split at those first. Points that are closer than `epsilon` are merged.
+fn boundary(polygons: &[Vec<Point<2>>], epsilon: Scalar) -> Vec<Vec<Point<2>>> {
+    let mut vertices: Vec<Point<2>> = Vec::new();
+    let mut index_of = |point: Point<2>| match vertices
+        .iter()
+        .position(|&vertex| (vertex - point).magnitude() < epsilon)
+    {
+        Some(index) => index,
+        None => {
+            vertices.push(point);
+            vertices.len() - 1
+        }
+    };
+
+    let polygons: Vec<Vec<usize>> = polygons
+        .iter()
+        .map(|polygon| {
+            let mut indices: Vec<_> =
+                polygon.iter().map(|&point| index_of(point)).collect();
+            indices.dedup();
+            if indices.len() > 1 && indices.first() == indices.last() {
+                indices.pop();
+            }
+            indices
+        })
+        .collect();
+
+    let mut edges = Vec::new();
+    for polygon in &polygons {
+        for i in 0..polygon.len() {
+            let [a, b] = [polygon[i], polygon[(i + 1) % polygon.len()]];
+            let [pa, pb] = [vertices[a], vertices[b]];
+            let ab = pb - pa;
+            let length = ab.magnitude();
+
+            // Split the edge at every vertex that lies on it.
+            let mut splits: Vec<_> = vertices
+                .iter()
+                .enumerate()
+                .filter(|&(k, _)| k != a && k != b)
+                .filter_map(|(k, &point)| {
+                    let t = (point - pa).dot(&ab) / (length * length);
+                    let distance = cross(ab, point - pa).abs() / length;
+                    (t > Scalar::ZERO && t < Scalar::ONE && distance < epsilon)
+                        .then_some((t, k))
+                })
+                .collect();
+            splits.sort();
+
+            let mut start = a;
+            for (_, k) in splits {
+                edges.push([start, k]);
+                start = k;
+            }
+            edges.push([start, b]);
+        }
+    }
+
+    // Shared edges cancel out.
+    let mut remaining: Vec<[usize; 2]> = Vec::new();
+    for [a, b] in edges {
+        match remaining.iter().position(|&edge| edge == [b, a]) {
+            Some(index) => {
+                remaining.swap_remove(index);
+            }
+            None => remaining.push([a, b]),
+        }
+    }
+
+    // Chain the remaining edges into cycles.
+    let mut cycles = Vec::new();
+    while let Some([start, mut next]) = remaining.pop() {
+        let mut cycle = vec![start];
+        while next != start {
+            cycle.push(next);
+            match remaining.iter().position(|&[a, _]| a == next) {
+                Some(index) => next = remaining.swap_remove(index)[1],
+                None => break,
+            }
+        }
+
+        let cycle: Vec<_> = cycle.into_iter().map(|k| vertices[k]).collect();
+        let cycle = without_collinear(cycle, epsilon);
+        if cycle.len() >= 3 {
+            cycles.push(cycle);
+        }
+    }
+
+    cycles
+}
+
+/// Remove the points of a cycle that lie on the line between their neighbors
+fn without_collinear(
+    mut cycle: Vec<Point<2>>,
+    epsilon: Scalar,
+) -> Vec<Point<2>> {
+    let mut i = 0;
+    while i < cycle.len() && cycle.len() >= 3 {
+        let n = cycle.len();
+        let [a, b, c] = [cycle[(i + n - 1) % n], cycle[i], cycle[(i + 1) % n]];
+
+        let ac = c - a;
+        let distance = cross(ac, b - a).abs() / ac.magnitude();
+        if distance < epsilon {
+            cycle.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+
+    cycle
+}
+
+/// The signed area of a polygon
+fn signed_area(polygon: &[Point<2>]) -> Scalar {
+    let n = polygon.len();
+    (0..n)
+        .map(|i| cross(polygon[i].coords, polygon[(i + 1) % n].coords))
+        .fold(Scalar::ZERO, |sum, area| sum + area)
+        / 2.
+}
+
+fn cross(a: Vector<2>, b: Vector<2>) -> Scalar {
+    a.u * b.v - a.v * b.u
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Scalar;
+
+    use crate::{
+        algorithms::{Tolerance, TransformObject},
+        objects::Solid,
+    };
+
+    use super::contact_patches;
+
+    #[test]
+    fn stacked_cubes() {
+        let base = Solid::cube_from_edge_length(2.);
+        let top = Solid::cube_from_edge_length(1.).translate([0.2, 0., 1.5]);
+
+        let patches = contact_patches(&base, &top, max_gap(), tolerance());
+        assert_eq!(patches.len(), 1);
+
+        let patch = &patches[0];
+        assert_area(patch.area, 1.);
+        assert_eq!(patch.cycles.len(), 1);
+        assert_eq!(patch.cycles[0].len(), 4);
+    }
+
+    #[test]
+    fn separated_cubes() {
+        let base = Solid::cube_from_edge_length(2.);
+        let gap = max_gap() * 2.;
+        let top = Solid::cube_from_edge_length(1.).translate([
+            0.,
+            0.,
+            1.5 + gap.into_f64(),
+        ]);
+
+        let patches = contact_patches(&base, &top, max_gap(), tolerance());
+        assert!(patches.is_empty());
+    }
+
+    #[test]
+    fn side_by_side_cubes() {
+        let left = Solid::cube_from_edge_length(2.);
+        let right = Solid::cube_from_edge_length(2.).translate([2., 0., 0.]);
+
+        let patches = contact_patches(&left, &right, max_gap(), tolerance());
+        assert_eq!(patches.len(), 1);
+        assert_area(patches[0].area, 4.);
+    }
+
+    fn assert_area(area: Scalar, expected: f64) {
+        assert!((area.into_f64() - expected).abs() < 1e-9, "Area: {area}");
+    }
+
+    fn max_gap() -> Scalar {
+        Scalar::from_f64(0.01)
+    }
+
+    fn tolerance() -> Tolerance {
+        Tolerance::from_scalar(0.001).unwrap()
+    }
+}
@@ -0,0 +1,516 @@
+use fj_interop::{
+    debug::DebugInfo,
+    mesh::{Color, Mesh},
+};
+use fj_math::{Point, Scalar, Triangle};
+
+use crate::objects::{Face, Solid, Surface};
+
+use super::{
+    boolean_2d::{
+        self, chain, combine, signed_area, Operation, Polygon, Region,
+    },
+    reverse_face,
+    split::{
+        cut_triangle, cycle, face_triangles, Plane, ON_PLANE_MAX_DISTANCE,
+    },
+    triangulate::{triangulate_face, TriangulationConfig, TriangulationError},
+    ApproxStore, CycleApprox, Tolerance,
+};
+
+/// Subtract one solid from another
+///
+/// Returns the part of `a` that is not inside of `b`. Faces of `a` that are
+/// inside of `b` are dropped, faces of `b` that are inside of `a` become part of
+/// the result, reversed. If `b` doesn't touch `a`, the result is `a`; if `b`
+/// contains `a`, it is an empty solid.
+///
+/// If `b` is completely inside of `a`, the result is `a`, with an internal
+/// void in the shape of `b`. Its faces point into the void.
+///
+/// Faces that are cut are triangulated using `tolerance`, and their triangles
+/// are trimmed, like [`split_solid`] does it. Faces that aren't cut are kept
+/// as they are.
+///
+/// # Implementation Note
+///
+/// Each face is trimmed by the cross-section of the other solid in the plane
+/// of the face. That cross-section can't be determined reliably, if a vertex
+/// of the other solid lies in that plane ([`DifferenceError::VertexOnPlane`]),
+/// or a face of the other solid lies in it
+/// ([`DifferenceError::CoplanarFaces`]). Moving one of the solids slightly
+/// avoids this.
+///
+/// [`split_solid`]: super::split_solid
+pub fn difference(
+    a: Solid,
+    b: Solid,
+    tolerance: Tolerance,
+) -> Result<Solid, DifferenceError> {
+    let span = fj_interop::span!(
+        INFO,
+        "difference",
+        faces_a = a.faces().count(),
+        faces_b = b.faces().count(),
+        faces = tracing::field::Empty,
+    );
+
+    let mut approx_store = ApproxStore::new();
+
+    let triangles_a = solid_triangles(&a, tolerance, &mut approx_store)?;
+    let triangles_b = solid_triangles(&b, tolerance, &mut approx_store)?;
+
+    let mut faces = Vec::new();
+    for face in a.into_faces() {
+        faces.extend(trim_face(
+            face,
+            &triangles_b,
+            Operation::Difference,
+            tolerance,
+            &mut approx_store,
+        )?);
+    }
+    for face in b.into_faces() {
+        faces.extend(trim_face(
+            face,
+            &triangles_a,
+            Operation::Intersection,
+            tolerance,
+            &mut approx_store,
+        )?);
+    }
+
+    fj_interop::record!(span, "faces", faces.len());
+
+    Ok(Solid::from_faces(faces))
+}
+
+/// Error subtracting one solid from another
+#[derive(Debug, thiserror::Error)]
+pub enum DifferenceError {
+    /// A face of one solid lies in the plane of a face of the other
+    #[error("Can't subtract solids that have faces in the same plane")]
+    CoplanarFaces,
+
+    /// A vertex of one solid lies in the plane of a face of the other
+    #[error(
+        "Can't subtract solids, if a vertex of one lies in the plane of a face \
+        of the other"
+    )]
+    VertexOnPlane,
+
+    /// A face could not be triangulated
+    #[error("Failed to triangulate face")]
+    Triangulation(#[from] TriangulationError),
+}
+
+/// Trim a face of one solid by the other solid
+///
+/// Faces of `a` are trimmed with [`Operation::Difference`], and keep their
+/// orientation. Faces of `b` are trimmed with [`Operation::Intersection`], and
+/// are reversed.
+fn trim_face(
+    face: Face,
+    other: &[[Point<3>; 3]],
+    operation: Operation,
+    tolerance: Tolerance,
+    approx_store: &mut ApproxStore,
+) -> Result<Option<Face>, DifferenceError> {
+    let _span = fj_interop::span!(DEBUG, "trim_face", ?operation);
+
+    let reverse = operation == Operation::Intersection;
+
+    if face.triangles().is_none() {
+        if let Ok(plane) = Plane::new(face.surface()) {
+            let polygons = face
+                .all_cycles()
+                .map(|cycle| {
+                    CycleApprox::new(cycle, tolerance)
+                        .points
+                        .into_iter()
+                        .map(|point| plane.surface_coords(*point.global_form()))
+                        .collect()
+                })
+                .collect();
+            let piece = Piece::new(plane, polygons, face.exteriors().count());
+
+            return match piece.trim(other, operation)? {
+                Trimmed::Unchanged if reverse => Ok(Some(reverse_face(&face))),
+                Trimmed::Unchanged => Ok(Some(face)),
+                Trimmed::Empty => Ok(None),
+                Trimmed::Polygons(polygons) => {
+                    let triangles = piece.triangulate(
+                        polygons,
+                        face.color(),
+                        reverse,
+                        tolerance,
+                        approx_store,
+                    )?;
+                    Ok(Some(Face::from_triangles(triangles)))
+                }
+            };
+        }
+    }
+
+    // The face consists of triangles, or is curved. Either way, each of its
+    // triangles is trimmed on its own.
+    let mut triangles = Vec::new();
+    let mut is_unchanged = true;
+
+    for (triangle, color) in face_triangles(&face, tolerance, approx_store)? {
+        let points = triangle.points();
+        let plane = Plane::new(&Surface::plane_from_points(points))
+            .expect("Surface created from points is a plane");
+        let polygon = points.map(|point| plane.surface_coords(point));
+        let piece = Piece::new(plane, vec![polygon.to_vec()], 1);
+
+        match piece.trim(other, operation)? {
+            Trimmed::Unchanged => {
+                let [a, b, c] = points;
+                let points = if reverse { [a, c, b] } else { [a, b, c] };
+                triangles.push((Triangle::from_points(points), color));
+            }
+            Trimmed::Empty => {
+                is_unchanged = false;
+            }
+            Trimmed::Polygons(polygons) => {
+                is_unchanged = false;
+                triangles.extend(piece.triangulate(
+                    polygons,
+                    color,
+                    reverse,
+                    tolerance,
+                    approx_store,
+                )?);
+            }
+        }
+    }
+
+    if is_unchanged && !reverse {
+        return Ok(Some(face));
+    }
+    if triangles.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(Face::from_triangles(triangles)))
+}
+
+/// The triangles of all faces of a solid
+fn solid_triangles(
+    solid: &Solid,
+    tolerance: Tolerance,
+    approx_store: &mut ApproxStore,
+) -> Result<Vec<[Point<3>; 3]>, TriangulationError> {
+    let mut triangles = Vec::new();
+
+    for face in solid.faces() {
+        let face_triangles = face_triangles(face, tolerance, approx_store)?;
+        triangles.extend(
+            face_triangles
+                .into_iter()
+                .map(|(triangle, _)| triangle.points()),
+        );
+    }
+
+    Ok(triangles)
+}
+
+/// A planar piece of a face, in the coordinates of its plane
+struct Piece {
+    plane: Plane,
+    region: Region,
+
+    /// Whether the piece faces along the normal of the plane
+    is_along_normal: bool,
+}
+
+impl Piece {
+    fn new(
+        plane: Plane,
+        polygons: Vec<Vec<Point<2>>>,
+        num_exteriors: usize,
+    ) -> Self {
+        let is_along_normal = polygons
+            .first()
+            .map_or(true, |exterior| signed_area(exterior) > Scalar::ZERO);
+        let region = Region::new(polygons, num_exteriors);
+
+        Self {
+            plane,
+            region,
+            is_along_normal,
+        }
+    }
+
+    /// Trim the piece by the cross-section of a solid in its plane
+    fn trim(
+        &self,
+        other: &[[Point<3>; 3]],
+        operation: Operation,
+    ) -> Result<Trimmed, DifferenceError> {
+        let section = self.section(other)?;
+
+        if section.is_empty() {
+            return match operation {
+                Operation::Intersection => Ok(Trimmed::Empty),
+                _ => Ok(Trimmed::Unchanged),
+            };
+        }
+
+        let epsilon = Scalar::from_f64(ON_PLANE_MAX_DISTANCE);
+        let polygons = combine(&self.region, &section, operation, epsilon);
+
+        if polygons.is_empty() {
+            return Ok(Trimmed::Empty);
+        }
+
+        let area = self.region.area();
+        if (area - boolean_2d::area(&polygons)).abs() <= area * epsilon {
+            return Ok(Trimmed::Unchanged);
+        }
+
+        Ok(Trimmed::Polygons(polygons))
+    }
+
+    /// Compute the cross-section of a solid in the plane of the piece
+    ///
+    /// The resulting region covers the inside of the solid.
+    fn section(
+        &self,
+        triangles: &[[Point<3>; 3]],
+    ) -> Result<Region, DifferenceError> {
+        let max_distance = Scalar::from_f64(ON_PLANE_MAX_DISTANCE);
+
+        let mut is_below = false;
+        let mut is_above = false;
+        let mut is_on_plane = false;
+
+        for &points in triangles {
+            let distances = points.map(|point| self.plane.distance(point));
+            if distances
+                .iter()
+                .all(|distance| distance.abs() <= max_distance)
+            {
+                return Err(DifferenceError::CoplanarFaces);
+            }
+
+            for distance in distances {
+                if distance.abs() <= max_distance {
+                    is_on_plane = true;
+                } else if distance < Scalar::ZERO {
+                    is_below = true;
+                } else {
+                    is_above = true;
+                }
+            }
+        }
+
+        if !(is_below && is_above) {
+            return Ok(Region::from_oriented(Vec::new()));
+        }
+        if is_on_plane {
+            return Err(DifferenceError::VertexOnPlane);
+        }
+
+        // The segments are oriented as the boundary of a cap that faces along
+        // the normal of the plane. That is counter-clockwise around the inside
+        // of the solid, which is just how a region is oriented.
+        let segments = triangles
+            .iter()
+            .filter_map(|&points| cut_triangle(points, &self.plane).segment)
+            .map(|segment| {
+                segment.map(|point| self.plane.surface_coords(point))
+            })
+            .collect();
+
+        Ok(Region::from_oriented(chain(segments, max_distance)))
+    }
+
+    /// Triangulate the polygons that are left of the piece after trimming
+    ///
+    /// The triangles face the same way as the piece, unless `reverse` is set.
+    fn triangulate(
+        &self,
+        polygons: Vec<Polygon>,
+        color: Color,
+        reverse: bool,
+        tolerance: Tolerance,
+        approx_store: &mut ApproxStore,
+    ) -> Result<Vec<(Triangle<3>, Color)>, TriangulationError> {
+        let surface = &self.plane.surface;
+        let to_cycle = |mut points: Vec<Point<2>>| {
+            points.push(points[0]);
+            cycle(surface, points)
+        };
+
+        let mut triangles = Vec::new();
+
+        for (exterior, interiors) in polygons {
+            // Exteriors are counter-clockwise, so the face faces along the
+            // normal of the plane.
+            let face = Face::new(
+                surface.clone(),
+                [to_cycle(exterior)],
+                interiors.into_iter().map(to_cycle),
+                color,
+            );
+
+            let mut mesh = Mesh::new();
+            triangulate_face(
+                &face,
+                0,
+                tolerance,
+                approx_store,
+                &mut DebugInfo::new(),
+                &TriangulationConfig::default(),
+                &mut mesh,
+            )?;
+
+            for triangle in mesh.triangles() {
+                let [a, b, c] = triangle.points;
+                let points = if self.is_along_normal != reverse {
+                    [a, b, c]
+                } else {
+                    [a, c, b]
+                };
+                triangles.push((Triangle::from_points(points), triangle.color));
+            }
+        }
+
+        Ok(triangles)
+    }
+}
+
+/// The result of trimming a piece
+enum Trimmed {
+    /// Nothing was trimmed off the piece
+    Unchanged,
+
+    /// The whole piece was trimmed off
+    Empty,
+
+    /// The polygons that are left of the piece
+    Polygons(Vec<Polygon>),
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::{mesh::Mesh, physical_summary::PhysicalSummary};
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        algorithms::{
+            sweep, test_helpers::closed_mesh, Tolerance, TransformObject,
+        },
+        iter::ObjectIters,
+        objects::{Cycle, Face, Sketch, Solid, Surface},
+    };
+
+    use super::{difference, DifferenceError};
+
+    #[test]
+    fn blind_hole() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        // The tool pokes through the top face of the block.
+        let block = cuboid([0., 0., 0.], [1., 1., 1.], tolerance)?;
+        let tool = cuboid([0.25, 0.25, 0.5], [0.5, 0.5, 1.], tolerance)?;
+
+        let result = difference(block, tool, tolerance)?;
+        let volume = volume(closed_mesh(result, tolerance)?, tolerance);
+        assert!(is_close(volume, 1. - 0.5 * 0.5 * 0.5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn internal_void() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let block = cuboid([0., 0., 0.], [1., 1., 1.], tolerance)?;
+        let tool = cuboid([0.25, 0.25, 0.25], [0.5, 0.5, 0.5], tolerance)?;
+
+        let result = difference(block, tool, tolerance)?;
+        assert_eq!(result.face_iter().count(), 12);
+
+        let volume = volume(closed_mesh(result, tolerance)?, tolerance);
+        assert!(is_close(volume, 1. - 0.5 * 0.5 * 0.5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn tool_contains_solid() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let block = cuboid([0., 0., 0.], [1., 1., 1.], tolerance)?;
+        let tool = cuboid([-1., -1., -1.], [3., 3., 3.], tolerance)?;
+
+        let result = difference(block, tool, tolerance)?;
+        assert_eq!(result.face_iter().count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn disjoint() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let block = cuboid([0., 0., 0.], [1., 1., 1.], tolerance)?;
+        let tool = cuboid([2., 0.25, 0.25], [0.5, 0.5, 0.5], tolerance)?;
+
+        let result = difference(block.clone(), tool, tolerance)?;
+        assert_eq!(result, block);
+
+        Ok(())
+    }
+
+    #[test]
+    fn coplanar_faces() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let block = cuboid([0., 0., 0.], [1., 1., 1.], tolerance)?;
+        let tool = cuboid([0.25, 0.25, 0.], [0.5, 0.5, 2.], tolerance)?;
+
+        assert!(matches!(
+            difference(block, tool, tolerance),
+            Err(DifferenceError::CoplanarFaces)
+        ));
+
+        Ok(())
+    }
+
+    fn cuboid(
+        [x, y, z]: [f64; 3],
+        [dx, dy, dz]: [f64; 3],
+        tolerance: Tolerance,
+    ) -> anyhow::Result<Solid> {
+        let surface = Surface::xy_plane();
+        let face = Face::new(
+            surface.clone(),
+            [Cycle::polygon_from_points(
+                &surface,
+                [[0., 0.], [dx, 0.], [dx, dy], [0., dy]],
+            )],
+            [],
+            [255, 0, 0, 255],
+        );
+        let solid = sweep(
+            Sketch::from_faces([face]),
+            [0., 0., dz],
+            tolerance,
+            [255, 0, 0, 255],
+        )?;
+
+        Ok(solid.translate([x, y, z]))
+    }
+
+    fn volume(mesh: Mesh<Point<3>>, tolerance: Tolerance) -> Scalar {
+        PhysicalSummary::from_mesh(&mesh, tolerance.inner()).volume
+    }
+
+    fn is_close(value: Scalar, expected: f64) -> bool {
+        (value.into_f64() - expected).abs() < 1e-9
+    }
+}
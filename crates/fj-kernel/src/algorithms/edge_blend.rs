@@ -0,0 +1,656 @@
+//! Infrastructure shared by operations that rebuild the planar faces of solids
+//!
+//! See [`fillet`], [`chamfer`], and [`shell`].
+//!
+//! [`fillet`]: super::fillet
+//! [`chamfer`]: super::chamfer
+//! [`shell`]: super::shell
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use fj_interop::mesh::Color;
+use fj_math::{Circle, Line, Point, Scalar, Vector};
+
+use crate::{
+    local::Local,
+    objects::{
+        Curve, Cycle, Edge, Face, GlobalVertex, Solid, Surface, SweptCurve,
+        Vertex, VerticesOfEdge,
+    },
+};
+
+use super::{reverse::reverse_edge, EdgeRef, EdgeSelector};
+
+/// Edges that trimming leaves shorter than this are considered to vanish
+pub(super) const MIN_EDGE_LENGTH: f64 = 1e-6;
+
+/// Resolve the selected edges, which must be straight
+///
+/// Edges are identified by the positions of their vertices from here on, as
+/// edge references are only valid for the solid as it was passed in. Solids
+/// with faces that are made of triangles are rejected, as those faces can't
+/// be trimmed.
+pub(super) fn select_straight_edges(
+    solid: &Solid,
+    edges: &EdgeSelector,
+) -> Result<BTreeMap<[Point<3>; 2], EdgeRef>, SelectionError> {
+    if let Some(index) =
+        solid.faces().position(|face| face.triangles().is_some())
+    {
+        return Err(SelectionError::TriangleFace(index));
+    }
+
+    let mut selected = BTreeMap::new();
+    for edge_ref in edges.select(solid) {
+        let edge = edge_ref
+            .resolve(solid)
+            .ok_or(SelectionError::EdgeNotFound(edge_ref))?;
+        let positions =
+            match (edge.curve().global_form(), edge.vertex_positions()) {
+                (Curve::Line(_), Some(positions)) => positions,
+                _ => return Err(SelectionError::UnsupportedEdge(edge_ref)),
+            };
+        selected.entry(edge_key(positions)).or_insert(edge_ref);
+    }
+
+    Ok(selected)
+}
+
+/// A selected edge can't be used
+pub(super) enum SelectionError {
+    EdgeNotFound(EdgeRef),
+    UnsupportedEdge(EdgeRef),
+    TriangleFace(usize),
+}
+
+/// The largest feasible size of blends, checked before blending edges
+///
+/// Returned by [`FilletError::Infeasible`] and
+/// [`ChamferError::Infeasible`], if the requested size is too large for the
+/// faces that the blends trim. See [`max_fillet_radius`] and
+/// [`max_chamfer_distance`], to query the maximum sizes up front.
+///
+/// [`FilletError::Infeasible`]: super::FilletError::Infeasible
+/// [`ChamferError::Infeasible`]: super::ChamferError::Infeasible
+/// [`max_fillet_radius`]: super::max_fillet_radius
+/// [`max_chamfer_distance`]: super::max_chamfer_distance
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeasibilityReport {
+    /// The size that was requested
+    pub requested: Scalar,
+
+    /// The selected edges that the requested size is too large for, each with
+    /// the largest size that would be feasible for it
+    pub exceeded: Vec<(EdgeRef, Scalar)>,
+}
+
+impl FeasibilityReport {
+    /// Check a requested size against the maximum sizes of the selected edges
+    ///
+    /// Returns `None`, if the size is feasible for all of them.
+    pub(super) fn check(
+        requested: Scalar,
+        maxima: &[(EdgeRef, Scalar)],
+    ) -> Option<Self> {
+        let exceeded: Vec<_> = maxima
+            .iter()
+            .copied()
+            .filter(|&(_, max)| requested >= max)
+            .collect();
+
+        (!exceeded.is_empty()).then_some(Self {
+            requested,
+            exceeded,
+        })
+    }
+
+    /// The largest size that is feasible for all offending edges
+    pub fn max(&self) -> Scalar {
+        self.exceeded
+            .iter()
+            .map(|&(_, max)| max)
+            .fold(Scalar::MAX, Scalar::min)
+    }
+}
+
+/// How far trimming moves the vertices of faces, per unit of blend size
+///
+/// Maps a vertex of a face to the displacements it undergoes, each with the
+/// selected edge that causes it. A vertex that is cut off at the end of a
+/// blend moves along both edges of the face at the vertex.
+pub(super) type Setbacks =
+    BTreeMap<(usize, Point<3>), Vec<(Vector<3>, [Point<3>; 2])>>;
+
+/// The largest size of the blends, per selected edge
+///
+/// The vertices of trimmed faces move linearly with the size of the blends.
+/// Each edge of a face therefore vanishes at the size, at which the movement
+/// of its vertices along it has used up its length. A selected edge is limited
+/// by all face edges that its blend shortens. Edges that no blend shortens
+/// don't limit the size, and are missing from the result.
+pub(super) fn max_sizes(
+    planar_faces: &[Option<PlanarFace>],
+    setbacks: &Setbacks,
+) -> BTreeMap<[Point<3>; 2], Scalar> {
+    let mut maxima = BTreeMap::new();
+
+    for (index, face) in planar_faces.iter().enumerate() {
+        let face = match face {
+            Some(face) => face,
+            None => continue,
+        };
+
+        let n = face.exterior.len();
+        for i in 0..n {
+            let [start, end] = [face.exterior[i], face.exterior[(i + 1) % n]];
+            let length = (end - start).magnitude();
+            let direction = (end - start) / length;
+
+            let mut rate = Scalar::ZERO;
+            let mut causes = Vec::new();
+            for (vertex, inward) in [(start, direction), (end, -direction)] {
+                let moves = match setbacks.get(&(index, vertex)) {
+                    Some(moves) => moves,
+                    None => continue,
+                };
+
+                // Only the displacement along this edge shortens it.
+                let along = moves
+                    .iter()
+                    .map(|(displacement, _)| *displacement)
+                    .filter(|displacement| {
+                        displacement.magnitude() > Scalar::ZERO
+                    })
+                    .max_by_key(|displacement| {
+                        displacement.dot(&inward) / displacement.magnitude()
+                    });
+                let contribution = along.map_or(Scalar::ZERO, |displacement| {
+                    displacement.dot(&inward)
+                });
+
+                if contribution > Scalar::ZERO {
+                    rate += contribution;
+                    causes.extend(moves.iter().map(|&(_, key)| key));
+                }
+            }
+
+            if rate <= Scalar::ZERO {
+                continue;
+            }
+            let max = (length - Scalar::from_f64(MIN_EDGE_LENGTH)) / rate;
+            for key in causes {
+                let entry = maxima.entry(key).or_insert(max);
+                *entry = (*entry).min(max);
+            }
+        }
+    }
+
+    maxima
+}
+
+/// Find the two planar faces that meet at an edge
+pub(super) fn adjacent_faces(
+    key: [Point<3>; 2],
+    planar_faces: &[Option<PlanarFace>],
+) -> Option<[usize; 2]> {
+    let adjacent: Vec<_> = planar_faces
+        .iter()
+        .enumerate()
+        .filter(|(_, face)| {
+            face.as_ref().map_or(false, |face| face.has_edge(key))
+        })
+        .map(|(index, _)| index)
+        .collect();
+
+    match adjacent.as_slice() {
+        &[a, b] => Some([a, b]),
+        _ => None,
+    }
+}
+
+/// The faces and selected edges at a vertex
+pub(super) struct VertexStar {
+    /// The faces that the vertex is a corner of
+    pub faces: Vec<usize>,
+
+    /// The selected edges that start or end at the vertex
+    pub selected: Vec<[Point<3>; 2]>,
+}
+
+impl VertexStar {
+    /// Collect the faces and selected edges at a vertex
+    ///
+    /// Returns `None`, if the vertex is part of a face that isn't planar, or of
+    /// an interior cycle.
+    pub fn new(
+        vertex: Point<3>,
+        selected: &BTreeMap<[Point<3>; 2], EdgeRef>,
+        faces: &[Face],
+        planar_faces: &[Option<PlanarFace>],
+    ) -> Option<Self> {
+        let mut faces_at_vertex = Vec::new();
+        for (index, (face, planar_face)) in
+            faces.iter().zip(planar_faces).enumerate()
+        {
+            match planar_face {
+                Some(planar_face) => {
+                    if planar_face.has_interior_vertex(vertex) {
+                        return None;
+                    }
+                    if planar_face.has_vertex(vertex) {
+                        faces_at_vertex.push(index);
+                    }
+                }
+                None => {
+                    if has_vertex(face, vertex) {
+                        return None;
+                    }
+                }
+            }
+        }
+
+        let mut edges_at_vertex = BTreeSet::new();
+        for &index in &faces_at_vertex {
+            let exterior = &planar_faces[index]
+                .as_ref()
+                .expect("Faces at vertex are planar")
+                .exterior;
+            let n = exterior.len();
+            for i in 0..n {
+                let edge = [exterior[i], exterior[(i + 1) % n]];
+                if edge.contains(&vertex) {
+                    edges_at_vertex.insert(edge_key(edge));
+                }
+            }
+        }
+
+        Some(Self {
+            faces: faces_at_vertex,
+            selected: edges_at_vertex
+                .into_iter()
+                .filter(|edge| selected.contains_key(edge))
+                .collect(),
+        })
+    }
+}
+
+/// A planar face, whose cycles are polygons
+///
+/// The exterior winds counter-clockwise around the normal.
+pub(super) struct PlanarFace {
+    pub exterior: Vec<Point<3>>,
+    pub interiors: Vec<Vec<Point<3>>>,
+    pub normal: Vector<3>,
+}
+
+impl PlanarFace {
+    /// Convert a face, if it is planar and bounded by straight edges only
+    ///
+    /// Expects the face to point outward.
+    pub fn from_face(face: &Face) -> Option<Self> {
+        if face.triangles().is_some() {
+            return None;
+        }
+
+        let Surface::SweptCurve(surface) = face.surface();
+        let is_polygon =
+            face.all_cycles()
+                .flat_map(|cycle| &cycle.edges)
+                .all(|edge| {
+                    matches!(edge.curve().global_form(), Curve::Line(_))
+                        && edge.vertices().get().is_some()
+                });
+        if !matches!(surface.curve, Curve::Line(_)) || !is_polygon {
+            return None;
+        }
+
+        let mut exteriors = face.exteriors();
+        let exterior = match (exteriors.next(), exteriors.next()) {
+            (Some(exterior), None) => polygon(exterior),
+            _ => return None,
+        };
+        let interiors = face.interiors().map(polygon).collect();
+
+        Some(Self {
+            normal: vector_area(&exterior).normalize(),
+            exterior,
+            interiors,
+        })
+    }
+
+    pub fn has_edge(&self, key: [Point<3>; 2]) -> bool {
+        let n = self.exterior.len();
+        (0..n).any(|i| {
+            edge_key([self.exterior[i], self.exterior[(i + 1) % n]]) == key
+        })
+    }
+
+    pub fn has_vertex(&self, vertex: Point<3>) -> bool {
+        self.exterior.contains(&vertex)
+    }
+
+    pub fn has_interior_vertex(&self, vertex: Point<3>) -> bool {
+        self.interiors
+            .iter()
+            .any(|interior| interior.contains(&vertex))
+    }
+
+    /// The neighbors of a vertex of the exterior, the previous one first
+    pub fn neighbors(&self, vertex: Point<3>) -> Option<[Point<3>; 2]> {
+        let n = self.exterior.len();
+        let i = self.exterior.iter().position(|&point| point == vertex)?;
+        Some([self.exterior[(i + n - 1) % n], self.exterior[(i + 1) % n]])
+    }
+
+    pub fn centroid(&self) -> Point<3> {
+        let sum = self
+            .exterior
+            .iter()
+            .fold(Vector::from([0., 0., 0.]), |sum, point| sum + point.coords);
+        Point::origin() + sum / self.exterior.len() as f64
+    }
+
+    /// Create the face, with its vertices moved or cut off
+    ///
+    /// Vertices in `trimmed_vertices` are moved to the given point. Vertices in
+    /// `end_cuts` are replaced by the cut, if it belongs to this face.
+    pub fn trim(
+        &self,
+        index: usize,
+        color: Color,
+        trimmed_vertices: &BTreeMap<(usize, Point<3>), Point<3>>,
+        end_cuts: &BTreeMap<Point<3>, EndCut>,
+    ) -> Result<Face, EdgeVanishes> {
+        let n = self.exterior.len();
+
+        // Each point of the boundary is followed by the cut that starts there,
+        // if any.
+        let mut boundary = Vec::new();
+        let mut first_of_vertex = Vec::new();
+        for (i, &vertex) in self.exterior.iter().enumerate() {
+            first_of_vertex.push(boundary.len());
+
+            if let Some(&point) = trimmed_vertices.get(&(index, vertex)) {
+                boundary.push((point, None));
+            } else if let Some(cut) =
+                end_cuts.get(&vertex).filter(|cut| cut.face == index)
+            {
+                // The cut starts on the edge that comes before the vertex.
+                let previous = self.exterior[(i + n - 1) % n] - vertex;
+                let [a, b] = cut.points;
+                let distance = |point: Point<3>| {
+                    (point - vertex).cross(&previous).magnitude()
+                };
+                let [first, last] = if distance(a) <= distance(b) {
+                    [a, b]
+                } else {
+                    [b, a]
+                };
+
+                boundary.push((first, Some(cut)));
+                boundary.push((last, None));
+            } else {
+                boundary.push((vertex, None));
+            }
+        }
+
+        // Trimming must not make the edges of the face vanish, or flip them.
+        for i in 0..n {
+            let [start, end] = [self.exterior[i], self.exterior[(i + 1) % n]];
+            let next = (i + 1) % n;
+            let last_of_vertex = if next == 0 {
+                boundary.len() - 1
+            } else {
+                first_of_vertex[next] - 1
+            };
+
+            let length = (end - start).magnitude();
+            let trimmed = (boundary[first_of_vertex[next]].0
+                - boundary[last_of_vertex].0)
+                .dot(&((end - start) / length));
+            if trimmed < Scalar::from_f64(MIN_EDGE_LENGTH) {
+                return Err(EdgeVanishes { length });
+            }
+        }
+
+        let frame = Frame::new(self.exterior[0], self.exterior[1], self.normal);
+
+        let mut edges = Vec::new();
+        for (i, &(point, cut)) in boundary.iter().enumerate() {
+            let next = boundary[(i + 1) % boundary.len()].0;
+
+            let edge = match cut {
+                Some(EndCut {
+                    points,
+                    arc: Some(CutArc { circle, angle }),
+                    ..
+                }) => {
+                    let local = Circle {
+                        center: frame.point(circle.center),
+                        a: frame.vector(circle.a),
+                        b: frame.vector(circle.b),
+                    };
+                    let arc = arc_edge(
+                        Curve::Circle(local),
+                        *circle,
+                        *angle,
+                        *points,
+                    );
+
+                    // The arc runs from the first to the second point of the
+                    // cut, but the face might run along it the other way.
+                    if point == points[0] {
+                        arc
+                    } else {
+                        reverse_edge(&arc)
+                    }
+                }
+                _ => line_edge(
+                    [point, next].map(|point| frame.point(point)),
+                    [point, next],
+                ),
+            };
+            edges.push(edge);
+        }
+
+        let interiors = self
+            .interiors
+            .iter()
+            .map(|interior| polygon_cycle(interior, &frame));
+
+        Ok(Face::new(
+            frame.surface(),
+            [Cycle { edges }],
+            interiors,
+            color,
+        ))
+    }
+}
+
+/// Trimming a face would make one of its edges vanish
+pub(super) struct EdgeVanishes {
+    /// The length of the edge, before trimming
+    pub length: Scalar,
+}
+
+/// The corner of a face at the end of a replaced edge, which is cut off
+pub(super) struct EndCut {
+    /// The face whose corner is cut off
+    pub face: usize,
+
+    /// The points where the cut meets the edges of the face
+    pub points: [Point<3>; 2],
+
+    /// The arc that the cut follows, if it isn't straight
+    pub arc: Option<CutArc>,
+}
+
+/// An arc, from the first to the second point of an [`EndCut`]
+#[derive(Clone, Copy)]
+pub(super) struct CutArc {
+    pub circle: Circle<3>,
+    pub angle: Scalar,
+}
+
+/// An orthonormal coordinate system in a plane
+pub(super) struct Frame {
+    origin: Point<3>,
+    u: Vector<3>,
+    v: Vector<3>,
+}
+
+impl Frame {
+    pub fn new(origin: Point<3>, towards: Point<3>, normal: Vector<3>) -> Self {
+        let u = (towards - origin).normalize();
+        let v = normal.cross(&u);
+        Self { origin, u, v }
+    }
+
+    pub fn surface(&self) -> Surface {
+        Surface::SweptCurve(SweptCurve {
+            curve: Curve::Line(Line {
+                origin: self.origin,
+                direction: self.u,
+            }),
+            path: self.v,
+        })
+    }
+
+    pub fn point(&self, point: Point<3>) -> Point<2> {
+        Point::origin() + self.vector(point - self.origin)
+    }
+
+    pub fn vector(&self, vector: Vector<3>) -> Vector<2> {
+        Vector::from([vector.dot(&self.u), vector.dot(&self.v)])
+    }
+}
+
+/// Create a planar face from a polygon
+///
+/// The polygon is wound, so the face points in the direction of `outward`.
+pub(super) fn polygon_face(
+    mut points: Vec<Point<3>>,
+    outward: Vector<3>,
+    color: Color,
+) -> Face {
+    let mut normal = vector_area(&points);
+    if normal.dot(&outward) < Scalar::ZERO {
+        points.reverse();
+        normal = -normal;
+    }
+
+    let frame = Frame::new(points[0], points[1], normal.normalize());
+    Face::new(frame.surface(), [polygon_cycle(&points, &frame)], [], color)
+}
+
+pub(super) fn line_edge(local: [Point<2>; 2], global: [Point<3>; 2]) -> Edge {
+    let curve = Local::new(
+        Curve::line_from_points(local),
+        Curve::line_from_points(global),
+    );
+
+    let [a, b] = global.map(GlobalVertex::from_position);
+    let vertices = VerticesOfEdge::from_vertices([
+        Vertex::new(Point::from([0.]), a),
+        Vertex::new(Point::from([1.]), b),
+    ]);
+
+    Edge::new(curve, vertices)
+}
+
+pub(super) fn arc_edge(
+    local: Curve<2>,
+    global: Circle<3>,
+    angle: Scalar,
+    points: [Point<3>; 2],
+) -> Edge {
+    let curve = Local::new(local, Curve::Circle(global));
+
+    let [a, b] = points.map(GlobalVertex::from_position);
+    let vertices = VerticesOfEdge::from_vertices([
+        Vertex::new(Point::from([Scalar::ZERO]), a),
+        Vertex::new(Point::from([angle]), b),
+    ]);
+
+    Edge::new(curve, vertices)
+}
+
+/// The positions of all vertices of a face
+///
+/// Identifies a face of a solid, regardless of its orientation.
+pub(super) fn face_vertices(face: &Face) -> BTreeSet<Point<3>> {
+    face.all_cycles()
+        .flat_map(|cycle| &cycle.edges)
+        .filter_map(Edge::vertex_positions)
+        .flatten()
+        .collect()
+}
+
+pub(super) fn edge_key([a, b]: [Point<3>; 2]) -> [Point<3>; 2] {
+    if a <= b {
+        [a, b]
+    } else {
+        [b, a]
+    }
+}
+
+/// The vertices of a cycle of straight edges, in the order of the cycle
+fn polygon(cycle: &Cycle) -> Vec<Point<3>> {
+    let segments: Vec<_> = cycle
+        .edges
+        .iter()
+        .filter_map(Edge::vertex_positions)
+        .collect();
+
+    // Edges don't necessarily point in the direction of the cycle, so each
+    // contributes the vertex that it doesn't share with the next one.
+    segments
+        .iter()
+        .enumerate()
+        .map(|(i, &[a, b])| {
+            let next = segments[(i + 1) % segments.len()];
+            if next.contains(&b) {
+                a
+            } else {
+                b
+            }
+        })
+        .collect()
+}
+
+fn polygon_cycle(points: &[Point<3>], frame: &Frame) -> Cycle {
+    let n = points.len();
+    let edges = (0..n)
+        .map(|i| {
+            let points = [points[i], points[(i + 1) % n]];
+            line_edge(points.map(|point| frame.point(point)), points)
+        })
+        .collect();
+
+    Cycle { edges }
+}
+
+/// Twice the vector area of a polygon
+///
+/// Points in the direction that the polygon winds counter-clockwise around.
+pub(super) fn vector_area(points: &[Point<3>]) -> Vector<3> {
+    let mut area = Vector::from([0., 0., 0.]);
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        area = area + a.coords.cross(&b.coords);
+    }
+    area
+}
+
+fn has_vertex(face: &Face, vertex: Point<3>) -> bool {
+    match face.triangles() {
+        Some(triangles) => triangles
+            .iter()
+            .any(|(triangle, _)| triangle.points().contains(&vertex)),
+        None => face
+            .all_cycles()
+            .flat_map(|cycle| &cycle.edges)
+            .filter_map(Edge::vertex_positions)
+            .any(|positions| positions.contains(&vertex)),
+    }
+}
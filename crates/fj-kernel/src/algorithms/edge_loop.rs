@@ -0,0 +1,396 @@
+use fj_math::{Point, Scalar, Vector};
+
+use crate::objects::{Edge, Solid};
+
+use super::EdgeRef;
+
+/// Find the loop of tangent-connected edges that contains an edge
+///
+/// Starting from `seed`, walks across the vertices of the solid to adjacent
+/// edges, as long as the direction at the shared vertex changes by less than
+/// `max_angle` (in radians). The walk continues in both directions, until
+/// either the loop closes, or no edge qualifies as a continuation.
+///
+/// Adjacent faces each have their own copy of a shared edge. Only the first
+/// copy (in the order of [`Solid::faces`]) is returned.
+///
+/// If more than one edge qualifies as a continuation at a vertex, it is not
+/// clear which way the loop continues. The walk stops there, and the vertex is
+/// reported in [`EdgeLoop::branch_points`].
+///
+/// # Panics
+///
+/// Panics, if `seed` doesn't refer to an edge of `solid`.
+pub fn edge_loop_from(
+    solid: &Solid,
+    seed: &EdgeRef,
+    max_angle: Scalar,
+) -> EdgeLoop {
+    let seed_edge = seed
+        .resolve(solid)
+        .expect("Seed must refer to an edge of the solid");
+
+    let edges = unique_edges(solid);
+
+    let seed_edge = SolidEdge::new(*seed, seed_edge);
+    let seed = match edges.iter().position(|edge| edge.is_same_as(&seed_edge)) {
+        Some(seed) => seed,
+        None => {
+            // The edge has no vertices, which means it is closed, and can't
+            // connect to any other edges.
+            return EdgeLoop {
+                edges: vec![*seed],
+                is_closed: true,
+                branch_points: Vec::new(),
+            };
+        }
+    };
+
+    let mut edge_loop = EdgeLoop {
+        edges: Vec::new(),
+        is_closed: false,
+        branch_points: Vec::new(),
+    };
+
+    let mut visited = vec![seed];
+
+    // Walk forward from the end of the seed edge.
+    let mut forward = Vec::new();
+    let mut current = seed;
+    let mut at_end = true;
+    loop {
+        match continuation(&edges, current, at_end, max_angle) {
+            Continuation::Edge { index, .. } if index == seed => {
+                edge_loop.is_closed = true;
+                break;
+            }
+            Continuation::Edge {
+                index,
+                enters_at_start,
+            } => {
+                if visited.contains(&index) {
+                    break;
+                }
+
+                visited.push(index);
+                forward.push(index);
+                current = index;
+                at_end = enters_at_start;
+            }
+            Continuation::Branch(point) => {
+                edge_loop.branch_points.push(point);
+                break;
+            }
+            Continuation::None => break,
+        }
+    }
+
+    // If the loop isn't closed, walk backward from the start of the seed edge.
+    let mut backward = Vec::new();
+    if !edge_loop.is_closed {
+        let mut current = seed;
+        let mut at_end = false;
+        loop {
+            match continuation(&edges, current, at_end, max_angle) {
+                Continuation::Edge {
+                    index,
+                    enters_at_start,
+                } => {
+                    if visited.contains(&index) {
+                        break;
+                    }
+
+                    visited.push(index);
+                    backward.push(index);
+                    current = index;
+                    at_end = enters_at_start;
+                }
+                Continuation::Branch(point) => {
+                    edge_loop.branch_points.push(point);
+                    break;
+                }
+                Continuation::None => break,
+            }
+        }
+    }
+
+    edge_loop.edges = backward
+        .into_iter()
+        .rev()
+        .chain([seed])
+        .chain(forward)
+        .map(|index| edges[index].edge_ref)
+        .collect();
+
+    edge_loop
+}
+
+/// A loop of tangent-connected edges
+///
+/// Returned by [`edge_loop_from`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EdgeLoop {
+    /// The edges of the loop, in order
+    pub edges: Vec<EdgeRef>,
+
+    /// Indicates whether the loop is closed
+    pub is_closed: bool,
+
+    /// The vertices, at which the walk stopped due to multiple continuations
+    pub branch_points: Vec<Point<3>>,
+}
+
+/// The maximum distance between vertices that are considered identical
+///
+/// Vertices that are shared by edges are not necessarily at the exact same
+/// position, if they were computed differently (like the end of an arc and the
+/// start of a line segment).
+const IDENTICAL_MAX_DISTANCE: f64 = 1e-9;
+
+/// An edge of a solid, with the information required to walk across it
+struct SolidEdge {
+    edge_ref: EdgeRef,
+
+    /// The positions of the start and end vertex
+    positions: [Point<3>; 2],
+
+    /// The tangents at the start and end vertex, in the direction of the curve
+    tangents: [Vector<3>; 2],
+
+    /// The point in the middle of the edge
+    ///
+    /// Used to distinguish edges that connect the same vertices.
+    midpoint: Point<3>,
+}
+
+impl SolidEdge {
+    fn new(edge_ref: EdgeRef, edge: &Edge) -> Option<Self> {
+        let [start, end] = edge.vertices().get()?;
+        let curve = edge.curve().global_form();
+
+        let positions = [start, end].map(|vertex| vertex.global().position());
+        let tangents = [start, end]
+            .map(|vertex| curve.tangent_at(vertex.position()).normalize());
+        let midpoint =
+            curve.point_from_curve_coords(Point::from([(start.position().t
+                + end.position().t)
+                / 2.]));
+
+        Some(Self {
+            edge_ref,
+            positions,
+            tangents,
+            midpoint,
+        })
+    }
+
+    fn is_same_as(&self, other: &Option<Self>) -> bool {
+        let other = match other {
+            Some(other) => other,
+            None => return false,
+        };
+
+        let [a, b] = self.positions;
+        let [c, d] = other.positions;
+
+        let same_vertices = (is_identical(a, c) && is_identical(b, d))
+            || (is_identical(a, d) && is_identical(b, c));
+
+        same_vertices && is_identical(self.midpoint, other.midpoint)
+    }
+}
+
+enum Continuation {
+    Edge { index: usize, enters_at_start: bool },
+    Branch(Point<3>),
+    None,
+}
+
+/// Find the edge that continues the edge at `current` across one of its ends
+///
+/// If `at_end` is `true`, the walk leaves `current` across its end vertex,
+/// otherwise across its start vertex.
+fn continuation(
+    edges: &[SolidEdge],
+    current: usize,
+    at_end: bool,
+    max_angle: Scalar,
+) -> Continuation {
+    let edge = &edges[current];
+
+    let (vertex, direction) = if at_end {
+        (edge.positions[1], edge.tangents[1])
+    } else {
+        (edge.positions[0], edge.tangents[0] * -1.)
+    };
+
+    let mut candidates = Vec::new();
+    for (index, other) in edges.iter().enumerate() {
+        if index == current {
+            continue;
+        }
+
+        for (enters_at_start, i, sign) in [(true, 0, 1.), (false, 1, -1.)] {
+            if !is_identical(other.positions[i], vertex) {
+                continue;
+            }
+
+            let other_direction = other.tangents[i] * sign;
+            let cos = direction
+                .dot(&other_direction)
+                .max(-Scalar::ONE)
+                .min(Scalar::ONE);
+
+            if cos.acos() < max_angle {
+                candidates.push(Continuation::Edge {
+                    index,
+                    enters_at_start,
+                });
+            }
+        }
+    }
+
+    match candidates.len() {
+        0 => Continuation::None,
+        1 => candidates.remove(0),
+        _ => Continuation::Branch(vertex),
+    }
+}
+
+/// Collect the edges of a solid, skipping copies of shared edges
+fn unique_edges(solid: &Solid) -> Vec<SolidEdge> {
+    let mut edges: Vec<SolidEdge> = Vec::new();
+
+    for (face_index, face) in solid.faces().enumerate() {
+        if face.triangles().is_some() {
+            continue;
+        }
+
+        for (cycle_index, cycle) in face.all_cycles().enumerate() {
+            for (edge_index, edge) in cycle.edges.iter().enumerate() {
+                let edge_ref = EdgeRef {
+                    face: face_index,
+                    cycle: cycle_index,
+                    edge: edge_index,
+                };
+                let edge = SolidEdge::new(edge_ref, edge);
+
+                let is_copy = edges.iter().any(|other| other.is_same_as(&edge));
+                if let (Some(edge), false) = (edge, is_copy) {
+                    edges.push(edge);
+                }
+            }
+        }
+    }
+
+    edges
+}
+
+fn is_identical(a: Point<3>, b: Point<3>) -> bool {
+    (a - b).magnitude() <= Scalar::from_f64(IDENTICAL_MAX_DISTANCE)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Arc, Scalar};
+
+    use crate::{
+        algorithms::{sweep, EdgeRef, EdgeSelector, Tolerance},
+        objects::{Cycle, Edge, Face, Sketch, Solid, Surface},
+    };
+
+    use super::edge_loop_from;
+
+    #[test]
+    fn tangent_fillets() -> anyhow::Result<()> {
+        let solid = rounded_block(0.5)?;
+        let seed = top_edge(&solid);
+
+        let edge_loop = edge_loop_from(&solid, &seed, degrees(30.));
+        assert_eq!(edge_loop.edges.len(), 8);
+        assert!(edge_loop.is_closed);
+        assert!(edge_loop.branch_points.is_empty());
+
+        // The fillets are tangent to the lines, so even a small angle selects
+        // the whole loop.
+        let edge_loop = edge_loop_from(&solid, &seed, degrees(1.));
+        assert_eq!(edge_loop.edges.len(), 8);
+
+        Ok(())
+    }
+
+    #[test]
+    fn kinked_corners() -> anyhow::Result<()> {
+        // Corners with a larger radius meet the lines at an angle of about 9°.
+        let solid = rounded_block(0.6)?;
+        let seed = top_edge(&solid);
+
+        let edges = EdgeSelector::loop_from(seed, degrees(30.)).select(&solid);
+        assert_eq!(edges.len(), 8);
+
+        let edge_loop = edge_loop_from(&solid, &seed, degrees(1.));
+        assert_eq!(edge_loop.edges.len(), 1);
+        assert!(!edge_loop.is_closed);
+
+        Ok(())
+    }
+
+    /// A block, whose vertical edges are rounded with the given radius
+    ///
+    /// The corner arcs always start and end at the same points. Only a radius
+    /// of `0.5` makes them tangent to the adjacent lines.
+    fn rounded_block(radius: f64) -> anyhow::Result<Solid> {
+        let surface = Surface::xy_plane();
+
+        let line = |a: [f64; 2], b: [f64; 2]| {
+            Edge::line_segment_from_points(&surface, [a, b])
+        };
+        let arc = |a: [f64; 2], b: [f64; 2]| {
+            Edge::arc(&surface, Arc::from_endpoints(a, b, radius, false, true))
+        };
+
+        let edges = vec![
+            line([0.5, 0.], [2.5, 0.]),
+            arc([2.5, 0.], [3., 0.5]),
+            line([3., 0.5], [3., 2.5]),
+            arc([3., 2.5], [2.5, 3.]),
+            line([2.5, 3.], [0.5, 3.]),
+            arc([0.5, 3.], [0., 2.5]),
+            line([0., 2.5], [0., 0.5]),
+            arc([0., 0.5], [0.5, 0.]),
+        ];
+        let face =
+            Face::new(surface.clone(), [Cycle { edges }], [], [255, 0, 0, 255]);
+
+        Ok(sweep(
+            Sketch::from_faces([face]),
+            [0., 0., 1.],
+            Tolerance::from_scalar(0.01)?,
+            [255, 0, 0, 255],
+        )?)
+    }
+
+    /// Find the first edge of the top face
+    fn top_edge(solid: &Solid) -> EdgeRef {
+        let face = solid
+            .faces()
+            .position(|face| {
+                face.all_cycles()
+                    .flat_map(|cycle| &cycle.edges)
+                    .filter_map(|edge| edge.vertex_positions())
+                    .flatten()
+                    .all(|point| point.z == Scalar::ONE)
+            })
+            .expect("Solid has no top face");
+
+        EdgeRef {
+            face,
+            cycle: 0,
+            edge: 0,
+        }
+    }
+
+    fn degrees(angle: f64) -> Scalar {
+        Scalar::from_f64(angle.to_radians())
+    }
+}
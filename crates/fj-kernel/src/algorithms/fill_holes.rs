@@ -0,0 +1,299 @@
+use fj_interop::{
+    half_edge::HalfEdgeMesh,
+    mesh::{Color, Mesh},
+};
+use fj_math::{Point, Scalar, Vector};
+
+/// The color of triangles that [`fill_holes`] inserts into a mesh
+pub const HOLE_PATCH_COLOR: Color = [255, 0, 255, 255];
+
+/// Fill the holes in a triangle mesh
+///
+/// Detects loops of boundary edges (edges that belong to only one triangle)
+/// and closes each of them with a patch of triangles, colored with
+/// [`HOLE_PATCH_COLOR`]. The patch triangles are oriented consistently with
+/// the triangles around the hole.
+///
+/// Each loop is triangulated using ear clipping, on the plane that best fits
+/// its points. If that fails, for example because the loop's projection onto
+/// that plane intersects itself, a triangle fan is used instead. Loops that
+/// are not planar still get a valid patch, but for loops that are far from
+/// convex, the patch might not look pretty.
+///
+/// Loops whose perimeter exceeds `max_hole_perimeter` are skipped, as they are
+/// probably intentional openings. Chains of boundary edges that don't form a
+/// closed loop are ignored.
+pub fn fill_holes(
+    mesh: &mut Mesh<Point<3>>,
+    max_hole_perimeter: Option<Scalar>,
+) -> FillReport {
+    let mut report = FillReport::default();
+
+    for points in boundary_loops(mesh) {
+        let perimeter = perimeter(&points);
+        let hole = HoleLoop { points, perimeter };
+
+        if let Some(max_hole_perimeter) = max_hole_perimeter {
+            if perimeter > max_hole_perimeter {
+                report.skipped.push(hole);
+                continue;
+            }
+        }
+
+        for triangle in triangulate_loop(&hole.points) {
+            mesh.push_triangle(triangle, HOLE_PATCH_COLOR);
+        }
+
+        report.filled.push(hole);
+    }
+
+    report
+}
+
+/// The result of [`fill_holes`]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FillReport {
+    /// The loops that were filled
+    pub filled: Vec<HoleLoop>,
+
+    /// The loops that were skipped, because their perimeter was too large
+    pub skipped: Vec<HoleLoop>,
+}
+
+/// A loop of boundary edges in a mesh
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HoleLoop {
+    /// The points of the loop, in the orientation of the patch that fills it
+    pub points: Vec<Point<3>>,
+
+    /// The length of the loop
+    pub perimeter: Scalar,
+}
+
+/// Find the closed loops of boundary edges in a mesh
+///
+/// Each loop is returned in the orientation that a patch filling it needs,
+/// which is opposite to the orientation of its edges in the adjacent
+/// triangles.
+fn boundary_loops(mesh: &Mesh<Point<3>>) -> Vec<Vec<Point<3>>> {
+    let mesh = HalfEdgeMesh::from_mesh(mesh);
+
+    mesh.boundary_loops()
+        .into_iter()
+        .map(|vertices| {
+            vertices
+                .into_iter()
+                .rev()
+                .map(|vertex| mesh.point(vertex))
+                .collect()
+        })
+        .collect()
+}
+
+fn perimeter(points: &[Point<3>]) -> Scalar {
+    let mut perimeter = Scalar::ZERO;
+
+    for (i, &a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        perimeter += (b - a).magnitude();
+    }
+
+    perimeter
+}
+
+fn triangulate_loop(points: &[Point<3>]) -> Vec<[Point<3>; 3]> {
+    let indices = project_onto_best_fit_plane(points)
+        .and_then(|projected| ear_clip(&projected))
+        .unwrap_or_else(|| {
+            (1..points.len() - 1).map(|i| [0, i, i + 1]).collect()
+        });
+
+    indices
+        .into_iter()
+        .map(|triangle| triangle.map(|i| points[i]))
+        .collect()
+}
+
+/// Project the points onto the plane that best fits them
+///
+/// The plane's normal is computed using Newell's method, so the projected
+/// polygon runs counter-clockwise. Returns `None`, if the points don't define
+/// a plane.
+fn project_onto_best_fit_plane(points: &[Point<3>]) -> Option<Vec<Point<2>>> {
+    let centroid = points
+        .iter()
+        .fold(Vector::from([0., 0., 0.]), |sum, point| sum + point.coords)
+        / points.len() as f64;
+    let centroid = Point { coords: centroid };
+
+    let mut normal = Vector::from([0., 0., 0.]);
+    for (i, &a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        normal = normal + (a - centroid).cross(&(b - centroid));
+    }
+    if normal.magnitude() <= Scalar::from_f64(f64::EPSILON) {
+        return None;
+    }
+    let normal = normal.normalize();
+
+    let u = points
+        .iter()
+        .map(|&point| {
+            let v = point - centroid;
+            v - normal * v.dot(&normal)
+        })
+        .max_by_key(|v| v.magnitude())?;
+    if u.magnitude() <= Scalar::from_f64(f64::EPSILON) {
+        return None;
+    }
+    let u = u.normalize();
+    let v = normal.cross(&u);
+
+    let projected = points
+        .iter()
+        .map(|&point| {
+            let w = point - centroid;
+            Point::from([w.dot(&u), w.dot(&v)])
+        })
+        .collect();
+
+    Some(projected)
+}
+
+/// Triangulate a counter-clockwise polygon using ear clipping
+///
+/// Returns `None`, if no ear can be found, which happens if the polygon
+/// intersects itself.
+fn ear_clip(points: &[Point<2>]) -> Option<Vec<[usize; 3]>> {
+    let mut remaining: Vec<_> = (0..points.len()).collect();
+    let mut triangles = Vec::new();
+
+    while remaining.len() > 3 {
+        let n = remaining.len();
+
+        let ear = (0..n).find(|&i| {
+            let [a, b, c] = [
+                remaining[(i + n - 1) % n],
+                remaining[i],
+                remaining[(i + 1) % n],
+            ];
+            let triangle = [points[a], points[b], points[c]];
+
+            is_counter_clockwise(triangle)
+                && remaining.iter().all(|&j| {
+                    j == a
+                        || j == b
+                        || j == c
+                        || !triangle_contains(triangle, points[j])
+                })
+        })?;
+
+        triangles.push([
+            remaining[(ear + n - 1) % n],
+            remaining[ear],
+            remaining[(ear + 1) % n],
+        ]);
+        remaining.remove(ear);
+    }
+
+    triangles.push([remaining[0], remaining[1], remaining[2]]);
+
+    Some(triangles)
+}
+
+fn is_counter_clockwise([a, b, c]: [Point<2>; 3]) -> bool {
+    cross(b - a, c - b) > Scalar::ZERO
+}
+
+fn triangle_contains([a, b, c]: [Point<2>; 3], point: Point<2>) -> bool {
+    cross(b - a, point - a) >= Scalar::ZERO
+        && cross(c - b, point - b) >= Scalar::ZERO
+        && cross(a - c, point - c) >= Scalar::ZERO
+}
+
+fn cross(a: Vector<2>, b: Vector<2>) -> Scalar {
+    a.u * b.v - a.v * b.u
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::{half_edge::HalfEdgeMesh, mesh::Mesh};
+    use fj_math::{Point, Scalar};
+
+    use super::{fill_holes, HOLE_PATCH_COLOR};
+
+    #[test]
+    fn fill_cube_with_missing_face() {
+        let mut mesh = cube_without_top();
+
+        let report = fill_holes(&mut mesh, None);
+
+        assert_eq!(report.filled.len(), 1);
+        assert!(report.skipped.is_empty());
+        assert_eq!(report.filled[0].perimeter, Scalar::from_f64(4.));
+
+        assert!(is_watertight(&mesh));
+        assert_eq!(
+            mesh.triangles()
+                .filter(|triangle| triangle.color == HOLE_PATCH_COLOR)
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn skip_large_holes() {
+        let mut mesh = cube_without_top();
+
+        let report = fill_holes(&mut mesh, Some(Scalar::from_f64(3.)));
+
+        assert!(report.filled.is_empty());
+        assert_eq!(report.skipped.len(), 1);
+        assert!(!is_watertight(&mesh));
+    }
+
+    /// A unit cube with consistently oriented triangles, missing its top face
+    fn cube_without_top() -> Mesh<Point<3>> {
+        let p = |x, y, z| Point::from([x, y, z]);
+
+        let [a, b, c, d] =
+            [p(0., 0., 0.), p(1., 0., 0.), p(1., 1., 0.), p(0., 1., 0.)];
+        let [e, f, g, h] =
+            [p(0., 0., 1.), p(1., 0., 1.), p(1., 1., 1.), p(0., 1., 1.)];
+
+        let triangles = [
+            // bottom
+            [a, c, b],
+            [a, d, c],
+            // front
+            [a, b, f],
+            [a, f, e],
+            // right
+            [b, c, g],
+            [b, g, f],
+            // back
+            [c, d, h],
+            [c, h, g],
+            // left
+            [d, a, e],
+            [d, e, h],
+        ];
+
+        let mut mesh = Mesh::new();
+        for triangle in triangles {
+            mesh.push_triangle(triangle, [255, 0, 0, 255]);
+        }
+
+        mesh
+    }
+
+    /// Check that every edge is shared by two triangles, in opposite directions
+    fn is_watertight(mesh: &Mesh<Point<3>>) -> bool {
+        let mesh = HalfEdgeMesh::from_mesh(mesh);
+
+        mesh.check_integrity().is_ok()
+            && mesh
+                .half_edges()
+                .all(|half_edge| !mesh.is_boundary_edge(half_edge))
+    }
+}
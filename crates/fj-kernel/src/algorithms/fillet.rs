@@ -0,0 +1,923 @@
+use std::collections::BTreeMap;
+
+use fj_interop::mesh::Color;
+use fj_math::{Circle, Line, Point, Scalar, Triangle, Vector};
+
+use crate::objects::{Curve, Cycle, Edge, Face, Solid, Surface, SweptCurve};
+
+use super::{
+    approx::approx_edge_points,
+    edge_blend::{
+        adjacent_faces, arc_edge, line_edge, max_sizes, select_straight_edges,
+        CutArc, EdgeVanishes, EndCut, FeasibilityReport, PlanarFace,
+        SelectionError, Setbacks, VertexStar, MIN_EDGE_LENGTH,
+    },
+    reverse::reverse_edge,
+    EdgeRef, EdgeSelector, Tolerance,
+};
+
+/// The maximum cosine of the angle between a fillet and the face at its end
+const MAX_END_FACE_COS: f64 = 1e-9;
+
+/// The fraction of the maximum radius, that merged fillets are reduced to
+///
+/// Keeps a sliver of the faces between the fillets, so the trimmed faces are
+/// still valid.
+const MERGE_FACTOR: f64 = 0.999;
+
+/// Round edges of a solid
+///
+/// Replaces each selected edge with a cylindrical face of the given radius,
+/// that is tangent to both faces that meet at the edge. Those faces are
+/// trimmed back to where the cylinder touches them.
+///
+/// Only straight, convex edges between two planar faces can be filleted. At
+/// each end of a filleted edge, either
+///
+/// - the edge ends at a face that is perpendicular to it, and no other
+///   filleted edge ends there. The face gets a rounded corner.
+/// - three filleted edges meet at a vertex of three faces, like at the corner
+///   of a cube. The corner is rounded by a patch of a sphere.
+///
+/// Other vertices result in [`FilletError::UnsupportedVertex`]. If the faces
+/// are too small for `radius`, so trimming them would leave edges of zero or
+/// negative length, [`FilletError::Infeasible`] is returned, listing the
+/// largest radius that each offending edge allows (see [`max_fillet_radius`]).
+/// Use [`fillet_with_options`] to merge such fillets instead.
+///
+/// Solids with faces that are made of triangles can't be filleted. They result
+/// in [`FilletError::UnsupportedFace`].
+///
+/// # Implementation Note
+///
+/// There is no spherical surface yet, so the corner patches are made up of
+/// triangles, approximated within `tolerance`. Their boundary matches the
+/// approximation of the adjacent cylindrical faces only at that tolerance.
+pub fn fillet(
+    solid: Solid,
+    edges: &EdgeSelector,
+    radius: impl Into<Scalar>,
+    tolerance: Tolerance,
+) -> Result<Solid, FilletError> {
+    fillet_with_options(
+        solid,
+        edges,
+        radius,
+        tolerance,
+        FilletOptions::default(),
+    )
+}
+
+/// Round edges of a solid, with options
+///
+/// Like [`fillet`], but the behavior in edge cases can be adjusted. See
+/// [`FilletOptions`].
+pub fn fillet_with_options(
+    solid: Solid,
+    edges: &EdgeSelector,
+    radius: impl Into<Scalar>,
+    tolerance: Tolerance,
+    options: FilletOptions,
+) -> Result<Solid, FilletError> {
+    let mut radius = radius.into();
+    if radius <= Scalar::ZERO {
+        return Err(FilletError::InvalidRadius(radius));
+    }
+
+    let maxima = max_fillet_radius(&solid, edges)?;
+    if let Some(report) = FeasibilityReport::check(radius, &maxima) {
+        if !options.allow_topology_change {
+            return Err(FilletError::Infeasible(report));
+        }
+        radius = report.max() * MERGE_FACTOR;
+    }
+
+    let selected = select_straight_edges(&solid, edges)?;
+
+    let faces: Vec<_> = solid
+        .reoriented_outward()
+        .into_faces()
+        .into_iter()
+        .collect();
+    let planar_faces: Vec<_> =
+        faces.iter().map(PlanarFace::from_face).collect();
+
+    let vertices = vertex_kinds(&selected, &faces, &planar_faces, radius)?;
+
+    let mut blends = Vec::new();
+    for (&key, &edge_ref) in &selected {
+        let adjacent = adjacent_faces(key, &planar_faces)
+            .ok_or(FilletError::UnsupportedEdge(edge_ref))?;
+
+        blends.push(Blend::new(
+            key,
+            adjacent,
+            &planar_faces,
+            &vertices,
+            radius,
+            faces[adjacent[0]].color(),
+            edge_ref,
+        )?);
+    }
+
+    // Where faces are trimmed, their vertices move to the edges of the
+    // fillets. Faces at the end of a fillet get a rounded corner instead.
+    let mut trimmed_vertices = BTreeMap::new();
+    let mut end_cuts = BTreeMap::new();
+    for blend in &blends {
+        for (index, end) in blend.ends.iter().enumerate() {
+            for (&face, &point) in blend.faces.iter().zip(&end.points) {
+                trimmed_vertices.insert((face, end.vertex), point);
+            }
+            if let VertexKind::End { face } = vertices[&end.vertex] {
+                end_cuts.insert(
+                    end.vertex,
+                    EndCut {
+                        face,
+                        points: end.points,
+                        arc: Some(CutArc {
+                            circle: blend.circle(index),
+                            angle: blend.angle,
+                        }),
+                    },
+                );
+            }
+        }
+    }
+
+    let mut result = Vec::new();
+    for (index, (face, planar_face)) in
+        faces.iter().zip(&planar_faces).enumerate()
+    {
+        let is_trimmed = planar_face.as_ref().map_or(false, |planar_face| {
+            planar_face.exterior.iter().any(|vertex| {
+                trimmed_vertices.contains_key(&(index, *vertex))
+                    || end_cuts
+                        .get(vertex)
+                        .map_or(false, |cut| cut.face == index)
+            })
+        });
+
+        match planar_face {
+            Some(planar_face) if is_trimmed => {
+                let face = planar_face
+                    .trim(index, face.color(), &trimmed_vertices, &end_cuts)
+                    .map_err(|EdgeVanishes { length }| {
+                        FilletError::RadiusTooLarge { radius, length }
+                    })?;
+                result.push(face);
+            }
+            _ => result.push(face.clone()),
+        }
+    }
+
+    for blend in &blends {
+        result.push(blend.face());
+    }
+
+    for (&vertex, kind) in &vertices {
+        if let VertexKind::Corner {
+            center,
+            faces: corner_faces,
+        } = kind
+        {
+            let arcs: Vec<_> = blends
+                .iter()
+                .flat_map(|blend| {
+                    blend
+                        .ends
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, end)| end.vertex == vertex)
+                        .map(|(index, _)| blend.arc_edge(index))
+                })
+                .collect();
+            let color = faces[corner_faces[0]].color();
+
+            result.push(corner_patch(
+                &arcs,
+                *center,
+                corner_faces,
+                &planar_faces,
+                radius,
+                tolerance,
+                color,
+            ));
+        }
+    }
+
+    Ok(Solid::from_faces(result))
+}
+
+/// Options for [`fillet_with_options`]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FilletOptions {
+    /// Merge fillets that are too large for the faces between them
+    ///
+    /// By default, a radius that would consume an edge of a trimmed face
+    /// results in [`FilletError::Infeasible`]. If this is `true`, the radius is
+    /// reduced instead, so the fillets meet where the faces between them run
+    /// out, leaving only a sliver of those faces.
+    ///
+    /// Removing the consumed faces entirely isn't supported yet, so the
+    /// topology of the result is that of the fillets at the reduced radius.
+    pub allow_topology_change: bool,
+}
+
+/// Compute the largest radius that each selected edge can be filleted with
+///
+/// The faces that meet at an edge limit the radius, as do other fillets that
+/// trim the same faces. For example, fillets at both ends of an edge can each
+/// take up to half of it. A fillet is feasible, if its radius is smaller than
+/// the maximum of every selected edge.
+///
+/// Only planar faces are supported, for which the limits are exact. Edges that
+/// don't limit the radius at all get [`Scalar::MAX`]. Returns the same errors
+/// as [`fillet`] for selections that can't be filleted at any radius.
+pub fn max_fillet_radius(
+    solid: &Solid,
+    edges: &EdgeSelector,
+) -> Result<Vec<(EdgeRef, Scalar)>, FilletError> {
+    let selected = select_straight_edges(solid, edges)?;
+
+    let faces: Vec<_> = solid
+        .clone()
+        .reoriented_outward()
+        .into_faces()
+        .into_iter()
+        .collect();
+    let planar_faces: Vec<_> =
+        faces.iter().map(PlanarFace::from_face).collect();
+
+    // Everything that trimming moves scales with the radius, so the setbacks
+    // of a unit radius are all that's needed.
+    let vertices = vertex_kinds(&selected, &faces, &planar_faces, Scalar::ONE)?;
+
+    let mut setbacks = Setbacks::new();
+    for (&key, &edge_ref) in &selected {
+        let adjacent = adjacent_faces(key, &planar_faces)
+            .ok_or(FilletError::UnsupportedEdge(edge_ref))?;
+        let normals = check_convex(key, adjacent, &planar_faces, edge_ref)?;
+
+        for vertex in key {
+            let kind = vertices[&vertex];
+            let end = BlendEnd::new(vertex, normals, kind, Scalar::ONE);
+
+            for (&face, &point) in adjacent.iter().zip(&end.points) {
+                let setback = (point - vertex, key);
+                setbacks.entry((face, vertex)).or_default().push(setback);
+
+                // The end face loses its corner, along both of its edges.
+                if let VertexKind::End { face: end_face } = kind {
+                    setbacks
+                        .entry((end_face, vertex))
+                        .or_default()
+                        .push(setback);
+                }
+            }
+        }
+    }
+
+    let maxima = max_sizes(&planar_faces, &setbacks);
+    Ok(selected
+        .iter()
+        .map(|(key, &edge_ref)| {
+            (edge_ref, maxima.get(key).copied().unwrap_or(Scalar::MAX))
+        })
+        .collect())
+}
+
+/// Error filleting edges of a solid
+#[derive(Debug, thiserror::Error)]
+pub enum FilletError {
+    /// The radius is not positive
+    #[error("Fillet radius must be positive, but is {0}")]
+    InvalidRadius(Scalar),
+
+    /// A selected edge doesn't exist
+    #[error("Edge {0:?} doesn't refer to an edge of the solid")]
+    EdgeNotFound(EdgeRef),
+
+    /// A selected edge is not a straight edge between two planar faces
+    #[error("Can only fillet straight edges between two planar faces: {0:?}")]
+    UnsupportedEdge(EdgeRef),
+
+    /// A face of the solid is made of triangles
+    #[error("Can only fillet solids with faces bounded by edges: face {0}")]
+    UnsupportedFace(usize),
+
+    /// A selected edge is concave
+    #[error("Can only fillet convex edges: {0:?}")]
+    ConcaveEdge(EdgeRef),
+
+    /// Fillets end at a vertex in a way that is not supported
+    #[error(
+        "Can't fillet at vertex {0:?}: Fillets can only end at a perpendicular \
+        face, or meet in threes at the corner of three faces"
+    )]
+    UnsupportedVertex(Point<3>),
+
+    /// The radius is too large for some of the selected edges
+    #[error(
+        "Fillet radius {} is too large; the largest feasible radius is {}",
+        .0.requested,
+        .0.max()
+    )]
+    Infeasible(FeasibilityReport),
+
+    /// Trimming a face would make one of its edges vanish
+    #[error(
+        "Fillet radius {radius} is too large for an edge of length {length}"
+    )]
+    RadiusTooLarge {
+        /// The radius of the fillet
+        radius: Scalar,
+
+        /// The length of the edge that would vanish
+        length: Scalar,
+    },
+}
+
+impl From<SelectionError> for FilletError {
+    fn from(err: SelectionError) -> Self {
+        match err {
+            SelectionError::EdgeNotFound(edge_ref) => {
+                Self::EdgeNotFound(edge_ref)
+            }
+            SelectionError::UnsupportedEdge(edge_ref) => {
+                Self::UnsupportedEdge(edge_ref)
+            }
+            SelectionError::TriangleFace(index) => Self::UnsupportedFace(index),
+        }
+    }
+}
+
+/// Determine how each vertex at the end of a filleted edge is treated
+fn vertex_kinds(
+    selected: &BTreeMap<[Point<3>; 2], EdgeRef>,
+    faces: &[Face],
+    planar_faces: &[Option<PlanarFace>],
+    radius: Scalar,
+) -> Result<BTreeMap<Point<3>, VertexKind>, FilletError> {
+    let mut vertices = BTreeMap::new();
+    for &[a, b] in selected.keys() {
+        for vertex in [a, b] {
+            if vertices.contains_key(&vertex) {
+                continue;
+            }
+            let kind =
+                VertexKind::new(vertex, selected, faces, planar_faces, radius)?;
+            vertices.insert(vertex, kind);
+        }
+    }
+
+    Ok(vertices)
+}
+
+/// Make sure a filleted edge is convex, and return the normals of its faces
+fn check_convex(
+    edge: [Point<3>; 2],
+    faces: [usize; 2],
+    planar_faces: &[Option<PlanarFace>],
+    edge_ref: EdgeRef,
+) -> Result<[Vector<3>; 2], FilletError> {
+    let [face_a, face_b] = faces.map(|index| {
+        planar_faces[index]
+            .as_ref()
+            .expect("Faces of filleted edge are planar")
+    });
+
+    if (face_b.centroid() - edge[0]).dot(&face_a.normal) >= Scalar::ZERO {
+        return Err(FilletError::ConcaveEdge(edge_ref));
+    }
+
+    Ok([face_a.normal, face_b.normal])
+}
+
+/// How a vertex at the end of a filleted edge is treated
+#[derive(Clone, Copy)]
+enum VertexKind {
+    /// One fillet ends at the given face, which is perpendicular to it
+    End { face: usize },
+
+    /// Three fillets meet at a corner, which is rounded by a sphere
+    Corner { center: Point<3>, faces: [usize; 3] },
+}
+
+impl VertexKind {
+    fn new(
+        vertex: Point<3>,
+        selected: &BTreeMap<[Point<3>; 2], EdgeRef>,
+        faces: &[Face],
+        planar_faces: &[Option<PlanarFace>],
+        radius: Scalar,
+    ) -> Result<Self, FilletError> {
+        let star = VertexStar::new(vertex, selected, faces, planar_faces)
+            .ok_or(FilletError::UnsupportedVertex(vertex))?;
+
+        let normal = |index: usize| {
+            planar_faces[index]
+                .as_ref()
+                .expect("Faces at vertex are planar")
+                .normal
+        };
+
+        match (star.faces.as_slice(), star.selected.as_slice()) {
+            (&[a, b, c], &[[start, end]]) => {
+                let direction = (end - start).normalize();
+                let end_faces: Vec<_> = [a, b, c]
+                    .into_iter()
+                    .filter(|&index| {
+                        !planar_faces[index]
+                            .as_ref()
+                            .map_or(false, |face| face.has_edge([start, end]))
+                    })
+                    .collect();
+
+                match end_faces.as_slice() {
+                    &[face]
+                        if (Scalar::ONE
+                            - normal(face).dot(&direction).abs())
+                            <= Scalar::from_f64(MAX_END_FACE_COS) =>
+                    {
+                        Ok(Self::End { face })
+                    }
+                    _ => Err(FilletError::UnsupportedVertex(vertex)),
+                }
+            }
+            (&[a, b, c], selected_edges) if selected_edges.len() == 3 => {
+                // The center of the sphere is at a distance of `radius` from
+                // all three planes.
+                let [n1, n2, n3] = [a, b, c].map(normal);
+                let offset = (n2.cross(&n3) + n3.cross(&n1) + n1.cross(&n2))
+                    / n1.dot(&n2.cross(&n3));
+
+                Ok(Self::Corner {
+                    center: vertex - offset * radius,
+                    faces: [a, b, c],
+                })
+            }
+            _ => Err(FilletError::UnsupportedVertex(vertex)),
+        }
+    }
+}
+
+/// The cylindrical face that replaces a filleted edge
+struct Blend {
+    /// The faces that the fillet is tangent to
+    faces: [usize; 2],
+
+    /// The ends of the fillet
+    ///
+    /// Ordered, so the arcs at the ends run counter-clockwise around the axis
+    /// of the fillet, from `faces[0]` to `faces[1]`.
+    ends: [BlendEnd; 2],
+
+    /// The angle of the arcs at the ends
+    angle: Scalar,
+
+    color: Color,
+}
+
+impl Blend {
+    fn new(
+        edge: [Point<3>; 2],
+        faces: [usize; 2],
+        planar_faces: &[Option<PlanarFace>],
+        vertices: &BTreeMap<Point<3>, VertexKind>,
+        radius: Scalar,
+        color: Color,
+        edge_ref: EdgeRef,
+    ) -> Result<Self, FilletError> {
+        let normals = check_convex(edge, faces, planar_faces, edge_ref)?;
+
+        let mut ends = edge.map(|vertex| {
+            BlendEnd::new(vertex, normals, vertices[&vertex], radius)
+        });
+
+        let [start, end] = edge;
+        let length = (end - start).magnitude();
+        let trimmed_length =
+            (ends[1].center - ends[0].center).dot(&(end - start)) / length;
+        if trimmed_length < Scalar::from_f64(MIN_EDGE_LENGTH) {
+            return Err(FilletError::RadiusTooLarge { radius, length });
+        }
+
+        let [a, b] = {
+            let axis = (ends[1].center - ends[0].center).normalize();
+            let a = ends[0].points[0] - ends[0].center;
+            [a, axis.cross(&a)]
+        };
+        let to_b = ends[0].points[1] - ends[0].center;
+        if b.dot(&to_b) < Scalar::ZERO {
+            ends.reverse();
+        }
+
+        Ok(Self {
+            faces,
+            ends,
+            angle: a.cross(&to_b).magnitude().atan2(a.dot(&to_b)),
+            color,
+        })
+    }
+
+    /// The circle that the arc at the given end is a section of
+    fn circle(&self, end: usize) -> Circle<3> {
+        let axis = (self.ends[1].center - self.ends[0].center).normalize();
+        let end = &self.ends[end];
+        let a = end.points[0] - end.center;
+
+        Circle {
+            center: end.center,
+            a,
+            b: axis.cross(&a),
+        }
+    }
+
+    /// The arc at the given end, as an edge of the cylindrical face
+    fn arc_edge(&self, end: usize) -> Edge {
+        let local = Curve::Line(Line {
+            origin: Point::from([0., end as f64]),
+            direction: Vector::unit_u(),
+        });
+        arc_edge(local, self.circle(end), self.angle, self.ends[end].points)
+    }
+
+    fn face(&self) -> Face {
+        let [start, end] = &self.ends;
+        let angle = self.angle.into_f64();
+
+        let surface = Surface::SweptCurve(SweptCurve {
+            curve: Curve::Circle(self.circle(0)),
+            path: end.center - start.center,
+        });
+
+        let edges = vec![
+            self.arc_edge(0),
+            line_edge(
+                [[angle, 0.], [angle, 1.]].map(Point::from),
+                [start.points[1], end.points[1]],
+            ),
+            reverse_edge(&self.arc_edge(1)),
+            line_edge(
+                [[0., 1.], [0., 0.]].map(Point::from),
+                [end.points[0], start.points[0]],
+            ),
+        ];
+
+        Face::new(surface, [Cycle { edges }], [], self.color)
+    }
+}
+
+/// An end of a [`Blend`]
+struct BlendEnd {
+    /// The vertex of the filleted edge
+    vertex: Point<3>,
+
+    /// The center of the arc
+    center: Point<3>,
+
+    /// The points where the arc touches the faces of the filleted edge
+    points: [Point<3>; 2],
+}
+
+impl BlendEnd {
+    fn new(
+        vertex: Point<3>,
+        [n_a, n_b]: [Vector<3>; 2],
+        kind: VertexKind,
+        radius: Scalar,
+    ) -> Self {
+        let center = match kind {
+            // The center is at a distance of `radius` from both faces, in the
+            // plane of the vertex that is perpendicular to the edge.
+            VertexKind::End { .. } => {
+                vertex - (n_a + n_b) * (radius / (Scalar::ONE + n_a.dot(&n_b)))
+            }
+            VertexKind::Corner { center, .. } => center,
+        };
+
+        Self {
+            vertex,
+            center,
+            points: [center + n_a * radius, center + n_b * radius],
+        }
+    }
+}
+
+/// Create a patch of a sphere, that connects three fillets at a corner
+///
+/// The patch is bounded by the arcs at the ends of the fillets. It is made up
+/// of rings of points, that are spaced closely enough to stay within
+/// `tolerance` of the sphere.
+fn corner_patch(
+    arcs: &[Edge],
+    center: Point<3>,
+    faces: &[usize; 3],
+    planar_faces: &[Option<PlanarFace>],
+    radius: Scalar,
+    tolerance: Tolerance,
+    color: Color,
+) -> Face {
+    let mut arcs: Vec<Vec<_>> = arcs
+        .iter()
+        .map(|arc| {
+            approx_edge_points(arc, tolerance)
+                .into_iter()
+                .map(|point| *point.global_form())
+                .collect()
+        })
+        .collect();
+
+    // Chain the arcs into a loop.
+    let mut boundary = arcs.remove(0);
+    while !arcs.is_empty() {
+        let last = boundary[boundary.len() - 1];
+        let next = arcs
+            .iter()
+            .position(|arc| {
+                arc.first() == Some(&last) || arc.last() == Some(&last)
+            })
+            .expect("Arcs at corner are connected");
+
+        let mut arc = arcs.remove(next);
+        if arc.first() != Some(&last) {
+            arc.reverse();
+        }
+        boundary.extend(arc.into_iter().skip(1));
+    }
+    boundary.pop();
+
+    let direction = faces
+        .iter()
+        .map(|&index| {
+            planar_faces[index]
+                .as_ref()
+                .expect("Faces at corner are planar")
+                .normal
+        })
+        .fold(Vector::from([0., 0., 0.]), |sum, normal| sum + normal)
+        .normalize();
+    let apex = center + direction * radius;
+
+    // Space the rings, so the sagitta between them stays within tolerance.
+    let max_angle = boundary
+        .iter()
+        .map(|&point| {
+            ((point - center).dot(&direction) / radius)
+                .max(-Scalar::ONE)
+                .min(Scalar::ONE)
+                .acos()
+        })
+        .fold(Scalar::ZERO, Scalar::max);
+    let step = {
+        let cos = Scalar::ONE - tolerance.inner() / radius;
+        if cos > Scalar::ZERO {
+            cos.acos() * 2.
+        } else {
+            Scalar::PI / 2.
+        }
+    };
+    let num_rings = (max_angle / step).ceil().into_u64().max(1);
+
+    let rings: Vec<Vec<_>> = (1..=num_rings)
+        .map(|k| {
+            if k == num_rings {
+                return boundary.clone();
+            }
+
+            let t = k as f64 / num_rings as f64;
+            boundary
+                .iter()
+                .map(|&point| {
+                    let point = apex + (point - apex) * t;
+                    center + (point - center).normalize() * radius
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut triangles = Vec::new();
+    let mut push = |points: [Point<3>; 3]| {
+        let [a, b, c] = points;
+        let normal = (b - a).cross(&(c - a));
+        let points = if normal.dot(&(a - center)) >= Scalar::ZERO {
+            [a, b, c]
+        } else {
+            [a, c, b]
+        };
+        triangles.push((Triangle::from_points(points), color));
+    };
+
+    let n = boundary.len();
+    for i in 0..n {
+        let j = (i + 1) % n;
+        push([apex, rings[0][i], rings[0][j]]);
+
+        for rings in rings.windows(2) {
+            let [inner, outer] = [&rings[0], &rings[1]];
+            push([inner[i], outer[i], outer[j]]);
+            push([inner[i], outer[j], inner[j]]);
+        }
+    }
+
+    Face::from_triangles(triangles)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use fj_interop::{mesh::Mesh, physical_summary::PhysicalSummary};
+    use fj_math::{Point, Scalar, Triangle};
+
+    use crate::{
+        algorithms::{test_helpers::closed_mesh, EdgeSelector, Tolerance},
+        objects::{Face, Solid},
+    };
+
+    use super::{
+        fillet, fillet_with_options, max_fillet_radius, FilletError,
+        FilletOptions,
+    };
+
+    #[test]
+    fn single_edge() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        let solid = Solid::cube_from_edge_length(2.);
+
+        // The edge at the top right, which runs along the y-axis.
+        let edges = EdgeSelector::matching(&solid, |points| {
+            points
+                .iter()
+                .all(|point| point.x == Scalar::ONE && point.z == Scalar::ONE)
+        });
+
+        let result = fillet(solid, &edges, 0.5, tolerance)?;
+        assert_eq!(result.faces().count(), 7);
+
+        let removed = (1. - PI / 4.) * 0.5 * 0.5 * 2.;
+        let volume = volume(closed_mesh(result, tolerance)?, tolerance);
+        assert!((volume - (8. - removed)).abs() < 0.01);
+
+        Ok(())
+    }
+
+    #[test]
+    fn all_edges() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        let solid = Solid::cube_from_edge_length(2.);
+
+        let result = fillet(solid, &EdgeSelector::All, 0.5, tolerance)?;
+
+        // The original faces, a cylinder per edge, a sphere per corner.
+        assert_eq!(result.faces().count(), 6 + 12 + 8);
+
+        // A cube of edge length 1, grown by the radius in every direction.
+        let r = 0.5;
+        let expected =
+            1. + 6. * r + 12. * PI * r * r / 4. + 4. / 3. * PI * r * r * r;
+        let volume = volume(closed_mesh(result, tolerance)?, tolerance);
+        assert!((volume - expected).abs() < expected * 0.01);
+
+        Ok(())
+    }
+
+    #[test]
+    fn radius_too_large() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        let solid = Solid::cube_from_edge_length(2.);
+
+        let edges = EdgeSelector::matching(&solid, |points| {
+            points
+                .iter()
+                .all(|point| point.x == Scalar::ONE && point.z == Scalar::ONE)
+        });
+        assert!(matches!(
+            fillet(solid.clone(), &edges, 2.5, tolerance),
+            Err(FilletError::Infeasible(_))
+        ));
+
+        // Fillets on both ends of an edge of length 2 leave nothing of it.
+        assert!(matches!(
+            fillet(solid, &EdgeSelector::All, 1., tolerance),
+            Err(FilletError::Infeasible(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn max_radius() -> anyhow::Result<()> {
+        let solid = Solid::cube_from_edge_length(10.);
+
+        // Neighboring fillets meet in the middle of the faces between them.
+        let maxima = max_fillet_radius(&solid, &EdgeSelector::All)?;
+        assert_eq!(maxima.len(), 12);
+        assert!(maxima.iter().all(|&(_, max)| is_close(max, 5.)));
+
+        // A single fillet is only limited by the width of its faces.
+        let edges = EdgeSelector::matching(&solid, |points| {
+            points.iter().all(|point| {
+                point.x == Scalar::from_f64(5.)
+                    && point.z == Scalar::from_f64(5.)
+            })
+        });
+        let maxima = max_fillet_radius(&solid, &edges)?;
+        assert_eq!(maxima.len(), 1);
+        assert!(is_close(maxima[0].1, 10.));
+
+        Ok(())
+    }
+
+    #[test]
+    fn infeasible_radius() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.01)?;
+        let solid = Solid::cube_from_edge_length(10.);
+
+        let report =
+            match fillet(solid.clone(), &EdgeSelector::All, 6., tolerance) {
+                Err(FilletError::Infeasible(report)) => report,
+                _ => panic!("Expected radius to be infeasible"),
+            };
+        assert_eq!(report.requested, Scalar::from_f64(6.));
+        assert_eq!(report.exceeded.len(), 12);
+        assert!(is_close(report.max(), 5.));
+
+        // Merged fillets round the cube into almost a sphere.
+        let options = FilletOptions {
+            allow_topology_change: true,
+        };
+        let result = fillet_with_options(
+            solid,
+            &EdgeSelector::All,
+            6.,
+            tolerance,
+            options,
+        )?;
+        assert_eq!(result.faces().count(), 6 + 12 + 8);
+
+        let expected = 4. / 3. * PI * 5. * 5. * 5.;
+        let volume = volume(closed_mesh(result, tolerance)?, tolerance);
+        assert!((volume - expected).abs() < expected * 0.01);
+
+        Ok(())
+    }
+
+    #[test]
+    fn two_fillets_at_vertex() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        let solid = Solid::cube_from_edge_length(2.);
+
+        // Two edges of the top face, that meet at a corner.
+        let edges = EdgeSelector::matching(&solid, |points| {
+            points.iter().all(|point| {
+                point.z == Scalar::ONE
+                    && (point.x == Scalar::ONE || point.y == Scalar::ONE)
+            })
+        });
+
+        assert!(matches!(
+            fillet(solid, &edges, 0.5, tolerance),
+            Err(FilletError::UnsupportedVertex(vertex))
+                if vertex == Point::from([1., 1., 1.])
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn triangle_face() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let triangle = Triangle::from_points(
+            [[3., 0., 0.], [4., 0., 0.], [3., 1., 0.]].map(Point::from),
+        );
+        let mut faces: Vec<_> = Solid::cube_from_edge_length(2.)
+            .into_faces()
+            .into_iter()
+            .collect();
+        faces.push(Face::from_triangles(vec![(triangle, [255, 0, 0, 255])]));
+        let solid = Solid::from_faces(faces);
+
+        // Faces that are made of triangles have no edges to select.
+        assert_eq!(EdgeSelector::All.select(&solid).len(), 6 * 4);
+
+        assert!(matches!(
+            fillet(solid, &EdgeSelector::All, 0.5, tolerance),
+            Err(FilletError::UnsupportedFace(_))
+        ));
+
+        Ok(())
+    }
+
+    fn volume(mesh: Mesh<Point<3>>, tolerance: Tolerance) -> f64 {
+        PhysicalSummary::from_mesh(&mesh, tolerance.inner())
+            .volume
+            .into_f64()
+    }
+
+    fn is_close(a: Scalar, b: f64) -> bool {
+        (a - Scalar::from_f64(b)).abs() < Scalar::from_f64(1e-6)
+    }
+}
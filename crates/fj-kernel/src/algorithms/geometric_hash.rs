@@ -0,0 +1,308 @@
+use fj_math::{Point, Scalar, Vector};
+
+use crate::{
+    iter::ObjectIters,
+    objects::{Curve, Face, Surface},
+};
+
+use super::{CycleApprox, Tolerance};
+
+/// Compute a content hash of a shape, that is invariant under rigid transforms
+///
+/// The hash is built from a signature of each face, consisting of its surface
+/// type, color, area, the number of points in the approximation of each of its
+/// cycles, and the distances between those points. Lengths are quantized using
+/// `tolerance`, and the signatures are sorted, so neither the position and
+/// orientation of the shape, nor the order of its faces affect the hash.
+///
+/// Shapes that are equal within `tolerance` usually have the same hash, but
+/// that is not guaranteed, as two lengths that only differ slightly could
+/// still be quantized differently. Shapes with the same hash are not
+/// necessarily equal, so users need to verify equality, for example using
+/// [`equivalent_translation`].
+///
+/// The hash only depends on the canonical bit patterns of the quantized
+/// values (see [`Scalar::to_canonical_bits`]), and is computed using a fixed
+/// hash function. This makes it reproducible across platforms and builds.
+pub fn geometric_hash<'r>(
+    shape: &'r impl ObjectIters<'r>,
+    tolerance: Tolerance,
+) -> u64 {
+    let faces: Vec<_> = shape
+        .face_iter()
+        .map(|face| (face, face_points(face, tolerance)))
+        .collect();
+
+    let shape_centroid = centroid(
+        faces
+            .iter()
+            .flat_map(|(_, cycles)| cycles.iter().flatten().copied()),
+    );
+
+    let mut signatures: Vec<_> = faces
+        .iter()
+        .map(|(face, cycles)| {
+            let points: Vec<_> = cycles.iter().flatten().copied().collect();
+
+            let mut cycle_lengths: Vec<_> =
+                cycles.iter().map(|cycle| cycle.len() as u64).collect();
+            cycle_lengths.sort_unstable();
+
+            let mut distances = Vec::new();
+            for (i, &a) in points.iter().enumerate() {
+                for &b in &points[i + 1..] {
+                    distances.push(quantize((b - a).magnitude(), tolerance));
+                }
+            }
+            distances.sort_unstable();
+
+            let offset =
+                shape_centroid.map_or(Scalar::ZERO, |shape_centroid| {
+                    let face_centroid = centroid(points.iter().copied())
+                        .unwrap_or(shape_centroid);
+                    (face_centroid - shape_centroid).magnitude()
+                });
+
+            FaceSignature {
+                kind: surface_kind(face),
+                color: face.color(),
+                area: quantize(area(face, cycles).sqrt(), tolerance),
+                offset: quantize(offset, tolerance),
+                cycle_lengths,
+                distances,
+            }
+        })
+        .collect();
+    signatures.sort();
+
+    let mut hasher = StableHasher::new();
+    for signature in &signatures {
+        signature.write_to(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Find the translation that turns one shape into another
+///
+/// Returns the translation from `a` to `b`, if every face of `b` is a
+/// translated face of `a`, within `tolerance`. Returns `None` otherwise.
+///
+/// Other rigid transforms, like rotations, are not detected. Shapes that are
+/// only equal after rotating them are considered to be different.
+pub fn equivalent_translation<'r>(
+    a: &'r impl ObjectIters<'r>,
+    b: &'r impl ObjectIters<'r>,
+    tolerance: Tolerance,
+) -> Option<Vector<3>> {
+    let a: Vec<_> = a
+        .face_iter()
+        .map(|face| (face, face_points(face, tolerance)))
+        .collect();
+    let b: Vec<_> = b
+        .face_iter()
+        .map(|face| (face, face_points(face, tolerance)))
+        .collect();
+
+    if a.len() != b.len() {
+        return None;
+    }
+
+    let centroid_a = centroid(
+        a.iter()
+            .flat_map(|(_, cycles)| cycles.iter().flatten().copied()),
+    );
+    let centroid_b = centroid(
+        b.iter()
+            .flat_map(|(_, cycles)| cycles.iter().flatten().copied()),
+    );
+    let translation = match (centroid_a, centroid_b) {
+        (Some(a), Some(b)) => b - a,
+        (None, None) => Vector::from([0., 0., 0.]),
+        _ => return None,
+    };
+
+    let mut matched = vec![false; b.len()];
+    for (face_a, cycles_a) in &a {
+        let index =
+            b.iter().enumerate().position(|(i, (face_b, cycles_b))| {
+                !matched[i]
+                    && surface_kind(face_a) == surface_kind(face_b)
+                    && face_a.color() == face_b.color()
+                    && cycles_match(cycles_a, cycles_b, translation, tolerance)
+            })?;
+
+        matched[index] = true;
+    }
+
+    Some(translation)
+}
+
+#[derive(Eq, Ord, PartialEq, PartialOrd)]
+struct FaceSignature {
+    kind: u8,
+    color: [u8; 4],
+    area: Scalar,
+    offset: Scalar,
+    cycle_lengths: Vec<u64>,
+    distances: Vec<Scalar>,
+}
+
+impl FaceSignature {
+    fn write_to(&self, hasher: &mut StableHasher) {
+        hasher.write(&[self.kind]);
+        hasher.write(&self.color);
+        hasher.write_scalar(self.area);
+        hasher.write_scalar(self.offset);
+
+        hasher.write_u64(self.cycle_lengths.len() as u64);
+        for &length in &self.cycle_lengths {
+            hasher.write_u64(length);
+        }
+
+        hasher.write_u64(self.distances.len() as u64);
+        for &distance in &self.distances {
+            hasher.write_scalar(distance);
+        }
+    }
+}
+
+/// A 64-bit FNV-1a hasher
+///
+/// Unlike the hashers from the standard library, its output is guaranteed to
+/// stay the same across platforms and Rust versions.
+struct StableHasher(u64);
+
+impl StableHasher {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_scalar(&mut self, value: Scalar) {
+        self.write_u64(value.to_canonical_bits());
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Compute the points of a face, grouped into cycles
+///
+/// Faces that are defined by triangles have one cycle per triangle.
+fn face_points(face: &Face, tolerance: Tolerance) -> Vec<Vec<Point<3>>> {
+    if let Some(triangles) = face.triangles() {
+        return triangles
+            .iter()
+            .map(|(triangle, _)| triangle.points().to_vec())
+            .collect();
+    }
+
+    face.all_cycles()
+        .map(|cycle| {
+            CycleApprox::new(cycle, tolerance)
+                .points
+                .into_iter()
+                .map(|point| *point.global_form())
+                .collect()
+        })
+        .collect()
+}
+
+fn surface_kind(face: &Face) -> u8 {
+    if face.triangles().is_some() {
+        return 0;
+    }
+
+    let Surface::SweptCurve(surface) = face.surface();
+    match surface.curve {
+        Curve::Line(_) => 1,
+        Curve::Circle(_) => 2,
+        Curve::Polyline(_) => 3,
+        Curve::Ellipse(_) => 4,
+    }
+}
+
+/// Compute the area of a face from the points of its cycles
+fn area(face: &Face, cycles: &[Vec<Point<3>>]) -> Scalar {
+    let cycle_area = |points: &[Point<3>]| {
+        let mut normal = Vector::from([0., 0., 0.]);
+        for (i, a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+            normal = normal + a.coords.cross(&b.coords);
+        }
+        normal.magnitude() / 2.
+    };
+
+    if face.triangles().is_some() {
+        return cycles
+            .iter()
+            .fold(Scalar::ZERO, |area, points| area + cycle_area(points));
+    }
+
+    let num_exteriors = face.exteriors().count();
+    cycles
+        .iter()
+        .enumerate()
+        .fold(Scalar::ZERO, |area, (i, points)| {
+            if i < num_exteriors {
+                area + cycle_area(points)
+            } else {
+                area - cycle_area(points)
+            }
+        })
+        .abs()
+}
+
+fn centroid(points: impl IntoIterator<Item = Point<3>>) -> Option<Point<3>> {
+    let mut sum = Vector::from([0., 0., 0.]);
+    let mut count = 0;
+    for point in points {
+        sum = sum + point.coords;
+        count += 1;
+    }
+
+    if count == 0 {
+        return None;
+    }
+
+    Some(Point {
+        coords: sum / count as f64,
+    })
+}
+
+/// Check that each cycle of `a`, translated, matches the cycle of `b`
+///
+/// The points of the cycles need to be equal within `tolerance`. Their order
+/// is not checked, as it depends on where the approximation of a cycle starts.
+fn cycles_match(
+    a: &[Vec<Point<3>>],
+    b: &[Vec<Point<3>>],
+    translation: Vector<3>,
+    tolerance: Tolerance,
+) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b).all(|(a, b)| {
+            a.len() == b.len()
+                && a.iter().all(|&point_a| {
+                    b.iter().any(|&point_b| {
+                        (point_a + translation - point_b).magnitude()
+                            <= tolerance.inner()
+                    })
+                })
+        })
+}
+
+fn quantize(length: Scalar, tolerance: Tolerance) -> Scalar {
+    (length / tolerance.inner()).round()
+}
@@ -0,0 +1,92 @@
+use fj_math::{Vector, Winding};
+
+use crate::objects::Face;
+
+use super::{CycleApprox, Tolerance};
+
+/// The handedness of a face's surface frame, relative to the face
+///
+/// The frame is made up of the surface's u- and v-axis, and the face's outward
+/// normal. It is right-handed, if the determinant of `[u, v, outward]` is
+/// positive, which means the outward normal is the normal of the surface. It
+/// is left-handed otherwise. This happens for faces that point against their
+/// surface, like the bottom face of a swept solid, or faces that were
+/// mirrored.
+///
+/// The outward normal is defined by the winding of the exterior cycle (see
+/// [`Face`]), so that is what the handedness is derived from. Triangulation
+/// and orientation checks both rely on this, so they always agree.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Handedness {
+    /// The outward normal is the normal of the surface
+    Right,
+
+    /// The outward normal points opposite to the normal of the surface
+    Left,
+}
+
+impl Handedness {
+    /// Determine the handedness of a face
+    ///
+    /// `tolerance` is used to approximate the exterior cycle, to determine its
+    /// winding. Returns `None`, if the face uses triangle representation.
+    pub fn of_face(face: &Face, tolerance: Tolerance) -> Option<Self> {
+        if face.triangles().is_some() {
+            return None;
+        }
+
+        let winding = face
+            .exteriors()
+            .next()
+            .and_then(|cycle| CycleApprox::new(cycle, tolerance).winding());
+
+        Some(Self::from_exterior_winding(winding))
+    }
+
+    /// Determine the handedness from the winding of an exterior cycle
+    ///
+    /// Cycles that don't enclose any area have no winding. Those faces are
+    /// considered right-handed.
+    pub fn from_exterior_winding(winding: Option<Winding>) -> Self {
+        match winding {
+            Some(Winding::Cw) => Self::Left,
+            Some(Winding::Ccw) | None => Self::Right,
+        }
+    }
+
+    /// The winding that exterior cycles have in surface coordinates
+    pub fn exterior_winding(&self) -> Winding {
+        match self {
+            Self::Right => Winding::Ccw,
+            Self::Left => Winding::Cw,
+        }
+    }
+
+    /// The winding that interior cycles are expected to have
+    pub fn interior_winding(&self) -> Winding {
+        match self {
+            Self::Right => Winding::Cw,
+            Self::Left => Winding::Ccw,
+        }
+    }
+
+    /// Compute the outward normal from the normal of the surface
+    pub fn outward(&self, surface_normal: Vector<3>) -> Vector<3> {
+        match self {
+            Self::Right => surface_normal,
+            Self::Left => -surface_normal,
+        }
+    }
+
+    /// Wind a triangle, such that it faces outward
+    ///
+    /// Expects a triangle that is counter-clockwise in surface coordinates.
+    /// Its normal is then the normal of the surface, so it only needs to be
+    /// flipped, if the frame is left-handed.
+    pub fn orient<T>(&self, [a, b, c]: [T; 3]) -> [T; 3] {
+        match self {
+            Self::Right => [a, b, c],
+            Self::Left => [a, c, b],
+        }
+    }
+}
@@ -1,11 +1,27 @@
 use std::vec;
 
-use fj_math::{Scalar, Segment};
-use parry2d_f64::query::{Ray, RayCast};
+use fj_math::{Point, Scalar, Vector};
 
-use crate::objects::{Curve, Face};
+use crate::objects::{Curve, Cycle, Face, GlobalVertex};
+
+use super::IntersectionTolerances;
 
 /// The intersections between a [`Curve`] and a [`Face`], in curve coordinates
+///
+/// The intervals make up the intersection of the curve with the closed face,
+/// which includes its boundary. Each interval endpoint is classified by how
+/// the curve relates to the face boundary there (see [`BoundaryContact`]).
+///
+/// Where the curve touches the boundary without entering the face, the
+/// intervals only cover the contact:
+///
+/// - If the curve touches a single vertex, the interval has zero length, and
+///   both of its endpoints are [`BoundaryContact::ThroughVertex`].
+/// - If the curve runs along one or more edges, the interval covers those
+///   edges, and both of its endpoints are [`BoundaryContact::TangentTouch`].
+///
+/// If such a contact lies within another interval (because the face is on
+/// both sides of the boundary there), it is not reported separately.
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct CurveFaceIntersectionList {
     intervals: Vec<CurveFaceIntersection>,
@@ -14,98 +30,100 @@ pub struct CurveFaceIntersectionList {
 impl CurveFaceIntersectionList {
     /// Create a new instance from the intersection intervals
     ///
+    /// All interval endpoints are classified as
+    /// [`BoundaryContact::TransversalCross`].
+    ///
     /// This method is useful for test code.
     pub fn from_intervals(
         intervals: impl IntoIterator<Item = [impl Into<Scalar>; 2]>,
     ) -> Self {
         let intervals = intervals
             .into_iter()
-            .map(|interval| interval.map(Into::into))
+            .map(|interval| CurveFaceIntersection {
+                interval: interval.map(Into::into),
+                contacts: [BoundaryContact::TransversalCross; 2],
+                touches_only: false,
+            })
             .collect();
         Self { intervals }
     }
 
     /// Compute the intersections between a [`Curve`] and a [`Face`]
-    pub fn compute(curve: &Curve<2>, face: &Face) -> Self {
+    ///
+    /// Boundary vertices that are within
+    /// [`IntersectionTolerances::coincidence_distance`] of the curve are
+    /// treated as lying on it. An edge that leaves such a vertex at an angle
+    /// within [`IntersectionTolerances::tangency_angle`] is treated as running
+    /// along the curve. This makes the classification of tangent contacts
+    /// robust against floating point accuracy issues.
+    ///
+    /// The same distance applies to the intervals themselves. Intervals that
+    /// are separated by a shorter gap are merged, as the curve only leaves the
+    /// face at what is considered a single point. Intervals that are shorter
+    /// than that distance only graze the face. They are classified as touches
+    /// (see [`CurveFaceIntersection::touches_only`]), so they don't contribute
+    /// to the intersection of the interiors of the faces, just like the
+    /// contacts that are within the distance in the first place. This means
+    /// the result doesn't flip between an interval and none, if the curve
+    /// moves across a vertex of the face by a tiny amount.
+    pub fn compute(
+        curve: &Curve<2>,
+        face: &Face,
+        tolerances: &IntersectionTolerances,
+    ) -> Self {
         let line = match curve {
             Curve::Line(line) => line,
             _ => todo!("Curve-face intersection only supports lines"),
         };
 
-        let face_as_polygon = face
-            .exteriors()
-            .chain(face.interiors())
-            .flat_map(|cycle| {
-                let edges: Vec<_> = cycle.edges().cloned().collect();
-                edges
-            })
-            .map(|edge| {
-                let line = match edge.curve().local_form() {
-                    Curve::Line(line) => line,
-                    _ => {
-                        todo!("Curve-face intersection only supports polygons")
-                    }
-                };
-
-                let vertices = match edge.vertices().get() {
-                    Some(vertices) => vertices.map(|&vertex| vertex),
-                    None => todo!(
-                        "Curve-face intersection does not support faces with \
-                    continuous edges"
-                    ),
-                };
-
-                (*line, vertices)
-            });
-
-        let mut intersections = Vec::new();
-
-        for (edge_line, vertices) in face_as_polygon {
-            let vertices = vertices.map(|vertex| {
-                edge_line.point_from_line_coords(vertex.position())
-            });
-            let segment = Segment::from_points(vertices);
-
-            let ray = Ray {
-                origin: line.origin.to_na(),
-                dir: line.direction.to_na(),
-            };
-            let ray_inv = Ray {
-                origin: line.origin.to_na(),
-                dir: -line.direction.to_na(),
-            };
-
-            let result =
-                segment
-                    .to_parry()
-                    .cast_local_ray(&ray, f64::INFINITY, false);
-            let result_inv = segment.to_parry().cast_local_ray(
-                &ray_inv,
-                f64::INFINITY,
-                false,
+        let mut events = Vec::new();
+        for cycle in face.exteriors().chain(face.interiors()) {
+            boundary_events(
+                line.origin,
+                line.direction,
+                cycle,
+                tolerances,
+                &mut events,
             );
+        }
 
-            if let Some(result) = result {
-                intersections.push(Scalar::from(result));
-            }
-            if let Some(result_inv) = result_inv {
-                intersections.push(-Scalar::from(result_inv));
+        events.sort_by_key(|event| event.interval[0]);
+
+        let mut intervals = Vec::new();
+        let mut entry = None;
+
+        for event in events {
+            match (event.crosses, entry) {
+                (true, None) => {
+                    entry = Some((event.interval[0], event.contact));
+                }
+                (true, Some((start, start_contact))) => {
+                    intervals.push(CurveFaceIntersection {
+                        interval: [start, event.interval[1]],
+                        contacts: [start_contact, event.contact],
+                        touches_only: false,
+                    });
+                    entry = None;
+                }
+                (false, None) => {
+                    intervals.push(CurveFaceIntersection {
+                        interval: event.interval,
+                        contacts: [event.contact; 2],
+                        touches_only: true,
+                    });
+                }
+                (false, Some(_)) => {
+                    // The contact is within an interval. Nothing to do.
+                }
             }
         }
 
-        assert!(intersections.len() % 2 == 0);
-
-        intersections.sort();
+        assert!(entry.is_none(), "Curve enters face, but never leaves it");
 
-        // Can be cleaned up, once `array_chunks` is stable:
-        // https://doc.rust-lang.org/std/primitive.slice.html#method.array_chunks
-        let intervals = intersections
-            .chunks(2)
-            .map(|chunk| {
-                // Can't panic, as we passed `2` to `windows`.
-                [chunk[0], chunk[1]]
-            })
-            .collect();
+        // Curve coordinates are scaled by the magnitude of the direction.
+        let threshold =
+            tolerances.coincidence_distance / line.direction.magnitude();
+        let intervals = snap_intervals(intervals, threshold);
 
         CurveFaceIntersectionList { intervals }
     }
@@ -113,7 +131,17 @@ impl CurveFaceIntersectionList {
     /// Merge this intersection list with another
     ///
     /// The merged list will contain all overlaps of the intervals from the two
-    /// other lists.
+    /// other lists. The endpoints of each overlap keep the classification of
+    /// the interval endpoint they originate from. If the endpoints of both
+    /// intervals coincide, the classification from `self` is used.
+    ///
+    /// Overlaps of zero length are only included, if one of the overlapping
+    /// intervals has zero length itself. Intervals that merely touch each
+    /// other don't overlap.
+    ///
+    /// An overlap only touches the faces, if one of the overlapping intervals
+    /// does (see [`CurveFaceIntersection::touches_only`]). Use
+    /// [`CurveFaceIntersectionList::without_touches`] to drop those.
     pub fn merge(&self, other: &Self) -> Self {
         let mut self_ = self.intervals.iter().copied();
         let mut other = other.intervals.iter().copied();
@@ -123,24 +151,40 @@ impl CurveFaceIntersectionList {
 
         let mut intervals = Vec::new();
 
-        while let (
-            Some([self_start, self_end]),
-            Some([other_start, other_end]),
-        ) = (next_self, next_other)
-        {
+        while let (Some(a), Some(b)) = (next_self, next_other) {
             // If we're starting another loop iteration, we have another
             // interval available from both `self` and `other` each. Only if
             // that's the case, is there a chance for an overlap.
+            let [self_start, self_end] = a.interval;
+            let [other_start, other_end] = b.interval;
 
             // Build the overlap of the two next intervals, by comparing them.
             // At this point we don't know yet, if this is a valid interval.
-            let overlap_start = self_start.max(other_start);
-            let overlap_end = self_end.min(other_end);
+            let (overlap_start, start_contact) = if other_start > self_start {
+                (other_start, b.contacts[0])
+            } else {
+                (self_start, a.contacts[0])
+            };
+            let (overlap_end, end_contact) = if other_end < self_end {
+                (other_end, b.contacts[1])
+            } else {
+                (self_end, a.contacts[1])
+            };
 
-            if overlap_start < overlap_end {
+            let is_point_contact = |[start, end]: [Scalar; 2]| start == end;
+            let is_overlap = overlap_start < overlap_end
+                || (overlap_start == overlap_end
+                    && (is_point_contact(a.interval)
+                        || is_point_contact(b.interval)));
+
+            if is_overlap {
                 // This is indeed a valid overlap. Add it to our list of
                 // results.
-                intervals.push([overlap_start, overlap_end]);
+                intervals.push(CurveFaceIntersection {
+                    interval: [overlap_start, overlap_end],
+                    contacts: [start_contact, end_contact],
+                    touches_only: a.touches_only || b.touches_only,
+                });
             }
 
             // Only if the end of the overlap interval has overtaken one of the
@@ -162,6 +206,23 @@ impl CurveFaceIntersectionList {
         Self { intervals }
     }
 
+    /// Remove the intervals where the curve only touches the face
+    ///
+    /// Those intervals don't contribute to the intersection of the interiors
+    /// of the faces. For example, they are the result of intersecting the
+    /// faces of two solids that touch each other along an edge.
+    ///
+    /// See [`CurveFaceIntersection::touches_only`].
+    pub fn without_touches(self) -> Self {
+        let intervals = self
+            .intervals
+            .into_iter()
+            .filter(|intersection| !intersection.touches_only)
+            .collect();
+
+        Self { intervals }
+    }
+
     /// Indicate whether the intersection list is empty
     pub fn is_empty(&self) -> bool {
         self.intervals.is_empty()
@@ -178,15 +239,270 @@ impl IntoIterator for CurveFaceIntersectionList {
 }
 
 /// An intersection between a curve and a face, in curve coordinates
-pub type CurveFaceIntersection = [Scalar; 2];
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct CurveFaceIntersection {
+    /// The start and end of the intersection interval
+    pub interval: [Scalar; 2],
+
+    /// How the curve relates to the face boundary at the start and end
+    pub contacts: [BoundaryContact; 2],
+
+    /// Whether the curve only touches the face, without entering it
+    ///
+    /// This is the case, if the curve touches a vertex, or runs along one or
+    /// more edges, while the face is on the same side of the curve before and
+    /// after the contact. Whether the curve touches or crosses is decided from
+    /// the sides of the boundary vertices, within the
+    /// [`IntersectionTolerances`], so it doesn't depend on floating point
+    /// accuracy.
+    pub touches_only: bool,
+}
+
+/// How a curve relates to a face boundary, at an intersection interval endpoint
+///
+/// See [`CurveFaceIntersectionList`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum BoundaryContact {
+    /// The curve crosses an edge of the boundary
+    TransversalCross,
+
+    /// The curve runs along the boundary
+    ///
+    /// This is the case, if the curve is tangent to one or more edges of the
+    /// boundary. The curve might enter or leave the face at the end of that
+    /// contact, or just touch the boundary there.
+    TangentTouch,
+
+    /// The curve passes through a vertex of the boundary
+    ///
+    /// The curve might cross the boundary at the vertex, or just touch it.
+    ThroughVertex {
+        /// The vertex
+        vertex: GlobalVertex,
+    },
+}
+
+/// A place where a curve meets the boundary of a face
+struct BoundaryEvent {
+    /// The interval of curve coordinates that the contact covers
+    ///
+    /// Both values are the same, unless the curve runs along the boundary.
+    interval: [Scalar; 2],
+
+    /// Whether the curve crosses from one side of the boundary to the other
+    crosses: bool,
+
+    contact: BoundaryContact,
+}
+
+/// Merge intervals across short gaps, and classify short intervals as touches
+///
+/// Expects the intervals to be sorted and not to overlap, which is how
+/// [`CurveFaceIntersectionList::compute`] builds them. The merged interval
+/// only touches the face, if all of the intervals it is made of do.
+fn snap_intervals(
+    intervals: Vec<CurveFaceIntersection>,
+    threshold: Scalar,
+) -> Vec<CurveFaceIntersection> {
+    let mut snapped: Vec<CurveFaceIntersection> = Vec::new();
+
+    for intersection in intervals {
+        if let Some(last) = snapped.last_mut() {
+            if intersection.interval[0] - last.interval[1] < threshold {
+                last.interval[1] = intersection.interval[1];
+                last.contacts[1] = intersection.contacts[1];
+                last.touches_only &= intersection.touches_only;
+                continue;
+            }
+        }
+
+        snapped.push(intersection);
+    }
+
+    for intersection in &mut snapped {
+        let [start, end] = intersection.interval;
+        if end - start < threshold {
+            intersection.touches_only = true;
+        }
+    }
+
+    snapped
+}
+
+/// Find the places where a line meets a cycle
+fn boundary_events(
+    origin: Point<2>,
+    direction: Vector<2>,
+    cycle: &Cycle,
+    tolerances: &IntersectionTolerances,
+    events: &mut Vec<BoundaryEvent>,
+) {
+    let vertices: Vec<_> = cycle
+        .edges()
+        .map(|edge| {
+            let line = match edge.curve().local_form() {
+                Curve::Line(line) => line,
+                _ => {
+                    todo!("Curve-face intersection only supports polygons")
+                }
+            };
+
+            // The vertices are in ascending order, which means the first one
+            // is the start of the edge.
+            let [start, _] = match edge.vertices().get() {
+                Some(vertices) => vertices,
+                None => todo!(
+                    "Curve-face intersection does not support faces with \
+                    continuous edges"
+                ),
+            };
+
+            (
+                line.point_from_line_coords(start.position()),
+                *start.global(),
+            )
+        })
+        .collect();
+
+    let n = vertices.len();
+
+    // The side of the line each vertex is on: `1`/`-1` for left/right, `0`
+    // for vertices that are on the line.
+    let mut sides: Vec<_> = vertices
+        .iter()
+        .map(|&(point, _)| {
+            let distance =
+                cross(direction, point - origin) / direction.magnitude();
+
+            if distance.abs() <= tolerances.coincidence_distance {
+                0
+            } else if distance > Scalar::ZERO {
+                1
+            } else {
+                -1
+            }
+        })
+        .collect();
+
+    // Vertices that are connected to a vertex on the line, by an edge that is
+    // tangent to the line, are on the line too. Only vertices that are on the
+    // line by distance are considered, so this doesn't propagate along a chain
+    // of edges that slowly drifts away from the line.
+    let on_line: Vec<_> = sides.iter().map(|&side| side == 0).collect();
+    for i in 0..n {
+        let j = (i + 1) % n;
+
+        if on_line[i] == on_line[j] {
+            continue;
+        }
+
+        let edge = vertices[j].0 - vertices[i].0;
+        let is_tangent = IntersectionTolerances::is_within_angle(
+            cross(direction, edge),
+            direction.magnitude() * edge.magnitude(),
+            tolerances.tangency_angle,
+        );
+
+        if is_tangent {
+            sides[i] = 0;
+            sides[j] = 0;
+        }
+    }
+
+    if sides.iter().all(|&side| side == 0) {
+        // The cycle is degenerate. It doesn't enclose any area.
+        return;
+    }
+
+    // Dividing by the squared magnitude directly, instead of by the
+    // magnitude twice, keeps curve coordinates exact where possible.
+    let t = |point: Point<2>| {
+        (point - origin).dot(&direction) / direction.dot(&direction)
+    };
+
+    // Edges that cross the line between their vertices.
+    for i in 0..n {
+        let j = (i + 1) % n;
+
+        if sides[i] * sides[j] < 0 {
+            let (p, _) = vertices[i];
+            let (q, _) = vertices[j];
+            let edge = q - p;
+
+            let t = cross(p - origin, edge) / cross(direction, edge);
+            events.push(BoundaryEvent {
+                interval: [t, t],
+                crosses: true,
+                contact: BoundaryContact::TransversalCross,
+            });
+        }
+    }
+
+    // Runs of consecutive vertices on the line. Start at a vertex that is not
+    // on the line, so no run wraps around the start of the cycle.
+    let first = sides
+        .iter()
+        .position(|&side| side != 0)
+        .expect("Checked above that not all vertices are on the line");
+
+    let mut i = 0;
+    while i < n {
+        let index = (first + i) % n;
+        if sides[index] != 0 {
+            i += 1;
+            continue;
+        }
+
+        let run_start = i;
+        while sides[(first + i) % n] == 0 {
+            i += 1;
+        }
+        let run = run_start..i;
+
+        let side_before = sides[(first + run.start - 1) % n];
+        let side_after = sides[(first + run.end) % n];
+        let crosses = side_before != side_after;
+
+        let (interval, contact) = if run.len() == 1 {
+            let (point, vertex) = vertices[(first + run.start) % n];
+            let t = t(point);
+
+            ([t, t], BoundaryContact::ThroughVertex { vertex })
+        } else {
+            let [a, b] = [run.start, run.end - 1]
+                .map(|i| t(vertices[(first + i) % n].0));
+
+            ([a.min(b), a.max(b)], BoundaryContact::TangentTouch)
+        };
+
+        events.push(BoundaryEvent {
+            interval,
+            crosses,
+            contact,
+        });
+    }
+}
+
+/// The z-component of the cross product of two 2-dimensional vectors
+fn cross(a: Vector<2>, b: Vector<2>) -> Scalar {
+    a.u * b.v - a.v * b.u
+}
 
 #[cfg(test)]
 mod tests {
-    use fj_math::{Line, Point, Vector};
+    use fj_math::{Aabb, Line, Point, Scalar, Transform, Vector};
 
-    use crate::objects::{Curve, Face, Surface};
+    use crate::{
+        algorithms::{
+            intersection::surface_surface, IntersectionTolerances,
+            TransformObject,
+        },
+        objects::{Curve, Face, GlobalVertex, Surface},
+    };
 
-    use super::CurveFaceIntersectionList;
+    use super::{
+        BoundaryContact, CurveFaceIntersection, CurveFaceIntersectionList,
+    };
 
     #[test]
     fn compute() {
@@ -217,7 +533,109 @@ mod tests {
 
         let expected =
             CurveFaceIntersectionList::from_intervals([[1., 2.], [4., 5.]]);
-        assert_eq!(CurveFaceIntersectionList::compute(&curve, &face), expected);
+        assert_eq!(
+            CurveFaceIntersectionList::compute(
+                &curve,
+                &face,
+                &IntersectionTolerances::default(),
+            ),
+            expected
+        );
+    }
+
+    #[test]
+    fn transversal_cross() {
+        let face = square();
+        let curve = line([-3., 0.], [1., 0.]);
+
+        let intervals: Vec<_> = CurveFaceIntersectionList::compute(
+            &curve,
+            &face,
+            &IntersectionTolerances::default(),
+        )
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            intervals,
+            [CurveFaceIntersection {
+                interval: [Scalar::ONE, Scalar::from_f64(5.)],
+                contacts: [BoundaryContact::TransversalCross; 2],
+                touches_only: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn tangent_touch() {
+        let face = square();
+
+        // The line runs along the bottom edge of the square, touching it
+        // without entering the face.
+        let curve = line([-3., -2.], [1., 0.]);
+
+        let intervals: Vec<_> = CurveFaceIntersectionList::compute(
+            &curve,
+            &face,
+            &IntersectionTolerances::default(),
+        )
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            intervals,
+            [CurveFaceIntersection {
+                interval: [Scalar::ONE, Scalar::from_f64(5.)],
+                contacts: [BoundaryContact::TangentTouch; 2],
+                touches_only: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn through_vertex() {
+        let face = square();
+        let vertex = |position: [f64; 3]| {
+            let vertex = GlobalVertex::from_position(position);
+            BoundaryContact::ThroughVertex { vertex }
+        };
+
+        // The diagonal of the square, entering and leaving through corners.
+        let curve = line([-3., -3.], [1., 1.]);
+        let intervals: Vec<_> = CurveFaceIntersectionList::compute(
+            &curve,
+            &face,
+            &IntersectionTolerances::default(),
+        )
+        .into_iter()
+        .collect();
+        assert_eq!(
+            intervals,
+            [CurveFaceIntersection {
+                interval: [Scalar::ONE, Scalar::from_f64(5.)],
+                contacts: [vertex([-2., -2., 0.]), vertex([2., 2., 0.])],
+                touches_only: false,
+            }]
+        );
+
+        // A line that only touches a corner results in a zero-length
+        // interval.
+        let curve = line([-3., 1.], [1., 1.]);
+        let intervals: Vec<_> = CurveFaceIntersectionList::compute(
+            &curve,
+            &face,
+            &IntersectionTolerances::default(),
+        )
+        .into_iter()
+        .collect();
+        assert_eq!(
+            intervals,
+            [CurveFaceIntersection {
+                interval: [Scalar::ONE, Scalar::ONE],
+                contacts: [vertex([-2., 2., 0.]); 2],
+                touches_only: true,
+            }]
+        );
     }
 
     #[test]
@@ -289,4 +707,220 @@ mod tests {
         ]);
         assert_eq!(merged, expected);
     }
+
+    #[test]
+    fn merge_keeps_contacts() {
+        let touch = CurveFaceIntersectionList::compute(
+            &line([-3., 1.], [1., 1.]),
+            &square(),
+            &IntersectionTolerances::default(),
+        );
+        let all = CurveFaceIntersectionList::from_intervals([[0., 2.]]);
+
+        // The point contact lies within the other interval, so it survives the
+        // merge, including its classification.
+        assert_eq!(touch.merge(&all), touch);
+        assert_eq!(
+            all.merge(&touch).into_iter().next().map(|i| i.interval),
+            Some([Scalar::ONE, Scalar::ONE])
+        );
+    }
+
+    #[test]
+    fn cubes_touching_face_to_face() {
+        let tolerances = IntersectionTolerances::default();
+
+        // Two unit cubes, one on top of the other. The top face of the lower
+        // cube coincides with the bottom face of the upper one.
+        let top_of_lower = Face::builder(
+            Surface::xy_plane()
+                .transform(&Transform::translation([0., 0., 1.])),
+        )
+        .with_exterior_polygon([[0., 0.], [1., 0.], [1., 1.], [0., 1.]])
+        .build();
+        let bottom_of_upper = top_of_lower.clone();
+        let side_of_upper = Face::builder(Surface::xz_plane())
+            .with_exterior_polygon([[0., 1.], [1., 1.], [1., 2.], [0., 2.]])
+            .build();
+
+        // Coincident faces don't intersect along a curve.
+        assert_eq!(
+            surface_surface(
+                top_of_lower.surface(),
+                bottom_of_upper.surface(),
+                &tolerances
+            ),
+            None
+        );
+
+        // The side of the upper cube meets the top of the lower one along
+        // their common edge. Both faces are on the same side of it, so the
+        // faces only touch.
+        let (curve_top, curve_side, _) = surface_surface(
+            top_of_lower.surface(),
+            side_of_upper.surface(),
+            &tolerances,
+        )
+        .expect("Faces are not parallel");
+
+        let top = CurveFaceIntersectionList::compute(
+            &curve_top,
+            &top_of_lower,
+            &tolerances,
+        );
+        let side = CurveFaceIntersectionList::compute(
+            &curve_side,
+            &side_of_upper,
+            &tolerances,
+        );
+
+        let merged = top.merge(&side);
+        let intervals: Vec<_> = merged.clone().into_iter().collect();
+        assert_eq!(intervals.len(), 1);
+        assert!(intervals[0].touches_only);
+        assert_eq!(intervals[0].contacts, [BoundaryContact::TangentTouch; 2]);
+
+        assert!(merged.without_touches().is_empty());
+    }
+
+    #[test]
+    fn relative_tolerances_are_scale_invariant() {
+        // A triangle whose top vertex almost touches the line. It's close
+        // enough to be considered on the line, relative to the size of the
+        // triangle.
+        let num_intervals = |scale: f64, tolerances: Option<_>| {
+            let points = [[-2., -2.], [2., -2.], [0., -1e-12]]
+                .map(|[u, v]| [u * scale, v * scale]);
+            let face = Face::builder(Surface::xy_plane())
+                .with_exterior_polygon(points)
+                .build();
+            let curve = line([-3. * scale, 0.], [1., 0.]);
+
+            let tolerances = tolerances.unwrap_or_else(|| {
+                IntersectionTolerances::relative_to(&Aabb::<3>::from_points(
+                    points.map(|[u, v]| [u, v, 0.]),
+                ))
+            });
+
+            CurveFaceIntersectionList::compute(&curve, &face, &tolerances)
+                .into_iter()
+                .count()
+        };
+
+        assert_eq!(num_intervals(1., None), 1);
+        assert_eq!(num_intervals(1e4, None), 1);
+
+        // Absolute tolerances only suit one scale.
+        let absolute = Some(IntersectionTolerances::default());
+        assert_eq!(num_intervals(1., absolute), 1);
+        assert_eq!(num_intervals(1e4, absolute), 0);
+    }
+
+    #[test]
+    fn short_intervals_and_gaps() {
+        let tolerances = IntersectionTolerances {
+            coincidence_distance: Scalar::from_f64(0.01),
+            ..IntersectionTolerances::default()
+        };
+        let curve = line([-3., 0.], [1., 0.]);
+
+        // A narrow notch from the top of the square, reaching across the line.
+        // The gap it leaves in the intersection is shorter than the
+        // coincidence distance.
+        let face = Face::builder(Surface::xy_plane())
+            .with_exterior_polygon([
+                [-2., -2.],
+                [2., -2.],
+                [2., 2.],
+                [0.001, 2.],
+                [0., -0.5],
+                [-0.001, 2.],
+                [-2., 2.],
+            ])
+            .build();
+        let intervals: Vec<_> =
+            CurveFaceIntersectionList::compute(&curve, &face, &tolerances)
+                .into_iter()
+                .collect();
+        assert_eq!(
+            intervals,
+            [CurveFaceIntersection {
+                interval: [Scalar::ONE, Scalar::from_f64(5.)],
+                contacts: [BoundaryContact::TransversalCross; 2],
+                touches_only: false,
+            }]
+        );
+
+        // A narrow spike that reaches across the line. The interval is shorter
+        // than the coincidence distance, so the line only grazes the face.
+        let face = Face::builder(Surface::xy_plane())
+            .with_exterior_polygon([[-0.001, -2.], [0.001, -2.], [0., 0.5]])
+            .build();
+        let intervals: Vec<_> =
+            CurveFaceIntersectionList::compute(&curve, &face, &tolerances)
+                .into_iter()
+                .collect();
+        assert_eq!(intervals.len(), 1);
+        assert!(intervals[0].touches_only);
+    }
+
+    #[test]
+    fn stable_near_tangency() {
+        let tolerances = IntersectionTolerances {
+            coincidence_distance: Scalar::from_f64(2.5e-7),
+            ..IntersectionTolerances::default()
+        };
+
+        // The top face of a solid, and the front face of a cube that is
+        // rotated by 45 degrees around the y-axis. The lowest corner of the
+        // cube is moved from below the top face to above it.
+        let top = Face::builder(Surface::xy_plane())
+            .with_exterior_polygon([[-2., -2.], [2., -2.], [2., 2.], [-2., 2.]])
+            .build();
+        let num_intervals = |offset: f64| {
+            let front = Face::builder(
+                Surface::xz_plane()
+                    .transform(&Transform::translation([0., 0., offset])),
+            )
+            .with_exterior_polygon([[0., 0.], [1., 1.], [0., 2.], [-1., 1.]])
+            .build();
+
+            let (curve_top, curve_front, _) =
+                surface_surface(top.surface(), front.surface(), &tolerances)
+                    .expect("Faces are not parallel");
+
+            CurveFaceIntersectionList::compute(&curve_top, &top, &tolerances)
+                .merge(&CurveFaceIntersectionList::compute(
+                    &curve_front,
+                    &front,
+                    &tolerances,
+                ))
+                .without_touches()
+                .into_iter()
+                .count()
+        };
+
+        let counts: Vec<_> = (0..=20)
+            .map(|i| num_intervals(-1e-6 + f64::from(i) * 1e-7))
+            .collect();
+
+        assert_eq!(counts[0], 1);
+        assert_eq!(counts[20], 0);
+        let changes = counts.windows(2).filter(|w| w[0] != w[1]).count();
+        assert_eq!(changes, 1, "Unstable intersection: {counts:?}");
+    }
+
+    /// A square from `[-2, -2]` to `[2, 2]`
+    fn square() -> Face {
+        Face::builder(Surface::xy_plane())
+            .with_exterior_polygon([[-2., -2.], [2., -2.], [2., 2.], [-2., 2.]])
+            .build()
+    }
+
+    fn line(origin: [f64; 2], direction: [f64; 2]) -> Curve<2> {
+        Curve::Line(Line {
+            origin: Point::from(origin),
+            direction: Vector::from(direction),
+        })
+    }
 }
@@ -1,9 +1,17 @@
 use fj_math::{Aabb, Line, Scalar, Segment, Vector};
 
+use super::IntersectionTolerances;
+
 /// Determine the intersection between a [`Line`] and a [`Segment`]
+///
+/// `line` and `segment` are considered parallel, if the angle between them is
+/// within [`IntersectionTolerances::parallelism_angle`]. Parallel ones are
+/// considered coincident, if the segment is within
+/// [`IntersectionTolerances::coincidence_distance`] of the line.
 pub fn line_segment(
     line: &Line<2>,
     segment: &Segment<2>,
+    tolerances: &IntersectionTolerances,
 ) -> Option<LineSegmentIntersection> {
     // Algorithm adapted from Real-Time Collision Detection by Christer Ericson.
     // See section 5.1.9.1, 2D Segment Intersection.
@@ -13,18 +21,28 @@ pub fn line_segment(
     // Find vector that is orthogonal to `segment`.
     let n = {
         let ab = b - a;
-        Vector::from([ab.v, ab.u])
+        Vector::from([-ab.v, ab.u])
     };
 
     let n_dot_origin = n.dot(&(b - line.origin));
     let n_dot_direction = n.dot(&line.direction);
 
-    if n_dot_origin == Scalar::ZERO && n_dot_direction == Scalar::ZERO {
-        // `line` and `segment` are not just parallel, but coincident!
-        return Some(LineSegmentIntersection::Coincident);
-    }
+    let is_parallel = IntersectionTolerances::is_within_angle(
+        n_dot_direction,
+        n.magnitude() * line.direction.magnitude(),
+        tolerances.parallelism_angle,
+    );
+
+    if is_parallel {
+        // `n` is orthogonal to `segment`, so this is the distance between the
+        // segment and the origin of the line.
+        let distance = n_dot_origin.abs() / n.magnitude();
+
+        if distance <= tolerances.coincidence_distance {
+            // `line` and `segment` are not just parallel, but coincident!
+            return Some(LineSegmentIntersection::Coincident);
+        }
 
-    if n_dot_direction == Scalar::ZERO {
         // `line` and `segment` are parallel, but not coincident
         return None;
     }
@@ -58,10 +76,14 @@ pub enum LineSegmentIntersection {
 mod tests {
     use fj_math::{Line, Point, Scalar, Segment, Vector};
 
-    use crate::algorithms::intersection::LineSegmentIntersection;
+    use crate::algorithms::intersection::{
+        IntersectionTolerances, LineSegmentIntersection,
+    };
 
     #[test]
     fn line_segment() {
+        let tolerances = IntersectionTolerances::default();
+
         let line = Line {
             origin: Point::origin(),
             direction: Vector::unit_u(),
@@ -72,6 +94,7 @@ mod tests {
             super::line_segment(
                 &line,
                 &Segment::from_points([[1., -1.], [1., 1.]]),
+                &tolerances,
             ),
             Some(LineSegmentIntersection::PointOnLine(Scalar::ONE)),
         );
@@ -81,6 +104,7 @@ mod tests {
             super::line_segment(
                 &line,
                 &Segment::from_points([[1., 0.], [2., 0.]]),
+                &tolerances,
             ),
             Some(LineSegmentIntersection::Coincident),
         );
@@ -90,6 +114,7 @@ mod tests {
             super::line_segment(
                 &line,
                 &Segment::from_points([[1., 1.], [1., 2.]]),
+                &tolerances,
             ),
             None,
         );
@@ -99,6 +124,7 @@ mod tests {
             super::line_segment(
                 &line,
                 &Segment::from_points([[1., -2.], [1., -1.]]),
+                &tolerances,
             ),
             None,
         );
@@ -108,8 +134,36 @@ mod tests {
             super::line_segment(
                 &line,
                 &Segment::from_points([[-1., 1.], [1., 1.]]),
+                &tolerances,
             ),
             None,
         );
     }
+
+    #[test]
+    fn line_segment_parallelism_angle() {
+        let tolerances = IntersectionTolerances::default();
+        let line = Line {
+            origin: Point::origin(),
+            direction: Vector::unit_u(),
+        };
+
+        // Segments that cross the line at its origin, at a shallow angle.
+        let [below, above] = [0.5, 2.].map(|factor| {
+            let (sin, cos) = (tolerances.parallelism_angle * factor).sin_cos();
+            Segment::from_points([
+                Point::from([-cos, -sin]),
+                Point::from([cos, sin]),
+            ])
+        });
+
+        assert_eq!(
+            super::line_segment(&line, &below, &tolerances),
+            Some(LineSegmentIntersection::Coincident)
+        );
+        assert!(matches!(
+            super::line_segment(&line, &above, &tolerances),
+            Some(LineSegmentIntersection::PointOnLine(_))
+        ));
+    }
 }
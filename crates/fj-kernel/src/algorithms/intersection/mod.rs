@@ -2,10 +2,16 @@
 
 mod curve_face;
 mod line_segment;
+mod plane_cylinder;
 mod surface_surface;
+mod tolerances;
 
 pub use self::{
-    curve_face::{CurveFaceIntersection, CurveFaceIntersectionList},
+    curve_face::{
+        BoundaryContact, CurveFaceIntersection, CurveFaceIntersectionList,
+    },
     line_segment::{line_segment, LineSegmentIntersection},
+    plane_cylinder::plane_cylinder,
     surface_surface::surface_surface,
+    tolerances::IntersectionTolerances,
 };
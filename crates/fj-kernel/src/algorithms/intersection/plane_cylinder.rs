@@ -0,0 +1,168 @@
+use fj_math::Ellipse;
+
+use crate::objects::{Curve, Surface};
+
+use super::IntersectionTolerances;
+
+/// Compute the intersection of a plane and a cylinder
+///
+/// The cylinder is a surface that is swept from a circle. It doesn't need to be
+/// a right cylinder, so the path can be oblique to the plane of the circle.
+///
+/// Returns `None`, if `plane` is not a plane, if `cylinder` is not a cylinder,
+/// or if the plane is parallel to the cylinder's axis, within
+/// [`IntersectionTolerances::parallelism_angle`]. In the latter case, the
+/// intersection consists of up to two lines, or none at all.
+///
+/// The resulting ellipse has the same curve coordinates as the cylinder's
+/// circle: the point at coordinate `t` on the ellipse lies on the line that is
+/// swept from the point at coordinate `t` on the circle. Use
+/// [`project_curve_into_surface`] to get the ellipse in the plane's
+/// coordinates.
+///
+/// [`project_curve_into_surface`]: crate::algorithms::project_curve_into_surface
+pub fn plane_cylinder(
+    plane: &Surface,
+    cylinder: &Surface,
+    tolerances: &IntersectionTolerances,
+) -> Option<Curve<3>> {
+    let Surface::SweptCurve(plane) = plane;
+    let Surface::SweptCurve(cylinder) = cylinder;
+
+    let line = match plane.curve {
+        Curve::Line(line) => line,
+        _ => return None,
+    };
+    let circle = match cylinder.curve {
+        Curve::Circle(circle) => circle,
+        _ => return None,
+    };
+
+    let normal = line.direction.cross(&plane.path);
+    let axis = cylinder.path;
+
+    // The axis is parallel to the plane, if it's orthogonal to its normal. The
+    // dot product is the cosine of the angle between them, which is the sine
+    // of the angle between axis and plane.
+    let axis_dot_normal = axis.dot(&normal);
+    if IntersectionTolerances::is_within_angle(
+        axis_dot_normal,
+        axis.magnitude() * normal.magnitude(),
+        tolerances.parallelism_angle,
+    ) {
+        return None;
+    }
+
+    // Every point of the cylinder is `circle(t) + axis * s`. Moving each point
+    // of the circle along the axis until it hits the plane is an affine map,
+    // so it maps the circle's center and semi-diameters onto the ellipse's.
+    let center = circle.center
+        + axis * ((line.origin - circle.center).dot(&normal) / axis_dot_normal);
+    let a = circle.a - axis * (circle.a.dot(&normal) / axis_dot_normal);
+    let b = circle.b - axis * (circle.b.dot(&normal) / axis_dot_normal);
+
+    Some(Curve::Ellipse(Ellipse { center, a, b }))
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use fj_math::{Circle, Line, Point, Scalar, Vector};
+
+    use crate::{
+        algorithms::IntersectionTolerances,
+        objects::{Curve, Surface, SweptCurve},
+    };
+
+    fn plane_cylinder(plane: &Surface, cylinder: &Surface) -> Option<Curve<3>> {
+        super::plane_cylinder(
+            plane,
+            cylinder,
+            &IntersectionTolerances::default(),
+        )
+    }
+
+    fn cylinder(radius: f64) -> Surface {
+        Surface::SweptCurve(SweptCurve {
+            curve: Curve::Circle(Circle {
+                center: Point::from([0., 0., 0.]),
+                a: Vector::from([radius, 0., 0.]),
+                b: Vector::from([0., radius, 0.]),
+            }),
+            path: Vector::from([0., 0., 1.]),
+        })
+    }
+
+    fn tilted_plane(angle: f64) -> Surface {
+        Surface::SweptCurve(SweptCurve {
+            curve: Curve::Line(Line {
+                origin: Point::from([0., 0., 3.]),
+                direction: Vector::from([0., 1., 0.]),
+            }),
+            path: Vector::from([angle.cos(), 0., angle.sin()]),
+        })
+    }
+
+    #[test]
+    fn section_of_right_cylinder() {
+        let radius = 2.;
+
+        for angle in [0.2, 0.5, 1.] {
+            let ellipse =
+                match plane_cylinder(&tilted_plane(angle), &cylinder(radius)) {
+                    Some(Curve::Ellipse(ellipse)) => ellipse,
+                    curve => panic!("Expected ellipse, got {curve:?}"),
+                };
+
+            assert_abs_diff_eq!(
+                ellipse.center,
+                Point::from([0., 0., 3.]),
+                epsilon = 1e-12
+            );
+
+            // The minor axis is the cylinder's radius. The major axis is
+            // stretched by the tilt of the plane.
+            let [major, minor] = ellipse.semi_axes();
+            assert_abs_diff_eq!(
+                major.magnitude(),
+                Scalar::from_f64(radius / angle.cos()),
+                epsilon = 1e-12
+            );
+            assert_abs_diff_eq!(
+                minor.magnitude(),
+                Scalar::from_f64(radius),
+                epsilon = 1e-12
+            );
+
+            // All points of the ellipse are on the cylinder.
+            for t in [0., 1., 2., 4.] {
+                let point = ellipse.point_from_ellipse_coords([t]);
+                assert_abs_diff_eq!(
+                    Vector::from([point.x, point.y]).magnitude(),
+                    Scalar::from_f64(radius),
+                    epsilon = 1e-12
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn plane_parallel_to_axis() {
+        let plane = Surface::xz_plane();
+        assert_eq!(plane_cylinder(&plane, &cylinder(1.)), None);
+    }
+
+    #[test]
+    fn plane_almost_parallel_to_axis() {
+        let tolerances = IntersectionTolerances::default();
+        let angle = tolerances.parallelism_angle.into_f64();
+
+        // `tilted_plane` is tilted against the xy-plane, so the angle between
+        // plane and axis is the complement.
+        let [below, above] = [0.5, 2.]
+            .map(|factor| std::f64::consts::FRAC_PI_2 - angle * factor);
+
+        assert_eq!(plane_cylinder(&tilted_plane(below), &cylinder(1.)), None);
+        assert!(plane_cylinder(&tilted_plane(above), &cylinder(1.)).is_some());
+    }
+}
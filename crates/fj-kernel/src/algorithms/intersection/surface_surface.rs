@@ -2,10 +2,17 @@ use fj_math::{Line, Point, Scalar, Vector};
 
 use crate::objects::{Curve, Surface};
 
+use super::IntersectionTolerances;
+
 /// Test intersection between two surfaces
+///
+/// Returns `None`, if the surfaces are parallel or coincident, which they are
+/// considered to be, if the angle between them is within
+/// [`IntersectionTolerances::parallelism_angle`].
 pub fn surface_surface(
     a: &Surface,
     b: &Surface,
+    tolerances: &IntersectionTolerances,
 ) -> Option<(Curve<2>, Curve<2>, Curve<3>)> {
     // Algorithm from Real-Time Collision Detection by Christer Ericson. See
     // section 5.4.4, Intersection of Two Planes.
@@ -21,18 +28,18 @@ pub fn surface_surface(
 
     let direction = a.normal.cross(&b.normal);
 
-    let denom = direction.dot(&direction);
-    if denom == Scalar::ZERO {
-        // Comparing `denom` against zero looks fishy. It's probably better to
-        // compare it against an epsilon value, but I don't know how large that
-        // epsilon should be.
-        //
-        // I'll just leave it like that, until we had the opportunity to collect
-        // some experience with this code.
-        // - @hannobraun
+    // Both normals are normalized, so the magnitude of their cross product is
+    // the sine of the angle between them.
+    if IntersectionTolerances::is_within_angle(
+        direction.magnitude(),
+        Scalar::ONE,
+        tolerances.parallelism_angle,
+    ) {
         return None;
     }
 
+    let denom = direction.dot(&direction);
+
     let origin = (b.normal * a.distance - a.normal * b.distance)
         .cross(&direction)
         / denom;
@@ -127,26 +134,29 @@ fn project_line_into_plane(
 
 #[cfg(test)]
 mod tests {
-    use fj_math::Transform;
+    use fj_math::{Line, Point, Scalar, Transform, Vector};
 
     use crate::{
-        algorithms::TransformObject,
-        objects::{Curve, Surface},
+        algorithms::{IntersectionTolerances, TransformObject},
+        objects::{Curve, Surface, SweptCurve},
     };
 
-    use super::surface_surface;
-
     #[test]
     fn plane_plane() {
         let xy = Surface::xy_plane();
         let xz = Surface::xz_plane();
 
+        let tolerances = IntersectionTolerances::default();
+        let surface_surface = |a: &Surface, b: &Surface| {
+            super::surface_surface(a, b, &tolerances)
+        };
+
         // Coincident and parallel planes don't have an intersection curve.
         assert_eq!(surface_surface(&xy, &xy), None);
         assert_eq!(
             surface_surface(
                 &xy,
-                &xy.transform(&Transform::translation([0., 0., 1.]))
+                &xy.clone().transform(&Transform::translation([0., 0., 1.]))
             ),
             None,
         );
@@ -160,4 +170,35 @@ mod tests {
             Some((expected_xy, expected_xz, expected_global))
         );
     }
+
+    #[test]
+    fn plane_plane_parallelism_angle() {
+        let tolerances = IntersectionTolerances::default();
+        let xy = Surface::xy_plane();
+
+        let below = tolerances.parallelism_angle * Scalar::from_f64(0.5);
+        let above = tolerances.parallelism_angle * Scalar::from_f64(2.);
+
+        assert_eq!(
+            super::surface_surface(&xy, &tilted_plane(below), &tolerances),
+            None
+        );
+        assert!(
+            super::surface_surface(&xy, &tilted_plane(above), &tolerances)
+                .is_some()
+        );
+    }
+
+    /// The xy-plane, rotated about the x-axis by `angle`
+    fn tilted_plane(angle: Scalar) -> Surface {
+        let (sin, cos) = angle.sin_cos();
+
+        Surface::SweptCurve(SweptCurve {
+            curve: Curve::Line(Line {
+                origin: Point::origin(),
+                direction: Vector::unit_x(),
+            }),
+            path: Vector::from([Scalar::ZERO, cos, sin]),
+        })
+    }
 }
@@ -0,0 +1,109 @@
+use fj_math::{Aabb, Scalar};
+
+/// Tolerances that decide the special cases of intersection algorithms
+///
+/// Intersection algorithms need to decide whether curves and surfaces are
+/// parallel, coincident, or tangent to each other. Due to the limited accuracy
+/// of floating point numbers, they can't compare against exact values to do
+/// that, and any fixed distance would only suit models of a specific scale.
+///
+/// Use [`IntersectionTolerances::relative_to`] to derive tolerances from the
+/// size of a model. The angles don't depend on the scale, but the distance
+/// does.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct IntersectionTolerances {
+    /// The maximum angle between two directions, to be considered parallel
+    ///
+    /// In radians. Applies to planes, to lines, and to the axis of a cylinder.
+    pub parallelism_angle: Scalar,
+
+    /// The maximum distance between two points, to be considered coincident
+    ///
+    /// This also applies to points that lie on a curve or surface.
+    pub coincidence_distance: Scalar,
+
+    /// The maximum angle between a curve and an edge, to be considered tangent
+    ///
+    /// In radians. A curve that passes through a vertex of an edge, at an
+    /// angle smaller than this, is considered to run along that edge.
+    pub tangency_angle: Scalar,
+}
+
+impl IntersectionTolerances {
+    /// The default parallelism angle, in radians
+    pub const PARALLELISM_ANGLE: f64 = 1e-9;
+
+    /// The default coincidence distance, relative to the size of the model
+    pub const RELATIVE_COINCIDENCE_DISTANCE: f64 = 1e-12;
+
+    /// The default tangency angle, in radians
+    pub const TANGENCY_ANGLE: f64 = 1e-9;
+
+    /// Derive the tolerances from the bounding volume of a model
+    ///
+    /// The coincidence distance is relative to the largest extent of the
+    /// bounding volume. This means scaling a model doesn't change the outcome
+    /// of the intersection algorithms.
+    pub fn relative_to(aabb: &Aabb<3>) -> Self {
+        let size = aabb.size();
+        let extent = size.x.max(size.y).max(size.z);
+
+        Self {
+            coincidence_distance: extent
+                * Scalar::from_f64(Self::RELATIVE_COINCIDENCE_DISTANCE),
+            ..Self::default()
+        }
+    }
+
+    /// Determine whether the angle between two directions is within a limit
+    ///
+    /// `sine` is the sine of the angle between the two directions, scaled by
+    /// `magnitudes`, the product of their magnitudes. That's the magnitude of
+    /// their cross product, for example. Directions that point opposite to
+    /// each other are considered parallel.
+    pub(super) fn is_within_angle(
+        sine: Scalar,
+        magnitudes: Scalar,
+        angle: Scalar,
+    ) -> bool {
+        let (sin, _) = angle.sin_cos();
+        sine.abs() <= magnitudes * sin
+    }
+}
+
+impl Default for IntersectionTolerances {
+    /// Tolerances for a model with a size of about one unit
+    fn default() -> Self {
+        Self {
+            parallelism_angle: Scalar::from_f64(Self::PARALLELISM_ANGLE),
+            coincidence_distance: Scalar::from_f64(
+                Self::RELATIVE_COINCIDENCE_DISTANCE,
+            ),
+            tangency_angle: Scalar::from_f64(Self::TANGENCY_ANGLE),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Aabb, Scalar};
+
+    use super::IntersectionTolerances;
+
+    #[test]
+    fn relative_to() {
+        let unit = Aabb::<3>::from_points([[0., 0., 0.], [1., 0.5, 0.]]);
+        let scaled = Aabb::<3>::from_points([[0., 0., 0.], [1e4, 5e3, 0.]]);
+
+        let unit = IntersectionTolerances::relative_to(&unit);
+        let scaled = IntersectionTolerances::relative_to(&scaled);
+
+        assert_eq!(unit, IntersectionTolerances::default());
+        assert_eq!(
+            scaled.coincidence_distance,
+            unit.coincidence_distance * Scalar::from_f64(1e4)
+        );
+        assert_eq!(scaled.parallelism_angle, unit.parallelism_angle);
+        assert_eq!(scaled.tangency_angle, unit.tangency_angle);
+    }
+}
@@ -0,0 +1,565 @@
+use fj_math::{Aabb, Line, Point, Scalar, Vector};
+
+use crate::{
+    iter::ObjectIters,
+    objects::{Curve, Cycle, Face, Sketch, Solid, Surface, SweptCurve},
+};
+
+use super::{
+    split::{contains, face_points, Plane},
+    CycleApprox, Handedness, Tolerance,
+};
+
+/// Describe an extrusion-like solid as a stack of 2D layers
+///
+/// This is intended for parts that are machined from sheet stock, by CNC
+/// milling or laser cutting. Those don't need a triangle mesh, but the outline
+/// of the part, and the boundaries of the pockets that are cut into it.
+///
+/// The solid must be extrusion-like along `axis`: All of its faces must be
+/// either perpendicular to the axis (caps), or parallel to it (walls). Faces
+/// may deviate from that by the angle at which they would depart by
+/// `tolerance` over the extent of the solid.
+///
+/// The part is machined from the top, which is the end of the solid that
+/// `axis` points to. Its bottom must be flat, and pockets must be open
+/// towards the top.
+///
+/// Returns a [`LayeredProfile`], which contains the outline of the part,
+/// any through-holes, and any pockets, with their respective depths. Curved
+/// boundaries are approximated within `tolerance`.
+pub fn layers_from_solid(
+    solid: &Solid,
+    axis: Vector<3>,
+    tolerance: Tolerance,
+) -> Result<LayeredProfile, LayerError> {
+    if axis.magnitude() == Scalar::ZERO {
+        return Err(LayerError::ZeroAxis);
+    }
+    let axis = axis.normalize();
+
+    let points: Vec<_> = solid
+        .face_iter()
+        .flat_map(|face| face_points(face, tolerance))
+        .collect();
+    if points.is_empty() {
+        return Err(LayerError::NoCaps);
+    }
+    let extent = Aabb::<3>::from_points(points).size().magnitude();
+
+    // The sine of the angle, by which faces may deviate from being parallel
+    // or perpendicular to the axis.
+    let max_sine = (tolerance.inner() / extent).min(Scalar::ONE);
+    let is_perpendicular = |direction: Vector<3>| {
+        direction.normalize().dot(&axis).abs() <= max_sine
+    };
+    let is_parallel = |direction: Vector<3>| {
+        direction.normalize().cross(&axis).magnitude() <= max_sine
+    };
+
+    let mut caps = Vec::new();
+
+    for (index, face) in solid.face_iter().enumerate() {
+        if let Some(triangles) = face.triangles() {
+            for (triangle, _) in triangles {
+                let normal = triangle.normal();
+
+                if is_perpendicular(normal) {
+                    continue;
+                }
+                if is_parallel(normal) {
+                    // Caps need cycles, to be turned into layers.
+                    return Err(LayerError::TriangleRepresentation {
+                        face: index,
+                    });
+                }
+
+                return Err(LayerError::NotExtrusionLike { face: index });
+            }
+
+            continue;
+        }
+
+        let plane = match Plane::new(face.surface()) {
+            Ok(plane) => plane,
+            Err(_) => {
+                // A curved surface is a wall, if it's swept along the axis.
+                let Surface::SweptCurve(surface) = face.surface();
+                if is_parallel(surface.path) {
+                    continue;
+                }
+
+                return Err(LayerError::NotExtrusionLike { face: index });
+            }
+        };
+
+        if is_perpendicular(plane.normal) {
+            continue;
+        }
+        if !is_parallel(plane.normal) {
+            return Err(LayerError::NotExtrusionLike { face: index });
+        }
+
+        let handedness = Handedness::of_face(face, tolerance)
+            .expect("Checked above that face uses boundary representation");
+
+        caps.push(Cap {
+            index,
+            face,
+            height: plane.origin.coords.dot(&axis),
+            faces_up: handedness.outward(plane.normal).dot(&axis)
+                > Scalar::ZERO,
+        });
+    }
+
+    let bottom = caps
+        .iter()
+        .filter(|cap| !cap.faces_up)
+        .map(|cap| cap.height)
+        .min();
+    let top = caps
+        .iter()
+        .filter(|cap| cap.faces_up)
+        .map(|cap| cap.height)
+        .max();
+    let (bottom, top) = match (bottom, top) {
+        (Some(bottom), Some(top)) => (bottom, top),
+        _ => return Err(LayerError::NoCaps),
+    };
+
+    let thickness = top - bottom;
+
+    let frame = Frame::new(axis, bottom);
+    let mut layers = Vec::new();
+
+    for cap in &caps {
+        if !cap.faces_up {
+            if cap.height - bottom > tolerance.inner() {
+                return Err(LayerError::BottomNotFlat { face: cap.index });
+            }
+
+            for cycle in cap.face.exteriors() {
+                layers.push(Layer {
+                    kind: LayerKind::Outline,
+                    depth: thickness,
+                    exterior: frame.project(cycle, true, tolerance),
+                    interiors: Vec::new(),
+                });
+            }
+            for cycle in cap.face.interiors() {
+                layers.push(Layer {
+                    kind: LayerKind::Through,
+                    depth: thickness,
+                    exterior: frame.project(cycle, true, tolerance),
+                    interiors: Vec::new(),
+                });
+            }
+
+            continue;
+        }
+
+        let depth = top - cap.height;
+        if depth <= tolerance.inner() {
+            // This is the top of the part, where no material is removed.
+            continue;
+        }
+
+        let exteriors: Vec<_> = cap
+            .face
+            .exteriors()
+            .map(|cycle| frame.project(cycle, true, tolerance))
+            .collect();
+        let mut interiors: Vec<_> =
+            exteriors.iter().map(|_| Vec::new()).collect();
+
+        for cycle in cap.face.interiors() {
+            let cycle = frame.project(cycle, false, tolerance);
+
+            // Assign the interior to the exterior that contains it. If the
+            // face has only one exterior, there's no need to check.
+            let exterior = if exteriors.len() == 1 {
+                Some(0)
+            } else {
+                cycle.first().and_then(|&point| {
+                    exteriors
+                        .iter()
+                        .position(|exterior| contains(exterior, point))
+                })
+            };
+
+            if let Some(i) = exterior {
+                interiors[i].push(cycle);
+            }
+        }
+
+        for (exterior, interiors) in exteriors.into_iter().zip(interiors) {
+            layers.push(Layer {
+                kind: LayerKind::Pocket,
+                depth,
+                exterior,
+                interiors,
+            });
+        }
+    }
+
+    layers.sort_by_key(|layer| (layer.kind, layer.depth));
+
+    Ok(LayeredProfile {
+        surface: frame.surface,
+        thickness,
+        layers,
+    })
+}
+
+/// An extrusion-like solid, described as a stack of 2D layers
+///
+/// Returned by [`layers_from_solid`].
+#[derive(Clone, Debug)]
+pub struct LayeredProfile {
+    /// The plane that the layers are defined in
+    ///
+    /// This plane is perpendicular to the axis, at the bottom of the part. Its
+    /// normal is the axis, and its parametrization is isometric.
+    pub surface: Surface,
+
+    /// The thickness of the part, along the axis
+    pub thickness: Scalar,
+
+    /// The layers of the part
+    ///
+    /// Sorted by kind, then by depth.
+    pub layers: Vec<Layer>,
+}
+
+impl LayeredProfile {
+    /// Access the layers of a specific kind
+    pub fn layers_of_kind(
+        &self,
+        kind: LayerKind,
+    ) -> impl Iterator<Item = &Layer> + '_ {
+        self.layers.iter().filter(move |layer| layer.kind == kind)
+    }
+
+    /// Convert a layer into a sketch, in the plane of this profile
+    ///
+    /// The resulting sketch can be exported, for example as SVG, with one file
+    /// per layer.
+    pub fn sketch(&self, layer: &Layer) -> Sketch {
+        let mut face = Face::builder(self.surface.clone())
+            .with_exterior_polygon(layer.exterior.iter().copied());
+        for interior in &layer.interiors {
+            face = face.with_interior_polygon(interior.iter().copied());
+        }
+
+        Sketch::from_faces([face.build()])
+    }
+}
+
+/// A layer of a [`LayeredProfile`]
+#[derive(Clone, Debug)]
+pub struct Layer {
+    /// The kind of layer
+    pub kind: LayerKind,
+
+    /// The depth of the layer, measured from the top of the part
+    ///
+    /// Outlines and through-holes go through the whole part, so their depth
+    /// is the thickness of the part.
+    pub depth: Scalar,
+
+    /// The boundary of the layer, counter-clockwise
+    ///
+    /// In coordinates of [`LayeredProfile::surface`]. The last point is not
+    /// repeated.
+    pub exterior: Vec<Point<2>>,
+
+    /// The boundaries of any islands within the layer, clockwise
+    ///
+    /// Only pockets have those. They are either material that rises above
+    /// the pocket floor, or pockets that go deeper, which are reported as
+    /// their own layers.
+    pub interiors: Vec<Vec<Point<2>>>,
+}
+
+/// The kind of a [`Layer`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum LayerKind {
+    /// The outline of the part
+    Outline,
+
+    /// A hole that goes through the whole part
+    Through,
+
+    /// A pocket that is cut into the part from the top
+    Pocket,
+}
+
+/// An error that can occur in [`layers_from_solid`]
+#[derive(Debug, thiserror::Error)]
+pub enum LayerError {
+    /// The axis has zero length
+    #[error("Axis of layers has zero length")]
+    ZeroAxis,
+
+    /// A face is neither perpendicular nor parallel to the axis
+    ///
+    /// The face is indexed in the order the faces of the solid are iterated
+    /// over.
+    #[error("Solid is not 2.5D: face {face} is tilted against the axis")]
+    NotExtrusionLike {
+        /// The index of the face
+        face: usize,
+    },
+
+    /// A face that is perpendicular to the axis uses triangle representation
+    #[error("Face {face} is perpendicular to the axis, but has no boundary")]
+    TriangleRepresentation {
+        /// The index of the face
+        face: usize,
+    },
+
+    /// A downward-facing face is not at the bottom of the part
+    ///
+    /// This would be a pocket that is open towards the bottom, which can't be
+    /// machined from the top.
+    #[error("Face {face} faces down, but is not at the bottom of the part")]
+    BottomNotFlat {
+        /// The index of the face
+        face: usize,
+    },
+
+    /// The solid has no top or no bottom
+    #[error("Solid has no faces that cap it along the axis")]
+    NoCaps,
+}
+
+struct Cap<'r> {
+    index: usize,
+    face: &'r Face,
+    height: Scalar,
+    faces_up: bool,
+}
+
+/// An orthonormal frame in the plane that the layers are defined in
+struct Frame {
+    surface: Surface,
+    origin: Point<3>,
+    u: Vector<3>,
+    v: Vector<3>,
+}
+
+impl Frame {
+    fn new(axis: Vector<3>, height: Scalar) -> Self {
+        // Any vector that isn't parallel to the axis can be projected into
+        // the plane, to get its u-axis.
+        let reference = if axis.x.abs() < Scalar::from_f64(0.9) {
+            Vector::unit_x()
+        } else {
+            Vector::unit_y()
+        };
+        let u = (reference - axis * reference.dot(&axis)).normalize();
+        let v = axis.cross(&u);
+
+        let origin = Point {
+            coords: axis * height,
+        };
+        let surface = Surface::SweptCurve(SweptCurve {
+            curve: Curve::Line(Line {
+                origin,
+                direction: u,
+            }),
+            path: v,
+        });
+
+        Self {
+            surface,
+            origin,
+            u,
+            v,
+        }
+    }
+
+    /// Project a cycle into the plane, winding it as requested
+    fn project(
+        &self,
+        cycle: &Cycle,
+        ccw: bool,
+        tolerance: Tolerance,
+    ) -> Vec<Point<2>> {
+        let mut points: Vec<_> = CycleApprox::new(cycle, tolerance)
+            .points
+            .into_iter()
+            .map(|point| {
+                let offset = *point.global_form() - self.origin;
+                Point::from([offset.dot(&self.u), offset.dot(&self.v)])
+            })
+            .collect();
+
+        // The end of the last edge is the start of the first.
+        if points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+
+        if (signed_area(&points) > Scalar::ZERO) != ccw {
+            points.reverse();
+        }
+
+        points
+    }
+}
+
+/// Twice the signed area of a polygon; positive, if it's counter-clockwise
+fn signed_area(points: &[Point<2>]) -> Scalar {
+    let mut area = Scalar::ZERO;
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        area += a.u * b.v - b.u * a.v;
+    }
+    area
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::Color;
+    use fj_math::{Point, Scalar, Triangle};
+
+    use crate::{
+        algorithms::{reverse_face, sweep, Tolerance, TransformObject},
+        iter::ObjectIters,
+        objects::{Face, Sketch, Solid, Surface},
+    };
+
+    use super::{layers_from_solid, LayerError, LayerKind};
+
+    #[test]
+    fn plate_with_hole_and_pocket() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        let color = [255, 0, 0, 255];
+
+        let outline = [[-2., -2.], [2., -2.], [2., 2.], [-2., 2.]];
+        let hole = [[-1.5, -0.5], [-0.5, -0.5], [-0.5, 0.5], [-1.5, 0.5]];
+        let pocket = [[0.5, -0.5], [1.5, -0.5], [1.5, 0.5], [0.5, 0.5]];
+
+        // A plate of thickness 2, with a through-hole. The top is replaced
+        // below, to make room for the pocket.
+        let plate = sweep(
+            Sketch::from_faces([Face::builder(Surface::xy_plane())
+                .with_exterior_polygon(outline)
+                .with_interior_polygon(hole)
+                .build()]),
+            [0., 0., 2.],
+            tolerance,
+            color,
+        )?;
+        let is_top = |face: &Face| {
+            super::face_points(face, tolerance)
+                .iter()
+                .all(|point| point.z == Scalar::from_f64(2.))
+        };
+        let mut faces: Vec<_> = plate
+            .face_iter()
+            .filter(|face| !is_top(face))
+            .cloned()
+            .collect();
+        faces.push(
+            Face::builder(Surface::xy_plane().translate([0., 0., 2.]))
+                .with_exterior_polygon(outline)
+                .with_interior_polygon(hole)
+                .with_interior_polygon(pocket)
+                .build(),
+        );
+
+        // The pocket, half as deep as the plate. It's the inside of a box,
+        // minus the top.
+        let pocket_box = sweep(
+            Sketch::from_faces([Face::builder(
+                Surface::xy_plane().translate([0., 0., 1.]),
+            )
+            .with_exterior_polygon(pocket)
+            .build()]),
+            [0., 0., 1.],
+            tolerance,
+            color,
+        )?;
+        faces.extend(
+            pocket_box
+                .face_iter()
+                .filter(|face| !is_top(face))
+                .map(reverse_face),
+        );
+
+        let profile = layers_from_solid(
+            &Solid::from_faces(faces),
+            [0., 0., 1.].into(),
+            tolerance,
+        )?;
+
+        assert_eq!(profile.thickness, Scalar::from_f64(2.));
+        assert_eq!(profile.layers.len(), 3);
+
+        let layer = |kind| {
+            let layers: Vec<_> = profile.layers_of_kind(kind).collect();
+            assert_eq!(layers.len(), 1);
+            layers[0]
+        };
+        let outline = layer(LayerKind::Outline);
+        let through = layer(LayerKind::Through);
+        let pocket = layer(LayerKind::Pocket);
+
+        assert_eq!(outline.depth, Scalar::from_f64(2.));
+        assert_eq!(through.depth, Scalar::from_f64(2.));
+        assert_eq!(pocket.depth, Scalar::from_f64(1.));
+
+        let centroid = |points: &[Point<2>]| {
+            points.iter().fold(Point::origin(), |sum, point| {
+                sum + point.coords / points.len() as f64
+            })
+        };
+        assert_eq!(outline.exterior.len(), 4);
+        assert_eq!(through.exterior.len(), 4);
+        assert_eq!(pocket.exterior.len(), 4);
+        assert_eq!(centroid(&through.exterior).u, Scalar::from_f64(-1.));
+        assert_eq!(centroid(&pocket.exterior).u, Scalar::from_f64(1.));
+        assert!(pocket.interiors.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sphere() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        let color: Color = [255, 0, 0, 255];
+
+        // A very coarse approximation of a sphere.
+        let [px, nx, py, ny, pz, nz] = [
+            [1., 0., 0.],
+            [-1., 0., 0.],
+            [0., 1., 0.],
+            [0., -1., 0.],
+            [0., 0., 1.],
+            [0., 0., -1.],
+        ]
+        .map(Point::from);
+        let triangles = [
+            [px, py, pz],
+            [py, nx, pz],
+            [nx, ny, pz],
+            [ny, px, pz],
+            [py, px, nz],
+            [nx, py, nz],
+            [ny, nx, nz],
+            [px, ny, nz],
+        ]
+        .map(|points| (Triangle::from(points), color));
+        let sphere =
+            Solid::from_faces([Face::from_triangles(triangles.to_vec())]);
+
+        let result = layers_from_solid(&sphere, [0., 0., 1.].into(), tolerance);
+        assert!(matches!(
+            result,
+            Err(LayerError::NotExtrusionLike { face: 0 })
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,355 @@
+use std::{collections::HashMap, iter};
+
+use fj_interop::{
+    debug::DebugInfo,
+    mesh::{Index, Mesh},
+};
+use fj_math::Point;
+
+use crate::objects::{Cycle, Face};
+
+use super::{
+    approx::{
+        approx_edge_points, estimate_num_points_for_curve, refine_curve_approx,
+    },
+    triangulate::{
+        triangulate_face, triangulate_face_approx, TriangulationConfig,
+        TriangulationError,
+    },
+    ApproxStore, CycleApprox, FaceApprox, Tolerance,
+};
+
+/// Triangulate faces at several levels of detail
+///
+/// Returns one level per tolerance, from the coarsest to the finest. All levels
+/// share one vertex buffer (see [`Lods::vertices`]), which starts with the
+/// vertices of the coarsest level.
+///
+/// If the tolerances are nested, that is each is a whole multiple of the next
+/// finer one, the finer levels are refined from the coarser ones, instead of
+/// being approximated from scratch. Then every vertex of a level is also a
+/// vertex of all finer levels, and the buffer only grows by the vertices that
+/// each level adds. Otherwise, each level is triangulated on its own.
+pub fn generate_lods(
+    faces: &[Face],
+    tolerances: &[Tolerance],
+    approx_store: &mut ApproxStore,
+    debug_info: &mut DebugInfo,
+) -> Result<Lods, TriangulationError> {
+    let mut tolerances = tolerances.to_vec();
+    tolerances.sort();
+    tolerances.reverse();
+
+    let mut meshes: Vec<_> = tolerances.iter().map(|_| Mesh::new()).collect();
+    let mut curve_evaluations = 0;
+
+    let config = TriangulationConfig::default();
+    let is_nested = is_nested(&tolerances);
+
+    for (index, face) in faces.iter().enumerate() {
+        if face.triangles().is_some() || !is_nested {
+            for (&tolerance, mesh) in tolerances.iter().zip(&mut meshes) {
+                if face.triangles().is_none() {
+                    curve_evaluations += num_evaluations(face, tolerance);
+                }
+
+                triangulate_face(
+                    face,
+                    index,
+                    tolerance,
+                    approx_store,
+                    debug_info,
+                    &config,
+                    mesh,
+                )?;
+            }
+            continue;
+        }
+
+        let approxes =
+            refined_face_approx(face, &tolerances, &mut curve_evaluations);
+        for (approx, mesh) in approxes.iter().zip(&mut meshes) {
+            triangulate_face_approx(
+                face, index, approx, debug_info, &config, mesh,
+            )?;
+        }
+    }
+
+    let mut vertices = Vec::new();
+    let mut indices_by_vertex = HashMap::new();
+    let levels = tolerances
+        .into_iter()
+        .zip(meshes)
+        .map(|(tolerance, mesh)| {
+            let mesh_vertices: Vec<_> = mesh.vertices().collect();
+            let indices = mesh
+                .indices()
+                .map(|index| {
+                    let vertex = mesh_vertices[index as usize];
+                    *indices_by_vertex.entry(vertex).or_insert_with(|| {
+                        vertices.push(vertex);
+                        (vertices.len() - 1) as Index
+                    })
+                })
+                .collect();
+
+            Lod {
+                tolerance,
+                mesh,
+                indices,
+            }
+        })
+        .collect();
+
+    Ok(Lods {
+        vertices,
+        levels,
+        curve_evaluations,
+    })
+}
+
+/// Triangle meshes of a shape, at several levels of detail
+///
+/// See [`generate_lods`].
+pub struct Lods {
+    vertices: Vec<Point<3>>,
+    levels: Vec<Lod>,
+    curve_evaluations: u64,
+}
+
+impl Lods {
+    /// Access the vertex buffer that all levels share
+    pub fn vertices(&self) -> &[Point<3>] {
+        &self.vertices
+    }
+
+    /// Access the levels, from the coarsest to the finest
+    pub fn levels(&self) -> &[Lod] {
+        &self.levels
+    }
+
+    /// Access the number of points on curves that were computed
+    ///
+    /// This is a measure of the work that went into approximating the faces.
+    pub fn curve_evaluations(&self) -> u64 {
+        self.curve_evaluations
+    }
+}
+
+/// A level of detail
+pub struct Lod {
+    tolerance: Tolerance,
+    mesh: Mesh<Point<3>>,
+    indices: Vec<Index>,
+}
+
+impl Lod {
+    /// Access the tolerance of this level
+    pub fn tolerance(&self) -> Tolerance {
+        self.tolerance
+    }
+
+    /// Access the mesh of this level
+    pub fn mesh(&self) -> &Mesh<Point<3>> {
+        &self.mesh
+    }
+
+    /// Access the indices of this level into the shared vertex buffer
+    ///
+    /// Every three indices make up a triangle of [`Lod::mesh`].
+    pub fn indices(&self) -> &[Index] {
+        &self.indices
+    }
+}
+
+/// Determine whether each tolerance is a whole multiple of the next
+///
+/// Expects the tolerances to be sorted from the coarsest to the finest.
+fn is_nested(tolerances: &[Tolerance]) -> bool {
+    tolerances.windows(2).all(|pair| {
+        let ratio = (pair[0].inner() / pair[1].inner()).into_f64();
+        (ratio - ratio.round()).abs() < 1e-9
+    })
+}
+
+/// Approximate a face at each tolerance, refining the coarser approximations
+///
+/// Expects the tolerances to be sorted from the coarsest to the finest.
+fn refined_face_approx(
+    face: &Face,
+    tolerances: &[Tolerance],
+    curve_evaluations: &mut u64,
+) -> Vec<FaceApprox> {
+    let num_levels = tolerances.len();
+
+    let mut refine_cycles = |cycles: Vec<&Cycle>| {
+        let mut levels: Vec<Vec<_>> =
+            iter::repeat_with(Vec::new).take(num_levels).collect();
+
+        for cycle in cycles {
+            let mut edge_levels: Vec<Vec<_>> =
+                iter::repeat_with(Vec::new).take(num_levels).collect();
+
+            for edge in &cycle.edges {
+                let curve = edge.curve().global_form();
+
+                let mut points = approx_edge_points(edge, tolerances[0]);
+                *curve_evaluations +=
+                    estimate_num_points_for_curve(curve, tolerances[0]);
+                edge_levels[0].push(points.clone());
+
+                for (&tolerance, edges) in
+                    tolerances.iter().zip(&mut edge_levels).skip(1)
+                {
+                    points = refine_curve_approx(
+                        curve,
+                        &points,
+                        tolerance,
+                        curve_evaluations,
+                    );
+                    edges.push(points.clone());
+                }
+            }
+
+            for (edge_points, cycles) in
+                edge_levels.into_iter().zip(&mut levels)
+            {
+                cycles.push(CycleApprox::from_edge_points(cycle, edge_points));
+            }
+        }
+
+        levels
+    };
+
+    let exteriors = refine_cycles(face.exteriors().collect());
+    let interiors = refine_cycles(face.interiors().collect());
+
+    exteriors
+        .into_iter()
+        .zip(interiors)
+        .map(|(exteriors, interiors)| {
+            FaceApprox::from_cycles(face, exteriors, interiors)
+        })
+        .collect()
+}
+
+/// The number of points on curves, that approximating a face computes
+fn num_evaluations(face: &Face, tolerance: Tolerance) -> u64 {
+    face.all_cycles()
+        .flat_map(|cycle| &cycle.edges)
+        .map(|edge| {
+            estimate_num_points_for_curve(edge.curve().global_form(), tolerance)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use fj_interop::debug::DebugInfo;
+    use fj_math::Scalar;
+
+    use crate::{
+        algorithms::{sweep, ApproxStore, Tolerance},
+        iter::ObjectIters,
+        objects::{Cycle, Edge, Face, Sketch, Surface},
+    };
+
+    use super::generate_lods;
+
+    #[test]
+    fn nested_levels_share_vertices() -> anyhow::Result<()> {
+        let faces = cylinder()?;
+        let coarse = Tolerance::from_scalar(0.1)?;
+        let fine = Tolerance::from_scalar(0.01)?;
+
+        let lods = generate_lods(
+            &faces,
+            &[fine, coarse],
+            &mut ApproxStore::new(),
+            &mut DebugInfo::new(),
+        )?;
+
+        let [coarse_level, fine_level] = [&lods.levels()[0], &lods.levels()[1]];
+        assert_eq!(coarse_level.tolerance(), coarse);
+        assert_eq!(fine_level.tolerance(), fine);
+
+        // Points are compared exactly, not within some epsilon.
+        let fine_vertices: HashSet<_> = fine_level.mesh().vertices().collect();
+        for vertex in coarse_level.mesh().vertices() {
+            assert!(fine_vertices.contains(&vertex));
+        }
+        assert_eq!(lods.vertices().len(), fine_vertices.len());
+
+        // Refining the coarse level takes less work than approximating the
+        // fine level from scratch.
+        let independent: u64 = [coarse, fine]
+            .into_iter()
+            .map(|tolerance| {
+                generate_lods(
+                    &faces,
+                    &[tolerance],
+                    &mut ApproxStore::new(),
+                    &mut DebugInfo::new(),
+                )
+                .map(|lods| lods.curve_evaluations())
+            })
+            .sum::<Result<_, _>>()?;
+        assert!(lods.curve_evaluations() < independent);
+
+        Ok(())
+    }
+
+    #[test]
+    fn levels_that_are_not_nested() -> anyhow::Result<()> {
+        let faces = cylinder()?;
+        let tolerances =
+            [Tolerance::from_scalar(0.1)?, Tolerance::from_scalar(0.03)?];
+
+        let lods = generate_lods(
+            &faces,
+            &tolerances,
+            &mut ApproxStore::new(),
+            &mut DebugInfo::new(),
+        )?;
+
+        // Every level can be drawn from the shared vertex buffer.
+        for level in lods.levels() {
+            let points: Vec<_> = level
+                .indices()
+                .iter()
+                .map(|&index| lods.vertices()[index as usize])
+                .collect();
+            let expected: Vec<_> = level
+                .mesh()
+                .triangles()
+                .flat_map(|triangle| triangle.points)
+                .collect();
+            assert_eq!(points, expected);
+        }
+
+        Ok(())
+    }
+
+    fn cylinder() -> anyhow::Result<Vec<Face>> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let circle = Face::new(
+            Surface::xy_plane(),
+            [Cycle {
+                edges: vec![Edge::circle_from_radius(Scalar::ONE)],
+            }],
+            [],
+            [255, 0, 0, 255],
+        );
+        let solid = sweep(
+            Sketch::from_faces([circle]),
+            [0., 0., 1.],
+            tolerance,
+            [255, 0, 0, 255],
+        )?;
+
+        Ok(solid.face_iter().cloned().collect())
+    }
+}
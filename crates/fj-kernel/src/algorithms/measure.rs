@@ -0,0 +1,143 @@
+use fj_interop::{
+    debug::DebugInfo, half_edge::HalfEdgeMesh, mesh::Mesh,
+    physical_summary::PhysicalSummary,
+};
+use fj_math::{Point, Scalar};
+
+use crate::{iter::ObjectIters, objects::Solid};
+
+use super::{
+    triangulate, ApproxStore, ErrorBudget, Tolerance, TriangulationError,
+};
+
+/// Measure the volume of a solid
+///
+/// The solid is triangulated with `tolerance`, and the volume is computed from
+/// the triangles, using the divergence theorem. For solids that only consist
+/// of planar faces, the result is exact. For curved solids, it is as accurate
+/// as the approximation.
+///
+/// The volume is signed. It is negative, if the faces of the solid point
+/// inward, which makes inverted solids detectable. Cavities, whose faces point
+/// inward, reduce the volume.
+///
+/// Faces that don't form closed shells don't enclose anything, so their
+/// volume is zero.
+pub fn volume(
+    solid: &Solid,
+    tolerance: Tolerance,
+) -> Result<Scalar, TriangulationError> {
+    let mesh = mesh(solid, tolerance)?;
+
+    if !HalfEdgeMesh::from_mesh(&mesh).boundary_loops().is_empty() {
+        return Ok(Scalar::ZERO);
+    }
+
+    Ok(PhysicalSummary::from_mesh(&mesh, tolerance.inner()).volume)
+}
+
+/// Measure the area of the surface of a shape
+///
+/// Sums up the areas of the triangles of the shape, triangulated with
+/// `tolerance`. Unlike [`volume`], this works for any shape, not just closed
+/// solids.
+pub fn surface_area<'r>(
+    shape: &'r impl ObjectIters<'r>,
+    tolerance: Tolerance,
+) -> Result<Scalar, TriangulationError> {
+    let mesh = mesh(shape, tolerance)?;
+    Ok(PhysicalSummary::from_mesh(&mesh, tolerance.inner()).surface_area)
+}
+
+/// Compute the center of mass of a solid, assuming uniform density
+///
+/// Computed from the same triangles as [`volume`]. If the solid doesn't
+/// enclose any volume, this is the center of its surface instead.
+pub fn center_of_mass(
+    solid: &Solid,
+    tolerance: Tolerance,
+) -> Result<Point<3>, TriangulationError> {
+    let mesh = mesh(solid, tolerance)?;
+    Ok(PhysicalSummary::from_mesh(&mesh, tolerance.inner()).centroid)
+}
+
+fn mesh<'r>(
+    shape: &'r impl ObjectIters<'r>,
+    tolerance: Tolerance,
+) -> Result<Mesh<Point<3>>, TriangulationError> {
+    triangulate(
+        shape.face_iter().cloned().collect(),
+        tolerance,
+        &ErrorBudget::new(),
+        &mut ApproxStore::new(),
+        &mut DebugInfo::new(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        algorithms::{reverse_face, Tolerance, TransformObject},
+        objects::{Face, Solid, Surface},
+    };
+
+    use super::{center_of_mass, surface_area, volume};
+
+    #[test]
+    fn cube() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        let cube = Solid::cube_from_edge_length(1.).translate([1., 2., 3.]);
+
+        assert!(is_close(volume(&cube, tolerance)?, 1.));
+        assert!(is_close(surface_area(&cube, tolerance)?, 6.));
+
+        let center = center_of_mass(&cube, tolerance)?;
+        let distance = (center - Point::from([1., 2., 3.])).magnitude();
+        assert!(distance < Scalar::from_f64(1e-9));
+
+        // Reversing all faces turns the cube inside out.
+        let inverted = Solid::from_faces(cube.faces().map(reverse_face));
+        assert!(is_close(volume(&inverted, tolerance)?, -1.));
+
+        Ok(())
+    }
+
+    #[test]
+    fn cube_with_cavity() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let outer = Solid::cube_from_edge_length(2.);
+        let cavity = Solid::cube_from_edge_length(1.);
+        let solid = Solid::from_faces(
+            outer
+                .faces()
+                .cloned()
+                .chain(cavity.faces().map(reverse_face)),
+        );
+
+        assert!(is_close(volume(&solid, tolerance)?, 7.));
+        assert!(is_close(surface_area(&solid, tolerance)?, 30.));
+
+        Ok(())
+    }
+
+    #[test]
+    fn face() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        let face = Face::builder(Surface::xy_plane().translate([0., 0., 1.]))
+            .with_exterior_polygon([[0., 0.], [2., 0.], [2., 1.], [0., 1.]])
+            .build();
+        let shape = Solid::from_faces([face]);
+
+        assert_eq!(volume(&shape, tolerance)?, Scalar::ZERO);
+        assert!(is_close(surface_area(&shape, tolerance)?, 2.));
+
+        Ok(())
+    }
+
+    fn is_close(value: Scalar, expected: f64) -> bool {
+        (value.into_f64() - expected).abs() < 1e-9
+    }
+}
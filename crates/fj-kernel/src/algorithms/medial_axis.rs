@@ -0,0 +1,543 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    iter,
+};
+
+use fj_math::{Point, Scalar, Vector};
+use spade::HasPosition;
+
+use crate::objects::{Curve, Face, Surface};
+
+use super::{FaceApprox, Tolerance};
+
+/// Compute an approximation of the medial axis of a planar face
+///
+/// The medial axis (or skeleton) of a face is the set of points that have more
+/// than one closest point on the face's boundary. Every point on it is the
+/// center of a circle that touches the boundary in at least two points, without
+/// crossing it. The radius of that circle is the clearance at that point.
+///
+/// The boundary is approximated using `tolerance`, then sampled densely (with a
+/// spacing of `tolerance`). The medial axis is approximated by the edges of
+/// the Voronoi diagram of those samples that are inside the face and separate
+/// samples that are far apart, relative to the local clearance. This filters
+/// out the Voronoi edges between neighboring samples on the same boundary
+/// segment, which are artifacts of the sampling.
+///
+/// Faces with holes produce skeleton loops around the holes.
+///
+/// Positions and clearances are given in surface coordinates. They are only
+/// meaningful as distances, if the surface's coordinate axes are orthogonal and
+/// of unit length.
+pub fn medial_axis(
+    face: &Face,
+    tolerance: Tolerance,
+) -> Result<Skeleton, MedialAxisError> {
+    if face.triangles().is_some() {
+        return Err(MedialAxisError::TriangleRepresentation);
+    }
+
+    let Surface::SweptCurve(surface) = face.surface();
+    if !matches!(surface.curve, Curve::Line(_)) {
+        return Err(MedialAxisError::NonPlanar);
+    }
+
+    let approx = FaceApprox::new(face, tolerance);
+    let boundary: Vec<Vec<Point<2>>> = iter::once(&approx.exterior)
+        .chain(&approx.interiors)
+        .map(|cycle| {
+            let mut points: Vec<_> = cycle
+                .points
+                .iter()
+                .map(|point| *point.local_form())
+                .collect();
+
+            // The approximation of a cycle is closed, which means the first
+            // point is repeated at the end.
+            if points.len() > 1 && points.first() == points.last() {
+                points.pop();
+            }
+
+            points
+        })
+        .collect();
+
+    let samples = sample_boundary(&boundary, tolerance.inner());
+    let triangles = voronoi_vertices(samples.clone(), &boundary)?;
+
+    // Merging the Voronoi vertices that coincide (which happens if more than 3
+    // samples are co-circular) leaves us with the nodes of the skeleton.
+    let mut nodes = UnionFind::new(triangles.len());
+    let mut edges = Vec::new();
+
+    let mut triangles_by_edge: HashMap<[usize; 2], Vec<usize>> = HashMap::new();
+    for (index, triangle) in triangles.iter().enumerate() {
+        let [a, b, c] = triangle.samples;
+        for [i, j] in [[a, b], [b, c], [c, a]] {
+            triangles_by_edge
+                .entry(edge_key(i, j))
+                .or_default()
+                .push(index);
+        }
+    }
+
+    for ([i, j], adjacent) in &triangles_by_edge {
+        let (t, u) = match adjacent.as_slice() {
+            [t, u] => (*t, *u),
+            _ => continue,
+        };
+        let (t_triangle, u_triangle) = (&triangles[t], &triangles[u]);
+
+        // Only keep the Voronoi edges that separate samples that are far apart
+        // relative to the clearance. Edges between samples that are close
+        // together on the same boundary segment have nothing to do with the
+        // medial axis.
+        let distance = (samples[*i] - samples[*j]).magnitude();
+        let clearance = t_triangle.clearance.min(u_triangle.clearance);
+        if distance < clearance {
+            continue;
+        }
+
+        let length = (t_triangle.center - u_triangle.center).magnitude();
+        if length <= tolerance.inner() * MERGE_FACTOR {
+            nodes.union(t, u);
+        } else {
+            edges.push([t, u]);
+        }
+    }
+
+    let mut adjacency: BTreeMap<usize, BTreeSet<usize>> = BTreeMap::new();
+    for [t, u] in edges {
+        let [t, u] = [nodes.find(t), nodes.find(u)];
+        if t == u {
+            continue;
+        }
+
+        adjacency.entry(t).or_default().insert(u);
+        adjacency.entry(u).or_default().insert(t);
+    }
+    let adjacency: BTreeMap<usize, Vec<usize>> = adjacency
+        .into_iter()
+        .map(|(node, neighbors)| (node, neighbors.into_iter().collect()))
+        .collect();
+
+    let point = |node: usize| SkeletonPoint {
+        position: triangles[node].center,
+        clearance: triangles[node].clearance,
+    };
+
+    let mut visited = HashSet::new();
+    let mut branches = Vec::new();
+
+    // Start with the branches that begin at endpoints or branch points. Any
+    // edges that are left over afterwards form loops.
+    for only_junctions in [true, false] {
+        for (&node, neighbors) in &adjacency {
+            if only_junctions && neighbors.len() == 2 {
+                continue;
+            }
+
+            for &next in neighbors {
+                if visited.contains(&edge_key(node, next)) {
+                    continue;
+                }
+
+                let branch = walk_branch(node, next, &adjacency, &mut visited);
+                branches.push(SkeletonBranch {
+                    points: branch.into_iter().map(point).collect(),
+                });
+            }
+        }
+    }
+
+    Ok(Skeleton { branches })
+}
+
+/// An approximation of the medial axis of a face
+///
+/// Returned by [`medial_axis`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Skeleton {
+    /// The branches of the skeleton
+    ///
+    /// Branches meet at their endpoints. A branch whose first and last points
+    /// are identical forms a loop.
+    pub branches: Vec<SkeletonBranch>,
+}
+
+impl Skeleton {
+    /// Find the largest circle that fits into the face
+    ///
+    /// Returns `None`, if the skeleton is empty.
+    pub fn max_inscribed_circle(&self) -> Option<SkeletonPoint> {
+        self.points()
+            .max_by(|a, b| a.clearance.cmp(&b.clearance))
+            .copied()
+    }
+
+    /// Find the points where three or more branches meet
+    pub fn branch_points(&self) -> Vec<SkeletonPoint> {
+        let mut branch_ends: HashMap<Point<2>, (SkeletonPoint, usize)> =
+            HashMap::new();
+
+        for branch in &self.branches {
+            let ends = [branch.points.first(), branch.points.last()];
+            for &end in ends.iter().flatten() {
+                branch_ends.entry(end.position).or_insert((*end, 0)).1 += 1;
+            }
+        }
+
+        let mut branch_points: Vec<_> = branch_ends
+            .into_values()
+            .filter(|(_, num_branches)| *num_branches >= 3)
+            .map(|(point, _)| point)
+            .collect();
+        branch_points.sort_by_key(|point| point.position);
+
+        branch_points
+    }
+
+    /// Compute the local width of the face at a point
+    ///
+    /// This is twice the clearance of the skeleton point closest to `point`.
+    /// Returns `None`, if the skeleton is empty.
+    pub fn local_width(&self, point: impl Into<Point<2>>) -> Option<Scalar> {
+        let point = point.into();
+
+        self.points()
+            .min_by_key(|skeleton_point| {
+                (skeleton_point.position - point).magnitude()
+            })
+            .map(|skeleton_point| skeleton_point.clearance * 2.)
+    }
+
+    fn points(&self) -> impl Iterator<Item = &SkeletonPoint> {
+        self.branches.iter().flat_map(|branch| &branch.points)
+    }
+}
+
+/// A branch of a [`Skeleton`]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SkeletonBranch {
+    /// The points that make up the branch, as a polyline
+    pub points: Vec<SkeletonPoint>,
+}
+
+/// A point on a [`Skeleton`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SkeletonPoint {
+    /// The position of the point, in surface coordinates
+    pub position: Point<2>,
+
+    /// The distance from the point to the closest point on the boundary
+    pub clearance: Scalar,
+}
+
+/// Error computing the medial axis of a face
+#[derive(Debug, thiserror::Error)]
+pub enum MedialAxisError {
+    /// The face is not planar
+    #[error("Medial axis can only be computed for planar faces")]
+    NonPlanar,
+
+    /// The face uses triangle representation
+    #[error(
+        "Medial axis can't be computed for faces in triangle representation"
+    )]
+    TriangleRepresentation,
+
+    /// The face has coordinates that can't be triangulated
+    #[error("Face has coordinates that can't be triangulated")]
+    InvalidCoordinates(#[from] spade::InsertionError),
+}
+
+/// Voronoi vertices closer together than this factor times the tolerance are
+/// merged into a single skeleton node
+const MERGE_FACTOR: f64 = 1e-6;
+
+fn sample_boundary(
+    boundary: &[Vec<Point<2>>],
+    spacing: Scalar,
+) -> Vec<Point<2>> {
+    let mut samples = Vec::new();
+
+    for points in boundary {
+        for (i, &a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+
+            let num_samples =
+                ((b - a).magnitude() / spacing).ceil().into_u64().max(1);
+
+            for j in 0..num_samples {
+                let t = Scalar::from_u64(j) / Scalar::from_u64(num_samples);
+                samples.push(a + (b - a) * t);
+            }
+        }
+    }
+
+    samples
+}
+
+/// A Voronoi vertex, as the circumcircle of a Delaunay triangle
+struct VoronoiVertex {
+    samples: [usize; 3],
+    center: Point<2>,
+    clearance: Scalar,
+}
+
+/// Compute the Voronoi vertices of the samples that are inside the face
+///
+/// The index of every returned vertex is stable, as it is used to identify
+/// skeleton nodes.
+fn voronoi_vertices(
+    samples: Vec<Point<2>>,
+    boundary: &[Vec<Point<2>>],
+) -> Result<Vec<VoronoiVertex>, MedialAxisError> {
+    use spade::Triangulation as _;
+
+    let samples = samples
+        .into_iter()
+        .enumerate()
+        .map(|(index, position)| Sample { index, position })
+        .collect();
+    let triangulation =
+        spade::DelaunayTriangulation::<Sample>::bulk_load(samples)?;
+
+    let mut vertices = Vec::new();
+    for triangle in triangulation.inner_faces() {
+        let [a, b, c] = triangle.vertices().map(|vertex| *vertex.data());
+
+        let (center, clearance) =
+            match circumcircle([a.position, b.position, c.position]) {
+                Some(circle) => circle,
+                None => continue,
+            };
+
+        if !contains_point(boundary, center) {
+            continue;
+        }
+
+        vertices.push(VoronoiVertex {
+            samples: [a.index, b.index, c.index],
+            center,
+            clearance,
+        });
+    }
+
+    Ok(vertices)
+}
+
+fn circumcircle([a, b, c]: [Point<2>; 3]) -> Option<(Point<2>, Scalar)> {
+    let b = b - a;
+    let c = c - a;
+
+    let d = (b.u * c.v - b.v * c.u) * 2.;
+    if d == Scalar::ZERO {
+        return None;
+    }
+
+    let b2 = b.dot(&b);
+    let c2 = c.dot(&c);
+
+    let offset =
+        Vector::from([(c.v * b2 - b.v * c2) / d, (b.u * c2 - c.u * b2) / d]);
+
+    Some((a + offset, offset.magnitude()))
+}
+
+/// Determine whether a point is within the area bounded by the given loops
+fn contains_point(boundary: &[Vec<Point<2>>], point: Point<2>) -> bool {
+    let mut inside = false;
+
+    for points in boundary {
+        for (i, &a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+
+            if (a.v > point.v) != (b.v > point.v) {
+                let u = a.u + (point.v - a.v) * (b.u - a.u) / (b.v - a.v);
+                if point.u < u {
+                    inside = !inside;
+                }
+            }
+        }
+    }
+
+    inside
+}
+
+fn walk_branch(
+    start: usize,
+    next: usize,
+    adjacency: &BTreeMap<usize, Vec<usize>>,
+    visited: &mut HashSet<[usize; 2]>,
+) -> Vec<usize> {
+    let mut nodes = vec![start];
+
+    let mut previous = start;
+    let mut current = next;
+    visited.insert(edge_key(previous, current));
+
+    loop {
+        nodes.push(current);
+
+        let neighbors = &adjacency[&current];
+        if current == start || neighbors.len() != 2 {
+            break;
+        }
+
+        let following = if neighbors[0] == previous {
+            neighbors[1]
+        } else {
+            neighbors[0]
+        };
+        if !visited.insert(edge_key(current, following)) {
+            break;
+        }
+
+        previous = current;
+        current = following;
+    }
+
+    nodes
+}
+
+fn edge_key(a: usize, b: usize) -> [usize; 2] {
+    if a < b {
+        [a, b]
+    } else {
+        [b, a]
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Sample {
+    index: usize,
+    position: Point<2>,
+}
+
+impl HasPosition for Sample {
+    type Scalar = Scalar;
+
+    fn position(&self) -> spade::Point2<Self::Scalar> {
+        spade::Point2 {
+            x: self.position.u,
+            y: self.position.v,
+        }
+    }
+}
+
+struct UnionFind {
+    parents: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+        }
+    }
+
+    fn find(&mut self, index: usize) -> usize {
+        let mut root = index;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        let mut index = index;
+        while self.parents[index] != root {
+            let parent = self.parents[index];
+            self.parents[index] = root;
+            index = parent;
+        }
+
+        root
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let [a, b] = [self.find(a), self.find(b)];
+        self.parents[b] = a;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        algorithms::Tolerance,
+        objects::{Face, Surface},
+    };
+
+    use super::medial_axis;
+
+    #[test]
+    fn rectangle() -> anyhow::Result<()> {
+        let face = Face::builder(Surface::xy_plane())
+            .with_exterior_polygon([[0., 0.], [10., 0.], [10., 2.], [0., 2.]])
+            .build();
+
+        let skeleton = medial_axis(&face, Tolerance::from_scalar(0.05)?)?;
+        let epsilon = Scalar::from_f64(0.01);
+
+        // Away from the ends, the skeleton is the centerline.
+        let middle: Vec<_> = skeleton
+            .branches
+            .iter()
+            .flat_map(|branch| &branch.points)
+            .filter(|point| {
+                point.position.u >= Scalar::from_f64(2.)
+                    && point.position.u <= Scalar::from_f64(8.)
+            })
+            .collect();
+        assert!(!middle.is_empty());
+        for point in middle {
+            assert!((point.position.v - Scalar::ONE).abs() < epsilon);
+            assert!((point.clearance - Scalar::ONE).abs() < epsilon);
+        }
+
+        let circle = skeleton
+            .max_inscribed_circle()
+            .expect("Skeleton should not be empty");
+        assert!((circle.clearance - Scalar::ONE).abs() < epsilon);
+
+        let width = skeleton
+            .local_width([5., 1.])
+            .expect("Skeleton should not be empty");
+        assert!((width - Scalar::TWO).abs() < epsilon * 2.);
+
+        Ok(())
+    }
+
+    #[test]
+    fn l_shape() -> anyhow::Result<()> {
+        let face = Face::builder(Surface::xy_plane())
+            .with_exterior_polygon([
+                [0., 0.],
+                [4., 0.],
+                [4., 1.],
+                [1., 1.],
+                [1., 4.],
+                [0., 4.],
+            ])
+            .build();
+
+        let skeleton = medial_axis(&face, Tolerance::from_scalar(0.05)?)?;
+
+        // The arms of the L meet at the point that is equidistant from the
+        // outer walls and the inner corner at `[1., 1.]`.
+        let t = 2_f64.sqrt() / (1. + 2_f64.sqrt());
+        let expected = Point::from([t, t]);
+
+        let branch_point = skeleton
+            .branch_points()
+            .into_iter()
+            .min_by_key(|point| (point.position - expected).magnitude())
+            .expect("Expected branch point");
+        assert!(
+            (branch_point.position - expected).magnitude()
+                < Scalar::from_f64(0.1)
+        );
+
+        Ok(())
+    }
+}
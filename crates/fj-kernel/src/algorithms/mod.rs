@@ -4,17 +4,105 @@
 //! on their respective purpose.
 
 mod approx;
+mod boolean_2d;
+mod boolean_chain;
+mod chamfer;
+mod connectors;
+mod contact;
+mod difference;
+mod edge_blend;
+mod edge_loop;
+mod fill_holes;
+mod fillet;
+mod geometric_hash;
+mod handedness;
+mod layers;
+mod lod;
+mod measure;
+mod medial_axis;
+mod nest;
+mod pick;
+mod project;
+mod refine;
 mod reverse;
+mod revolve;
+mod section;
+mod select;
+mod shell;
+mod sketch_analysis;
+mod split;
 mod sweep;
+#[cfg(test)]
+mod test_helpers;
+mod text;
+mod thicken;
 mod transform;
 mod triangulate;
+mod union;
 
 pub mod intersection;
 
 pub use self::{
-    approx::{CycleApprox, FaceApprox, InvalidTolerance, Tolerance},
+    approx::{
+        ApproxStore, ApproxStoreStats, CycleApprox, EffortBudget, ErrorBudget,
+        ErrorBudgetExceeded, ErrorContribution, FaceApprox, InvalidTolerance,
+        Tolerance,
+    },
+    boolean_chain::{BooleanChain, BooleanError},
+    chamfer::{
+        chamfer, chamfer_asymmetric, max_chamfer_distance, ChamferError,
+    },
+    connectors::{
+        add_connectors, ConnectorError, ConnectorShape, ConnectorSpec,
+    },
+    contact::{
+        contact_patches, contact_report, ContactPatch, ContactReport,
+        ContactWarning,
+    },
+    difference::{difference, DifferenceError},
+    edge_blend::FeasibilityReport,
+    edge_loop::{edge_loop_from, EdgeLoop},
+    fill_holes::{fill_holes, FillReport, HoleLoop, HOLE_PATCH_COLOR},
+    fillet::{
+        fillet, fillet_with_options, max_fillet_radius, FilletError,
+        FilletOptions,
+    },
+    geometric_hash::{equivalent_translation, geometric_hash},
+    handedness::Handedness,
+    intersection::IntersectionTolerances,
+    layers::{layers_from_solid, Layer, LayerError, LayerKind, LayeredProfile},
+    lod::{generate_lods, Lod, Lods},
+    measure::{center_of_mass, surface_area, volume},
+    medial_axis::{
+        medial_axis, MedialAxisError, Skeleton, SkeletonBranch, SkeletonPoint,
+    },
+    nest::{cut_sheet, nest_outlines, NestError, Placement},
+    pick::{
+        pick, pick_edge, pick_face, pick_vertex, EdgePick, FacePick, Pick, Ray,
+        VertexPick,
+    },
+    project::{
+        project_curve_into_surface, project_curve_onto_surface, ProjectionError,
+    },
+    refine::refine_point,
     reverse::reverse_face,
-    sweep::sweep,
+    revolve::{revolve, RevolveError},
+    section::{clip_mesh, section_mesh, SECTION_CAP_COLOR},
+    select::{EdgeRef, EdgeSelector, FaceSelector},
+    shell::{shell, ShellError},
+    sketch_analysis::{
+        analyze_sketch, FindingSeverity, SketchAnalysisThresholds,
+        SketchFinding, SketchFindingKind, SketchReport,
+    },
+    split::{split_solid, SplitError},
+    sweep::{sweep, sweep_with_scale, ScaleProfile, SweepError},
+    text::{sketch_from_text_on_path, Font, Glyph, TextError, TextLayout},
+    thicken::{thicken_triangles, ThickenError},
     transform::{transform_faces, TransformObject},
-    triangulate::triangulate,
+    triangulate::{
+        triangulate, triangulate_instanced, triangulate_selection,
+        triangulate_with_config, Instance, InstancedMesh, TriangulationBackend,
+        TriangulationConfig, TriangulationError,
+    },
+    union::union_coplanar,
 };
@@ -0,0 +1,500 @@
+use fj_math::{Point, Scalar, Transform, Vector};
+
+use crate::objects::Sketch;
+
+use super::{CycleApprox, Tolerance, TransformObject};
+
+/// The number of grid cells along the shorter side of the sheet
+const GRID_RESOLUTION: f64 = 128.;
+
+/// Nest sketch outlines on a rectangular sheet
+///
+/// The sheet spans from the origin to `sheet` in the xy-plane. Each outline
+/// is represented by the convex hull of the exterior cycles of its faces, so
+/// holes are ignored, and concave outlines don't interlock. The outlines are
+/// placed one after the other, in the order they are passed in. Each one goes
+/// to the left-most position of a grid over the sheet, then to the lowest,
+/// at which its hull lies within the sheet and keeps at least `spacing` from
+/// the hulls placed before it. All `rotations` are tried, and the one that
+/// leads to the left-most position wins, with ties going to the rotation
+/// that comes first. The results are deterministic for identical input.
+///
+/// Rotations are angles in radians, counter-clockwise around the z-axis.
+///
+/// The grid has 128 cells along the shorter side of the sheet.
+/// Curved edges are approximated within a tenth of a cell, and the spacing is
+/// increased accordingly, so the actual outlines don't come closer than
+/// `spacing` either.
+///
+/// Outlines that don't fit are skipped, and all of them are reported in
+/// [`NestError::DoesNotFit`]. Use [`cut_sheet`] to combine the placed
+/// outlines into a single sketch, which can then be exported.
+pub fn nest_outlines(
+    outlines: &[Sketch],
+    sheet: [Scalar; 2],
+    spacing: Scalar,
+    rotations: &[Scalar],
+) -> Result<Vec<Placement>, NestError> {
+    let [width, height] = sheet;
+    if width <= Scalar::ZERO || height <= Scalar::ZERO {
+        return Err(NestError::InvalidSheet(sheet));
+    }
+    if spacing < Scalar::ZERO {
+        return Err(NestError::NegativeSpacing(spacing));
+    }
+    if rotations.is_empty() {
+        return Err(NestError::NoRotations);
+    }
+
+    let step = width.min(height) / GRID_RESOLUTION;
+    let tolerance = Tolerance::from_scalar(step / 10.)
+        .expect("Sheet dimensions are positive");
+    let spacing = spacing + tolerance.inner() * 2.;
+
+    let mut placed: Vec<Vec<Point<2>>> = Vec::new();
+    let mut placements = Vec::new();
+    let mut not_fitting = Vec::new();
+
+    for (index, outline) in outlines.iter().enumerate() {
+        let points = outline_points(outline, tolerance);
+        if points.is_empty() {
+            return Err(NestError::EmptyOutline(index));
+        }
+
+        let mut best: Option<(Candidate, Placement)> = None;
+        for &rotation in rotations {
+            let (sin, cos) = rotation.sin_cos();
+            let rotated: Vec<_> = points
+                .iter()
+                .map(|point| {
+                    Point::from([
+                        point.u * cos - point.v * sin,
+                        point.u * sin + point.v * cos,
+                    ])
+                })
+                .collect();
+            let hull = convex_hull(rotated);
+
+            let candidate = match find_position(
+                &hull,
+                &placed,
+                [width, height],
+                step,
+                spacing,
+            ) {
+                Some(candidate) => candidate,
+                None => continue,
+            };
+
+            let is_better = best
+                .as_ref()
+                .map_or(true, |(best, _)| candidate.cell < best.cell);
+            if is_better {
+                let placement = Placement {
+                    rotation,
+                    translation: candidate.offset,
+                };
+                best = Some((candidate, placement));
+            }
+        }
+
+        match best {
+            Some((candidate, placement)) => {
+                placed.push(candidate.hull);
+                placements.push(placement);
+            }
+            None => not_fitting.push(index),
+        }
+    }
+
+    if !not_fitting.is_empty() {
+        return Err(NestError::DoesNotFit(not_fitting));
+    }
+
+    Ok(placements)
+}
+
+/// Combine nested outlines into a single sketch
+///
+/// Each outline is transformed by its placement, as returned by
+/// [`nest_outlines`]. Outlines without a placement are left out.
+pub fn cut_sheet(outlines: &[Sketch], placements: &[Placement]) -> Sketch {
+    Sketch::from_faces(outlines.iter().zip(placements).flat_map(
+        |(outline, placement)| {
+            outline
+                .clone()
+                .transform(&placement.transform())
+                .into_faces()
+        },
+    ))
+}
+
+/// The placement of an outline on the sheet
+///
+/// Returned by [`nest_outlines`]. The outline is first rotated around the
+/// z-axis, then translated.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Placement {
+    /// The counter-clockwise rotation around the z-axis, in radians
+    pub rotation: Scalar,
+
+    /// The translation within the sheet, applied after the rotation
+    pub translation: Vector<2>,
+}
+
+impl Placement {
+    /// Convert the placement into a transform
+    pub fn transform(&self) -> Transform {
+        let translation = Vector::from([
+            self.translation.u,
+            self.translation.v,
+            Scalar::ZERO,
+        ]);
+        Transform::translation(translation)
+            * Transform::rotation(Vector::unit_z() * self.rotation)
+    }
+}
+
+/// Error nesting outlines
+#[derive(Debug, thiserror::Error)]
+pub enum NestError {
+    /// The sheet has no area
+    #[error("Sheet dimensions must be positive: {0:?}")]
+    InvalidSheet([Scalar; 2]),
+
+    /// The spacing is negative
+    #[error("Spacing between outlines must not be negative: {0}")]
+    NegativeSpacing(Scalar),
+
+    /// No rotations to try were given
+    #[error("At least one rotation must be allowed")]
+    NoRotations,
+
+    /// An outline has no faces to place
+    #[error("Outline {0} has no faces")]
+    EmptyOutline(usize),
+
+    /// Some of the outlines don't fit on the sheet
+    #[error("Outlines don't fit on the sheet: {0:?}")]
+    DoesNotFit(Vec<usize>),
+}
+
+/// A position on the sheet that a hull fits into
+struct Candidate {
+    /// The column and row of the grid cell
+    cell: (u64, u64),
+
+    /// The offset that moves the hull into the cell
+    offset: Vector<2>,
+
+    /// The hull, moved into the cell
+    hull: Vec<Point<2>>,
+}
+
+/// The points of the exterior cycles of a sketch, projected into the xy-plane
+fn outline_points(outline: &Sketch, tolerance: Tolerance) -> Vec<Point<2>> {
+    let mut points = Vec::new();
+
+    for face in outline.faces() {
+        if let Some(triangles) = face.triangles() {
+            points.extend(
+                triangles
+                    .iter()
+                    .flat_map(|(triangle, _)| triangle.points())
+                    .map(|point| Point::from([point.x, point.y])),
+            );
+            continue;
+        }
+
+        for cycle in face.exteriors() {
+            points.extend(
+                CycleApprox::new(cycle, tolerance).points.into_iter().map(
+                    |point| {
+                        let point = face
+                            .surface()
+                            .point_from_surface_coords(*point.local_form());
+                        Point::from([point.x, point.y])
+                    },
+                ),
+            );
+        }
+    }
+
+    points
+}
+
+/// Find the left-most, then lowest, grid cell that a hull fits into
+fn find_position(
+    hull: &[Point<2>],
+    placed: &[Vec<Point<2>>],
+    [width, height]: [Scalar; 2],
+    step: Scalar,
+    spacing: Scalar,
+) -> Option<Candidate> {
+    let [min, max] = bounds(hull);
+    let size = max - min;
+
+    let cells = |available: Scalar| {
+        if available < Scalar::ZERO {
+            return None;
+        }
+        Some((available / step).into_f64().floor() as u64)
+    };
+    let columns = cells(width - size.u)?;
+    let rows = cells(height - size.v)?;
+
+    for column in 0..=columns {
+        for row in 0..=rows {
+            let position = Point::from([
+                step * Scalar::from_u64(column),
+                step * Scalar::from_u64(row),
+            ]);
+            let offset = position - min;
+
+            let moved: Vec<_> =
+                hull.iter().map(|&point| point + offset).collect();
+            let is_free = placed
+                .iter()
+                .all(|other| hull_distance(&moved, other) >= spacing);
+
+            if is_free {
+                return Some(Candidate {
+                    cell: (column, row),
+                    offset,
+                    hull: moved,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// The convex hull of a set of points, counter-clockwise
+fn convex_hull(mut points: Vec<Point<2>>) -> Vec<Point<2>> {
+    points.sort();
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    let is_left_turn = |a: Point<2>, b: Point<2>, c: Point<2>| {
+        let [ab, ac] = [b - a, c - a];
+        ab.u * ac.v - ab.v * ac.u > Scalar::ZERO
+    };
+
+    let mut lower: Vec<Point<2>> = Vec::new();
+    for &point in &points {
+        while lower.len() >= 2
+            && !is_left_turn(
+                lower[lower.len() - 2],
+                lower[lower.len() - 1],
+                point,
+            )
+        {
+            lower.pop();
+        }
+        lower.push(point);
+    }
+
+    let mut upper: Vec<Point<2>> = Vec::new();
+    for &point in points.iter().rev() {
+        while upper.len() >= 2
+            && !is_left_turn(
+                upper[upper.len() - 2],
+                upper[upper.len() - 1],
+                point,
+            )
+        {
+            upper.pop();
+        }
+        upper.push(point);
+    }
+
+    lower.pop();
+    upper.pop();
+    lower.extend(upper);
+    lower
+}
+
+/// The distance between two convex polygons, zero if they overlap
+fn hull_distance(a: &[Point<2>], b: &[Point<2>]) -> Scalar {
+    if !is_separated(a, b) {
+        return Scalar::ZERO;
+    }
+
+    let vertex_to_edges = |points: &[Point<2>], polygon: &[Point<2>]| {
+        points
+            .iter()
+            .flat_map(|&point| {
+                edges(polygon).map(move |edge| segment_distance(point, edge))
+            })
+            .min()
+            .unwrap_or(Scalar::MAX)
+    };
+
+    vertex_to_edges(a, b).min(vertex_to_edges(b, a))
+}
+
+/// Check whether two convex polygons are separated by one of their edges
+fn is_separated(a: &[Point<2>], b: &[Point<2>]) -> bool {
+    let project = |polygon: &[Point<2>], normal: Vector<2>| {
+        let projections = polygon.iter().map(|point| point.coords.dot(&normal));
+        let min = projections.clone().min().unwrap_or(Scalar::ZERO);
+        let max = projections.max().unwrap_or(Scalar::ZERO);
+        (min, max)
+    };
+
+    edges(a).chain(edges(b)).any(|[start, end]| {
+        let direction = end - start;
+        let normal = Vector::from([direction.v, -direction.u]);
+
+        let (min_a, max_a) = project(a, normal);
+        let (min_b, max_b) = project(b, normal);
+        max_a < min_b || max_b < min_a
+    })
+}
+
+fn edges(
+    polygon: &[Point<2>],
+) -> impl Iterator<Item = [Point<2>; 2]> + Clone + '_ {
+    (0..polygon.len()).map(|i| [polygon[i], polygon[(i + 1) % polygon.len()]])
+}
+
+fn segment_distance(point: Point<2>, [start, end]: [Point<2>; 2]) -> Scalar {
+    let direction = end - start;
+    let length_squared = direction.dot(&direction);
+
+    let t = if length_squared == Scalar::ZERO {
+        Scalar::ZERO
+    } else {
+        let t = (point - start).dot(&direction) / length_squared;
+        t.clamp(Scalar::ZERO, Scalar::ONE)
+    };
+
+    (point - (start + direction * t)).magnitude()
+}
+
+fn bounds(points: &[Point<2>]) -> [Point<2>; 2] {
+    let coordinate =
+        |i: usize| points.iter().map(move |point| point.coords.components[i]);
+    let min = [0, 1].map(|i| coordinate(i).min().unwrap_or(Scalar::ZERO));
+    let max = [0, 1].map(|i| coordinate(i).max().unwrap_or(Scalar::ZERO));
+    [Point::from(min), Point::from(max)]
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Scalar;
+
+    use crate::objects::{Cycle, Face, Sketch, Surface};
+
+    use super::{
+        convex_hull, cut_sheet, hull_distance, nest_outlines, outline_points,
+        NestError,
+    };
+    use crate::algorithms::Tolerance;
+
+    #[test]
+    fn l_shapes() -> anyhow::Result<()> {
+        let outlines = vec![l_shape(); 4];
+        let sheet = [10.5, 5.3].map(Scalar::from_f64);
+        let spacing = Scalar::from_f64(0.1);
+        let rotations = [Scalar::ZERO, Scalar::PI];
+
+        // Without rotating, only two of them fit.
+        let result = nest_outlines(&outlines, sheet, spacing, &rotations[..1]);
+        assert!(
+            matches!(result, Err(NestError::DoesNotFit(indices)) if indices == [2, 3])
+        );
+
+        let placements = nest_outlines(&outlines, sheet, spacing, &rotations)?;
+        assert_eq!(placements.len(), 4);
+
+        let num_rotated = placements
+            .iter()
+            .filter(|placement| placement.rotation == Scalar::PI)
+            .count();
+        assert_eq!(num_rotated, 2);
+
+        // The placed outlines keep their distance from each other.
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        let hulls: Vec<_> = cut_sheet(&outlines, &placements)
+            .faces()
+            .map(|face| {
+                let sketch = Sketch::from_faces([face.clone()]);
+                convex_hull(outline_points(&sketch, tolerance))
+            })
+            .collect();
+        assert_eq!(hulls.len(), 4);
+        for (i, a) in hulls.iter().enumerate() {
+            for b in &hulls[i + 1..] {
+                assert!(hull_distance(a, b) >= spacing);
+            }
+            for point in a {
+                assert!(point.u >= Scalar::ZERO && point.u <= sheet[0]);
+                assert!(point.v >= Scalar::ZERO && point.v <= sheet[1]);
+            }
+        }
+
+        // There's no room left for another one.
+        let outlines = vec![l_shape(); 5];
+        let result = nest_outlines(&outlines, sheet, spacing, &rotations);
+        assert!(
+            matches!(result, Err(NestError::DoesNotFit(indices)) if indices == [4])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn deterministic() -> anyhow::Result<()> {
+        let outlines = vec![l_shape(); 3];
+        let sheet = [20., 20.].map(Scalar::from_f64);
+        let rotations = [0., 0.5, 1.5].map(Scalar::from_f64);
+
+        let a = nest_outlines(&outlines, sheet, Scalar::ONE, &rotations)?;
+        let b = nest_outlines(&outlines, sheet, Scalar::ONE, &rotations)?;
+        assert_eq!(a, b);
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_input() {
+        let outlines = vec![l_shape()];
+        let sheet = [10., 10.].map(Scalar::from_f64);
+
+        assert!(matches!(
+            nest_outlines(
+                &outlines,
+                [Scalar::ZERO, Scalar::ONE],
+                Scalar::ONE,
+                &[Scalar::ZERO]
+            ),
+            Err(NestError::InvalidSheet(_))
+        ));
+        assert!(matches!(
+            nest_outlines(&outlines, sheet, -Scalar::ONE, &[Scalar::ZERO]),
+            Err(NestError::NegativeSpacing(_))
+        ));
+        assert!(matches!(
+            nest_outlines(&outlines, sheet, Scalar::ONE, &[]),
+            Err(NestError::NoRotations)
+        ));
+    }
+
+    /// An L-shaped outline, whose convex hull is a square with a cut-off
+    /// corner
+    fn l_shape() -> Sketch {
+        let points =
+            [[0., 0.], [4., 0.], [4., 1.], [1., 1.], [1., 4.], [0., 4.]];
+        let face = Face::new(
+            Surface::xy_plane(),
+            [Cycle::polygon_from_points(&Surface::xy_plane(), points)],
+            [],
+            [255, 0, 0, 255],
+        );
+        Sketch::from_faces([face])
+    }
+}
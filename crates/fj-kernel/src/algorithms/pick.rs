@@ -0,0 +1,558 @@
+use fj_interop::mesh::Mesh;
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use crate::{
+    iter::ObjectIters,
+    local::Local,
+    objects::{Edge, Face, GlobalVertex},
+};
+
+use super::{
+    approx::{approx_curve, approx_curve_between, approx_edge},
+    refine_point, Tolerance,
+};
+
+/// A ray in model coordinates
+///
+/// Used to pick objects, usually by casting it from the camera through the
+/// cursor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray {
+    /// The point the ray starts at
+    pub origin: Point<3>,
+
+    /// The direction the ray points in
+    ///
+    /// Doesn't need to be normalized, but must not be zero.
+    pub direction: Vector<3>,
+}
+
+/// The result of [`pick`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pick<'r> {
+    /// A vertex was picked
+    Vertex(VertexPick<'r>),
+
+    /// An edge was picked
+    Edge(EdgePick<'r>),
+}
+
+/// A vertex that was picked by [`pick_vertex`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VertexPick<'r> {
+    /// The vertex
+    pub vertex: &'r GlobalVertex,
+
+    /// The point on the vertex that is closest to the ray
+    ///
+    /// This is the position of the vertex.
+    pub point: Point<3>,
+
+    /// The distance between the vertex and the ray
+    pub distance: Scalar,
+}
+
+/// An edge that was picked by [`pick_edge`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EdgePick<'r> {
+    /// The edge
+    pub edge: &'r Edge,
+
+    /// The point on the edge that is closest to the ray
+    ///
+    /// This is a point on the curve of the edge, not on its approximation.
+    pub point: Point<3>,
+
+    /// The distance between the edge and the ray
+    pub distance: Scalar,
+
+    /// The position of `point` on the curve of the edge
+    pub curve_coords: Point<1>,
+}
+
+/// A face that was picked by [`pick_face`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FacePick<'r> {
+    /// The face
+    pub face: &'r Face,
+
+    /// The index of the mesh triangle that the ray hit
+    pub triangle: usize,
+
+    /// The point on the face where the ray hits it
+    ///
+    /// This is a point on the surface of the face, not on the mesh (see
+    /// [`refine_point`]).
+    pub point: Point<3>,
+
+    /// The distance from the origin of the ray to the mesh triangle it hit
+    ///
+    /// This is measured in units of the ray's direction.
+    pub distance: Scalar,
+}
+
+/// Pick the vertex or edge of a shape that is closest to a ray
+///
+/// Vertices take priority over edges: If any vertex is within `max_distance`
+/// of the ray, the closest vertex is picked, even if an edge is closer. Every
+/// vertex is the end of edges, which are at least as close to the ray as the
+/// vertex itself, so otherwise, a vertex could never be picked.
+///
+/// See [`pick_vertex`] and [`pick_edge`] for details.
+pub fn pick<'r, S>(
+    shape: &'r S,
+    ray: &Ray,
+    max_distance: Scalar,
+    tolerance: Tolerance,
+) -> Option<Pick<'r>>
+where
+    S: ObjectIters<'r>,
+{
+    if let Some(pick) = pick_vertex(shape, ray, max_distance) {
+        return Some(Pick::Vertex(pick));
+    }
+
+    pick_edge(shape, ray, max_distance, tolerance).map(Pick::Edge)
+}
+
+/// Pick the vertex of a shape that is closest to a ray
+///
+/// Only vertices within `max_distance` of the ray are considered. If multiple
+/// vertices are equally close, the one that is ordered first is picked. Objects
+/// have no identity beyond their structure, so their ordering is what makes
+/// the result deterministic.
+pub fn pick_vertex<'r, S>(
+    shape: &'r S,
+    ray: &Ray,
+    max_distance: Scalar,
+) -> Option<VertexPick<'r>>
+where
+    S: ObjectIters<'r>,
+{
+    if ray.direction == Vector::from([0., 0., 0.]) {
+        return None;
+    }
+
+    let mut closest: Option<VertexPick> = None;
+
+    for vertex in shape.global_vertex_iter() {
+        let point = vertex.position();
+        let distance = (point - ray.closest_point(point)).magnitude();
+
+        if distance > max_distance {
+            continue;
+        }
+
+        let is_closer = match closest {
+            Some(closest) => {
+                (distance, vertex) < (closest.distance, closest.vertex)
+            }
+            None => true,
+        };
+        if is_closer {
+            closest = Some(VertexPick {
+                vertex,
+                point,
+                distance,
+            });
+        }
+    }
+
+    closest
+}
+
+/// Pick the edge of a shape that is closest to a ray
+///
+/// Only edges within `max_distance` of the ray are considered. If multiple
+/// edges are equally close, the one that is ordered first is picked. This
+/// happens regularly, as faces that share an edge each have their own copy of
+/// it.
+///
+/// The distance is computed against an approximation of each edge, within
+/// `tolerance`. Edges whose bounding box the ray misses are skipped without
+/// looking at their approximation.
+pub fn pick_edge<'r, S>(
+    shape: &'r S,
+    ray: &Ray,
+    max_distance: Scalar,
+    tolerance: Tolerance,
+) -> Option<EdgePick<'r>>
+where
+    S: ObjectIters<'r>,
+{
+    if ray.direction == Vector::from([0., 0., 0.]) {
+        return None;
+    }
+
+    let mut closest: Option<EdgePick> = None;
+
+    for edge in shape.edge_iter() {
+        let points = approx_edge_in_order(edge, tolerance);
+
+        let aabb = Aabb::<3>::from_points(
+            points.iter().map(|point| *point.global_form()),
+        );
+        if !ray.hits_aabb(&aabb, max_distance) {
+            continue;
+        }
+
+        for segment in points.windows(2) {
+            let [a, b] = [segment[0], segment[1]];
+            let (ab, on_ray) =
+                ray.closest_to_segment([*a.global_form(), *b.global_form()]);
+
+            let point =
+                *a.global_form() + (*b.global_form() - *a.global_form()) * ab;
+            let distance = (point - on_ray).magnitude();
+
+            if distance > max_distance {
+                continue;
+            }
+
+            let is_closer = match closest {
+                Some(closest) => {
+                    (distance, edge) < (closest.distance, closest.edge)
+                }
+                None => true,
+            };
+            if is_closer {
+                let [a, b] = [a, b].map(|point| point.local_form().t);
+                let curve_coords = Point::from([a + (b - a) * ab]);
+
+                closest = Some(EdgePick {
+                    edge,
+                    point: edge
+                        .curve()
+                        .global_form()
+                        .point_from_curve_coords(curve_coords),
+                    distance,
+                    curve_coords,
+                });
+            }
+        }
+    }
+
+    closest
+}
+
+/// Pick the face of a shape that a ray hits first
+///
+/// The ray is cast against `mesh`, which must have been triangulated from the
+/// faces of `shape` (see [`refine_point`]). Triangles that don't belong to a
+/// face are ignored. The point where the ray hits the mesh is then mapped back
+/// to the exact surface of the face.
+pub fn pick_face<'r, S>(
+    shape: &'r S,
+    mesh: &Mesh<Point<3>>,
+    ray: &Ray,
+) -> Option<FacePick<'r>>
+where
+    S: ObjectIters<'r>,
+{
+    let mut closest: Option<(usize, usize, Scalar, [Scalar; 3])> = None;
+
+    for (index, triangle) in mesh.triangles().enumerate() {
+        let face = match triangle.face {
+            Some(face) => face,
+            None => continue,
+        };
+        let (distance, barycentric) =
+            match ray.intersect_triangle(triangle.points) {
+                Some(hit) => hit,
+                None => continue,
+            };
+
+        let is_closer = match closest {
+            Some((_, _, closest, _)) => distance < closest,
+            None => true,
+        };
+        if is_closer {
+            closest = Some((index, face, distance, barycentric));
+        }
+    }
+
+    let (triangle, face, distance, barycentric) = closest?;
+    let face = shape.face_iter().nth(face)?;
+    let point =
+        refine_point(shape, mesh, triangle, barycentric.map(Scalar::into_f64))?;
+
+    Some(FacePick {
+        face,
+        triangle,
+        point,
+        distance,
+    })
+}
+
+/// Approximate an edge, from its start to its end
+fn approx_edge_in_order(
+    edge: &Edge,
+    tolerance: Tolerance,
+) -> Vec<Local<Point<1>>> {
+    let mut points = Vec::new();
+    match edge.vertices().get() {
+        Some(vertices) => approx_curve_between(
+            edge.curve().global_form(),
+            vertices.map(|vertex| vertex.position()),
+            tolerance,
+            &mut points,
+        ),
+        None => {
+            approx_curve(edge.curve().global_form(), tolerance, &mut points)
+        }
+    }
+
+    approx_edge(*edge.vertices(), &mut points);
+
+    points
+}
+
+impl Ray {
+    /// Compute the point on the ray that is closest to the given point
+    fn closest_point(&self, point: Point<3>) -> Point<3> {
+        let s = (point - self.origin).dot(&self.direction)
+            / self.direction.dot(&self.direction);
+
+        self.origin + self.direction * s.max(Scalar::ZERO)
+    }
+
+    /// Compute the closest points between the ray and a line segment
+    ///
+    /// Returns the position of the closest point on the segment, from `0.` at
+    /// its start to `1.` at its end, and the closest point on the ray.
+    ///
+    /// See "Real-Time Collision Detection" by Christer Ericson, section 5.1.9,
+    /// for the algorithm.
+    fn closest_to_segment(&self, [a, b]: [Point<3>; 2]) -> (Scalar, Point<3>) {
+        let d = self.direction;
+        let e = b - a;
+        let w = self.origin - a;
+
+        let dd = d.dot(&d);
+        let de = d.dot(&e);
+        let ee = e.dot(&e);
+        let dw = d.dot(&w);
+        let ew = e.dot(&w);
+
+        // A degenerate segment is a point.
+        if ee == Scalar::ZERO {
+            return (Scalar::ZERO, self.closest_point(a));
+        }
+
+        let denominator = dd * ee - de * de;
+        let s = if denominator > Scalar::ZERO {
+            ((de * ew - dw * ee) / denominator).max(Scalar::ZERO)
+        } else {
+            // The ray and the segment are parallel. Any point will do.
+            Scalar::ZERO
+        };
+
+        let t = (de * s + ew) / ee;
+        let (t, s) = if t < Scalar::ZERO {
+            (Scalar::ZERO, -dw / dd)
+        } else if t > Scalar::ONE {
+            (Scalar::ONE, (de - dw) / dd)
+        } else {
+            (t, s)
+        };
+
+        (t, self.origin + d * s.max(Scalar::ZERO))
+    }
+
+    /// Compute where the ray hits a triangle
+    ///
+    /// Returns the position of the hit along the ray, in units of its
+    /// direction, and the barycentric coordinates of the hit within the
+    /// triangle. Triangles that the ray is parallel to are never hit.
+    ///
+    /// See "Fast, Minimum Storage Ray/Triangle Intersection" by Tomas Möller
+    /// and Ben Trumbore for the algorithm.
+    fn intersect_triangle(
+        &self,
+        [a, b, c]: [Point<3>; 3],
+    ) -> Option<(Scalar, [Scalar; 3])> {
+        let ab = b - a;
+        let ac = c - a;
+
+        let p = self.direction.cross(&ac);
+        let determinant = ab.dot(&p);
+        if determinant == Scalar::ZERO {
+            return None;
+        }
+
+        let s = self.origin - a;
+        let u = s.dot(&p) / determinant;
+        if u < Scalar::ZERO || u > Scalar::ONE {
+            return None;
+        }
+
+        let q = s.cross(&ab);
+        let v = self.direction.dot(&q) / determinant;
+        if v < Scalar::ZERO || u + v > Scalar::ONE {
+            return None;
+        }
+
+        let t = ac.dot(&q) / determinant;
+        if t < Scalar::ZERO {
+            return None;
+        }
+
+        Some((t, [Scalar::ONE - u - v, u, v]))
+    }
+
+    /// Determine whether the ray passes within `margin` of the AABB
+    fn hits_aabb(&self, aabb: &Aabb<3>, margin: Scalar) -> bool {
+        let mut near = Scalar::ZERO;
+        let mut far = Scalar::MAX;
+
+        for i in 0..3 {
+            let origin = self.origin.coords.components[i];
+            let direction = self.direction.components[i];
+            let min = aabb.min.coords.components[i] - margin;
+            let max = aabb.max.coords.components[i] + margin;
+
+            if direction == Scalar::ZERO {
+                if origin < min || origin > max {
+                    return false;
+                }
+                continue;
+            }
+
+            let [a, b] = [min, max].map(|x| (x - origin) / direction);
+            let [a, b] = if a < b { [a, b] } else { [b, a] };
+
+            near = near.max(a);
+            far = if b < far { b } else { far };
+
+            if near > far {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::debug::DebugInfo;
+    use fj_math::{Point, Scalar, Vector};
+
+    use crate::{
+        algorithms::{triangulate, ApproxStore, ErrorBudget, Tolerance},
+        iter::ObjectIters,
+        objects::Solid,
+    };
+
+    use super::{pick, pick_edge, pick_face, pick_vertex, Pick, Ray};
+
+    #[test]
+    fn vertex_takes_priority() {
+        let cube = Solid::cube_from_edge_length(2.);
+
+        // Passes close to the corner at (1, 1, 1), then through the cube, far
+        // from any other corner.
+        let direction = Vector::from([-1., -0.2, -0.3]);
+        let ray = Ray {
+            origin: Point::from([1.02, 1.01, 1.]) - direction * 3.,
+            direction,
+        };
+        let max_distance = Scalar::from_f64(0.1);
+
+        assert!(pick_edge(&cube, &ray, max_distance, tolerance()).is_some());
+
+        let pick = pick(&cube, &ray, max_distance, tolerance());
+        let vertex = match pick {
+            Some(Pick::Vertex(pick)) => pick,
+            pick => panic!("Expected vertex, got {pick:?}"),
+        };
+        assert_eq!(vertex.point, Point::from([1., 1., 1.]));
+        assert!(vertex.distance < max_distance);
+    }
+
+    #[test]
+    fn edge() {
+        let cube = Solid::cube_from_edge_length(2.);
+
+        // Passes over the middle of the top front edge, from (-1, -1, 1) to
+        // (1, -1, 1), then through the bottom of the cube.
+        let direction = Vector::from([0., 1., -2.]);
+        let ray = Ray {
+            origin: Point::from([0.2, -1., 1.05]) - direction * 2.,
+            direction,
+        };
+        let max_distance = Scalar::from_f64(0.1);
+
+        assert_eq!(pick_vertex(&cube, &ray, max_distance), None);
+
+        let pick = pick(&cube, &ray, max_distance, tolerance());
+        let edge = match pick {
+            Some(Pick::Edge(pick)) => pick,
+            pick => panic!("Expected edge, got {pick:?}"),
+        };
+
+        let expected = Point::from([0.2, -1., 1.]);
+        assert!((edge.point - expected).magnitude() < tolerance().inner());
+
+        // The edge might run in either direction, depending on which face it
+        // belongs to.
+        let on_curve = edge
+            .edge
+            .curve()
+            .global_form()
+            .point_from_curve_coords(edge.curve_coords);
+        assert!((on_curve - expected).magnitude() < tolerance().inner());
+    }
+
+    #[test]
+    fn face() -> anyhow::Result<()> {
+        let cube = Solid::cube_from_edge_length(2.);
+        let mesh = triangulate(
+            cube.face_iter().cloned().collect(),
+            tolerance(),
+            &ErrorBudget::new(),
+            &mut ApproxStore::new(),
+            &mut DebugInfo::new(),
+        )?;
+
+        // Hits the top face first, then the bottom face.
+        let ray = Ray {
+            origin: Point::from([0.3, -0.2, 3.]),
+            direction: Vector::from([0., 0., -1.]),
+        };
+
+        let pick = pick_face(&cube, &mesh, &ray).expect("Face was hit");
+        let expected = Point::from([0.3, -0.2, 1.]);
+        assert!((pick.point - expected).magnitude() < Scalar::from_f64(1e-12));
+        assert!((pick.distance - Scalar::TWO).abs() < Scalar::from_f64(1e-12));
+        assert_eq!(
+            pick.face.surface().normal_at([0., 0.]).z.abs(),
+            Scalar::ONE
+        );
+
+        let ray = Ray {
+            direction: Vector::from([0., 0., 1.]),
+            ..ray
+        };
+        assert_eq!(pick_face(&cube, &mesh, &ray), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn nothing() {
+        let cube = Solid::cube_from_edge_length(2.);
+
+        let ray = Ray {
+            origin: Point::from([10., 10., 10.]),
+            direction: Vector::from([1., 0., 0.]),
+        };
+
+        let pick = pick(&cube, &ray, Scalar::from_f64(0.1), tolerance());
+        assert_eq!(pick, None);
+    }
+
+    fn tolerance() -> Tolerance {
+        Tolerance::from_scalar(0.001).unwrap()
+    }
+}
@@ -0,0 +1,351 @@
+use fj_math::{Circle, Ellipse, Line, Point, Polyline, Scalar, Vector};
+
+use crate::objects::{Curve, Surface};
+
+use super::Tolerance;
+
+/// Project a curve onto a surface
+///
+/// Returns the orthogonal projection of `curve` onto `surface`, in model
+/// coordinates. The curve coordinates of the projected curve match those of the
+/// original curve.
+///
+/// Only projections whose result can be represented exactly are supported as
+/// of now: lines, circles, ellipses, and polylines onto planes. A circle that
+/// is parallel to the plane projects onto a circle, otherwise it projects onto
+/// an ellipse. Whether a circle is parallel to a plane is decided using
+/// `tolerance`. Circles and ellipses that project onto a line segment are not
+/// supported.
+///
+/// Returns [`ProjectionError::Degenerate`], if the curve projects onto a single
+/// point (a line perpendicular to the plane). A line is considered
+/// perpendicular, if its direction vector, projected onto the plane, is
+/// shorter than `tolerance`. A polyline is considered degenerate, if all of its
+/// projected segments are shorter than `tolerance`.
+pub fn project_curve_onto_surface(
+    curve: &Curve<3>,
+    surface: &Surface,
+    tolerance: Tolerance,
+) -> Result<Curve<3>, ProjectionError> {
+    let unsupported = || ProjectionError::Unsupported {
+        curve: curve.clone(),
+        surface: surface.clone(),
+    };
+
+    let plane = Plane::from_surface(surface).ok_or_else(unsupported)?;
+
+    match curve {
+        Curve::Line(line) => {
+            let origin = plane.project_point(line.origin);
+            let direction = plane.project_vector(line.direction);
+
+            if direction.magnitude() < tolerance.inner() {
+                return Err(ProjectionError::Degenerate(origin));
+            }
+
+            Ok(Curve::Line(Line { origin, direction }))
+        }
+        Curve::Circle(circle) => {
+            let is_parallel = [circle.a, circle.b].into_iter().all(|vector| {
+                vector.dot(&plane.normal).abs() <= tolerance.inner()
+            });
+
+            let projected = plane.project_ellipse(&(*circle).into(), tolerance);
+            let projected = projected.ok_or_else(unsupported)?;
+
+            if is_parallel {
+                Ok(Curve::Circle(Circle {
+                    center: projected.center,
+                    a: projected.a,
+                    b: projected.b,
+                }))
+            } else {
+                Ok(Curve::Ellipse(projected))
+            }
+        }
+        Curve::Ellipse(ellipse) => {
+            let projected = plane.project_ellipse(ellipse, tolerance);
+            Ok(Curve::Ellipse(projected.ok_or_else(unsupported)?))
+        }
+        Curve::Polyline(polyline) => {
+            let points: Vec<_> = polyline
+                .points()
+                .iter()
+                .map(|&point| plane.project_point(point))
+                .collect();
+
+            let is_degenerate = points.windows(2).all(|segment| {
+                (segment[1] - segment[0]).magnitude() < tolerance.inner()
+            });
+            if is_degenerate {
+                return Err(ProjectionError::Degenerate(points[0]));
+            }
+
+            Ok(Curve::Polyline(Polyline::from_points_and_knots(
+                points,
+                polyline.knots().iter().copied(),
+            )))
+        }
+    }
+}
+
+/// Project a curve into a surface
+///
+/// Like [`project_curve_onto_surface`], but returns the projected curve in the
+/// local coordinates of the surface.
+pub fn project_curve_into_surface(
+    curve: &Curve<3>,
+    surface: &Surface,
+    tolerance: Tolerance,
+) -> Result<Curve<2>, ProjectionError> {
+    let projected = project_curve_onto_surface(curve, surface, tolerance)?;
+
+    // Can't fail, as `project_curve_onto_surface` already checked that the
+    // surface is a plane.
+    let plane = Plane::from_surface(surface)
+        .expect("Projection succeeded, so surface must be a plane");
+
+    let curve = match projected {
+        Curve::Line(line) => Curve::Line(Line {
+            origin: plane.point_to_surface_coords(line.origin),
+            direction: plane.vector_to_surface_coords(line.direction),
+        }),
+        Curve::Circle(circle) => Curve::Circle(Circle {
+            center: plane.point_to_surface_coords(circle.center),
+            a: plane.vector_to_surface_coords(circle.a),
+            b: plane.vector_to_surface_coords(circle.b),
+        }),
+        Curve::Ellipse(ellipse) => Curve::Ellipse(Ellipse {
+            center: plane.point_to_surface_coords(ellipse.center),
+            a: plane.vector_to_surface_coords(ellipse.a),
+            b: plane.vector_to_surface_coords(ellipse.b),
+        }),
+        Curve::Polyline(polyline) => {
+            Curve::Polyline(Polyline::from_points_and_knots(
+                polyline
+                    .points()
+                    .iter()
+                    .map(|&point| plane.point_to_surface_coords(point)),
+                polyline.knots().iter().copied(),
+            ))
+        }
+    };
+
+    Ok(curve)
+}
+
+/// An error projecting a curve onto a surface
+#[derive(Debug, thiserror::Error)]
+pub enum ProjectionError {
+    /// The curve projects onto a single point
+    #[error("Curve projects onto a single point ({0:?})")]
+    Degenerate(Point<3>),
+
+    /// Projecting this curve onto this surface is not supported yet
+    #[error("Projecting {curve:?} onto {surface:?} is not supported")]
+    Unsupported {
+        /// The curve that was to be projected
+        curve: Curve<3>,
+
+        /// The surface it was to be projected onto
+        surface: Surface,
+    },
+}
+
+struct Plane {
+    origin: Point<3>,
+    u: Vector<3>,
+    v: Vector<3>,
+    normal: Vector<3>,
+}
+
+impl Plane {
+    fn from_surface(surface: &Surface) -> Option<Self> {
+        let Surface::SweptCurve(surface) = surface;
+        let line = match surface.curve {
+            Curve::Line(line) => line,
+            Curve::Circle(_) | Curve::Ellipse(_) | Curve::Polyline(_) => {
+                return None
+            }
+        };
+
+        let u = line.direction;
+        let v = surface.path;
+
+        Some(Self {
+            origin: line.origin,
+            u,
+            v,
+            normal: u.cross(&v).normalize(),
+        })
+    }
+
+    fn project_point(&self, point: Point<3>) -> Point<3> {
+        point - self.normal * (point - self.origin).dot(&self.normal)
+    }
+
+    fn project_vector(&self, vector: Vector<3>) -> Vector<3> {
+        vector - self.normal * vector.dot(&self.normal)
+    }
+
+    /// Project an ellipse onto the plane
+    ///
+    /// Returns `None`, if the ellipse projects onto a line segment. That is
+    /// decided by the area of the parallelogram spanned by the projected
+    /// semi-diameters, relative to the original one.
+    fn project_ellipse(
+        &self,
+        ellipse: &Ellipse<3>,
+        tolerance: Tolerance,
+    ) -> Option<Ellipse<3>> {
+        let a = self.project_vector(ellipse.a);
+        let b = self.project_vector(ellipse.b);
+
+        let scale = ellipse.a.magnitude().max(ellipse.b.magnitude());
+        if a.cross(&b).magnitude() < tolerance.inner() * scale {
+            return None;
+        }
+
+        Some(Ellipse {
+            center: self.project_point(ellipse.center),
+            a,
+            b,
+        })
+    }
+
+    fn point_to_surface_coords(&self, point: Point<3>) -> Point<2> {
+        Point {
+            coords: self.vector_to_surface_coords(point - self.origin),
+        }
+    }
+
+    fn vector_to_surface_coords(&self, vector: Vector<3>) -> Vector<2> {
+        // The surface's axes aren't necessarily orthogonal or normalized, so we
+        // need to solve a linear system for the coordinates.
+        let uu = self.u.dot(&self.u);
+        let uv = self.u.dot(&self.v);
+        let vv = self.v.dot(&self.v);
+        let wu = vector.dot(&self.u);
+        let wv = vector.dot(&self.v);
+
+        let det = uu * vv - uv * uv;
+
+        let u: Scalar = (wu * vv - wv * uv) / det;
+        let v: Scalar = (wv * uu - wu * uv) / det;
+
+        Vector::from([u, v])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Circle, Ellipse, Line, Point, Vector};
+
+    use crate::{
+        algorithms::Tolerance,
+        objects::{Curve, Surface},
+    };
+
+    use super::{
+        project_curve_into_surface, project_curve_onto_surface, ProjectionError,
+    };
+
+    #[test]
+    fn line_onto_plane() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let line = Curve::Line(Line {
+            origin: Point::from([0., 0., 1.]),
+            direction: Vector::from([1., 0., 1.]),
+        });
+
+        let expected = Curve::Line(Line {
+            origin: Point::from([0., 0., 0.]),
+            direction: Vector::from([1., 0., 0.]),
+        });
+        assert_eq!(
+            project_curve_onto_surface(&line, &Surface::xy_plane(), tolerance)?,
+            expected,
+        );
+        assert_eq!(
+            project_curve_into_surface(&line, &Surface::xy_plane(), tolerance)?,
+            Curve::u_axis(),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn circle_onto_parallel_plane() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let circle = Curve::Circle(Circle {
+            center: Point::from([1., 2., 3.]),
+            a: Vector::from([2., 0., 0.]),
+            b: Vector::from([0., 2., 0.]),
+        });
+
+        let expected = Curve::Circle(Circle {
+            center: Point::from([1., 2., 0.]),
+            a: Vector::from([2., 0., 0.]),
+            b: Vector::from([0., 2., 0.]),
+        });
+        assert_eq!(
+            project_curve_onto_surface(
+                &circle,
+                &Surface::xy_plane(),
+                tolerance
+            )?,
+            expected,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn tilted_circle_onto_plane() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let circle = Curve::Circle(Circle {
+            center: Point::from([1., 2., 3.]),
+            a: Vector::from([2., 0., 0.]),
+            b: Vector::from([0., 1., 1.]),
+        });
+
+        let expected = Curve::Ellipse(Ellipse {
+            center: Point::from([1., 2., 0.]),
+            a: Vector::from([2., 0., 0.]),
+            b: Vector::from([0., 1., 0.]),
+        });
+        assert_eq!(
+            project_curve_onto_surface(
+                &circle,
+                &Surface::xy_plane(),
+                tolerance
+            )?,
+            expected,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn perpendicular_line_is_degenerate() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let line = Curve::Line(Line {
+            origin: Point::from([1., 2., 3.]),
+            direction: Vector::from([0., 0., 1.]),
+        });
+
+        let result =
+            project_curve_onto_surface(&line, &Surface::xy_plane(), tolerance);
+        assert!(matches!(
+            result,
+            Err(ProjectionError::Degenerate(point))
+                if point == Point::from([1., 2., 0.])
+        ));
+
+        Ok(())
+    }
+}
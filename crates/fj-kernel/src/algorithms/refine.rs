@@ -0,0 +1,192 @@
+use fj_interop::mesh::Mesh;
+use fj_math::{Point, Scalar};
+
+use crate::iter::ObjectIters;
+
+/// Map a point within a mesh triangle back to the exact surface of its face
+///
+/// The point is given by the barycentric coordinates of the triangle with the
+/// index `triangle_index` (see [`Mesh::triangle`]). They are normalized, so
+/// they don't need to sum up to `1.`.
+///
+/// The surface coordinates of the triangle's points, as recorded by the
+/// triangulation, are interpolated and the surface of the face is evaluated at
+/// the result. That is exact for planar faces, and much closer to the shape
+/// than the mesh for curved faces, whose triangles cut through them. The mesh
+/// must have been triangulated from the faces of `shape`, in the order they are
+/// returned by [`ObjectIters::face_iter`].
+///
+/// If the surface coordinates of the triangle are not known, for example
+/// because its face is made of triangles to begin with, the point on the mesh
+/// triangle is returned.
+///
+/// Returns `None`, if there is no triangle with the given index, or if the
+/// barycentric coordinates sum up to zero.
+pub fn refine_point<'r>(
+    shape: &'r impl ObjectIters<'r>,
+    mesh: &Mesh<Point<3>>,
+    triangle_index: usize,
+    barycentric: [f64; 3],
+) -> Option<Point<3>> {
+    let triangle = mesh.triangle(triangle_index)?;
+
+    let sum: f64 = barycentric.iter().sum();
+    if sum == 0. {
+        return None;
+    }
+    let weights = barycentric.map(|weight| Scalar::from_f64(weight / sum));
+
+    let on_surface = triangle
+        .face
+        .zip(mesh.surface_coords(triangle_index))
+        .and_then(|(face, surface_coords)| {
+            let face = shape.face_iter().nth(face)?;
+            if face.triangles().is_some() {
+                return None;
+            }
+
+            let point = interpolate(surface_coords, weights);
+            Some(face.surface().point_from_surface_coords(point))
+        });
+
+    Some(on_surface.unwrap_or_else(|| interpolate(triangle.points, weights)))
+}
+
+fn interpolate<const D: usize>(
+    [a, b, c]: [Point<D>; 3],
+    [wa, wb, wc]: [Scalar; 3],
+) -> Point<D> {
+    Point {
+        coords: a.coords * wa + b.coords * wb + c.coords * wc,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::{debug::DebugInfo, mesh::Mesh};
+    use fj_math::{Circle, Line, Point, Scalar, Vector};
+
+    use crate::{
+        algorithms::{triangulate, ApproxStore, ErrorBudget, Tolerance},
+        local::Local,
+        objects::{
+            Curve, Cycle, Edge, Face, Solid, Surface, SweptCurve,
+            VerticesOfEdge,
+        },
+    };
+
+    use super::refine_point;
+
+    const CENTROID: [f64; 3] = [1., 1., 1.];
+
+    #[test]
+    fn cylinder_barrel() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.1)?;
+        let barrel = barrel();
+        let mesh = triangulate_faces(vec![barrel.clone()], tolerance)?;
+
+        let distance_from_axis = |point: Point<3>| {
+            (point.x * point.x + point.y * point.y).sqrt().into_f64()
+        };
+
+        let mut max_mesh_error: f64 = 0.;
+        for (index, triangle) in mesh.triangles().enumerate() {
+            let [a, b, c] = triangle.points;
+            let on_mesh = Point {
+                coords: (a.coords + b.coords + c.coords) / 3.,
+            };
+            max_mesh_error =
+                max_mesh_error.max(1. - distance_from_axis(on_mesh));
+
+            let refined = refine_point(&barrel, &mesh, index, CENTROID)
+                .expect("Triangle exists");
+            assert!((distance_from_axis(refined) - 1.).abs() < 1e-12);
+        }
+
+        // The refined points are exact, where the mesh is not.
+        assert!(max_mesh_error > 0.01);
+        assert!(max_mesh_error <= tolerance.inner().into_f64());
+
+        Ok(())
+    }
+
+    #[test]
+    fn planar_faces_are_exact() -> anyhow::Result<()> {
+        let cube = Solid::cube_from_edge_length(2.);
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        let mesh =
+            triangulate_faces(cube.faces().cloned().collect(), tolerance)?;
+
+        for (index, triangle) in mesh.triangles().enumerate() {
+            let [a, b, _] = triangle.points;
+            let refined = refine_point(&cube, &mesh, index, [1., 1., 0.])
+                .expect("Triangle exists");
+
+            let expected = Point {
+                coords: (a.coords + b.coords) / 2.,
+            };
+            assert!((refined - expected).magnitude() < Scalar::from_f64(1e-12));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_input() -> anyhow::Result<()> {
+        let cube = Solid::cube_from_edge_length(2.);
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        let mesh =
+            triangulate_faces(cube.faces().cloned().collect(), tolerance)?;
+
+        let num_triangles = mesh.triangles().count();
+        assert_eq!(refine_point(&cube, &mesh, num_triangles, CENTROID), None);
+        assert_eq!(refine_point(&cube, &mesh, 0, [0., 0., 0.]), None);
+
+        Ok(())
+    }
+
+    /// The barrel of a cylinder with radius `1`, whose rims are a single
+    /// circle each
+    fn barrel() -> Face {
+        let circle = |z: f64, b: Vector<3>| Circle {
+            center: Point::from([0., 0., z]),
+            a: Vector::unit_x(),
+            b,
+        };
+        let surface = Surface::SweptCurve(SweptCurve {
+            curve: Curve::Circle(circle(0., Vector::unit_y())),
+            path: Vector::unit_z(),
+        });
+
+        let rim = |v: f64, direction: Vector<2>, global: Circle<3>| {
+            let local = Curve::Line(Line {
+                origin: Point::from([0., v]),
+                direction,
+            });
+            Cycle {
+                edges: vec![Edge::new(
+                    Local::new(local, Curve::Circle(global)),
+                    VerticesOfEdge::none(),
+                )],
+            }
+        };
+        let bottom = rim(0., Vector::unit_u(), circle(0., Vector::unit_y()));
+        let top = rim(1., -Vector::unit_u(), circle(1., -Vector::unit_y()));
+
+        Face::new(surface, [bottom], [top], [255, 0, 0, 255])
+    }
+
+    fn triangulate_faces(
+        faces: Vec<Face>,
+        tolerance: Tolerance,
+    ) -> anyhow::Result<Mesh<Point<3>>> {
+        let mesh = triangulate(
+            faces,
+            tolerance,
+            &ErrorBudget::new(),
+            &mut ApproxStore::new(),
+            &mut DebugInfo::new(),
+        )?;
+        Ok(mesh)
+    }
+}
@@ -1,22 +1,90 @@
-use fj_math::{Circle, Line, Point, Vector};
+use fj_math::{Circle, Ellipse, Line, Point, Polyline, Scalar, Vector};
 
 use crate::{
     local::Local,
-    objects::{Curve, Cycle, Edge, Face},
+    objects::{Curve, Cycle, Edge, Face, Vertex, VerticesOfEdge},
 };
 
 /// Reverse the direction of a face
+///
+/// Reverses the surface, as well as the direction of the face's cycles. The
+/// exterior cycle stays counter-clockwise in surface coordinates, so the
+/// outward normal of the reversed face is the normal of the reversed surface
+/// (see [`Face`]).
 pub fn reverse_face(face: &Face) -> Face {
     if face.triangles().is_some() {
         panic!("Reversing tri-rep faces is not supported");
     }
 
-    let surface = face.surface().reverse();
+    let surface = face.surface().clone().reverse();
 
-    let exteriors = reverse_local_coordinates_in_cycle(face.exteriors());
-    let interiors = reverse_local_coordinates_in_cycle(face.interiors());
+    let exteriors =
+        reverse_local_coordinates_in_cycle(face.exteriors()).map(reverse_cycle);
+    let interiors =
+        reverse_local_coordinates_in_cycle(face.interiors()).map(reverse_cycle);
 
+    // Only the v-coordinates are reversed, so the seam stays where it is.
     Face::new(surface, exteriors, interiors, face.color())
+        .with_overrides_of(face)
+}
+
+/// Reverse the direction of a cycle
+///
+/// The order of the edges is reversed, as is the direction of each edge. The
+/// edges stay in the same place, both in surface and in global coordinates.
+pub(crate) fn reverse_cycle(cycle: Cycle) -> Cycle {
+    let mut edges: Vec<_> = cycle.edges.iter().map(reverse_edge).collect();
+    edges.reverse();
+
+    Cycle { edges }
+}
+
+pub(crate) fn reverse_edge(edge: &Edge) -> Edge {
+    let vertices = match edge.vertices().get() {
+        Some([a, b]) => [a, b],
+        None => {
+            let curve = Local::new(
+                edge.curve().local_form().clone().reverse(),
+                edge.curve().global_form().clone().reverse(),
+            );
+            return Edge::new(curve, *edge.vertices());
+        }
+    };
+
+    // Lines are reversed such that the vertices keep their curve coordinates,
+    // just swapped. This results in the same representation that constructing
+    // the edge in reverse would have produced.
+    //
+    // Other curves are reversed by negating their curve coordinates.
+    let [a, b] = vertices;
+    let t_sum = a.position().t + b.position().t;
+
+    let (local, global, vertices) =
+        match (edge.curve().local_form(), edge.curve().global_form()) {
+            (Curve::Line(local), Curve::Line(global)) => (
+                Curve::Line(reverse_line(*local, t_sum)),
+                Curve::Line(reverse_line(*global, t_sum)),
+                VerticesOfEdge::from_vertices([
+                    Vertex::new(a.position(), *b.global()),
+                    Vertex::new(b.position(), *a.global()),
+                ]),
+            ),
+            (local, global) => (
+                local.clone().reverse(),
+                global.clone().reverse(),
+                edge.vertices().reverse(),
+            ),
+        };
+
+    Edge::new(Local::new(local, global), vertices)
+}
+
+/// Reverse a line, mapping curve coordinate `t` to `t_sum - t`
+fn reverse_line<const D: usize>(line: Line<D>, t_sum: Scalar) -> Line<D> {
+    Line {
+        origin: line.origin + line.direction * t_sum,
+        direction: -line.direction,
+    }
 }
 
 fn reverse_local_coordinates_in_cycle<'r>(
@@ -37,6 +105,14 @@ fn reverse_local_coordinates_in_cycle<'r>(
 
                             Curve::Circle(Circle { center, a, b })
                         }
+                        Curve::Ellipse(Ellipse { center, a, b }) => {
+                            let center = Point::from([center.u, -center.v]);
+
+                            let a = Vector::from([a.u, -a.v]);
+                            let b = Vector::from([b.u, -b.v]);
+
+                            Curve::Ellipse(Ellipse { center, a, b })
+                        }
                         Curve::Line(Line { origin, direction }) => {
                             let origin = Point::from([origin.u, -origin.v]);
                             let direction =
@@ -44,9 +120,20 @@ fn reverse_local_coordinates_in_cycle<'r>(
 
                             Curve::Line(Line { origin, direction })
                         }
+                        Curve::Polyline(polyline) => {
+                            let points = polyline
+                                .points()
+                                .iter()
+                                .map(|point| Point::from([point.u, -point.v]));
+
+                            Curve::Polyline(Polyline::from_points_and_knots(
+                                points,
+                                polyline.knots().iter().copied(),
+                            ))
+                        }
                     };
 
-                    Local::new(local, *edge.curve().global_form())
+                    Local::new(local, edge.curve().global_form().clone())
                 };
 
                 Edge::new(curve, *edge.vertices())
@@ -72,7 +159,7 @@ mod tests {
         let reversed = super::reverse_face(&original);
 
         let expected = Face::builder(Surface::xy_plane().reverse())
-            .with_exterior_polygon([[0., 0.], [1., 0.], [0., -1.]])
+            .with_exterior_polygon([[0., 0.], [0., -1.], [1., 0.]])
             .build();
 
         assert_eq!(expected, reversed);
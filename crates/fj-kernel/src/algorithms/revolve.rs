@@ -0,0 +1,409 @@
+use fj_math::{Point, Scalar, Transform, Triangle, Vector};
+
+use crate::{
+    iter::ObjectIters,
+    objects::{Cycle, Face, Sketch, Solid},
+    validation::ValidationConfig,
+};
+
+use super::{
+    approx::number_of_vertices_for_circle, reverse_face, sweep::front_normal,
+    CycleApprox, Tolerance, TransformObject,
+};
+
+/// Create a solid by revolving a sketch around an axis
+///
+/// The axis passes through `axis_origin`, in the direction of
+/// `axis_direction`. The sketch is rotated by `angle` (in radians) around it,
+/// following the right-hand rule. Negative angles rotate the other way.
+///
+/// If the absolute value of `angle` is a full turn or more, the sketch is
+/// revolved by exactly a full turn, and the resulting solid has no cap faces.
+/// Otherwise, the sketch and its rotated copy are the caps at both ends.
+///
+/// The side faces are created from the approximation of the sketch's edges,
+/// like the side faces of [`sweep`] that don't lie in a plane. Edges that lie
+/// on the axis don't sweep out any area, and don't result in side faces.
+///
+/// [`sweep`]: super::sweep
+pub fn revolve(
+    source: Sketch,
+    axis_origin: impl Into<Point<3>>,
+    axis_direction: impl Into<Vector<3>>,
+    angle: impl Into<Scalar>,
+    tolerance: Tolerance,
+    color: [u8; 4],
+) -> Result<Solid, RevolveError> {
+    let axis = Axis::new(axis_origin.into(), axis_direction.into())?;
+    let angle = angle.into();
+
+    if angle == Scalar::ZERO {
+        return Err(RevolveError::ZeroAngle);
+    }
+
+    let full_turn = Scalar::PI * 2.;
+    let is_full_turn = angle.abs() >= full_turn;
+    let angle = if is_full_turn {
+        if angle > Scalar::ZERO {
+            full_turn
+        } else {
+            -full_turn
+        }
+    } else {
+        angle
+    };
+
+    let mut target = Vec::new();
+
+    for face in source.face_iter() {
+        let approx: Vec<_> = face
+            .all_cycles()
+            .map(|cycle| CycleApprox::new(cycle, tolerance))
+            .collect();
+        let points = approx
+            .iter()
+            .flat_map(|cycle| cycle.points.iter())
+            .map(|point| *point.global_form());
+
+        // The direction in which the points of the face move, relative to its
+        // front side, scaled by their distance from the axis.
+        let normal = front_normal(face, tolerance);
+        let min_distance = ValidationConfig::default().distinct_min_distance;
+
+        let direction = if angle < Scalar::ZERO {
+            -Scalar::ONE
+        } else {
+            Scalar::ONE
+        };
+
+        let mut max_radius = Scalar::ZERO;
+        let [mut front, mut back] = [false; 2];
+        for point in points {
+            let movement = axis.movement_at(point) * direction;
+            let along_normal = movement.dot(&normal);
+
+            front |= along_normal > min_distance;
+            back |= along_normal < -min_distance;
+            max_radius = max_radius.max(movement.magnitude());
+        }
+
+        let is_revolve_along_negative_direction = match (front, back) {
+            (true, false) => false,
+            (false, true) => true,
+            (true, true) => return Err(RevolveError::SketchCrossesAxis),
+            (false, false) => return Err(RevolveError::AxisNormalToSketch),
+        };
+
+        let steps = number_of_steps(angle, max_radius, tolerance);
+        let stations: Vec<_> = (0..=steps)
+            .map(|i| {
+                // The first station must match the sketch exactly, and so
+                // must the last station of a full turn, or the solid wouldn't
+                // be closed.
+                if i == 0 || (is_full_turn && i == steps) {
+                    return Transform::identity();
+                }
+
+                axis.rotation(angle * (i as f64 / steps as f64))
+            })
+            .collect();
+
+        if !is_full_turn {
+            let bottom = if is_revolve_along_negative_direction {
+                face.clone()
+            } else {
+                reverse_face(face)
+            };
+
+            let mut top = face.clone().transform(&stations[steps]);
+            if is_revolve_along_negative_direction {
+                top = reverse_face(&top);
+            }
+
+            target.push(bottom);
+            target.push(top);
+        }
+
+        for cycle in face.all_cycles() {
+            for edge in &cycle.edges {
+                let cycle = Cycle {
+                    edges: vec![edge.clone()],
+                };
+                let approx = CycleApprox::new(&cycle, tolerance);
+
+                let mut side_face = Vec::new();
+                for segment in approx.segments() {
+                    let [a, b] = segment.points();
+
+                    for stations in stations.windows(2) {
+                        let [v0, v1] = [a, b].map(|point| {
+                            axis.rotate(point, &stations[0], min_distance)
+                        });
+                        let [v3, v2] = [a, b].map(|point| {
+                            axis.rotate(point, &stations[1], min_distance)
+                        });
+
+                        let quad = if is_revolve_along_negative_direction {
+                            [v1, v0, v3, v2]
+                        } else {
+                            [v0, v1, v2, v3]
+                        };
+
+                        let [v0, v1, v2, v3] = quad;
+                        for triangle in [[v0, v1, v2], [v0, v2, v3]] {
+                            if is_degenerate(triangle, min_distance) {
+                                continue;
+                            }
+
+                            side_face
+                                .push((Triangle::from_points(triangle), color));
+                        }
+                    }
+                }
+
+                if !side_face.is_empty() {
+                    target.push(Face::from_triangles(side_face));
+                }
+            }
+        }
+    }
+
+    Ok(Solid::from_faces(target))
+}
+
+/// The axis of a revolution
+struct Axis {
+    origin: Point<3>,
+    direction: Vector<3>,
+}
+
+impl Axis {
+    fn new(
+        origin: Point<3>,
+        direction: Vector<3>,
+    ) -> Result<Self, RevolveError> {
+        if direction.magnitude() == Scalar::ZERO {
+            return Err(RevolveError::ZeroAxis);
+        }
+
+        Ok(Self {
+            origin,
+            direction: direction.normalize(),
+        })
+    }
+
+    /// The direction in which a point moves, when rotated around the axis
+    ///
+    /// The magnitude of the returned vector is the distance of the point from
+    /// the axis.
+    fn movement_at(&self, point: Point<3>) -> Vector<3> {
+        self.direction.cross(&(point - self.origin))
+    }
+
+    /// Rotate a point using a rotation from [`Axis::rotation`]
+    ///
+    /// Points on the axis are returned unchanged, instead of picking up
+    /// floating-point noise from the rotation. That way, the triangles that
+    /// collapse there can be recognized reliably.
+    fn rotate(
+        &self,
+        point: Point<3>,
+        rotation: &Transform,
+        min_distance: Scalar,
+    ) -> Point<3> {
+        if self.movement_at(point).magnitude() < min_distance {
+            return point;
+        }
+
+        rotation.transform_point(&point)
+    }
+
+    fn rotation(&self, angle: Scalar) -> Transform {
+        Transform::translation(self.origin.coords)
+            * Transform::rotation(self.direction * angle)
+            * Transform::translation(-self.origin.coords)
+    }
+}
+
+/// The number of steps in which the side faces are approximated
+///
+/// A full turn is approximated like a circle with the largest radius of any of
+/// the sketch's points, so the approximation stays within the tolerance.
+fn number_of_steps(
+    angle: Scalar,
+    max_radius: Scalar,
+    tolerance: Tolerance,
+) -> usize {
+    let full_turn = number_of_vertices_for_circle(tolerance, max_radius);
+    let steps = (Scalar::from_u64(full_turn) * angle.abs() / (Scalar::PI * 2.))
+        .ceil()
+        .into_u64();
+
+    steps.max(1) as usize
+}
+
+/// Triangles with vertices on the axis collapse, as those don't move
+fn is_degenerate(triangle: [Point<3>; 3], min_distance: Scalar) -> bool {
+    let [a, b, c] = triangle;
+
+    [[a, b], [b, c], [c, a]]
+        .into_iter()
+        .any(|[a, b]| (a - b).magnitude() < min_distance)
+}
+
+/// An error that can occur while revolving
+#[derive(Debug, thiserror::Error)]
+pub enum RevolveError {
+    /// The direction of the axis has zero length
+    #[error("Axis of revolution has no direction")]
+    ZeroAxis,
+
+    /// The angle of the revolution is zero
+    #[error("Angle of revolution is zero")]
+    ZeroAngle,
+
+    /// The sketch lies on both sides of the axis
+    ///
+    /// The revolved sketch would intersect itself.
+    #[error("Sketch crosses the axis of revolution")]
+    SketchCrossesAxis,
+
+    /// The axis is normal to the sketch
+    ///
+    /// The sketch would rotate within its own plane, which doesn't create a
+    /// solid.
+    #[error("Axis of revolution is normal to sketch")]
+    AxisNormalToSketch,
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::physical_summary::PhysicalSummary;
+    use fj_math::Scalar;
+
+    use crate::{
+        algorithms::{test_helpers::closed_mesh, Tolerance},
+        iter::ObjectIters,
+        objects::{Face, Sketch, Surface},
+    };
+
+    use super::RevolveError;
+
+    #[test]
+    fn full_turn() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        // A square next to the y-axis, which turns into a torus with a square
+        // cross-section.
+        let sketch = square([[1., 0.], [2., 0.], [2., 1.], [1., 1.]]);
+        let solid = super::revolve(
+            sketch,
+            [0., 0., 0.],
+            [0., 1., 0.],
+            Scalar::PI * 2.,
+            tolerance,
+            [255, 0, 0, 255],
+        )?;
+
+        // There are no caps, only side faces.
+        assert!(solid.face_iter().all(|face| face.triangles().is_some()));
+
+        let mesh = closed_mesh(solid, tolerance)?;
+
+        // The volume of the torus is the area of the square, times the length
+        // of the path of its centroid. The approximation is slightly smaller.
+        let expected = 1.5 * std::f64::consts::PI * 2.;
+        let summary = PhysicalSummary::from_mesh(&mesh, tolerance.inner());
+        let volume = summary.volume.into_f64();
+        assert!(volume < expected);
+        assert!(volume > expected * 0.99);
+
+        Ok(())
+    }
+
+    #[test]
+    fn partial_turn() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let sketch = square([[1., 0.], [2., 0.], [2., 1.], [1., 1.]]);
+        let solid = super::revolve(
+            sketch,
+            [0., 0., 0.],
+            [0., 1., 0.],
+            Scalar::PI / 2.,
+            tolerance,
+            [255, 0, 0, 255],
+        )?;
+
+        // Both caps are planar faces.
+        let num_caps = solid
+            .face_iter()
+            .filter(|face| face.triangles().is_none())
+            .count();
+        assert_eq!(num_caps, 2);
+
+        let mesh = closed_mesh(solid, tolerance)?;
+
+        let expected = 1.5 * std::f64::consts::PI / 2.;
+        let summary = PhysicalSummary::from_mesh(&mesh, tolerance.inner());
+        let volume = summary.volume.into_f64();
+        assert!(volume > 0.);
+        assert!((volume - expected).abs() < expected * 0.01);
+
+        Ok(())
+    }
+
+    #[test]
+    fn edge_on_axis() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        // A square with its left edge on the axis. Turns into a cylinder.
+        let sketch = square([[0., 0.], [1., 0.], [1., 1.], [0., 1.]]);
+        let solid = super::revolve(
+            sketch,
+            [0., 0., 0.],
+            [0., 1., 0.],
+            -Scalar::PI * 2.,
+            tolerance,
+            [255, 0, 0, 255],
+        )?;
+
+        // Only the other three edges result in side faces.
+        assert_eq!(solid.face_iter().count(), 3);
+
+        let mesh = closed_mesh(solid, tolerance)?;
+
+        let expected = std::f64::consts::PI;
+        let summary = PhysicalSummary::from_mesh(&mesh, tolerance.inner());
+        let volume = summary.volume.into_f64();
+        assert!(volume < expected);
+        assert!(volume > expected * 0.98);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sketch_crosses_axis() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let sketch = square([[-1., 0.], [1., 0.], [1., 1.], [-1., 1.]]);
+        let result = super::revolve(
+            sketch,
+            [0., 0., 0.],
+            [0., 1., 0.],
+            Scalar::PI,
+            tolerance,
+            [255, 0, 0, 255],
+        );
+        assert!(matches!(result, Err(RevolveError::SketchCrossesAxis)));
+
+        Ok(())
+    }
+
+    fn square(points: [[f64; 2]; 4]) -> Sketch {
+        let face = Face::builder(Surface::xy_plane())
+            .with_exterior_polygon(points)
+            .build();
+        Sketch::from_faces([face])
+    }
+}
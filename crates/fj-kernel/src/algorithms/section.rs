@@ -0,0 +1,195 @@
+use std::collections::BTreeMap;
+
+use fj_interop::{
+    debug::DebugInfo,
+    mesh::{Color, Mesh},
+};
+use fj_math::Point;
+
+use crate::objects::{Sketch, Surface};
+
+use super::{
+    split::{cap_faces, cut_triangle, Plane, SplitError},
+    triangulate, ApproxStore, ErrorBudget, Tolerance,
+};
+
+/// The color of triangles that [`clip_mesh`] closes the cut with
+pub const SECTION_CAP_COLOR: Color = [255, 160, 0, 255];
+
+/// Compute the cross-section of a triangle mesh along a plane
+///
+/// Returns a sketch on the plane, with one face per region of the
+/// cross-section. Holes in the cross-section become interior cycles of the
+/// faces. The faces point along the plane's normal, which means they close the
+/// part of the mesh below the plane.
+///
+/// The mesh must be closed and its triangles must point outward. Otherwise,
+/// [`SplitError::OpenSection`] is returned. Vertices that lie exactly on the
+/// plane count as being above it.
+pub fn section_mesh(
+    mesh: &Mesh<Point<3>>,
+    plane: &Surface,
+) -> Result<Sketch, SplitError> {
+    let plane = Plane::new(plane)?;
+    let section = section_segments(mesh, &plane);
+
+    let faces = cap_faces(section, &plane, SECTION_CAP_COLOR)?;
+    Ok(Sketch::from_faces(faces))
+}
+
+/// Remove the part of a triangle mesh above a plane
+///
+/// Triangles that cross the plane are cut, and the cut is closed with the
+/// triangulated cross-section (see [`section_mesh`]), colored with
+/// [`SECTION_CAP_COLOR`]. The pieces of cut triangles keep their color and
+/// face, but not their surface coordinates. Their normals are computed from
+/// their points.
+pub fn clip_mesh(
+    mesh: &Mesh<Point<3>>,
+    plane: &Surface,
+    tolerance: Tolerance,
+) -> Result<Mesh<Point<3>>, SplitError> {
+    let plane = Plane::new(plane)?;
+
+    let mut clipped = Mesh::new();
+    clipped.set_double_sided(mesh.is_double_sided());
+
+    for triangle in mesh.triangles() {
+        let below = cut_triangle(triangle.points, &plane).below;
+
+        // The pieces of a triangle are convex, so a fan is fine.
+        for i in 1..below.len().saturating_sub(1) {
+            let points = [below[0], below[i], below[i + 1]];
+            match triangle.face {
+                Some(face) => {
+                    clipped.push_face_triangle(points, triangle.color, face);
+                }
+                None => clipped.push_triangle(points, triangle.color),
+            }
+        }
+    }
+
+    let caps =
+        cap_faces(section_segments(mesh, &plane), &plane, SECTION_CAP_COLOR)?;
+    let caps = triangulate(
+        caps,
+        tolerance,
+        &ErrorBudget::new(),
+        &mut ApproxStore::new(),
+        &mut DebugInfo::new(),
+    )?;
+    for triangle in caps.triangles() {
+        clipped.push_triangle(triangle.points, SECTION_CAP_COLOR);
+    }
+
+    Ok(clipped)
+}
+
+/// The segments in which the triangles of a mesh cross a plane
+///
+/// Maps the start of each segment to its end, oriented as the boundary of the
+/// cap below the plane.
+fn section_segments(
+    mesh: &Mesh<Point<3>>,
+    plane: &Plane,
+) -> BTreeMap<Point<3>, Point<3>> {
+    mesh.triangles()
+        .filter_map(|triangle| cut_triangle(triangle.points, plane).segment)
+        .map(|[start, end]| (start, end))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::{
+        debug::DebugInfo, mesh::Mesh, physical_summary::PhysicalSummary,
+    };
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        algorithms::{
+            sweep, triangulate, ApproxStore, ErrorBudget, Tolerance,
+            TransformObject,
+        },
+        objects::{Face, Sketch, Surface},
+    };
+
+    use super::{clip_mesh, section_mesh, SECTION_CAP_COLOR};
+
+    #[test]
+    fn cube() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        let mesh = cube_mesh(tolerance)?;
+
+        let plane = Surface::xy_plane().translate([0., 0., 0.3]);
+
+        let section = section_mesh(&mesh, &plane)?;
+        let faces: Vec<_> = section.faces().collect();
+        assert_eq!(faces.len(), 1);
+        assert_eq!(faces[0].interiors().count(), 0);
+
+        let section = triangulate(
+            section.into_faces().into_iter().collect(),
+            tolerance,
+            &ErrorBudget::new(),
+            &mut ApproxStore::new(),
+            &mut DebugInfo::new(),
+        )?;
+        assert!(is_close(area(&section), 1.));
+        for triangle in section.triangles() {
+            assert!(triangle.points.iter().all(|point| is_close(point.z, 0.3)));
+        }
+
+        let clipped = clip_mesh(&mesh, &plane, tolerance)?;
+        let volume =
+            PhysicalSummary::from_mesh(&clipped, tolerance.inner()).volume;
+        assert!(is_close(volume, 0.3));
+        assert!(clipped
+            .triangles()
+            .any(|triangle| triangle.color == SECTION_CAP_COLOR));
+
+        // A plane that misses the mesh leaves nothing to cap.
+        let plane = Surface::xy_plane().translate([0., 0., 2.]);
+        assert_eq!(section_mesh(&mesh, &plane)?.faces().count(), 0);
+        assert_eq!(
+            clip_mesh(&mesh, &plane, tolerance)?.triangle_count(),
+            mesh.triangle_count()
+        );
+
+        Ok(())
+    }
+
+    fn cube_mesh(tolerance: Tolerance) -> anyhow::Result<Mesh<Point<3>>> {
+        let face = Face::builder(Surface::xy_plane())
+            .with_exterior_polygon([[0., 0.], [1., 0.], [1., 1.], [0., 1.]])
+            .build();
+        let cube = sweep(
+            Sketch::from_faces([face]),
+            [0., 0., 1.],
+            tolerance,
+            [255, 0, 0, 255],
+        )?;
+
+        let mesh = triangulate(
+            cube.into_faces().into_iter().collect(),
+            tolerance,
+            &ErrorBudget::new(),
+            &mut ApproxStore::new(),
+            &mut DebugInfo::new(),
+        )?;
+        Ok(mesh)
+    }
+
+    fn area(mesh: &Mesh<Point<3>>) -> Scalar {
+        mesh.triangles()
+            .map(|triangle| {
+                let [a, b, c] = triangle.points;
+                (b - a).cross(&(c - a)).magnitude() / 2.
+            })
+            .fold(Scalar::ZERO, |sum, area| sum + area)
+    }
+
+    fn is_close(a: Scalar, b: f64) -> bool {
+        (a - Scalar::from_f64(b)).abs() < Scalar::from_f64(1e-9)
+    }
+}
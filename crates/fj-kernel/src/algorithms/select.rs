@@ -0,0 +1,227 @@
+use fj_math::{Point, Scalar, Vector};
+
+use crate::objects::{Curve, Edge, Face, Solid, Surface};
+
+use super::{edge_loop_from, Handedness, Tolerance};
+
+/// Selects faces of a shape
+///
+/// Used to restrict algorithms to a part of a shape, for example to only
+/// triangulate some of its faces (see [`triangulate_selection`]).
+///
+/// [`triangulate_selection`]: super::triangulate_selection
+#[derive(Clone, Debug, PartialEq)]
+pub enum FaceSelector {
+    /// Select all faces
+    All,
+
+    /// Select the faces at the given indices
+    ///
+    /// The indices refer to the order in which the faces of the shape are
+    /// iterated over.
+    Indices(Vec<usize>),
+
+    /// Select the planar faces whose outward normal points in the given
+    /// direction
+    ///
+    /// A face is selected, if the angle between its normal and the direction
+    /// is at most `max_angle` (in radians). Faces that aren't planar are never
+    /// selected.
+    Normal {
+        /// The direction the normal of a selected face points in
+        direction: Vector<3>,
+
+        /// The maximum angle between the normal and the direction
+        max_angle: Scalar,
+    },
+}
+
+impl FaceSelector {
+    /// Select the planar faces whose normal points exactly in `direction`
+    ///
+    /// Allows for a small angle between the two, to be robust against floating
+    /// point accuracy issues.
+    pub fn normal(direction: impl Into<Vector<3>>) -> Self {
+        Self::Normal {
+            direction: direction.into(),
+            max_angle: Scalar::from_f64(1e-6),
+        }
+    }
+
+    /// Determine whether the face at the given index is selected
+    pub fn selects(&self, index: usize, face: &Face) -> bool {
+        match self {
+            Self::All => true,
+            Self::Indices(indices) => indices.contains(&index),
+            Self::Normal {
+                direction,
+                max_angle,
+            } => match planar_normal(face) {
+                Some(normal) => {
+                    let cos = normal.dot(&direction.normalize());
+                    cos >= max_angle.cos()
+                }
+                None => false,
+            },
+        }
+    }
+}
+
+/// Refers to an edge of a solid
+///
+/// Edges are identified by their position within the solid: The index of the
+/// face within [`Solid::faces`], the index of the cycle within
+/// [`Face::all_cycles`], and the index of the edge within that cycle.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct EdgeRef {
+    /// The index of the face
+    pub face: usize,
+
+    /// The index of the cycle within the face
+    pub cycle: usize,
+
+    /// The index of the edge within the cycle
+    pub edge: usize,
+}
+
+impl EdgeRef {
+    /// Access the edge that this reference refers to
+    ///
+    /// Returns `None`, if the reference doesn't refer to an edge of `solid`.
+    /// Faces that are made of triangles have no edges.
+    pub fn resolve<'r>(&self, solid: &'r Solid) -> Option<&'r Edge> {
+        let face = solid.faces().nth(self.face)?;
+        if face.triangles().is_some() {
+            return None;
+        }
+
+        face.all_cycles().nth(self.cycle)?.edges.get(self.edge)
+    }
+}
+
+/// Selects edges of a solid
+#[derive(Clone, Debug, PartialEq)]
+pub enum EdgeSelector {
+    /// Select all edges
+    ///
+    /// Edges that are shared by two faces are selected twice, once from each
+    /// face.
+    All,
+
+    /// Select the given edges
+    Edges(Vec<EdgeRef>),
+
+    /// Select the loop of tangent-connected edges that contains an edge
+    ///
+    /// See [`edge_loop_from`] for details.
+    Loop {
+        /// The edge from which to start the loop
+        seed: EdgeRef,
+
+        /// The maximum change of direction between connected edges
+        max_angle: Scalar,
+    },
+}
+
+impl EdgeSelector {
+    /// Select the edges of `solid` whose vertices satisfy a predicate
+    ///
+    /// `predicate` is called with the positions of the vertices of each edge.
+    /// Edges without vertices are never selected.
+    pub fn matching(
+        solid: &Solid,
+        mut predicate: impl FnMut([Point<3>; 2]) -> bool,
+    ) -> Self {
+        let edges = Self::All
+            .select(solid)
+            .into_iter()
+            .filter(|edge_ref| {
+                edge_ref
+                    .resolve(solid)
+                    .and_then(Edge::vertex_positions)
+                    .map_or(false, &mut predicate)
+            })
+            .collect();
+
+        Self::Edges(edges)
+    }
+
+    /// Select the loop of tangent-connected edges that contains `seed`
+    ///
+    /// `max_angle` is in radians.
+    pub fn loop_from(seed: EdgeRef, max_angle: impl Into<Scalar>) -> Self {
+        Self::Loop {
+            seed,
+            max_angle: max_angle.into(),
+        }
+    }
+
+    /// Select edges of the given solid
+    ///
+    /// Faces that are made of triangles have no edges, so none are selected
+    /// from them.
+    pub fn select(&self, solid: &Solid) -> Vec<EdgeRef> {
+        match self {
+            Self::All => {
+                let mut edges = Vec::new();
+                for (face_index, face) in solid.faces().enumerate() {
+                    if face.triangles().is_some() {
+                        continue;
+                    }
+
+                    for (cycle_index, cycle) in face.all_cycles().enumerate() {
+                        edges.extend((0..cycle.edges.len()).map(|edge| {
+                            EdgeRef {
+                                face: face_index,
+                                cycle: cycle_index,
+                                edge,
+                            }
+                        }));
+                    }
+                }
+                edges
+            }
+            Self::Edges(edges) => edges.clone(),
+            Self::Loop { seed, max_angle } => {
+                edge_loop_from(solid, seed, *max_angle).edges
+            }
+        }
+    }
+}
+
+/// Compute the normal of a face, if it is planar
+///
+/// Faces that are defined by triangles are considered planar, if all of their
+/// triangles have the same normal.
+fn planar_normal(face: &Face) -> Option<Vector<3>> {
+    if let Some(triangles) = face.triangles() {
+        let mut normals = triangles
+            .iter()
+            .map(|(triangle, _)| triangle.normal().normalize());
+        let normal = normals.next()?;
+
+        let epsilon = Scalar::from_f64(1e-6);
+        return normals
+            .all(|other| (other - normal).magnitude() <= epsilon)
+            .then(|| normal);
+    }
+
+    let surface = face.surface();
+    let normal = surface
+        .vector_from_surface_coords([1., 0.])
+        .cross(&surface.vector_from_surface_coords([0., 1.]));
+
+    // Which way the face points depends on the winding of its exterior cycle.
+    // Even the coarsest approximation of the cycle has the right winding.
+    let normal = Handedness::of_face(face, Tolerance::from(Scalar::MAX))?
+        .outward(normal);
+
+    // Only planes have a constant normal. For other surfaces, the normal
+    // differs depending on the surface coordinates.
+    let is_plane = {
+        let Surface::SweptCurve(surface) = surface;
+        matches!(surface.curve, Curve::Line(_))
+    };
+
+    is_plane.then(|| normal.normalize())
+}
@@ -0,0 +1,382 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use fj_math::{Point, Scalar, Vector};
+
+use crate::objects::Solid;
+
+use super::{
+    edge_blend::{face_vertices, polygon_face, vector_area, PlanarFace},
+    FaceSelector,
+};
+
+/// Hollow out a solid, leaving walls of the given thickness
+///
+/// Each face of the solid is copied to the inside, offset by `thickness`. The
+/// copies point into the cavity they bound. The faces in `faces_to_remove` are
+/// not copied, but opened up: each of their edges is connected to the inner
+/// walls by a face in the plane of the removed face, which forms the rim of
+/// the opening. For a cube with an open top, that results in 5 outer faces, 5
+/// inner faces, and 4 faces for the rim. If no faces are removed, the result
+/// is a solid with a closed cavity.
+///
+/// Faces are selected from the solid as it is passed in, but selecting them
+/// by their normal refers to their outward normal.
+///
+/// Only solids whose faces are planar polygons without holes are supported.
+/// Each vertex must be a corner of three faces, and removed faces must not
+/// share an edge. The thickness must be less than half of the smallest
+/// dimension of the solid, measured perpendicular to any of its faces.
+pub fn shell(
+    solid: Solid,
+    thickness: impl Into<Scalar>,
+    faces_to_remove: &FaceSelector,
+) -> Result<Solid, ShellError> {
+    let thickness = thickness.into();
+    if thickness <= Scalar::ZERO {
+        return Err(ShellError::InvalidThickness(thickness));
+    }
+
+    // Faces that are made of triangles have no vertices to recognize them by.
+    if let Some(index) =
+        solid.faces().position(|face| face.triangles().is_some())
+    {
+        return Err(ShellError::UnsupportedFace(index));
+    }
+
+    let faces: Vec<_> = solid
+        .clone()
+        .reoriented_outward()
+        .into_faces()
+        .into_iter()
+        .collect();
+
+    // Reorienting the faces might reverse them, so the faces that are selected
+    // are recognized by their vertices.
+    let index_of: BTreeMap<_, _> = faces
+        .iter()
+        .enumerate()
+        .map(|(index, face)| (face_vertices(face), index))
+        .collect();
+    let mut is_removed = vec![false; faces.len()];
+    for (index, face) in solid.faces().enumerate() {
+        let reoriented = index_of[&face_vertices(face)];
+        if faces_to_remove.selects(index, &faces[reoriented]) {
+            is_removed[reoriented] = true;
+        }
+    }
+
+    let planar_faces = faces
+        .iter()
+        .enumerate()
+        .map(|(index, face)| match PlanarFace::from_face(face) {
+            Some(planar_face) if planar_face.interiors.is_empty() => {
+                Ok(planar_face)
+            }
+            _ => Err(ShellError::UnsupportedFace(index)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (index, planar_face) in planar_faces.iter().enumerate() {
+        if !is_removed[index] {
+            continue;
+        }
+
+        let n = planar_face.exterior.len();
+        for i in 0..n {
+            let edge =
+                [planar_face.exterior[i], planar_face.exterior[(i + 1) % n]];
+            let is_shared =
+                planar_faces.iter().enumerate().any(|(other, face)| {
+                    other != index
+                        && is_removed[other]
+                        && face.has_vertex(edge[0])
+                        && face.has_vertex(edge[1])
+                });
+            if is_shared {
+                return Err(ShellError::AdjacentRemovedFaces(edge));
+            }
+        }
+    }
+
+    let vertices: BTreeSet<_> = planar_faces
+        .iter()
+        .flat_map(|face| face.exterior.iter().copied())
+        .collect();
+
+    let dimension = smallest_dimension(&planar_faces, &vertices);
+    if thickness * 2. >= dimension {
+        return Err(ShellError::ThicknessTooLarge {
+            thickness,
+            dimension,
+        });
+    }
+
+    // Each vertex moves to where the offset planes of its faces meet. The
+    // planes of removed faces stay where they are, so the inner walls reach up
+    // to the opening.
+    let mut inner_vertices = BTreeMap::new();
+    for &vertex in &vertices {
+        let offsets: Vec<_> = planar_faces
+            .iter()
+            .enumerate()
+            .filter(|(_, face)| face.has_vertex(vertex))
+            .map(|(index, face)| {
+                let offset = if is_removed[index] {
+                    Scalar::ZERO
+                } else {
+                    thickness
+                };
+                (face.normal, offset)
+            })
+            .collect();
+
+        let offset = match offsets.as_slice() {
+            &[a, b, c] => offset_of_planes([a, b, c]),
+            _ => None,
+        };
+        let offset = offset.ok_or(ShellError::UnsupportedVertex(vertex))?;
+
+        inner_vertices.insert(vertex, vertex + offset);
+    }
+
+    let mut result: Vec<_> = faces
+        .iter()
+        .zip(&is_removed)
+        .filter(|(_, &is_removed)| !is_removed)
+        .map(|(face, _)| face.clone())
+        .collect();
+    for ((index, face), planar_face) in
+        faces.iter().enumerate().zip(&planar_faces)
+    {
+        if is_removed[index] {
+            let n = planar_face.exterior.len();
+            for i in 0..n {
+                let [a, b] = [
+                    planar_face.exterior[i],
+                    planar_face.exterior[(i + 1) % n],
+                ];
+                let points = vec![a, b, inner_vertices[&b], inner_vertices[&a]];
+                result.push(polygon_face(
+                    points,
+                    planar_face.normal,
+                    face.color(),
+                ));
+            }
+        } else {
+            let points: Vec<_> = planar_face
+                .exterior
+                .iter()
+                .map(|vertex| inner_vertices[vertex])
+                .collect();
+
+            // If the walls are too thick for the face, its inner copy turns
+            // inside out.
+            if vector_area(&points).dot(&planar_face.normal) <= Scalar::ZERO {
+                return Err(ShellError::ThicknessTooLarge {
+                    thickness,
+                    dimension,
+                });
+            }
+
+            result.push(polygon_face(
+                points,
+                -planar_face.normal,
+                face.color(),
+            ));
+        }
+    }
+
+    Ok(Solid::from_faces(result))
+}
+
+/// Error hollowing out a solid
+#[derive(Debug, thiserror::Error)]
+pub enum ShellError {
+    /// The thickness is not positive
+    #[error("Shell thickness must be positive, but is {0}")]
+    InvalidThickness(Scalar),
+
+    /// A face is made of triangles, or not a planar polygon without holes
+    #[error("Can only shell solids with planar faces without holes: face {0}")]
+    UnsupportedFace(usize),
+
+    /// A vertex is not the corner of three faces
+    #[error("Can't shell at vertex {0:?}: Must be a corner of three faces")]
+    UnsupportedVertex(Point<3>),
+
+    /// Two removed faces share an edge
+    #[error("Can't remove two faces that share the edge {0:?}")]
+    AdjacentRemovedFaces([Point<3>; 2]),
+
+    /// The thickness is too large for the solid
+    #[error(
+        "Shell thickness {thickness} must be less than half of the smallest \
+        dimension of the solid ({dimension})"
+    )]
+    ThicknessTooLarge {
+        /// The thickness of the walls
+        thickness: Scalar,
+
+        /// The smallest dimension of the solid
+        dimension: Scalar,
+    },
+}
+
+/// The smallest extent of the solid, perpendicular to any of its faces
+fn smallest_dimension(
+    faces: &[PlanarFace],
+    vertices: &BTreeSet<Point<3>>,
+) -> Scalar {
+    faces
+        .iter()
+        .map(|face| {
+            let origin = face.exterior[0];
+            vertices
+                .iter()
+                .map(|&vertex| (origin - vertex).dot(&face.normal))
+                .max()
+                .unwrap_or(Scalar::ZERO)
+        })
+        .min()
+        .unwrap_or(Scalar::ZERO)
+}
+
+/// The offset from a vertex to where three planes through it meet, after each
+/// plane has been moved against its normal by the given distance
+///
+/// Returns `None`, if the planes don't meet in a single point.
+fn offset_of_planes(planes: [(Vector<3>, Scalar); 3]) -> Option<Vector<3>> {
+    let [(a, da), (b, db), (c, dc)] = planes;
+
+    let determinant = a.dot(&b.cross(&c));
+    if determinant.abs() < Scalar::from_f64(1e-9) {
+        return None;
+    }
+
+    let offset = b.cross(&c) * da + c.cross(&a) * db + a.cross(&b) * dc;
+    Some(-offset / determinant)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::{mesh::Mesh, physical_summary::PhysicalSummary};
+    use fj_math::{Point, Scalar, Triangle, Vector};
+
+    use crate::{
+        algorithms::{test_helpers::closed_mesh, FaceSelector, Tolerance},
+        objects::{Face, Solid},
+        validation::{check_shells, validate, ValidationConfig},
+    };
+
+    use super::{shell, ShellError};
+
+    #[test]
+    fn open_cube() -> anyhow::Result<()> {
+        let solid = Solid::cube_from_edge_length(10.);
+
+        let result = shell(solid, 1.5, &FaceSelector::normal([0., 0., 1.]))?;
+
+        // 5 outer walls, 5 inner walls, and the rim around the opening.
+        assert_eq!(result.faces().count(), 5 + 5 + 4);
+
+        let result = validate(result, &ValidationConfig::default())?;
+
+        let expected = 10. * 10. * 10. - 7. * 7. * 8.5;
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        let volume =
+            volume(closed_mesh(result.into_inner(), tolerance)?, tolerance);
+        assert!((volume - expected).abs() < 1e-9);
+
+        Ok(())
+    }
+
+    #[test]
+    fn closed_cube() -> anyhow::Result<()> {
+        let solid = Solid::cube_from_edge_length(10.);
+
+        let result = shell(solid, 1.5, &FaceSelector::Indices(Vec::new()))?;
+        assert_eq!(result.faces().count(), 6 + 6);
+
+        // The inner shell points into the cavity, as it should.
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        assert!(check_shells(&result, tolerance).is_empty());
+
+        let expected = 10. * 10. * 10. - 7. * 7. * 7.;
+        let volume = volume(closed_mesh(result, tolerance)?, tolerance);
+        assert!((volume - expected).abs() < 1e-9);
+
+        Ok(())
+    }
+
+    #[test]
+    fn inner_walls_are_parallel() -> anyhow::Result<()> {
+        let solid = Solid::cube_from_edge_length(10.);
+        let result = shell(solid, 1., &FaceSelector::normal([0., 0., 1.]))?;
+
+        let vertices: Vec<_> = result
+            .faces()
+            .flat_map(|face| face.all_cycles())
+            .flat_map(|cycle| &cycle.edges)
+            .filter_map(|edge| edge.vertex_positions())
+            .flatten()
+            .collect();
+        let has_vertex = |expected: [f64; 3]| {
+            vertices.iter().any(|&vertex| {
+                (vertex - Point::from(expected)).magnitude()
+                    < Scalar::from_f64(1e-12)
+            })
+        };
+
+        // The bottom is offset, the top stays where it is.
+        assert!(has_vertex([-4., -4., -4.]));
+        assert!(has_vertex([-4., -4., 5.]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn thickness_too_large() {
+        let solid = Solid::cube_from_edge_length(10.);
+
+        assert!(matches!(
+            shell(solid, 5., &FaceSelector::normal([0., 0., 1.])),
+            Err(ShellError::ThicknessTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn adjacent_removed_faces() {
+        let solid = Solid::cube_from_edge_length(10.);
+        let top_and_right = FaceSelector::Normal {
+            direction: Vector::from([1., 0., 1.]),
+            max_angle: Scalar::PI / 4. + Scalar::from_f64(1e-6),
+        };
+
+        assert!(matches!(
+            shell(solid, 1., &top_and_right),
+            Err(ShellError::AdjacentRemovedFaces(_))
+        ));
+    }
+
+    #[test]
+    fn triangle_face() {
+        let triangle = Triangle::from_points(
+            [[0., 0., 0.], [0., 1., 0.], [1., 0., 0.]].map(Point::from),
+        );
+        let solid = Solid::from_faces([Face::from_triangles(vec![(
+            triangle,
+            [255, 0, 0, 255],
+        )])]);
+
+        assert!(matches!(
+            shell(solid, 0.1, &FaceSelector::normal([0., 0., 1.])),
+            Err(ShellError::UnsupportedFace(0))
+        ));
+    }
+
+    fn volume(mesh: Mesh<Point<3>>, tolerance: Tolerance) -> f64 {
+        PhysicalSummary::from_mesh(&mesh, tolerance.inner())
+            .volume
+            .into_f64()
+    }
+}
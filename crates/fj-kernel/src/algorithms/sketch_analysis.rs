@@ -0,0 +1,653 @@
+use std::fmt;
+
+use fj_math::{Point, Scalar, Vector};
+
+use crate::objects::{Curve, Cycle, Edge, Sketch};
+
+use super::{CycleApprox, Tolerance};
+
+/// Find features of a sketch that are likely to cause trouble later on
+///
+/// Nothing that is reported here makes a sketch invalid. But corners that are
+/// almost tangent, tiny edges, and nearly touching edges lead to needle
+/// triangles, and to offsets and fillets that can't be computed. The
+/// [`SketchReport`] lists all of those features, with their positions in the
+/// surface of their face:
+///
+/// - Corners at which edges meet at an angle below
+///   [`SketchAnalysisThresholds::min_corner_angle`]. This includes corners that
+///   point into the face, like the end of a narrow slit.
+/// - Edges whose vertices are closer together than
+///   [`SketchAnalysisThresholds::min_edge_length`].
+/// - Straight edges of the same cycle that are parallel, overlap, and are
+///   closer together than [`SketchAnalysisThresholds::min_edge_distance`].
+///   Adjacent edges are not considered, as their corner is reported instead.
+/// - Interior cycles that come closer to the exterior cycle than
+///   [`SketchAnalysisThresholds::min_wall_thickness`].
+pub fn analyze_sketch(
+    sketch: &Sketch,
+    thresholds: &SketchAnalysisThresholds,
+) -> SketchReport {
+    let mut findings = Vec::new();
+
+    for (face_index, face) in sketch.faces().enumerate() {
+        let mut push = |kind, position, threshold| {
+            findings.push(SketchFinding::new(
+                face_index, kind, position, threshold,
+            ));
+        };
+
+        for cycle in face.all_cycles() {
+            let edges = cycle_edges(cycle);
+
+            for (i, edge) in edges.iter().enumerate() {
+                let length = (edge.points[1] - edge.points[0]).magnitude();
+                if length < thresholds.min_edge_length {
+                    let position = mid_point(edge.points[0], edge.points[1]);
+                    push(
+                        SketchFindingKind::ShortEdge { length },
+                        position,
+                        thresholds.min_edge_length,
+                    );
+                }
+
+                let next = &edges[(i + 1) % edges.len()];
+                let angle = angle_between(-edge.tangents[1], next.tangents[0]);
+                if edges.len() > 1 && angle < thresholds.min_corner_angle {
+                    push(
+                        SketchFindingKind::SharpCorner { angle },
+                        edge.points[1],
+                        thresholds.min_corner_angle,
+                    );
+                }
+            }
+
+            for (i, a) in edges.iter().enumerate() {
+                for (j, b) in edges.iter().enumerate().skip(i + 2) {
+                    let is_adjacent = i == 0 && j == edges.len() - 1;
+                    if is_adjacent || !a.is_straight || !b.is_straight {
+                        continue;
+                    }
+
+                    if let Some((distance, position)) =
+                        parallel_distance(a, b, thresholds.min_corner_angle)
+                    {
+                        if distance < thresholds.min_edge_distance {
+                            push(
+                                SketchFindingKind::ParallelEdges { distance },
+                                position,
+                                thresholds.min_edge_distance,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        // Curved edges are approximated well within the wall thickness, so the
+        // approximation doesn't affect which walls are found.
+        let tolerance =
+            match Tolerance::from_scalar(thresholds.min_wall_thickness / 10.) {
+                Ok(tolerance) => tolerance,
+                Err(_) => continue,
+            };
+        let polyline = |cycle: &Cycle| {
+            CycleApprox::new(cycle, tolerance)
+                .points
+                .into_iter()
+                .map(|point| *point.local_form())
+                .collect::<Vec<_>>()
+        };
+
+        let exteriors: Vec<_> = face.exteriors().map(polyline).collect();
+        for (interior, cycle) in face.interiors().enumerate() {
+            let interior_points = polyline(cycle);
+
+            let closest = exteriors
+                .iter()
+                .filter_map(|exterior| {
+                    polyline_distance(&interior_points, exterior)
+                })
+                .min_by_key(|&(thickness, _)| thickness);
+
+            if let Some((thickness, position)) = closest {
+                if thickness < thresholds.min_wall_thickness {
+                    push(
+                        SketchFindingKind::ThinWall {
+                            interior,
+                            thickness,
+                        },
+                        position,
+                        thresholds.min_wall_thickness,
+                    );
+                }
+            }
+        }
+    }
+
+    SketchReport { findings }
+}
+
+/// The thresholds below which [`analyze_sketch`] reports features
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SketchAnalysisThresholds {
+    /// The smallest angle at which edges may meet, in radians
+    ///
+    /// Also used as the largest angle between edges that are considered
+    /// parallel.
+    pub min_corner_angle: Scalar,
+
+    /// The smallest distance between the vertices of an edge
+    pub min_edge_length: Scalar,
+
+    /// The smallest distance between parallel edges of the same cycle
+    pub min_edge_distance: Scalar,
+
+    /// The smallest distance between an interior and an exterior cycle
+    pub min_wall_thickness: Scalar,
+}
+
+impl SketchAnalysisThresholds {
+    /// Derive thresholds from the tolerance that the sketch is processed with
+    ///
+    /// Corners below 1° are reported, as are distances below 10 times the
+    /// tolerance.
+    pub fn from_tolerance(tolerance: Tolerance) -> Self {
+        let min_distance = tolerance.inner() * 10.;
+
+        Self {
+            min_corner_angle: Scalar::PI / 180.,
+            min_edge_length: min_distance,
+            min_edge_distance: min_distance,
+            min_wall_thickness: min_distance,
+        }
+    }
+}
+
+/// The result of [`analyze_sketch`]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SketchReport {
+    /// The features that were found
+    pub findings: Vec<SketchFinding>,
+}
+
+impl SketchReport {
+    /// Indicate whether no features were found
+    pub fn is_empty(&self) -> bool {
+        self.findings.is_empty()
+    }
+}
+
+/// A feature of a sketch that is likely to cause trouble
+///
+/// Returned by [`analyze_sketch`], as part of [`SketchReport`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SketchFinding {
+    /// The index of the face, in the order of [`Sketch::faces`]
+    pub face: usize,
+
+    /// What was found
+    pub kind: SketchFindingKind,
+
+    /// Where it was found, in surface coordinates of the face
+    pub position: Point<2>,
+
+    /// How severe it is
+    pub severity: FindingSeverity,
+}
+
+impl SketchFinding {
+    fn new(
+        face: usize,
+        kind: SketchFindingKind,
+        position: Point<2>,
+        threshold: Scalar,
+    ) -> Self {
+        let severity = if kind.value() < threshold / 10. {
+            FindingSeverity::Severe
+        } else {
+            FindingSeverity::Warning
+        };
+
+        Self {
+            face,
+            kind,
+            position,
+            severity,
+        }
+    }
+}
+
+impl fmt::Display for SketchFinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let position = format!(
+            "({}, {})",
+            round(self.position.u, 3),
+            round(self.position.v, 3)
+        );
+
+        match self.kind {
+            SketchFindingKind::SharpCorner { angle } => write!(
+                f,
+                "corner of {}° at {position}; consider filleting or merging \
+                points",
+                round(Scalar::from_f64(angle.into_f64().to_degrees()), 1)
+            ),
+            SketchFindingKind::ShortEdge { length } => write!(
+                f,
+                "edge of length {} at {position}; consider merging its points",
+                round(length, 6)
+            ),
+            SketchFindingKind::ParallelEdges { distance } => write!(
+                f,
+                "parallel edges {} apart at {position}; consider merging them \
+                or moving them apart",
+                round(distance, 6)
+            ),
+            SketchFindingKind::ThinWall {
+                interior,
+                thickness,
+            } => write!(
+                f,
+                "wall of thickness {} between interior cycle {interior} and \
+                the exterior at {position}; consider moving them apart",
+                round(thickness, 6)
+            ),
+        }
+    }
+}
+
+/// The kind of a [`SketchFinding`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SketchFindingKind {
+    /// Two edges meet at a sharp angle
+    SharpCorner {
+        /// The angle between the edges, in radians
+        angle: Scalar,
+    },
+
+    /// An edge is very short
+    ShortEdge {
+        /// The distance between the vertices of the edge
+        length: Scalar,
+    },
+
+    /// Two parallel edges of the same cycle are very close
+    ParallelEdges {
+        /// The distance between the edges
+        distance: Scalar,
+    },
+
+    /// An interior cycle comes very close to the exterior cycle
+    ThinWall {
+        /// The index of the interior cycle within the face
+        interior: usize,
+
+        /// The smallest distance between the cycles
+        thickness: Scalar,
+    },
+}
+
+impl SketchFindingKind {
+    fn value(&self) -> Scalar {
+        match *self {
+            Self::SharpCorner { angle } => angle,
+            Self::ShortEdge { length } => length,
+            Self::ParallelEdges { distance } => distance,
+            Self::ThinWall { thickness, .. } => thickness,
+        }
+    }
+}
+
+/// The severity of a [`SketchFinding`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum FindingSeverity {
+    /// The feature is below its threshold
+    Warning,
+
+    /// The feature is below a tenth of its threshold
+    ///
+    /// It is almost degenerate, and will very likely cause trouble.
+    Severe,
+}
+
+/// An edge with vertices, in the direction of its cycle
+#[derive(Clone, Copy)]
+struct CycleEdge {
+    points: [Point<2>; 2],
+    tangents: [Vector<2>; 2],
+    is_straight: bool,
+}
+
+fn cycle_edges(cycle: &Cycle) -> Vec<CycleEdge> {
+    let edges: Vec<_> = cycle.edges.iter().filter_map(cycle_edge).collect();
+
+    // Edges don't necessarily point in the direction of the cycle. Each one is
+    // reversed, if its start is the vertex it shares with the next edge.
+    let mut oriented = Vec::new();
+    for (i, edge) in edges.iter().enumerate() {
+        let next = &edges[(i + 1) % edges.len()];
+        let distance_to_next = |point: Point<2>| {
+            next.points
+                .iter()
+                .map(|&other| (other - point).magnitude())
+                .min()
+                .expect("Edge has two points")
+        };
+
+        let [a, b] = edge.points;
+        let [ta, tb] = edge.tangents;
+        if distance_to_next(a) < distance_to_next(b) {
+            oriented.push(CycleEdge {
+                points: [b, a],
+                tangents: [-tb, -ta],
+                is_straight: edge.is_straight,
+            });
+        } else {
+            oriented.push(*edge);
+        }
+    }
+
+    oriented
+}
+
+fn cycle_edge(edge: &Edge) -> Option<CycleEdge> {
+    let curve = edge.curve().local_form();
+    let [a, b] = edge.vertices().get()?.map(|vertex| vertex.position());
+
+    Some(CycleEdge {
+        points: [a, b].map(|t| curve.point_from_curve_coords(t)),
+        tangents: [curve.tangent_at(a), curve.tangent_at(b)],
+        is_straight: matches!(curve, Curve::Line(_)),
+    })
+}
+
+/// The angle between two vectors, between 0 and π
+fn angle_between(a: Vector<2>, b: Vector<2>) -> Scalar {
+    cross(a, b).abs().atan2(a.dot(&b))
+}
+
+/// The distance between two straight edges, if they are parallel and overlap
+///
+/// Returns the distance in the middle of the overlap, and the point between
+/// the edges there.
+fn parallel_distance(
+    a: &CycleEdge,
+    b: &CycleEdge,
+    max_angle: Scalar,
+) -> Option<(Scalar, Point<2>)> {
+    let [a0, a1] = a.points;
+    let [b0, b1] = b.points;
+
+    let direction_a = a1 - a0;
+    let direction_b = b1 - b0;
+    let length_a = direction_a.magnitude();
+    if length_a == Scalar::ZERO || direction_b.magnitude() == Scalar::ZERO {
+        return None;
+    }
+
+    // The edges are parallel, regardless of which way they point.
+    let angle = angle_between(direction_a, direction_b);
+    if angle.min(Scalar::PI - angle) >= max_angle {
+        return None;
+    }
+
+    let direction_a = direction_a.normalize();
+    let [s0, s1] = [b0, b1].map(|point| (point - a0).dot(&direction_a));
+    let start = s0.min(s1).max(Scalar::ZERO);
+    let end = s0.max(s1).min(length_a);
+    if end <= start {
+        return None;
+    }
+
+    let on_a = a0 + direction_a * ((start + end) / 2.);
+    let on_b = closest_point_on_segment(on_a, [b0, b1]);
+
+    Some(((on_b - on_a).magnitude(), mid_point(on_a, on_b)))
+}
+
+/// The smallest distance between two closed polylines
+///
+/// Returns the distance, and the point between the polylines where it occurs.
+fn polyline_distance(
+    a: &[Point<2>],
+    b: &[Point<2>],
+) -> Option<(Scalar, Point<2>)> {
+    let mut closest = None;
+
+    for segment_a in a.windows(2) {
+        for segment_b in b.windows(2) {
+            let (distance, position) = segment_distance(
+                [segment_a[0], segment_a[1]],
+                [segment_b[0], segment_b[1]],
+            );
+
+            match closest {
+                Some((min, _)) if min <= distance => {}
+                _ => closest = Some((distance, position)),
+            }
+        }
+    }
+
+    closest
+}
+
+fn segment_distance(a: [Point<2>; 2], b: [Point<2>; 2]) -> (Scalar, Point<2>) {
+    if let Some(intersection) = segment_intersection(a, b) {
+        return (Scalar::ZERO, intersection);
+    }
+
+    // Segments that don't intersect are closest at one of their endpoints.
+    let candidates = a
+        .iter()
+        .map(|&point| (point, closest_point_on_segment(point, b)))
+        .chain(
+            b.iter()
+                .map(|&point| (point, closest_point_on_segment(point, a))),
+        );
+
+    candidates
+        .map(|(p, q)| ((q - p).magnitude(), mid_point(p, q)))
+        .min_by_key(|&(distance, _)| distance)
+        .expect("Segments have endpoints")
+}
+
+fn segment_intersection(
+    [a0, a1]: [Point<2>; 2],
+    [b0, b1]: [Point<2>; 2],
+) -> Option<Point<2>> {
+    let direction_a = a1 - a0;
+    let direction_b = b1 - b0;
+
+    let denominator = cross(direction_a, direction_b);
+    if denominator == Scalar::ZERO {
+        return None;
+    }
+
+    let s = cross(b0 - a0, direction_b) / denominator;
+    let t = cross(b0 - a0, direction_a) / denominator;
+    let range = Scalar::ZERO..=Scalar::ONE;
+    if !range.contains(&s) || !range.contains(&t) {
+        return None;
+    }
+
+    Some(a0 + direction_a * s)
+}
+
+fn closest_point_on_segment(
+    point: Point<2>,
+    [a, b]: [Point<2>; 2],
+) -> Point<2> {
+    let direction = b - a;
+    let length_squared = direction.dot(&direction);
+    if length_squared == Scalar::ZERO {
+        return a;
+    }
+
+    let t = (point - a).dot(&direction) / length_squared;
+    a + direction * t.max(Scalar::ZERO).min(Scalar::ONE)
+}
+
+fn cross(a: Vector<2>, b: Vector<2>) -> Scalar {
+    a.u * b.v - a.v * b.u
+}
+
+fn mid_point(a: Point<2>, b: Point<2>) -> Point<2> {
+    a + (b - a) / 2.
+}
+
+/// Round for display, without adding trailing zeros
+fn round(value: Scalar, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+
+    // Adding zero turns negative zero into positive zero.
+    (value.into_f64() * factor).round() / factor + 0.
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::objects::{Face, Sketch, Surface};
+
+    use super::{
+        analyze_sketch, FindingSeverity, SketchAnalysisThresholds,
+        SketchFinding, SketchFindingKind,
+    };
+
+    #[test]
+    fn clean_rectangle() {
+        let sketch = sketch([[0., 0.], [10., 0.], [10., 5.], [0., 5.]], []);
+        assert!(analyze_sketch(&sketch, &thresholds()).is_empty());
+    }
+
+    #[test]
+    fn sharp_corner() {
+        let sketch = sketch([[0., 0.], [10., 0.], [10., 0.05]], []);
+        let report = analyze_sketch(&sketch, &thresholds());
+
+        let [finding]: [SketchFinding; 1] = report.findings.try_into().unwrap();
+        let angle = match finding.kind {
+            SketchFindingKind::SharpCorner { angle } => angle,
+            kind => panic!("Unexpected finding: {kind:?}"),
+        };
+        assert_close(angle, 0.005_f64.atan());
+        assert_eq!(finding.position, Point::from([0., 0.]));
+        assert_eq!(finding.severity, FindingSeverity::Warning);
+        assert_eq!(
+            finding.to_string(),
+            "corner of 0.3° at (0, 0); consider filleting or merging points"
+        );
+    }
+
+    #[test]
+    fn short_edge() {
+        let sketch = sketch(
+            [[0., 0.], [10., 0.], [10., 9.998], [9.998, 10.], [0., 10.]],
+            [],
+        );
+        let report = analyze_sketch(&sketch, &thresholds());
+
+        let [finding]: [SketchFinding; 1] = report.findings.try_into().unwrap();
+        let length = match finding.kind {
+            SketchFindingKind::ShortEdge { length } => length,
+            kind => panic!("Unexpected finding: {kind:?}"),
+        };
+        assert_close(length, 0.002 * 2_f64.sqrt());
+        assert_close_point(finding.position, [9.999, 9.999]);
+        assert_eq!(finding.severity, FindingSeverity::Warning);
+    }
+
+    #[test]
+    fn parallel_edges() {
+        // A square with a narrow slot, cut in from the top.
+        let sketch = sketch(
+            [
+                [0., 0.],
+                [10., 0.],
+                [10., 10.],
+                [5.001, 10.],
+                [5.001, 2.],
+                [4.999, 2.],
+                [4.999, 10.],
+                [0., 10.],
+            ],
+            [],
+        );
+        let report = analyze_sketch(&sketch, &thresholds());
+
+        // The bottom of the slot is a short edge, which is not checked here.
+        let findings: Vec<_> = report
+            .findings
+            .into_iter()
+            .filter(|finding| {
+                !matches!(finding.kind, SketchFindingKind::ShortEdge { .. })
+            })
+            .collect();
+
+        let [finding]: [SketchFinding; 1] = findings.try_into().unwrap();
+        let distance = match finding.kind {
+            SketchFindingKind::ParallelEdges { distance } => distance,
+            kind => panic!("Unexpected finding: {kind:?}"),
+        };
+        assert_close(distance, 0.002);
+        assert_close_point(finding.position, [5., 6.]);
+    }
+
+    #[test]
+    fn thin_wall() {
+        let sketch = sketch(
+            [[0., 0.], [10., 0.], [10., 10.], [0., 10.]],
+            [[0.0005, 2.], [0.0005, 4.], [2., 4.], [2., 2.]],
+        );
+        let report = analyze_sketch(&sketch, &thresholds());
+
+        let [finding]: [SketchFinding; 1] = report.findings.try_into().unwrap();
+        let thickness = match finding.kind {
+            SketchFindingKind::ThinWall {
+                interior: 0,
+                thickness,
+            } => thickness,
+            kind => panic!("Unexpected finding: {kind:?}"),
+        };
+        assert_close(thickness, 0.0005);
+        assert_close(finding.position.u, 0.00025);
+        assert!(finding.position.v >= Scalar::from_f64(2.));
+        assert!(finding.position.v <= Scalar::from_f64(4.));
+        assert_eq!(finding.severity, FindingSeverity::Severe);
+    }
+
+    fn thresholds() -> SketchAnalysisThresholds {
+        SketchAnalysisThresholds {
+            min_corner_angle: Scalar::PI / 180.,
+            min_edge_length: Scalar::from_f64(0.01),
+            min_edge_distance: Scalar::from_f64(0.01),
+            min_wall_thickness: Scalar::from_f64(0.01),
+        }
+    }
+
+    fn sketch<const E: usize, const I: usize>(
+        exterior: [[f64; 2]; E],
+        interior: [[f64; 2]; I],
+    ) -> Sketch {
+        let mut builder =
+            Face::builder(Surface::xy_plane()).with_exterior_polygon(exterior);
+        if I > 0 {
+            builder = builder.with_interior_polygon(interior);
+        }
+
+        Sketch::from_faces([builder.build()])
+    }
+
+    fn assert_close(value: Scalar, expected: f64) {
+        assert!(
+            (value.into_f64() - expected).abs() < 1e-9,
+            "{value:?} is not {expected}"
+        );
+    }
+
+    fn assert_close_point(point: Point<2>, expected: [f64; 2]) {
+        assert_close(point.u, expected[0]);
+        assert_close(point.v, expected[1]);
+    }
+}
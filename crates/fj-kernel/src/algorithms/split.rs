@@ -0,0 +1,565 @@
+use std::collections::BTreeMap;
+
+use fj_interop::{
+    debug::DebugInfo,
+    mesh::{Color, Mesh},
+};
+use fj_math::{Point, Scalar, Triangle, Vector};
+
+use crate::objects::{Curve, Cycle, Edge, Face, Solid, Surface};
+
+use super::{
+    reverse_face,
+    triangulate::{triangulate_face, TriangulationConfig, TriangulationError},
+    ApproxStore, CycleApprox, Tolerance,
+};
+
+/// The minimum distance of a vertex from the plane, when splitting a solid
+///
+/// Vertices that are closer to the plane than this, can't be reliably
+/// assigned to either side of it.
+pub(super) const ON_PLANE_MAX_DISTANCE: f64 = 1e-9;
+
+/// Split a solid into two solids, along a plane
+///
+/// Returns the part of the solid below the plane, and the part above it, in
+/// that order. "Above" is the side that the plane's normal points to. If the
+/// solid doesn't intersect the plane, it is returned on its side, and the
+/// other part is an empty solid.
+///
+/// Faces that cross the plane are triangulated using `tolerance`, and their
+/// triangles are cut along the plane. Both parts get cap faces in the plane,
+/// which close them. Holes in the cross-section become interior cycles of the
+/// cap faces, which consist of a single polyline edge each.
+///
+/// # Implementation Note
+///
+/// Vertices that lie on the plane are not supported. If the solid crosses
+/// the plane and one of its vertices lies on it, [`SplitError::VertexOnPlane`]
+/// is returned. Moving the plane slightly avoids this.
+pub fn split_solid(
+    solid: Solid,
+    plane: &Surface,
+    tolerance: Tolerance,
+) -> Result<(Solid, Solid), SplitError> {
+    let plane = Plane::new(plane)?;
+
+    let mut is_below = false;
+    let mut is_above = false;
+    let mut is_on_plane = false;
+
+    for face in solid.faces() {
+        for point in face_points(face, tolerance) {
+            let distance = plane.distance(point);
+
+            if distance.abs() <= Scalar::from_f64(ON_PLANE_MAX_DISTANCE) {
+                is_on_plane = true;
+            } else if distance < Scalar::ZERO {
+                is_below = true;
+            } else {
+                is_above = true;
+            }
+        }
+    }
+
+    match (is_below, is_above) {
+        (false, _) => return Ok((Solid::from_faces([]), solid)),
+        (true, false) => return Ok((solid, Solid::from_faces([]))),
+        (true, true) => {}
+    }
+    if is_on_plane {
+        return Err(SplitError::VertexOnPlane);
+    }
+
+    let mut below = Vec::new();
+    let mut above = Vec::new();
+
+    // The sections of the cut faces. Each maps the start of a segment in the
+    // plane to its end, oriented as the boundary of the cap below the plane.
+    let mut section = BTreeMap::new();
+    let mut cap_color = None;
+
+    let mut approx_store = ApproxStore::new();
+
+    for face in solid.into_faces() {
+        let points = face_points(&face, tolerance);
+        if points
+            .iter()
+            .all(|&point| plane.distance(point) < Scalar::ZERO)
+        {
+            below.push(face);
+            continue;
+        }
+        if points
+            .iter()
+            .all(|&point| plane.distance(point) > Scalar::ZERO)
+        {
+            above.push(face);
+            continue;
+        }
+
+        let triangles = face_triangles(&face, tolerance, &mut approx_store)?;
+
+        let mut triangles_below = Vec::new();
+        let mut triangles_above = Vec::new();
+
+        for (triangle, color) in triangles {
+            let cut = cut_triangle(triangle.points(), &plane);
+
+            for (polygon, target) in [
+                (cut.below, &mut triangles_below),
+                (cut.above, &mut triangles_above),
+            ] {
+                // The pieces of a triangle are convex, so a fan is fine.
+                for i in 1..polygon.len().saturating_sub(1) {
+                    let triangle = Triangle::from_points([
+                        polygon[0],
+                        polygon[i],
+                        polygon[i + 1],
+                    ]);
+                    target.push((triangle, color));
+                }
+            }
+
+            if let Some([start, end]) = cut.segment {
+                section.insert(start, end);
+                cap_color.get_or_insert(color);
+            }
+        }
+
+        for (triangles, target) in
+            [(triangles_below, &mut below), (triangles_above, &mut above)]
+        {
+            if !triangles.is_empty() {
+                target.push(Face::from_triangles(triangles));
+            }
+        }
+    }
+
+    let caps =
+        cap_faces(section, &plane, cap_color.unwrap_or([255, 0, 0, 255]))?;
+    for cap in caps {
+        above.push(reverse_face(&cap));
+        below.push(cap);
+    }
+
+    Ok((Solid::from_faces(below), Solid::from_faces(above)))
+}
+
+/// Error splitting a solid
+#[derive(Debug, thiserror::Error)]
+pub enum SplitError {
+    /// The surface that the solid is split along is not a plane
+    #[error("Solids can only be split along planes")]
+    NotAPlane,
+
+    /// A vertex of the solid lies on the plane
+    #[error(
+        "Can't split solid along a plane that contains one of its vertices"
+    )]
+    VertexOnPlane,
+
+    /// The cross-section of the solid is not made up of closed loops
+    ///
+    /// This happens, if the solid isn't closed.
+    #[error("Cross-section of the solid is not closed; is the solid closed?")]
+    OpenSection,
+
+    /// A face that crosses the plane could not be triangulated
+    #[error("Failed to triangulate face that crosses the plane")]
+    Triangulation(#[from] TriangulationError),
+}
+
+/// A plane, with an orthonormal coordinate system
+pub(super) struct Plane {
+    pub(super) surface: Surface,
+    pub(super) origin: Point<3>,
+    pub(super) u: Vector<3>,
+    pub(super) v: Vector<3>,
+    pub(super) normal: Vector<3>,
+}
+
+impl Plane {
+    pub(super) fn new(surface: &Surface) -> Result<Self, SplitError> {
+        let Surface::SweptCurve(swept) = surface;
+        if !matches!(swept.curve, Curve::Line(_)) {
+            return Err(SplitError::NotAPlane);
+        }
+
+        let surface = surface.normalized();
+        let origin = surface.point_from_surface_coords([0., 0.]);
+        let u = surface.vector_from_surface_coords([1., 0.]);
+        let v = surface.vector_from_surface_coords([0., 1.]);
+
+        Ok(Self {
+            surface,
+            origin,
+            u,
+            v,
+            normal: u.cross(&v).normalize(),
+        })
+    }
+
+    pub(super) fn distance(&self, point: Point<3>) -> Scalar {
+        (point - self.origin).dot(&self.normal)
+    }
+
+    pub(super) fn surface_coords(&self, point: Point<3>) -> Point<2> {
+        let offset = point - self.origin;
+        Point::from([offset.dot(&self.u), offset.dot(&self.v)])
+    }
+}
+
+/// The points that determine on which side of a plane a face is
+pub(super) fn face_points(face: &Face, tolerance: Tolerance) -> Vec<Point<3>> {
+    if let Some(triangles) = face.triangles() {
+        return triangles
+            .iter()
+            .flat_map(|(triangle, _)| triangle.points())
+            .collect();
+    }
+
+    face.all_cycles()
+        .flat_map(|cycle| CycleApprox::new(cycle, tolerance).points)
+        .map(|point| *point.global_form())
+        .collect()
+}
+
+/// The triangles of a face, triangulating it, if it doesn't consist of them
+pub(super) fn face_triangles(
+    face: &Face,
+    tolerance: Tolerance,
+    approx_store: &mut ApproxStore,
+) -> Result<Vec<(Triangle<3>, Color)>, TriangulationError> {
+    if let Some(triangles) = face.triangles() {
+        return Ok(triangles.clone());
+    }
+
+    let mut mesh = Mesh::new();
+    triangulate_face(
+        face,
+        0,
+        tolerance,
+        approx_store,
+        &mut DebugInfo::new(),
+        &TriangulationConfig::default(),
+        &mut mesh,
+    )?;
+
+    let triangles = mesh
+        .triangles()
+        .map(|triangle| {
+            (Triangle::from_points(triangle.points), triangle.color)
+        })
+        .collect();
+
+    Ok(triangles)
+}
+
+/// A triangle, cut along a plane
+pub(super) struct CutTriangle {
+    /// The polygon below the plane
+    pub(super) below: Vec<Point<3>>,
+
+    /// The polygon above the plane
+    above: Vec<Point<3>>,
+
+    /// The segment along the plane, oriented as the boundary of the cap below
+    pub(super) segment: Option<[Point<3>; 2]>,
+}
+
+pub(super) fn cut_triangle(
+    points: [Point<3>; 3],
+    plane: &Plane,
+) -> CutTriangle {
+    let mut cut = CutTriangle {
+        below: Vec::new(),
+        above: Vec::new(),
+        segment: None,
+    };
+
+    let mut entry = None;
+    let mut exit = None;
+
+    for i in 0..3 {
+        let [a, b] = [points[i], points[(i + 1) % 3]];
+        let [distance_a, distance_b] =
+            [a, b].map(|point| plane.distance(point));
+
+        if distance_a < Scalar::ZERO {
+            cut.below.push(a);
+        } else {
+            cut.above.push(a);
+        }
+
+        if (distance_a < Scalar::ZERO) != (distance_b < Scalar::ZERO) {
+            let point = edge_plane_intersection(a, b, plane);
+
+            cut.below.push(point);
+            cut.above.push(point);
+
+            if distance_a < Scalar::ZERO {
+                exit = Some(point);
+            } else {
+                entry = Some(point);
+            }
+        }
+    }
+
+    // The polygon below the plane runs from the exit point to the entry point
+    // along the plane. The cap needs to run the opposite way.
+    if let (Some(entry), Some(exit)) = (entry, exit) {
+        cut.segment = Some([entry, exit]);
+    }
+
+    cut
+}
+
+/// Compute the intersection of an edge with a plane
+///
+/// The result only depends on the edge, not on its direction. Triangles that
+/// share the edge get the exact same point.
+fn edge_plane_intersection(
+    a: Point<3>,
+    b: Point<3>,
+    plane: &Plane,
+) -> Point<3> {
+    let [a, b] = if a <= b { [a, b] } else { [b, a] };
+    let [distance_a, distance_b] = [a, b].map(|point| plane.distance(point));
+
+    a + (b - a) * (distance_a / (distance_a - distance_b))
+}
+
+/// Build the cap faces below the plane from the segments of the cross-section
+///
+/// The cap faces above the plane are their reversed versions.
+pub(super) fn cap_faces(
+    mut section: BTreeMap<Point<3>, Point<3>>,
+    plane: &Plane,
+    color: Color,
+) -> Result<Vec<Face>, SplitError> {
+    let mut loops = Vec::new();
+
+    while let Some((&start, _)) = section.iter().next() {
+        let mut points = vec![plane.surface_coords(start)];
+
+        let mut current = start;
+        loop {
+            let next =
+                section.remove(&current).ok_or(SplitError::OpenSection)?;
+            points.push(plane.surface_coords(next));
+
+            if next == start {
+                break;
+            }
+            current = next;
+        }
+
+        loops.push(points);
+    }
+
+    // The caps below the plane face along its normal. Their exterior cycles
+    // are counter-clockwise in the plane's coordinates; holes are clockwise.
+    let (exteriors, interiors): (Vec<_>, Vec<_>) = loops
+        .into_iter()
+        .partition(|points| signed_area(points) > Scalar::ZERO);
+
+    let mut holes: Vec<Vec<Cycle>> = vec![Vec::new(); exteriors.len()];
+    for interior in interiors {
+        let index = exteriors
+            .iter()
+            .position(|exterior| contains(exterior, interior[0]))
+            .ok_or(SplitError::OpenSection)?;
+
+        holes[index].push(cycle(&plane.surface, interior));
+    }
+
+    let caps = exteriors
+        .into_iter()
+        .zip(holes)
+        .map(|(exterior, interiors)| {
+            Face::new(
+                plane.surface.clone(),
+                [cycle(&plane.surface, exterior)],
+                interiors,
+                color,
+            )
+        })
+        .collect();
+
+    Ok(caps)
+}
+
+/// Create a cycle from a closed polygon
+pub(super) fn cycle(surface: &Surface, points: Vec<Point<2>>) -> Cycle {
+    Cycle {
+        edges: vec![Edge::polyline_from_points(surface, points)],
+    }
+}
+
+/// Twice the signed area of a closed polygon
+fn signed_area(points: &[Point<2>]) -> Scalar {
+    points.windows(2).fold(Scalar::ZERO, |area, segment| {
+        let [a, b] = [segment[0], segment[1]];
+        area + (a.u * b.v - b.u * a.v)
+    })
+}
+
+/// Check whether a closed polygon contains a point, using the even-odd rule
+pub(super) fn contains(polygon: &[Point<2>], point: Point<2>) -> bool {
+    let mut inside = false;
+
+    for segment in polygon.windows(2) {
+        let [a, b] = [segment[0], segment[1]];
+
+        if (a.v > point.v) != (b.v > point.v) {
+            let u = a.u + (point.v - a.v) / (b.v - a.v) * (b.u - a.u);
+            if point.u < u {
+                inside = !inside;
+            }
+        }
+    }
+
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::{mesh::Mesh, physical_summary::PhysicalSummary};
+    use fj_math::{Aabb, Point, Scalar};
+
+    use crate::{
+        algorithms::{
+            reverse::reverse_cycle, sweep, test_helpers::closed_mesh,
+            CycleApprox, Tolerance, TransformObject,
+        },
+        iter::ObjectIters,
+        objects::{Cycle, Edge, Face, Sketch, Surface},
+    };
+
+    use super::split_solid;
+
+    #[test]
+    fn cube_off_center() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let surface = Surface::xy_plane();
+        let face = Face::new(
+            surface.clone(),
+            [Cycle::polygon_from_points(
+                &surface,
+                [[0., 0.], [1., 0.], [1., 1.], [0., 1.]],
+            )],
+            [],
+            [255, 0, 0, 255],
+        );
+        let cube = sweep(
+            Sketch::from_faces([face]),
+            [0., 0., 1.],
+            tolerance,
+            [255, 0, 0, 255],
+        )?;
+
+        let plane = Surface::xy_plane().translate([0., 0., 0.3]);
+        let (below, above) = split_solid(cube.clone(), &plane, tolerance)?;
+
+        let below = closed_mesh(below, tolerance)?;
+        let above = closed_mesh(above, tolerance)?;
+
+        assert_box(&below, [0., 0., 0.], [1., 1., 0.3]);
+        assert_box(&above, [0., 0., 0.3], [1., 1., 1.]);
+
+        let [below, above] = [below, above].map(|mesh| {
+            PhysicalSummary::from_mesh(&mesh, tolerance.inner()).volume
+        });
+        assert!(is_close(below, 0.3));
+        assert!(is_close(above, 0.7));
+
+        // A plane that misses the cube leaves it intact.
+        let plane = Surface::xy_plane().translate([0., 0., 2.]);
+        let (below, above) = split_solid(cube, &plane, tolerance)?;
+        assert_eq!(below.face_iter().count(), 6);
+        assert_eq!(above.face_iter().count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cube_with_hole() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.01)?;
+        let radius = 0.25;
+
+        let surface = Surface::xy_plane();
+        let face = Face::new(
+            surface.clone(),
+            [Cycle::polygon_from_points(
+                &surface,
+                [[-1., -1.], [1., -1.], [1., 1.], [-1., 1.]],
+            )],
+            [reverse_cycle(Cycle {
+                edges: vec![Edge::circle_from_radius(Scalar::from_f64(radius))],
+            })],
+            [255, 0, 0, 255],
+        );
+        let solid = sweep(
+            Sketch::from_faces([face]),
+            [0., 0., 2.],
+            tolerance,
+            [255, 0, 0, 255],
+        )?;
+
+        let plane = Surface::xy_plane().translate([0., 0., 0.7]);
+        let (below, above) = split_solid(solid.clone(), &plane, tolerance)?;
+
+        for half in [&below, &above] {
+            let caps: Vec<_> = half
+                .face_iter()
+                .filter(|face| face.triangles().is_none())
+                .filter(|face| {
+                    super::face_points(face, tolerance)
+                        .iter()
+                        .all(|point| is_close(point.z, 0.7))
+                })
+                .collect();
+            assert_eq!(caps.len(), 1);
+
+            let interiors: Vec<_> = caps[0].interiors().collect();
+            assert_eq!(interiors.len(), 1);
+
+            // The cut runs along the approximation of the hole, so its points
+            // are within the tolerance of the circle.
+            for point in CycleApprox::new(interiors[0], tolerance).points {
+                let point = point.global_form();
+                let distance = (point.x * point.x + point.y * point.y).sqrt();
+                let deviation = Scalar::from_f64(radius) - distance;
+
+                assert!(deviation > Scalar::from_f64(-1e-9));
+                assert!(deviation <= tolerance.inner());
+            }
+        }
+
+        let [below, above, solid] = [below, above, solid].map(|solid| {
+            let mesh = closed_mesh(solid, tolerance)
+                .expect("Failed to triangulate solid");
+            PhysicalSummary::from_mesh(&mesh, tolerance.inner()).volume
+        });
+        assert!(is_close(below + above, solid.into_f64()));
+
+        Ok(())
+    }
+
+    fn assert_box(mesh: &Mesh<Point<3>>, min: [f64; 3], max: [f64; 3]) {
+        let aabb = Aabb::<3>::from_points(mesh.vertices());
+        for (actual, expected) in [(aabb.min, min), (aabb.max, max)] {
+            for (actual, expected) in
+                actual.coords.components.into_iter().zip(expected)
+            {
+                assert!(is_close(actual, expected));
+            }
+        }
+    }
+
+    fn is_close(value: Scalar, expected: f64) -> bool {
+        (value.into_f64() - expected).abs() < 1e-9
+    }
+}
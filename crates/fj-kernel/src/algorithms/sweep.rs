@@ -1,31 +1,48 @@
-use fj_math::{Point, Scalar, Transform, Triangle, Vector};
+use fj_interop::{debug::DebugInfo, mesh::Mesh};
+use fj_math::{Line, Point, Scalar, Transform, Triangle, Vector};
 
 use crate::{
     iter::ObjectIters,
     local::Local,
     objects::{
-        Curve, Cycle, Edge, Face, GlobalVertex, Sketch, Solid, Surface, Vertex,
-        VerticesOfEdge,
+        Curve, Cycle, Edge, Face, GlobalVertex, Sketch, Solid, Surface,
+        SweptCurve, Vertex, VerticesOfEdge,
     },
+    validation::ValidationConfig,
 };
 
-use super::{reverse_face, CycleApprox, Tolerance, TransformObject};
+use super::{
+    reverse_face,
+    triangulate::{triangulate_face, TriangulationConfig, TriangulationError},
+    ApproxStore, CycleApprox, Handedness, Tolerance, TransformObject,
+};
 
 /// Create a solid by sweeping a sketch
+///
+/// The path must point out of the sketch plane. If it lies within the plane of
+/// any of the sketch's faces, [`SweepError::PathParallelToSketch`] is returned.
+///
+/// The path may point to either side of the sketch. If it points to the back
+/// side of a face, the orientation of all faces created from it is flipped, so
+/// the resulting solid still faces outward.
+///
+/// Full circles sweep out exact cylindrical faces. The side faces of other
+/// curved edges are approximated by triangles.
 pub fn sweep(
     source: Sketch,
     path: impl Into<Vector<3>>,
     tolerance: Tolerance,
     color: [u8; 4],
-) -> Solid {
+) -> Result<Solid, SweepError> {
     let path = path.into();
-
-    let is_sweep_along_negative_direction =
-        path.dot(&Vector::from([0., 0., 1.])) < Scalar::ZERO;
+    check_path(&source, path)?;
 
     let mut target = Vec::new();
 
     for face in source.face_iter() {
+        let is_sweep_along_negative_direction =
+            path.dot(&front_normal(face, tolerance)) < Scalar::ZERO;
+
         create_bottom_faces(
             face,
             is_sweep_along_negative_direction,
@@ -40,11 +57,34 @@ pub fn sweep(
 
         for cycle in face.all_cycles() {
             for edge in &cycle.edges {
+                // The side face of a polyline edge isn't flat, so it is created
+                // from its approximation, like the side face of a continuous
+                // edge.
+                let is_polyline =
+                    matches!(edge.curve().global_form(), Curve::Polyline(_));
+
                 if let Some(vertices) = edge.vertices().get() {
-                    create_non_continuous_side_face(
+                    if !is_polyline {
+                        create_non_continuous_side_face(
+                            path,
+                            is_sweep_along_negative_direction,
+                            vertices.map(|vertex| *vertex.global()),
+                            color,
+                            &mut target,
+                        );
+                        continue;
+                    }
+                }
+
+                // A circle sweeps out a cylinder, which can be represented
+                // exactly.
+                let is_circle =
+                    matches!(edge.curve().global_form(), Curve::Circle(_));
+                if is_circle && edge.vertices().get().is_none() {
+                    create_cylindrical_side_face(
+                        edge,
                         path,
                         is_sweep_along_negative_direction,
-                        vertices.map(|vertex| *vertex.global()),
                         color,
                         &mut target,
                     );
@@ -52,8 +92,9 @@ pub fn sweep(
                 }
 
                 create_continuous_side_face(
-                    *edge,
+                    edge.clone(),
                     path,
+                    is_sweep_along_negative_direction,
                     tolerance,
                     color,
                     &mut target,
@@ -62,7 +103,48 @@ pub fn sweep(
         }
     }
 
-    Solid::from_faces(target)
+    Ok(Solid::from_faces(target))
+}
+
+/// Check that the path of a sweep points out of the plane of each face
+///
+/// If the component of the path along the normal of a face is smaller than the
+/// minimum distance between distinct objects, the top and bottom of the swept
+/// solid are identical, and its side faces have no area.
+fn check_path(source: &Sketch, path: Vector<3>) -> Result<(), SweepError> {
+    let min_distance = ValidationConfig::default().distinct_min_distance;
+
+    for face in source.face_iter() {
+        let normal = sketch_normal(face);
+
+        let distance = path.dot(&normal);
+        if distance.abs() < min_distance {
+            let in_plane = (path - normal * distance).magnitude();
+            let angle = Scalar::atan2(distance.abs(), in_plane);
+
+            return Err(SweepError::PathParallelToSketch { angle });
+        }
+    }
+
+    Ok(())
+}
+
+/// The normal of the plane of a sketch face
+fn sketch_normal(face: &Face) -> Vector<3> {
+    face.surface().normal_at([0., 0.])
+}
+
+/// The outward normal of a sketch face, pointing to its front side
+///
+/// This is the opposite of the normal of its plane, if the face is left-handed
+/// (see [`Handedness`]).
+pub(super) fn front_normal(face: &Face, tolerance: Tolerance) -> Vector<3> {
+    let normal = sketch_normal(face);
+
+    match Handedness::of_face(face, tolerance) {
+        Some(handedness) => handedness.outward(normal),
+        None => normal,
+    }
 }
 
 fn create_bottom_faces(
@@ -164,9 +246,53 @@ fn create_non_continuous_side_face(
     target.push(face);
 }
 
+fn create_cylindrical_side_face(
+    edge: &Edge,
+    path: Vector<3>,
+    is_sweep_along_negative_direction: bool,
+    color: [u8; 4],
+    target: &mut Vec<Face>,
+) {
+    // The normal of the surface is the tangent of the curve, crossed with the
+    // path. Reversing the curve makes it point outward, if the sweep goes the
+    // other way.
+    let mut curve = edge.curve().global_form().clone();
+    if is_sweep_along_negative_direction {
+        curve = curve.reverse();
+    }
+
+    // The bottom rim runs along the u-axis, the top rim back against it, so
+    // the face points outward.
+    let rim = |v: f64, direction: [f64; 2], global: Curve<3>| {
+        let local = Curve::Line(Line {
+            origin: Point::from([0., v]),
+            direction: Vector::from(direction),
+        });
+        Cycle {
+            edges: vec![Edge::new(
+                Local::new(local, global),
+                VerticesOfEdge::none(),
+            )],
+        }
+    };
+    let bottom = rim(0., [1., 0.], curve.clone());
+    let top = rim(
+        1.,
+        [-1., 0.],
+        curve
+            .clone()
+            .transform(&Transform::translation(path))
+            .reverse(),
+    );
+
+    let surface = Surface::SweptCurve(SweptCurve { curve, path });
+    target.push(Face::new(surface, [bottom], [top], color));
+}
+
 fn create_continuous_side_face(
     edge: Edge,
     path: Vector<3>,
+    is_sweep_along_negative_direction: bool,
     tolerance: Tolerance,
     color: [u8; 4],
     target: &mut Vec<Face>,
@@ -184,7 +310,11 @@ fn create_continuous_side_face(
             segment.points()
         };
 
-        quads.push([v0, v1, v2, v3]);
+        if is_sweep_along_negative_direction {
+            quads.push([v1, v0, v3, v2]);
+        } else {
+            quads.push([v0, v1, v2, v3]);
+        }
     }
 
     let mut side_face: Vec<(Triangle<3>, _)> = Vec::new();
@@ -196,14 +326,291 @@ fn create_continuous_side_face(
     target.push(Face::from_triangles(side_face));
 }
 
+/// Create a solid by sweeping a sketch, while scaling it along the path
+///
+/// The sketch is scaled about its centroid, according to the scale profile.
+/// Wherever the slope of the profile changes, a station is placed along the
+/// path. The side walls between two stations are approximated by triangles, so
+/// unlike with [`sweep`], all faces of the resulting solid are made up of
+/// triangles.
+///
+/// If the scale is `1.` along the whole path, this is the same as [`sweep`].
+/// If the scale reaches zero at the end of the path, the side walls meet in an
+/// apex, and there is no top face.
+pub fn sweep_with_scale(
+    source: Sketch,
+    path: impl Into<Vector<3>>,
+    scale_profile: &ScaleProfile,
+    tolerance: Tolerance,
+    color: [u8; 4],
+) -> Result<Solid, SweepError> {
+    let path = path.into();
+    let stations = scale_profile.stations()?;
+
+    if stations.iter().all(|&(_, scale)| scale == Scalar::ONE) {
+        return sweep(source, path, tolerance, color);
+    }
+
+    check_path(&source, path)?;
+
+    let mut approx_store = ApproxStore::new();
+    let mut caps = Vec::new();
+    for (index, face) in source.face_iter().enumerate() {
+        let mut mesh = Mesh::new();
+        triangulate_face(
+            face,
+            index,
+            tolerance,
+            &mut approx_store,
+            &mut DebugInfo::new(),
+            &TriangulationConfig::default(),
+            &mut mesh,
+        )?;
+        caps.push(mesh);
+    }
+
+    let centroid = {
+        let mut area = Scalar::ZERO;
+        let mut moment = Vector::from([0., 0., 0.]);
+
+        for triangle in caps.iter().flat_map(|mesh| mesh.triangles()) {
+            let [a, b, c] = triangle.points;
+
+            let triangle_area = (b - a).cross(&(c - a)).magnitude() / 2.;
+            area += triangle_area;
+            moment =
+                moment + (a.coords + b.coords + c.coords) / 3. * triangle_area;
+        }
+
+        if area == Scalar::ZERO {
+            Point::origin()
+        } else {
+            Point {
+                coords: moment / area,
+            }
+        }
+    };
+
+    // Scale a point of the sketch and move it to the given station.
+    let at = |point: Point<3>, (t, scale): (Scalar, Scalar)| {
+        centroid + (point - centroid) * scale + path * t
+    };
+
+    let first = stations[0];
+    let last = stations[stations.len() - 1];
+
+    let mut target = Vec::new();
+
+    for (face, cap) in source.face_iter().zip(&caps) {
+        // The triangles of the cap face along the outward normal of the face.
+        let is_along_normal =
+            front_normal(face, tolerance).dot(&path) > Scalar::ZERO;
+
+        let bottom = cap.triangles().map(|triangle| {
+            let [a, b, c] = triangle.points.map(|point| at(point, first));
+            if is_along_normal {
+                [a, c, b]
+            } else {
+                [a, b, c]
+            }
+        });
+        push_triangles(bottom, face.color(), &mut target);
+
+        if last.1 > Scalar::ZERO {
+            let top = cap.triangles().map(|triangle| {
+                let [a, b, c] = triangle.points.map(|point| at(point, last));
+                if is_along_normal {
+                    [a, b, c]
+                } else {
+                    [a, c, b]
+                }
+            });
+            push_triangles(top, face.color(), &mut target);
+        }
+
+        for cycle in face.all_cycles() {
+            let approx = CycleApprox::new(cycle, tolerance);
+
+            let mut side_face = Vec::new();
+            for segment in approx.segments() {
+                let points = segment.points();
+
+                for stations in stations.windows(2) {
+                    let [v0, v1] = points.map(|point| at(point, stations[0]));
+                    let [v3, v2] = points.map(|point| at(point, stations[1]));
+
+                    let [v0, v1, v2, v3] = if is_along_normal {
+                        [v0, v1, v2, v3]
+                    } else {
+                        [v1, v0, v3, v2]
+                    };
+
+                    side_face.push([v0, v1, v2]);
+                    side_face.push([v0, v2, v3]);
+                }
+            }
+
+            push_triangles(side_face, color, &mut target);
+        }
+    }
+
+    Ok(Solid::from_faces(target))
+}
+
+fn push_triangles(
+    triangles: impl IntoIterator<Item = [Point<3>; 3]>,
+    color: [u8; 4],
+    target: &mut Vec<Face>,
+) {
+    let triangles: Vec<_> = triangles
+        .into_iter()
+        // Side walls that end in an apex have collapsed triangles.
+        .filter(|&[a, b, c]| a != b && b != c && c != a)
+        .map(|points| (Triangle::from_points(points), color))
+        .collect();
+
+    if !triangles.is_empty() {
+        target.push(Face::from_triangles(triangles));
+    }
+}
+
+/// The scale of a swept sketch along the path of the sweep
+///
+/// The scale is defined in terms of the normalized path length `t`, which is
+/// `0.` at the start of the path and `1.` at its end. See
+/// [`sweep_with_scale`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScaleProfile {
+    /// The same scale along the whole path
+    Constant(Scalar),
+
+    /// A scale that changes linearly from the start to the end of the path
+    Linear {
+        /// The scale at the start of the path
+        start: Scalar,
+
+        /// The scale at the end of the path
+        end: Scalar,
+    },
+
+    /// A scale that is interpolated linearly between `(t, scale)` pairs
+    ///
+    /// Before the first and after the last pair, the scale stays constant.
+    PiecewiseLinear(Vec<(Scalar, Scalar)>),
+}
+
+impl ScaleProfile {
+    /// Compute the stations of the profile
+    ///
+    /// Returns the `(t, scale)` pairs between which the scale changes linearly,
+    /// including the start and the end of the path.
+    ///
+    /// Scales must be positive. The only exception is the end of the path,
+    /// where a scale of zero results in an apex.
+    pub fn stations(&self) -> Result<Vec<(Scalar, Scalar)>, SweepError> {
+        let stations = match self {
+            Self::Constant(scale) => {
+                vec![(Scalar::ZERO, *scale), (Scalar::ONE, *scale)]
+            }
+            Self::Linear { start, end } => {
+                vec![(Scalar::ZERO, *start), (Scalar::ONE, *end)]
+            }
+            Self::PiecewiseLinear(pairs) => {
+                let (first, last) = match (pairs.first(), pairs.last()) {
+                    (Some(first), Some(last)) => (*first, *last),
+                    _ => return Err(SweepError::EmptyProfile),
+                };
+
+                let is_ordered =
+                    pairs.windows(2).all(|pair| pair[0].0 < pair[1].0);
+                if !is_ordered || first.0 < Scalar::ZERO || last.0 > Scalar::ONE
+                {
+                    return Err(SweepError::InvalidStations);
+                }
+
+                let mut stations = Vec::new();
+                if first.0 > Scalar::ZERO {
+                    stations.push((Scalar::ZERO, first.1));
+                }
+                stations.extend(pairs.iter().copied());
+                if last.0 < Scalar::ONE {
+                    stations.push((Scalar::ONE, last.1));
+                }
+
+                stations
+            }
+        };
+
+        for (i, &(t, scale)) in stations.iter().enumerate() {
+            let is_end = i == stations.len() - 1;
+
+            if scale < Scalar::ZERO || (scale == Scalar::ZERO && !is_end) {
+                return Err(SweepError::InvalidScale { t, scale });
+            }
+        }
+
+        Ok(stations)
+    }
+}
+
+impl Default for ScaleProfile {
+    fn default() -> Self {
+        Self::Constant(Scalar::ONE)
+    }
+}
+
+/// An error that can occur while sweeping
+#[derive(Debug, thiserror::Error)]
+pub enum SweepError {
+    /// The scale profile has no `(t, scale)` pairs
+    #[error("Scale profile is empty")]
+    EmptyProfile,
+
+    /// The `(t, scale)` pairs are not ordered, or not within the path
+    #[error("Scale profile must be ordered by `t`, with `t` within [0, 1]")]
+    InvalidStations,
+
+    /// The scale is not positive
+    ///
+    /// A scale of zero is only valid at the end of the path.
+    #[error("Invalid scale {scale} at t = {t}")]
+    InvalidScale {
+        /// The position along the path
+        t: Scalar,
+
+        /// The invalid scale
+        scale: Scalar,
+    },
+
+    /// The path of the sweep lies within the plane of the sketch
+    #[error("Sweep path is parallel to sketch (angle: {angle} rad)")]
+    PathParallelToSketch {
+        /// The angle between the path and the plane of the sketch, in radians
+        angle: Scalar,
+    },
+
+    /// The sketch could not be triangulated
+    #[error("Failed to triangulate sketch")]
+    Triangulation(#[from] TriangulationError),
+}
+
 #[cfg(test)]
 mod tests {
-    use fj_math::{Point, Scalar, Vector};
+    use std::collections::BTreeMap;
+
+    use approx::assert_abs_diff_eq;
+    use fj_interop::debug::DebugInfo;
+    use fj_math::{Ellipse, Point, Scalar, Vector};
+
+    use fj_interop::physical_summary::PhysicalSummary;
 
     use crate::{
-        algorithms::Tolerance,
+        algorithms::{
+            test_helpers::closed_mesh, triangulate, ApproxStore, ErrorBudget,
+            ScaleProfile, SweepError, Tolerance,
+        },
         iter::ObjectIters,
-        objects::{Face, Sketch, Surface},
+        objects::{Cycle, Edge, Face, Sketch, Surface},
     };
 
     #[test]
@@ -211,7 +618,7 @@ mod tests {
         test_bottom_top(
             [0., 0., 1.],
             [[0., 0., 0.], [1., 0., 0.], [0., -1., 0.]],
-            [[0., 0.], [1., 0.], [0., -1.]],
+            [[0., 0.], [0., -1.], [1., 0.]],
         )
     }
 
@@ -238,7 +645,7 @@ mod tests {
         test_bottom_top(
             [0., 0., -1.],
             [[0., 0., -1.], [1., 0., -1.], [0., -1., -1.]],
-            [[0., 0.], [1., 0.], [0., -1.]],
+            [[0., 0.], [0., -1.], [1., 0.]],
         )
     }
 
@@ -266,6 +673,329 @@ mod tests {
         )
     }
 
+    #[test]
+    fn polyline() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(Scalar::ONE)?;
+
+        let surface = Surface::xy_plane();
+        let edge = Edge::polyline_from_points(
+            &surface,
+            [[0., 0.], [1., 0.], [1., 1.], [0., 1.], [0., 0.]],
+        );
+        let face = Face::new(
+            surface,
+            [Cycle { edges: vec![edge] }],
+            [],
+            [255, 0, 0, 255],
+        );
+        let sketch = Sketch::from_faces([face]);
+
+        let solid =
+            super::sweep(sketch, [0., 0., 1.], tolerance, [255, 0, 0, 255])?;
+
+        let mesh = triangulate(
+            solid.into_faces().into_iter().collect(),
+            tolerance,
+            &ErrorBudget::new(),
+            &mut ApproxStore::new(),
+            &mut DebugInfo::new(),
+        )?;
+
+        // The solid is closed, if every edge of the mesh is shared by exactly
+        // two triangles.
+        let mut edges = BTreeMap::new();
+        for triangle in mesh.triangles() {
+            let [a, b, c] = triangle.points;
+            for [a, b] in [[a, b], [b, c], [c, a]] {
+                let edge = if a < b { [a, b] } else { [b, a] };
+                *edges.entry(edge).or_insert(0) += 1;
+            }
+        }
+        assert_eq!(mesh.triangles().count(), 12);
+        assert!(edges.values().all(|&count| count == 2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn circle() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let face = Face::new(
+            Surface::xy_plane(),
+            [Cycle {
+                edges: vec![Edge::circle_from_radius(Scalar::ONE)],
+            }],
+            [],
+            [255, 0, 0, 255],
+        );
+        let sketch = Sketch::from_faces([face]);
+
+        let solid =
+            super::sweep(sketch, [0., 0., 2.], tolerance, [255, 0, 0, 255])?;
+
+        // The side of the solid is a single, curved face.
+        let side: Vec<_> = solid
+            .faces()
+            .filter(|face| face.surface().period().is_some())
+            .cloned()
+            .collect();
+        assert_eq!(side.len(), 1);
+        assert_abs_diff_eq!(
+            side[0].surface().normal_at([0., 0.]),
+            Vector::from([1., 0., 0.]),
+            epsilon = 1e-12
+        );
+
+        let mesh = triangulate(
+            side,
+            tolerance,
+            &ErrorBudget::new(),
+            &mut ApproxStore::new(),
+            &mut DebugInfo::new(),
+        )?;
+        assert!(mesh.triangles().count() > 0);
+
+        let distance_from_axis =
+            |point: Point<3>| (point.x * point.x + point.y * point.y).sqrt();
+        for point in mesh.vertices() {
+            assert_abs_diff_eq!(
+                distance_from_axis(point),
+                Scalar::ONE,
+                epsilon = 1e-12
+            );
+            assert!(point.z >= Scalar::ZERO && point.z <= Scalar::TWO);
+        }
+        for triangle in mesh.triangles() {
+            let [a, b, c] = triangle.points;
+            let centroid = Point {
+                coords: (a.coords + b.coords + c.coords) / 3.,
+            };
+            assert!(
+                Scalar::ONE - distance_from_axis(centroid) <= tolerance.inner()
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn ellipse() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.1)?;
+
+        let surface = Surface::xy_plane();
+        let edge = Edge::ellipse(
+            &surface,
+            Ellipse {
+                center: Point::from([0., 0.]),
+                a: Vector::from([3., 0.]),
+                b: Vector::from([0., 1.]),
+            },
+        );
+        let face = Face::new(
+            surface,
+            [Cycle { edges: vec![edge] }],
+            [],
+            [255, 0, 0, 255],
+        );
+        let sketch = Sketch::from_faces([face]);
+
+        let solid =
+            super::sweep(sketch, [0., 0., 1.], tolerance, [255, 0, 0, 255])?;
+        let mesh = closed_mesh(solid, tolerance)?;
+
+        // The side of the solid is made from the approximation of the ellipse,
+        // so all of its vertices are on the elliptic cylinder.
+        for point in mesh.vertices() {
+            let x = point.x / 3.;
+            assert_abs_diff_eq!(
+                x * x + point.y * point.y,
+                Scalar::ONE,
+                epsilon = 1e-9
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn scale_linear_frustum() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let solid = super::sweep_with_scale(
+            unit_square(),
+            [0., 0., 1.],
+            &ScaleProfile::Linear {
+                start: Scalar::ONE,
+                end: Scalar::from_f64(0.5),
+            },
+            tolerance,
+            [255, 0, 0, 255],
+        )?;
+        let mesh = closed_mesh(solid, tolerance)?;
+
+        // The volume of a frustum is `h / 3 * (A1 + A2 + sqrt(A1 * A2))`.
+        let expected = 1. / 3. * (1. + 0.25 + 0.5);
+        let summary = PhysicalSummary::from_mesh(&mesh, tolerance.inner());
+        assert!((summary.volume.into_f64() - expected).abs() < 1e-9);
+
+        Ok(())
+    }
+
+    #[test]
+    fn scale_to_apex() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let solid = super::sweep_with_scale(
+            unit_square(),
+            [0., 0., 1.],
+            &ScaleProfile::Linear {
+                start: Scalar::ONE,
+                end: Scalar::ZERO,
+            },
+            tolerance,
+            [255, 0, 0, 255],
+        )?;
+        let mesh = closed_mesh(solid, tolerance)?;
+
+        // The side walls meet in the apex above the centroid. There is no top
+        // face, so nothing else is at the top.
+        let top: Vec<_> = mesh
+            .vertices()
+            .filter(|vertex| vertex.z == Scalar::ONE)
+            .collect();
+        assert_eq!(top.len(), 1);
+        assert!(
+            Point::distance(&top[0], &Point::from([0.5, 0.5, 1.])).into_f64()
+                < 1e-12
+        );
+
+        // Bottom face and four side walls
+        assert_eq!(mesh.triangles().count(), 2 + 4);
+
+        let summary = PhysicalSummary::from_mesh(&mesh, tolerance.inner());
+        assert!((summary.volume.into_f64() - 1. / 3.).abs() < 1e-9);
+
+        Ok(())
+    }
+
+    #[test]
+    fn scale_profile_validation() {
+        let valid = [
+            ScaleProfile::Constant(Scalar::ONE),
+            ScaleProfile::Linear {
+                start: Scalar::ONE,
+                end: Scalar::ZERO,
+            },
+            ScaleProfile::PiecewiseLinear(vec![
+                (Scalar::from_f64(0.25), Scalar::ONE),
+                (Scalar::from_f64(0.75), Scalar::from_f64(2.)),
+            ]),
+        ];
+        for profile in valid {
+            let stations = profile.stations().unwrap();
+            assert_eq!(stations.first().map(|s| s.0), Some(Scalar::ZERO));
+            assert_eq!(stations.last().map(|s| s.0), Some(Scalar::ONE));
+        }
+
+        let invalid = [
+            ScaleProfile::Constant(Scalar::ZERO),
+            ScaleProfile::Linear {
+                start: Scalar::ONE,
+                end: Scalar::from_f64(-1.),
+            },
+            ScaleProfile::PiecewiseLinear(vec![
+                (Scalar::from_f64(0.5), Scalar::ZERO),
+                (Scalar::ONE, Scalar::ONE),
+            ]),
+        ];
+        for profile in invalid {
+            assert!(matches!(
+                profile.stations(),
+                Err(SweepError::InvalidScale { .. })
+            ));
+        }
+
+        assert!(matches!(
+            ScaleProfile::PiecewiseLinear(vec![]).stations(),
+            Err(SweepError::EmptyProfile)
+        ));
+        assert!(matches!(
+            ScaleProfile::PiecewiseLinear(vec![
+                (Scalar::ONE, Scalar::ONE),
+                (Scalar::ZERO, Scalar::ONE),
+            ])
+            .stations(),
+            Err(SweepError::InvalidStations)
+        ));
+    }
+
+    #[test]
+    fn path_parallel_to_sketch() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let result =
+            super::sweep(unit_square(), [1., 0., 0.], tolerance, [0; 4]);
+        assert!(matches!(
+            result,
+            Err(SweepError::PathParallelToSketch { angle })
+                if angle == Scalar::ZERO
+        ));
+
+        let result = super::sweep_with_scale(
+            unit_square(),
+            [1., 0., 0.],
+            &ScaleProfile::Linear {
+                start: Scalar::ONE,
+                end: Scalar::from_f64(0.5),
+            },
+            tolerance,
+            [0; 4],
+        );
+        assert!(matches!(
+            result,
+            Err(SweepError::PathParallelToSketch { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn path_against_sketch_normal() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let solid = super::sweep(
+            unit_square(),
+            [0., 0., -1.],
+            tolerance,
+            [255, 0, 0, 255],
+        )?;
+        let mesh = closed_mesh(solid, tolerance)?;
+
+        let summary = PhysicalSummary::from_mesh(&mesh, tolerance.inner());
+        assert!((summary.volume.into_f64() - 1.).abs() < 1e-9);
+
+        // The solid is convex, so every triangle faces away from its center.
+        let center = Point::from([0.5, 0.5, -0.5]);
+        for triangle in mesh.triangles() {
+            let [a, b, c] = triangle.points;
+            let normal = (b - a).cross(&(c - a));
+            let centroid = Point {
+                coords: (a.coords + b.coords + c.coords) / 3.,
+            };
+            assert!(normal.dot(&(centroid - center)) > Scalar::ZERO);
+        }
+
+        Ok(())
+    }
+
+    fn unit_square() -> Sketch {
+        let face = Face::builder(Surface::xy_plane())
+            .with_exterior_polygon([[0., 0.], [1., 0.], [1., 1.], [0., 1.]])
+            .build();
+        Sketch::from_faces([face])
+    }
+
     fn test_side(
         direction: impl Into<Vector<3>>,
         expected_surfaces: [[impl Into<Point<3>>; 3]; 3],
@@ -298,7 +1028,7 @@ mod tests {
         let sketch = Sketch::from_faces([face]);
 
         let solid =
-            super::sweep(sketch, direction, tolerance, [255, 0, 0, 255]);
+            super::sweep(sketch, direction, tolerance, [255, 0, 0, 255])?;
 
         let expected_vertices: Vec<_> = expected_vertices
             .into_iter()
@@ -308,8 +1038,11 @@ mod tests {
         let faces = expected_surfaces.into_iter().map(|surface| {
             let surface = Surface::plane_from_points(surface);
 
+            // The sweep creates side faces on surfaces that aren't
+            // normalized, so don't normalize the expected ones either.
             Face::builder(surface)
                 .with_exterior_polygon(expected_vertices.clone())
+                .without_surface_normalization()
                 .build()
         });
 
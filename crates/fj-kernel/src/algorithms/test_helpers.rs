@@ -0,0 +1,50 @@
+//! Helpers that are shared by the tests of the algorithms
+
+use std::collections::BTreeMap;
+
+use fj_interop::{debug::DebugInfo, mesh::Mesh};
+use fj_math::Point;
+
+use crate::objects::Solid;
+
+use super::{triangulate, ApproxStore, ErrorBudget, Tolerance};
+
+/// Triangulate a solid and check that the resulting mesh is closed
+///
+/// The mesh is closed, if every edge is used by exactly two triangles, once in
+/// each direction. Points are compared after rounding, as points that were
+/// computed from different faces, like where faces were trimmed or cut, went
+/// through the coordinate systems of different surfaces.
+pub fn closed_mesh(
+    solid: Solid,
+    tolerance: Tolerance,
+) -> anyhow::Result<Mesh<Point<3>>> {
+    let mesh = triangulate(
+        solid.into_faces().into_iter().collect(),
+        tolerance,
+        &ErrorBudget::new(),
+        &mut ApproxStore::new(),
+        &mut DebugInfo::new(),
+    )?;
+
+    let key = |point: Point<3>| {
+        point
+            .coords
+            .components
+            .map(|c| (c.into_f64() * 1e6).round() as i64)
+    };
+
+    let mut edges = BTreeMap::new();
+    for triangle in mesh.triangles() {
+        let [a, b, c] = triangle.points.map(key);
+        for edge in [[a, b], [b, c], [c, a]] {
+            *edges.entry(edge).or_insert(0) += 1;
+        }
+    }
+    for (&[a, b], &count) in &edges {
+        assert_eq!(count, 1);
+        assert_eq!(edges.get(&[b, a]), Some(&1));
+    }
+
+    Ok(mesh)
+}
@@ -0,0 +1,457 @@
+use std::collections::BTreeMap;
+
+use fj_math::{Point, Scalar, Vector};
+
+use crate::objects::{Curve, Cycle, Face, Sketch, Surface};
+
+use super::split::contains;
+
+/// A font, as a collection of glyph outlines
+///
+/// Fonts are not loaded from font files here. Whatever reads the font file is
+/// expected to convert the outlines of the required glyphs into polygons.
+#[derive(Clone, Debug, Default)]
+pub struct Font {
+    glyphs: BTreeMap<char, Glyph>,
+}
+
+impl Font {
+    /// Create an empty font
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a glyph to the font
+    pub fn with_glyph(mut self, character: char, glyph: Glyph) -> Self {
+        self.glyphs.insert(character, glyph);
+        self
+    }
+
+    /// Access the glyph for a character
+    pub fn glyph(&self, character: char) -> Option<&Glyph> {
+        self.glyphs.get(&character)
+    }
+}
+
+/// The outline of a single character
+///
+/// All coordinates are in units of the font size, relative to the origin of
+/// the glyph, which is on the baseline.
+#[derive(Clone, Debug)]
+pub struct Glyph {
+    /// The closed polygons that make up the outline
+    ///
+    /// Counter-clockwise polygons enclose the glyph's area, clockwise ones are
+    /// holes in it. The last point of a polygon connects back to the first.
+    pub outlines: Vec<Vec<Point<2>>>,
+
+    /// The distance from the origin of this glyph to that of the next one
+    pub advance: Scalar,
+}
+
+/// Defines how [`sketch_from_text_on_path`] lays out text
+#[derive(Clone, Copy, Debug)]
+pub struct TextLayout {
+    /// The distance along the path, before the first glyph
+    pub start_offset: Scalar,
+
+    /// Additional space between glyphs, on top of their advance
+    pub letter_spacing: Scalar,
+
+    /// Scale the text down, if it doesn't fit on the path
+    ///
+    /// If this is `false`, text that doesn't fit results in an error.
+    pub scale_to_fit: bool,
+
+    /// The color of the created faces
+    pub color: [u8; 4],
+}
+
+impl Default for TextLayout {
+    fn default() -> Self {
+        Self {
+            start_offset: Scalar::ZERO,
+            letter_spacing: Scalar::ZERO,
+            scale_to_fit: false,
+            color: [255, 0, 0, 255],
+        }
+    }
+}
+
+/// Create a sketch of text that follows a path
+///
+/// The glyphs are placed one after the other along `path`, which is defined in
+/// the coordinates of `surface`. The origin of each glyph is placed on the path
+/// at the arc length that corresponds to its advance position, and the glyph is
+/// rotated to align its baseline with the tangent of the path there.
+///
+/// Each glyph results in one face per filled area of its outline.
+///
+/// # Implementation Note
+///
+/// Glyphs are placed rigidly. They are not bent to follow the path, which
+/// would be required for tightly curved paths.
+///
+/// Circles are used from their start point around once, lines from their
+/// origin to the point at curve coordinate `1`.
+pub fn sketch_from_text_on_path(
+    text: &str,
+    font: &Font,
+    size: Scalar,
+    path: &Curve<2>,
+    surface: &Surface,
+    layout: TextLayout,
+) -> Result<Sketch, TextError> {
+    let glyphs = text
+        .chars()
+        .map(|character| {
+            font.glyph(character)
+                .ok_or(TextError::MissingGlyph(character))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let path_length = path_length(path);
+
+    let mut size = size;
+    let mut letter_spacing = layout.letter_spacing;
+
+    let text_length = text_length(&glyphs, size, letter_spacing);
+    if layout.start_offset + text_length > path_length {
+        let available = path_length - layout.start_offset;
+
+        if !layout.scale_to_fit || available <= Scalar::ZERO {
+            return Err(TextError::TooLong {
+                text_length: layout.start_offset + text_length,
+                path_length,
+            });
+        }
+
+        let scale = available / text_length;
+        size = size * scale;
+        letter_spacing = letter_spacing * scale;
+    }
+
+    let mut faces = Vec::new();
+    let mut position = layout.start_offset;
+
+    for glyph in glyphs {
+        let (origin, direction) = point_at_arc_length(path, position);
+        let normal = Vector::from([-direction.v, direction.u]);
+
+        let outlines: Vec<Vec<Point<2>>> = glyph
+            .outlines
+            .iter()
+            .map(|outline| {
+                outline
+                    .iter()
+                    .map(|point| {
+                        origin
+                            + direction * (point.u * size)
+                            + normal * (point.v * size)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        faces.extend(glyph_faces(outlines, surface, layout.color));
+
+        position = position + glyph.advance * size + letter_spacing;
+    }
+
+    Ok(Sketch::from_faces(faces))
+}
+
+/// Error creating a sketch from text
+#[derive(Debug, thiserror::Error)]
+pub enum TextError {
+    /// The font has no glyph for a character of the text
+    #[error("Font has no glyph for character `{0}`")]
+    MissingGlyph(char),
+
+    /// The text doesn't fit on the path
+    #[error("Text of length {text_length} doesn't fit on path of length {path_length}")]
+    TooLong {
+        /// The length of the text, including the start offset
+        text_length: Scalar,
+
+        /// The length of the path
+        path_length: Scalar,
+    },
+}
+
+/// The distance along the path from the first glyph origin to the end of text
+fn text_length(
+    glyphs: &[&Glyph],
+    size: Scalar,
+    letter_spacing: Scalar,
+) -> Scalar {
+    let advances = glyphs
+        .iter()
+        .fold(Scalar::ZERO, |length, glyph| length + glyph.advance * size);
+    let spacing = letter_spacing * glyphs.len().saturating_sub(1) as f64;
+
+    advances + spacing
+}
+
+fn path_length(path: &Curve<2>) -> Scalar {
+    match path {
+        Curve::Circle(circle) => circle.a.magnitude() * Scalar::PI * 2.,
+        Curve::Ellipse(ellipse) => ellipse.circumference(),
+        Curve::Line(line) => line.direction.magnitude(),
+        Curve::Polyline(polyline) => {
+            polyline
+                .segments()
+                .iter()
+                .fold(Scalar::ZERO, |length, segment| {
+                    let [a, b] = segment.points();
+                    length + (b - a).magnitude()
+                })
+        }
+    }
+}
+
+/// Compute the point and unit tangent at the given arc length along the path
+fn point_at_arc_length(
+    path: &Curve<2>,
+    arc_length: Scalar,
+) -> (Point<2>, Vector<2>) {
+    let t = match path {
+        Curve::Circle(circle) => arc_length / circle.a.magnitude(),
+        Curve::Ellipse(ellipse) => ellipse.coords_at_arc_length(arc_length).t,
+        Curve::Line(line) => arc_length / line.direction.magnitude(),
+        Curve::Polyline(polyline) => {
+            let points = polyline.points();
+            let knots = polyline.knots();
+
+            let mut start = Scalar::ZERO;
+            let mut t = knots[knots.len() - 1];
+
+            for i in 0..points.len() - 1 {
+                let length = (points[i + 1] - points[i]).magnitude();
+                if arc_length <= start + length {
+                    let s = (arc_length - start) / length;
+                    t = knots[i] + (knots[i + 1] - knots[i]) * s;
+                    break;
+                }
+                start += length;
+            }
+
+            t
+        }
+    };
+
+    let t = Point::from([t]);
+    (
+        path.point_from_curve_coords(t),
+        path.tangent_at(t).normalize(),
+    )
+}
+
+/// Build the faces of a glyph from its placed outlines
+fn glyph_faces(
+    outlines: Vec<Vec<Point<2>>>,
+    surface: &Surface,
+    color: [u8; 4],
+) -> Vec<Face> {
+    let outlines = outlines.into_iter().map(|mut outline| {
+        if let Some(&first) = outline.first() {
+            outline.push(first);
+        }
+        outline
+    });
+
+    let (exteriors, interiors): (Vec<_>, Vec<_>) = outlines
+        .filter(|outline| outline.len() > 3)
+        .partition(|outline| signed_area(outline) > Scalar::ZERO);
+
+    let mut holes = vec![Vec::new(); exteriors.len()];
+    for interior in interiors {
+        let index = exteriors
+            .iter()
+            .position(|exterior| contains(exterior, interior[0]));
+
+        // A hole that's not within any area of the glyph has no effect.
+        if let Some(index) = index {
+            holes[index].push(interior);
+        }
+    }
+
+    exteriors
+        .into_iter()
+        .zip(holes)
+        .map(|(exterior, holes)| {
+            let cycle = |mut points: Vec<Point<2>>| {
+                points.pop();
+                Cycle::polygon_from_points(surface, points)
+            };
+
+            Face::new(
+                surface.clone(),
+                [cycle(exterior)],
+                holes.into_iter().map(cycle),
+                color,
+            )
+        })
+        .collect()
+}
+
+/// Twice the signed area of a closed polygon
+fn signed_area(points: &[Point<2>]) -> Scalar {
+    points.windows(2).fold(Scalar::ZERO, |area, segment| {
+        let [a, b] = [segment[0], segment[1]];
+        area + (a.u * b.v - b.u * a.v)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Circle, Point, Scalar, Vector};
+
+    use crate::{
+        algorithms::{CycleApprox, Tolerance},
+        iter::ObjectIters,
+        objects::{Curve, Surface},
+    };
+
+    use super::{sketch_from_text_on_path, Font, Glyph, TextError, TextLayout};
+
+    #[test]
+    fn text_on_circle() -> anyhow::Result<()> {
+        let radius = 10.;
+        let path = circle(radius);
+
+        let sketch = sketch_from_text_on_path(
+            "ABC",
+            &font(),
+            Scalar::ONE,
+            &path,
+            &Surface::xy_plane(),
+            TextLayout {
+                start_offset: Scalar::from_f64(1.),
+                letter_spacing: Scalar::from_f64(0.1),
+                ..TextLayout::default()
+            },
+        )?;
+        assert_eq!(sketch.face_iter().count(), 3);
+
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        for (i, interiors) in [0, 1, 0].into_iter().enumerate() {
+            let arc_length = 1. + i as f64 * 0.8;
+            let angle = arc_length / radius;
+
+            let origin = Vector::from([angle.cos(), angle.sin()]) * radius;
+            let direction = Vector::from([-angle.sin(), angle.cos()]);
+            let normal = Vector::from([-direction.v, direction.u]);
+
+            // The glyph's centroid is at (0.35, 0.35) in its own coordinates.
+            let expected = origin + direction * 0.35 + normal * 0.35;
+
+            let (points, face) = sketch
+                .face_iter()
+                .map(|face| {
+                    let exterior =
+                        face.exteriors().next().expect("No exterior");
+                    let mut points: Vec<_> =
+                        CycleApprox::new(exterior, tolerance)
+                            .points
+                            .into_iter()
+                            .map(|point| *point.global_form())
+                            .collect();
+
+                    // The last point repeats the first one.
+                    points.pop();
+
+                    (points, face)
+                })
+                .find(|(points, _)| {
+                    let centroid = centroid(points);
+                    is_close(centroid.x, expected.u)
+                        && is_close(centroid.y, expected.v)
+                })
+                .expect("No glyph at expected position");
+
+            // The first edge of each glyph runs along its baseline.
+            let baseline = (points[1] - points[0]).normalize();
+            assert!(is_close(baseline.x, direction.u));
+            assert!(is_close(baseline.y, direction.v));
+
+            // The glyph for "B" has a hole.
+            assert_eq!(face.interiors().count(), interiors);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn text_too_long() -> anyhow::Result<()> {
+        // A circle that's shorter than the text.
+        let path = circle(0.3);
+
+        let result = sketch_from_text_on_path(
+            "ABC",
+            &font(),
+            Scalar::ONE,
+            &path,
+            &Surface::xy_plane(),
+            TextLayout::default(),
+        );
+        assert!(matches!(result, Err(TextError::TooLong { .. })));
+
+        let sketch = sketch_from_text_on_path(
+            "ABC",
+            &font(),
+            Scalar::ONE,
+            &path,
+            &Surface::xy_plane(),
+            TextLayout {
+                scale_to_fit: true,
+                ..TextLayout::default()
+            },
+        )?;
+        assert_eq!(sketch.face_iter().count(), 3);
+
+        Ok(())
+    }
+
+    fn circle(radius: f64) -> Curve<2> {
+        Curve::Circle(Circle {
+            center: Point::origin(),
+            a: Vector::from([radius, 0.]),
+            b: Vector::from([0., radius]),
+        })
+    }
+
+    /// A font with square glyphs, to make their placement easy to check
+    fn font() -> Font {
+        let square = points(&[[0., 0.], [0.7, 0.], [0.7, 0.7], [0., 0.7]]);
+        let hole = points(&[[0.2, 0.2], [0.2, 0.5], [0.5, 0.5], [0.5, 0.2]]);
+
+        let glyph = |outlines| Glyph {
+            outlines,
+            advance: Scalar::from_f64(0.7),
+        };
+
+        Font::new()
+            .with_glyph('A', glyph(vec![square.clone()]))
+            .with_glyph('B', glyph(vec![square.clone(), hole]))
+            .with_glyph('C', glyph(vec![square]))
+    }
+
+    fn points(points: &[[f64; 2]]) -> Vec<Point<2>> {
+        points.iter().copied().map(Point::from).collect()
+    }
+
+    fn centroid(points: &[Point<3>]) -> Point<3> {
+        let sum = points
+            .iter()
+            .fold(Vector::from([0., 0., 0.]), |sum, point| sum + point.coords);
+        Point {
+            coords: sum / points.len() as f64,
+        }
+    }
+
+    fn is_close(a: Scalar, b: Scalar) -> bool {
+        (a - b).abs() < Scalar::from_f64(1e-9)
+    }
+}
@@ -0,0 +1,277 @@
+use fj_interop::{
+    half_edge::{HalfEdgeMesh, IntegrityError},
+    mesh::Mesh,
+};
+use fj_math::{Aabb, Point, Scalar, Triangle};
+
+use crate::objects::{Face, Solid};
+
+/// Give a face that is made of triangles a thickness
+///
+/// Each vertex of the face is moved against its normal by `offset`, which
+/// results in a copy of the face behind it. The copy is reversed, and its
+/// boundary is connected to the boundary of the face by walls of two triangles
+/// per boundary edge. This closes the space between both, so the triangles of
+/// the face end up on the outside of the solid. The solid consists of a single
+/// face that is made of triangles, with the colors of the original triangles.
+///
+/// The normal at a vertex is the average of the normals of the triangles
+/// around it (see [`Mesh::vertex_normals`]). The triangles must form a
+/// manifold sheet with consistent winding, like the surface of an imported
+/// relief.
+///
+/// If the face is curved more sharply than `offset` allows, the copy folds
+/// over itself. The resulting solid intersects itself, which is reported as
+/// [`ThickenError::SelfIntersection`].
+pub fn thicken_triangles(
+    face: &Face,
+    offset: impl Into<Scalar>,
+) -> Result<Solid, ThickenError> {
+    let offset = offset.into();
+    if offset <= Scalar::ZERO {
+        return Err(ThickenError::InvalidOffset(offset));
+    }
+
+    let triangles = face.triangles().ok_or(ThickenError::NotTriangles)?;
+
+    // All triangles are part of the same face, so each vertex has a single
+    // normal, averaged over all triangles around it.
+    let mut sheet = Mesh::new();
+    for (triangle, color) in triangles {
+        sheet.push_face_triangle(triangle.points(), *color, 0);
+    }
+
+    let half_edges = HalfEdgeMesh::from_mesh(&sheet);
+    half_edges
+        .check_integrity()
+        .map_err(ThickenError::InvalidMesh)?;
+
+    let points: Vec<_> = sheet.vertices().collect();
+    let indices: Vec<_> = sheet.indices().map(|index| index as usize).collect();
+
+    let mut offset_points = points.clone();
+    let normals = sheet.vertex_normals(Scalar::PI);
+    for (i, normals) in normals.into_iter().enumerate() {
+        for (k, normal) in normals.into_iter().enumerate() {
+            let vertex = indices[3 * i + k];
+            offset_points[vertex] = points[vertex] - normal * offset;
+        }
+    }
+
+    // Vertices of the copy have the index of the original vertex, plus `n`.
+    let n = points.len();
+    let mut solid = Vec::new();
+
+    for (i, triangle) in sheet.triangles().enumerate() {
+        let [a, b, c] = [0, 1, 2].map(|k| indices[3 * i + k]);
+        if a == b || b == c || c == a {
+            continue;
+        }
+
+        solid.push(([a, b, c], triangle.color));
+        solid.push(([a + n, c + n, b + n], triangle.color));
+    }
+
+    for boundary in half_edges.boundary_loops() {
+        for (i, &a) in boundary.iter().enumerate() {
+            let b = boundary[(i + 1) % boundary.len()];
+
+            let color = half_edges
+                .half_edge(a, b)
+                .and_then(|half_edge| half_edges.triangle(half_edge / 3))
+                .expect("Boundary loop consists of edges of triangles")
+                .color;
+
+            // The wall runs along the edge in the opposite direction than the
+            // triangle of the face does, and along the reversed copy in the
+            // same direction.
+            solid.push(([b, a, a + n], color));
+            solid.push(([b, a + n, b + n], color));
+        }
+    }
+
+    let position = |vertex: usize| {
+        if vertex < n {
+            points[vertex]
+        } else {
+            offset_points[vertex - n]
+        }
+    };
+
+    let mut mesh = Mesh::new();
+    for (vertices, color) in &solid {
+        mesh.push_triangle(vertices.map(position), *color);
+    }
+    HalfEdgeMesh::from_mesh(&mesh)
+        .check_integrity()
+        .map_err(ThickenError::InvalidMesh)?;
+
+    let vertices: Vec<_> =
+        solid.iter().map(|&(vertices, _)| vertices).collect();
+    if let Some(point) = self_intersection(&vertices, position) {
+        return Err(ThickenError::SelfIntersection(point));
+    }
+
+    let triangles = solid
+        .into_iter()
+        .map(|(vertices, color)| {
+            (Triangle::from_points(vertices.map(position)), color)
+        })
+        .collect();
+
+    Ok(Solid::from_faces([Face::from_triangles(triangles)]))
+}
+
+/// Find a point where two triangles intersect, that don't share a vertex
+///
+/// Triangles that share a vertex are neighbors in a closed mesh, and touch by
+/// definition. A triangle without any area intersects itself.
+fn self_intersection(
+    triangles: &[[usize; 3]],
+    position: impl Fn(usize) -> Point<3>,
+) -> Option<Point<3>> {
+    let points: Vec<_> = triangles
+        .iter()
+        .map(|vertices| vertices.map(&position))
+        .collect();
+
+    for &[a, b, c] in &points {
+        if (b - a).cross(&(c - a)).magnitude() == Scalar::ZERO {
+            return Some(a);
+        }
+    }
+
+    let aabbs: Vec<_> = points
+        .iter()
+        .map(|&points| Aabb::<3>::from_points(points))
+        .collect();
+
+    for i in 0..triangles.len() {
+        for j in i + 1..triangles.len() {
+            let shares_vertex = triangles[i]
+                .iter()
+                .any(|vertex| triangles[j].contains(vertex));
+            if shares_vertex || !aabbs[i].intersects(&aabbs[j]) {
+                continue;
+            }
+
+            for (edges_of, other) in [(i, j), (j, i)] {
+                let other = Triangle::from_points(points[other]);
+                let [a, b, c] = points[edges_of];
+
+                for [start, end] in [[a, b], [b, c], [c, a]] {
+                    let direction = end - start;
+                    if let Some(toi) =
+                        other.cast_local_ray(start, direction, 1., true)
+                    {
+                        return Some(start + direction * toi);
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Error thickening a face
+#[derive(Debug, thiserror::Error)]
+pub enum ThickenError {
+    /// The offset is not positive
+    #[error("Offset for thickening must be positive, but is {0}")]
+    InvalidOffset(Scalar),
+
+    /// The face is not made of triangles
+    #[error("Can only thicken faces that are made of triangles")]
+    NotTriangles,
+
+    /// The triangles of the face, or of the thickened face, are not a valid
+    /// closed mesh
+    #[error("Thickening resulted in an invalid mesh: {0:?}")]
+    InvalidMesh(IntegrityError),
+
+    /// The offset is too large for the curvature of the face
+    #[error(
+        "Offset is too large for the curvature of the face; thickened face \
+        intersects itself at {0:?}"
+    )]
+    SelfIntersection(Point<3>),
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::{
+        debug::DebugInfo, half_edge::HalfEdgeMesh,
+        physical_summary::PhysicalSummary,
+    };
+    use fj_math::{Scalar, Triangle};
+
+    use crate::{
+        algorithms::{triangulate, ApproxStore, ErrorBudget, Tolerance},
+        objects::Face,
+    };
+
+    use super::{thicken_triangles, ThickenError};
+
+    const COLOR: [u8; 4] = [255, 0, 0, 255];
+
+    #[test]
+    fn flat_square() -> anyhow::Result<()> {
+        let [a, b, c, d] =
+            [[0., 0., 0.], [1., 0., 0.], [1., 1., 0.], [0., 1., 0.]];
+        let face = Face::from_triangles(vec![
+            (Triangle::from_points([a, b, c]), COLOR),
+            (Triangle::from_points([a, c, d]), COLOR),
+        ]);
+
+        let solid = thicken_triangles(&face, 1.)?;
+
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        let mesh = triangulate(
+            solid.into_faces().into_iter().collect(),
+            tolerance,
+            &ErrorBudget::new(),
+            &mut ApproxStore::new(),
+            &mut DebugInfo::new(),
+        )?;
+
+        // 2 triangles on each side, and 2 per edge of the square.
+        assert_eq!(mesh.triangle_count(), 12);
+
+        let half_edges = HalfEdgeMesh::from_mesh(&mesh);
+        assert_eq!(half_edges.check_integrity(), Ok(()));
+        assert!(half_edges.boundary_loops().is_empty());
+
+        let volume =
+            PhysicalSummary::from_mesh(&mesh, tolerance.inner()).volume;
+        assert!((volume - Scalar::ONE).abs() < Scalar::from_f64(1e-9));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sharp_fold() {
+        // A sheet that is folded into a ridge along the y-axis, with its
+        // triangles facing outward, away from the inside of the fold. Both
+        // sides are triangulated symmetrically, so the normals along the ridge
+        // point straight up.
+        let [a0, a1] = [[-1., 0., -3.], [-1., 1., -3.]];
+        let [b0, b1] = [[0., 0., 0.], [0., 1., 0.]];
+        let [c0, c1] = [[1., 0., -3.], [1., 1., -3.]];
+        let face = Face::from_triangles(vec![
+            (Triangle::from_points([a0, b0, a1]), COLOR),
+            (Triangle::from_points([a1, b0, b1]), COLOR),
+            (Triangle::from_points([b0, c0, c1]), COLOR),
+            (Triangle::from_points([b0, c1, b1]), COLOR),
+        ]);
+
+        // A small offset fits into the fold.
+        assert!(thicken_triangles(&face, 0.1).is_ok());
+
+        // A large one makes the sides of the thickened fold cross each other.
+        assert!(matches!(
+            thicken_triangles(&face, 2.),
+            Err(ThickenError::SelfIntersection(_))
+        ));
+    }
+}
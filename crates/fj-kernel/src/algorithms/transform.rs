@@ -1,4 +1,4 @@
-use fj_math::{Transform, Vector};
+use fj_math::{Scalar, Transform, Triangle, Vector};
 
 use crate::{
     local::Local,
@@ -7,6 +7,8 @@ use crate::{
     },
 };
 
+use super::reverse::reverse_cycle;
+
 /// Transform an object
 ///
 /// # Implementation Note
@@ -38,9 +40,29 @@ impl TransformObject for Curve<3> {
     fn transform(self, transform: &Transform) -> Self {
         match self {
             Self::Circle(curve) => {
-                Self::Circle(transform.transform_circle(&curve))
+                let circle = transform.transform_circle(&curve);
+
+                // Only similarity transforms keep a circle circular. Rigid
+                // transforms introduce rounding errors, which must not turn
+                // circles into ellipses.
+                let epsilon = circle.a.dot(&circle.a) * 1e-12;
+                let is_circle =
+                    (circle.a.dot(&circle.a) - circle.b.dot(&circle.b)).abs()
+                        <= epsilon
+                        && circle.a.dot(&circle.b).abs() <= epsilon;
+                if is_circle {
+                    Self::Circle(circle)
+                } else {
+                    Self::Ellipse(circle.into())
+                }
+            }
+            Self::Ellipse(curve) => {
+                Self::Ellipse(transform.transform_ellipse(&curve))
             }
             Self::Line(curve) => Self::Line(transform.transform_line(&curve)),
+            Self::Polyline(curve) => {
+                Self::Polyline(transform.transform_polyline(&curve))
+            }
         }
     }
 }
@@ -48,7 +70,7 @@ impl TransformObject for Curve<3> {
 impl TransformObject for Cycle {
     fn transform(mut self, transform: &Transform) -> Self {
         for edge in &mut self.edges {
-            *edge = edge.transform(transform);
+            *edge = edge.clone().transform(transform);
         }
 
         self
@@ -58,8 +80,8 @@ impl TransformObject for Cycle {
 impl TransformObject for Edge {
     fn transform(self, transform: &Transform) -> Self {
         let curve = Local::new(
-            *self.curve().local_form(),
-            self.curve().global_form().transform(transform),
+            self.curve().local_form().clone(),
+            self.curve().global_form().clone().transform(transform),
         );
 
         let vertices =
@@ -71,25 +93,40 @@ impl TransformObject for Edge {
 
 impl TransformObject for Face {
     fn transform(self, transform: &Transform) -> Self {
+        // A transform that mirrors space also mirrors the frame of the
+        // surface, which would turn the face inside out. Reversing the cycles
+        // flips the handedness of the face back, so it keeps pointing outward.
+        let is_mirror = transform.determinant() < Scalar::ZERO;
+
         if let Some(triangles) = self.triangles() {
             let mut target = Vec::new();
 
             for (triangle, color) in triangles.clone() {
-                let triangle = transform.transform_triangle(&triangle);
+                let mut triangle = transform.transform_triangle(&triangle);
+                if is_mirror {
+                    let [a, b, c] = triangle.points();
+                    triangle = Triangle::from_points([a, c, b]);
+                }
                 target.push((triangle, color));
             }
 
             return Self::from_triangles(target);
         }
 
-        let surface = self.surface().transform(transform);
+        let surface = self.surface().clone().transform(transform);
 
-        let exteriors = transform_cycles(self.exteriors(), transform);
-        let interiors = transform_cycles(self.interiors(), transform);
+        let mut exteriors: Vec<_> =
+            transform_cycles(self.exteriors(), transform).collect();
+        let mut interiors: Vec<_> =
+            transform_cycles(self.interiors(), transform).collect();
+        if is_mirror {
+            exteriors = exteriors.into_iter().map(reverse_cycle).collect();
+            interiors = interiors.into_iter().map(reverse_cycle).collect();
+        }
 
         let color = self.color();
 
-        Face::new(surface, exteriors, interiors, color)
+        Face::new(surface, exteriors, interiors, color).with_overrides_of(&self)
     }
 }
 
@@ -1,14 +1,22 @@
 use fj_math::{Point, Scalar, Triangle, Winding};
 use spade::HasPosition;
 
-use crate::local::Local;
+use crate::{algorithms::Handedness, local::Local};
 
 /// Create a Delaunay triangulation of all points
-pub fn triangulate(points: Vec<Local<Point<2>>>) -> Vec<[Local<Point<2>>; 3]> {
+///
+/// The triangles are wound such that they face outward, for a face with the
+/// given handedness (see [`Handedness::orient`]).
+///
+/// Returns an error, if any of the points can't be triangulated, because its
+/// coordinates are not finite, or too large or small.
+pub fn triangulate(
+    points: Vec<Local<Point<2>>>,
+    handedness: Handedness,
+) -> Result<Vec<[Local<Point<2>>; 3]>, spade::InsertionError> {
     use spade::Triangulation as _;
 
-    let triangulation = spade::DelaunayTriangulation::<_>::bulk_load(points)
-        .expect("Inserted invalid values into triangulation");
+    let triangulation = spade::DelaunayTriangulation::<_>::bulk_load(points)?;
 
     let mut triangles = Vec::new();
     for triangle in triangulation.inner_faces() {
@@ -25,10 +33,10 @@ pub fn triangulate(points: Vec<Local<Point<2>>>) -> Vec<[Local<Point<2>>; 3]> {
             Winding::Cw => [v0, v2, v1],
         };
 
-        triangles.push(triangle);
+        triangles.push(handedness.orient(triangle));
     }
 
-    triangles
+    Ok(triangles)
 }
 
 // Enables the use of `LocalPoint` in the triangulation.
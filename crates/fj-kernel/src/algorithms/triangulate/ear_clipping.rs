@@ -0,0 +1,345 @@
+use std::collections::BTreeSet;
+
+use fj_math::{Point, Scalar};
+
+use crate::{algorithms::Handedness, local::Local};
+
+type Vertex = Local<Point<2>>;
+
+/// Triangulate a polygon with holes by ear clipping
+///
+/// The exterior and the interiors must be simple polygons. The interiors must
+/// be within the exterior, and not touch each other. The winding of the cycles
+/// doesn't matter. The returned triangles are wound such that they face
+/// outward, for a face with the given handedness (see [`Handedness::orient`]).
+///
+/// Unlike a Delaunay triangulation of the same points, the triangles never
+/// cross the boundary of the polygon, so they don't need to be filtered
+/// afterwards.
+///
+/// Returns `None`, if the polygon turns out not to meet the requirements.
+pub fn triangulate(
+    exterior: &[Vertex],
+    interiors: &[&[Vertex]],
+    handedness: Handedness,
+) -> Option<Vec<[Vertex; 3]>> {
+    let mut polygon = cycle(exterior);
+    if polygon.len() < 3 {
+        return None;
+    }
+    let area = signed_area(&polygon);
+    if area == Scalar::ZERO {
+        return None;
+    }
+    if area < Scalar::ZERO {
+        polygon.reverse();
+    }
+
+    let mut holes: Vec<_> = interiors
+        .iter()
+        .map(|interior| cycle(interior))
+        .filter(|hole| hole.len() >= 3)
+        .map(|mut hole| {
+            if signed_area(&hole) > Scalar::ZERO {
+                hole.reverse();
+            }
+            hole
+        })
+        .collect();
+
+    // The holes are bridged from right to left, so a bridge never crosses a
+    // hole that hasn't been bridged yet.
+    holes.sort_by_key(|hole| {
+        std::cmp::Reverse(hole[rightmost(hole)].local_form().u)
+    });
+    for hole in holes {
+        polygon = bridge(&polygon, &hole)?;
+    }
+
+    clip_ears(&polygon, handedness)
+}
+
+/// Remove duplicate points from a cycle, including a closing one
+fn cycle(points: &[Vertex]) -> Vec<Vertex> {
+    let mut points = points.to_vec();
+    points.dedup_by(|a, b| a.local_form() == b.local_form());
+    if points.len() > 1
+        && points.first().map(Local::local_form)
+            == points.last().map(Local::local_form)
+    {
+        points.pop();
+    }
+    points
+}
+
+/// Compute twice the signed area of a cycle
+fn signed_area(cycle: &[Vertex]) -> Scalar {
+    let mut area = Scalar::ZERO;
+    for (i, a) in cycle.iter().enumerate() {
+        let b = cycle[(i + 1) % cycle.len()];
+        let [a, b] = [a, &b].map(|point| point.local_form());
+        area += a.u * b.v - b.u * a.v;
+    }
+    area
+}
+
+fn rightmost(cycle: &[Vertex]) -> usize {
+    let mut index = 0;
+    for (i, point) in cycle.iter().enumerate() {
+        if point.local_form().u > cycle[index].local_form().u {
+            index = i;
+        }
+    }
+    index
+}
+
+/// Connect a hole to the polygon, turning both into a single cycle
+///
+/// The rightmost vertex of the hole is connected to a vertex of the polygon
+/// that is visible from it, as described in "Triangulation by Ear Clipping" by
+/// David Eberly. The connection is traversed twice, once in each direction.
+fn bridge(polygon: &[Vertex], hole: &[Vertex]) -> Option<Vec<Vertex>> {
+    let m_index = rightmost(hole);
+    let m = *hole[m_index].local_form();
+    let n = polygon.len();
+
+    // Cast a ray from `m` to the right, and find the closest edge it hits.
+    // Since the polygon is counter-clockwise, and the hole is inside of it,
+    // only edges going up can be hit from the inside.
+    let mut hit: Option<(Scalar, usize)> = None;
+    for i in 0..n {
+        let j = (i + 1) % n;
+        let [a, b] = [i, j].map(|k| *polygon[k].local_form());
+
+        if !(a.v <= m.v && m.v <= b.v && a.v < b.v) {
+            continue;
+        }
+
+        let u = a.u + (m.v - a.v) * (b.u - a.u) / (b.v - a.v);
+        if u < m.u || hit.map(|(closest, _)| u >= closest).unwrap_or(false) {
+            continue;
+        }
+
+        // Of the edge, the vertex that's further to the right is the
+        // candidate for the bridge, unless the ray hits a vertex directly.
+        let candidate = if m.v == a.v {
+            i
+        } else if m.v == b.v || b.u > a.u {
+            j
+        } else {
+            i
+        };
+
+        hit = Some((u, candidate));
+    }
+    let (u, mut p_index) = hit?;
+
+    // If the ray didn't hit the candidate directly, other vertices might block
+    // the view. Those would be reflex vertices within the triangle formed by
+    // `m`, the hit, and the candidate. Of those, the one that's closest in
+    // angle to the ray is visible.
+    let hit = Point::from([u, m.v]);
+    let p = *polygon[p_index].local_form();
+    if p != hit {
+        let mut closest: Option<(Scalar, Scalar)> = None;
+
+        for (i, vertex) in polygon.iter().enumerate() {
+            let r = *vertex.local_form();
+            if i == p_index || r == p || !is_reflex(polygon, i) {
+                continue;
+            }
+            if !contains([m, hit, p], r) {
+                continue;
+            }
+
+            let d = r - m;
+            let key = (d.v.abs() / d.u, d.magnitude());
+            if closest.map(|closest| key < closest).unwrap_or(true) {
+                closest = Some(key);
+                p_index = i;
+            }
+        }
+    }
+
+    let mut merged = Vec::with_capacity(n + hole.len() + 2);
+    merged.extend_from_slice(&polygon[..=p_index]);
+    merged.extend_from_slice(&hole[m_index..]);
+    merged.extend_from_slice(&hole[..=m_index]);
+    merged.extend_from_slice(&polygon[p_index..]);
+
+    Some(merged)
+}
+
+fn clip_ears(
+    polygon: &[Vertex],
+    handedness: Handedness,
+) -> Option<Vec<[Vertex; 3]>> {
+    let n = polygon.len();
+    let point = |i: usize| *polygon[i].local_form();
+
+    let mut prev: Vec<_> = (0..n).map(|i| (i + n - 1) % n).collect();
+    let mut next: Vec<_> = (0..n).map(|i| (i + 1) % n).collect();
+
+    // Only reflex vertices can be inside an ear. Collinear vertices are
+    // included, as they could be on the edge of one.
+    let mut reflex: BTreeSet<_> = (0..n)
+        .filter(|&i| orient(point(prev[i]), point(i), point(next[i])) <= 0.)
+        .collect();
+
+    let mut triangles = Vec::with_capacity(n - 2);
+    let mut remaining = n;
+    let mut i = 0;
+    let mut misses = 0;
+
+    while remaining > 3 {
+        let [a, b, c] = [prev[i], i, next[i]];
+        let [pa, pb, pc] = [a, b, c].map(point);
+
+        let is_ear = orient(pa, pb, pc) > 0.
+            && reflex.iter().all(|&r| {
+                let pr = point(r);
+                [a, b, c].contains(&r)
+                    || [pa, pb, pc].contains(&pr)
+                    || !contains([pa, pb, pc], pr)
+            });
+
+        if !is_ear {
+            i = c;
+            misses += 1;
+
+            // A full round without finding an ear. The polygon isn't simple.
+            if misses > remaining {
+                return None;
+            }
+
+            continue;
+        }
+
+        triangles.push(handedness.orient([a, b, c].map(|k| polygon[k])));
+        next[a] = c;
+        prev[c] = a;
+        reflex.remove(&b);
+        remaining -= 1;
+
+        // Clipping an ear can only make its neighbors more convex.
+        for k in [a, c] {
+            if orient(point(prev[k]), point(k), point(next[k])) > 0. {
+                reflex.remove(&k);
+            }
+        }
+
+        i = c;
+        misses = 0;
+    }
+
+    triangles
+        .push(handedness.orient([prev[i], i, next[i]].map(|k| polygon[k])));
+
+    Some(triangles)
+}
+
+fn is_reflex(polygon: &[Vertex], i: usize) -> bool {
+    let n = polygon.len();
+    let [a, b, c] =
+        [(i + n - 1) % n, i, (i + 1) % n].map(|k| *polygon[k].local_form());
+
+    orient(a, b, c) < 0.
+}
+
+/// Determine whether the point is within the triangle, or on its boundary
+///
+/// The triangle may have either winding.
+fn contains([a, b, c]: [Point<2>; 3], point: Point<2>) -> bool {
+    let orientations = [
+        orient(a, b, point),
+        orient(b, c, point),
+        orient(c, a, point),
+    ];
+
+    orientations.iter().all(|&o| o >= 0.)
+        || orientations.iter().all(|&o| o <= 0.)
+}
+
+/// Positive, if the points are counter-clockwise; negative, if clockwise
+fn orient(a: Point<2>, b: Point<2>, c: Point<2>) -> f64 {
+    let [a, b, c] = [a, b, c].map(|point| robust::Coord {
+        x: point.u,
+        y: point.v,
+    });
+    robust::orient2d(a, b, c)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::{algorithms::Handedness, local::Local};
+
+    use super::triangulate;
+
+    #[test]
+    fn concave() {
+        // An L-shape
+        let exterior = points(&[
+            [0., 0.],
+            [2., 0.],
+            [2., 1.],
+            [1., 1.],
+            [1., 2.],
+            [0., 2.],
+        ]);
+
+        let triangles = triangulate(&exterior, &[], Handedness::Right).unwrap();
+        assert_eq!(triangles.len(), 4);
+        assert_eq!(area(&triangles), Scalar::from_f64(3.));
+    }
+
+    #[test]
+    fn holes() {
+        let exterior = points(&[[0., 0.], [6., 0.], [6., 4.], [0., 4.]]);
+        let left = points(&[[1., 1.], [2., 1.], [2., 3.], [1., 3.]]);
+        let right = points(&[[4., 1.], [5., 1.], [5., 3.], [4., 3.]]);
+
+        let triangles =
+            triangulate(&exterior, &[&left, &right], Handedness::Right)
+                .unwrap();
+
+        // Each bridge adds two vertices.
+        assert_eq!(triangles.len(), 12 + 4 - 2);
+        assert_eq!(area(&triangles), Scalar::from_f64(24. - 2. - 2.));
+    }
+
+    #[test]
+    fn clockwise_input() {
+        let exterior = points(&[[0., 0.], [0., 1.], [1., 1.], [1., 0.]]);
+
+        let triangles = triangulate(&exterior, &[], Handedness::Right).unwrap();
+        assert_eq!(area(&triangles), Scalar::ONE);
+
+        // Left-handed faces are wound the other way, regardless of the input.
+        let triangles = triangulate(&exterior, &[], Handedness::Left).unwrap();
+        assert_eq!(area(&triangles), -Scalar::ONE);
+    }
+
+    fn points(points: &[[f64; 2]]) -> Vec<Local<Point<2>>> {
+        points
+            .iter()
+            .map(|&point| {
+                let point = Point::from(point);
+                Local::new(point, point.to_xyz())
+            })
+            .collect()
+    }
+
+    /// The sum of the signed areas of the triangles
+    fn area(triangles: &[[Local<Point<2>>; 3]]) -> Scalar {
+        triangles
+            .iter()
+            .map(|triangle| {
+                let [a, b, c] = triangle.map(|point| *point.local_form());
+                let [ab, ac] = [b - a, c - a];
+                (ab.u * ac.v - ab.v * ac.u) / 2.
+            })
+            .fold(Scalar::ZERO, |sum, area| sum + area)
+    }
+}
@@ -0,0 +1,227 @@
+use fj_interop::{debug::DebugInfo, mesh::Mesh};
+use fj_math::{Point, Transform, Vector};
+
+use crate::{
+    algorithms::{
+        equivalent_translation, geometric_hash, ApproxStore, ErrorBudget,
+        Tolerance,
+    },
+    objects::Face,
+};
+
+use super::{triangulate, TriangulationError};
+
+/// Triangulate the parts of a shape, sharing meshes between equivalent parts
+///
+/// Parts that are equal, except for their position, are only triangulated
+/// once. Candidates are found using [`geometric_hash`], then verified using
+/// [`equivalent_translation`], so hash collisions never lead to wrong results.
+///
+/// See [`triangulate`] for the meaning of the other arguments.
+pub fn triangulate_instanced(
+    parts: Vec<Vec<Face>>,
+    tolerance: Tolerance,
+    error_budget: &ErrorBudget,
+    approx_store: &mut ApproxStore,
+    debug_info: &mut DebugInfo,
+) -> Result<InstancedMesh, TriangulationError> {
+    let mut instanced = InstancedMesh::default();
+
+    // The hash and faces of every part that has been triangulated, by index
+    // of its mesh.
+    let mut unique: Vec<(u64, Vec<Face>)> = Vec::new();
+
+    let mut face_offset = 0;
+
+    for faces in parts {
+        let hash = geometric_hash(&faces, tolerance);
+        let num_faces = faces.len();
+
+        let existing = unique.iter().enumerate().find_map(
+            |(mesh, (other_hash, other))| {
+                if *other_hash != hash {
+                    return None;
+                }
+
+                equivalent_translation(other, &faces, tolerance)
+                    .map(|translation| (mesh, translation))
+            },
+        );
+
+        let (mesh, translation) = match existing {
+            Some(existing) => existing,
+            None => {
+                let mesh = triangulate(
+                    faces.clone(),
+                    tolerance,
+                    error_budget,
+                    approx_store,
+                    debug_info,
+                )?;
+
+                instanced.meshes.push(mesh);
+                unique.push((hash, faces));
+
+                (instanced.meshes.len() - 1, Vector::from([0., 0., 0.]))
+            }
+        };
+
+        instanced.instances.push(Instance {
+            mesh,
+            transform: Transform::translation(translation),
+            face_offset,
+        });
+
+        face_offset += num_faces;
+    }
+
+    Ok(instanced)
+}
+
+/// A triangle mesh, made up of instances of shared meshes
+///
+/// Returned by [`triangulate_instanced`].
+#[derive(Default)]
+pub struct InstancedMesh {
+    /// The meshes that are shared between instances
+    pub meshes: Vec<Mesh<Point<3>>>,
+
+    /// The instances
+    pub instances: Vec<Instance>,
+}
+
+impl InstancedMesh {
+    /// Combine all instances into a single mesh
+    ///
+    /// The face index of each triangle is offset by the instance's
+    /// `face_offset`, so it refers to the face in the original shape.
+    pub fn to_mesh(&self) -> Mesh<Point<3>> {
+        let mut mesh = Mesh::new();
+
+        for instance in &self.instances {
+            for triangle in self.meshes[instance.mesh].triangles() {
+                let points = triangle
+                    .points
+                    .map(|point| instance.transform.transform_point(&point));
+
+                match triangle.face {
+                    Some(face) => mesh.push_face_triangle(
+                        points,
+                        triangle.color,
+                        instance.face_offset + face,
+                    ),
+                    None => mesh.push_triangle(points, triangle.color),
+                }
+            }
+        }
+
+        mesh
+    }
+}
+
+/// An instance of a shared mesh
+///
+/// See [`InstancedMesh`].
+#[derive(Debug)]
+pub struct Instance {
+    /// The index of the mesh in [`InstancedMesh::meshes`]
+    pub mesh: usize,
+
+    /// The transform that places the mesh
+    pub transform: Transform,
+
+    /// The index of the instance's first face, within the whole shape
+    pub face_offset: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::debug::DebugInfo;
+
+    use crate::{
+        algorithms::{sweep, ApproxStore, ErrorBudget, Tolerance},
+        objects::{Face, Sketch, Surface},
+    };
+
+    #[test]
+    fn identical_parts() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let parts = (0..10)
+            .map(|i| bracket([i as f64 * 3., 1.], 0., tolerance))
+            .collect();
+        let instanced = triangulate_instanced(parts, tolerance)?;
+
+        assert_eq!(instanced.meshes.len(), 1);
+        assert_eq!(instanced.instances.len(), 10);
+        assert_eq!(
+            instanced.to_mesh().triangles().count(),
+            instanced.meshes[0].triangles().count() * 10
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn slightly_different_part() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let parts = (0..10)
+            .map(|i| {
+                let moved = if i == 5 { 0.01 } else { 0. };
+                bracket([i as f64 * 3., 1.], moved, tolerance)
+            })
+            .collect();
+        let instanced = triangulate_instanced(parts, tolerance)?;
+
+        assert_eq!(instanced.meshes.len(), 2);
+        assert_eq!(instanced.instances.len(), 10);
+
+        Ok(())
+    }
+
+    /// An L-shaped bracket at the given position
+    ///
+    /// One of its vertices is moved along the x-axis by `moved`.
+    fn bracket(
+        [x, y]: [f64; 2],
+        moved: f64,
+        tolerance: Tolerance,
+    ) -> Vec<Face> {
+        let polygon = [
+            [0., 0.],
+            [2., 0.],
+            [2., 0.5],
+            [0.5 + moved, 0.5],
+            [0.5, 2.],
+            [0., 2.],
+        ]
+        .map(|[u, v]| [x + u, y + v]);
+
+        let face = Face::builder(Surface::xy_plane())
+            .with_exterior_polygon(polygon)
+            .build();
+        let solid = sweep(
+            Sketch::from_faces([face]),
+            [0., 0., 1.],
+            tolerance,
+            [255, 0, 0, 255],
+        )
+        .expect("Failed to sweep bracket");
+
+        solid.into_faces().into_iter().collect()
+    }
+
+    fn triangulate_instanced(
+        parts: Vec<Vec<Face>>,
+        tolerance: Tolerance,
+    ) -> anyhow::Result<super::InstancedMesh> {
+        Ok(super::triangulate_instanced(
+            parts,
+            tolerance,
+            &ErrorBudget::new(),
+            &mut ApproxStore::new(),
+            &mut DebugInfo::new(),
+        )?)
+    }
+}
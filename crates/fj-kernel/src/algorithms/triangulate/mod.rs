@@ -1,75 +1,487 @@
 mod delaunay;
+mod ear_clipping;
+mod instanced;
 mod polygon;
 mod ray;
 
-use fj_interop::{debug::DebugInfo, mesh::Mesh};
+use std::collections::HashSet;
+
+use fj_interop::{
+    debug::DebugInfo,
+    mesh::{FaceColoring, Mesh},
+};
 use fj_math::Point;
 
-use crate::objects::Face;
+use crate::{
+    iter::ObjectIters,
+    objects::{Curve, Face, Surface},
+};
+
+pub use self::instanced::{triangulate_instanced, Instance, InstancedMesh};
 
 use self::polygon::Polygon;
 
-use super::{FaceApprox, Tolerance};
+use super::{
+    ApproxStore, EffortBudget, ErrorBudget, ErrorBudgetExceeded, FaceApprox,
+    FaceSelector, Handedness, Tolerance,
+};
 
 /// Triangulate a shape
+///
+/// The triangles of each face are oriented such that their normals point along
+/// the face's outward normal (see [`Face`]). Each triangle records the index of
+/// its face, and the outward normal of the face at the triangle (see
+/// [`fj_interop::mesh::Triangle`]).
+///
+/// The error accumulated in `error_budget` is subtracted from `tolerance`
+/// before approximating the faces, so the resulting mesh still meets the
+/// requested tolerance. Returns an error, if the accumulated error already
+/// exhausts the tolerance.
+///
+/// Face approximations are taken from `approx_store`, so they can be shared
+/// with other algorithms.
+///
+/// Faces are always approximated within `tolerance`, unless they override it
+/// (see [`Face::with_tolerance`]). To trade accuracy for speed, pass an
+/// [`EffortBudget`] to [`triangulate_with_config`].
 pub fn triangulate(
     faces: Vec<Face>,
     tolerance: Tolerance,
+    error_budget: &ErrorBudget,
+    approx_store: &mut ApproxStore,
+    debug_info: &mut DebugInfo,
+) -> Result<Mesh<Point<3>>, TriangulationError> {
+    triangulate_with_config(
+        faces,
+        tolerance,
+        error_budget,
+        approx_store,
+        debug_info,
+        &TriangulationConfig::default(),
+    )
+}
+
+/// Triangulate a shape, using the given configuration
+///
+/// See [`triangulate`] for details. If the configuration limits the effort
+/// (see [`TriangulationConfig::effort`]), faces might be approximated with a
+/// coarser tolerance. Those are listed in [`Mesh::degraded_faces`].
+pub fn triangulate_with_config(
+    faces: Vec<Face>,
+    tolerance: Tolerance,
+    error_budget: &ErrorBudget,
+    approx_store: &mut ApproxStore,
     debug_info: &mut DebugInfo,
-) -> Mesh<Point<3>> {
+    config: &TriangulationConfig,
+) -> Result<Mesh<Point<3>>, TriangulationError> {
+    let tolerance = error_budget.remaining_tolerance(tolerance)?;
+
+    let span = fj_interop::span!(
+        INFO,
+        "triangulate",
+        faces = faces.len(),
+        tolerance = tolerance.inner().into_f64(),
+        triangles = tracing::field::Empty,
+    );
+
     let mut mesh = Mesh::new();
 
-    for face in faces {
-        if let Some(triangles) = face.triangles() {
-            for &(triangle, color) in triangles {
-                mesh.push_triangle(triangle.points(), color);
-            }
+    for (index, face) in faces.into_iter().enumerate() {
+        triangulate_face(
+            &face,
+            index,
+            tolerance,
+            approx_store,
+            debug_info,
+            config,
+            &mut mesh,
+        )?;
+    }
+
+    if config.face_coloring != FaceColoring::Original {
+        mesh = mesh.with_face_coloring(config.face_coloring);
+    }
+
+    fj_interop::record!(span, "triangles", mesh.triangle_count());
+
+    Ok(mesh)
+}
+
+/// Triangulate the selected faces of a shape
+///
+/// Only the faces that `selector` selects end up in the mesh. The triangles of
+/// each face are labeled with the index of the face within the whole shape, not
+/// just within the selection, so they can be related back to the shape.
+///
+/// The resulting mesh is usually open. Exporters need to be told explicitly to
+/// accept it. If any faces are left out, the mesh is marked as double-sided
+/// (see [`Mesh::is_double_sided`]).
+pub fn triangulate_selection<'r>(
+    shape: &'r impl ObjectIters<'r>,
+    selector: &FaceSelector,
+    tolerance: Tolerance,
+    debug_info: &mut DebugInfo,
+) -> Result<Mesh<Point<3>>, TriangulationError> {
+    let mut approx_store = ApproxStore::new();
+    let mut mesh = Mesh::new();
+
+    let mut is_partial = false;
+
+    for (index, face) in shape.face_iter().enumerate() {
+        if !selector.selects(index, face) {
+            is_partial = true;
             continue;
         }
 
-        let surface = face.surface();
-        let approx = FaceApprox::new(&face, tolerance);
-
-        let points: Vec<_> = approx.points.into_iter().collect();
-        let face_as_polygon = Polygon::new(*surface)
-            .with_exterior(
-                approx
-                    .exterior
-                    .points
-                    .into_iter()
-                    .map(|point| *point.local_form()),
-            )
-            .with_interiors(approx.interiors.into_iter().map(|interior| {
-                interior.points.into_iter().map(|point| *point.local_form())
-            }));
-
-        let mut triangles = delaunay::triangulate(points);
-        triangles.retain(|triangle| {
-            face_as_polygon.contains_triangle(
-                triangle.map(|point| *point.local_form()),
-                debug_info,
-            )
-        });
+        triangulate_face(
+            face,
+            index,
+            tolerance,
+            &mut approx_store,
+            debug_info,
+            &TriangulationConfig::default(),
+            &mut mesh,
+        )?;
+    }
+
+    // Part of a shape is an open surface, which can be seen from both sides.
+    mesh.set_double_sided(is_partial);
+
+    Ok(mesh)
+}
+
+pub(crate) fn triangulate_face(
+    face: &Face,
+    index: usize,
+    tolerance: Tolerance,
+    approx_store: &mut ApproxStore,
+    debug_info: &mut DebugInfo,
+    config: &TriangulationConfig,
+    mesh: &mut Mesh<Point<3>>,
+) -> Result<(), TriangulationError> {
+    let _span = fj_interop::span!(DEBUG, "triangulate_face", index);
+
+    if let Some(triangles) = face.triangles() {
+        for &(triangle, color) in triangles {
+            mesh.push_face_triangle(triangle.points(), color, index);
+
+            // Triangle faces have no boundary that could constrain them.
+            if let Some(report) = debug_info.mesh_quality_mut() {
+                report.push_triangle(index, triangle.points(), false);
+            }
+        }
+        return Ok(());
+    }
+
+    let tolerance = face.tolerance().unwrap_or(tolerance);
+    let achieved_tolerance = tolerance_within_budget(
+        face,
+        tolerance,
+        &config.effort,
+        mesh.triangle_count(),
+    );
+    if achieved_tolerance != tolerance {
+        mesh.mark_degraded(index, achieved_tolerance.inner());
+    }
+
+    let approx = approx_store.face_approx(face, achieved_tolerance);
+    triangulate_face_approx(face, index, &approx, debug_info, config, mesh)
+}
+
+/// Triangulate a face from an approximation of it
+///
+/// The face must not use the triangle representation.
+pub(crate) fn triangulate_face_approx(
+    face: &Face,
+    index: usize,
+    approx: &FaceApprox,
+    debug_info: &mut DebugInfo,
+    config: &TriangulationConfig,
+    mesh: &mut Mesh<Point<3>>,
+) -> Result<(), TriangulationError> {
+    let surface = face.surface();
+
+    // The triangles must face outward. Which way that is in surface
+    // coordinates is decided once for the whole face, from the winding of its
+    // exterior cycle.
+    let handedness =
+        Handedness::from_exterior_winding(approx.exterior.winding());
+
+    // The points come from a `HashSet`, whose order changes from run to run.
+    // If points are cocircular, the Delaunay triangulation depends on that
+    // order, so they are sorted to keep the mesh reproducible.
+    let mut points: Vec<_> = approx.points.iter().copied().collect();
+    points.sort();
+
+    let face_as_polygon = Polygon::new(surface.clone())
+        .with_exterior(
+            approx
+                .exterior
+                .points
+                .iter()
+                .map(|point| *point.local_form()),
+        )
+        .with_interiors(approx.interiors.iter().map(|interior| {
+            interior.points.iter().map(|point| *point.local_form())
+        }));
+
+    let exterior = approx.exterior.points.as_slice();
+    let interiors: Vec<_> = approx
+        .interiors
+        .iter()
+        .map(|interior| interior.points.as_slice())
+        .collect();
+
+    // Ear clipping only uses the boundary of the face. It can't replace the
+    // Delaunay triangulation, if there are other points.
+    let boundary: HashSet<_> = interiors
+        .iter()
+        .chain([&exterior])
+        .flat_map(|cycle| cycle.iter())
+        .collect();
+    let is_planar = {
+        let Surface::SweptCurve(surface) = surface;
+        matches!(surface.curve, Curve::Line(_))
+    };
+    let may_clip_ears = config.backend == TriangulationBackend::Auto
+        && is_planar
+        && approx.points.iter().all(|point| boundary.contains(point));
+
+    let clip_ears = match config.backend {
+        TriangulationBackend::Auto => {
+            may_clip_ears && boundary.len() > config.ear_clipping_threshold
+        }
+        TriangulationBackend::Delaunay => false,
+        TriangulationBackend::EarClipping => true,
+    };
+
+    let triangles = clip_ears
+        .then(|| ear_clipping::triangulate(exterior, &interiors, handedness))
+        .flatten();
+    let triangles = match triangles {
+        Some(triangles) => triangles,
+        None => {
+            // Containment checks are recorded into a local buffer, so there's
+            // no shared state while checking the triangles.
+            let mut local_debug_info = debug_info.local();
+
+            let mut triangles = delaunay::triangulate(points, handedness)?;
+            let num_triangles = triangles.len();
+            triangles.retain(|triangle| {
+                face_as_polygon.contains_triangle(
+                    triangle.map(|point| *point.local_form()),
+                    &mut local_debug_info,
+                )
+            });
+
+            debug_info.merge(local_debug_info);
+
+            // If most triangles are outside of the face, the points are
+            // probably pathological, and the remaining triangles might not
+            // cover the face properly.
+            let discarded = num_triangles - triangles.len();
+            let is_pathological = discarded as f64
+                > num_triangles as f64 * config.max_discarded_fraction;
+
+            if may_clip_ears && is_pathological {
+                ear_clipping::triangulate(exterior, &interiors, handedness)
+                    .unwrap_or(triangles)
+            } else {
+                triangles
+            }
+        }
+    };
+
+    if let Some(report) = debug_info.mesh_quality_mut() {
+        let boundary_edges: HashSet<_> = interiors
+            .iter()
+            .chain([&exterior])
+            .flat_map(|points| {
+                points
+                    .iter()
+                    .zip(points.iter().cycle().skip(1))
+                    .flat_map(|(a, b)| [(a, b), (b, a)])
+            })
+            .collect();
+
+        for triangle in &triangles {
+            let [a, b, c] = triangle;
+            let is_boundary = [(a, b), (b, c), (c, a)]
+                .iter()
+                .any(|edge| boundary_edges.contains(edge));
 
-        for triangle in triangles {
             let points = triangle.map(|point| *point.global_form());
-            mesh.push_triangle(points, face.color());
+            report.push_triangle(index, points, is_boundary);
         }
     }
 
-    mesh
+    for triangle in triangles {
+        let points = triangle.map(|point| *point.global_form());
+        let surface_coords = triangle.map(|point| *point.local_form());
+
+        // The normal is taken from the surface, not the triangle's points, so
+        // it is exact at the triangle's center, even if the triangle is thin.
+        let [a, b, c] = surface_coords;
+        let center = Point {
+            coords: (a.coords + b.coords + c.coords) / 3.,
+        };
+        let normal = handedness.outward(surface.normal_at(center));
+
+        mesh.push_face_triangle_with_normal(
+            points,
+            face.color(),
+            index,
+            surface_coords,
+            normal,
+        );
+    }
+
+    Ok(())
+}
+
+/// Determine the tolerance that a face can be approximated with
+///
+/// Returns `tolerance`, unless the effort budget requires a coarser one.
+/// `num_triangles` is the number of triangles created so far.
+fn tolerance_within_budget(
+    face: &Face,
+    tolerance: Tolerance,
+    effort: &EffortBudget,
+    num_triangles: usize,
+) -> Tolerance {
+    let max_points = if effort.is_exhausted(num_triangles) {
+        Some(0)
+    } else {
+        effort.max_points_per_face
+    };
+
+    match max_points {
+        Some(max_points) => {
+            FaceApprox::tolerance_for_max_points(face, tolerance, max_points)
+        }
+        None => tolerance,
+    }
+}
+
+/// Configures how faces are triangulated
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TriangulationConfig {
+    /// The algorithm that triangulates faces
+    pub backend: TriangulationBackend,
+
+    /// The number of boundary points, above which planar faces are
+    /// triangulated by ear clipping
+    ///
+    /// Only used by [`TriangulationBackend::Auto`].
+    pub ear_clipping_threshold: usize,
+
+    /// The fraction of Delaunay triangles that may be outside of a planar face
+    ///
+    /// If more triangles have to be discarded, the face is triangulated again
+    /// by ear clipping. Only used by [`TriangulationBackend::Auto`].
+    pub max_discarded_fraction: f64,
+
+    /// Limits the effort spent on approximating faces
+    ///
+    /// Unlimited by default, which means every face is approximated within
+    /// the requested tolerance.
+    pub effort: EffortBudget,
+
+    /// Overrides the colors of the faces, to tell them apart while debugging
+    ///
+    /// The original colors are kept by default. Hosts that switch between
+    /// colorings can also apply them to an existing mesh, using
+    /// [`Mesh::with_face_coloring`].
+    pub face_coloring: FaceColoring,
+}
+
+impl Default for TriangulationConfig {
+    fn default() -> Self {
+        Self {
+            backend: TriangulationBackend::default(),
+            ear_clipping_threshold: 1000,
+            max_discarded_fraction: 0.75,
+            effort: EffortBudget::default(),
+            face_coloring: FaceColoring::default(),
+        }
+    }
+}
+
+/// An algorithm that triangulates faces
+///
+/// Both algorithms only use the points that approximate the boundary of a
+/// face, and result in triangles that cover the same area.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TriangulationBackend {
+    /// Choose the algorithm for each face, depending on its shape
+    ///
+    /// Faces are triangulated by ear clipping, if they are planar and have
+    /// many boundary points, or if the Delaunay triangulation turns out to be
+    /// pathological (see [`TriangulationConfig`]). Otherwise, a Delaunay
+    /// triangulation is used.
+    #[default]
+    Auto,
+
+    /// Filter a Delaunay triangulation of the face's points
+    ///
+    /// Results in well-shaped triangles, but is slow for faces with many
+    /// points, as each triangle needs to be checked against the boundary.
+    Delaunay,
+
+    /// Clip ears off the polygon that approximates the face
+    ///
+    /// Falls back to a Delaunay triangulation, if the boundary of a face is
+    /// not a simple polygon.
+    EarClipping,
+}
+
+/// An error that can occur while triangulating
+#[derive(Debug, thiserror::Error)]
+pub enum TriangulationError {
+    /// Accumulated error exceeds the tolerance
+    #[error("Accumulated error exceeds the tolerance")]
+    ErrorBudget(#[from] ErrorBudgetExceeded),
+
+    /// A face has coordinates that can't be triangulated
+    ///
+    /// This happens, if coordinates are not finite, or too large or small.
+    #[error("Face has coordinates that can't be triangulated")]
+    InvalidCoordinates(#[from] spade::InsertionError),
 }
 
 #[cfg(test)]
 mod tests {
-    use fj_interop::{debug::DebugInfo, mesh::Mesh};
-    use fj_math::{Point, Scalar};
+    use std::{
+        collections::{BTreeMap, HashSet},
+        f64::consts::PI,
+        time::Instant,
+    };
+
+    use approx::assert_abs_diff_eq;
+    use fj_interop::{
+        debug::{CaptureLevel, DebugConfig, DebugInfo, DEFAULT_MEMORY_LIMIT},
+        mesh::{FaceColoring, Mesh},
+        physical_summary::PhysicalSummary,
+    };
+    use fj_math::{Point, Scalar, Transform, Vector};
 
     use crate::{
-        algorithms::Tolerance,
-        objects::{Face, Surface},
+        algorithms::{
+            sweep, ApproxStore, CycleApprox, EffortBudget, ErrorBudget,
+            FaceApprox, FaceSelector, Handedness, Tolerance, TransformObject,
+        },
+        objects::{Curve, Cycle, Edge, Face, Sketch, Solid, Surface},
+    };
+
+    use super::{
+        TriangulationBackend, TriangulationConfig, TriangulationError,
     };
 
+    /// The difference in coordinates that tests accept between triangles
+    ///
+    /// Only tolerates floating-point noise, not actual differences.
+    const EPSILON: f64 = 1e-12;
+
     #[test]
     fn simple() -> anyhow::Result<()> {
         let a = [0., 0.];
@@ -88,8 +500,8 @@ mod tests {
 
         let triangles = triangulate(face)?;
 
-        assert!(triangles.contains_triangle([a, b, d]));
-        assert!(triangles.contains_triangle([b, c, d]));
+        assert!(triangles.contains_triangle_approx([a, b, d], EPSILON));
+        assert!(triangles.contains_triangle_approx([b, c, d], EPSILON));
         assert!(!triangles.contains_triangle([a, b, c]));
         assert!(!triangles.contains_triangle([a, c, d]));
 
@@ -124,8 +536,8 @@ mod tests {
 
         // Should contain some triangles from the polygon. Don't need to test
         // them all.
-        assert!(triangles.contains_triangle([a, e, h]));
-        assert!(triangles.contains_triangle([a, d, h]));
+        assert!(triangles.contains_triangle_approx([a, e, h], EPSILON));
+        assert!(triangles.contains_triangle_approx([a, h, d], EPSILON));
 
         // Shouldn't contain any possible triangle from the hole.
         assert!(!triangles.contains_triangle([e, f, g]));
@@ -136,6 +548,134 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn polyline_edge() -> anyhow::Result<()> {
+        let surface = Surface::xy_plane();
+        let edge = Edge::polyline_from_points(
+            &surface,
+            [[0., 0.], [1., 0.], [1., 1.], [0., 1.], [0., 0.]],
+        );
+        let face =
+            Face::new(surface, [Cycle { edges: vec![edge] }], [], [0; 4]);
+
+        let triangles = triangulate(face)?;
+        assert_eq!(triangles.triangles().count(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn circular_hole_with_seam_on_delaunay_edge() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.05)?;
+
+        // The seam of the circle is at angle `0`, at `[1., 0.]`. The exterior
+        // has vertices to the left and right of it, on the same line.
+        let exterior = Face::builder(Surface::xy_plane())
+            .with_exterior_polygon([
+                [-2., -2.],
+                [2., -2.],
+                [2., 0.],
+                [2., 2.],
+                [-2., 2.],
+                [-2., 0.],
+            ])
+            .build()
+            .exteriors()
+            .next()
+            .cloned()
+            .expect("Face has an exterior cycle");
+        let hole = Cycle {
+            edges: vec![Edge::circle_from_radius(Scalar::ONE)],
+        };
+
+        let face = Face::new(
+            Surface::xy_plane(),
+            [exterior],
+            [hole.clone()],
+            [255, 0, 0, 255],
+        );
+
+        let mesh = super::triangulate(
+            vec![face],
+            tolerance,
+            &ErrorBudget::new(),
+            &mut ApproxStore::new(),
+            &mut DebugInfo::new(),
+        )?;
+
+        let hole: Vec<_> = CycleApprox::new(&hole, tolerance)
+            .points
+            .iter()
+            .map(|point| *point.local_form())
+            .collect();
+        for triangle in mesh.triangles() {
+            let [a, b, c] = triangle.points.map(|point| point.coords);
+            let centroid =
+                Point::from([(a.x + b.x + c.x) / 3., (a.y + b.y + c.y) / 3.]);
+
+            assert!(
+                !is_inside(centroid, &hole),
+                "Triangle {:?} is in the hole",
+                triangle.points,
+            );
+        }
+
+        // A crossing-number test, independent of the one under test.
+        fn is_inside(point: Point<2>, polygon: &[Point<2>]) -> bool {
+            let mut inside = false;
+            for edge in polygon.windows(2) {
+                let [a, b] = [edge[0], edge[1]];
+                if (a.v > point.v) != (b.v > point.v) {
+                    let u = a.u + (point.v - a.v) / (b.v - a.v) * (b.u - a.u);
+                    if point.u < u {
+                        inside = !inside;
+                    }
+                }
+            }
+            inside
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn stretched_surface() -> anyhow::Result<()> {
+        // A rhombus that is longer along the x-axis than along the y-axis. Its
+        // Delaunay triangulation uses the short diagonal, from b to d.
+        let a = Point::from([0., 0., 0.]);
+        let b = Point::from([2., -1., 0.]);
+        let c = Point::from([4., 0., 0.]);
+        let d = Point::from([2., 1., 0.]);
+
+        // Build the rhombus on a plane, whose u-axis is 10 units long. In its
+        // surface coordinates, the rhombus is longer along the v-axis.
+        let stretched = Surface::plane_from_points([
+            [0., 0., 0.],
+            [10., 0., 0.],
+            [0., 1., 0.],
+        ]);
+        let polygon = [[0., 0.], [0.2, -1.], [0.4, 0.], [0.2, 1.]];
+
+        let face = Face::builder(stretched.clone())
+            .with_exterior_polygon(polygon)
+            .build();
+        let triangles = triangulate(face)?;
+        assert!(triangles.contains_triangle_approx([a, b, d], EPSILON));
+        assert!(triangles.contains_triangle_approx([b, c, d], EPSILON));
+
+        // Without normalization, the triangulation operates in the stretched
+        // surface coordinates, and ends up with skinny triangles.
+        let face = Face::builder(stretched)
+            .with_exterior_polygon(polygon)
+            .without_surface_normalization()
+            .build();
+        let triangles = triangulate(face)?;
+        assert!(triangles.contains_triangle_approx([a, b, c], EPSILON));
+        assert!(triangles.contains_triangle_approx([a, c, d], EPSILON));
+
+        Ok(())
+    }
+
     #[ignore]
     #[test]
     fn sharp_concave_shape() -> anyhow::Result<()> {
@@ -170,19 +710,999 @@ mod tests {
         let d3 = d.to_xyz();
         let e3 = e.to_xyz();
 
-        assert!(triangles.contains_triangle([a3, b3, d3]));
-        assert!(triangles.contains_triangle([b3, c3, d3]));
-        assert!(triangles.contains_triangle([a3, d3, e3]));
+        assert!(triangles.contains_triangle_approx([a3, b3, d3], EPSILON));
+        assert!(triangles.contains_triangle_approx([b3, c3, d3], EPSILON));
+        assert!(triangles.contains_triangle_approx([a3, d3, e3], EPSILON));
 
         assert!(!triangles.contains_triangle([b3, e3, d3]));
 
         Ok(())
     }
 
+    #[test]
+    fn error_budget_reduces_tolerance() -> anyhow::Result<()> {
+        let face = circle_face();
+
+        let mut error_budget = ErrorBudget::new();
+        error_budget.record("healing", 0.4);
+
+        let mesh = super::triangulate(
+            vec![face.clone()],
+            Tolerance::from_scalar(1.)?,
+            &error_budget,
+            &mut ApproxStore::new(),
+            &mut DebugInfo::new(),
+        )?;
+        let expected = super::triangulate(
+            vec![face],
+            Tolerance::from_scalar(0.6)?,
+            &ErrorBudget::new(),
+            &mut ApproxStore::new(),
+            &mut DebugInfo::new(),
+        )?;
+
+        // A circle with a radius of 10 needs 7 segments to meet a tolerance of
+        // 1.0, but 10 segments to meet a tolerance of 0.6.
+        assert_eq!(mesh.vertices().count(), 10);
+        assert_eq!(mesh.vertices().count(), expected.vertices().count());
+
+        Ok(())
+    }
+
+    #[test]
+    fn face_overrides_tolerance() -> anyhow::Result<()> {
+        let face = circle_face().with_tolerance(Tolerance::from_scalar(1.)?);
+
+        let mesh = super::triangulate(
+            vec![face],
+            Tolerance::from_scalar(0.6)?,
+            &ErrorBudget::new(),
+            &mut ApproxStore::new(),
+            &mut DebugInfo::new(),
+        )?;
+
+        assert_eq!(mesh.vertices().count(), 7);
+
+        Ok(())
+    }
+
+    #[test]
+    fn error_budget_exceeded() -> anyhow::Result<()> {
+        let mut error_budget = ErrorBudget::new();
+        error_budget.record("healing", 1.5);
+
+        let result = super::triangulate(
+            vec![circle_face()],
+            Tolerance::from_scalar(1.)?,
+            &error_budget,
+            &mut ApproxStore::new(),
+            &mut DebugInfo::new(),
+        );
+
+        let err = match result {
+            Err(TriangulationError::ErrorBudget(err)) => err,
+            Err(err) => panic!("Unexpected error: {err}"),
+            Ok(_) => panic!("Error budget should be exceeded"),
+        };
+        assert_eq!(err.stage, "healing");
+        assert!(err.to_string().contains("healing"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn approximations_are_shared() -> anyhow::Result<()> {
+        let faces = vec![
+            Face::builder(Surface::xy_plane())
+                .with_exterior_polygon([[0., 0.], [1., 0.], [0., 1.]])
+                .build(),
+            Face::builder(Surface::xz_plane())
+                .with_exterior_polygon([[0., 0.], [1., 0.], [0., 1.]])
+                .build(),
+        ];
+        let tolerance = Tolerance::from_scalar(1.)?;
+
+        let mut approx_store = ApproxStore::new();
+        let mesh = super::triangulate(
+            faces.clone(),
+            tolerance,
+            &ErrorBudget::new(),
+            &mut approx_store,
+            &mut DebugInfo::new(),
+        )?;
+        assert_eq!(approx_store.stats().misses, 2);
+
+        // Another algorithm working with the same shape gets the same
+        // approximations, without computing them again.
+        for face in &faces {
+            let approx = approx_store.face_approx(face, tolerance);
+            assert_eq!(*approx, FaceApprox::new(face, tolerance));
+        }
+        assert_eq!(approx_store.stats().misses, 2);
+        assert_eq!(approx_store.stats().hits, 2);
+
+        let uncached = super::triangulate(
+            faces,
+            tolerance,
+            &ErrorBudget::new(),
+            &mut ApproxStore::new(),
+            &mut DebugInfo::new(),
+        )?;
+        assert_eq!(mesh.triangles().count(), uncached.triangles().count());
+        for triangle in mesh.triangles() {
+            assert!(uncached.contains_triangle_approx(triangle.points, EPSILON));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn selection() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(Scalar::ONE)?;
+
+        let face = Face::builder(Surface::xy_plane())
+            .with_exterior_polygon([[0., 0.], [1., 0.], [1., 1.], [0., 1.]])
+            .build();
+        let cube = sweep(
+            Sketch::from_faces([face]),
+            [0., 0., 2.],
+            tolerance,
+            [255, 0, 0, 255],
+        )?;
+
+        let mesh = super::triangulate_selection(
+            &cube,
+            &FaceSelector::normal([0., 0., 1.]),
+            tolerance,
+            &mut DebugInfo::new(),
+        )?;
+
+        assert_eq!(mesh.triangles().count(), 2);
+        assert!(mesh.is_double_sided());
+        for triangle in mesh.triangles() {
+            assert!(triangle.face.is_some());
+            for point in triangle.points {
+                assert_eq!(point.z, Scalar::from_f64(2.));
+            }
+        }
+
+        // The whole solid is closed, and only needs to be seen from outside.
+        let mesh = super::triangulate_selection(
+            &cube,
+            &FaceSelector::All,
+            tolerance,
+            &mut DebugInfo::new(),
+        )?;
+        assert!(!mesh.is_double_sided());
+
+        Ok(())
+    }
+
+    #[test]
+    fn triangles_face_outward() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let square = [[0., 0.], [1., 0.], [1., 1.], [0., 1.]];
+        let l_profile = [
+            [0., 0.],
+            [2., 0.],
+            [2., 0.5],
+            [0.5, 0.5],
+            [0.5, 2.],
+            [0., 2.],
+        ];
+
+        for polygon in [&square[..], &l_profile[..]] {
+            let face = Face::builder(Surface::xy_plane())
+                .with_exterior_polygon(polygon.to_vec())
+                .build();
+            let solid = sweep(
+                Sketch::from_faces([face]),
+                [0., 0., 1.],
+                tolerance,
+                [255, 0, 0, 255],
+            )?;
+            let faces: Vec<_> = solid.into_faces().into_iter().collect();
+
+            let mesh = super::triangulate(
+                faces.clone(),
+                tolerance,
+                &ErrorBudget::new(),
+                &mut ApproxStore::new(),
+                &mut DebugInfo::new(),
+            )?;
+
+            for triangle in mesh.triangles() {
+                let face = &faces[triangle.face.expect("Face index missing")];
+                let outward = outward_normal(face, tolerance);
+
+                let [a, b, c] = triangle.points;
+                let normal = (b - a).cross(&(c - a));
+                assert!(normal.dot(&outward) > Scalar::ZERO);
+                assert_abs_diff_eq!(triangle.normal, outward, epsilon = 1e-12);
+            }
+
+            // All triangles of a closed solid face outward, if it encloses a
+            // positive volume.
+            let summary = PhysicalSummary::from_mesh(&mesh, tolerance.inner());
+            assert!(summary.volume > Scalar::ZERO);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn normals_of_curved_faces() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let solid = sweep(
+            Sketch::from_faces([circle_face()]),
+            [0., 0., 1.],
+            tolerance,
+            [255, 0, 0, 255],
+        )?;
+        let mesh = super::triangulate(
+            solid.into_faces().into_iter().collect(),
+            tolerance,
+            &ErrorBudget::new(),
+            &mut ApproxStore::new(),
+            &mut DebugInfo::new(),
+        )?;
+
+        let mut num_side_triangles = 0;
+        for triangle in mesh.triangles() {
+            assert!(triangle.face.is_some());
+
+            let [a, b, c] = triangle.points;
+            let center = (a.coords + b.coords + c.coords) / 3.;
+            if center.z == Scalar::ZERO || center.z == Scalar::ONE {
+                continue;
+            }
+            num_side_triangles += 1;
+
+            // The normals of the side are those of the cylinder, which are
+            // horizontal and point away from its axis.
+            let radial =
+                Vector::from([center.x, center.y, Scalar::ZERO]).normalize();
+            assert_abs_diff_eq!(triangle.normal.z, Scalar::ZERO);
+            assert_abs_diff_eq!(
+                triangle.normal.magnitude(),
+                Scalar::ONE,
+                epsilon = 1e-12
+            );
+            assert!(triangle.normal.dot(&radial) > Scalar::from(0.99));
+        }
+        assert!(num_side_triangles > 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn normals_of_triangle_faces() -> anyhow::Result<()> {
+        let points =
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]].map(Point::from);
+        let face = Face::from_triangles(vec![(
+            fj_math::Triangle::from_points(points),
+            [255, 0, 0, 255],
+        )]);
+
+        let mesh = triangulate(face)?;
+        let triangle = mesh.triangle(0).expect("Triangle is missing");
+        assert_eq!(triangle.face, Some(0));
+        assert_eq!(triangle.normal, Vector::unit_z());
+
+        Ok(())
+    }
+
+    #[test]
+    fn cube_shares_vertices() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let cube = Solid::cube_from_edge_length(1.);
+        let mesh = super::triangulate(
+            cube.into_faces().into_iter().collect(),
+            tolerance,
+            &ErrorBudget::new(),
+            &mut ApproxStore::new(),
+            &mut DebugInfo::new(),
+        )?;
+
+        // Adjacent faces share their vertices, instead of each face adding
+        // its own copies.
+        assert_eq!(mesh.triangles().count(), 12);
+        assert_eq!(mesh.vertices().count(), 8);
+        assert_eq!(mesh.indices().count(), 36);
+
+        Ok(())
+    }
+
+    #[test]
+    fn handedness_decides_winding() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        // A clockwise exterior cycle, so the face points along the negative
+        // z-axis. It ends up as the bottom face of the cube, which keeps that
+        // left-handed frame.
+        let face = Face::builder(Surface::xy_plane())
+            .with_exterior_polygon([[0., 0.], [0., 1.], [1., 1.], [1., 0.]])
+            .build();
+        let cube = sweep(
+            Sketch::from_faces([face]),
+            [0., 0., 1.],
+            tolerance,
+            [255, 0, 0, 255],
+        )?;
+        let mirrored = cube
+            .clone()
+            .transform(&Transform::scale([-1., 1., 1.]))
+            .translate([3., 0., 0.]);
+
+        for (solid, center) in
+            [(cube, [0.5, 0.5, 0.5]), (mirrored, [2.5, 0.5, 0.5])]
+        {
+            let faces: Vec<_> = solid.into_faces().into_iter().collect();
+
+            // Mirroring flips the handedness of every face.
+            let handedness: Vec<_> = faces
+                .iter()
+                .map(|face| Handedness::of_face(face, tolerance))
+                .collect();
+            assert!(handedness.contains(&Some(Handedness::Left)));
+            assert!(handedness.contains(&Some(Handedness::Right)));
+
+            let mesh = super::triangulate(
+                faces.clone(),
+                tolerance,
+                &ErrorBudget::new(),
+                &mut ApproxStore::new(),
+                &mut DebugInfo::new(),
+            )?;
+
+            // Triangulation used to flip triangles that pointed against the
+            // outward normal of their face. That correction must never be
+            // necessary.
+            for triangle in mesh.triangles() {
+                let face = &faces[triangle.face.expect("Face index missing")];
+                let [a, b, c] = triangle.points;
+                let normal = (b - a).cross(&(c - a));
+
+                let outward = outward_normal(face, tolerance);
+                assert!(normal.dot(&outward) > Scalar::ZERO);
+
+                // The cube is convex, so every triangle faces away from its
+                // center.
+                let centroid = Point {
+                    coords: (a.coords + b.coords + c.coords) / 3.,
+                };
+                let center = Point::from(center);
+                assert!(normal.dot(&(centroid - center)) > Scalar::ZERO);
+            }
+
+            let summary = PhysicalSummary::from_mesh(&mesh, tolerance.inner());
+            assert!(summary.volume > Scalar::ZERO);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn reversed_winding() -> anyhow::Result<()> {
+        // A clockwise exterior cycle, so the face points along the negative
+        // z-axis, opposite to the normal of its surface.
+        let face = Face::builder(Surface::xy_plane())
+            .with_exterior_polygon([[0., 0.], [0., 2.], [2., 2.], [2., 0.]])
+            .build();
+
+        let mesh = triangulate(face)?;
+
+        assert_eq!(mesh.triangles().count(), 2);
+        for triangle in mesh.triangles() {
+            let [a, b, c] = triangle.points;
+            assert!((b - a).cross(&(c - a)).z < Scalar::ZERO);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn scale_invariance() -> anyhow::Result<()> {
+        let reference = triangle_topology(1.)?;
+        assert!(!reference.is_empty());
+
+        for scale in [1e-6, 1e6] {
+            assert_eq!(triangle_topology(scale)?, reference);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn uniform_scaling_preserves_topology() -> anyhow::Result<()> {
+        let reference = triangle_topology(1.)?;
+
+        for exponent in -12..=12 {
+            for mantissa in [1., 1.5, 3.7] {
+                let scale = mantissa * 10f64.powi(exponent);
+                assert_eq!(
+                    triangle_topology(scale)?,
+                    reference,
+                    "Topology changed at scale {scale}"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Triangulate a concave face with a hole, scaled by `scale`
+    ///
+    /// Returns the triangles as sorted indices into the face's points, so the
+    /// results of different scales can be compared.
+    fn triangle_topology(scale: f64) -> anyhow::Result<Vec<[usize; 3]>> {
+        // None of the points are cocircular, so the Delaunay triangulation is
+        // unique, and doesn't depend on rounding.
+        let exterior = [[0., 0.], [4., 0.], [4., 4.], [2., 1.], [0., 4.]];
+        let interior = [[1., 0.5], [3., 0.6], [2.1, 0.8]];
+
+        let scaled = |points: &[[f64; 2]]| -> Vec<Point<2>> {
+            points
+                .iter()
+                .map(|&[u, v]| Point::from([u * scale, v * scale]))
+                .collect()
+        };
+        let exterior = scaled(&exterior);
+        let interior = scaled(&interior);
+
+        let face = Face::builder(Surface::xy_plane())
+            .with_exterior_polygon(exterior.clone())
+            .with_interior_polygon(interior.clone())
+            .build();
+
+        let points: Vec<_> = exterior
+            .into_iter()
+            .chain(interior)
+            .map(|point| point.to_xyz())
+            .collect();
+
+        let mut topology = triangulate(face)?
+            .triangles()
+            .map(|triangle| {
+                let mut indices = triangle.points.map(|point| {
+                    points
+                        .iter()
+                        .position(|&p| p == point)
+                        .expect("Triangle point is not a point of the face")
+                });
+                indices.sort_unstable();
+                indices
+            })
+            .collect::<Vec<_>>();
+        topology.sort_unstable();
+
+        Ok(topology)
+    }
+
+    #[test]
+    fn debug_capture_levels() -> anyhow::Result<()> {
+        // A fine approximation of a circle results in many containment checks.
+        let tolerance = Tolerance::from_scalar(1e-4)?;
+        let record = |level, memory_limit| -> anyhow::Result<DebugInfo> {
+            let mut debug_info = DebugInfo::with_config(DebugConfig {
+                level,
+                memory_limit,
+                ..DebugConfig::default()
+            });
+            super::triangulate(
+                vec![circle_face()],
+                tolerance,
+                &ErrorBudget::new(),
+                &mut ApproxStore::new(),
+                &mut debug_info,
+            )?;
+            Ok(debug_info)
+        };
+
+        let full = record(CaptureLevel::Full, DEFAULT_MEMORY_LIMIT)?;
+        let num_checks = full.num_triangle_edge_checks();
+        assert!(num_checks >= 500);
+        assert_eq!(full.triangle_edge_checks().count(), num_checks);
+
+        let sampled = record(
+            CaptureLevel::SampledDetail { every_nth: 10 },
+            DEFAULT_MEMORY_LIMIT,
+        )?;
+        let num_sampled = sampled.triangle_edge_checks().count();
+        assert_eq!(sampled.num_triangle_edge_checks(), num_checks);
+        assert!(num_sampled * 5 <= num_checks);
+        assert!(num_sampled * 20 >= num_checks);
+
+        let off = record(CaptureLevel::Off, DEFAULT_MEMORY_LIMIT)?;
+        assert_eq!(off.num_triangle_edge_checks(), 0);
+        assert_eq!(off.triangle_edge_checks().count(), 0);
+        assert_eq!(off.memory_usage(), 0);
+
+        // Far too little memory to record everything.
+        let memory_limit = 4096;
+        let capped = record(CaptureLevel::Full, memory_limit)?;
+        assert!(capped.memory_usage() <= memory_limit);
+        assert!(capped.num_dropped() > 0);
+        assert_eq!(
+            capped.triangle_edge_checks().count() + capped.num_dropped(),
+            num_checks
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn mesh_quality_report() -> anyhow::Result<()> {
+        let face = Face::builder(Surface::xy_plane())
+            .with_exterior_polygon([[0., 0.], [1., 0.], [1., 1.], [0., 1.]])
+            .build();
+
+        let triangulate = |mesh_quality| -> anyhow::Result<DebugInfo> {
+            let mut debug_info = DebugInfo::with_config(DebugConfig {
+                mesh_quality,
+                ..DebugConfig::default()
+            });
+            super::triangulate(
+                vec![face.clone()],
+                Tolerance::from_scalar(Scalar::ONE)?,
+                &ErrorBudget::new(),
+                &mut ApproxStore::new(),
+                &mut debug_info,
+            )?;
+            Ok(debug_info)
+        };
+
+        assert!(triangulate(false)?.mesh_quality().is_empty());
+
+        let debug_info = triangulate(true)?;
+        let report = debug_info.mesh_quality();
+        let stats = report.face(0).expect("Expected stats for face");
+        assert_eq!(stats, report.total());
+
+        assert_eq!(stats.num_triangles(), 2);
+        assert_eq!(stats.num_boundary(), 2);
+        assert_eq!(stats.num_poor(), 0);
+
+        let value = |value: Option<f64>| value.expect("Expected triangles");
+        assert_abs_diff_eq!(value(stats.min_angle()), 45., epsilon = 1e-9);
+        assert_abs_diff_eq!(value(stats.max_angle()), 90., epsilon = 1e-9);
+        assert_abs_diff_eq!(
+            value(stats.max_edge_length()),
+            2f64.sqrt(),
+            epsilon = 1e-9
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn non_uniformly_scaled_circle() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.1)?;
+
+        let face = circle_face().transform(&Transform::scale([3., 1., 1.]));
+
+        let exterior =
+            face.exteriors().next().expect("Face has no exterior cycle");
+        let ellipse = match exterior.edges[0].curve().global_form() {
+            Curve::Ellipse(ellipse) => *ellipse,
+            curve => panic!("Expected ellipse, got {curve:?}"),
+        };
+
+        // Every segment of the approximation must stay within the tolerance
+        // of the part of the ellipse it replaces.
+        let approx = CycleApprox::new(exterior, tolerance);
+        for segment in approx.segments() {
+            let [a, b] = segment.points();
+            let t_a = ellipse.point_to_ellipse_coords(a).t;
+            let mut t_b = ellipse.point_to_ellipse_coords(b).t;
+            if t_b <= t_a {
+                t_b += Scalar::PI * 2.;
+            }
+
+            for i in 0..=16 {
+                let t = t_a + (t_b - t_a) * (f64::from(i) / 16.);
+                let point = ellipse.point_from_ellipse_coords([t]);
+
+                let direction = (b - a).normalize();
+                let offset = point - a;
+                let distance =
+                    (offset - direction * offset.dot(&direction)).magnitude();
+                assert!(distance <= tolerance.inner());
+            }
+        }
+
+        let mesh = super::triangulate(
+            vec![face],
+            tolerance,
+            &ErrorBudget::new(),
+            &mut ApproxStore::new(),
+            &mut DebugInfo::new(),
+        )?;
+        assert!(mesh.triangles().count() > 0);
+        for point in mesh.vertices() {
+            let t = ellipse.point_to_ellipse_coords(point);
+            assert_abs_diff_eq!(
+                ellipse.point_from_ellipse_coords(t),
+                point,
+                epsilon = 1e-9
+            );
+        }
+
+        Ok(())
+    }
+
+    /// The outward normal of a planar face, according to its definition
+    #[test]
+    fn ear_clipping_many_points() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let face = circle_polygon_face(10_000);
+
+        let start = Instant::now();
+        let mesh =
+            triangulate_with(face.clone(), tolerance, Default::default())?;
+        let ear_clipping_time = start.elapsed();
+
+        assert_valid_triangulation(&face, &mesh, tolerance);
+        assert_eq!(mesh.triangles().count(), 10_000 - 2);
+
+        // The face has enough points to be triangulated by ear clipping.
+        let ear_clipping = triangulate_with(
+            face,
+            tolerance,
+            TriangulationConfig {
+                backend: TriangulationBackend::EarClipping,
+                ..Default::default()
+            },
+        )?;
+        assert!(mesh.triangles().eq(ear_clipping.triangles()));
+
+        // To keep the test fast, the Delaunay triangulation only gets a fifth
+        // of the points. Ear clipping still needs to be faster.
+        let start = Instant::now();
+        triangulate_with(
+            circle_polygon_face(2_000),
+            tolerance,
+            TriangulationConfig {
+                backend: TriangulationBackend::Delaunay,
+                ..Default::default()
+            },
+        )?;
+        let delaunay_time = start.elapsed();
+
+        assert!(
+            ear_clipping_time < delaunay_time,
+            "Ear clipping took {ear_clipping_time:?}, Delaunay took \
+            {delaunay_time:?}"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn backends_result_in_valid_triangulations() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.05)?;
+
+        let square = [[-2., -2.], [2., -2.], [2., 2.], [-2., 2.]];
+        let faces = [
+            Face::builder(Surface::xy_plane())
+                .with_exterior_polygon(square)
+                .build(),
+            Face::builder(Surface::xy_plane())
+                .with_exterior_polygon([
+                    [0., 0.],
+                    [2., 0.],
+                    [2., 0.5],
+                    [0.5, 0.5],
+                    [0.5, 2.],
+                    [0., 2.],
+                ])
+                .build(),
+            Face::builder(Surface::xy_plane())
+                .with_exterior_polygon(square)
+                .with_interior_polygon([
+                    [-1., -1.],
+                    [1., -1.],
+                    [1., 1.],
+                    [-1., 0.],
+                ])
+                .build(),
+            circle_face(),
+            Face::new(
+                Surface::xy_plane(),
+                Face::builder(Surface::xy_plane())
+                    .with_exterior_polygon(square)
+                    .build()
+                    .exteriors()
+                    .cloned(),
+                [Cycle {
+                    edges: vec![Edge::circle_from_radius(Scalar::ONE)],
+                }],
+                [255, 0, 0, 255],
+            ),
+        ];
+
+        for face in faces {
+            for backend in [
+                TriangulationBackend::Delaunay,
+                TriangulationBackend::EarClipping,
+            ] {
+                let mesh = triangulate_with(
+                    face.clone(),
+                    tolerance,
+                    TriangulationConfig {
+                        backend,
+                        ..Default::default()
+                    },
+                )?;
+                assert_valid_triangulation(&face, &mesh, tolerance);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn effort_budget() -> anyhow::Result<()> {
+        // Approximating this circle within the tolerance would take about a
+        // million points.
+        let edge = Edge::circle_from_radius(Scalar::from_f64(1000.));
+        let cycle = Cycle { edges: vec![edge] };
+        let face =
+            Face::new(Surface::xy_plane(), [cycle], [], [255, 0, 0, 255]);
+        let tolerance = Tolerance::from_scalar(5e-9)?;
+
+        let max_points = 10_000;
+        let mesh = triangulate_with(
+            face.clone(),
+            tolerance,
+            TriangulationConfig {
+                effort: EffortBudget {
+                    max_points_per_face: Some(max_points),
+                    ..EffortBudget::default()
+                },
+                ..Default::default()
+            },
+        )?;
+
+        let degraded: Vec<_> = mesh.degraded_faces().collect();
+        assert_eq!(degraded.len(), 1);
+        let (index, achieved) = degraded[0];
+        assert_eq!(index, 0);
+        assert!(achieved > tolerance.inner());
+
+        // The face is still covered completely, just more coarsely.
+        let achieved = Tolerance::from_scalar(achieved)?;
+        assert_valid_triangulation(&face, &mesh, achieved);
+        assert!(mesh.triangle_count() < max_points);
+        assert!(mesh.triangle_count() > max_points / 10);
+
+        Ok(())
+    }
+
+    #[test]
+    fn effort_budget_exhausted() -> anyhow::Result<()> {
+        let face = circle_face();
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let mesh = triangulate_with(
+            face,
+            tolerance,
+            TriangulationConfig {
+                effort: EffortBudget {
+                    deadline: Some(Instant::now()),
+                    ..EffortBudget::default()
+                },
+                ..Default::default()
+            },
+        )?;
+
+        // The deadline has passed, so the face is approximated as coarsely as
+        // possible.
+        assert!(mesh.is_degraded(0));
+        assert_eq!(mesh.triangle_count(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn effort_budget_unlimited() -> anyhow::Result<()> {
+        let face = circle_face();
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let unlimited = triangulate_with(
+            face.clone(),
+            tolerance,
+            TriangulationConfig {
+                effort: EffortBudget {
+                    max_points_per_face: Some(1_000_000),
+                    ..EffortBudget::default()
+                },
+                ..Default::default()
+            },
+        )?;
+        let default = triangulate_with(face, tolerance, Default::default())?;
+
+        assert_eq!(unlimited.degraded_faces().count(), 0);
+        assert!(unlimited.triangles().eq(default.triangles()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn distinct_face_colors() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        let config = TriangulationConfig {
+            face_coloring: FaceColoring::Distinct,
+            ..Default::default()
+        };
+
+        let colors = |height: f64| -> anyhow::Result<Vec<_>> {
+            let face = Face::builder(Surface::xy_plane())
+                .with_exterior_polygon([[0., 0.], [1., 0.], [1., 1.], [0., 1.]])
+                .build();
+            let cube = sweep(
+                Sketch::from_faces([face]),
+                [0., 0., height],
+                tolerance,
+                [255, 0, 0, 255],
+            )?;
+
+            let mesh = super::triangulate_with_config(
+                cube.into_faces().into_iter().collect(),
+                tolerance,
+                &ErrorBudget::new(),
+                &mut ApproxStore::new(),
+                &mut DebugInfo::new(),
+                &config,
+            )?;
+
+            let mut colors = BTreeMap::new();
+            for triangle in mesh.triangles() {
+                let face = triangle.face.expect("Triangle records its face");
+                assert_eq!(
+                    *colors.entry(face).or_insert(triangle.color),
+                    triangle.color
+                );
+            }
+            Ok(colors.into_values().collect())
+        };
+
+        let cube = colors(1.)?;
+        assert_eq!(cube.len(), 6);
+        assert_eq!(cube.iter().collect::<HashSet<_>>().len(), 6);
+
+        // Moving the top face doesn't change the colors of any face.
+        assert_eq!(colors(2.)?, cube);
+
+        Ok(())
+    }
+
+    fn triangulate_with(
+        face: Face,
+        tolerance: Tolerance,
+        config: TriangulationConfig,
+    ) -> anyhow::Result<Mesh<Point<3>>> {
+        Ok(super::triangulate_with_config(
+            vec![face],
+            tolerance,
+            &ErrorBudget::new(),
+            &mut ApproxStore::new(),
+            &mut DebugInfo::new(),
+            &config,
+        )?)
+    }
+
+    /// A face in the xy-plane, bounded by a polygon with points on a circle
+    fn circle_polygon_face(num_points: usize) -> Face {
+        let points = (0..num_points).map(|i| {
+            let angle = PI * 2. * i as f64 / num_points as f64;
+            [angle.cos() * 10., angle.sin() * 10.]
+        });
+
+        Face::builder(Surface::xy_plane())
+            .with_exterior_polygon(points.collect::<Vec<_>>())
+            .build()
+    }
+
+    /// Check the triangulation of a face in the xy-plane
+    ///
+    /// The triangles must face up, cover the area of the face exactly, and
+    /// share their edges with each other, except at the face's boundary.
+    fn assert_valid_triangulation(
+        face: &Face,
+        mesh: &Mesh<Point<3>>,
+        tolerance: Tolerance,
+    ) {
+        let approx = FaceApprox::new(face, tolerance);
+
+        let mut expected_area = Scalar::ZERO;
+        let mut boundary = HashSet::new();
+        for (i, cycle) in Some(&approx.exterior)
+            .into_iter()
+            .chain(&approx.interiors)
+            .enumerate()
+        {
+            let points: Vec<_> = cycle
+                .points
+                .iter()
+                .map(|point| *point.global_form())
+                .collect();
+
+            let mut area = Scalar::ZERO;
+            for (j, &a) in points.iter().enumerate() {
+                let b = points[(j + 1) % points.len()];
+                area += (a.x * b.y - b.x * a.y) / 2.;
+                if a != b {
+                    boundary.insert(if a < b { [a, b] } else { [b, a] });
+                }
+            }
+
+            if i == 0 {
+                expected_area += area.abs();
+            } else {
+                expected_area = expected_area - area.abs();
+            }
+        }
+
+        let mut area = Scalar::ZERO;
+        let mut edges = BTreeMap::new();
+        for triangle in mesh.triangles() {
+            let [a, b, c] = triangle.points;
+            let normal = (b - a).cross(&(c - a));
+            assert!(normal.z > Scalar::ZERO, "Invalid triangle {triangle:?}");
+
+            area += normal.z / 2.;
+            for edge in [[a, b], [b, c], [c, a]] {
+                *edges.entry(edge).or_insert(0) += 1;
+            }
+        }
+        assert!((area - expected_area).abs() < expected_area * 1e-9);
+
+        for (&[a, b], &count) in &edges {
+            assert_eq!(count, 1, "Edge {:?} is used more than once", [a, b]);
+
+            let edge = if a < b { [a, b] } else { [b, a] };
+            let is_shared = edges.contains_key(&[b, a]);
+            assert!(
+                is_shared != boundary.contains(&edge),
+                "Edge {:?} is not shared, or on the boundary",
+                [a, b],
+            );
+        }
+        for [a, b] in boundary {
+            assert!(edges.contains_key(&[a, b]) || edges.contains_key(&[b, a]));
+        }
+    }
+
+    fn outward_normal(face: &Face, tolerance: Tolerance) -> Vector<3> {
+        let normal = face.surface().normal_at([0., 0.]);
+
+        Handedness::of_face(face, tolerance)
+            .expect("Face uses triangle representation")
+            .outward(normal)
+    }
+
+    fn circle_face() -> Face {
+        let edge = Edge::circle_from_radius(Scalar::from_f64(10.));
+        let cycle = Cycle { edges: vec![edge] };
+
+        Face::new(Surface::xy_plane(), [cycle], [], [255, 0, 0, 255])
+    }
+
     fn triangulate(face: Face) -> anyhow::Result<Mesh<Point<3>>> {
         let tolerance = Tolerance::from_scalar(Scalar::ONE)?;
 
         let mut debug_info = DebugInfo::new();
-        Ok(super::triangulate(vec![face], tolerance, &mut debug_info))
+        Ok(super::triangulate(
+            vec![face],
+            tolerance,
+            &ErrorBudget::new(),
+            &mut ApproxStore::new(),
+            &mut debug_info,
+        )?)
     }
 }
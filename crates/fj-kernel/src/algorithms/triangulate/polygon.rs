@@ -1,4 +1,4 @@
-use fj_interop::debug::{DebugInfo, TriangleEdgeCheck};
+use fj_interop::debug::DebugInfo;
 use fj_math::{Point, PolyChain, Segment};
 
 use crate::objects::Surface;
@@ -7,8 +7,8 @@ use super::ray::{Hit, HorizontalRayToTheRight};
 
 pub struct Polygon {
     surface: Surface,
-    exterior: PolyChain<2>,
-    interiors: Vec<PolyChain<2>>,
+    exterior: Vec<Segment<2>>,
+    interiors: Vec<Vec<Segment<2>>>,
 }
 
 impl Polygon {
@@ -30,13 +30,13 @@ impl Polygon {
     pub fn new(surface: Surface) -> Self {
         Self {
             surface,
-            exterior: PolyChain::new(),
+            exterior: Vec::new(),
             interiors: Vec::new(),
         }
     }
 
     pub fn with_exterior(mut self, exterior: impl Into<PolyChain<2>>) -> Self {
-        self.exterior = exterior.into();
+        self.exterior = cycle_segments(exterior.into());
         self
     }
 
@@ -44,16 +44,24 @@ impl Polygon {
         mut self,
         interiors: impl IntoIterator<Item = impl Into<PolyChain<2>>>,
     ) -> Self {
-        self.interiors.extend(interiors.into_iter().map(Into::into));
+        self.interiors.extend(
+            interiors
+                .into_iter()
+                .map(|interior| cycle_segments(interior.into())),
+        );
         self
     }
 
     #[cfg(test)]
     pub fn invert_winding(mut self) -> Self {
-        self.exterior = self.exterior.reverse();
-
-        for interior in &mut self.interiors {
-            *interior = interior.clone().reverse();
+        for cycle in Some(&mut self.exterior)
+            .into_iter()
+            .chain(&mut self.interiors)
+        {
+            cycle.reverse();
+            for segment in cycle.iter_mut() {
+                *segment = segment.reverse();
+            }
         }
 
         self
@@ -115,21 +123,21 @@ impl Polygon {
     }
 
     pub fn contains_exterior_edge(&self, edge: Segment<2>) -> bool {
-        self.exterior.segments().contains(&edge)
-            || self.exterior.segments().contains(&edge.reverse())
+        self.exterior.contains(&edge) || self.exterior.contains(&edge.reverse())
     }
 
     pub fn contains_interior_edge(&self, edge: Segment<2>) -> bool {
-        let mut contains = false;
-
-        for chain in &self.interiors {
-            contains |= chain.segments().contains(&edge);
-            contains |= chain.segments().contains(&edge.reverse());
-        }
-
-        contains
+        self.interiors.iter().any(|cycle| {
+            cycle.contains(&edge) || cycle.contains(&edge.reverse())
+        })
     }
 
+    /// Determine whether the polygon contains the point
+    ///
+    /// None of the checks involved use a fixed epsilon. Comparisons against
+    /// vertices are exact and the side of a segment is determined using a
+    /// robust orientation predicate, so the result doesn't depend on the scale
+    /// of the polygon.
     pub fn contains_point(
         &self,
         point: impl Into<Point<2>>,
@@ -139,15 +147,13 @@ impl Polygon {
             origin: point.into(),
         };
 
-        let mut check = TriangleEdgeCheck::new(
-            self.surface.point_from_surface_coords(ray.origin),
-        );
+        let mut check = debug_info.begin_triangle_edge_check(|| {
+            self.surface.point_from_surface_coords(ray.origin)
+        });
 
         let mut num_hits = 0;
 
-        for chain in Some(&self.exterior).into_iter().chain(&self.interiors) {
-            let edges = chain.segments();
-
+        for edges in Some(&self.exterior).into_iter().chain(&self.interiors) {
             // We need to properly detect the ray passing the boundary at the
             // "seam" of the polygon, i.e. the vertex between the last and the
             // first segment. The logic in the loop properly takes care of that,
@@ -158,7 +164,7 @@ impl Polygon {
                 .copied()
                 .and_then(|edge| ray.hits_segment(edge));
 
-            for edge in edges {
+            for &edge in edges {
                 let hit = ray.hits_segment(edge);
 
                 let count_hit = match (hit, previous_hit) {
@@ -199,27 +205,57 @@ impl Polygon {
                 if count_hit {
                     num_hits += 1;
 
-                    let edge =
-                        Segment::from_points(edge.points().map(|point| {
-                            self.surface.point_from_surface_coords(point)
-                        }));
-                    check.hits.push(edge);
+                    if let Some(check) = &mut check {
+                        let edge =
+                            Segment::from_points(edge.points().map(|point| {
+                                self.surface.point_from_surface_coords(point)
+                            }));
+                        check.hits.push(edge);
+                    }
                 }
 
                 previous_hit = hit;
             }
         }
 
-        debug_info.triangle_edge_checks.push(check);
+        if let Some(check) = check {
+            debug_info.push_triangle_edge_check(check);
+        }
 
         num_hits % 2 == 1
     }
 }
 
+/// Convert a cycle into the segments that bound it
+///
+/// Every cycle is normalized the same way, so the segment that closes the
+/// cycle is indistinguishable from any other segment:
+///
+/// - Consecutive duplicate points are removed, as they don't form a segment.
+/// - If the last point repeats the first, it is removed. The closing segment
+///   is then added explicitly, whether the cycle was closed before or not.
+fn cycle_segments(cycle: PolyChain<2>) -> Vec<Segment<2>> {
+    let mut points = cycle.points().to_vec();
+    points.dedup();
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+
+    if points.len() < 2 {
+        return Vec::new();
+    }
+
+    (0..points.len())
+        .map(|i| {
+            Segment::from_points([points[i], points[(i + 1) % points.len()]])
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use fj_interop::debug::DebugInfo;
-    use fj_math::{Point, PolyChain};
+    use fj_math::{Point, PolyChain, Segment};
 
     use crate::objects::Surface;
 
@@ -242,6 +278,35 @@ mod tests {
         assert!(!polygon.contains_triangle([d, e, f], &mut DebugInfo::new()));
     }
 
+    #[test]
+    fn closing_segment_of_interior() {
+        let a = [0., 0.];
+        let b = [3., 0.];
+        let c = [0., 3.];
+
+        let d = [1., 1.];
+        let e = [2., 1.];
+        let f = [1., 2.];
+
+        // However the hole is specified, its closing segment must be an edge
+        // like any other.
+        for interior in [
+            PolyChain::from([d, e, f]).close(),
+            PolyChain::from([d, e, f]),
+            PolyChain::from([d, d, e, f, f, d]),
+        ] {
+            let polygon = Polygon::new(Surface::xy_plane())
+                .with_exterior(PolyChain::from([a, b, c]).close())
+                .with_interiors([interior]);
+
+            assert!(polygon.contains_interior_edge(Segment::from([f, d])));
+            assert!(polygon.contains_interior_edge(Segment::from([d, f])));
+            assert!(
+                !polygon.contains_triangle([d, e, f], &mut DebugInfo::new())
+            );
+        }
+    }
+
     #[test]
     fn contains_point_ray_hits_vertex_while_passing_outside() {
         let a = [0., 0.];
@@ -0,0 +1,321 @@
+use fj_math::{Point, Scalar, Vector};
+
+use crate::objects::{Curve, Cycle, Face, Surface};
+
+use super::{
+    boolean_2d::{combine, signed_area, Operation, Region},
+    boolean_chain::is_same_surface,
+    intersection::surface_surface,
+    BooleanError, CycleApprox, IntersectionTolerances, Tolerance,
+};
+
+/// Compute the union of two faces in the same or parallel surfaces
+///
+/// If both faces lie in the same plane, the result are the faces that bound
+/// the union of both, in the surface of `a`, and with the color of `a`. The
+/// faces may touch or overlap, or be disjoint. Faces in the same non-planar
+/// surface are supported too, if both surfaces are parametrized the same way
+/// (see [`BooleanChain::apply`]).
+///
+/// If the surfaces are parallel, but don't coincide, the faces can't overlap,
+/// and both are returned as they are.
+///
+/// # Limitations
+///
+/// The cycles of the faces are approximated within `tolerance`, so curved
+/// edges become polygonal in the result. Returns an error, if the surfaces
+/// intersect, which isn't supported yet.
+///
+/// [`BooleanChain::apply`]: super::BooleanChain::apply
+pub fn union_coplanar(
+    a: &Face,
+    b: &Face,
+    tolerance: Tolerance,
+    tolerances: &IntersectionTolerances,
+) -> Result<Vec<Face>, BooleanError> {
+    if a.triangles().is_some() || b.triangles().is_some() {
+        return Err(BooleanError::TriangleRepresentation);
+    }
+
+    let surface = a.surface();
+    let epsilon = tolerances.coincidence_distance;
+
+    let polygons_a: Vec<_> = a
+        .all_cycles()
+        .map(|cycle| local_points(cycle, tolerance))
+        .collect();
+    let polygons_b: Vec<_> =
+        if is_same_surface(surface, b.surface(), tolerances) {
+            b.all_cycles()
+                .map(|cycle| local_points(cycle, tolerance))
+                .collect()
+        } else {
+            let plane = match Plane::from_surface(surface) {
+                Some(plane) if Plane::from_surface(b.surface()).is_some() => {
+                    plane
+                }
+                _ => return Err(BooleanError::DifferentSurfaces),
+            };
+            if surface_surface(surface, b.surface(), tolerances).is_some() {
+                return Err(BooleanError::IntersectingSurfaces);
+            }
+
+            let origin_b = b.surface().point_from_surface_coords([0., 0.]);
+            if plane.distance(origin_b) > epsilon {
+                return Ok(vec![a.clone(), b.clone()]);
+            }
+
+            b.all_cycles()
+                .map(|cycle| {
+                    CycleApprox::new(cycle, tolerance)
+                        .points
+                        .into_iter()
+                        .map(|point| plane.project(*point.global_form()))
+                        .collect()
+                })
+                .collect()
+        };
+
+    // The result winds the same way as `a`, so it faces the same direction.
+    let is_ccw = a
+        .exteriors()
+        .next()
+        .map(|cycle| signed_area(&local_points(cycle, tolerance)))
+        .map_or(true, |area| area >= Scalar::ZERO);
+
+    let region_a = Region::new(polygons_a, a.exteriors().count());
+    let region_b = Region::new(polygons_b, b.exteriors().count());
+    let polygons = combine(&region_a, &region_b, Operation::Union, epsilon);
+
+    let to_cycle = |mut points: Vec<Point<2>>| {
+        if !is_ccw {
+            points.reverse();
+        }
+        Cycle::polygon_from_points(surface, points)
+    };
+
+    let faces = polygons
+        .into_iter()
+        .map(|(exterior, interiors)| {
+            Face::new(
+                surface.clone(),
+                [to_cycle(exterior)],
+                interiors.into_iter().map(to_cycle),
+                a.color(),
+            )
+        })
+        .collect();
+
+    Ok(faces)
+}
+
+/// A plane, used to express points of another surface in a planar surface
+struct Plane {
+    origin: Point<3>,
+    u: Vector<3>,
+    v: Vector<3>,
+}
+
+impl Plane {
+    fn from_surface(surface: &Surface) -> Option<Self> {
+        let Surface::SweptCurve(surface) = surface;
+        match &surface.curve {
+            Curve::Line(line) => Some(Self {
+                origin: line.origin,
+                u: line.direction,
+                v: surface.path,
+            }),
+            _ => None,
+        }
+    }
+
+    fn distance(&self, point: Point<3>) -> Scalar {
+        let normal = self.u.cross(&self.v).normalize();
+        (point - self.origin).dot(&normal).abs()
+    }
+
+    /// Compute the surface coordinates of a point's projection into the plane
+    fn project(&self, point: Point<3>) -> Point<2> {
+        // The axes are not necessarily orthogonal, so this solves the normal
+        // equations, instead of just projecting onto each axis.
+        let d = point - self.origin;
+        let [uu, uv, vv] = [
+            self.u.dot(&self.u),
+            self.u.dot(&self.v),
+            self.v.dot(&self.v),
+        ];
+        let [ud, vd] = [self.u.dot(&d), self.v.dot(&d)];
+
+        let det = uu * vv - uv * uv;
+        Point::from([(ud * vv - vd * uv) / det, (vd * uu - ud * uv) / det])
+    }
+}
+
+fn local_points(cycle: &Cycle, tolerance: Tolerance) -> Vec<Point<2>> {
+    CycleApprox::new(cycle, tolerance)
+        .points
+        .into_iter()
+        .map(|point| *point.local_form())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Scalar;
+
+    use crate::{
+        algorithms::{
+            BooleanError, IntersectionTolerances, Tolerance, TransformObject,
+        },
+        iter::ObjectIters,
+        objects::{Cycle, Face, Surface},
+    };
+
+    use super::{local_points, signed_area, union_coplanar};
+
+    #[test]
+    fn overlapping_squares() -> anyhow::Result<()> {
+        let a = square([0., 0.], 2.);
+        let b = square([1., 1.], 2.);
+
+        let faces = union(&a, &b)?;
+        assert_eq!(faces.len(), 1);
+
+        // An L-shape, with a notch: 8 corners.
+        let face = &faces[0];
+        assert_eq!(face.exteriors().count(), 1);
+        assert_eq!(face.interiors().count(), 0);
+        assert_eq!(face.edge_iter().count(), 8);
+        assert_eq!(area(face), 7.);
+
+        Ok(())
+    }
+
+    #[test]
+    fn adjacent_squares() -> anyhow::Result<()> {
+        // Two boxes that sit next to each other on the same plane.
+        let a = square([0., 0.], 1.);
+        let b = square([1., 0.], 1.);
+
+        let faces = union(&a, &b)?;
+        assert_eq!(faces.len(), 1);
+        assert_eq!(faces[0].edge_iter().count(), 4);
+        assert_eq!(area(&faces[0]), 2.);
+
+        Ok(())
+    }
+
+    #[test]
+    fn disjoint_squares() -> anyhow::Result<()> {
+        let a = square([0., 0.], 1.);
+        let b = square([2., 0.], 1.);
+
+        let faces = union(&a, &b)?;
+        assert_eq!(faces.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn enclosed_hole() -> anyhow::Result<()> {
+        // A U-shape, closed by a bar, encloses a hole.
+        let u = Face::builder(Surface::xy_plane())
+            .with_exterior_polygon([
+                [0., 0.],
+                [3., 0.],
+                [3., 3.],
+                [2., 3.],
+                [2., 1.],
+                [1., 1.],
+                [1., 3.],
+                [0., 3.],
+            ])
+            .build();
+        let bar = Face::builder(Surface::xy_plane())
+            .with_exterior_polygon([[0., 2.], [3., 2.], [3., 3.], [0., 3.]])
+            .build();
+
+        let faces = union(&u, &bar)?;
+        assert_eq!(faces.len(), 1);
+        assert_eq!(faces[0].interiors().count(), 1);
+        assert_eq!(area(&faces[0]), 8.);
+
+        Ok(())
+    }
+
+    #[test]
+    fn same_plane_different_parametrization() -> anyhow::Result<()> {
+        let a = square([0., 0.], 2.);
+
+        // The square is in a plane whose origin is offset, and whose normal
+        // points the other way.
+        let surface = Surface::plane_from_points([
+            [1., 1., 0.],
+            [1., 2., 0.],
+            [2., 1., 0.],
+        ]);
+        let b = Face::builder(surface)
+            .with_exterior_polygon([[0., 0.], [2., 0.], [2., 2.], [0., 2.]])
+            .build();
+
+        let faces = union(&a, &b)?;
+        assert_eq!(faces.len(), 1);
+        assert_eq!(area(&faces[0]), 7.);
+
+        Ok(())
+    }
+
+    #[test]
+    fn parallel_planes() -> anyhow::Result<()> {
+        let a = square([0., 0.], 2.);
+        let b = square([1., 1.], 2.).translate([0., 0., 1.]);
+
+        let faces = union(&a, &b)?;
+        assert_eq!(faces, vec![a, b]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn intersecting_planes() {
+        let a = square([0., 0.], 2.);
+        let b = Face::builder(Surface::xz_plane())
+            .with_exterior_polygon([[0., 0.], [1., 0.], [1., 1.], [0., 1.]])
+            .build();
+
+        assert!(matches!(
+            union(&a, &b),
+            Err(BooleanError::IntersectingSurfaces)
+        ));
+    }
+
+    fn union(a: &Face, b: &Face) -> Result<Vec<Face>, BooleanError> {
+        let tolerance =
+            Tolerance::from_scalar(0.01).expect("Tolerance is positive");
+        union_coplanar(a, b, tolerance, &IntersectionTolerances::default())
+    }
+
+    fn square([x, y]: [f64; 2], size: f64) -> Face {
+        Face::builder(Surface::xy_plane())
+            .with_exterior_polygon([
+                [x, y],
+                [x + size, y],
+                [x + size, y + size],
+                [x, y + size],
+            ])
+            .build()
+    }
+
+    fn area(face: &Face) -> f64 {
+        let tolerance =
+            Tolerance::from_scalar(0.01).expect("Tolerance is positive");
+        let area = |sum: Scalar, cycle: &Cycle| {
+            let points = local_points(cycle, tolerance);
+            sum + signed_area(&points).abs()
+        };
+
+        let exteriors = face.exteriors().fold(Scalar::ZERO, area);
+        let interiors = face.interiors().fold(Scalar::ZERO, area);
+        (exteriors - interiors).into_f64()
+    }
+}
@@ -1,16 +1,17 @@
 //! Convenient API to build objects
 
-use fj_math::Point;
+use fj_math::{Arc, Circle, Point, Scalar, Transform2};
 
-use crate::objects::{Cycle, Face, Surface};
+use crate::objects::{Cycle, Edge, Face, Surface};
 
 /// API for building a [`Face`]
 #[must_use]
 pub struct FaceBuilder {
     surface: Surface,
-    exterior: Option<Vec<Point<2>>>,
+    exterior: Option<Boundary>,
     interiors: Vec<Vec<Point<2>>>,
     color: Option<[u8; 4]>,
+    normalize_surface: bool,
 }
 
 impl FaceBuilder {
@@ -21,6 +22,7 @@ impl FaceBuilder {
             exterior: None,
             interiors: Vec::new(),
             color: None,
+            normalize_surface: true,
         }
     }
 
@@ -32,7 +34,30 @@ impl FaceBuilder {
         let points = points.into_iter().map(Into::into).collect();
 
         Self {
-            exterior: Some(points),
+            exterior: Some(Boundary::Polygon(points)),
+            ..self
+        }
+    }
+
+    /// Make the exterior of the face a path of lines and arcs
+    ///
+    /// The path starts at `start`, and each segment continues from the end of
+    /// the previous one. If the last segment doesn't end at `start`, the path
+    /// is closed with a line segment. To close it with an arc instead, end the
+    /// last arc at `start`.
+    ///
+    /// Arcs result in edges on actual circles, not in polyline
+    /// approximations. See [`PathSegment`] for details.
+    pub fn with_exterior_path(
+        self,
+        start: impl Into<Point<2>>,
+        segments: impl IntoIterator<Item = PathSegment>,
+    ) -> Self {
+        let start = start.into();
+        let segments = segments.into_iter().collect();
+
+        Self {
+            exterior: Some(Boundary::Path(start, segments)),
             ..self
         }
     }
@@ -56,19 +81,56 @@ impl FaceBuilder {
         self
     }
 
+    /// Keep the surface's parameterization as it is
+    ///
+    /// By default, the face is built on the [normalized] version of the
+    /// surface, and the polygon points are converted accordingly. This doesn't
+    /// change the face's shape, but makes its surface coordinates isometric,
+    /// which helps algorithms that work in surface coordinates, like
+    /// triangulation.
+    ///
+    /// [normalized]: Surface::normalized
+    pub fn without_surface_normalization(mut self) -> Self {
+        self.normalize_surface = false;
+        self
+    }
+
     /// Build the face
     pub fn build(self) -> Face {
-        let surface = self.surface;
+        let (surface, transform) = if self.normalize_surface {
+            (
+                self.surface.normalized(),
+                self.surface.normalization_transform(),
+            )
+        } else {
+            (self.surface, Transform2::identity())
+        };
+
+        let convert = |points: Vec<Point<2>>| {
+            points
+                .into_iter()
+                .map(|point| transform.transform_point(&point))
+                .collect::<Vec<_>>()
+        };
 
         let mut exteriors = Vec::new();
-        if let Some(points) = self.exterior {
-            let cycle = Cycle::polygon_from_points(&self.surface, points);
-            exteriors.push(cycle);
+        match self.exterior {
+            Some(Boundary::Polygon(points)) => {
+                let cycle =
+                    Cycle::polygon_from_points(&surface, convert(points));
+                exteriors.push(cycle);
+            }
+            Some(Boundary::Path(start, segments)) => {
+                let cycle =
+                    path_to_cycle(&surface, &transform, start, segments);
+                exteriors.push(cycle);
+            }
+            None => {}
         }
 
         let mut interiors = Vec::new();
         for points in self.interiors {
-            let cycle = Cycle::polygon_from_points(&self.surface, points);
+            let cycle = Cycle::polygon_from_points(&surface, convert(points));
             interiors.push(cycle);
         }
 
@@ -77,3 +139,243 @@ impl FaceBuilder {
         Face::new(surface, exteriors, interiors, color)
     }
 }
+
+/// A segment of a path, as passed to [`FaceBuilder::with_exterior_path`]
+///
+/// Each segment starts where the previous one ends, so only its end point is
+/// defined here.
+#[derive(Clone, Copy, Debug)]
+pub enum PathSegment {
+    /// A line segment to the given point
+    Line(Point<2>),
+
+    /// A circular arc to the given point
+    ///
+    /// Of the arcs with the given radius that connect the start and end point,
+    /// `clockwise` and `large_arc` select the one that runs in the given
+    /// direction, and that sweeps through more or less than 180°, respectively.
+    ///
+    /// If the radius is too small to connect the points, the arc is still
+    /// built, but its vertices don't lie on its circle. The resulting face
+    /// fails coherence validation.
+    Arc {
+        /// The point at which the arc ends
+        end: Point<2>,
+
+        /// The radius of the arc
+        radius: Scalar,
+
+        /// Whether the arc runs clockwise
+        clockwise: bool,
+
+        /// Whether the arc sweeps through more than 180°
+        large_arc: bool,
+    },
+}
+
+impl PathSegment {
+    /// Construct a line segment to the given point
+    pub fn line(end: impl Into<Point<2>>) -> Self {
+        Self::Line(end.into())
+    }
+
+    /// Construct an arc to the given point
+    ///
+    /// The arc sweeps through 180° at most. Use [`PathSegment::Arc`] directly
+    /// to construct a larger one.
+    pub fn arc(
+        end: impl Into<Point<2>>,
+        radius: impl Into<Scalar>,
+        clockwise: bool,
+    ) -> Self {
+        Self::Arc {
+            end: end.into(),
+            radius: radius.into(),
+            clockwise,
+            large_arc: false,
+        }
+    }
+
+    /// Access the point at which the segment ends
+    pub fn end(&self) -> Point<2> {
+        match self {
+            Self::Line(end) => *end,
+            Self::Arc { end, .. } => *end,
+        }
+    }
+}
+
+enum Boundary {
+    Polygon(Vec<Point<2>>),
+    Path(Point<2>, Vec<PathSegment>),
+}
+
+fn path_to_cycle(
+    surface: &Surface,
+    transform: &Transform2,
+    start: Point<2>,
+    mut segments: Vec<PathSegment>,
+) -> Cycle {
+    if let Some(last) = segments.last() {
+        if last.end() != start {
+            segments.push(PathSegment::Line(start));
+        }
+    }
+
+    let mut edges = Vec::new();
+    let mut previous = start;
+
+    for segment in segments {
+        let end = segment.end();
+        let points =
+            [previous, end].map(|point| transform.transform_point(&point));
+
+        let edge = match segment {
+            PathSegment::Line(_) => {
+                Edge::line_segment_from_points(surface, points)
+            }
+            PathSegment::Arc {
+                radius,
+                clockwise,
+                large_arc,
+                ..
+            } => {
+                // `Arc::from_endpoints` scales up a radius that is too small,
+                // but we want the resulting edge to fail validation instead.
+                // With the original radius, the vertices don't lie on the
+                // circle then.
+                let arc = Arc::from_endpoints(
+                    previous, end, radius, large_arc, !clockwise,
+                );
+                let circle = Arc {
+                    radius: radius.abs(),
+                    ..arc
+                }
+                .to_circle();
+
+                // The surface coordinates of the face might be normalized, so
+                // we need to convert the circle too. This is exact, even if the
+                // normalization is not isometric.
+                let circle = Circle {
+                    center: transform.transform_point(&circle.center),
+                    a: transform.transform_vector(&circle.a),
+                    b: transform.transform_vector(&circle.b),
+                };
+
+                Edge::circle_section(
+                    surface,
+                    circle,
+                    arc.sweep_angle.abs(),
+                    points,
+                )
+            }
+        };
+
+        edges.push(edge);
+        previous = end;
+    }
+
+    Cycle { edges }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        algorithms::{CycleApprox, Tolerance},
+        objects::{Curve, Face, Surface},
+        validation::{validate, ValidationConfig, ValidationError},
+    };
+
+    use super::PathSegment;
+
+    #[test]
+    fn exterior_path() -> anyhow::Result<()> {
+        // A rounded square. The last arc closes the path.
+        let face = Face::builder(Surface::xy_plane())
+            .with_exterior_path(
+                [1., 0.],
+                [
+                    PathSegment::line([2., 0.]),
+                    PathSegment::arc([3., 1.], 1., false),
+                    PathSegment::line([3., 2.]),
+                    PathSegment::arc([2., 3.], 1., false),
+                    PathSegment::line([1., 3.]),
+                    PathSegment::arc([0., 2.], 1., false),
+                    PathSegment::line([0., 1.]),
+                    PathSegment::arc([1., 0.], 1., false),
+                ],
+            )
+            .build();
+
+        let cycle = face.exteriors().next().unwrap();
+        assert_eq!(cycle.edges.len(), 8);
+
+        let num_circles = cycle
+            .edges()
+            .filter(|edge| {
+                matches!(edge.curve().global_form(), Curve::Circle(_))
+            })
+            .count();
+        assert_eq!(num_circles, 4);
+
+        validate(face, &ValidationConfig::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn exterior_path_large_arc() -> anyhow::Result<()> {
+        // Three quarters of a circle around `[1., 1.]`, closed by a line.
+        let face = Face::builder(Surface::xy_plane())
+            .with_exterior_path(
+                [1., 0.],
+                [PathSegment::Arc {
+                    end: Point::from([0., 1.]),
+                    radius: Scalar::ONE,
+                    clockwise: false,
+                    large_arc: true,
+                }],
+            )
+            .build();
+
+        let cycle = face.exteriors().next().unwrap();
+        assert_eq!(cycle.edges.len(), 2);
+
+        let tolerance = Tolerance::from_scalar(0.01)?;
+        let approx = CycleApprox::new(cycle, tolerance);
+
+        let center = Point::from([1., 1.]);
+        for point in &approx.points {
+            let distance = (*point.local_form() - center).magnitude();
+            assert!((distance - Scalar::ONE).abs() < Scalar::from(1e-9));
+        }
+
+        // The arc runs around the far side of the circle, and is approximated
+        // according to the tolerance there.
+        assert!(approx.points.len() > 10);
+        assert!(approx
+            .points
+            .iter()
+            .any(|point| point.local_form().u > Scalar::from(1.9)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn exterior_path_radius_too_small() {
+        let face = Face::builder(Surface::xy_plane())
+            .with_exterior_path(
+                [0., 0.],
+                [
+                    PathSegment::arc([4., 0.], 1., false),
+                    PathSegment::line([4., 4.]),
+                ],
+            )
+            .build();
+
+        let result = validate(face, &ValidationConfig::default());
+        assert!(matches!(result, Err(ValidationError::Coherence(_))));
+    }
+}
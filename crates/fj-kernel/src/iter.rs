@@ -1,125 +1,188 @@
 //! API for iterating over the objects of a shape, or part of a shape
 
-use std::collections::VecDeque;
+use std::{collections::HashSet, hash::Hash, iter::Filter, mem::size_of};
 
-use crate::objects::{
-    Curve, Cycle, Edge, Face, GlobalVertex, Sketch, Solid, Surface, Vertex,
+use fj_interop::{memory::MemoryReport, mesh::Color};
+use fj_math::{Aabb, Triangle};
+
+use crate::{
+    algorithms::Tolerance,
+    objects::{
+        Curve, Cycle, Edge, Face, GlobalVertex, Sketch, Solid, Surface, Vertex,
+    },
 };
 
 /// Access iterators over all objects of a shape, or part of it
 ///
 /// Implemented for all object types. An implementation must return itself, in
 /// addition to any other objects it references.
+///
+/// Objects are values, not handles into a shared store. The iterators yield
+/// each object only once, even if it is part of the shape multiple times, as
+/// determined by exact structural equality. Many faces on the same surface, for
+/// example, result in a single surface.
+///
+/// Objects are yielded in the order in which they are first encountered.
+/// Duplicates are detected by hashing, so the cost of iterating is roughly
+/// linear in the size of the shape.
+///
+/// The iterators are lazy. The shape is traversed while iterating, so methods
+/// like [`Iterator::find`] stop traversing it, once they have found an object.
 pub trait ObjectIters<'r> {
     /// Return all objects being referenced
     fn referenced_objects(&'r self) -> Vec<&'r dyn ObjectIters>;
 
     /// Iterate over all curves
-    fn curve_iter(&'r self) -> Iter<&'r Curve<3>> {
-        let mut iter = Iter::empty();
-
-        for object in self.referenced_objects() {
-            iter = iter.with(object.curve_iter());
-        }
-
-        iter
+    fn curve_iter(&'r self) -> Iter<'r, &'r Curve<3>> {
+        Iter::from_references(self.referenced_objects(), |object| {
+            object.curve_iter()
+        })
     }
 
     /// Iterate over all cycles
-    fn cycle_iter(&'r self) -> Iter<&'r Cycle> {
-        let mut iter = Iter::empty();
-
-        for object in self.referenced_objects() {
-            iter = iter.with(object.cycle_iter());
-        }
-
-        iter
+    fn cycle_iter(&'r self) -> Iter<'r, &'r Cycle> {
+        Iter::from_references(self.referenced_objects(), |object| {
+            object.cycle_iter()
+        })
     }
 
     /// Iterate over all edges
-    fn edge_iter(&'r self) -> Iter<&'r Edge> {
-        let mut iter = Iter::empty();
-
-        for object in self.referenced_objects() {
-            iter = iter.with(object.edge_iter());
-        }
-
-        iter
+    fn edge_iter(&'r self) -> Iter<'r, &'r Edge> {
+        Iter::from_references(self.referenced_objects(), |object| {
+            object.edge_iter()
+        })
     }
 
     /// Iterate over all faces
-    fn face_iter(&'r self) -> Iter<&'r Face> {
-        let mut iter = Iter::empty();
+    fn face_iter(&'r self) -> Iter<'r, &'r Face> {
+        Iter::from_references(self.referenced_objects(), |object| {
+            object.face_iter()
+        })
+    }
 
-        for object in self.referenced_objects() {
-            iter = iter.with(object.face_iter());
+    /// Iterate over the faces that satisfy a predicate
+    fn filter_faces<P>(&'r self, predicate: P) -> Filter<Iter<'r, &'r Face>, P>
+    where
+        Self: Sized,
+        P: FnMut(&&'r Face) -> bool,
+    {
+        self.face_iter().filter(predicate)
+    }
+
+    /// Iterate over the faces that intersect an AABB
+    ///
+    /// Faces whose bounding box (see [`Face::aabb`]) doesn't intersect `aabb`
+    /// are skipped. Faces that straddle the boundary of `aabb` are included.
+    /// The bounding box of each face is computed once, when the iterator
+    /// reaches the face.
+    fn faces_in_aabb(
+        &'r self,
+        aabb: &Aabb<3>,
+        tolerance: Tolerance,
+    ) -> FacesInAabb<'r> {
+        FacesInAabb {
+            faces: self.face_iter(),
+            aabb: *aabb,
+            tolerance,
         }
-
-        iter
     }
 
     /// Iterate over all global vertices
-    fn global_vertex_iter(&'r self) -> Iter<&'r GlobalVertex> {
-        let mut iter = Iter::empty();
-
-        for object in self.referenced_objects() {
-            iter = iter.with(object.global_vertex_iter());
-        }
-
-        iter
+    fn global_vertex_iter(&'r self) -> Iter<'r, &'r GlobalVertex> {
+        Iter::from_references(self.referenced_objects(), |object| {
+            object.global_vertex_iter()
+        })
     }
 
     /// Iterate over all sketches
-    fn sketch_iter(&'r self) -> Iter<&'r Sketch> {
-        let mut iter = Iter::empty();
-
-        for object in self.referenced_objects() {
-            iter = iter.with(object.sketch_iter());
-        }
-
-        iter
+    fn sketch_iter(&'r self) -> Iter<'r, &'r Sketch> {
+        Iter::from_references(self.referenced_objects(), |object| {
+            object.sketch_iter()
+        })
     }
 
     /// Iterate over all solids
-    fn solid_iter(&'r self) -> Iter<&'r Solid> {
-        let mut iter = Iter::empty();
-
-        for object in self.referenced_objects() {
-            iter = iter.with(object.solid_iter());
-        }
-
-        iter
+    fn solid_iter(&'r self) -> Iter<'r, &'r Solid> {
+        Iter::from_references(self.referenced_objects(), |object| {
+            object.solid_iter()
+        })
     }
 
     /// Iterate over all surfaces
-    fn surface_iter(&'r self) -> Iter<&'r Surface> {
-        let mut iter = Iter::empty();
-
-        for object in self.referenced_objects() {
-            iter = iter.with(object.surface_iter());
-        }
-
-        iter
+    fn surface_iter(&'r self) -> Iter<'r, &'r Surface> {
+        Iter::from_references(self.referenced_objects(), |object| {
+            object.surface_iter()
+        })
     }
 
     /// Iterator over all vertices
-    fn vertex_iter(&'r self) -> Iter<&'r Vertex> {
-        let mut iter = Iter::empty();
-
-        for object in self.referenced_objects() {
-            iter = iter.with(object.vertex_iter());
-        }
+    fn vertex_iter(&'r self) -> Iter<'r, &'r Vertex> {
+        Iter::from_references(self.referenced_objects(), |object| {
+            object.vertex_iter()
+        })
+    }
+
+    /// Estimate the memory used by all objects
+    ///
+    /// Reports the number of objects of each kind, as yielded by the
+    /// iterators, and their estimated size. The size includes the points of
+    /// polylines and the triangles of faces that are made of triangles, which
+    /// are stored outside of the objects themselves.
+    ///
+    /// Since objects that are part of the shape multiple times are counted
+    /// once, this underestimates the memory used by shapes that contain many
+    /// copies of the same object.
+    fn memory_report(&'r self) -> MemoryReport {
+        let mut report = MemoryReport::new();
+
+        add_objects(&mut report, "curves", self.curve_iter(), |curve| {
+            curve.heap_size()
+        });
+        add_objects(&mut report, "cycles", self.cycle_iter(), |_| 0);
+        add_objects(&mut report, "edges", self.edge_iter(), |edge| {
+            edge.curve().local_form().heap_size()
+        });
+        add_objects(&mut report, "faces", self.face_iter(), |face| {
+            face.triangles().map_or(0, |triangles| {
+                triangles.len() * size_of::<(Triangle<3>, Color)>()
+            })
+        });
+        add_objects(
+            &mut report,
+            "global vertices",
+            self.global_vertex_iter(),
+            |_| 0,
+        );
+        add_objects(&mut report, "sketches", self.sketch_iter(), |_| 0);
+        add_objects(&mut report, "solids", self.solid_iter(), |_| 0);
+        add_objects(&mut report, "surfaces", self.surface_iter(), |surface| {
+            let Surface::SweptCurve(surface) = surface;
+            surface.curve.heap_size()
+        });
+        add_objects(&mut report, "vertices", self.vertex_iter(), |_| 0);
 
-        iter
+        report
     }
 }
 
+fn add_objects<'r, T: 'r>(
+    report: &mut MemoryReport,
+    kind: &'static str,
+    objects: impl Iterator<Item = &'r T>,
+    heap_size: impl Fn(&T) -> usize,
+) {
+    let (count, bytes) = objects.fold((0, 0), |(count, bytes), object| {
+        (count + 1, bytes + size_of::<T>() + heap_size(object))
+    });
+    report.add(kind, count, bytes);
+}
+
 impl<'r> ObjectIters<'r> for Curve<3> {
     fn referenced_objects(&'r self) -> Vec<&'r dyn ObjectIters> {
         Vec::new()
     }
 
-    fn curve_iter(&'r self) -> Iter<&'r Curve<3>> {
+    fn curve_iter(&'r self) -> Iter<'r, &'r Curve<3>> {
         Iter::from_object(self)
     }
 }
@@ -135,7 +198,7 @@ impl<'r> ObjectIters<'r> for Cycle {
         objects
     }
 
-    fn cycle_iter(&'r self) -> Iter<&'r Cycle> {
+    fn cycle_iter(&'r self) -> Iter<'r, &'r Cycle> {
         Iter::from_object(self)
     }
 }
@@ -151,7 +214,7 @@ impl<'r> ObjectIters<'r> for Edge {
         objects
     }
 
-    fn edge_iter(&'r self) -> Iter<&'r Edge> {
+    fn edge_iter(&'r self) -> Iter<'r, &'r Edge> {
         Iter::from_object(self)
     }
 }
@@ -171,7 +234,7 @@ impl<'r> ObjectIters<'r> for Face {
         objects
     }
 
-    fn face_iter(&'r self) -> Iter<&'r Face> {
+    fn face_iter(&'r self) -> Iter<'r, &'r Face> {
         Iter::from_object(self)
     }
 }
@@ -181,7 +244,7 @@ impl<'r> ObjectIters<'r> for GlobalVertex {
         Vec::new()
     }
 
-    fn global_vertex_iter(&'r self) -> Iter<&'r GlobalVertex> {
+    fn global_vertex_iter(&'r self) -> Iter<'r, &'r GlobalVertex> {
         Iter::from_object(self)
     }
 }
@@ -197,7 +260,7 @@ impl<'r> ObjectIters<'r> for Sketch {
         objects
     }
 
-    fn sketch_iter(&'r self) -> Iter<&'r Sketch> {
+    fn sketch_iter(&'r self) -> Iter<'r, &'r Sketch> {
         Iter::from_object(self)
     }
 }
@@ -213,7 +276,7 @@ impl<'r> ObjectIters<'r> for Solid {
         objects
     }
 
-    fn solid_iter(&'r self) -> Iter<&'r Solid> {
+    fn solid_iter(&'r self) -> Iter<'r, &'r Solid> {
         Iter::from_object(self)
     }
 }
@@ -223,7 +286,7 @@ impl<'r> ObjectIters<'r> for Surface {
         Vec::new()
     }
 
-    fn surface_iter(&'r self) -> Iter<&'r Surface> {
+    fn surface_iter(&'r self) -> Iter<'r, &'r Surface> {
         Iter::from_object(self)
     }
 }
@@ -233,7 +296,7 @@ impl<'r> ObjectIters<'r> for Vertex {
         vec![self.global() as &dyn ObjectIters]
     }
 
-    fn vertex_iter(&'r self) -> Iter<&'r Vertex> {
+    fn vertex_iter(&'r self) -> Iter<'r, &'r Vertex> {
         Iter::from_object(self)
     }
 }
@@ -259,48 +322,128 @@ where
 /// An iterator over objects
 ///
 /// See [`ObjectIters`].
-pub struct Iter<T>(VecDeque<T>);
+pub struct Iter<'r, T> {
+    /// The objects and references that have yet to be visited
+    ///
+    /// This is a stack, so the next object to visit is the last one.
+    pending: Vec<Pending<'r, T>>,
+
+    /// All objects that have been yielded, to detect duplicates
+    yielded: HashSet<T>,
+
+    /// Select the objects of type `T` among an object and its references
+    ///
+    /// `None`, if the iterator has been created from a single object, and
+    /// doesn't need to visit any references.
+    select: Option<Select<'r, T>>,
+}
 
-impl<T> Iter<T> {
-    fn empty() -> Self {
-        Self(VecDeque::new())
-    }
+type Select<'r, T> = fn(&'r dyn ObjectIters<'r>) -> Iter<'r, T>;
 
+impl<'r, T> Iter<'r, T>
+where
+    T: Copy + Eq + Hash,
+{
     fn from_object(object: T) -> Self {
-        let mut objects = VecDeque::new();
-        objects.push_back(object);
-        Self(objects)
+        Self {
+            pending: vec![Pending::Object(object)],
+            yielded: HashSet::new(),
+            select: None,
+        }
     }
 
-    fn with(mut self, other: Self) -> Self
-    where
-        T: PartialEq,
-    {
-        for object in other {
-            if !self.0.contains(&object) {
-                self.0.push_back(object);
+    fn from_references(
+        references: Vec<&'r dyn ObjectIters<'r>>,
+        select: Select<'r, T>,
+    ) -> Self {
+        let pending = references
+            .into_iter()
+            .rev()
+            .map(Pending::Reference)
+            .collect();
+
+        Self {
+            pending,
+            yielded: HashSet::new(),
+            select: Some(select),
+        }
+    }
+}
+
+impl<T> Iterator for Iter<'_, T>
+where
+    T: Copy + Eq + Hash,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(pending) = self.pending.pop() {
+            match pending {
+                Pending::Object(object) => {
+                    if self.yielded.insert(object) {
+                        return Some(object);
+                    }
+                }
+                Pending::Reference(object) => {
+                    let select = self
+                        .select
+                        .expect("Iterator with references can select objects");
+
+                    // The pending objects of the new iterator are in stack
+                    // order already, so they are visited before the rest.
+                    self.pending.extend(select(object).pending);
+                }
             }
         }
 
-        self
+        None
     }
 }
 
-impl<T> Iterator for Iter<T> {
-    type Item = T;
+/// An iterator over the faces that intersect an AABB
+///
+/// See [`ObjectIters::faces_in_aabb`].
+pub struct FacesInAabb<'r> {
+    faces: Iter<'r, &'r Face>,
+    aabb: Aabb<3>,
+    tolerance: Tolerance,
+}
+
+impl<'r> Iterator for FacesInAabb<'r> {
+    type Item = &'r Face;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.0.pop_front()
+        let aabb = &self.aabb;
+        let tolerance = self.tolerance;
+
+        self.faces
+            .find(|face| face.aabb(tolerance).intersects(aabb))
     }
 }
 
+enum Pending<'r, T> {
+    /// An object of the type that is iterated over
+    Object(T),
+
+    /// An object that might be of that type, or reference objects of it
+    Reference(&'r dyn ObjectIters<'r>),
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::objects::{
-        Curve, Cycle, Edge, Face, GlobalVertex, Sketch, Solid, Surface, Vertex,
+    use std::{cell::Cell, mem::size_of};
+
+    use fj_math::{Aabb, Point, Scalar};
+
+    use crate::{
+        algorithms::{Tolerance, TransformObject},
+        objects::{
+            Curve, Cycle, Edge, Face, GlobalVertex, Sketch, Solid, Surface,
+            Vertex,
+        },
     };
 
-    use super::ObjectIters as _;
+    use super::ObjectIters;
 
     #[test]
     fn curve() {
@@ -403,13 +546,185 @@ mod tests {
         assert_eq!(6, object.vertex_iter().count());
     }
 
+    #[test]
+    fn structurally_equal_objects() {
+        let faces = (0..100).map(|i| {
+            let x = f64::from(i) * 2.;
+            Face::builder(Surface::xy_plane())
+                .with_exterior_polygon([[x, 0.], [x + 1., 0.], [x, 1.]])
+                .build()
+        });
+        let object = Sketch::from_faces(faces);
+
+        assert_eq!(100, object.face_iter().count());
+        assert_eq!(1, object.surface_iter().count());
+    }
+
+    #[test]
+    fn many_objects() {
+        // Deduplicating these used to take time quadratic in the number of
+        // objects, which made this test take minutes.
+        let faces = (0..10_000).map(|i| {
+            let x = f64::from(i) * 2.;
+            Face::builder(Surface::xy_plane())
+                .with_exterior_polygon([[x, 0.], [x + 1., 0.], [x, 1.]])
+                .build()
+        });
+        let object = Sketch::from_faces(faces);
+
+        assert_eq!(10_000, object.face_iter().count());
+        assert_eq!(30_000, object.edge_iter().count());
+        assert_eq!(30_000, object.global_vertex_iter().count());
+    }
+
+    #[test]
+    fn filter_faces() {
+        let faces = [0., 1.].map(|z| {
+            Face::builder(Surface::xy_plane().translate([0., 0., z]))
+                .with_exterior_polygon([[0., 0.], [1., 0.], [0., 1.]])
+                .build()
+        });
+        let object = Sketch::from_faces(faces);
+
+        let bottom = object
+            .filter_faces(|face| *face.surface() == Surface::xy_plane())
+            .count();
+        assert_eq!(bottom, 1);
+    }
+
+    #[test]
+    fn faces_in_aabb() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        let aabb = |min: [f64; 3], max: [f64; 3]| Aabb {
+            min: Point::from(min),
+            max: Point::from(max),
+        };
+
+        // Squares at `x = 0..1`, `x = 2..3`, and `x = 4..5`
+        let [a, b, c] = [0., 2., 4.].map(|x| {
+            Face::builder(Surface::xy_plane())
+                .with_exterior_polygon([
+                    [x, 0.],
+                    [x + 1., 0.],
+                    [x + 1., 1.],
+                    [x, 1.],
+                ])
+                .build()
+        });
+        let squares = Sketch::from_faces([a, b.clone(), c.clone()]);
+
+        // The second square straddles the boundary of the box.
+        let query = aabb([2.5, -1., -1.], [10., 2., 1.]);
+        let mut found: Vec<_> =
+            squares.faces_in_aabb(&query, tolerance).collect();
+        found.sort();
+        let mut expected = vec![&b, &c];
+        expected.sort();
+        assert_eq!(found, expected);
+
+        // A circle of radius 1 around the origin has no vertices, but it
+        // reaches `x = 1`.
+        let circle = Face::new(
+            Surface::xy_plane(),
+            [Cycle {
+                edges: vec![Edge::circle_from_radius(Scalar::ONE)],
+            }],
+            [],
+            [255, 0, 0, 255],
+        );
+        let circle = Sketch::from_faces([circle]);
+
+        let near = aabb([0.9, -0.1, -0.1], [2., 0.1, 0.1]);
+        assert_eq!(circle.faces_in_aabb(&near, tolerance).count(), 1);
+
+        let far = aabb([1.1, -0.1, -0.1], [2., 0.1, 0.1]);
+        assert_eq!(circle.faces_in_aabb(&far, tolerance).count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn lazy() {
+        let visits = Cell::new(0);
+        let faces: Vec<_> = (0..1000)
+            .map(|i| {
+                let x = f64::from(i) * 2.;
+                let face = Face::builder(Surface::xy_plane())
+                    .with_exterior_polygon([[x, 0.], [x + 1., 0.], [x, 1.]])
+                    .build();
+                Counted {
+                    object: face,
+                    visits: &visits,
+                }
+            })
+            .collect();
+
+        // The 11th face is found after visiting 11 faces.
+        let position = Point::from([20., 0., 0.]);
+        let face = faces.face_iter().find(|face| {
+            face.global_vertex_iter()
+                .any(|vertex| vertex.position() == position)
+        });
+        assert!(face.is_some());
+        assert_eq!(visits.get(), 11);
+
+        visits.set(0);
+        assert_eq!(faces.face_iter().count(), 1000);
+        assert_eq!(visits.get(), 1000);
+    }
+
+    /// Counts how often the objects it references are visited
+    struct Counted<'a, T> {
+        object: T,
+        visits: &'a Cell<usize>,
+    }
+
+    impl<'r, T> ObjectIters<'r> for Counted<'_, T>
+    where
+        T: ObjectIters<'r> + 'r,
+    {
+        fn referenced_objects(&'r self) -> Vec<&'r dyn ObjectIters> {
+            self.visits.set(self.visits.get() + 1);
+            vec![&self.object as &dyn ObjectIters]
+        }
+    }
+
+    #[test]
+    fn memory_report() {
+        let object = Solid::cube_from_edge_length(1.);
+        let report = object.memory_report();
+
+        for (kind, count, size) in [
+            ("faces", object.face_iter().count(), size_of::<Face>()),
+            ("edges", object.edge_iter().count(), size_of::<Edge>()),
+            (
+                "vertices",
+                object.vertex_iter().count(),
+                size_of::<Vertex>(),
+            ),
+        ] {
+            let usage = report.get(kind).expect("Kind is reported");
+            assert_eq!(usage.count, count);
+
+            // The cube has no polylines or faces made of triangles, so there's
+            // nothing beyond the objects themselves.
+            assert_eq!(usage.bytes, count * size);
+            assert!(usage.bytes > 0);
+        }
+
+        assert_eq!(report.get("solids").map(|usage| usage.count), Some(1));
+        assert_eq!(report.get("sketches").map(|usage| usage.bytes), Some(0));
+    }
+
     #[test]
     fn solid() {
         let object = Solid::cube_from_edge_length(1.);
 
-        assert_eq!(18, object.curve_iter().count());
+        // Neighboring faces traverse their shared edges in opposite
+        // directions, so no curves or edges are shared between faces.
+        assert_eq!(24, object.curve_iter().count());
         assert_eq!(6, object.cycle_iter().count());
-        assert_eq!(20, object.edge_iter().count());
+        assert_eq!(24, object.edge_iter().count());
         assert_eq!(6, object.face_iter().count());
         assert_eq!(8, object.global_vertex_iter().count());
         assert_eq!(0, object.sketch_iter().count());
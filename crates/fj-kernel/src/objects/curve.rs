@@ -1,6 +1,6 @@
-use std::fmt;
+use std::{fmt, mem::size_of};
 
-use fj_math::{Circle, Line, Point, Vector};
+use fj_math::{Circle, Ellipse, Line, Point, Polyline, Scalar, Vector};
 
 /// A one-dimensional shape
 ///
@@ -15,13 +15,25 @@ use fj_math::{Circle, Line, Point, Vector};
 /// The `D` parameter defines the dimensions in which the curve is defined.
 /// Typically, only `2` or `3` make sense, which means the curve is defined on
 /// a surface or in a space, respectively.
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub enum Curve<const D: usize> {
     /// A circle
     Circle(Circle<D>),
 
+    /// An ellipse
+    ///
+    /// Results from non-uniformly scaled circles, plane sections of cylinders,
+    /// and imported geometry.
+    Ellipse(Ellipse<D>),
+
     /// A line
     Line(Line<D>),
+
+    /// A polyline
+    ///
+    /// Used for geometry that is only known as a sequence of points, like
+    /// imported or approximated geometry. A polyline is its own approximation.
+    Polyline(Polyline<D>),
 }
 
 impl<const D: usize> Curve<D> {
@@ -34,7 +46,22 @@ impl<const D: usize> Curve<D> {
     pub fn origin(&self) -> Point<D> {
         match self {
             Self::Circle(curve) => curve.center,
+            Self::Ellipse(curve) => curve.center,
             Self::Line(curve) => curve.origin,
+            Self::Polyline(curve) => curve.points()[0],
+        }
+    }
+
+    /// Estimate the memory that the curve uses outside of itself
+    ///
+    /// Only polylines store their points on the heap.
+    pub(crate) fn heap_size(&self) -> usize {
+        match self {
+            Self::Polyline(curve) => {
+                curve.points().len() * size_of::<Point<D>>()
+                    + curve.knots().len() * size_of::<Scalar>()
+            }
+            Self::Circle(_) | Self::Ellipse(_) | Self::Line(_) => 0,
         }
     }
 
@@ -43,7 +70,9 @@ impl<const D: usize> Curve<D> {
     pub fn reverse(self) -> Self {
         match self {
             Self::Circle(curve) => Self::Circle(curve.reverse()),
+            Self::Ellipse(curve) => Self::Ellipse(curve.reverse()),
             Self::Line(curve) => Self::Line(curve.reverse()),
+            Self::Polyline(curve) => Self::Polyline(curve.reverse()),
         }
     }
 
@@ -54,7 +83,27 @@ impl<const D: usize> Curve<D> {
     ) -> Point<D> {
         match self {
             Self::Circle(curve) => curve.point_from_circle_coords(point),
+            Self::Ellipse(curve) => curve.point_from_ellipse_coords(point),
             Self::Line(curve) => curve.point_from_line_coords(point),
+            Self::Polyline(curve) => curve.point_from_polyline_coords(point),
+        }
+    }
+
+    /// Compute the tangent of the curve at the given curve coordinate
+    ///
+    /// The tangent is the derivative of the curve with respect to its curve
+    /// coordinate. It is not normalized.
+    pub fn tangent_at(&self, point: impl Into<Point<1>>) -> Vector<D> {
+        let point = point.into();
+
+        match self {
+            Self::Circle(curve) => {
+                let (sin, cos) = point.t.sin_cos();
+                curve.b * cos - curve.a * sin
+            }
+            Self::Ellipse(curve) => curve.tangent_at(point),
+            Self::Line(curve) => curve.direction,
+            Self::Polyline(curve) => curve.tangent_at(point),
         }
     }
 
@@ -65,7 +114,9 @@ impl<const D: usize> Curve<D> {
     ) -> Vector<D> {
         match self {
             Self::Circle(curve) => curve.vector_from_circle_coords(point),
+            Self::Ellipse(curve) => curve.vector_from_ellipse_coords(point),
             Self::Line(curve) => curve.vector_from_line_coords(point),
+            Self::Polyline(curve) => curve.vector_from_polyline_coords(point),
         }
     }
 }
@@ -118,7 +169,9 @@ impl<const D: usize> fmt::Display for Curve<D> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::Circle(curve) => write!(f, "{:?}", curve),
+            Self::Ellipse(curve) => write!(f, "{:?}", curve),
             Self::Line(curve) => write!(f, "{:?}", curve),
+            Self::Polyline(curve) => write!(f, "{:?}", curve),
         }
     }
 }
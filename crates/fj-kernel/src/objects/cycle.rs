@@ -1,6 +1,8 @@
-use fj_math::Point;
+use fj_math::{Point, Scalar, Vector};
 
-use super::{Edge, Surface};
+use crate::algorithms::Tolerance;
+
+use super::{Edge, SampleError, Surface};
 
 /// A cycle of connected edges
 ///
@@ -44,4 +46,95 @@ impl Cycle {
     pub fn edges(&self) -> impl Iterator<Item = &Edge> + '_ {
         self.edges.iter()
     }
+
+    /// Sample points that are evenly spaced by arc length around the cycle
+    ///
+    /// Returns `n` points, starting at the start of the first edge. The
+    /// spacing continues across the boundaries between edges, and a point
+    /// that falls onto a boundary is placed at the start of the next edge.
+    /// As the cycle is closed, the start is not repeated at the end.
+    ///
+    /// See [`Edge::sample_even`] for the tangents and the use of `tolerance`.
+    pub fn sample_even(
+        &self,
+        n: usize,
+        tolerance: Tolerance,
+    ) -> Result<Vec<(Point<3>, Vector<3>)>, SampleError> {
+        if n < 2 {
+            return Err(SampleError::TooFewPoints(n));
+        }
+
+        let lengths = self
+            .edges()
+            .map(Edge::arc_length)
+            .collect::<Result<Vec<_>, _>>()?;
+        let length = lengths
+            .iter()
+            .fold(Scalar::ZERO, |sum, &length| sum + length);
+        if length == Scalar::ZERO {
+            return Err(SampleError::ZeroLength);
+        }
+
+        let mut points = Vec::new();
+        let mut edge = 0;
+        let mut edge_start = Scalar::ZERO;
+
+        for i in 0..n {
+            let arc_length = length * i as f64 / n as f64;
+
+            while edge < self.edges.len() - 1
+                && arc_length >= edge_start + lengths[edge]
+            {
+                edge_start += lengths[edge];
+                edge += 1;
+            }
+
+            points.push(
+                self.edges[edge]
+                    .point_at_arc_length(arc_length - edge_start, tolerance)?,
+            );
+        }
+
+        Ok(points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Vector};
+
+    use crate::{algorithms::Tolerance, objects::Surface};
+
+    use super::Cycle;
+
+    #[test]
+    fn sample_even() -> anyhow::Result<()> {
+        let cycle = Cycle::polygon_from_points(
+            &Surface::xy_plane(),
+            [[0., 0.], [1., 0.], [1., 1.], [0., 1.]],
+        );
+
+        let samples = cycle.sample_even(8, Tolerance::from_scalar(1e-6)?)?;
+
+        // Corners and midpoints alternate, and the tangent changes at each
+        // corner.
+        let expected = [
+            ([0., 0.], [1., 0.]),
+            ([0.5, 0.], [1., 0.]),
+            ([1., 0.], [0., 1.]),
+            ([1., 0.5], [0., 1.]),
+            ([1., 1.], [-1., 0.]),
+            ([0.5, 1.], [-1., 0.]),
+            ([0., 1.], [0., -1.]),
+            ([0., 0.5], [0., -1.]),
+        ]
+        .map(|([x, y], [tx, ty])| {
+            (Point::from([x, y, 0.]), Vector::from([tx, ty, 0.]))
+        });
+        assert_eq!(samples, expected);
+
+        assert!(cycle.sample_even(1, Tolerance::from_scalar(1e-6)?).is_err());
+
+        Ok(())
+    }
 }
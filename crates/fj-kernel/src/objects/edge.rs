@@ -1,13 +1,13 @@
 use std::fmt;
 
-use fj_math::{Circle, Line, Point, Scalar, Vector};
+use fj_math::{Arc, Circle, Ellipse, Line, Point, Polyline, Scalar, Vector};
 
-use crate::local::Local;
+use crate::{algorithms::Tolerance, local::Local};
 
 use super::{Curve, GlobalVertex, Surface, Vertex};
 
 /// An edge of a shape
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct Edge {
     curve: Local<Curve<2>>,
     vertices: VerticesOfEdge,
@@ -38,6 +38,24 @@ impl Edge {
         }
     }
 
+    /// Create an ellipse
+    ///
+    /// The ellipse is defined in the coordinates of `surface`, which must be a
+    /// plane. The resulting edge is continuous, and has no vertices.
+    pub fn ellipse(surface: &Surface, ellipse: Ellipse<2>) -> Self {
+        let curve_local = Curve::Ellipse(ellipse);
+        let curve_canonical = Curve::Ellipse(Ellipse {
+            center: surface.point_from_surface_coords(ellipse.center),
+            a: surface.vector_from_surface_coords(ellipse.a),
+            b: surface.vector_from_surface_coords(ellipse.b),
+        });
+
+        Self {
+            curve: Local::new(curve_local, curve_canonical),
+            vertices: VerticesOfEdge::none(),
+        }
+    }
+
     /// Create a line segment from two points
     pub fn line_segment_from_points(
         surface: &Surface,
@@ -71,6 +89,107 @@ impl Edge {
         }
     }
 
+    /// Create a circular arc
+    ///
+    /// The arc is defined in the coordinates of `surface`, which must be a
+    /// plane. The resulting edge is bounded by vertices at the start and end of
+    /// the arc.
+    pub fn arc(surface: &Surface, arc: Arc) -> Self {
+        Self::circle_section(
+            surface,
+            arc.to_circle(),
+            arc.sweep_angle.abs(),
+            [arc.start(), arc.end()],
+        )
+    }
+
+    /// Create a section of a circle, bounded by the given points
+    ///
+    /// The circle is defined in the coordinates of `surface`, which must be a
+    /// plane. The edge covers the circle coordinates from `0.` to `end`. Its
+    /// vertices are placed at `points`, instead of being computed from the
+    /// circle, so the edge can share its vertices exactly with adjacent edges.
+    ///
+    /// If `points` don't lie on the circle at those coordinates, the resulting
+    /// edge fails coherence validation.
+    pub fn circle_section(
+        surface: &Surface,
+        circle: Circle<2>,
+        end: Scalar,
+        points: [impl Into<Point<2>>; 2],
+    ) -> Self {
+        let curve_local = Curve::Circle(circle);
+        let curve_canonical = Curve::Circle(Circle {
+            center: surface.point_from_surface_coords(circle.center),
+            a: surface.vector_from_surface_coords(circle.a),
+            b: surface.vector_from_surface_coords(circle.b),
+        });
+
+        let [start_point, end_point] = points.map(Into::into);
+        let vertices = [
+            (Point::from([Scalar::ZERO]), start_point),
+            (Point::from([end]), end_point),
+        ]
+        .map(|(position, point)| {
+            let point = surface.point_from_surface_coords(point);
+            Vertex::new(position, GlobalVertex::from_position(point))
+        });
+
+        Self {
+            curve: Local::new(curve_local, curve_canonical),
+            vertices: VerticesOfEdge::from_vertices(vertices),
+        }
+    }
+
+    /// Create a polyline from points
+    ///
+    /// The points are defined in the coordinates of `surface`. The polyline is
+    /// parameterized by its arc length in those coordinates, and its 3D form
+    /// shares that parameterization.
+    ///
+    /// If the first and last point are equal, the polyline is closed, and the
+    /// resulting edge has no vertices. Otherwise, it is bounded by vertices at
+    /// the first and last point.
+    pub fn polyline_from_points(
+        surface: &Surface,
+        points: impl IntoIterator<Item = impl Into<Point<2>>>,
+    ) -> Self {
+        let curve_local = Polyline::from_points(points);
+        let curve_canonical = Polyline::from_points_and_knots(
+            curve_local
+                .points()
+                .iter()
+                .map(|&point| surface.point_from_surface_coords(point)),
+            curve_local.knots().iter().copied(),
+        );
+
+        let vertices = if curve_local.is_closed() {
+            VerticesOfEdge::none()
+        } else {
+            let knots = curve_local.knots();
+            let points = curve_canonical.points();
+
+            VerticesOfEdge::from_vertices([
+                Vertex::new(
+                    Point::from([knots[0]]),
+                    GlobalVertex::from_position(points[0]),
+                ),
+                Vertex::new(
+                    Point::from([knots[knots.len() - 1]]),
+                    GlobalVertex::from_position(points[points.len() - 1]),
+                ),
+            ])
+        };
+
+        Self {
+            curve: Local::new(
+                Curve::Polyline(curve_local),
+                Curve::Polyline(curve_canonical),
+            ),
+            vertices,
+        }
+    }
+
     /// Access the curve that defines the edge's geometry
     ///
     /// The edge can be a segment of the curve that is bounded by two vertices,
@@ -88,6 +207,177 @@ impl Edge {
     pub fn vertices(&self) -> &VerticesOfEdge {
         &self.vertices
     }
+
+    /// Access the vertex at which the edge starts
+    ///
+    /// The start vertex is the one with the lower curve coordinate, meaning
+    /// the edge runs from its start vertex to its end vertex in the direction
+    /// of the curve.
+    ///
+    /// Returns `None`, if the edge has no vertices.
+    pub fn start_vertex(&self) -> Option<&Vertex> {
+        self.vertices.get().map(|[start, _]| start)
+    }
+
+    /// Access the vertex at which the edge ends
+    ///
+    /// See [`Edge::start_vertex`] for more information.
+    ///
+    /// Returns `None`, if the edge has no vertices.
+    pub fn end_vertex(&self) -> Option<&Vertex> {
+        self.vertices.get().map(|[_, end]| end)
+    }
+
+    /// Access the global positions of the edge's vertices
+    ///
+    /// The positions are returned in the order of the curve's parameterization,
+    /// i.e. the position of the start vertex comes first.
+    ///
+    /// Returns `None`, if the edge has no vertices.
+    pub fn vertex_positions(&self) -> Option<[Point<3>; 2]> {
+        self.vertices.convert(|vertex| vertex.global().position())
+    }
+
+    /// Sample points that are evenly spaced by arc length along the edge
+    ///
+    /// Returns `n` points, including the start and end of the edge, each with
+    /// the unit tangent of the edge there. The tangents point in the direction
+    /// of the curve's parameterization. An edge without vertices covers its
+    /// whole curve, so its first and last point coincide.
+    ///
+    /// The arc length is exact for lines, circles, and polylines. Ellipses are
+    /// measured using quadrature, and the points are placed within
+    /// `tolerance` of the exact arc length.
+    pub fn sample_even(
+        &self,
+        n: usize,
+        tolerance: Tolerance,
+    ) -> Result<Vec<(Point<3>, Vector<3>)>, SampleError> {
+        if n < 2 {
+            return Err(SampleError::TooFewPoints(n));
+        }
+
+        let length = self.arc_length()?;
+        if length == Scalar::ZERO {
+            return Err(SampleError::ZeroLength);
+        }
+
+        (0..n)
+            .map(|i| {
+                let arc_length = length * i as f64 / (n - 1) as f64;
+                self.point_at_arc_length(arc_length, tolerance)
+            })
+            .collect()
+    }
+
+    /// Compute the length of the edge
+    ///
+    /// Returns an error, if the edge is an unbounded line.
+    pub fn arc_length(&self) -> Result<Scalar, SampleError> {
+        let [start, end] = self.range()?;
+
+        let length = match self.curve.global_form() {
+            Curve::Circle(circle) => circle.a.magnitude() * (end - start),
+            Curve::Ellipse(ellipse) => ellipse.arc_length([start], [end]),
+            Curve::Line(line) => line.direction.magnitude() * (end - start),
+            Curve::Polyline(polyline) => {
+                polyline_segments(polyline, [start, end])
+                    .map(|(_, length)| length)
+                    .fold(Scalar::ZERO, |sum, length| sum + length)
+            }
+        };
+
+        Ok(length)
+    }
+
+    /// Compute the point and unit tangent at the given arc length
+    ///
+    /// The arc length is measured from the start of the edge. Arc lengths
+    /// beyond the end of the edge are clamped to it.
+    pub(super) fn point_at_arc_length(
+        &self,
+        arc_length: Scalar,
+        tolerance: Tolerance,
+    ) -> Result<(Point<3>, Vector<3>), SampleError> {
+        let [start, end] = self.range()?;
+        let length = self.arc_length()?;
+
+        // Return the ends of the edge exactly, to not introduce any floating
+        // point accuracy issues there.
+        let t = if arc_length <= Scalar::ZERO {
+            start
+        } else if arc_length >= length {
+            end
+        } else {
+            match self.curve.global_form() {
+                Curve::Circle(circle) => {
+                    start + arc_length / circle.a.magnitude()
+                }
+                Curve::Ellipse(ellipse) => {
+                    // The arc length grows monotonically with the curve
+                    // coordinate, and its derivative is the speed along the
+                    // ellipse, so Newton's method converges quickly.
+                    let mut t = start + (end - start) * arc_length / length;
+                    for _ in 0..32 {
+                        let error =
+                            ellipse.arc_length([start], [t]) - arc_length;
+                        if error.abs() < tolerance.inner() {
+                            break;
+                        }
+
+                        t = t - error / ellipse.tangent_at([t]).magnitude();
+                        t = t.max(start).min(end);
+                    }
+                    t
+                }
+                Curve::Line(line) => {
+                    start + arc_length / line.direction.magnitude()
+                }
+                Curve::Polyline(polyline) => {
+                    let mut remaining = arc_length;
+                    let mut t = end;
+
+                    for ([a, b], segment_length) in
+                        polyline_segments(polyline, [start, end])
+                    {
+                        if remaining <= segment_length {
+                            t = a + (b - a) * remaining / segment_length;
+                            break;
+                        }
+                        remaining = remaining - segment_length;
+                    }
+
+                    t
+                }
+            }
+        };
+
+        let curve = self.curve.global_form();
+        let t = Point::from([t]);
+        Ok((
+            curve.point_from_curve_coords(t),
+            curve.tangent_at(t).normalize(),
+        ))
+    }
+
+    /// The range of curve coordinates that the edge covers
+    fn range(&self) -> Result<[Scalar; 2], SampleError> {
+        if let Some([start, end]) = self.vertices.get() {
+            return Ok([start.position().t, end.position().t]);
+        }
+
+        // An edge without vertices covers its whole curve.
+        match self.curve.global_form() {
+            Curve::Circle(_) | Curve::Ellipse(_) => {
+                Ok([Scalar::ZERO, Scalar::PI * 2.])
+            }
+            Curve::Line(_) => Err(SampleError::Unbounded),
+            Curve::Polyline(polyline) => {
+                let knots = polyline.knots();
+                Ok([knots[0], knots[knots.len() - 1]])
+            }
+        }
+    }
 }
 
 impl fmt::Display for Edge {
@@ -107,18 +397,58 @@ impl fmt::Display for Edge {
 }
 
 /// The vertices that bound an edge
+///
+/// # Vertex order
+///
+/// If an edge has vertices, they are always stored in the order of the curve's
+/// parameterization: The curve coordinate of the first vertex is less than or
+/// equal to the curve coordinate of the second one. The constructors of this
+/// type enforce that, by swapping vertices that are passed in descending
+/// order. Use [`VerticesOfEdge::from_vertices_checked`], if you'd rather get
+/// an error in that case.
+///
+/// Since a [`Vertex`] is defined in terms of its position on the curve, no
+/// access to the curve itself is required to establish this order.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct VerticesOfEdge(Option<[Vertex; 2]>);
 
 impl VerticesOfEdge {
     /// Construct an instance of `VerticesOfEdge` from zero or two vertices
+    ///
+    /// Swaps the vertices, if they are not in ascending order. See
+    /// [`VerticesOfEdge`] for details.
     pub fn new(vertices: Option<[Vertex; 2]>) -> Self {
-        Self(vertices)
+        Self(vertices.map(|[a, b]| {
+            if a.position() <= b.position() {
+                [a, b]
+            } else {
+                [b, a]
+            }
+        }))
     }
 
     /// Construct an instance of `VerticesOfEdge` from two vertices
+    ///
+    /// Swaps the vertices, if they are not in ascending order. See
+    /// [`VerticesOfEdge`] for details.
     pub fn from_vertices(vertices: [Vertex; 2]) -> Self {
-        Self(Some(vertices))
+        Self::new(Some(vertices))
+    }
+
+    /// Construct an instance of `VerticesOfEdge` from two ordered vertices
+    ///
+    /// Returns an error, if the curve coordinate of the first vertex is larger
+    /// than the curve coordinate of the second one.
+    pub fn from_vertices_checked(
+        vertices: [Vertex; 2],
+    ) -> Result<Self, InvalidVertexOrder> {
+        let [a, b] = vertices;
+
+        if a.position() > b.position() {
+            return Err(InvalidVertexOrder([a.position(), b.position()]));
+        }
+
+        Ok(Self(Some(vertices)))
     }
 
     /// Construct an instance of `VerticesOfEdge` without vertices
@@ -165,7 +495,8 @@ impl VerticesOfEdge {
 
     /// Reverse the order of vertices
     ///
-    /// Makes sure that the local coordinates are still correct.
+    /// Makes sure that the local coordinates are still correct. Since the local
+    /// coordinates are negated, the vertices stay in ascending order.
     pub fn reverse(self) -> Self {
         Self(self.0.map(|[a, b]| {
             [
@@ -176,11 +507,14 @@ impl VerticesOfEdge {
     }
 
     /// Map each vertex using the provided function
+    ///
+    /// The resulting vertices are re-ordered, if necessary. See
+    /// [`VerticesOfEdge`] for details.
     pub fn map<F>(self, f: F) -> Self
     where
         F: FnMut(Vertex) -> Vertex,
     {
-        Self(self.convert(f))
+        Self::new(self.convert(f))
     }
 
     /// Convert each vertex using the provided function
@@ -207,3 +541,267 @@ impl VerticesOfEdge {
         Ok(vertices)
     }
 }
+
+/// The segments of a polyline within a range of its coordinates
+///
+/// Returns the range of each segment, clipped to `range`, along with its
+/// length. Segments outside of `range` are skipped.
+fn polyline_segments(
+    polyline: &Polyline<3>,
+    [start, end]: [Scalar; 2],
+) -> impl Iterator<Item = ([Scalar; 2], Scalar)> + '_ {
+    let points = polyline.points();
+    let knots = polyline.knots();
+
+    (0..points.len() - 1).filter_map(move |i| {
+        let [a, b] = [knots[i].max(start), knots[i + 1].min(end)];
+        if a >= b {
+            return None;
+        }
+
+        let speed =
+            (points[i + 1] - points[i]).magnitude() / (knots[i + 1] - knots[i]);
+        Some(([a, b], speed * (b - a)))
+    })
+}
+
+/// Error sampling points along an edge or cycle
+///
+/// Returned by [`Edge::sample_even`] and [`Cycle::sample_even`].
+///
+/// [`Cycle::sample_even`]: super::Cycle::sample_even
+#[derive(Debug, thiserror::Error)]
+pub enum SampleError {
+    /// Fewer than two points have been requested
+    #[error("Can't sample fewer than 2 points (requested {0})")]
+    TooFewPoints(usize),
+
+    /// The edge or cycle has zero length
+    #[error("Can't sample points along an edge or cycle of zero length")]
+    ZeroLength,
+
+    /// The edge is a line without vertices
+    #[error("Can't sample points along an unbounded line")]
+    Unbounded,
+}
+
+/// Error constructing [`VerticesOfEdge`] from vertices in descending order
+///
+/// Returned by [`VerticesOfEdge::from_vertices_checked`].
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "Vertices of edge are in descending order ({0:?}); expected the curve \
+    coordinate of the first vertex to be less than or equal to the second"
+)]
+pub struct InvalidVertexOrder(pub [Point<1>; 2]);
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use approx::assert_abs_diff_eq;
+    use fj_math::{Arc, Circle, Point, Scalar, Vector};
+
+    use crate::{
+        algorithms::Tolerance,
+        objects::{Curve, GlobalVertex, Surface, Vertex},
+    };
+
+    use super::{Edge, SampleError, VerticesOfEdge};
+
+    #[test]
+    fn vertices_are_normalized() {
+        let a = Vertex::new([0.], GlobalVertex::from_position([0., 0., 0.]));
+        let b = Vertex::new([1.], GlobalVertex::from_position([1., 0., 0.]));
+
+        let ascending = VerticesOfEdge::from_vertices([a, b]);
+        let descending = VerticesOfEdge::from_vertices([b, a]);
+
+        assert_eq!(ascending, descending);
+        assert_eq!(ascending.get(), Some([&a, &b]));
+
+        assert!(VerticesOfEdge::from_vertices_checked([a, b]).is_ok());
+        assert!(VerticesOfEdge::from_vertices_checked([b, a]).is_err());
+    }
+
+    #[test]
+    fn vertices_stay_ordered_when_reversed() {
+        let a = Vertex::new([0.], GlobalVertex::from_position([0., 0., 0.]));
+        let b = Vertex::new([1.], GlobalVertex::from_position([1., 0., 0.]));
+
+        let vertices = VerticesOfEdge::from_vertices([a, b]).reverse();
+        let [start, end] = vertices.expect_vertices();
+
+        assert!(start.position() <= end.position());
+    }
+
+    #[test]
+    fn vertices_ordered_for_random_intervals() {
+        // Simple linear congruential generator, to get reproducible
+        // pseudo-random intervals without pulling in a dependency.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = || {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (state >> 11) as f64 / (1u64 << 53) as f64 * 200. - 100.
+        };
+
+        for _ in 0..1000 {
+            let [t_a, t_b] = [next(), next()];
+
+            let a =
+                Vertex::new([t_a], GlobalVertex::from_position([t_a, 0., 0.]));
+            let b =
+                Vertex::new([t_b], GlobalVertex::from_position([t_b, 0., 0.]));
+
+            let [start, end] =
+                VerticesOfEdge::from_vertices([a, b]).expect_vertices();
+            assert!(start.position() <= end.position());
+        }
+    }
+
+    #[test]
+    fn start_and_end_vertex() {
+        let edge = Edge::line_segment_from_points(
+            &Surface::xy_plane(),
+            [[1., 2.], [3., 4.]],
+        );
+
+        let start = edge.start_vertex().map(Vertex::position);
+        let end = edge.end_vertex().map(Vertex::position);
+
+        assert_eq!(start, Some(Point::from([0.])));
+        assert_eq!(end, Some(Point::from([1.])));
+        assert_eq!(
+            edge.vertex_positions(),
+            Some([Point::from([1., 2., 0.]), Point::from([3., 4., 0.])]),
+        );
+
+        let edge = Edge::circle_from_radius(Scalar::ONE);
+        assert!(edge.start_vertex().is_none());
+        assert!(edge.end_vertex().is_none());
+        assert!(edge.vertex_positions().is_none());
+    }
+
+    #[test]
+    fn arc() {
+        let surface = Surface::xz_plane();
+        let arc = Arc::from_endpoints([1., 0.], [0., 1.], 1., true, false);
+
+        let edge = Edge::arc(&surface, arc);
+
+        let [start, end] =
+            edge.vertex_positions().expect("Arc should have vertices");
+        assert_abs_diff_eq!(start, Point::from([1., 0., 0.]), epsilon = 1e-9);
+        assert_abs_diff_eq!(end, Point::from([0., 0., 1.]), epsilon = 1e-9);
+
+        // The arc can be recovered from the edge without loss.
+        let circle = match edge.curve().local_form() {
+            Curve::Circle(circle) => *circle,
+            curve => panic!("Expected circle, got {:?}", curve),
+        };
+        let [a, b] = edge.vertices().expect_vertices();
+        let sweep_angle = b.position().t - a.position().t;
+        let clockwise =
+            circle.a.u * circle.b.v - circle.a.v * circle.b.u < Scalar::ZERO;
+        let recovered = Arc {
+            center: circle.center,
+            radius: circle.a.magnitude(),
+            start_angle: Scalar::atan2(circle.a.v, circle.a.u),
+            sweep_angle: if clockwise { -sweep_angle } else { sweep_angle },
+        };
+
+        for (recovered, original) in recovered
+            .counter_clockwise_angles()
+            .into_iter()
+            .zip(arc.counter_clockwise_angles())
+        {
+            assert_abs_diff_eq!(recovered, original, epsilon = 1e-9);
+        }
+        assert_abs_diff_eq!(recovered.center, arc.center, epsilon = 1e-9);
+        assert_abs_diff_eq!(recovered.radius, arc.radius, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn polyline() {
+        let edge = Edge::polyline_from_points(
+            &Surface::xz_plane(),
+            [[0., 0.], [1., 0.], [1., 1.], [0., 1.], [0., 0.]],
+        );
+
+        // The polyline is closed, so the edge is continuous.
+        assert!(edge.vertices().get().is_none());
+
+        let polyline = match edge.curve().global_form() {
+            Curve::Polyline(polyline) => polyline,
+            curve => panic!("Expected polyline, got {:?}", curve),
+        };
+        assert_eq!(polyline.length(), Scalar::from(4.));
+
+        // Half the length along the polyline is halfway around the square.
+        assert_eq!(
+            edge.curve().global_form().point_from_curve_coords([2.]),
+            Point::from([1., 0., 1.]),
+        );
+        assert_eq!(
+            edge.curve().local_form().point_from_curve_coords([2.5]),
+            Point::from([0.5, 1.]),
+        );
+    }
+
+    #[test]
+    fn sample_even() -> anyhow::Result<()> {
+        let radius = 2.;
+        let circle = Circle {
+            center: Point::origin(),
+            a: Vector::from([radius, 0.]),
+            b: Vector::from([0., radius]),
+        };
+        let edge = Edge::circle_section(
+            &Surface::xy_plane(),
+            circle,
+            Scalar::PI / 2.,
+            [[radius, 0.], [0., radius]],
+        );
+
+        let tolerance = Tolerance::from_scalar(1e-6)?;
+        let samples = edge.sample_even(5, tolerance)?;
+        assert_eq!(samples.len(), 5);
+
+        // The points are a quarter of the quarter circle apart, so at the arc
+        // lengths `0`, `π/4`, `π/2`, `3π/4`, and `π`.
+        for (i, (point, tangent)) in samples.into_iter().enumerate() {
+            let angle = FRAC_PI_2 * i as f64 / 4.;
+            let (sin, cos) = angle.sin_cos();
+
+            assert_abs_diff_eq!(
+                point,
+                Point::from([radius * cos, radius * sin, 0.]),
+                epsilon = 1e-12
+            );
+            assert_abs_diff_eq!(
+                tangent,
+                Vector::from([-sin, cos, 0.]),
+                epsilon = 1e-12
+            );
+        }
+
+        assert!(matches!(
+            edge.sample_even(1, tolerance),
+            Err(SampleError::TooFewPoints(1))
+        ));
+
+        let point = Point::from([1., 1.]);
+        let zero_length = Edge::line_segment_from_points(
+            &Surface::xy_plane(),
+            [point, point],
+        );
+        assert!(matches!(
+            zero_length.sample_even(2, tolerance),
+            Err(SampleError::ZeroLength)
+        ));
+
+        Ok(())
+    }
+}
@@ -1,42 +1,108 @@
+use std::cmp::Ordering;
+
 use fj_interop::mesh::Color;
-use fj_math::Triangle;
+use fj_math::{Aabb, Scalar, Triangle, Vector};
 
-use crate::builder::FaceBuilder;
+use crate::{
+    algorithms::{CycleApprox, Tolerance},
+    builder::FaceBuilder,
+};
 
-use super::{Cycle, Surface};
+use super::{Curve, Cycle, Surface};
 
 /// A face of a shape
-#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+///
+/// # Orientation
+///
+/// The outward normal of a face is the normal of its surface, if its exterior
+/// cycle is counter-clockwise in surface coordinates. If the exterior cycle is
+/// clockwise, the outward normal points the other way. Interior cycles are
+/// expected to wind opposite to the exterior cycle.
+///
+/// Triangle representations have no such convention, as each triangle defines
+/// its own orientation.
+///
+/// # Ordering
+///
+/// Faces are compared by their geometry, color, and overrides, never by where
+/// or when they were created. The exterior and interior cycles are stored in
+/// a canonical order, so faces that were constructed with the same cycles in a
+/// different order are equal. This is what makes the order of faces in a
+/// [`Solid`] deterministic.
+///
+/// Faces are ordered by their geometry first: the kind of their surface, then
+/// the plane or axis of the surface, then the positions of their vertices,
+/// sorted lexicographically. These values are rounded to a grid of
+/// [`Face::ORDER_RESOLUTION`], so faces that only differ in how their surface
+/// is parametrized, or by rounding errors, end up in the same place relative to
+/// other faces. Only if that doesn't decide the order, faces are compared by
+/// their full representation.
+///
+/// This order is part of what makes the results of Fornjot reproducible. It
+/// must only change deliberately, as it determines the order of faces in
+/// meshes and exported files, and the indices that faces are selected by.
+///
+/// The edges of a cycle are not reordered, as the first edge is meaningful to
+/// some operations. Two cycles that start at different vertices are
+/// different.
+///
+/// [`Solid`]: super::Solid
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Face {
     representation: Representation,
+
+    /// The geometric part of the order of faces
+    ///
+    /// Faces are compared a lot, as they are kept in sets. Computing this on
+    /// every comparison would be wasteful. It doesn't depend on the overrides,
+    /// so it stays valid, when those are changed.
+    order_key: OrderKey,
 }
 
 impl Face {
+    /// The resolution of the values that faces are ordered by
+    ///
+    /// See [the type-level documentation](Face#ordering).
+    pub const ORDER_RESOLUTION: f64 = 1e-9;
+
     /// Construct a new instance of `Face`
+    ///
+    /// The order of the cycles is not preserved. See [the type-level
+    /// documentation](Face#ordering).
     pub fn new(
         surface: Surface,
         exteriors: impl IntoIterator<Item = Cycle>,
         interiors: impl IntoIterator<Item = Cycle>,
         color: [u8; 4],
     ) -> Self {
-        let exteriors = exteriors.into_iter().collect();
-        let interiors = interiors.into_iter().collect();
-
-        Self {
-            representation: Representation::BRep(BRep {
-                surface,
-                exteriors,
-                interiors,
-                color,
-            }),
-        }
+        let mut exteriors: Vec<_> = exteriors.into_iter().collect();
+        let mut interiors: Vec<_> = interiors.into_iter().collect();
+
+        exteriors.sort();
+        interiors.sort();
+
+        Self::from_representation(Representation::BRep(BRep {
+            surface,
+            exteriors,
+            interiors,
+            color,
+            seam: None,
+            tolerance: None,
+        }))
     }
 
     /// Contact an instance that uses triangle representation
     pub fn from_triangles(triangles: TriRep) -> Self {
-        Self {
-            representation: Representation::TriRep(triangles),
-        }
+        Self::from_representation(Representation::TriRep(triangles))
+    }
+
+    fn from_representation(representation: Representation) -> Self {
+        let mut face = Self {
+            representation,
+            order_key: OrderKey::default(),
+        };
+        face.order_key = face.compute_order_key();
+        face
     }
 
     /// Build a face using the [`FaceBuilder`] API
@@ -74,6 +140,56 @@ impl Face {
         self.brep().color
     }
 
+    /// Override the location of the seam
+    ///
+    /// Faces on periodic surfaces are cut open along the seam, to unroll them
+    /// for approximation (see [`Surface::seam`]). The seam is given as a
+    /// u-coordinate of the surface, which is an angle for surfaces swept from
+    /// circles. It has no effect on other surfaces.
+    pub fn with_seam(mut self, seam: impl Into<Scalar>) -> Self {
+        self.brep_mut().seam = Some(seam.into());
+        self
+    }
+
+    /// Access the location of the seam, if the surface is periodic
+    ///
+    /// Returns the location that was set by [`Face::with_seam`], if any, or
+    /// the default of the surface otherwise.
+    pub fn seam(&self) -> Option<Scalar> {
+        let brep = self.brep();
+        brep.surface.seam().map(|seam| brep.seam.unwrap_or(seam))
+    }
+
+    /// Override the tolerance that the face is approximated with
+    ///
+    /// By default, faces are approximated with the tolerance that is passed to
+    /// the triangulation. This allows for approximating some faces of a shape
+    /// more coarsely, or more finely, than others.
+    pub fn with_tolerance(mut self, tolerance: Tolerance) -> Self {
+        self.brep_mut().tolerance = Some(tolerance);
+        self
+    }
+
+    /// Access the tolerance that was set by [`Face::with_tolerance`], if any
+    pub fn tolerance(&self) -> Option<Tolerance> {
+        self.brep().tolerance
+    }
+
+    /// Copy the overrides of another face to this one
+    ///
+    /// Used by operations that create a new face from an existing one, like
+    /// transforming or reversing it, to carry over what was set by
+    /// [`Face::with_seam`] and [`Face::with_tolerance`].
+    pub(crate) fn with_overrides_of(mut self, other: &Face) -> Self {
+        let other = other.brep();
+        let brep = self.brep_mut();
+
+        brep.seam = other.seam;
+        brep.tolerance = other.tolerance;
+
+        self
+    }
+
     /// Access triangles, if this face uses triangle representation
     ///
     /// Only some faces still use triangle representation. At some point, none
@@ -87,6 +203,89 @@ impl Face {
         None
     }
 
+    /// Compute the axis-aligned bounding box of the face
+    ///
+    /// The box is computed from the approximated cycles of the face, and grown
+    /// by `tolerance` on all sides, as approximations of curved edges can cut
+    /// corners. Faces on swept surfaces reach their extremes on their
+    /// boundary, so the box contains the whole face, not just its vertices.
+    ///
+    /// Faces that consist of triangles get the box of their triangles.
+    pub fn aabb(&self, tolerance: Tolerance) -> Aabb<3> {
+        if let Some(triangles) = self.triangles() {
+            return Aabb::<3>::from_points(
+                triangles.iter().flat_map(|(triangle, _)| triangle.points()),
+            );
+        }
+
+        let aabb = Aabb::<3>::from_points(
+            self.all_cycles()
+                .flat_map(|cycle| CycleApprox::new(cycle, tolerance).points)
+                .map(|point| *point.global_form()),
+        );
+
+        let margin = Vector::from([tolerance.inner(); 3]);
+        Aabb {
+            min: aabb.min - margin,
+            max: aabb.max + margin,
+        }
+    }
+
+    /// Compute the geometric part of the order of faces
+    ///
+    /// See [the type-level documentation](Face#ordering).
+    fn compute_order_key(&self) -> OrderKey {
+        if self.triangles().is_some() {
+            // Triangles have only one representation. There's nothing to gain
+            // from rounding them, and they can be a lot.
+            return OrderKey::default();
+        }
+
+        let Surface::SweptCurve(surface) = self.surface();
+        let (kind, parameters) = match &surface.curve {
+            Curve::Line(line) => {
+                let normal =
+                    canonical_direction(line.direction.cross(&surface.path));
+                let distance = line.origin.coords.dot(&normal);
+
+                let mut parameters = normal.components.to_vec();
+                parameters.push(distance);
+                (1, parameters)
+            }
+            Curve::Circle(circle) => {
+                // The center can be anywhere on the axis.
+                let axis = canonical_direction(surface.path);
+                let center = circle.center.coords;
+                let center = center - axis * center.dot(&axis);
+                let radius = circle.a.magnitude();
+
+                let mut parameters = axis.components.to_vec();
+                parameters.extend(center.components);
+                parameters.push(radius);
+                (2, parameters)
+            }
+            Curve::Polyline(_) => (3, Vec::new()),
+            Curve::Ellipse(_) => (4, Vec::new()),
+        };
+
+        let mut vertices: Vec<_> = self
+            .all_cycles()
+            .flat_map(|cycle| &cycle.edges)
+            .filter_map(|edge| edge.vertices().get())
+            .flatten()
+            .map(|vertex| {
+                vertex.global().position().coords.components.map(quantize)
+            })
+            .collect();
+        vertices.sort_unstable();
+
+        OrderKey {
+            kind,
+            surface: parameters.into_iter().map(quantize).collect(),
+            vertices,
+        }
+    }
+
     /// Access the boundary representation of the face
     fn brep(&self) -> &BRep {
         if let Representation::BRep(face) = &self.representation {
@@ -97,6 +296,56 @@ impl Face {
         // method.
         unreachable!()
     }
+
+    fn brep_mut(&mut self) -> &mut BRep {
+        if let Representation::BRep(face) = &mut self.representation {
+            return face;
+        }
+
+        unreachable!()
+    }
+}
+
+impl Ord for Face {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.order_key
+            .cmp(&other.order_key)
+            .then_with(|| self.representation.cmp(&other.representation))
+    }
+}
+
+impl PartialOrd for Face {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+struct OrderKey {
+    kind: u8,
+    surface: Vec<i64>,
+    vertices: Vec<[i64; 3]>,
+}
+
+/// Normalize a direction, and make it point the same way as its opposite
+///
+/// Flipping the surface of a face doesn't move it, so it must not affect its
+/// place in the order of faces either.
+fn canonical_direction(direction: Vector<3>) -> Vector<3> {
+    let direction = direction.normalize();
+
+    let first = direction
+        .components
+        .into_iter()
+        .find(|&component| quantize(component) != 0);
+    match first {
+        Some(component) if component < Scalar::ZERO => -direction,
+        _ => direction,
+    }
+}
+
+fn quantize(value: Scalar) -> i64 {
+    (value.into_f64() / Face::ORDER_RESOLUTION).round() as i64
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
@@ -111,6 +360,8 @@ struct BRep {
     exteriors: Vec<Cycle>,
     interiors: Vec<Cycle>,
     color: [u8; 4],
+    seam: Option<Scalar>,
+    tolerance: Option<Tolerance>,
 }
 
 type TriRep = Vec<(Triangle<3>, Color)>;
@@ -17,7 +17,7 @@ mod vertex;
 pub use self::{
     curve::Curve,
     cycle::Cycle,
-    edge::{Edge, VerticesOfEdge},
+    edge::{Edge, InvalidVertexOrder, SampleError, VerticesOfEdge},
     face::Face,
     global_vertex::GlobalVertex,
     sketch::Sketch,
@@ -2,12 +2,20 @@ use std::collections::BTreeSet;
 
 use fj_math::Scalar;
 
-use crate::algorithms::TransformObject;
+use crate::{algorithms::TransformObject, validation::reorient_outward};
 
 use super::{Face, Surface};
 
 /// A 3-dimensional shape
 ///
+/// The faces of a solid are kept in the order defined by [`Face`]'s `Ord`
+/// implementation, which only depends on their content. Solids that are made
+/// of the same faces are equal, and iterate over them in the same order,
+/// regardless of the order the faces were passed to [`Solid::from_faces`] in.
+/// This makes anything derived from a solid, like its triangle mesh or an
+/// exported file, reproducible. See [the ordering of faces](Face#ordering)
+/// for details.
+///
 /// # Implementation Note
 ///
 /// The faces that make up the solid must form a closed shape. This is not
@@ -25,24 +33,31 @@ impl Solid {
     }
 
     /// Create a cube from the length of its edges
+    ///
+    /// The cube is centered on the origin. Its faces point outward.
     pub fn cube_from_edge_length(edge_length: impl Into<Scalar>) -> Self {
         // Let's define a short-hand for half the edge length. We're going to
         // need it a lot.
         let h = edge_length.into() / 2.;
 
+        // Counter-clockwise in surface coordinates, so a face made from these
+        // points faces in the direction of its surface's normal. Where that
+        // normal points into the cube, the points are reversed.
         let points = [[-h, -h], [h, -h], [h, h], [-h, h]];
+        let mut reversed = points;
+        reversed.reverse();
 
         const Z: Scalar = Scalar::ZERO;
         let planes = [
-            Surface::xy_plane().translate([Z, Z, -h]), // bottom
-            Surface::xy_plane().translate([Z, Z, h]),  // top
-            Surface::xz_plane().translate([Z, -h, Z]), // front
-            Surface::xz_plane().translate([Z, h, Z]),  // back
-            Surface::yz_plane().translate([-h, Z, Z]), // left
-            Surface::yz_plane().translate([h, Z, Z]),  // right
+            (Surface::xy_plane().translate([Z, Z, -h]), reversed), // bottom
+            (Surface::xy_plane().translate([Z, Z, h]), points),    // top
+            (Surface::xz_plane().translate([Z, -h, Z]), points),   // front
+            (Surface::xz_plane().translate([Z, h, Z]), reversed),  // back
+            (Surface::yz_plane().translate([-h, Z, Z]), reversed), // left
+            (Surface::yz_plane().translate([h, Z, Z]), points),    // right
         ];
 
-        let faces = planes.map(|plane| {
+        let faces = planes.map(|(plane, points)| {
             Face::builder(plane).with_exterior_polygon(points).build()
         });
 
@@ -54,8 +69,72 @@ impl Solid {
         self.faces.iter()
     }
 
+    /// Reverse the faces of all shells that point in the wrong direction
+    ///
+    /// The outer shell of a solid must point outward, while the shell of a
+    /// cavity must point inward, into the cavity. Shells that already point in
+    /// the right direction are left as they are. See [`check_shells`] for
+    /// details.
+    ///
+    /// [`check_shells`]: crate::validation::check_shells
+    #[must_use]
+    pub fn reoriented_outward(self) -> Self {
+        let faces = self.faces.into_iter().collect();
+        Self::from_faces(reorient_outward(faces))
+    }
+
     /// Convert the solid into a list of faces
     pub fn into_faces(self) -> BTreeSet<Face> {
         self.faces
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        algorithms::TransformObject,
+        objects::{Face, Surface},
+    };
+
+    use super::Solid;
+
+    #[test]
+    fn face_order() {
+        let cube = Solid::cube_from_edge_length(2.);
+
+        let mut faces: Vec<_> = cube.clone().into_faces().into_iter().collect();
+        faces.reverse();
+        faces.rotate_left(2);
+
+        let shuffled = Solid::from_faces(faces.clone());
+        assert_eq!(
+            cube.faces().collect::<Vec<_>>(),
+            shuffled.faces().collect::<Vec<_>>()
+        );
+
+        // The same bottom face, but on a flipped surface with a different
+        // origin. It's not equal to the original, but takes its place.
+        let bottom = Surface::xy_plane().translate([0., 0., -1.]);
+        let flipped = Face::builder(Surface::plane_from_points([
+            [3., 0., -1.],
+            [3., 1., -1.],
+            [4., 0., -1.],
+        ]))
+        .with_exterior_polygon([[-1., -4.], [1., -4.], [1., -2.], [-1., -2.]])
+        .build();
+
+        let faces = faces.into_iter().map(|face| {
+            if face.surface() == &bottom {
+                flipped.clone()
+            } else {
+                face
+            }
+        });
+        let solid = Solid::from_faces(faces);
+
+        assert_eq!(
+            cube.faces().position(|face| face.surface() == &bottom),
+            solid.faces().position(|face| face == &flipped),
+        );
+    }
+}
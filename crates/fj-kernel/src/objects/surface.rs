@@ -1,11 +1,11 @@
-use fj_math::{Line, Point, Transform, Vector};
+use fj_math::{Circle, Line, Point, Scalar, Transform, Transform2, Vector};
 
 use crate::algorithms::TransformObject;
 
 use super::Curve;
 
 /// A two-dimensional shape
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub enum Surface {
     /// A swept curve
     SweptCurve(SweptCurve),
@@ -46,6 +46,35 @@ impl Surface {
         Self::SweptCurve(SweptCurve { curve, path })
     }
 
+    /// Construct a cylinder around an axis
+    ///
+    /// The u-coordinate of the surface is the angle around the axis, starting
+    /// at `reference`, which is projected into the plane perpendicular to the
+    /// axis. The v-coordinate is the height along the axis, starting at
+    /// `origin`. The normal of the surface points away from the axis.
+    pub fn cylinder(
+        origin: impl Into<Point<3>>,
+        axis: impl Into<Vector<3>>,
+        radius: impl Into<Scalar>,
+        reference: impl Into<Vector<3>>,
+    ) -> Self {
+        let axis = axis.into().normalize();
+        let reference = reference.into();
+
+        let a = (reference - axis * reference.dot(&axis)).normalize()
+            * radius.into();
+        let b = axis.cross(&a);
+
+        Self::SweptCurve(SweptCurve {
+            curve: Curve::Circle(Circle {
+                center: origin.into(),
+                a,
+                b,
+            }),
+            path: axis,
+        })
+    }
+
     /// Create a new instance that is reversed
     #[must_use]
     pub fn reverse(self) -> Self {
@@ -54,6 +83,54 @@ impl Surface {
         }
     }
 
+    /// Create an equivalent surface with an isometric parameterization
+    ///
+    /// For planes, the returned surface has orthogonal coordinate axes of unit
+    /// length, so distances in surface coordinates match distances in model
+    /// coordinates. Other surfaces are returned unchanged.
+    ///
+    /// Use [`Surface::normalization_transform`] to convert surface coordinates
+    /// of this surface into those of the returned one.
+    #[must_use]
+    pub fn normalized(&self) -> Self {
+        match self {
+            Self::SweptCurve(surface) => Self::SweptCurve(surface.normalized()),
+        }
+    }
+
+    /// Access the mapping from this surface's coordinates to normalized ones
+    ///
+    /// Converts surface coordinates of this surface into surface coordinates
+    /// of [`Surface::normalized`] that refer to the same point in model
+    /// coordinates.
+    pub fn normalization_transform(&self) -> Transform2 {
+        match self {
+            Self::SweptCurve(surface) => surface.normalization_transform(),
+        }
+    }
+
+    /// Access the period of the surface in the u-direction, if it is periodic
+    ///
+    /// Surfaces that are swept from closed curves, like circles, repeat
+    /// themselves after a full turn of the curve.
+    pub fn period(&self) -> Option<Scalar> {
+        match self {
+            Self::SweptCurve(surface) => surface.period(),
+        }
+    }
+
+    /// Access the default location of the seam, if the surface is periodic
+    ///
+    /// To unroll a periodic surface into a rectangle of surface coordinates,
+    /// it is cut open along a line of constant u-coordinate, the seam. This
+    /// returns the u-coordinate of that line. Faces can override it (see
+    /// [`Face::with_seam`]).
+    ///
+    /// [`Face::with_seam`]: super::Face::with_seam
+    pub fn seam(&self) -> Option<Scalar> {
+        self.period().map(|_| Scalar::ZERO)
+    }
+
     /// Convert a point in surface coordinates to model coordinates
     pub fn point_from_surface_coords(
         &self,
@@ -66,6 +143,34 @@ impl Surface {
         }
     }
 
+    /// Convert a point in model coordinates to surface coordinates
+    ///
+    /// The point is projected onto the surface along its normal, for planes,
+    /// or along the plane of the curve it was swept from, for surfaces swept
+    /// from circles and ellipses. For periodic surfaces, the u-coordinate is
+    /// between `0.` (inclusive) and the period (exclusive).
+    ///
+    /// Returns `None` for surfaces swept from polylines, or if the surface is
+    /// degenerate.
+    pub fn point_to_surface_coords(
+        &self,
+        point: impl Into<Point<3>>,
+    ) -> Option<Point<2>> {
+        match self {
+            Self::SweptCurve(surface) => surface.point_to_surface_coords(point),
+        }
+    }
+
+    /// Compute the normal of the surface at the given surface coordinates
+    ///
+    /// The normal is the cross product of the surface's u- and v-axis at that
+    /// point. It is normalized.
+    pub fn normal_at(&self, point: impl Into<Point<2>>) -> Vector<3> {
+        match self {
+            Self::SweptCurve(surface) => surface.normal_at(point),
+        }
+    }
+
     /// Convert a vector in surface coordinates to model coordinates
     pub fn vector_from_surface_coords(
         &self,
@@ -80,7 +185,7 @@ impl Surface {
 }
 
 /// A surface that was swept from a curve
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct SweptCurve {
     /// The curve that this surface was swept from
     pub curve: Curve<3>,
@@ -105,6 +210,64 @@ impl SweptCurve {
         self
     }
 
+    /// Create an equivalent surface with an isometric parameterization
+    ///
+    /// See [`Surface::normalized`].
+    #[must_use]
+    pub fn normalized(&self) -> Self {
+        let line = match self.curve {
+            Curve::Line(line) => line,
+            Curve::Circle(_) | Curve::Ellipse(_) | Curve::Polyline(_) => {
+                return self.clone()
+            }
+        };
+
+        let u = line.direction.normalize();
+        let v = (self.path - u * self.path.dot(&u)).normalize();
+
+        Self {
+            curve: Curve::Line(Line {
+                origin: line.origin,
+                direction: u,
+            }),
+            path: v,
+        }
+    }
+
+    /// Access the mapping from this surface's coordinates to normalized ones
+    ///
+    /// See [`Surface::normalization_transform`].
+    pub fn normalization_transform(&self) -> Transform2 {
+        let line = match self.curve {
+            Curve::Line(line) => line,
+            Curve::Circle(_) | Curve::Ellipse(_) | Curve::Polyline(_) => {
+                return Transform2::identity();
+            }
+        };
+
+        let normalized = self.normalized();
+        let u = normalized.curve.vector_from_curve_coords([1.]);
+        let v = normalized.path;
+
+        // Both surfaces share an origin, so the mapping is linear. Its columns
+        // are the original axes, expressed in the normalized ones.
+        Transform2::from_axes(
+            [line.direction.dot(&u), line.direction.dot(&v)],
+            [self.path.dot(&u), self.path.dot(&v)],
+            [0., 0.],
+        )
+    }
+
+    /// Access the period of the surface in the u-direction, if it is periodic
+    ///
+    /// See [`Surface::period`].
+    pub fn period(&self) -> Option<Scalar> {
+        match self.curve {
+            Curve::Circle(_) | Curve::Ellipse(_) => Some(Scalar::PI * 2.),
+            Curve::Line(_) | Curve::Polyline(_) => None,
+        }
+    }
+
     /// Convert a point in surface coordinates to model coordinates
     pub fn point_from_surface_coords(
         &self,
@@ -115,6 +278,66 @@ impl SweptCurve {
             + self.path_to_line().vector_from_line_coords([point.v])
     }
 
+    /// Convert a point in model coordinates to surface coordinates
+    ///
+    /// See [`Surface::point_to_surface_coords`].
+    pub fn point_to_surface_coords(
+        &self,
+        point: impl Into<Point<3>>,
+    ) -> Option<Point<2>> {
+        let point = point.into();
+
+        let (origin, [a, b]) = match self.curve {
+            Curve::Line(line) => {
+                // The point is decomposed along the axes of the plane, which
+                // don't need to be orthogonal.
+                let [u, v] = [line.direction, self.path];
+                let normal = u.cross(&v);
+                let norm = normal.dot(&normal);
+                if norm == Scalar::ZERO {
+                    return None;
+                }
+
+                let d = point - line.origin;
+                return Some(Point::from([
+                    d.cross(&v).dot(&normal) / norm,
+                    u.cross(&d).dot(&normal) / norm,
+                ]));
+            }
+            Curve::Circle(circle) => (circle.center, [circle.a, circle.b]),
+            Curve::Ellipse(ellipse) => (ellipse.center, [ellipse.a, ellipse.b]),
+            Curve::Polyline(_) => return None,
+        };
+
+        // The height along the path is measured perpendicular to the plane of
+        // the curve, so paths that are oblique to it work too.
+        let normal = a.cross(&b);
+        let height = self.path.dot(&normal);
+        if height == Scalar::ZERO {
+            return None;
+        }
+
+        let d = point - origin;
+        let v = d.dot(&normal) / height;
+
+        let in_plane = d - self.path * v;
+        let cos = in_plane.dot(&a) / a.dot(&a);
+        let sin = in_plane.dot(&b) / b.dot(&b);
+
+        let mut u = Scalar::atan2(sin, cos);
+        if u < Scalar::ZERO {
+            u += Scalar::PI * 2.;
+        }
+
+        // A tiny negative angle, due to rounding, ends up as a full turn, which
+        // is the start of the curve.
+        if u >= Scalar::PI * 2. {
+            u = Scalar::ZERO;
+        }
+
+        Some(Point::from([u, v]))
+    }
+
     /// Convert a vector in surface coordinates to model coordinates
     pub fn vector_from_surface_coords(
         &self,
@@ -125,6 +348,17 @@ impl SweptCurve {
             + self.path_to_line().vector_from_line_coords([vector.v])
     }
 
+    /// Compute the normal of the surface at the given surface coordinates
+    ///
+    /// See [`Surface::normal_at`].
+    pub fn normal_at(&self, point: impl Into<Point<2>>) -> Vector<3> {
+        let point = point.into();
+        self.curve
+            .tangent_at([point.u])
+            .cross(&self.path)
+            .normalize()
+    }
+
     fn path_to_line(&self) -> Line<3> {
         Line {
             origin: self.curve.origin(),
@@ -135,12 +369,13 @@ impl SweptCurve {
 
 #[cfg(test)]
 mod tests {
-    use fj_math::{Line, Point, Vector};
+    use approx::assert_abs_diff_eq;
+    use fj_math::{Line, Point, Scalar, Transform, Vector};
     use pretty_assertions::assert_eq;
 
-    use crate::objects::Curve;
+    use crate::{algorithms::TransformObject, objects::Curve};
 
-    use super::SweptCurve;
+    use super::{Surface, SweptCurve};
 
     #[test]
     fn reverse() {
@@ -195,4 +430,116 @@ mod tests {
             Vector::from([0., 4., 8.]),
         );
     }
+
+    #[test]
+    fn normalized() {
+        let swept = SweptCurve {
+            curve: Curve::Line(Line {
+                origin: Point::from([1., 0., 0.]),
+                direction: Vector::from([10., 0., 0.]),
+            }),
+            path: Vector::from([1., 2., 0.]),
+        };
+
+        let normalized = swept.normalized();
+        assert_eq!(
+            normalized,
+            SweptCurve {
+                curve: Curve::Line(Line {
+                    origin: Point::from([1., 0., 0.]),
+                    direction: Vector::from([1., 0., 0.]),
+                }),
+                path: Vector::from([0., 1., 0.]),
+            }
+        );
+
+        let transform = swept.normalization_transform();
+        for point in [[0., 0.], [1., 0.], [0., 1.], [0.5, -2.]] {
+            let point = Point::from(point);
+
+            assert_abs_diff_eq!(
+                normalized.point_from_surface_coords(
+                    transform.transform_point(&point)
+                ),
+                swept.point_from_surface_coords(point),
+                epsilon = 1e-12,
+            );
+        }
+    }
+
+    #[test]
+    fn point_to_surface_coords() {
+        let plane = SweptCurve {
+            curve: Curve::Line(Line {
+                origin: Point::from([1., 0., 0.]),
+                direction: Vector::from([0., 2., 0.]),
+            }),
+            path: Vector::from([1., 0., 2.]),
+        };
+        let cylinder =
+            Surface::cylinder([1., 2., 3.], [0., 0., 2.], 3., [1., 1., 0.]);
+
+        for point in [[0., 0.], [1., 0.], [0.5, -2.], [4., 3.]] {
+            let point = Point::from(point);
+
+            let surface = Surface::SweptCurve(plane.clone());
+            let model = surface.point_from_surface_coords(point);
+            assert_abs_diff_eq!(
+                surface.point_to_surface_coords(model).unwrap(),
+                point,
+                epsilon = 1e-12,
+            );
+
+            let model = cylinder.point_from_surface_coords(point);
+            assert_abs_diff_eq!(
+                cylinder.point_to_surface_coords(model).unwrap(),
+                point,
+                epsilon = 1e-12,
+            );
+        }
+    }
+
+    #[test]
+    fn cylinder() {
+        let cylinder =
+            Surface::cylinder([1., 2., 3.], [0., 0., 2.], 3., [1., 1., 0.]);
+
+        let start = Point::from([1., 2., 3.])
+            + Vector::from([1., 1., 0.]).normalize() * 3.;
+        assert_abs_diff_eq!(
+            cylinder.point_from_surface_coords([0., 0.]),
+            start,
+            epsilon = 1e-12,
+        );
+
+        // The v-coordinate is the height along the axis, and the normal
+        // points away from it.
+        assert_abs_diff_eq!(
+            cylinder.point_from_surface_coords([0., 2.]),
+            start + Vector::from([0., 0., 2.]),
+            epsilon = 1e-12,
+        );
+        assert_abs_diff_eq!(
+            cylinder.normal_at([0., 0.]),
+            Vector::from([1., 1., 0.]).normalize(),
+            epsilon = 1e-12,
+        );
+
+        // Rigid transforms keep the radius, and move the axis and the
+        // reference direction along.
+        let transform = Transform::translation([1., 0., 0.])
+            * Transform::rotation(Vector::unit_x() * (Scalar::PI / 2.));
+        let transformed = cylinder.clone().transform(&transform);
+        for point in [[0., 0.], [1., 0.], [2., -1.]] {
+            let point = Point::from(point);
+
+            assert_abs_diff_eq!(
+                transformed.point_from_surface_coords(point),
+                transform.transform_point(
+                    &cylinder.point_from_surface_coords(point)
+                ),
+                epsilon = 1e-12,
+            );
+        }
+    }
 }
@@ -0,0 +1,137 @@
+use std::fmt;
+
+use fj_math::{Point, Scalar};
+
+use crate::{
+    algorithms::{CycleApprox, Tolerance},
+    objects::Face,
+};
+
+pub fn validate_face(
+    face: &Face,
+    face_index: usize,
+    tolerance: Tolerance,
+) -> Result<(), GeometricIssues> {
+    if face.triangles().is_some() {
+        return Ok(());
+    }
+
+    // Curved edges are approximated, and the segments of the approximation
+    // are checked against each other. Consecutive segments share a vertex, so
+    // they always touch there. That's fine, and they are not checked.
+    //
+    // The segments of any other pair must not cross each other.
+
+    let mut self_intersecting_cycles = Vec::new();
+
+    for (cycle_index, cycle) in face.all_cycles().enumerate() {
+        let points: Vec<_> = CycleApprox::new(cycle, tolerance)
+            .points
+            .into_iter()
+            .map(|point| *point.local_form())
+            .collect();
+        let num_segments = points.len();
+        let segment = |i: usize| [points[i], points[(i + 1) % points.len()]];
+
+        'segments: for i in 0..num_segments {
+            for j in i + 2..num_segments {
+                let is_consecutive = i == 0 && j == num_segments - 1;
+                if is_consecutive {
+                    continue;
+                }
+
+                if let Some(point) = crossing(segment(i), segment(j)) {
+                    self_intersecting_cycles.push(SelfIntersection {
+                        face: face_index,
+                        cycle: cycle_index,
+                        point: face.surface().point_from_surface_coords(point),
+                    });
+
+                    // One intersection is enough to find the problem.
+                    break 'segments;
+                }
+            }
+        }
+    }
+
+    if !self_intersecting_cycles.is_empty() {
+        return Err(GeometricIssues {
+            self_intersecting_cycles,
+        });
+    }
+
+    Ok(())
+}
+
+/// Geometric issues found during validation
+///
+/// Used by [`ValidationError`].
+///
+/// [`ValidationError`]: super::ValidationError
+#[derive(Debug, Default, thiserror::Error)]
+pub struct GeometricIssues {
+    /// Cycles whose edges cross each other
+    pub self_intersecting_cycles: Vec<SelfIntersection>,
+}
+
+impl fmt::Display for GeometricIssues {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Geometric issues found:")?;
+
+        if !self.self_intersecting_cycles.is_empty() {
+            writeln!(f, "- Self-intersecting cycles:")?;
+
+            for intersection in &self.self_intersecting_cycles {
+                writeln!(f, "  - {}", intersection)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A cycle whose edges cross each other
+#[derive(Debug)]
+pub struct SelfIntersection {
+    /// The index of the face, in the order of iteration over the shape
+    pub face: usize,
+
+    /// The index of the cycle within the face
+    ///
+    /// The exterior cycles come first, then the interior ones.
+    pub cycle: usize,
+
+    /// The approximate point of intersection
+    pub point: Point<3>,
+}
+
+impl fmt::Display for SelfIntersection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "cycle {} of face {} intersects itself near {:?}",
+            self.cycle, self.face, self.point
+        )
+    }
+}
+
+/// The point at which two line segments cross each other
+///
+/// Segments that merely touch, or that are collinear, don't cross.
+fn crossing([a, b]: [Point<2>; 2], [c, d]: [Point<2>; 2]) -> Option<Point<2>> {
+    let orientation = |p: Point<2>, q: Point<2>, r: Point<2>| {
+        let [pq, pr] = [q - p, r - p];
+        pq.u * pr.v - pq.v * pr.u
+    };
+
+    let [ab_c, ab_d] = [orientation(a, b, c), orientation(a, b, d)];
+    let [cd_a, cd_b] = [orientation(c, d, a), orientation(c, d, b)];
+
+    let on_opposite_sides = |x: Scalar, y: Scalar| x * y < Scalar::ZERO;
+    if !on_opposite_sides(ab_c, ab_d) || !on_opposite_sides(cd_a, cd_b) {
+        return None;
+    }
+
+    let t = cd_a / (cd_a - cd_b);
+    Some(a + (b - a) * t)
+}
@@ -8,25 +8,49 @@
 //! - **Geometric:** Comprises various object-specific constraints, for example
 //!   edges or faces might not be allowed to intersect.
 //! - **Structural:** All other objects that an object references must be part
-//!   of the same shape.
+//!   of the same shape. For example, the edges of a face must have been created
+//!   for the surface of that face.
 //! - **Uniqueness:** Objects within a shape must be unique.
 //!
 //! Please note that not all of these validation categories are fully
 //! implemented, as of this writing.
+//!
+//! Some issues don't make an object invalid, but are probably not intended.
+//! Those are reported as warnings by separate checks, like
+//! [`check_orientation`]. Some of them, like inverted shells (see
+//! [`check_shells`]), can be turned into errors, using their [`Severity`] in
+//! [`ValidationConfig`].
+//!
+//! Validating the result of every step of a long chain of operations can get
+//! expensive, as the same geometry is validated over and over. The
+//! [`ValidationScope`] in [`ValidationConfig`] controls whether intermediate
+//! results are validated (see [`validate_intermediate`]), or whether validation
+//! is deferred until the final result (see [`validate_deferred`]).
 
 mod coherence;
+mod geometric;
+mod orientation;
+mod shells;
+mod structure;
+mod topology;
 mod uniqueness;
 
 pub use self::{
     coherence::{CoherenceIssues, CoherenceMismatch},
+    geometric::{GeometricIssues, SelfIntersection},
+    orientation::{check_orientation, OrientationWarning},
+    shells::{check_shells, InvertedShell},
+    structure::StructuralIssues,
     uniqueness::UniquenessIssues,
 };
 
+pub(crate) use self::shells::reorient_outward;
+
 use std::{collections::HashSet, ops::Deref};
 
-use fj_math::Scalar;
+use fj_math::{Aabb, Point, Scalar};
 
-use crate::iter::ObjectIters;
+use crate::{algorithms::IntersectionTolerances, iter::ObjectIters};
 
 /// Validate the given object
 pub fn validate<T>(
@@ -36,6 +60,12 @@ pub fn validate<T>(
 where
     T: for<'r> ObjectIters<'r>,
 {
+    #[cfg(test)]
+    tests::VALIDATION_RUNS.with(|runs| runs.set(runs.get() + 1));
+
+    let _span =
+        fj_interop::span!(INFO, "validate", faces = object.face_iter().count());
+
     let mut vertices = HashSet::new();
 
     for vertex in object.global_vertex_iter() {
@@ -52,11 +82,84 @@ where
         coherence::validate_edge(edge, config.identical_max_distance)?;
     }
 
+    // An edge that doesn't belong to its face is way off, not just slightly
+    // inaccurate. Mapping its points through the surface of the face adds
+    // floating-point error, which must not be mistaken for that.
+    for face in object.face_iter() {
+        structure::validate_face(face, config.distinct_min_distance)?;
+    }
+
+    if config.self_intersecting_cycles {
+        let tolerance = shells::tolerance_for(&object);
+        for (index, face) in object.face_iter().enumerate() {
+            geometric::validate_face(face, index, tolerance)?;
+        }
+    }
+
+    if config.solid_topology {
+        for (index, solid) in object.solid_iter().enumerate() {
+            topology::validate_solid(solid, index)?;
+        }
+    }
+
+    if config.inverted_shells == Severity::Error {
+        let tolerance = shells::tolerance_for(&object);
+        if let Some(shell) = check_shells(&object, tolerance).first() {
+            return Err(ValidationError::InvertedShell {
+                shell_index: shell.shell_index,
+                volume: shell.volume,
+            });
+        }
+    }
+
     Ok(Validated(object))
 }
 
+/// Validate an intermediate result of an operation
+///
+/// Validates the object, if [`ValidationConfig::scope`] is
+/// [`ValidationScope::ValidateEachStep`]. Otherwise, the object is passed
+/// through without validation, and must be validated later using
+/// [`validate_deferred`].
+pub fn validate_intermediate<T>(
+    object: T,
+    config: &ValidationConfig,
+) -> Result<Validated<T>, ValidationError>
+where
+    T: for<'r> ObjectIters<'r>,
+{
+    match config.scope {
+        ValidationScope::ValidateEachStep => validate(object, config),
+        ValidationScope::ValidateAtRoot => Ok(Validated(object)),
+    }
+}
+
+/// Perform any validation that was skipped for an object
+///
+/// If [`ValidationConfig::scope`] is [`ValidationScope::ValidateAtRoot`], the
+/// object might be the result of [`validate_intermediate`] skipping
+/// validation, and it is validated now. Any error is wrapped in
+/// [`ValidationError::Deferred`], to indicate that its cause might lie in an
+/// earlier, unvalidated step.
+///
+/// This must be called on the final result of an operation tree. Operations
+/// that need validated input to work correctly can call it on their input.
+pub fn validate_deferred<T>(
+    object: Validated<T>,
+    config: &ValidationConfig,
+) -> Result<Validated<T>, ValidationError>
+where
+    T: for<'r> ObjectIters<'r>,
+{
+    match config.scope {
+        ValidationScope::ValidateEachStep => Ok(object),
+        ValidationScope::ValidateAtRoot => validate(object.0, config)
+            .map_err(|err| ValidationError::Deferred(Box::new(err))),
+    }
+}
+
 /// Configuration required for the validation process
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct ValidationConfig {
     /// The minimum distance between distinct objects
     ///
@@ -71,6 +174,59 @@ pub struct ValidationConfig {
     /// that distance is less than the one defined in this field, can not be
     /// considered identical.
     pub identical_max_distance: Scalar,
+
+    /// Which results of a chain of operations are validated
+    pub scope: ValidationScope,
+
+    /// How closed shells that point in the wrong direction are treated
+    ///
+    /// See [`check_shells`].
+    pub inverted_shells: Severity,
+
+    /// Whether inverted shells are reoriented automatically
+    ///
+    /// Operations that create solids reorient them using
+    /// [`Solid::reoriented_outward`], before validating them.
+    ///
+    /// [`Solid::reoriented_outward`]: crate::objects::Solid::reoriented_outward
+    pub auto_reorient: bool,
+
+    /// Whether the faces of solids are checked to fit together
+    ///
+    /// If enabled, each edge of a solid must be shared by exactly two faces,
+    /// which traverse it in opposite directions. Disable this for solids that
+    /// are open shells on purpose.
+    pub solid_topology: bool,
+
+    /// Whether cycles are checked for self-intersections
+    ///
+    /// If enabled, the edges of each cycle must not cross each other. Curved
+    /// edges are approximated for the check, relative to the size of the
+    /// shape.
+    pub self_intersecting_cycles: bool,
+
+    /// The tolerances that intersection algorithms use
+    ///
+    /// If this is `None`, the tolerances are derived from the bounding volume
+    /// of the shape that is being processed. See
+    /// [`ValidationConfig::intersection_tolerances`].
+    pub intersection: Option<IntersectionTolerances>,
+}
+
+impl ValidationConfig {
+    /// The tolerances that intersection algorithms use for a shape
+    ///
+    /// Returns [`ValidationConfig::intersection`], if set. Otherwise, derives
+    /// the tolerances from the bounding volume of the shape, using
+    /// [`IntersectionTolerances::relative_to`].
+    pub fn intersection_tolerances(
+        &self,
+        bounding_volume: &Aabb<3>,
+    ) -> IntersectionTolerances {
+        self.intersection.unwrap_or_else(|| {
+            IntersectionTolerances::relative_to(bounding_volume)
+        })
+    }
 }
 
 impl Default for ValidationConfig {
@@ -83,10 +239,56 @@ impl Default for ValidationConfig {
             // false positives due to floating-point accuracy issues), we can
             // adjust it.
             identical_max_distance: Scalar::from_f64(5e-14),
+
+            scope: ValidationScope::default(),
+            inverted_shells: Severity::default(),
+            auto_reorient: false,
+            solid_topology: true,
+            self_intersecting_cycles: true,
+            intersection: None,
         }
     }
 }
 
+/// Defines which results of a chain of operations are validated
+///
+/// See [`ValidationConfig::scope`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ValidationScope {
+    /// Validate the result of every operation
+    ValidateEachStep,
+
+    /// Only validate the final result
+    ///
+    /// Intermediate results are only validated, if an operation explicitly
+    /// requests it for its input.
+    ValidateAtRoot,
+}
+
+impl Default for ValidationScope {
+    fn default() -> Self {
+        Self::ValidateEachStep
+    }
+}
+
+/// How an issue that is found during validation is treated
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Severity {
+    /// The issue doesn't fail validation
+    ///
+    /// It can still be found, using the separate check for it.
+    Warn,
+
+    /// The issue fails validation
+    Error,
+}
+
+impl Default for Severity {
+    fn default() -> Self {
+        Self::Warn
+    }
+}
+
 /// Wrapper around an object that indicates the object has been validated
 ///
 /// Returned by implementations of `Validate`.
@@ -118,23 +320,126 @@ pub enum ValidationError {
 
     /// Geometric validation failed
     #[error("Geometric validation failed")]
-    Geometric,
+    Geometric(#[from] GeometricIssues),
+
+    /// Structural validation failed
+    #[error("Structural validation failed")]
+    Structural(#[from] StructuralIssues),
 
     /// Uniqueness validation failed
     #[error("Uniqueness validation failed")]
     Uniqueness(#[from] UniquenessIssues),
+
+    /// A closed shell points in the wrong direction
+    ///
+    /// Only returned, if [`ValidationConfig::inverted_shells`] is
+    /// [`Severity::Error`].
+    #[error("Shell {shell_index} is inverted (volume: {volume})")]
+    InvertedShell {
+        /// The index of the shell
+        shell_index: usize,
+
+        /// The volume that the shell encloses
+        volume: Scalar,
+    },
+
+    /// Two adjacent faces of a solid are oriented inconsistently
+    ///
+    /// Both faces traverse their shared edge in the same direction, so one of
+    /// them points the wrong way. Only returned, if
+    /// [`ValidationConfig::solid_topology`] is enabled.
+    #[error(
+        "Faces {} and {} of solid {solid} are oriented inconsistently; both \
+        traverse the edge from {:?} to {:?}",
+        .faces[0], .faces[1], .edge[0], .edge[1]
+    )]
+    Orientation {
+        /// The index of the solid
+        solid: usize,
+
+        /// The indices of the conflicting faces within the solid
+        faces: [usize; 2],
+
+        /// The vertices of the edge, in the direction both faces traverse it
+        edge: [Point<3>; 2],
+    },
+
+    /// An edge of a solid belongs to only one face
+    ///
+    /// The shell of the solid is open. Only returned, if
+    /// [`ValidationConfig::solid_topology`] is enabled.
+    #[error(
+        "Edge from {:?} to {:?} of solid {solid} only belongs to face {face}",
+        .edge[0], .edge[1]
+    )]
+    OpenEdge {
+        /// The index of the solid
+        solid: usize,
+
+        /// The index of the face within the solid
+        face: usize,
+
+        /// The vertices of the edge
+        edge: [Point<3>; 2],
+    },
+
+    /// An edge of a solid is shared by more than two faces
+    ///
+    /// Only returned, if [`ValidationConfig::solid_topology`] is enabled.
+    #[error(
+        "Edge from {:?} to {:?} of solid {solid} is shared by faces {faces:?}",
+        .edge[0], .edge[1]
+    )]
+    NonManifoldEdge {
+        /// The index of the solid
+        solid: usize,
+
+        /// The indices of the faces within the solid
+        faces: Vec<usize>,
+
+        /// The vertices of the edge
+        edge: [Point<3>; 2],
+    },
+
+    /// Deferred validation failed
+    ///
+    /// Returned by [`validate_deferred`]. The validation of intermediate
+    /// results was skipped, so the problem might originate from any of them.
+    #[error(
+        "Validation failed; the problem may originate from an unvalidated \
+        intermediate result"
+    )]
+    Deferred(#[source] Box<ValidationError>),
 }
 
 #[cfg(test)]
 mod tests {
-    use fj_math::{Point, Scalar};
+    use std::cell::Cell;
+
+    use fj_math::{Point, Scalar, Transform, Vector};
 
     use crate::{
+        algorithms::{
+            project_curve_into_surface, project_curve_onto_surface,
+            transform_faces, Tolerance, TransformObject,
+        },
+        builder::FaceBuilder,
         local::Local,
-        objects::{Curve, Edge, GlobalVertex, Vertex, VerticesOfEdge},
-        validation::{validate, ValidationConfig, ValidationError},
+        objects::{
+            Curve, Cycle, Edge, Face, GlobalVertex, Surface, Vertex,
+            VerticesOfEdge,
+        },
+        validation::{
+            validate, validate_deferred, validate_intermediate,
+            ValidationConfig, ValidationError, ValidationScope,
+        },
     };
 
+    thread_local! {
+        /// Counts the runs of [`validate`], for instrumentation in tests
+        pub static VALIDATION_RUNS: Cell<usize> = Cell::new(0);
+    }
+
     #[test]
     fn coherence_edge() {
         let a = Point::from([0., 0., 0.]);
@@ -158,7 +463,7 @@ mod tests {
         let edge = Edge::new(curve, vertices);
 
         let result = validate(
-            edge,
+            edge.clone(),
             &ValidationConfig {
                 identical_max_distance: deviation * 2.,
                 ..ValidationConfig::default()
@@ -176,6 +481,88 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn structure_edge_from_other_surface() -> anyhow::Result<()> {
+        let surface = Surface::xy_plane();
+        let other = Surface::xy_plane()
+            .transform(&Transform::translation([0., 0., 1.]));
+
+        let points = [[0., 0.], [1., 0.], [0., 1.], [0., 0.]];
+        let edge = Edge::polyline_from_points(&other, points);
+        let face = Face::new(
+            surface.clone(),
+            [Cycle {
+                edges: vec![edge.clone()],
+            }],
+            [],
+            [255, 0, 0, 255],
+        );
+
+        let err = match validate(face, &ValidationConfig::default()) {
+            Err(ValidationError::Structural(err)) => err,
+            result => panic!("Expected structural error, got {result:?}"),
+        };
+        assert_eq!(err.edges_not_on_surface.len(), 1);
+        let mismatch = &err.edges_not_on_surface[0];
+        assert_eq!(mismatch.local_as_global, Point::from([0., 0., 0.]));
+        assert_eq!(mismatch.global, Point::from([0., 0., 1.]));
+        assert!(err.to_string().contains("surface of their face"));
+
+        // Projecting the edge onto the surface makes it part of the face.
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        let global = edge.curve().global_form();
+        let curve = Local::new(
+            project_curve_into_surface(global, &surface, tolerance)?,
+            project_curve_onto_surface(global, &surface, tolerance)?,
+        );
+        let edge = Edge::new(curve, *edge.vertices());
+        let face = Face::new(
+            surface,
+            [Cycle { edges: vec![edge] }],
+            [],
+            [255, 0, 0, 255],
+        );
+        validate(face, &ValidationConfig::default())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn geometric_self_intersecting_cycle() -> anyhow::Result<()> {
+        let bowtie = FaceBuilder::new(Surface::xy_plane())
+            .with_exterior_polygon([[0., 0.], [2., 2.], [2., 0.], [0., 2.]])
+            .build();
+
+        let err = match validate(bowtie.clone(), &ValidationConfig::default()) {
+            Err(ValidationError::Geometric(err)) => err,
+            result => panic!("Expected geometric error, got {result:?}"),
+        };
+        assert_eq!(err.self_intersecting_cycles.len(), 1);
+        let intersection = &err.self_intersecting_cycles[0];
+        assert_eq!(intersection.cycle, 0);
+        assert!(
+            (intersection.point - Point::from([1., 1., 0.])).magnitude()
+                < Scalar::from_f64(1e-12)
+        );
+
+        // The check can be disabled.
+        validate(
+            bowtie,
+            &ValidationConfig {
+                self_intersecting_cycles: false,
+                ..ValidationConfig::default()
+            },
+        )?;
+
+        // A regular quad is fine.
+        let quad = FaceBuilder::new(Surface::xy_plane())
+            .with_exterior_polygon([[0., 0.], [2., 0.], [2., 2.], [0., 2.]])
+            .build();
+        validate(quad, &ValidationConfig::default())?;
+
+        Ok(())
+    }
+
     #[test]
     fn uniqueness_vertex() -> anyhow::Result<()> {
         let mut shape = Vec::new();
@@ -203,4 +590,79 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn scope_validate_at_root() -> anyhow::Result<()> {
+        let face = FaceBuilder::new(Surface::xy_plane())
+            .with_exterior_polygon([[0., 0.], [1., 0.], [0., 1.]])
+            .build();
+
+        let each_step = ValidationConfig::default();
+        let at_root = ValidationConfig {
+            scope: ValidationScope::ValidateAtRoot,
+            ..ValidationConfig::default()
+        };
+
+        VALIDATION_RUNS.with(|runs| runs.set(0));
+        let expected = transform_chain(vec![face.clone()], &each_step)?;
+        assert_eq!(VALIDATION_RUNS.with(Cell::get), 11);
+
+        VALIDATION_RUNS.with(|runs| runs.set(0));
+        let faces = transform_chain(vec![face], &at_root)?;
+        assert_eq!(VALIDATION_RUNS.with(Cell::get), 1);
+
+        assert_eq!(faces, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn scope_validate_at_root_invalid_child() {
+        let config = ValidationConfig {
+            scope: ValidationScope::ValidateAtRoot,
+            ..ValidationConfig::default()
+        };
+
+        // An edge whose vertex doesn't match its curve.
+        let edge = {
+            let a = Point::from([0., 0., 0.]);
+            let b = Point::from([1., 0., 0.]);
+
+            let curve = Local::new(
+                Curve::line_from_points([[0., 0.], [1., 0.]]),
+                Curve::line_from_points([a, b]),
+            );
+            let vertices = VerticesOfEdge::from_vertices([
+                Vertex::new([0.25], GlobalVertex::from_position(a)),
+                Vertex::new([1.], GlobalVertex::from_position(b)),
+            ]);
+
+            Edge::new(curve, vertices)
+        };
+        let face = Face::new(
+            Surface::xy_plane(),
+            [Cycle { edges: vec![edge] }],
+            [],
+            [255, 0, 0, 255],
+        );
+
+        let result = transform_chain(vec![face], &config);
+        assert!(matches!(result, Err(ValidationError::Deferred(_))));
+    }
+
+    fn transform_chain(
+        faces: Vec<Face>,
+        config: &ValidationConfig,
+    ) -> Result<Vec<Face>, ValidationError> {
+        let transform = Transform::translation(Vector::from([1., 0., 0.]));
+
+        let mut faces = validate_intermediate(faces, config)?;
+        for _ in 0..10 {
+            let mut inner = faces.into_inner();
+            transform_faces(&mut inner, &transform);
+            faces = validate_intermediate(inner, config)?;
+        }
+
+        Ok(validate_deferred(faces, config)?.into_inner())
+    }
 }
@@ -0,0 +1,78 @@
+use std::fmt;
+
+use crate::{
+    algorithms::{CycleApprox, Handedness, Tolerance},
+    iter::ObjectIters,
+};
+
+/// Check that the cycles of each face wind in the expected directions
+///
+/// Interior cycles are expected to wind opposite to the exterior cycle of
+/// their face (see [`Face`]). Faces whose cycles contradict this are still
+/// valid, so this doesn't produce errors. But their holes would be oriented
+/// like the face itself, which is probably not intended.
+///
+/// The expected windings follow from the [`Handedness`] of the face, which
+/// triangulation relies on too.
+///
+/// `tolerance` is used to approximate the cycles, to determine their winding.
+///
+/// [`Face`]: crate::objects::Face
+pub fn check_orientation<'r>(
+    object: &'r impl ObjectIters<'r>,
+    tolerance: Tolerance,
+) -> Vec<OrientationWarning> {
+    let mut warnings = Vec::new();
+
+    for (face_index, face) in object.face_iter().enumerate() {
+        if face.triangles().is_some() {
+            continue;
+        }
+
+        // Faces whose exterior cycle doesn't enclose any area have no
+        // meaningful orientation.
+        let exterior = face
+            .exteriors()
+            .next()
+            .and_then(|cycle| CycleApprox::new(cycle, tolerance).winding());
+        if exterior.is_none() {
+            continue;
+        }
+        let handedness = Handedness::from_exterior_winding(exterior);
+
+        for (interior_index, cycle) in face.interiors().enumerate() {
+            let winding = CycleApprox::new(cycle, tolerance).winding();
+            if winding == Some(handedness.exterior_winding()) {
+                warnings.push(OrientationWarning {
+                    face: face_index,
+                    interior: interior_index,
+                });
+            }
+        }
+    }
+
+    warnings
+}
+
+/// An interior cycle that winds in the same direction as its exterior cycle
+///
+/// Returned by [`check_orientation`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OrientationWarning {
+    /// The index of the face
+    pub face: usize,
+
+    /// The index of the interior cycle within the face
+    pub interior: usize,
+}
+
+impl fmt::Display for OrientationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Interior cycle {} of face {} winds in the same direction as the \
+            exterior cycle",
+            self.interior, self.face
+        )
+    }
+}
@@ -0,0 +1,394 @@
+use std::{collections::BTreeMap, fmt};
+
+use fj_interop::debug::DebugInfo;
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use crate::{
+    algorithms::{
+        reverse_face, triangulate_selection, FaceSelector, Tolerance,
+    },
+    iter::ObjectIters,
+    objects::{Curve, Face},
+};
+
+/// Check that the closed shells of a shape are oriented outward
+///
+/// A shell is a set of faces that are connected to each other. A closed shell
+/// encloses a volume, which is computed from its triangulation. If the faces of
+/// the shell point inward, that volume is negative.
+///
+/// Shells can be nested within each other. A shell within an outer shell
+/// bounds a cavity, and is expected to point inward, towards the cavity. A
+/// shell within that cavity is expected to point outward again, and so on.
+///
+/// Faces that can't be triangulated are not checked. Shells that aren't closed,
+/// like those of sketches, are ignored.
+pub fn check_shells<'r>(
+    object: &'r impl ObjectIters<'r>,
+    tolerance: Tolerance,
+) -> Vec<InvertedShell> {
+    shells(object, tolerance)
+        .into_iter()
+        .enumerate()
+        .filter(|(_, shell)| shell.is_inverted)
+        .map(|(shell_index, shell)| InvertedShell {
+            shell_index,
+            volume: shell.volume,
+        })
+        .collect()
+}
+
+/// A closed shell that points in the wrong direction
+///
+/// Returned by [`check_shells`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InvertedShell {
+    /// The index of the shell
+    ///
+    /// Shells are ordered by the first of their faces, in the order the faces
+    /// of the shape are iterated over.
+    pub shell_index: usize,
+
+    /// The volume that the shell encloses
+    ///
+    /// Negative, if the shell points inward, while it is expected to point
+    /// outward. Positive, if it bounds a cavity, but points outward.
+    pub volume: Scalar,
+}
+
+impl fmt::Display for InvertedShell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Shell {} is inverted (volume: {})",
+            self.shell_index, self.volume
+        )
+    }
+}
+
+/// Reverse the faces of all inverted shells
+///
+/// See [`check_shells`] for which shells are considered inverted. Faces that
+/// are defined by triangles are left as they are.
+pub(crate) fn reorient_outward(faces: Vec<Face>) -> Vec<Face> {
+    let tolerance = tolerance_for(&faces);
+
+    let mut is_inverted = vec![false; faces.len()];
+    for shell in shells(&faces, tolerance) {
+        if shell.is_inverted {
+            for index in shell.faces {
+                is_inverted[index] = true;
+            }
+        }
+    }
+
+    faces
+        .into_iter()
+        .zip(is_inverted)
+        .map(|(face, is_inverted)| {
+            if is_inverted && face.triangles().is_none() {
+                reverse_face(&face)
+            } else {
+                face
+            }
+        })
+        .collect()
+}
+
+/// Determine a tolerance for checking the shells of a shape
+///
+/// The orientation of a shell doesn't depend on the accuracy of its
+/// triangulation, so a coarse tolerance, relative to the size of the shape, is
+/// enough.
+pub(crate) fn tolerance_for<'r>(object: &'r impl ObjectIters<'r>) -> Tolerance {
+    let mut points: Vec<_> = object
+        .global_vertex_iter()
+        .map(|vertex| vertex.position())
+        .collect();
+    for curve in object.curve_iter() {
+        let (center, radius) = match curve {
+            Curve::Circle(circle) => (circle.center, circle.a.magnitude()),
+            Curve::Ellipse(ellipse) => (
+                ellipse.center,
+                ellipse.a.magnitude() + ellipse.b.magnitude(),
+            ),
+            Curve::Line(_) => continue,
+            Curve::Polyline(polyline) => {
+                points.extend_from_slice(polyline.points());
+                continue;
+            }
+        };
+
+        let radius = Vector::from([radius, radius, radius]);
+        points.extend([center - radius, center + radius]);
+    }
+
+    let size = if points.is_empty() {
+        Scalar::ZERO
+    } else {
+        Aabb::<3>::from_points(points).size().magnitude()
+    };
+
+    Tolerance::from_scalar(size * 1e-3)
+        .or_else(|_| Tolerance::from_scalar(1e-3))
+        .expect("Fallback tolerance is positive")
+}
+
+pub(crate) struct Shell {
+    /// The indices of the faces that make up the shell
+    pub faces: Vec<usize>,
+
+    /// The volume that the shell encloses
+    pub volume: Scalar,
+
+    /// Whether the shell points in the wrong direction
+    pub is_inverted: bool,
+}
+
+/// Find the closed shells of a shape
+pub(crate) fn shells<'r>(
+    object: &'r impl ObjectIters<'r>,
+    tolerance: Tolerance,
+) -> Vec<Shell> {
+    let mesh = match triangulate_selection(
+        object,
+        &FaceSelector::All,
+        tolerance,
+        &mut DebugInfo::new(),
+    ) {
+        Ok(mesh) => mesh,
+        Err(_) => return Vec::new(),
+    };
+
+    let num_faces = object.face_iter().count();
+    let triangles: Vec<_> = mesh
+        .triangles()
+        .filter_map(|triangle| Some((triangle.face?, triangle.points)))
+        .collect();
+
+    // Faces whose triangles share a point are part of the same shell.
+    let mut parents: Vec<_> = (0..num_faces).collect();
+    let mut face_at_point = BTreeMap::new();
+    for &(face, points) in &triangles {
+        for point in points {
+            let other = *face_at_point.entry(point).or_insert(face);
+            let [a, b] = [face, other].map(|face| root(&mut parents, face));
+            parents[a.max(b)] = a.min(b);
+        }
+    }
+
+    // Shells are ordered by their first face, which is also their root.
+    let mut shell_at_root = BTreeMap::new();
+    for face in 0..num_faces {
+        let shell_root = root(&mut parents, face);
+        let next = shell_at_root.len();
+        shell_at_root.entry(shell_root).or_insert(next);
+    }
+
+    let mut shells: Vec<_> = (0..shell_at_root.len())
+        .map(|_| (Vec::new(), Vec::new()))
+        .collect();
+    for face in 0..num_faces {
+        let shell = shell_at_root[&root(&mut parents, face)];
+        shells[shell].0.push(face);
+    }
+    for &(face, points) in &triangles {
+        let shell = shell_at_root[&root(&mut parents, face)];
+        shells[shell].1.push(points);
+    }
+
+    let is_closed: Vec<_> = shells
+        .iter()
+        .map(|(_, triangles)| is_closed(triangles))
+        .collect();
+
+    shells
+        .iter()
+        .enumerate()
+        .map(|(i, (faces, triangles))| {
+            let volume = volume(triangles);
+
+            // The nesting depth of the shell determines which direction it
+            // is expected to point in.
+            let depth = match triangles.first() {
+                Some(&[point, _, _]) if is_closed[i] => shells
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, (_, other))| {
+                        i != j && is_closed[j] && contains(other, point)
+                    })
+                    .count(),
+                _ => 0,
+            };
+            let expects_positive_volume = depth % 2 == 0;
+
+            Shell {
+                faces: faces.clone(),
+                volume,
+                is_inverted: is_closed[i]
+                    && volume != Scalar::ZERO
+                    && (volume > Scalar::ZERO) != expects_positive_volume,
+            }
+        })
+        .collect()
+}
+
+fn root(parents: &mut [usize], mut index: usize) -> usize {
+    while parents[index] != index {
+        parents[index] = parents[parents[index]];
+        index = parents[index];
+    }
+    index
+}
+
+/// Determine whether every edge of the triangles is shared by exactly two
+fn is_closed(triangles: &[[Point<3>; 3]]) -> bool {
+    let mut edges = BTreeMap::new();
+    for &[a, b, c] in triangles {
+        for [a, b] in [[a, b], [b, c], [c, a]] {
+            let edge = if a < b { [a, b] } else { [b, a] };
+            *edges.entry(edge).or_insert(0) += 1;
+        }
+    }
+
+    !edges.is_empty() && edges.values().all(|&uses| uses == 2)
+}
+
+/// Compute the signed volume enclosed by the triangles
+///
+/// Uses the divergence theorem: The volume is the sum of the signed volumes of
+/// the tetrahedra formed by each triangle and a common point.
+fn volume(triangles: &[[Point<3>; 3]]) -> Scalar {
+    let origin = match triangles.first() {
+        Some(&[origin, _, _]) => origin,
+        None => return Scalar::ZERO,
+    };
+
+    triangles
+        .iter()
+        .map(|&[a, b, c]| {
+            let [a, b, c] = [a, b, c].map(|point| point - origin);
+            a.dot(&b.cross(&c)) / 6.
+        })
+        .fold(Scalar::ZERO, |sum, volume| sum + volume)
+}
+
+/// Determine whether the point is within the closed triangle mesh
+///
+/// Counts the crossings of a ray from the point with the triangles. The ray
+/// points in an arbitrary direction that is unlikely to be aligned with any
+/// edges of the mesh.
+fn contains(triangles: &[[Point<3>; 3]], point: Point<3>) -> bool {
+    let direction = Vector::from([1., 0.754_877_666, 0.569_840_291]);
+
+    let crossings = triangles
+        .iter()
+        .filter(|&&[a, b, c]| {
+            // Möller–Trumbore ray-triangle intersection
+            let [ab, ac] = [b - a, c - a];
+            let p = direction.cross(&ac);
+            let determinant = ab.dot(&p);
+            if determinant == Scalar::ZERO {
+                return false;
+            }
+
+            let t = point - a;
+            let u = t.dot(&p) / determinant;
+            if u < Scalar::ZERO || u > Scalar::ONE {
+                return false;
+            }
+
+            let q = t.cross(&ab);
+            let v = direction.dot(&q) / determinant;
+            if v < Scalar::ZERO || u + v > Scalar::ONE {
+                return false;
+            }
+
+            ac.dot(&q) / determinant > Scalar::ZERO
+        })
+        .count();
+
+    crossings % 2 == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Scalar;
+
+    use crate::{
+        algorithms::reverse_face,
+        objects::{Face, Solid},
+        validation::{validate, Severity, ValidationConfig, ValidationError},
+    };
+
+    use super::{check_shells, shells, tolerance_for};
+
+    #[test]
+    fn inverted_cube() {
+        let cube = Solid::from_faces(reversed(cube(2.)));
+
+        let inverted = check_shells(&cube, tolerance_for(&cube));
+        assert_eq!(inverted.len(), 1);
+        assert_volume(inverted[0].volume, -8.);
+
+        let result = validate(
+            cube.clone(),
+            &ValidationConfig {
+                inverted_shells: Severity::Error,
+                ..ValidationConfig::default()
+            },
+        );
+        assert!(matches!(
+            result,
+            Err(ValidationError::InvertedShell { shell_index: 0, .. })
+        ));
+
+        let cube = cube.reoriented_outward();
+        let tolerance = tolerance_for(&cube);
+        assert!(check_shells(&cube, tolerance).is_empty());
+
+        let shells = shells(&cube, tolerance);
+        assert_eq!(shells.len(), 1);
+        assert_volume(shells[0].volume, 8.);
+    }
+
+    #[test]
+    fn cube_with_cavity() {
+        let outer = cube(4.);
+        let cavity = reversed(cube(2.));
+        let solid = Solid::from_faces(outer.into_iter().chain(cavity));
+
+        assert!(check_shells(&solid, tolerance_for(&solid)).is_empty());
+        assert_eq!(solid.clone().reoriented_outward(), solid);
+    }
+
+    #[test]
+    fn cavity_wound_outward() {
+        let outer = cube(4.);
+        let cavity = cube(2.);
+        let solid = Solid::from_faces(outer.into_iter().chain(cavity));
+
+        let inverted = check_shells(&solid, tolerance_for(&solid));
+        assert_eq!(inverted.len(), 1);
+        assert_volume(inverted[0].volume, 8.);
+
+        let solid = solid.reoriented_outward();
+        assert!(check_shells(&solid, tolerance_for(&solid)).is_empty());
+    }
+
+    fn cube(edge_length: f64) -> Vec<Face> {
+        Solid::cube_from_edge_length(edge_length)
+            .into_faces()
+            .into_iter()
+            .collect()
+    }
+
+    fn reversed(faces: Vec<Face>) -> Vec<Face> {
+        faces.iter().map(reverse_face).collect()
+    }
+
+    fn assert_volume(volume: Scalar, expected: f64) {
+        let deviation = (volume - Scalar::from_f64(expected)).abs();
+        assert!(deviation < Scalar::from_f64(1e-9));
+    }
+}
@@ -0,0 +1,93 @@
+use std::fmt;
+
+use fj_math::{Point, Scalar};
+
+use crate::objects::Face;
+
+use super::CoherenceMismatch;
+
+pub fn validate_face(
+    face: &Face,
+    max_distance: impl Into<Scalar>,
+) -> Result<(), StructuralIssues> {
+    if face.triangles().is_some() {
+        return Ok(());
+    }
+
+    let max_distance = max_distance.into();
+
+    // The local form of an edge's curve is in the coordinates of the surface
+    // that the edge was created for. If that's not the surface of the face, the
+    // local form is meaningless within the face, and converting it into global
+    // coordinates ends up somewhere else than the global form.
+    //
+    // Checking the points where the edge is bounded is enough to catch that.
+    // Unbounded edges are checked at the start of their curve.
+
+    let mut edges_not_on_surface = Vec::new();
+
+    for edge in face.all_cycles().flat_map(|cycle| &cycle.edges) {
+        let curve_coords = match edge.vertices().get() {
+            Some(vertices) => vertices.map(|vertex| vertex.position()).to_vec(),
+            None => vec![Point::from([Scalar::ZERO])],
+        };
+
+        for point in curve_coords {
+            let local =
+                edge.curve().local_form().point_from_curve_coords(point);
+            let local_as_global =
+                face.surface().point_from_surface_coords(local);
+            let global =
+                edge.curve().global_form().point_from_curve_coords(point);
+
+            if (local_as_global - global).magnitude() > max_distance {
+                edges_not_on_surface.push(CoherenceMismatch {
+                    local,
+                    local_as_global,
+                    global,
+                });
+            }
+        }
+    }
+
+    if !edges_not_on_surface.is_empty() {
+        return Err(StructuralIssues {
+            edges_not_on_surface,
+        });
+    }
+
+    Ok(())
+}
+
+/// Structural issues found during validation
+///
+/// Used by [`ValidationError`].
+///
+/// [`ValidationError`]: super::ValidationError
+#[derive(Debug, Default, thiserror::Error)]
+pub struct StructuralIssues {
+    /// Edges of faces that were created for a different surface
+    ///
+    /// The local form of each mismatch is in the coordinates of the face's
+    /// surface. Its global form is the point on the edge's curve.
+    pub edges_not_on_surface: Vec<CoherenceMismatch<Point<2>, Point<3>>>,
+}
+
+impl fmt::Display for StructuralIssues {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Structural issues found:")?;
+
+        if !self.edges_not_on_surface.is_empty() {
+            writeln!(
+                f,
+                "- Edges that don't belong to the surface of their face:"
+            )?;
+
+            for mismatch in &self.edges_not_on_surface {
+                writeln!(f, "  - {}", mismatch)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+
+use crate::objects::Solid;
+
+use super::ValidationError;
+
+/// Check that the faces of a solid fit together
+///
+/// Edges are identified by the positions of their vertices. Each edge must be
+/// shared by exactly two faces, which traverse it in opposite directions.
+/// Otherwise, the shell of the solid is open, non-manifold, or some of its
+/// faces point the wrong way.
+///
+/// Continuous edges have no vertices to identify them by, and are not checked.
+/// Neither are solids that have faces which are defined by triangles, as those
+/// have no edges at all.
+pub fn validate_solid(
+    solid: &Solid,
+    solid_index: usize,
+) -> Result<(), ValidationError> {
+    if solid.faces().any(|face| face.triangles().is_some()) {
+        return Ok(());
+    }
+
+    let mut uses = BTreeMap::new();
+    for (face_index, face) in solid.faces().enumerate() {
+        for edge in face.all_cycles().flat_map(|cycle| &cycle.edges) {
+            let [a, b] = match edge.vertex_positions() {
+                Some(positions) => positions,
+                None => continue,
+            };
+            if a == b {
+                continue;
+            }
+
+            let key = if a < b { [a, b] } else { [b, a] };
+            uses.entry(key)
+                .or_insert_with(Vec::new)
+                .push((face_index, [a, b]));
+        }
+    }
+
+    for (edge, uses) in uses {
+        match uses.as_slice() {
+            [(face, _)] => {
+                return Err(ValidationError::OpenEdge {
+                    solid: solid_index,
+                    face: *face,
+                    edge,
+                });
+            }
+            [(a, direction_a), (b, direction_b)] => {
+                if direction_a == direction_b {
+                    return Err(ValidationError::Orientation {
+                        solid: solid_index,
+                        faces: [*a, *b],
+                        edge: *direction_a,
+                    });
+                }
+            }
+            uses => {
+                return Err(ValidationError::NonManifoldEdge {
+                    solid: solid_index,
+                    faces: uses.iter().map(|&(face, _)| face).collect(),
+                    edge,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        algorithms::reverse_face,
+        objects::{Face, Solid},
+        validation::{validate, ValidationConfig, ValidationError},
+    };
+
+    #[test]
+    fn cube() -> anyhow::Result<()> {
+        validate(cube_solid(), &ValidationConfig::default())?;
+        Ok(())
+    }
+
+    #[test]
+    fn flipped_face() {
+        let mut faces = faces();
+        faces[2] = reverse_face(&faces[2]);
+        let flipped = faces[2].clone();
+
+        let solid = Solid::from_faces(faces);
+        let flipped = solid
+            .faces()
+            .position(|face| face == &flipped)
+            .expect("Flipped face is part of the solid");
+
+        // The flipped face conflicts with one of its neighbors.
+        let result = validate(solid, &ValidationConfig::default());
+        let faces = match result {
+            Err(ValidationError::Orientation { faces, .. }) => faces,
+            result => panic!("Expected orientation error, got {result:?}"),
+        };
+        assert!(faces.contains(&flipped));
+        assert_ne!(faces[0], faces[1]);
+    }
+
+    #[test]
+    fn open_shell() -> anyhow::Result<()> {
+        let mut faces = faces();
+        faces.pop();
+        let solid = Solid::from_faces(faces);
+
+        let result = validate(solid.clone(), &ValidationConfig::default());
+        assert!(matches!(result, Err(ValidationError::OpenEdge { .. })));
+
+        // Open shells can be allowed.
+        validate(
+            solid,
+            &ValidationConfig {
+                solid_topology: false,
+                ..ValidationConfig::default()
+            },
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn non_manifold_edge() {
+        let mut faces = faces();
+        let extra = reverse_face(&faces[0]);
+        faces.push(extra);
+        let solid = Solid::from_faces(faces);
+
+        let result = validate(solid, &ValidationConfig::default());
+        assert!(matches!(
+            result,
+            Err(ValidationError::NonManifoldEdge { faces, .. })
+                if faces.len() == 3
+        ));
+    }
+
+    fn cube_solid() -> Solid {
+        Solid::cube_from_edge_length(2.)
+    }
+
+    fn faces() -> Vec<Face> {
+        cube_solid().into_faces().into_iter().collect()
+    }
+}
@@ -126,6 +126,13 @@ impl Aabb<3> {
     pub fn merged(&self, other: &Self) -> Self {
         self.to_parry().merged(&other.to_parry()).into()
     }
+
+    /// Determine whether this AABB intersects another
+    ///
+    /// AABBs that only touch count as intersecting.
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.to_parry().intersects(&other.to_parry())
+    }
 }
 
 impl From<parry2d_f64::bounding_volume::AABB> for Aabb<2> {
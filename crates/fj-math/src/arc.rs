@@ -0,0 +1,271 @@
+use crate::{Circle, Point, Scalar, Vector};
+
+/// A circular arc in 2D, in center parameterization
+///
+/// Angles are measured counter-clockwise from the positive x-axis, in radians.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Arc {
+    /// The center point of the arc's circle
+    pub center: Point<2>,
+
+    /// The radius of the arc's circle
+    pub radius: Scalar,
+
+    /// The angle at which the arc starts
+    pub start_angle: Scalar,
+
+    /// The angle that the arc sweeps through, from its start to its end
+    ///
+    /// Positive values mean the arc runs counter-clockwise, negative values
+    /// mean it runs clockwise.
+    pub sweep_angle: Scalar,
+}
+
+impl Arc {
+    /// Construct an arc from its endpoint parameterization
+    ///
+    /// This is the parameterization used by SVG path `A` commands, restricted
+    /// to circular arcs. Of the four arcs that connect `start` and `end` with
+    /// the given radius, `large_arc` selects one that sweeps through more than
+    /// 180°, and `sweep` selects one that runs counter-clockwise (in the
+    /// positive angle direction).
+    ///
+    /// If `radius` is too small for the arc to connect `start` and `end`, it is
+    /// scaled up to the smallest possible value, as the SVG specification
+    /// requires.
+    ///
+    /// See the SVG specification, appendix F.6.5, for the algorithm:
+    /// <https://www.w3.org/TR/SVG11/implnote.html#ArcConversionEndpointToCenter>
+    pub fn from_endpoints(
+        start: impl Into<Point<2>>,
+        end: impl Into<Point<2>>,
+        radius: impl Into<Scalar>,
+        large_arc: bool,
+        sweep: bool,
+    ) -> Self {
+        let start = start.into();
+        let end = end.into();
+        let radius = radius.into().abs();
+
+        // Half the vector from `end` to `start`. This is `(x1', y1')` in the
+        // specification, which can skip the rotation, as circles have none.
+        let half = (start - end) / 2.;
+        let half_distance_squared = half.dot(&half);
+
+        let radius = {
+            let lambda = half_distance_squared / (radius * radius);
+            if lambda > Scalar::ONE {
+                radius * lambda.sqrt()
+            } else {
+                radius
+            }
+        };
+
+        let coefficient = {
+            let radicand = (radius * radius - half_distance_squared)
+                / half_distance_squared;
+            let coefficient = radicand.max(Scalar::ZERO).sqrt();
+
+            if large_arc == sweep {
+                -coefficient
+            } else {
+                coefficient
+            }
+        };
+
+        // `(cx', cy')` in the specification: The center relative to the
+        // midpoint between `start` and `end`.
+        let center_offset =
+            Vector::from([half.v * coefficient, -half.u * coefficient]);
+        let midpoint = start + (end - start) / 2.;
+        let center = midpoint + center_offset;
+
+        let start_angle = angle_of(half - center_offset);
+        let end_angle = angle_of(-half - center_offset);
+
+        let mut sweep_angle = end_angle - start_angle;
+        if sweep && sweep_angle < Scalar::ZERO {
+            sweep_angle += Scalar::PI * 2.;
+        }
+        if !sweep && sweep_angle > Scalar::ZERO {
+            sweep_angle = sweep_angle - Scalar::PI * 2.;
+        }
+
+        Self {
+            center,
+            radius,
+            start_angle,
+            sweep_angle,
+        }
+    }
+
+    /// Compute the angle at which the arc ends
+    pub fn end_angle(&self) -> Scalar {
+        self.start_angle + self.sweep_angle
+    }
+
+    /// Compute the point at which the arc starts
+    pub fn start(&self) -> Point<2> {
+        self.point_at_angle(self.start_angle)
+    }
+
+    /// Compute the point at which the arc ends
+    pub fn end(&self) -> Point<2> {
+        self.point_at_angle(self.end_angle())
+    }
+
+    /// Compute start and end angle of the arc, running counter-clockwise
+    ///
+    /// Clockwise arcs are converted into the counter-clockwise arc that covers
+    /// the same points, by swapping start and end. Both angles are normalized
+    /// to the range from `0.` (inclusive) to `PI * 2.` (exclusive).
+    ///
+    /// This is the representation used by DXF `ARC` entities (aside from the
+    /// unit, which is degrees there).
+    pub fn counter_clockwise_angles(&self) -> [Scalar; 2] {
+        let [start, end] = if self.sweep_angle >= Scalar::ZERO {
+            [self.start_angle, self.end_angle()]
+        } else {
+            [self.end_angle(), self.start_angle]
+        };
+
+        [start, end].map(normalize_angle)
+    }
+
+    /// Convert the arc into a circle
+    ///
+    /// The circle coordinate `0.` of the returned circle is at the start of the
+    /// arc, and the circle runs in the direction of the arc. This means the arc
+    /// covers the circle coordinates from `0.` to the absolute value of
+    /// `sweep_angle`.
+    pub fn to_circle(&self) -> Circle<2> {
+        let (sin, cos) = self.start_angle.sin_cos();
+
+        let a = Vector::from([cos, sin]) * self.radius;
+        let b = Vector::from([-sin, cos]) * self.radius;
+
+        let circle = Circle {
+            center: self.center,
+            a,
+            b,
+        };
+
+        if self.sweep_angle < Scalar::ZERO {
+            circle.reverse()
+        } else {
+            circle
+        }
+    }
+
+    fn point_at_angle(&self, angle: Scalar) -> Point<2> {
+        let (sin, cos) = angle.sin_cos();
+        self.center + Vector::from([cos, sin]) * self.radius
+    }
+}
+
+fn angle_of(vector: Vector<2>) -> Scalar {
+    Scalar::atan2(vector.v, vector.u)
+}
+
+fn normalize_angle(angle: Scalar) -> Scalar {
+    let full_circle = Scalar::PI * 2.;
+
+    let mut angle = angle;
+    while angle < Scalar::ZERO {
+        angle += full_circle;
+    }
+    while angle >= full_circle {
+        angle = angle - full_circle;
+    }
+
+    angle
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use approx::assert_abs_diff_eq;
+
+    use crate::{Point, Scalar};
+
+    use super::Arc;
+
+    #[test]
+    fn from_endpoints() {
+        let start = [1., 0.];
+        let end = [0., 1.];
+
+        let check = |large_arc, sweep, center: [f64; 2], sweep_angle: f64| {
+            let arc = Arc::from_endpoints(start, end, 1., large_arc, sweep);
+
+            assert_abs_diff_eq!(
+                arc.center,
+                Point::from(center),
+                epsilon = 1e-9
+            );
+            assert_abs_diff_eq!(arc.radius, Scalar::ONE, epsilon = 1e-9);
+            assert_abs_diff_eq!(
+                arc.sweep_angle,
+                Scalar::from_f64(sweep_angle),
+                epsilon = 1e-9,
+            );
+            assert_abs_diff_eq!(
+                arc.start(),
+                Point::from(start),
+                epsilon = 1e-9
+            );
+            assert_abs_diff_eq!(arc.end(), Point::from(end), epsilon = 1e-9);
+        };
+
+        check(false, true, [0., 0.], FRAC_PI_2);
+        check(true, true, [1., 1.], FRAC_PI_2 * 3.);
+        check(false, false, [1., 1.], -FRAC_PI_2);
+        check(true, false, [0., 0.], -FRAC_PI_2 * 3.);
+    }
+
+    #[test]
+    fn from_endpoints_scales_up_radius() {
+        let arc = Arc::from_endpoints([-2., 0.], [2., 0.], 1., false, true);
+
+        assert_abs_diff_eq!(arc.center, Point::from([0., 0.]), epsilon = 1e-9);
+        assert_abs_diff_eq!(arc.radius, Scalar::TWO, epsilon = 1e-9);
+        assert_abs_diff_eq!(arc.sweep_angle.abs(), Scalar::PI, epsilon = 1e-9,);
+    }
+
+    #[test]
+    fn counter_clockwise_angles() {
+        let arc = Arc::from_endpoints([1., 0.], [0., 1.], 1., true, false);
+        let [start, end] = arc.counter_clockwise_angles();
+
+        assert_abs_diff_eq!(start, Scalar::from_f64(FRAC_PI_2), epsilon = 1e-9);
+        assert_abs_diff_eq!(end, Scalar::ZERO, epsilon = 1e-9);
+
+        let arc = Arc::from_endpoints([0., 1.], [0., -1.], 1., false, true);
+        let [start, end] = arc.counter_clockwise_angles();
+
+        assert_abs_diff_eq!(start, Scalar::from_f64(FRAC_PI_2), epsilon = 1e-9);
+        assert_abs_diff_eq!(
+            end,
+            Scalar::from_f64(PI + FRAC_PI_2),
+            epsilon = 1e-9,
+        );
+    }
+
+    #[test]
+    fn to_circle() {
+        let arc = Arc::from_endpoints([1., 0.], [0., 1.], 1., true, false);
+        let circle = arc.to_circle();
+
+        assert_abs_diff_eq!(
+            circle.point_from_circle_coords([0.]),
+            arc.start(),
+            epsilon = 1e-9,
+        );
+        assert_abs_diff_eq!(
+            circle.point_from_circle_coords([arc.sweep_angle.abs()]),
+            arc.end(),
+            epsilon = 1e-9,
+        );
+    }
+}
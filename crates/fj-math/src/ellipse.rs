@@ -0,0 +1,342 @@
+use crate::{Circle, Point, Scalar, Vector};
+
+/// An n-dimensional ellipse
+///
+/// The dimensionality of the ellipse is defined by the const generic `D`
+/// parameter.
+///
+/// The ellipse is defined by its center and two conjugate semi-diameters, `a`
+/// and `b`. The point at curve coordinate `t` is `center + a * cos(t) + b *
+/// sin(t)`. If `a` and `b` are perpendicular, they are the semi-axes of the
+/// ellipse. Use [`Ellipse::semi_axes`] to compute those in the general case.
+///
+/// This representation has the advantage, that an affine transform of the
+/// ellipse can be computed by transforming `center`, `a`, and `b`, without
+/// changing the curve coordinates of any point on the ellipse.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Ellipse<const D: usize> {
+    /// The center point of the ellipse
+    pub center: Point<D>,
+
+    /// A vector from the center to the starting point of the ellipse
+    pub a: Vector<D>,
+
+    /// A second semi-diameter, conjugate to `a`
+    ///
+    /// Must not be parallel to `a`.
+    pub b: Vector<D>,
+}
+
+impl<const D: usize> Ellipse<D> {
+    /// Create a new instance that is reversed
+    #[must_use]
+    pub fn reverse(mut self) -> Self {
+        self.b = -self.b;
+        self
+    }
+
+    /// Convert a `D`-dimensional point to ellipse coordinates
+    ///
+    /// Converts the provided point into ellipse coordinates between `0.`
+    /// (inclusive) and `PI * 2.` (exclusive).
+    ///
+    /// Like [`Circle::point_to_circle_coords`], this projects the point onto
+    /// the ellipse, so points that are not on the ellipse don't result in an
+    /// error.
+    pub fn point_to_ellipse_coords(
+        &self,
+        point: impl Into<Point<D>>,
+    ) -> Point<1> {
+        let v = point.into() - self.center;
+
+        // Solve `a * cos + b * sin = v` in the least-squares sense. The
+        // solution is not normalized, but only its angle matters.
+        let [aa, ab, bb] = [
+            self.a.dot(&self.a),
+            self.a.dot(&self.b),
+            self.b.dot(&self.b),
+        ];
+        let [av, bv] = [self.a.dot(&v), self.b.dot(&v)];
+
+        let cos = av * bb - bv * ab;
+        let sin = bv * aa - av * ab;
+
+        let atan = Scalar::atan2(sin, cos);
+        let coord = if atan >= Scalar::ZERO {
+            atan
+        } else {
+            atan + Scalar::PI * 2.
+        };
+        Point::from([coord])
+    }
+
+    /// Convert a point in ellipse coordinates into a `D`-dimensional point
+    pub fn point_from_ellipse_coords(
+        &self,
+        point: impl Into<Point<1>>,
+    ) -> Point<D> {
+        self.center + self.vector_from_ellipse_coords(point.into().coords)
+    }
+
+    /// Convert a vector in ellipse coordinates into a `D`-dimensional vector
+    pub fn vector_from_ellipse_coords(
+        &self,
+        vector: impl Into<Vector<1>>,
+    ) -> Vector<D> {
+        let angle = vector.into().t;
+        let (sin, cos) = angle.sin_cos();
+
+        self.a * cos + self.b * sin
+    }
+
+    /// Compute the tangent at the given ellipse coordinate
+    ///
+    /// The tangent is the derivative of the ellipse with respect to its curve
+    /// coordinate. It is not normalized.
+    pub fn tangent_at(&self, point: impl Into<Point<1>>) -> Vector<D> {
+        let (sin, cos) = point.into().t.sin_cos();
+        self.b * cos - self.a * sin
+    }
+
+    /// Compute the semi-axes of the ellipse
+    ///
+    /// Returns the semi-major axis first. Both are perpendicular to each other,
+    /// and define the same ellipse as `a` and `b`, although with curve
+    /// coordinates that are offset by a constant angle.
+    pub fn semi_axes(&self) -> [Vector<D>; 2] {
+        // The squared distance from the center is extremal where its
+        // derivative, `(bb - aa) * sin(2t) + 2ab * cos(2t)`, is zero. Of the
+        // two solutions, this is the maximum.
+        let [aa, ab, bb] = [
+            self.a.dot(&self.a),
+            self.a.dot(&self.b),
+            self.b.dot(&self.b),
+        ];
+        let t = Scalar::atan2(ab * 2., aa - bb) / 2.;
+
+        let major = self.vector_from_ellipse_coords([t]);
+        let minor = self.vector_from_ellipse_coords([t + Scalar::PI / 2.]);
+
+        [major, minor]
+    }
+
+    /// Compute the radius of curvature at the given ellipse coordinate
+    pub fn radius_of_curvature(&self, point: impl Into<Point<1>>) -> Scalar {
+        let point = point.into();
+
+        let d1 = self.tangent_at(point);
+        let d2 = -self.vector_from_ellipse_coords(point.coords);
+
+        // The magnitude of the cross product of both derivatives, computed in
+        // a way that works for any dimension.
+        let d1d1 = d1.dot(&d1);
+        let cross = (d1d1 * d2.dot(&d2) - d1.dot(&d2) * d1.dot(&d2)).sqrt();
+
+        d1d1 * d1d1.sqrt() / cross
+    }
+
+    /// Compute the length of the ellipse between two ellipse coordinates
+    ///
+    /// There is no closed-form solution, so this uses Gauss-Legendre
+    /// quadrature. The result is negative, if `end` is smaller than `start`.
+    pub fn arc_length(
+        &self,
+        start: impl Into<Point<1>>,
+        end: impl Into<Point<1>>,
+    ) -> Scalar {
+        // Nodes and weights of 5-point Gauss-Legendre quadrature
+        const NODES: [(f64, f64); 5] = [
+            (0., 0.568_888_888_888_888_9),
+            (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+            (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+            (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+            (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+        ];
+        const INTERVALS_PER_TURN: f64 = 32.;
+
+        let [start, end] = [start.into().t, end.into().t];
+        let span = end - start;
+
+        let intervals = (span.abs() / (Scalar::PI * 2.) * INTERVALS_PER_TURN)
+            .ceil()
+            .into_f64()
+            .max(1.);
+        let width = span / intervals;
+
+        let mut length = Scalar::ZERO;
+        for i in 0..intervals as u64 {
+            let center = start + width * (i as f64 + 0.5);
+
+            for (node, weight) in NODES {
+                let t = center + width * node / 2.;
+                length += self.tangent_at([t]).magnitude() * weight / 2.;
+            }
+        }
+
+        length * width
+    }
+
+    /// Compute the length of the whole ellipse
+    pub fn circumference(&self) -> Scalar {
+        self.arc_length([0.], [Scalar::PI * 2.])
+    }
+
+    /// Find the ellipse coordinate at the given arc length from coordinate `0.`
+    ///
+    /// Arc lengths beyond the circumference continue around the ellipse.
+    pub fn coords_at_arc_length(
+        &self,
+        arc_length: impl Into<Scalar>,
+    ) -> Point<1> {
+        let arc_length = arc_length.into();
+
+        let circumference = self.circumference();
+        let turns = (arc_length / circumference).into_f64().floor();
+        let remainder = arc_length - circumference * turns;
+
+        // The arc length is monotonic in the curve coordinate, and its
+        // derivative is the speed along the ellipse. Newton's method converges
+        // quickly, starting from the coordinate of a circle.
+        let mut t = remainder / circumference * Scalar::PI * 2.;
+        for _ in 0..32 {
+            let error = self.arc_length([0.], [t]) - remainder;
+            let step = error / self.tangent_at([t]).magnitude();
+            t = t - step;
+
+            if step.abs() < Scalar::from_f64(1e-12) {
+                break;
+            }
+        }
+
+        Point::from([t + Scalar::PI * 2. * turns])
+    }
+}
+
+impl<const D: usize> From<Circle<D>> for Ellipse<D> {
+    fn from(circle: Circle<D>) -> Self {
+        Self {
+            center: circle.center,
+            a: circle.a,
+            b: circle.b,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use approx::assert_abs_diff_eq;
+
+    use crate::{Point, Scalar, Vector};
+
+    use super::Ellipse;
+
+    fn ellipse() -> Ellipse<2> {
+        Ellipse {
+            center: Point::from([1., 2.]),
+            a: Vector::from([3., 0.]),
+            b: Vector::from([0., 2.]),
+        }
+    }
+
+    #[test]
+    fn ellipse_coords() {
+        let ellipse = ellipse();
+
+        for t in [0., 0.3, FRAC_PI_2, PI, 4.5] {
+            let point = ellipse.point_from_ellipse_coords([t]);
+            assert_abs_diff_eq!(
+                ellipse.point_to_ellipse_coords(point).t,
+                Scalar::from_f64(t),
+                epsilon = 1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn semi_axes_of_conjugate_diameters() {
+        // A sheared circle, whose semi-diameters are not the semi-axes.
+        let ellipse = Ellipse {
+            center: Point::from([0., 0.]),
+            a: Vector::from([2., 0.]),
+            b: Vector::from([1., 1.]),
+        };
+
+        let [major, minor] = ellipse.semi_axes();
+        assert_abs_diff_eq!(major.dot(&minor), Scalar::ZERO, epsilon = 1e-12);
+        assert!(major.magnitude() >= minor.magnitude());
+
+        // The semi-axes define the same ellipse. Its area is invariant.
+        let area = |a: Vector<2>, b: Vector<2>| (a.u * b.v - a.v * b.u).abs();
+        assert_abs_diff_eq!(
+            area(major, minor),
+            area(ellipse.a, ellipse.b),
+            epsilon = 1e-12
+        );
+        for axis in [major, -minor] {
+            let point = ellipse.center + axis;
+            let t = ellipse.point_to_ellipse_coords(point);
+            assert_abs_diff_eq!(
+                ellipse.point_from_ellipse_coords(t),
+                point,
+                epsilon = 1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn radius_of_curvature() {
+        let ellipse = ellipse();
+
+        // At the ends of the major axis, the radius is `b² / a`. At the ends
+        // of the minor axis, it is `a² / b`.
+        assert_abs_diff_eq!(
+            ellipse.radius_of_curvature([0.]),
+            Scalar::from_f64(4. / 3.),
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            ellipse.radius_of_curvature([FRAC_PI_2]),
+            Scalar::from_f64(9. / 2.),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn arc_length() {
+        // For a circle, the arc length is known exactly.
+        let circle = Ellipse {
+            center: Point::from([0., 0.]),
+            a: Vector::from([2., 0.]),
+            b: Vector::from([0., 2.]),
+        };
+        assert_abs_diff_eq!(
+            circle.arc_length([0.], [1.]),
+            Scalar::from_f64(2.),
+            epsilon = 1e-12
+        );
+
+        // Ramanujan's second approximation is very accurate for moderate
+        // eccentricities.
+        let ellipse = ellipse();
+        let (a, b) = (3., 2.);
+        let h = (a - b) * (a - b) / ((a + b) * (a + b));
+        let expected =
+            PI * (a + b) * (1. + 3. * h / (10. + (4. - 3. * h).sqrt()));
+        assert_abs_diff_eq!(
+            ellipse.circumference(),
+            Scalar::from_f64(expected),
+            epsilon = 1e-6
+        );
+
+        for arc_length in [0.5, 7., 20.] {
+            let t = ellipse.coords_at_arc_length(arc_length);
+            assert_abs_diff_eq!(
+                ellipse.arc_length([0.], t),
+                Scalar::from_f64(arc_length),
+                epsilon = 1e-9
+            );
+        }
+    }
+}
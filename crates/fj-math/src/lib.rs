@@ -36,27 +36,35 @@
 #![warn(missing_docs)]
 
 mod aabb;
+mod arc;
 mod circle;
 mod coordinates;
+mod ellipse;
 mod line;
 mod point;
 mod poly_chain;
+mod polyline;
 mod scalar;
 mod segment;
 mod transform;
+mod transform2;
 mod triangle;
 mod vector;
 
 pub use self::{
     aabb::Aabb,
+    arc::Arc,
     circle::Circle,
     coordinates::{Uv, Xyz, T},
+    ellipse::Ellipse,
     line::Line,
     point::Point,
     poly_chain::PolyChain,
-    scalar::Scalar,
+    polyline::Polyline,
+    scalar::{ParseScalarError, Scalar},
     segment::Segment,
     transform::Transform,
+    transform2::Transform2,
     triangle::{Triangle, Winding},
     vector::Vector,
 };
@@ -24,6 +24,11 @@ impl<const D: usize> PolyChain<D> {
         Self { points }
     }
 
+    /// Access the points of the polygonal chain
+    pub fn points(&self) -> &[Point<D>] {
+        &self.points
+    }
+
     /// Access the segments of the polygonal chain
     pub fn segments(&self) -> Vec<Segment<D>> {
         let mut segments = Vec::new();
@@ -0,0 +1,276 @@
+use crate::{Point, Scalar, Segment, Vector};
+
+/// An n-dimensional polyline, parameterized by arc length
+///
+/// The polyline consists of straight segments between its points. Each point
+/// has a knot, which is its coordinate in the polyline's 1-dimensional
+/// coordinate system. Knots are strictly increasing.
+///
+/// When constructed using [`Polyline::from_points`], the knots are the
+/// cumulative arc length of the polyline, starting at `0.` at the first point.
+/// Operations that need to keep existing curve coordinates valid, like
+/// [`Polyline::reverse`] or transforming a polyline, preserve the knots
+/// instead.
+///
+/// The dimensionality of the polyline is defined by the const generic `D`
+/// parameter.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Polyline<const D: usize> {
+    points: Vec<Point<D>>,
+    knots: Vec<Scalar>,
+}
+
+impl<const D: usize> Polyline<D> {
+    /// Construct a polyline from its points, parameterized by arc length
+    ///
+    /// # Panics
+    ///
+    /// Panics, if fewer than two points are passed, or if two consecutive
+    /// points are equal.
+    pub fn from_points(
+        points: impl IntoIterator<Item = impl Into<Point<D>>>,
+    ) -> Self {
+        let points: Vec<_> = points.into_iter().map(Into::into).collect();
+
+        let mut knots = Vec::with_capacity(points.len());
+        let mut length = Scalar::ZERO;
+        for (i, point) in points.iter().enumerate() {
+            if i > 0 {
+                length += (*point - points[i - 1]).magnitude();
+            }
+            knots.push(length);
+        }
+
+        Self::from_points_and_knots(points, knots)
+    }
+
+    /// Construct a polyline from its points and their knots
+    ///
+    /// This can be used to create a polyline that shares the parameterization
+    /// of another one, like the 3D form of a polyline defined on a surface.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if fewer than two points are passed, if the number of points and
+    /// knots doesn't match, or if the knots are not strictly increasing.
+    pub fn from_points_and_knots(
+        points: impl IntoIterator<Item = impl Into<Point<D>>>,
+        knots: impl IntoIterator<Item = impl Into<Scalar>>,
+    ) -> Self {
+        let points: Vec<_> = points.into_iter().map(Into::into).collect();
+        let knots: Vec<_> = knots.into_iter().map(Into::into).collect();
+
+        assert!(points.len() >= 2, "Polyline needs at least two points");
+        assert_eq!(
+            points.len(),
+            knots.len(),
+            "Polyline needs one knot per point"
+        );
+        assert!(
+            knots.windows(2).all(|knots| knots[0] < knots[1]),
+            "Polyline knots must be strictly increasing"
+        );
+
+        Self { points, knots }
+    }
+
+    /// Access the points of the polyline
+    pub fn points(&self) -> &[Point<D>] {
+        &self.points
+    }
+
+    /// Access the knots of the polyline
+    ///
+    /// These are the curve coordinates of the points.
+    pub fn knots(&self) -> &[Scalar] {
+        &self.knots
+    }
+
+    /// Access the segments of the polyline
+    pub fn segments(&self) -> Vec<Segment<D>> {
+        self.points
+            .windows(2)
+            .map(|points| Segment::from_points([points[0], points[1]]))
+            .collect()
+    }
+
+    /// Compute the length of the polyline's parameter range
+    ///
+    /// For a polyline that is parameterized by arc length, this is its length.
+    pub fn length(&self) -> Scalar {
+        self.last_knot() - self.knots[0]
+    }
+
+    /// Determine whether the polyline is closed
+    ///
+    /// A polyline is closed, if its first and last point are equal.
+    pub fn is_closed(&self) -> bool {
+        self.points.first() == self.points.last()
+    }
+
+    /// Create a new instance that is reversed
+    ///
+    /// The knots are negated, so the point at curve coordinate `t` on the
+    /// original polyline is at `-t` on the reversed one, like with lines.
+    #[must_use]
+    pub fn reverse(mut self) -> Self {
+        self.points.reverse();
+        self.knots.reverse();
+        for knot in &mut self.knots {
+            *knot = -*knot;
+        }
+        self
+    }
+
+    /// Convert a point in polyline coordinates into a `D`-dimensional point
+    ///
+    /// Interpolates linearly between the points of the polyline. Coordinates
+    /// before the first or after the last knot are extrapolated from the first
+    /// or last segment, respectively.
+    pub fn point_from_polyline_coords(
+        &self,
+        point: impl Into<Point<1>>,
+    ) -> Point<D> {
+        let t = point.into().t;
+
+        // Return points at the knots exactly, to not introduce any floating
+        // point accuracy issues there.
+        if let Ok(i) = self.knots.binary_search(&t) {
+            return self.points[i];
+        }
+
+        let i = self.segment_index(t);
+        let [a, b] = [self.points[i], self.points[i + 1]];
+        let s = (t - self.knots[i]) / (self.knots[i + 1] - self.knots[i]);
+
+        a + (b - a) * s
+    }
+
+    /// Convert a vector in polyline coordinates into a `D`-dimensional vector
+    ///
+    /// As the direction of a polyline changes at its knots, a vector in
+    /// polyline coordinates has no unique equivalent. This method uses the
+    /// direction at the start of the polyline.
+    pub fn vector_from_polyline_coords(
+        &self,
+        vector: impl Into<Vector<1>>,
+    ) -> Vector<D> {
+        self.tangent_at(Point::from([self.knots[0]])) * vector.into().t
+    }
+
+    /// Compute the tangent at the given polyline coordinate
+    ///
+    /// Returns the direction of the segment that contains the coordinate,
+    /// scaled to match the polyline's parameterization. For a polyline that
+    /// is parameterized by arc length, that is a unit vector.
+    ///
+    /// At a knot, the tangent of the segment that starts there is returned,
+    /// except at the last knot, which only has the segment that ends there.
+    pub fn tangent_at(&self, point: impl Into<Point<1>>) -> Vector<D> {
+        let i = self.segment_index(point.into().t);
+        let [a, b] = [self.points[i], self.points[i + 1]];
+
+        (b - a) / (self.knots[i + 1] - self.knots[i])
+    }
+
+    /// Convert a `D`-dimensional point to polyline coordinates
+    ///
+    /// Projects the point onto the closest segment of the polyline, to make
+    /// this method robust against floating point accuracy issues.
+    pub fn point_to_polyline_coords(
+        &self,
+        point: impl Into<Point<D>>,
+    ) -> Point<1> {
+        let point = point.into();
+
+        let mut closest: Option<(Scalar, Scalar)> = None;
+        for i in 0..self.points.len() - 1 {
+            let [a, b] = [self.points[i], self.points[i + 1]];
+            let ab = b - a;
+
+            let s = ((point - a).dot(&ab) / ab.dot(&ab))
+                .max(Scalar::ZERO)
+                .min(Scalar::ONE);
+            let distance = (a + ab * s - point).magnitude();
+            let t = self.knots[i] + (self.knots[i + 1] - self.knots[i]) * s;
+
+            if closest.map_or(true, |(closest, _)| distance < closest) {
+                closest = Some((distance, t));
+            }
+        }
+
+        // Can't panic, as a polyline has at least one segment.
+        let (_, t) = closest.expect("Polyline has no segments");
+        Point::from([t])
+    }
+
+    /// Create a new instance with transformed points, but the same knots
+    pub(crate) fn map_points(
+        &self,
+        f: impl FnMut(&Point<D>) -> Point<D>,
+    ) -> Self {
+        Self {
+            points: self.points.iter().map(f).collect(),
+            knots: self.knots.clone(),
+        }
+    }
+
+    fn last_knot(&self) -> Scalar {
+        self.knots[self.knots.len() - 1]
+    }
+
+    /// Find the index of the segment that contains the given coordinate
+    fn segment_index(&self, t: Scalar) -> usize {
+        let num_segments = self.points.len() - 1;
+
+        // The number of knots that are less than or equal to `t`. The segment
+        // that starts at the last of those is the one we're looking for.
+        let i = self.knots.partition_point(|&knot| knot <= t);
+        i.saturating_sub(1).min(num_segments - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, Scalar, Vector};
+
+    use super::Polyline;
+
+    #[test]
+    fn arc_length_parameterization() {
+        let polyline =
+            Polyline::from_points([[0., 0.], [3., 0.], [3., 4.], [0., 4.]]);
+
+        assert_eq!(polyline.length(), Scalar::from(10.));
+        assert_eq!(
+            polyline.point_from_polyline_coords([5.]),
+            Point::from([3., 2.])
+        );
+        assert_eq!(
+            polyline.point_to_polyline_coords([3., 2.]),
+            Point::from([5.])
+        );
+    }
+
+    #[test]
+    fn tangent_at_knots() {
+        let polyline = Polyline::from_points([[0., 0.], [2., 0.], [2., 2.]]);
+
+        assert_eq!(polyline.tangent_at([1.]), Vector::from([1., 0.]));
+        assert_eq!(polyline.tangent_at([2.]), Vector::from([0., 1.]));
+        assert_eq!(polyline.tangent_at([4.]), Vector::from([0., 1.]));
+    }
+
+    #[test]
+    fn reverse() {
+        let polyline = Polyline::from_points([[0., 0.], [2., 0.], [2., 2.]]);
+        let reversed = polyline.clone().reverse();
+
+        for t in [0., 1., 2., 3., 4.] {
+            assert_eq!(
+                reversed.point_from_polyline_coords([-t]),
+                polyline.point_from_polyline_coords([t]),
+            );
+        }
+    }
+}
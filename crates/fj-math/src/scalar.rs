@@ -1,13 +1,17 @@
 use std::{cmp, f64::consts::PI, fmt, hash::Hash, ops};
 
-use decorum::R64;
-
 /// A rational, finite scalar value
 ///
 /// This is a wrapper around `f64`. On construction, it checks that the `f64`
 /// value is not NaN. This allows `Scalar` to provide implementations of [`Eq`],
 /// [`Ord`], and [`Hash`], enabling `Scalar` (and types built on top of it), to
 /// be used as keys in hash maps, hash sets, and similar types.
+///
+/// As NaN is rejected, the only `f64` values that are equal despite having
+/// different bit patterns are `-0.0` and `0.0`. [`Scalar::to_canonical_bits`]
+/// maps both to the same bit pattern, and [`Hash`] and
+/// [`Scalar::to_string_exact`] are based on that. This makes hashes and
+/// serialized values reproducible.
 #[derive(Clone, Copy)]
 #[repr(C)]
 pub struct Scalar(f64);
@@ -61,6 +65,60 @@ impl Scalar {
         self.0 as u64
     }
 
+    /// Parse a scalar from a decimal string, without losing precision
+    ///
+    /// Accepts the same syntax as [`f64`]'s [`FromStr`] implementation, but
+    /// returns an error for strings that have more significant digits than
+    /// the `f64` value they parse to can represent, as well as for values that
+    /// are not finite.
+    ///
+    /// Every string returned by [`Scalar::to_string_exact`] is accepted, and
+    /// parses back to the same value.
+    ///
+    /// [`FromStr`]: std::str::FromStr
+    pub fn from_str_exact(s: &str) -> Result<Self, ParseScalarError> {
+        let value: f64 = s
+            .parse()
+            .map_err(|_| ParseScalarError::Invalid(s.to_owned()))?;
+
+        if !value.is_finite() {
+            return Err(ParseScalarError::NotFinite(s.to_owned()));
+        }
+
+        let scalar = Self::from_f64(value);
+
+        // The shortest string that parses back to the value has all the
+        // significant digits that the value can represent. If the input has
+        // different ones, precision was lost while parsing it.
+        if significant_digits(s)
+            != significant_digits(&scalar.to_string_exact())
+        {
+            return Err(ParseScalarError::Lossy(s.to_owned()));
+        }
+
+        Ok(scalar)
+    }
+
+    /// Format the scalar as a decimal string that parses back to it exactly
+    ///
+    /// Returns the shortest such string, which doesn't depend on the platform
+    /// or the environment. `-0.0` and `0.0` are formatted the same.
+    pub fn to_string_exact(self) -> String {
+        format!("{:?}", f64::from_bits(self.to_canonical_bits()))
+    }
+
+    /// Access the bit pattern of the scalar, after normalizing it
+    ///
+    /// Equal scalars always have the same bit pattern. To achieve that, `-0.0`
+    /// is normalized to `0.0`.
+    pub fn to_canonical_bits(self) -> u64 {
+        if self.0 == 0. {
+            0
+        } else {
+            self.0.to_bits()
+        }
+    }
+
     /// Compute the absolute value of the scalar
     pub fn abs(self) -> Self {
         self.0.abs().into()
@@ -71,6 +129,11 @@ impl Scalar {
         self.0.max(other.0).into()
     }
 
+    /// Compute the square root
+    pub fn sqrt(self) -> Self {
+        self.0.sqrt().into()
+    }
+
     /// Compute the smallest integer larger than or equal to this scalar
     pub fn ceil(self) -> Self {
         self.0.ceil().into()
@@ -127,9 +190,9 @@ impl PartialEq for Scalar {
 
 impl Hash for Scalar {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        // To the best of my knowledge, this matches the `PartialEq`
-        // implementation.
-        R64::from_inner(self.0).hash(state);
+        // This matches the `PartialEq` implementation, as NaN can't occur, and
+        // the canonical bits of `-0.0` and `0.0` are the same.
+        self.to_canonical_bits().hash(state);
     }
 }
 
@@ -518,6 +581,66 @@ impl fmt::Display for Scalar {
     }
 }
 
+/// An error that can occur when parsing a [`Scalar`]
+///
+/// Returned by [`Scalar::from_str_exact`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseScalarError {
+    /// The string is not a valid number
+    Invalid(String),
+
+    /// The string represents a value that is not finite
+    NotFinite(String),
+
+    /// The string has more precision than a scalar can represent
+    Lossy(String),
+}
+
+impl fmt::Display for ParseScalarError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Invalid(s) => write!(f, "`{s}` is not a valid number"),
+            Self::NotFinite(s) => write!(f, "`{s}` is not a finite number"),
+            Self::Lossy(s) => {
+                write!(f, "`{s}` can't be represented without losing precision")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseScalarError {}
+
+/// Extract the significant digits of a decimal number, and its exponent
+///
+/// The number is equal to `0.{digits} * 10^{exponent}`. Leading and trailing
+/// zeros are removed from the digits, and the sign is ignored. Returns `None`,
+/// if the string is not a decimal number.
+fn significant_digits(s: &str) -> Option<(String, i64)> {
+    let s = s.strip_prefix(|c| c == '+' || c == '-').unwrap_or(s);
+
+    let (mantissa, exponent) = match s.split_once(|c| c == 'e' || c == 'E') {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse().ok()?),
+        None => (s, 0),
+    };
+    let (integer, fraction) =
+        mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+    let digits = format!("{integer}{fraction}");
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let leading_zeros = digits.len() - digits.trim_start_matches('0').len();
+    let digits = digits.trim_start_matches('0').trim_end_matches('0');
+
+    if digits.is_empty() {
+        return Some((String::new(), 0));
+    }
+
+    let exponent = exponent + integer.len() as i64 - leading_zeros as i64;
+    Some((digits.to_owned(), exponent))
+}
+
 impl approx::AbsDiffEq for Scalar {
     type Epsilon = <f64 as approx::AbsDiffEq>::Epsilon;
 
@@ -529,3 +652,93 @@ impl approx::AbsDiffEq for Scalar {
         self.0.abs_diff_eq(&other.0, epsilon)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::hash_map::DefaultHasher,
+        hash::{Hash, Hasher},
+    };
+
+    use super::{ParseScalarError, Scalar};
+
+    #[test]
+    fn roundtrip() -> Result<(), ParseScalarError> {
+        // A simple xorshift generator, to get a reproducible sample of bit
+        // patterns from all over the range of `f64`.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+
+        let mut checked = 0;
+        while checked < 100_000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+
+            let value = f64::from_bits(state);
+            if !value.is_finite() {
+                continue;
+            }
+
+            let scalar = Scalar::from_f64(value);
+            let serialized = scalar.to_string_exact();
+            let parsed = Scalar::from_str_exact(&serialized)?;
+
+            assert_eq!(parsed.into_f64().to_bits(), value.to_bits());
+            assert_eq!(parsed.to_string_exact(), serialized);
+            assert_eq!(hash(parsed), hash(scalar));
+
+            checked += 1;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn signed_zero() {
+        let positive = Scalar::from_f64(0.);
+        let negative = Scalar::from_f64(-0.);
+
+        assert_eq!(positive, negative);
+        assert_eq!(hash(positive), hash(negative));
+        assert_eq!(positive.to_string_exact(), negative.to_string_exact());
+    }
+
+    #[test]
+    #[should_panic]
+    fn nan_is_rejected() {
+        Scalar::from_f64(f64::NAN);
+    }
+
+    #[test]
+    fn parse_exact() {
+        assert_eq!(Scalar::from_str_exact("1"), Ok(Scalar::ONE));
+        assert_eq!(Scalar::from_str_exact("0.100"), Ok(Scalar::from_f64(0.1)));
+        assert_eq!(
+            Scalar::from_str_exact("-2.5e3"),
+            Ok(Scalar::from_f64(-2500.))
+        );
+
+        assert!(matches!(
+            Scalar::from_str_exact("0.1000000000000000000001"),
+            Err(ParseScalarError::Lossy(_))
+        ));
+        assert!(matches!(
+            Scalar::from_str_exact("NaN"),
+            Err(ParseScalarError::NotFinite(_))
+        ));
+        assert!(matches!(
+            Scalar::from_str_exact("inf"),
+            Err(ParseScalarError::NotFinite(_))
+        ));
+        assert!(matches!(
+            Scalar::from_str_exact("one"),
+            Err(ParseScalarError::Invalid(_))
+        ));
+    }
+
+    fn hash(scalar: Scalar) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        scalar.hash(&mut hasher);
+        hasher.finish()
+    }
+}
@@ -2,7 +2,7 @@ use std::ops;
 
 use nalgebra::Perspective3;
 
-use crate::{Circle, Line, Scalar};
+use crate::{Circle, Ellipse, Line, Polyline, Scalar};
 
 use super::{Aabb, Point, Segment, Triangle, Vector};
 
@@ -40,6 +40,126 @@ impl Transform {
         ))
     }
 
+    /// Construct a rotation around an axis
+    ///
+    /// The rotation follows the right-hand rule, and `angle` is in radians.
+    /// Unlike [`Transform::rotation`], the length of `axis` doesn't matter, as
+    /// long as it isn't zero. If either `axis` or `angle` is zero, the result is
+    /// exactly the identity.
+    pub fn rotation_from_axis_angle(
+        axis: impl Into<Vector<3>>,
+        angle: impl Into<Scalar>,
+    ) -> Self {
+        let axis = axis.into();
+        let angle = angle.into();
+
+        let length = axis.magnitude();
+        if length == Scalar::ZERO || angle == Scalar::ZERO {
+            return Self::identity();
+        }
+
+        let (sin, cos) = (angle / 2.).sin_cos();
+        let [x, y, z] = (axis * (sin / length)).components;
+
+        Self::from_quaternion([x, y, z, cos].map(Scalar::into_f64))
+    }
+
+    /// Construct the rotation that turns one direction into another
+    ///
+    /// The rotation is around the axis that is perpendicular to both
+    /// directions, by the angle between them. If they point in opposite
+    /// directions, that axis isn't defined, and the rotation is by half a turn
+    /// around an axis that is perpendicular to `from`. The lengths of the
+    /// vectors don't matter, but they must not be zero.
+    pub fn rotation_between(
+        from: impl Into<Vector<3>>,
+        to: impl Into<Vector<3>>,
+    ) -> Self {
+        let from = from.into().normalize();
+        let to = to.into().normalize();
+
+        let cos = from.dot(&to);
+
+        // For nearly opposite directions, the cross product is too small to
+        // reliably define an axis. Crossing `from` with the coordinate axis
+        // that it's least aligned with gives a well-conditioned one.
+        if cos < Scalar::from_f64(-1. + 1e-12) {
+            let [x, y, z] = from.components.map(Scalar::abs);
+            let reference = if x <= y && x <= z {
+                Vector::unit_x()
+            } else if y <= z {
+                Vector::unit_y()
+            } else {
+                Vector::unit_z()
+            };
+
+            let [x, y, z] = from.cross(&reference).normalize().components;
+            return Self::from_quaternion(
+                [x, y, z, Scalar::ZERO].map(Scalar::into_f64),
+            );
+        }
+
+        // The quaternion for twice the rotation is made up of the cross
+        // product and the cosine. Adding the identity quaternion halves it.
+        let [x, y, z] = from.cross(&to).components;
+        Self::from_quaternion(
+            [x, y, z, Scalar::ONE + cos].map(Scalar::into_f64),
+        )
+    }
+
+    /// Construct a rotation from a quaternion
+    ///
+    /// The components are in the order `[x, y, z, w]`, where `w` is the real
+    /// part. The quaternion is normalized, so its length doesn't matter. If
+    /// it is zero, the result is the identity.
+    pub fn from_quaternion([x, y, z, w]: [f64; 4]) -> Self {
+        let quaternion = nalgebra::Quaternion::new(w, x, y, z);
+        if quaternion.norm() == 0. {
+            return Self::identity();
+        }
+
+        let rotation = nalgebra::UnitQuaternion::from_quaternion(quaternion);
+        Self(nalgebra::Transform::from_matrix_unchecked(
+            rotation.to_homogeneous(),
+        ))
+    }
+
+    /// Construct a scaling
+    ///
+    /// Each component of the vector defines the scaling factor along the
+    /// respective axis. Non-uniform scaling turns circles into ellipses.
+    pub fn scale(factors: impl Into<Vector<3>>) -> Self {
+        let factors = factors.into();
+
+        Self(nalgebra::Transform::from_matrix_unchecked(
+            nalgebra::Matrix4::new_nonuniform_scaling(&factors.to_na()),
+        ))
+    }
+
+    /// Construct a reflection across a plane
+    ///
+    /// The plane goes through `origin` and is perpendicular to `normal`. The
+    /// length of `normal` doesn't matter, but it must not be zero. Reflections
+    /// mirror space, so their determinant is negative.
+    pub fn reflection(
+        origin: impl Into<Point<3>>,
+        normal: impl Into<Vector<3>>,
+    ) -> Self {
+        let origin = origin.into();
+        let normal = normal.into().normalize().to_na();
+
+        // Householder reflection, which mirrors across the plane through the
+        // origin, followed by a translation that moves that plane into place.
+        let linear =
+            nalgebra::Matrix3::identity() - normal * normal.transpose() * 2.;
+        let offset = normal * (origin.coords.to_na().dot(&normal) * 2.);
+
+        let mut matrix = linear.to_homogeneous();
+        matrix.fixed_slice_mut::<3, 1>(0, 3).copy_from(&offset);
+
+        Self(nalgebra::Transform::from_matrix_unchecked(matrix))
+    }
+
     /// Transform the given point
     pub fn transform_point(&self, point: &Point<3>) -> Point<3> {
         Point::from(self.0.transform_point(&point.to_na()))
@@ -88,6 +208,27 @@ impl Transform {
         }
     }
 
+    /// Transform the given ellipse
+    ///
+    /// Any affine transform maps an ellipse to an ellipse, and preserves the
+    /// curve coordinates of its points.
+    pub fn transform_ellipse(&self, ellipse: &Ellipse<3>) -> Ellipse<3> {
+        Ellipse {
+            center: self.transform_point(&ellipse.center),
+            a: self.transform_vector(&ellipse.a),
+            b: self.transform_vector(&ellipse.b),
+        }
+    }
+
+    /// Transform the given polyline
+    ///
+    /// The knots of the polyline are preserved, so curve coordinates on the
+    /// polyline still refer to the same points. If the transform isn't rigid,
+    /// they are no longer arc lengths.
+    pub fn transform_polyline(&self, polyline: &Polyline<3>) -> Polyline<3> {
+        polyline.map_points(|point| self.transform_point(point))
+    }
+
     /// Inverse transform
     pub fn inverse(&self) -> Transform {
         Self(self.0.inverse())
@@ -119,6 +260,14 @@ impl Transform {
         array.map(Scalar::from)
     }
 
+    /// Compute the determinant of the linear part of the transform
+    ///
+    /// The determinant is negative, if the transform mirrors space, which
+    /// inverts the winding of triangles.
+    pub fn determinant(&self) -> Scalar {
+        Scalar::from(self.0.matrix().fixed_slice::<3, 3>(0, 0).determinant())
+    }
+
     /// Transform the given axis-aligned bounding box
     pub fn transform_aabb(&self, aabb: &Aabb<3>) -> Aabb<3> {
         Aabb {
@@ -182,6 +331,79 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rotation_by_zero_angle() {
+        let point = Point::from([0.1, -2.3, 4.5]);
+
+        for axis in [[0., 0., 1.], [1., 2., 3.], [0., 0., 0.]] {
+            let rotation = Transform::rotation_from_axis_angle(axis, 0.);
+            assert_eq!(rotation.transform_point(&point), point);
+        }
+
+        // Without an axis, there is nothing to rotate around.
+        let rotation = Transform::rotation_from_axis_angle([0., 0., 0.], 1.);
+        assert_eq!(rotation.transform_point(&point), point);
+    }
+
+    #[test]
+    fn rotation_between_opposite_directions() {
+        let rotation = Transform::rotation_between([1., 0., 0.], [-1., 0., 0.]);
+
+        assert_abs_diff_eq!(
+            rotation.transform_vector(&Vector::unit_x()),
+            Vector::from([-1., 0., 0.]),
+            epsilon = 1e-15,
+        );
+
+        let rotation = Transform::rotation_between([1., 2., 3.], [0., 0., 5.]);
+        assert_abs_diff_eq!(
+            rotation.transform_vector(&Vector::from([1., 2., 3.]).normalize()),
+            Vector::unit_z(),
+            epsilon = 1e-15,
+        );
+    }
+
+    #[test]
+    fn reflection() {
+        let reflection = Transform::reflection([1., 0., 0.], [2., 0., 0.]);
+
+        assert_abs_diff_eq!(
+            reflection.transform_point(&Point::from([3., 1., 2.])),
+            Point::from([-1., 1., 2.]),
+            epsilon = 1e-15,
+        );
+        assert_eq!(reflection.determinant(), -Scalar::ONE);
+
+        // Reflecting twice restores the original point.
+        let point = Point::from([0.1, -2.3, 4.5]);
+        let reflection = Transform::reflection([1., 2., 3.], [1., 1., 0.]);
+        assert_abs_diff_eq!(
+            (reflection * reflection).transform_point(&point),
+            point,
+            epsilon = 1e-12,
+        );
+    }
+
+    #[test]
+    fn many_small_rotations() {
+        let n = 1_000_000;
+        let step = Transform::rotation_from_axis_angle(
+            [1., 1., 0.],
+            Scalar::PI * 2. / n as f64,
+        );
+
+        let mut rotation = Transform::identity();
+        for _ in 0..n {
+            rotation = rotation * step;
+        }
+
+        assert_abs_diff_eq!(
+            rotation.data(),
+            Transform::identity().data(),
+            epsilon = 1e-9,
+        );
+    }
+
     #[test]
     fn extract_rotation_translation() {
         let rotation =
@@ -0,0 +1,104 @@
+use std::ops;
+
+use crate::{Line, Scalar};
+
+use super::{Point, Vector};
+
+/// An affine transform in 2D
+///
+/// Used to convert between different parameterizations of the same surface.
+#[derive(Debug, Clone, Copy)]
+pub struct Transform2(nalgebra::Transform<f64, nalgebra::TAffine, 2>);
+
+impl Transform2 {
+    /// Construct an identity transform
+    pub fn identity() -> Self {
+        Self(nalgebra::Transform::identity())
+    }
+
+    /// Construct a transform from its linear part and a translation
+    ///
+    /// The transform maps the unit vectors of the u- and v-axis to `u` and
+    /// `v` respectively, then applies `translation`.
+    pub fn from_axes(
+        u: impl Into<Vector<2>>,
+        v: impl Into<Vector<2>>,
+        translation: impl Into<Vector<2>>,
+    ) -> Self {
+        let [u, v, t] = [u.into(), v.into(), translation.into()]
+            .map(|vector| vector.components.map(Scalar::into_f64));
+
+        Self(nalgebra::Transform::from_matrix_unchecked(
+            nalgebra::Matrix3::new(
+                u[0], v[0], t[0], //
+                u[1], v[1], t[1], //
+                0., 0., 1.,
+            ),
+        ))
+    }
+
+    /// Transform the given point
+    pub fn transform_point(&self, point: &Point<2>) -> Point<2> {
+        Point::from(self.0.transform_point(&point.to_na()))
+    }
+
+    /// Transform the given vector
+    pub fn transform_vector(&self, vector: &Vector<2>) -> Vector<2> {
+        Vector::from(self.0.transform_vector(&vector.to_na()))
+    }
+
+    /// Transform the given line
+    pub fn transform_line(&self, line: &Line<2>) -> Line<2> {
+        Line {
+            origin: self.transform_point(&line.origin),
+            direction: self.transform_vector(&line.direction),
+        }
+    }
+
+    /// Inverse transform
+    pub fn inverse(&self) -> Transform2 {
+        Self(self.0.inverse())
+    }
+
+    /// Exposes the data of this transform as a slice of f64
+    pub fn data(&self) -> &[f64] {
+        self.0.matrix().data.as_slice()
+    }
+}
+
+impl ops::Mul<Self> for Transform2 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(self.0.mul(rhs.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use crate::{Point, Vector};
+
+    use super::Transform2;
+
+    #[test]
+    fn transform_and_inverse() {
+        let transform = Transform2::from_axes([2., 0.], [1., 3.], [1., 1.]);
+
+        let point = Point::from([1., 1.]);
+        let transformed = transform.transform_point(&point);
+        assert_abs_diff_eq!(transformed, Point::from([4., 4.]), epsilon = 1e-8);
+        assert_abs_diff_eq!(
+            transform.inverse().transform_point(&transformed),
+            point,
+            epsilon = 1e-8,
+        );
+
+        assert_abs_diff_eq!(
+            transform.transform_vector(&Vector::from([1., 1.])),
+            Vector::from([3., 3.]),
+            epsilon = 1e-8,
+        );
+    }
+}
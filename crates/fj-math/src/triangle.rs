@@ -1,5 +1,5 @@
 use parry2d_f64::utils::point_in_triangle::{corner_direction, Orientation};
-use parry3d_f64::query::{Ray, RayCast as _};
+use parry3d_f64::query::{PointQuery as _, Ray, RayCast as _};
 
 use crate::Vector;
 
@@ -88,6 +88,16 @@ impl Triangle<3> {
             .map(|f| f.into())
     }
 
+    /// Compute the distance between the triangle and a point
+    ///
+    /// The triangle is treated as solid, so the distance is zero for points
+    /// within it.
+    pub fn distance_to_point(&self, point: impl Into<Point<3>>) -> Scalar {
+        self.to_parry()
+            .distance_to_local_point(&point.into().to_na(), true)
+            .into()
+    }
+
     /// Compute the triangle's normal
     pub fn normal(&self) -> Vector<3> {
         self.to_parry()
@@ -108,6 +118,7 @@ where
 }
 
 /// Winding direction of a triangle.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Winding {
     /// Counter-clockwise
     Ccw,
@@ -127,7 +138,7 @@ impl From<Orientation> for Winding {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Point, Vector};
+    use crate::{Point, Scalar, Vector};
 
     use super::Triangle;
 
@@ -165,6 +176,19 @@ mod tests {
         let _triangle = Triangle::from([a, b, c]);
     }
 
+    #[test]
+    fn distance_to_point() {
+        let triangle =
+            Triangle::from([[0., 0., 0.], [2., 0., 0.], [0., 2., 0.]]);
+
+        assert_eq!(triangle.distance_to_point([0.5, 0.5, 0.]), Scalar::ZERO);
+        assert_eq!(
+            triangle.distance_to_point([0.5, 0.5, 3.]),
+            Scalar::from(3.)
+        );
+        assert_eq!(triangle.distance_to_point([-4., 0., 0.]), Scalar::from(4.));
+    }
+
     #[test]
     fn normal() {
         let triangle =
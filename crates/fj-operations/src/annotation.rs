@@ -0,0 +1,236 @@
+use fj_interop::{
+    annotation::{Annotation, AnnotationKind, ANNOTATION_COLOR},
+    debug::DebugInfo,
+    gizmo::LineSet,
+};
+use fj_kernel::{
+    algorithms::{ErrorBudget, Tolerance},
+    objects::{Curve, Edge, Face},
+    validation::{Validated, ValidationConfig},
+};
+use fj_math::{Aabb, Point, Vector};
+
+use crate::{Error, Shape};
+
+impl Shape for fj::Annotated {
+    type Brep = Vec<Face>;
+
+    fn compute_brep(
+        &self,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        error_budget: &mut ErrorBudget,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Validated<Self::Brep>, Error> {
+        // Annotations don't affect the geometry. They are resolved separately,
+        // once the geometry is known.
+        self.shape()
+            .compute_brep(config, tolerance, error_budget, debug_info)
+            .map_err(|err| err.in_shape("shape"))
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        self.shape().bounding_volume()
+    }
+}
+
+/// Resolve annotations against the faces of the annotated shape
+///
+/// The measured values are taken from the faces, so they reflect the shape as
+/// it was actually built.
+pub(crate) fn resolve_annotations(
+    annotations: &[fj::Annotation],
+    faces: &[Face],
+) -> Result<Vec<Annotation>, Error> {
+    annotations
+        .iter()
+        .enumerate()
+        .map(|(i, annotation)| {
+            resolve(annotation, faces).map_err(|err| {
+                Error::from(err).in_shape(format!("annotations[{i}]"))
+            })
+        })
+        .collect()
+}
+
+fn resolve(
+    annotation: &fj::Annotation,
+    faces: &[Face],
+) -> Result<Annotation, AnnotationError> {
+    let mut leaders = LineSet::new();
+
+    let annotation = match annotation {
+        fj::Annotation::LinearDimension {
+            from,
+            to,
+            offset_direction,
+        } => {
+            let [a, b] =
+                [resolve_vertex(faces, from)?, resolve_vertex(faces, to)?];
+            let length = (b - a).magnitude();
+
+            let offset = Vector::from(*offset_direction);
+            let [a_offset, b_offset] = [a + offset, b + offset];
+
+            leaders.push_line([a, a_offset], ANNOTATION_COLOR);
+            leaders.push_line([b, b_offset], ANNOTATION_COLOR);
+            leaders.push_line([a_offset, b_offset], ANNOTATION_COLOR);
+
+            Annotation {
+                kind: AnnotationKind::LinearDimension { length },
+                text: format!("{:.2}", length.into_f64()),
+                position: a_offset + (b_offset - a_offset) / 2.,
+                leaders,
+            }
+        }
+        fj::Annotation::RadiusDimension { edge_ref } => {
+            let circle =
+                match resolve_edge(faces, edge_ref)?.curve().global_form() {
+                    Curve::Circle(circle) => *circle,
+                    _ => return Err(AnnotationError::NotACircle(*edge_ref)),
+                };
+            let radius = circle.a.magnitude();
+
+            leaders.push_line(
+                [circle.center, circle.center + circle.a],
+                ANNOTATION_COLOR,
+            );
+
+            Annotation {
+                kind: AnnotationKind::RadiusDimension { radius },
+                text: format!("R{:.2}", radius.into_f64()),
+                position: circle.center + circle.a / 2.,
+                leaders,
+            }
+        }
+        fj::Annotation::Note { anchor, text } => Annotation {
+            kind: AnnotationKind::Note,
+            text: text.clone(),
+            position: Point::from(*anchor),
+            leaders,
+        },
+    };
+
+    Ok(annotation)
+}
+
+fn resolve_edge<'r>(
+    faces: &'r [Face],
+    edge_ref: &fj::EdgeRef,
+) -> Result<&'r Edge, AnnotationError> {
+    let face = faces
+        .get(edge_ref.face)
+        .ok_or(AnnotationError::InvalidEdgeRef(*edge_ref))?;
+
+    // Faces that are made of triangles, like imported meshes, have no edges.
+    if face.triangles().is_some() {
+        return Err(AnnotationError::NotBoundaryRepresentation(*edge_ref));
+    }
+
+    face.all_cycles()
+        .nth(edge_ref.cycle)
+        .and_then(|cycle| cycle.edges.get(edge_ref.edge))
+        .ok_or(AnnotationError::InvalidEdgeRef(*edge_ref))
+}
+
+fn resolve_vertex(
+    faces: &[Face],
+    vertex_ref: &fj::VertexRef,
+) -> Result<Point<3>, AnnotationError> {
+    resolve_edge(faces, &vertex_ref.edge)?
+        .vertices()
+        .get()
+        .map(|[start, _]| start.global().position())
+        .ok_or(AnnotationError::NoVertices(vertex_ref.edge))
+}
+
+/// An error that can occur while resolving annotations
+#[derive(Debug, thiserror::Error)]
+pub enum AnnotationError {
+    /// The edge reference doesn't refer to an edge of the shape
+    #[error("No edge at {0:?}")]
+    InvalidEdgeRef(fj::EdgeRef),
+
+    /// A vertex was referenced through an edge that has no vertices
+    #[error("Edge at {0:?} has no vertices")]
+    NoVertices(fj::EdgeRef),
+
+    /// The edge reference refers to a face that is made of triangles
+    #[error("Face of edge at {0:?} is made of triangles and has no edges")]
+    NotBoundaryRepresentation(fj::EdgeRef),
+
+    /// A radius dimension refers to an edge that isn't circular
+    #[error("Edge at {0:?} is not a circle")]
+    NotACircle(fj::EdgeRef),
+}
+
+#[cfg(test)]
+mod tests {
+    use fj::syntax::{Annotate as _, Sketch as _, Sweep as _};
+    use fj_interop::annotation::AnnotationKind;
+
+    use crate::{shape_processor::ShapeProcessor, Error};
+
+    fn cube(size: f64) -> fj::Shape {
+        let edge = fj::EdgeRef {
+            face: 0,
+            cycle: 0,
+            edge: 0,
+        };
+        let next_edge = fj::EdgeRef { edge: 1, ..edge };
+
+        [[0., 0.], [size, 0.], [size, size], [0., size]]
+            .sketch()
+            .sweep([0., 0., size])
+            .annotate(vec![fj::Annotation::LinearDimension {
+                from: fj::VertexRef { edge },
+                to: fj::VertexRef { edge: next_edge },
+                offset_direction: [0., 0., -1.],
+            }])
+            .into()
+    }
+
+    #[test]
+    fn linear_dimension_follows_geometry() -> Result<(), Error> {
+        let processor = ShapeProcessor::default();
+
+        for size in [1., 2.5] {
+            let shape = processor.process(&cube(size))?;
+
+            assert_eq!(shape.annotations.len(), 1);
+            match shape.annotations[0].kind {
+                AnnotationKind::LinearDimension { length } => {
+                    assert_eq!(length.into_f64(), size);
+                }
+                kind => panic!("Unexpected annotation: {kind:?}"),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_reference() {
+        let shape: fj::Shape = [[0., 0.], [1., 0.], [0., 1.]]
+            .sketch()
+            .sweep([0., 0., 1.])
+            .annotate(vec![fj::Annotation::RadiusDimension {
+                edge_ref: fj::EdgeRef {
+                    face: 0,
+                    cycle: 0,
+                    edge: 0,
+                },
+            }])
+            .into();
+
+        let err = ShapeProcessor::default()
+            .process(&shape)
+            .err()
+            .expect("Edge is not a circle");
+        assert_eq!(err.error_code(), "annotation");
+        assert_eq!(
+            err.path().map(ToString::to_string),
+            Some("annotations[0]".into())
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::{ErrorBudget, Tolerance},
+    objects::Face,
+    validation::{validate_intermediate, Validated, ValidationConfig},
+};
+use fj_math::{Aabb, Point};
+
+use crate::{Error, Shape};
+
+impl Shape for fj::Assembly {
+    type Brep = Vec<Face>;
+
+    fn compute_brep(
+        &self,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        error_budget: &mut ErrorBudget,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Validated<Self::Brep>, Error> {
+        let mut faces = Vec::new();
+
+        for (i, instance) in self.instances().iter().enumerate() {
+            let instance = instance
+                .compute_brep(config, tolerance, error_budget, debug_info)
+                .map_err(|err| err.in_shape(format!("instances[{i}]")))?;
+            faces.extend(instance.into_inner());
+        }
+
+        Ok(validate_intermediate(faces, config)?)
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        // Like in groups, empty instances don't have a position, and must not
+        // extend the bounding volume.
+        self.instances()
+            .iter()
+            .map(|instance| instance.bounding_volume())
+            .filter(|aabb| aabb.min != aabb.max)
+            .reduce(|a, b| a.merged(&b))
+            .unwrap_or_else(|| Aabb::<3>::from_points([Point::origin()]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj::syntax::{Sketch as _, Sweep as _, Transform as _};
+    use fj_math::{Point, Scalar};
+
+    use crate::{shape_processor::ShapeProcessor, Error, Shape as _};
+
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+
+    fn cube(color: [u8; 4]) -> fj::Sweep {
+        [[0., 0.], [1., 0.], [1., 1.], [0., 1.]]
+            .sketch()
+            .with_color(color)
+            .sweep([0., 0., 1.])
+    }
+
+    #[test]
+    fn overlapping_instances() -> Result<(), Error> {
+        let assembly = fj::Assembly::new()
+            .with_instance(cube(RED).translate([0., 0., 0.]))
+            .with_instance(cube(BLUE).translate([0.5, 0., 0.]));
+
+        let aabb = assembly.bounding_volume();
+        assert_eq!(aabb.min, Point::from([0., 0., 0.]));
+        assert_eq!(aabb.max, Point::from([1.5, 1., 1.]));
+
+        let processed = ShapeProcessor::default().process(&assembly.into())?;
+
+        // The instances are not united, so each keeps all of its faces.
+        assert_eq!(processed.mesh.triangle_count(), 24);
+        for color in [RED, BLUE] {
+            assert!(processed
+                .mesh
+                .triangles()
+                .any(|triangle| triangle.color == color));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn nested_assemblies() -> Result<(), Error> {
+        let inner = fj::Assembly::new()
+            .with_instance(cube(RED).translate([1., 0., 0.]));
+        let outer = fj::Assembly::new()
+            .with_instance(cube(BLUE).translate([0., 0., 0.]))
+            .with_instance(inner.translate([0., 0., 2.]));
+
+        let processed = ShapeProcessor::default().process(&outer.into())?;
+
+        // The transform of the inner assembly applies to its instances.
+        let red_points: Vec<_> = processed
+            .mesh
+            .triangles()
+            .filter(|triangle| triangle.color == RED)
+            .flat_map(|triangle| triangle.points)
+            .collect();
+        assert!(!red_points.is_empty());
+        for point in red_points {
+            assert!(point.x >= Scalar::ONE);
+            assert!(point.z >= Scalar::from_f64(2.));
+        }
+
+        Ok(())
+    }
+}
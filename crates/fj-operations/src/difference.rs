@@ -0,0 +1,81 @@
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::{difference, ErrorBudget, Tolerance},
+    objects::Solid,
+    validation::{validate_intermediate, Validated, ValidationConfig},
+};
+use fj_math::Aabb;
+
+use crate::{Error, Shape};
+
+impl Shape for fj::Difference {
+    type Brep = Solid;
+
+    fn compute_brep(
+        &self,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        error_budget: &mut ErrorBudget,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Validated<Self::Brep>, Error> {
+        let a = self
+            .a
+            .compute_brep(config, tolerance, error_budget, debug_info)
+            .map_err(|err| err.in_shape("a"))?;
+        let b = self
+            .b
+            .compute_brep(config, tolerance, error_budget, debug_info)
+            .map_err(|err| err.in_shape("b"))?;
+
+        let solid = difference(
+            Solid::from_faces(a.into_inner()),
+            Solid::from_faces(b.into_inner()),
+            tolerance,
+        )?;
+
+        Ok(validate_intermediate(solid, config)?)
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        // The difference can't extend beyond `a`.
+        self.a.bounding_volume()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj::syntax::{Sketch as _, Subtract as _, Sweep as _};
+
+    use crate::{shape_processor::ShapeProcessor, Error};
+
+    fn cuboid([x, y, z]: [f64; 3], [dx, dy, dz]: [f64; 3]) -> fj::Shape {
+        let cuboid = [[0., 0.], [dx, 0.], [dx, dy], [0., dy]]
+            .sketch()
+            .sweep([0., 0., dz]);
+
+        fj::Transform {
+            shape: cuboid.into(),
+            axis: [0., 0., 1.],
+            angle: fj::Angle::from_rad(0.),
+            offset: [x, y, z],
+        }
+        .into()
+    }
+
+    #[test]
+    fn blind_hole() -> Result<(), Error> {
+        let block = cuboid([0., 0., 0.], [1., 1., 1.]);
+        let tool = cuboid([0.25, 0.25, 0.5], [0.5, 0.5, 1.]);
+
+        let shape: fj::Shape = block.subtract(&tool).into();
+        let processed = ShapeProcessor::default().process(&shape)?;
+
+        let volume = processed.summary.volume.into_f64();
+        assert!((volume - (1. - 0.5 * 0.5 * 0.5)).abs() < 1e-9);
+
+        // The tool doesn't extend the bounding volume.
+        assert_eq!(processed.aabb.max.z.into_f64(), 1.);
+
+        Ok(())
+    }
+}
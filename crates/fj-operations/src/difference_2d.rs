@@ -1,14 +1,14 @@
 use fj_interop::debug::DebugInfo;
 use fj_kernel::{
-    algorithms::Tolerance,
-    iter::ObjectIters,
-    local::Local,
-    objects::{Cycle, Edge, Face, Sketch},
-    validation::{validate, Validated, ValidationConfig, ValidationError},
+    algorithms::{BooleanChain, ErrorBudget, Tolerance},
+    objects::Sketch,
+    validation::{
+        validate_deferred, validate_intermediate, Validated, ValidationConfig,
+    },
 };
 use fj_math::Aabb;
 
-use super::Shape;
+use crate::{Error, Shape};
 
 impl Shape for fj::Difference2d {
     type Brep = Sketch;
@@ -17,64 +17,34 @@ impl Shape for fj::Difference2d {
         &self,
         config: &ValidationConfig,
         tolerance: Tolerance,
+        error_budget: &mut ErrorBudget,
         debug_info: &mut DebugInfo,
-    ) -> Result<Validated<Self::Brep>, ValidationError> {
+    ) -> Result<Validated<Self::Brep>, Error> {
         // This method assumes that `b` is fully contained within `a`:
         // https://github.com/hannobraun/Fornjot/issues/92
 
-        let mut faces = Vec::new();
-
-        let mut exteriors = Vec::new();
-        let mut interiors = Vec::new();
-
         // Can be cleaned up, once `each_ref` and `try_map` are stable:
         // - https://doc.rust-lang.org/std/primitive.array.html#method.each_ref
         // - https://doc.rust-lang.org/std/primitive.array.html#method.try_map
         let [a, b] = self.shapes();
-        let [a, b] = [a, b]
-            .map(|shape| shape.compute_brep(config, tolerance, debug_info));
+        let [a, b] = [("a", a), ("b", b)].map(|(name, shape)| {
+            shape
+                .compute_brep(config, tolerance, error_budget, debug_info)
+                .map_err(|err| err.in_shape(name))
+        });
         let [a, b] = [a?, b?];
 
-        if let Some(face) = a.face_iter().next() {
-            // If there's at least one face to subtract from, we can proceed.
-
-            let surface = face.surface();
-
-            for face in a.face_iter() {
-                assert_eq!(
-                    surface,
-                    face.surface(),
-                    "Trying to subtract faces with different surfaces.",
-                );
-
-                for cycle in face.exteriors() {
-                    let cycle = add_cycle(cycle.clone(), false);
-                    exteriors.push(cycle);
-                }
-                for cycle in face.interiors() {
-                    let cycle = add_cycle(cycle.clone(), true);
-                    interiors.push(cycle);
-                }
-            }
-
-            for face in b.face_iter() {
-                assert_eq!(
-                    surface,
-                    face.surface(),
-                    "Trying to subtract faces with different surfaces.",
-                );
-
-                for cycle in face.exteriors() {
-                    let cycle = add_cycle(cycle.clone(), true);
-                    interiors.push(cycle);
-                }
-            }
-
-            faces.push(Face::new(*surface, exteriors, interiors, self.color()));
-        }
-
-        let difference = Sketch::from_faces(faces);
-        validate(difference, config)
+        // The subtraction below relies on valid input, so don't accept any
+        // unvalidated intermediate results.
+        let a = validate_deferred(a, config)?;
+        let b = validate_deferred(b, config)?;
+
+        let tolerances =
+            config.intersection_tolerances(&self.bounding_volume());
+        let difference = BooleanChain::new(a.into_inner())
+            .subtract_all([b.into_inner()])
+            .apply(&tolerances)?;
+        Ok(validate_intermediate(difference, config)?)
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
@@ -85,35 +55,44 @@ impl Shape for fj::Difference2d {
     }
 }
 
-fn add_cycle(cycle: Cycle, reverse: bool) -> Cycle {
-    let mut edges = Vec::new();
-    for edge in cycle.edges {
-        let curve_local = if reverse {
-            edge.curve().local_form().reverse()
-        } else {
-            *edge.curve().local_form()
-        };
-
-        let curve_global = if reverse {
-            edge.curve().global_form().reverse()
-        } else {
-            *edge.curve().global_form()
-        };
+impl Shape for fj::DifferenceAll {
+    type Brep = Sketch;
 
-        let vertices = if reverse {
-            edge.vertices().reverse()
-        } else {
-            *edge.vertices()
-        };
+    fn compute_brep(
+        &self,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        error_budget: &mut ErrorBudget,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Validated<Self::Brep>, Error> {
+        // Like `Difference2d`, this assumes that all tools are fully contained
+        // within the base, and don't overlap each other:
+        // https://github.com/hannobraun/Fornjot/issues/92
 
-        let edge = Edge::new(Local::new(curve_local, curve_global), vertices);
+        let base = self
+            .base()
+            .compute_brep(config, tolerance, error_budget, debug_info)
+            .map_err(|err| err.in_shape("base"))?;
+        let base = validate_deferred(base, config)?;
+
+        let mut tools = Vec::new();
+        for (i, tool) in self.tools().iter().enumerate() {
+            let tool = tool
+                .compute_brep(config, tolerance, error_budget, debug_info)
+                .map_err(|err| err.in_shape(format!("tools[{i}]")))?;
+            tools.push(validate_deferred(tool, config)?.into_inner());
+        }
 
-        edges.push(edge);
+        let tolerances =
+            config.intersection_tolerances(&self.bounding_volume());
+        let difference = BooleanChain::new(base.into_inner())
+            .subtract_all(tools)
+            .apply(&tolerances)?;
+        Ok(validate_intermediate(difference, config)?)
     }
 
-    if reverse {
-        edges.reverse();
+    fn bounding_volume(&self) -> Aabb<3> {
+        // See `Difference2d`.
+        self.base().bounding_volume()
     }
-
-    Cycle { edges }
 }
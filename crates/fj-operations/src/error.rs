@@ -0,0 +1,288 @@
+use std::fmt;
+
+use fj_export::ImportError;
+use fj_kernel::{
+    algorithms::{
+        BooleanError, DifferenceError, FilletError, InvalidTolerance,
+        RevolveError, ShellError, SweepError, TriangulationError,
+    },
+    validation::ValidationError,
+};
+
+use crate::{
+    annotation::AnnotationError, material::MaterialError, mirror::MirrorError,
+    section::SectionError,
+};
+
+/// An error that can occur while processing a shape
+///
+/// Errors from the kernel are wrapped, not converted, so the original error
+/// can be recovered from the [`std::error::Error::source`] chain.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Shape validation failed
+    #[error("Shape validation failed")]
+    Validation(#[from] ValidationError),
+
+    /// Triangulation failed
+    #[error("Triangulation failed")]
+    Triangulation(#[from] TriangulationError),
+
+    /// Model has zero size
+    #[error("Model has zero size")]
+    Extent(#[from] InvalidTolerance),
+
+    /// The tolerance that a shape overrides is invalid
+    #[error("Invalid tolerance override")]
+    Tolerance(#[source] InvalidTolerance),
+
+    /// A mesh could not be imported
+    #[error("Failed to import mesh")]
+    Import(#[from] ImportError),
+
+    /// Boolean operation failed
+    #[error("Boolean operation failed")]
+    Boolean(#[from] BooleanError),
+
+    /// Sweep failed
+    #[error("Sweep failed")]
+    Sweep(#[from] SweepError),
+
+    /// Revolve failed
+    #[error("Revolve failed")]
+    Revolve(#[from] RevolveError),
+
+    /// Difference failed
+    #[error("Difference failed")]
+    Difference(#[from] DifferenceError),
+
+    /// Fillet failed
+    #[error("Fillet failed")]
+    Fillet(#[from] FilletError),
+
+    /// Shell failed
+    #[error("Shell failed")]
+    Shell(#[from] ShellError),
+
+    /// An annotation could not be resolved
+    #[error("Failed to resolve annotation")]
+    Annotation(#[from] AnnotationError),
+
+    /// A material could not be assigned
+    #[error("Failed to assign material")]
+    Material(#[from] MaterialError),
+
+    /// A shape could not be reflected
+    #[error("Failed to mirror shape")]
+    Mirror(#[from] MirrorError),
+
+    /// A section could not be evaluated
+    #[error("Failed to evaluate section")]
+    Section(#[from] SectionError),
+
+    /// An error occurred while processing a part of a shape
+    #[error("Error in shape `{path}`")]
+    InShape {
+        /// The path to the part of the shape that caused the error
+        path: ShapePath,
+
+        /// The error that occurred
+        #[source]
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Access a stable code that identifies the kind of error
+    ///
+    /// Unlike the error message, this code is not going to change, so it can
+    /// be used to handle errors programmatically. Errors that occurred in a
+    /// part of a shape return the code of the original error.
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            Self::Validation(_) => "validation",
+            Self::Triangulation(_) => "triangulation",
+            Self::Extent(_) => "extent",
+            Self::Tolerance(_) => "tolerance",
+            Self::Import(_) => "import",
+            Self::Boolean(_) => "boolean",
+            Self::Sweep(_) => "sweep",
+            Self::Revolve(_) => "revolve",
+            Self::Difference(_) => "difference",
+            Self::Fillet(_) => "fillet",
+            Self::Shell(_) => "shell",
+            Self::Annotation(_) => "annotation",
+            Self::Material(_) => "material",
+            Self::Mirror(_) => "mirror",
+            Self::Section(_) => "section",
+            Self::InShape { source, .. } => source.error_code(),
+        }
+    }
+
+    /// Access the path to the part of the shape that caused the error
+    ///
+    /// Returns `None`, if the error didn't occur in a part of a shape.
+    pub fn path(&self) -> Option<&ShapePath> {
+        match self {
+            Self::InShape { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Mark the error as having occurred in a part of a shape
+    ///
+    /// If the error already occurred in a part of that part, the paths are
+    /// combined, instead of nesting the errors.
+    pub(crate) fn in_shape(self, part: impl Into<String>) -> Self {
+        let part = part.into();
+
+        match self {
+            Self::InShape { mut path, source } => {
+                path.0.insert(0, part);
+                Self::InShape { path, source }
+            }
+            err => Self::InShape {
+                path: ShapePath(vec![part]),
+                source: Box::new(err),
+            },
+        }
+    }
+}
+
+/// The path to a part of a shape
+///
+/// Made up of the names of the fields that lead to the part, starting from the
+/// shape that was processed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShapePath(pub(crate) Vec<String>);
+
+impl ShapePath {
+    /// Access the parts of the path
+    pub fn parts(&self) -> &[String] {
+        &self.0
+    }
+}
+
+impl fmt::Display for ShapePath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0.join("/"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error as _;
+
+    use fj_kernel::{
+        algorithms::{
+            BooleanChain, BooleanError, DifferenceError, ErrorBudget,
+            FilletError, IntersectionTolerances, RevolveError, SweepError,
+            Tolerance, TriangulationError,
+        },
+        objects::{Face, Sketch, Surface},
+        validation::{GeometricIssues, ValidationError},
+    };
+    use fj_math::Scalar;
+
+    use crate::shape_processor::ShapeProcessor;
+
+    use super::Error;
+
+    #[test]
+    fn source_chain() {
+        let budget = {
+            let mut error_budget = ErrorBudget::new();
+            error_budget.record("healing", 2.);
+
+            let tolerance =
+                Tolerance::from_scalar(1.).expect("Tolerance is positive");
+            error_budget
+                .remaining_tolerance(tolerance)
+                .expect_err("Error budget should be exceeded")
+        };
+        let extent =
+            Tolerance::from_scalar(0.).expect_err("Tolerance must not be zero");
+        let boolean = {
+            let face = |surface| {
+                Sketch::from_faces([Face::builder(surface)
+                    .with_exterior_polygon([[0., 0.], [1., 0.], [0., 1.]])
+                    .build()])
+            };
+            BooleanChain::new(face(Surface::xy_plane()))
+                .subtract_all([face(Surface::xz_plane())])
+                .apply(&IntersectionTolerances::default())
+                .expect_err("Surfaces are different")
+        };
+
+        let errors = [
+            Error::from(ValidationError::Geometric(GeometricIssues::default())),
+            Error::from(TriangulationError::from(budget)),
+            Error::from(extent),
+            Error::from(boolean),
+            Error::from(SweepError::EmptyProfile),
+            Error::from(RevolveError::ZeroAngle),
+            Error::from(DifferenceError::CoplanarFaces),
+            Error::from(FilletError::InvalidRadius(Scalar::ZERO)),
+        ];
+
+        for err in errors {
+            let code = err.error_code();
+
+            // Errors in parts of a shape keep their code and source.
+            let err = err.in_shape("b").in_shape("a");
+            assert_eq!(err.error_code(), code);
+            assert_eq!(err.path().map(ToString::to_string), Some("a/b".into()));
+
+            // The original error is boxed, and so is its source.
+            let source = err
+                .source()
+                .and_then(|source| source.downcast_ref::<Box<Error>>())
+                .expect("Expected source");
+            let kernel = source.source().expect("Expected kernel error");
+
+            let is_kernel_error = match code {
+                "validation" => kernel.is::<ValidationError>(),
+                "triangulation" => kernel.is::<TriangulationError>(),
+                "extent" => {
+                    kernel.is::<fj_kernel::algorithms::InvalidTolerance>()
+                }
+                "boolean" => kernel.is::<BooleanError>(),
+                "sweep" => kernel.is::<SweepError>(),
+                "revolve" => kernel.is::<RevolveError>(),
+                "difference" => kernel.is::<DifferenceError>(),
+                "fillet" => kernel.is::<FilletError>(),
+                code => panic!("Unexpected error code: {code}"),
+            };
+            assert!(is_kernel_error);
+        }
+    }
+
+    #[test]
+    fn path_in_group() {
+        // Two of the points are too close together, which fails validation.
+        let invalid: fj::Shape = fj::Sketch::from_points(vec![
+            [0., 0.],
+            [1e-9, 0.],
+            [1., 0.],
+            [0., 1.],
+        ])
+        .into();
+        let valid: fj::Shape =
+            fj::Sketch::from_points(vec![[0., 0.], [1., 0.], [0., 1.]]).into();
+
+        let inner: fj::Shape = fj::Group {
+            a: valid.clone(),
+            b: invalid,
+        }
+        .into();
+        let outer: fj::Shape = fj::Group { a: inner, b: valid }.into();
+
+        let err = ShapeProcessor::default()
+            .process(&outer)
+            .err()
+            .expect("Shape is invalid");
+
+        assert_eq!(err.error_code(), "validation");
+        assert!(err.to_string().contains("a/b"));
+    }
+}
@@ -0,0 +1,104 @@
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::{fillet, EdgeSelector, ErrorBudget, Tolerance},
+    objects::Solid,
+    validation::{validate_intermediate, Validated, ValidationConfig},
+};
+use fj_math::{Aabb, Point};
+
+use crate::{Error, Shape};
+
+impl Shape for fj::Fillet {
+    type Brep = Solid;
+
+    fn compute_brep(
+        &self,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        error_budget: &mut ErrorBudget,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Validated<Self::Brep>, Error> {
+        let solid = Solid::from_faces(
+            self.shape
+                .compute_brep(config, tolerance, error_budget, debug_info)
+                .map_err(|err| err.in_shape("shape"))?
+                .into_inner(),
+        );
+
+        let edges = match &self.edges {
+            fj::FilletEdges::All => EdgeSelector::All,
+            fj::FilletEdges::Within { min, max } => {
+                let aabb = Aabb {
+                    min: Point::from(*min),
+                    max: Point::from(*max),
+                };
+                EdgeSelector::matching(&solid, |points| {
+                    points.iter().all(|&point| aabb.contains(point))
+                })
+            }
+        };
+
+        // The corners, where fillets meet, are approximated within
+        // `tolerance`, just like the triangulation would approximate them. So
+        // there's nothing to record in the error budget here.
+        let solid = fillet(solid, &edges, self.radius, tolerance)?;
+
+        Ok(validate_intermediate(solid, config)?)
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        // Rounding edges only ever removes material.
+        self.shape.bounding_volume()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use fj::syntax::Fillet as _;
+
+    use crate::{shape_processor::ShapeProcessor, test_helpers::cube, Error};
+
+    #[test]
+    fn rounded_cube() -> Result<(), Error> {
+        let r = 0.25;
+        let shape: fj::Shape = cube(1.).fillet(r).into();
+        let processed = ShapeProcessor::default().process(&shape)?;
+
+        let inner = 1. - 2. * r;
+        let expected = inner * inner * inner
+            + 6. * inner * inner * r
+            + 12. * inner * PI * r * r / 4.
+            + 4. / 3. * PI * r * r * r;
+        let volume = processed.summary.volume.into_f64();
+        assert!((volume - expected).abs() < expected * 0.02);
+
+        Ok(())
+    }
+
+    #[test]
+    fn single_edge() -> Result<(), Error> {
+        let r = 0.25;
+        let shape: fj::Shape =
+            cube(1.).fillet(r).within([1., 0., 1.], [1., 1., 1.]).into();
+        let processed = ShapeProcessor::default().process(&shape)?;
+
+        let expected = 1. - (1. - PI / 4.) * r * r;
+        let volume = processed.summary.volume.into_f64();
+        assert!((volume - expected).abs() < expected * 0.01);
+
+        Ok(())
+    }
+
+    #[test]
+    fn radius_too_large() {
+        let shape: fj::Shape = cube(1.).fillet(0.5).into();
+        let err = ShapeProcessor::default()
+            .process(&shape)
+            .err()
+            .expect("Radius is too large");
+
+        assert_eq!(err.error_code(), "fillet");
+    }
+}
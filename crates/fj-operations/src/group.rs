@@ -1,12 +1,12 @@
 use fj_interop::debug::DebugInfo;
 use fj_kernel::{
-    algorithms::Tolerance,
+    algorithms::{ErrorBudget, Tolerance},
     objects::Face,
-    validation::{validate, Validated, ValidationConfig, ValidationError},
+    validation::{validate_intermediate, Validated, ValidationConfig},
 };
 use fj_math::Aabb;
 
-use super::Shape;
+use crate::{rebuild::BrepCache, Error, Shape};
 
 impl Shape for fj::Group {
     type Brep = Vec<Face>;
@@ -15,23 +15,184 @@ impl Shape for fj::Group {
         &self,
         config: &ValidationConfig,
         tolerance: Tolerance,
+        error_budget: &mut ErrorBudget,
         debug_info: &mut DebugInfo,
-    ) -> Result<Validated<Self::Brep>, ValidationError> {
-        let mut faces = Vec::new();
+    ) -> Result<Validated<Self::Brep>, Error> {
+        let a = self
+            .a
+            .compute_brep(config, tolerance, error_budget, debug_info)
+            .map_err(|err| err.in_shape("a"))?;
+        let b = self
+            .b
+            .compute_brep(config, tolerance, error_budget, debug_info)
+            .map_err(|err| err.in_shape("b"))?;
 
-        let a = self.a.compute_brep(config, tolerance, debug_info)?;
-        let b = self.b.compute_brep(config, tolerance, debug_info)?;
-
-        faces.extend(a.into_inner());
-        faces.extend(b.into_inner());
-
-        validate(faces, config)
+        merge_group([a, b], config)
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
         let a = self.a.bounding_volume();
         let b = self.b.bounding_volume();
 
+        // Empty shapes, like suppressed parts, don't have a position. Merging
+        // them would extend the bounding volume to an arbitrary point.
+        if a.min == a.max {
+            return b;
+        }
+        if b.min == b.max {
+            return a;
+        }
+
         a.merged(&b)
     }
 }
+
+/// Merge the boundary representations of the shapes of a group
+///
+/// The faces are sorted (see [the ordering of faces](Face#ordering)), so the
+/// order of the shapes within the group doesn't matter.
+pub(crate) fn merge_group(
+    shapes: [Validated<Vec<Face>>; 2],
+    config: &ValidationConfig,
+) -> Result<Validated<Vec<Face>>, Error> {
+    let mut faces: Vec<_> = shapes
+        .into_iter()
+        .flat_map(|shape| shape.into_inner())
+        .collect();
+    faces.sort();
+
+    Ok(validate_intermediate(faces, config)?)
+}
+
+/// Compute the boundary representations of the parts of a group separately
+///
+/// Nested groups are flattened, so none of the returned parts is a group. The
+/// parts are not validated as a whole; that is left to the caller.
+///
+/// Like in [`merge_group`], the faces of each part, and the parts themselves,
+/// are sorted. Groups of the same shapes result in the same parts, regardless
+/// of how the shapes are arranged within them.
+///
+/// If a cache is passed, parts that haven't changed since the previous
+/// evaluation are taken from it.
+pub fn compute_parts(
+    group: &fj::Group,
+    config: &ValidationConfig,
+    tolerance: Tolerance,
+    error_budget: &mut ErrorBudget,
+    debug_info: &mut DebugInfo,
+    mut cache: Option<&mut BrepCache>,
+) -> Result<Vec<Vec<Face>>, Error> {
+    let mut parts = Vec::new();
+
+    for (name, shape) in [("a", &group.a), ("b", &group.b)] {
+        if let Some(cache) = cache.as_deref_mut() {
+            cache.enter(name);
+        }
+
+        let result = match shape {
+            fj::Shape::Group(group) => compute_parts(
+                group,
+                config,
+                tolerance,
+                error_budget,
+                debug_info,
+                cache.as_deref_mut(),
+            ),
+            shape => {
+                let faces = match cache.as_deref_mut() {
+                    Some(cache) => cache.compute_node(
+                        shape,
+                        config,
+                        tolerance,
+                        error_budget,
+                        debug_info,
+                    ),
+                    None => shape.compute_brep(
+                        config,
+                        tolerance,
+                        error_budget,
+                        debug_info,
+                    ),
+                };
+
+                // Suppressed parts don't contribute any faces.
+                faces.map(|faces| {
+                    let mut faces = faces.into_inner();
+                    faces.sort();
+                    if faces.is_empty() {
+                        Vec::new()
+                    } else {
+                        vec![faces]
+                    }
+                })
+            }
+        };
+
+        if let Some(cache) = cache.as_deref_mut() {
+            cache.leave();
+        }
+
+        parts.extend(result.map_err(|err| err.in_shape(name))?);
+    }
+
+    parts.sort();
+
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj::syntax::{Group as _, Sketch as _, Sweep as _, Transform as _};
+    use fj_interop::debug::DebugInfo;
+    use fj_kernel::{
+        algorithms::{ErrorBudget, Tolerance},
+        validation::ValidationConfig,
+    };
+
+    use crate::{shape_processor::ShapeProcessor, Error, Shape as _};
+
+    fn cube(color: [u8; 4], x: f64) -> fj::Transform {
+        [[0., 0.], [1., 0.], [1., 1.], [0., 1.]]
+            .sketch()
+            .with_color(color)
+            .sweep([0., 0., 1.])
+            .translate([x, 0., 0.])
+    }
+
+    #[test]
+    fn order_of_shapes() -> Result<(), Error> {
+        let a = cube([255, 0, 0, 255], 0.);
+        let b = cube([0, 0, 255, 255], 2.);
+
+        let ab = a.group(&b);
+        let ba = b.group(&a);
+
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        let [faces_ab, faces_ba] = [&ab, &ba].map(|group| {
+            group
+                .compute_brep(
+                    &ValidationConfig::default(),
+                    tolerance,
+                    &mut ErrorBudget::new(),
+                    &mut DebugInfo::new(),
+                )
+                .map(|faces| faces.into_inner())
+        });
+        assert_eq!(faces_ab?, faces_ba?);
+
+        let processor = ShapeProcessor::default();
+        let ab = processor.process(&ab.into())?;
+        let ba = processor.process(&ba.into())?;
+
+        assert!(ab.mesh.vertices().eq(ba.mesh.vertices()));
+        assert!(ab.mesh.indices().eq(ba.mesh.indices()));
+        assert!(ab
+            .mesh
+            .triangles()
+            .map(|triangle| triangle.color)
+            .eq(ba.mesh.triangles().map(|triangle| triangle.color)));
+
+        Ok(())
+    }
+}
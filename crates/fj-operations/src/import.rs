@@ -0,0 +1,201 @@
+use std::fs::File;
+
+use fj_export::{import_stl_face, stl_aabb, ImportError};
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::{ErrorBudget, Tolerance},
+    objects::Face,
+    validation::{validate_intermediate, Validated, ValidationConfig},
+};
+use fj_math::{Aabb, Point};
+
+use crate::{Error, Shape};
+
+impl Shape for fj::ImportedMesh {
+    type Brep = Vec<Face>;
+
+    fn compute_brep(
+        &self,
+        config: &ValidationConfig,
+        _: Tolerance,
+        _: &mut ErrorBudget,
+        _: &mut DebugInfo,
+    ) -> Result<Validated<Self::Brep>, Error> {
+        let file = File::open(self.path()).map_err(ImportError::from)?;
+        let face = import_stl_face(file)?;
+
+        Ok(validate_intermediate(vec![face], config)?)
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        // The file is streamed, so only its bounding box is kept in memory.
+        // If it can't be read, computing the shape fails later anyway.
+        let aabb = File::open(self.path())
+            .map_err(ImportError::from)
+            .and_then(stl_aabb)
+            .ok()
+            .flatten();
+
+        aabb.unwrap_or(Aabb {
+            min: Point::origin(),
+            max: Point::origin(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use fj::syntax::{Annotate as _, Fillet as _, Shell as _};
+    use fj_export::ImportError;
+    use fj_interop::processed_shape::ProcessedShape;
+    use fj_math::{Aabb, Point};
+
+    use crate::{shape_processor::ShapeProcessor, Error, Shape as _};
+
+    #[test]
+    fn ascii_stl() -> Result<(), Error> {
+        let path = env::temp_dir()
+            .join(format!("fj-operations-import-{}.stl", process::id()));
+        fs::write(&path, TETRAHEDRON).expect("Failed to write test file");
+
+        let shape = fj::ImportedMesh::from_path(path.to_string_lossy());
+        let aabb = shape.bounding_volume();
+        let result = ShapeProcessor::default().process(&shape.into());
+        fs::remove_file(&path).expect("Failed to remove test file");
+
+        assert_eq!(
+            aabb,
+            Aabb {
+                min: Point::origin(),
+                max: Point::from([1., 1., 1.]),
+            }
+        );
+
+        let processed = result?;
+        assert_eq!(processed.mesh.triangles().count(), 4);
+        assert_eq!(processed.aabb.max, Point::from([1., 1., 1.]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn binary_stl_with_nan() {
+        let mut file = vec![0; 80];
+        file.extend(1u32.to_le_bytes());
+
+        // The normal, three vertices, and the attribute byte count.
+        let coords = [0., 0., 1., 0., 0., 0., f32::NAN, 0., 0., 0., 1., 0.];
+        for coord in coords {
+            file.extend(f32::to_le_bytes(coord));
+        }
+        file.extend([0, 0]);
+
+        let path = env::temp_dir()
+            .join(format!("fj-operations-import-nan-{}.stl", process::id()));
+        fs::write(&path, file).expect("Failed to write test file");
+
+        let shape = fj::ImportedMesh::from_path(path.to_string_lossy());
+        let result = ShapeProcessor::default().process(&shape.into());
+        fs::remove_file(&path).expect("Failed to remove test file");
+
+        assert!(matches!(
+            result,
+            Err(Error::Import(ImportError::Malformed { line: 1, .. }))
+        ));
+    }
+
+    #[test]
+    fn missing_file() {
+        let shape = fj::ImportedMesh::from_path("does/not/exist.stl");
+
+        let result = ShapeProcessor::default().process(&shape.into());
+        assert!(matches!(result, Err(Error::Import(_))));
+    }
+
+    #[test]
+    fn annotated() {
+        let edge_ref = fj::EdgeRef {
+            face: 0,
+            cycle: 0,
+            edge: 0,
+        };
+        let result = process_tetrahedron("annotated", |mesh| {
+            mesh.annotate(vec![fj::Annotation::RadiusDimension { edge_ref }])
+                .into()
+        });
+
+        let err = result.err().expect("Mesh has no edges to annotate");
+        assert_eq!(err.error_code(), "annotation");
+    }
+
+    #[test]
+    fn shell() {
+        let result = process_tetrahedron("shell", |mesh| {
+            mesh.shell(0.1, fj::FaceSelector::normal([0., 0., -1.]))
+                .into()
+        });
+
+        let err = result.err().expect("Mesh can't be hollowed out");
+        assert_eq!(err.error_code(), "shell");
+    }
+
+    #[test]
+    fn fillet() {
+        let result =
+            process_tetrahedron("fillet", |mesh| mesh.fillet(0.1).into());
+
+        let err = result.err().expect("Mesh has no edges to fillet");
+        assert_eq!(err.error_code(), "fillet");
+    }
+
+    /// Process a shape that is built from the imported [`TETRAHEDRON`]
+    fn process_tetrahedron(
+        name: &str,
+        shape: impl FnOnce(fj::ImportedMesh) -> fj::Shape,
+    ) -> Result<ProcessedShape, Error> {
+        let path = env::temp_dir()
+            .join(format!("fj-operations-import-{name}-{}.stl", process::id()));
+        fs::write(&path, TETRAHEDRON).expect("Failed to write test file");
+
+        let shape = shape(fj::ImportedMesh::from_path(path.to_string_lossy()));
+        let result = ShapeProcessor::default().process(&shape);
+        fs::remove_file(&path).expect("Failed to remove test file");
+
+        result
+    }
+
+    const TETRAHEDRON: &str = "\
+        solid tetrahedron
+          facet normal 0 0 -1
+            outer loop
+              vertex 0 0 0
+              vertex 0 1 0
+              vertex 1 0 0
+            endloop
+          endfacet
+          facet normal 0 -1 0
+            outer loop
+              vertex 0 0 0
+              vertex 1 0 0
+              vertex 0 0 1
+            endloop
+          endfacet
+          facet normal -1 0 0
+            outer loop
+              vertex 0 0 0
+              vertex 0 0 1
+              vertex 0 1 0
+            endloop
+          endfacet
+          facet normal 1 1 1
+            outer loop
+              vertex 1 0 0
+              vertex 0 1 0
+              vertex 0 0 1
+            endloop
+          endfacet
+        endsolid tetrahedron
+    ";
+}
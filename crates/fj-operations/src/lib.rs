@@ -16,34 +16,65 @@
 
 #![warn(missing_docs)]
 
+pub mod rebuild;
+pub mod repro;
 pub mod shape_processor;
 
+mod annotation;
+mod assembly;
+mod difference;
 mod difference_2d;
+mod error;
+mod fillet;
 mod group;
+mod import;
+mod material;
+mod mirror;
+mod part;
+mod revolve;
+mod section;
+mod shell;
 mod sketch;
 mod sweep;
+#[cfg(test)]
+mod test_helpers;
+mod tolerance;
+#[cfg(feature = "tracing")]
+mod trace;
 mod transform;
 
 use fj_interop::debug::DebugInfo;
 use fj_kernel::{
-    algorithms::Tolerance,
+    algorithms::{ErrorBudget, Tolerance},
     objects::{Face, Sketch},
-    validation::{validate, Validated, ValidationConfig, ValidationError},
+    validation::{validate_intermediate, Validated, ValidationConfig},
 };
 use fj_math::Aabb;
 
+pub use self::{
+    annotation::AnnotationError,
+    error::{Error, ShapePath},
+    material::MaterialError,
+    mirror::MirrorError,
+    section::SectionError,
+};
+
 /// Implemented for all operations from the [`fj`] crate
 pub trait Shape {
     /// The type that is used for the shape's boundary representation
     type Brep;
 
     /// Compute the boundary representation of the shape
+    ///
+    /// Operations that introduce positional error into the shape must record
+    /// their worst-case error in `error_budget`.
     fn compute_brep(
         &self,
         config: &ValidationConfig,
         tolerance: Tolerance,
+        error_budget: &mut ErrorBudget,
         debug_info: &mut DebugInfo,
-    ) -> Result<Validated<Self::Brep>, ValidationError>;
+    ) -> Result<Validated<Self::Brep>, Error>;
 
     /// Access the axis-aligned bounding box of a shape
     ///
@@ -59,41 +90,126 @@ impl Shape for fj::Shape {
         &self,
         config: &ValidationConfig,
         tolerance: Tolerance,
+        error_budget: &mut ErrorBudget,
         debug_info: &mut DebugInfo,
-    ) -> Result<Validated<Self::Brep>, ValidationError> {
-        match self {
-            Self::Shape2d(shape) => validate(
+    ) -> Result<Validated<Self::Brep>, Error> {
+        let span = fj_interop::span!(
+            INFO,
+            "shape",
+            kind = trace::kind(self),
+            label = ?trace::label(self),
+            faces = tracing::field::Empty,
+        );
+
+        let faces = match self {
+            Self::Annotated(shape) => {
+                shape.compute_brep(config, tolerance, error_budget, debug_info)
+            }
+            Self::Assembly(shape) => {
+                shape.compute_brep(config, tolerance, error_budget, debug_info)
+            }
+            Self::ImportedMesh(shape) => {
+                shape.compute_brep(config, tolerance, error_budget, debug_info)
+            }
+            Self::Materialized(shape) => {
+                shape.compute_brep(config, tolerance, error_budget, debug_info)
+            }
+            Self::Mirror(shape) => {
+                shape.compute_brep(config, tolerance, error_budget, debug_info)
+            }
+            Self::Part(shape) => {
+                shape.compute_brep(config, tolerance, error_budget, debug_info)
+            }
+            Self::Sectioned(shape) => {
+                shape.compute_brep(config, tolerance, error_budget, debug_info)
+            }
+            Self::Shape2d(shape) => Ok(validate_intermediate(
+                shape
+                    .compute_brep(config, tolerance, error_budget, debug_info)?
+                    .into_inner()
+                    .into_faces()
+                    .into_iter()
+                    .collect(),
+                config,
+            )?),
+            Self::Difference(shape) => Ok(validate_intermediate(
+                shape
+                    .compute_brep(config, tolerance, error_budget, debug_info)?
+                    .into_inner()
+                    .into_faces()
+                    .into_iter()
+                    .collect(),
+                config,
+            )?),
+            Self::Fillet(shape) => Ok(validate_intermediate(
                 shape
-                    .compute_brep(config, tolerance, debug_info)?
+                    .compute_brep(config, tolerance, error_budget, debug_info)?
                     .into_inner()
                     .into_faces()
                     .into_iter()
                     .collect(),
                 config,
-            ),
+            )?),
             Self::Group(shape) => {
-                shape.compute_brep(config, tolerance, debug_info)
+                shape.compute_brep(config, tolerance, error_budget, debug_info)
             }
-            Self::Sweep(shape) => validate(
+            Self::Revolve(shape) => Ok(validate_intermediate(
                 shape
-                    .compute_brep(config, tolerance, debug_info)?
+                    .compute_brep(config, tolerance, error_budget, debug_info)?
                     .into_inner()
                     .into_faces()
                     .into_iter()
                     .collect(),
                 config,
-            ),
+            )?),
+            Self::Shell(shape) => Ok(validate_intermediate(
+                shape
+                    .compute_brep(config, tolerance, error_budget, debug_info)?
+                    .into_inner()
+                    .into_faces()
+                    .into_iter()
+                    .collect(),
+                config,
+            )?),
+            Self::Sweep(shape) => Ok(validate_intermediate(
+                shape
+                    .compute_brep(config, tolerance, error_budget, debug_info)?
+                    .into_inner()
+                    .into_faces()
+                    .into_iter()
+                    .collect(),
+                config,
+            )?),
+            Self::Tolerance(shape) => {
+                shape.compute_brep(config, tolerance, error_budget, debug_info)
+            }
             Self::Transform(shape) => {
-                shape.compute_brep(config, tolerance, debug_info)
+                shape.compute_brep(config, tolerance, error_budget, debug_info)
             }
-        }
+        }?;
+
+        fj_interop::record!(span, "faces", faces.len());
+
+        Ok(faces)
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
         match self {
+            Self::Annotated(shape) => shape.bounding_volume(),
+            Self::Assembly(shape) => shape.bounding_volume(),
+            Self::ImportedMesh(shape) => shape.bounding_volume(),
+            Self::Materialized(shape) => shape.bounding_volume(),
+            Self::Mirror(shape) => shape.bounding_volume(),
+            Self::Part(shape) => shape.bounding_volume(),
+            Self::Sectioned(shape) => shape.bounding_volume(),
             Self::Shape2d(shape) => shape.bounding_volume(),
+            Self::Difference(shape) => shape.bounding_volume(),
+            Self::Fillet(shape) => shape.bounding_volume(),
             Self::Group(shape) => shape.bounding_volume(),
+            Self::Revolve(shape) => shape.bounding_volume(),
+            Self::Shell(shape) => shape.bounding_volume(),
             Self::Sweep(shape) => shape.bounding_volume(),
+            Self::Tolerance(shape) => shape.bounding_volume(),
             Self::Transform(shape) => shape.bounding_volume(),
         }
     }
@@ -106,14 +222,18 @@ impl Shape for fj::Shape2d {
         &self,
         config: &ValidationConfig,
         tolerance: Tolerance,
+        error_budget: &mut ErrorBudget,
         debug_info: &mut DebugInfo,
-    ) -> Result<Validated<Self::Brep>, ValidationError> {
+    ) -> Result<Validated<Self::Brep>, Error> {
         match self {
             Self::Difference(shape) => {
-                shape.compute_brep(config, tolerance, debug_info)
+                shape.compute_brep(config, tolerance, error_budget, debug_info)
+            }
+            Self::DifferenceAll(shape) => {
+                shape.compute_brep(config, tolerance, error_budget, debug_info)
             }
             Self::Sketch(shape) => {
-                shape.compute_brep(config, tolerance, debug_info)
+                shape.compute_brep(config, tolerance, error_budget, debug_info)
             }
         }
     }
@@ -121,6 +241,7 @@ impl Shape for fj::Shape2d {
     fn bounding_volume(&self) -> Aabb<3> {
         match self {
             Self::Difference(shape) => shape.bounding_volume(),
+            Self::DifferenceAll(shape) => shape.bounding_volume(),
             Self::Sketch(shape) => shape.bounding_volume(),
         }
     }
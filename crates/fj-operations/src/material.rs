@@ -0,0 +1,209 @@
+use fj_interop::{
+    debug::DebugInfo,
+    material::{Material, Materials},
+    mesh::Mesh,
+};
+use fj_kernel::{
+    algorithms::{ErrorBudget, FaceSelector, Tolerance},
+    objects::Face,
+    validation::{Validated, ValidationConfig},
+};
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use crate::{Error, Shape};
+
+impl Shape for fj::Materialized {
+    type Brep = Vec<Face>;
+
+    fn compute_brep(
+        &self,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        error_budget: &mut ErrorBudget,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Validated<Self::Brep>, Error> {
+        // Materials don't affect the geometry. They are assigned separately,
+        // once the faces are known.
+        self.shape()
+            .compute_brep(config, tolerance, error_budget, debug_info)
+            .map_err(|err| err.in_shape("shape"))
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        self.shape().bounding_volume()
+    }
+}
+
+/// Assign the materials of a shape to its faces
+pub(crate) fn resolve_materials(
+    materialized: &fj::Materialized,
+    faces: &[Face],
+) -> Result<Materials, Error> {
+    let mut materials = Materials::default();
+
+    for material in materialized.materials() {
+        if materials.find(&material.name).is_some() {
+            return Err(MaterialError::DuplicateMaterial(
+                material.name.clone(),
+            )
+            .into());
+        }
+
+        materials.push(Material {
+            name: material.name.clone(),
+            display_color: material.display_color,
+            metadata: material.metadata.clone(),
+        });
+    }
+
+    for (i, assignment) in materialized.assignments().iter().enumerate() {
+        let material =
+            materials.find(&assignment.material).ok_or_else(|| {
+                Error::from(MaterialError::UnknownMaterial(
+                    assignment.material.clone(),
+                ))
+                .in_shape(format!("assignments[{i}]"))
+            })?;
+
+        let selector = face_selector(&assignment.selector);
+        for (index, face) in faces.iter().enumerate() {
+            if selector.selects(index, face) {
+                materials.assign(index, material);
+            }
+        }
+    }
+
+    Ok(materials)
+}
+
+/// Display the triangles of assigned faces in the color of their material
+///
+/// Faces that use the default material keep their own color.
+pub(crate) fn apply_display_colors(
+    mesh: Mesh<Point<3>>,
+    materials: &Materials,
+) -> Mesh<Point<3>> {
+    if !materials.has_assignments() {
+        return mesh;
+    }
+
+    let mut colored = Mesh::new();
+    colored.set_double_sided(mesh.is_double_sided());
+    for (face, tolerance) in mesh.degraded_faces() {
+        colored.mark_degraded(face, tolerance);
+    }
+
+    for (index, triangle) in mesh.triangles().enumerate() {
+        let color = match materials.material_of(&triangle) {
+            0 => triangle.color,
+            material => materials.materials()[material].display_color,
+        };
+
+        match (triangle.face, mesh.surface_coords(index)) {
+            (Some(face), Some(surface_coords)) => colored
+                .push_face_triangle_with_normal(
+                    triangle.points,
+                    color,
+                    face,
+                    surface_coords,
+                    triangle.normal,
+                ),
+            (Some(face), None) => {
+                colored.push_face_triangle(triangle.points, color, face)
+            }
+            (None, _) => colored.push_triangle(triangle.points, color),
+        }
+    }
+
+    colored
+}
+
+pub(crate) fn face_selector(selector: &fj::FaceSelector) -> FaceSelector {
+    match selector {
+        fj::FaceSelector::All => FaceSelector::All,
+        fj::FaceSelector::Indices(indices) => {
+            FaceSelector::Indices(indices.clone())
+        }
+        fj::FaceSelector::Normal {
+            direction,
+            max_angle,
+        } => FaceSelector::Normal {
+            direction: Vector::from(*direction),
+            max_angle: Scalar::from_f64(*max_angle),
+        },
+    }
+}
+
+/// An error that can occur while assigning materials
+#[derive(Debug, thiserror::Error)]
+pub enum MaterialError {
+    /// Multiple materials in the registry have the same name
+    #[error("Material `{0}` is defined more than once")]
+    DuplicateMaterial(String),
+
+    /// A material is assigned that is not in the registry
+    #[error("Material `{0}` is not defined")]
+    UnknownMaterial(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use fj::syntax::Materialize as _;
+
+    use crate::{shape_processor::ShapeProcessor, test_helpers::cube, Error};
+
+    fn materialized_cube() -> fj::Materialized {
+        cube(1.).with_materials(vec![
+            fj::Material::new("PLA-black", [0, 0, 0, 255]),
+            fj::Material::new("TPU-clear", [255, 255, 255, 128]),
+        ])
+    }
+
+    #[test]
+    fn assign_materials() -> Result<(), Error> {
+        let shape = materialized_cube()
+            .with_material(fj::FaceSelector::All, "PLA-black")
+            .with_material(fj::FaceSelector::normal([0., 0., 1.]), "TPU-clear")
+            .into();
+
+        let shape = ShapeProcessor::default().process(&shape)?;
+        let materials = &shape.materials;
+
+        let mut black = 0;
+        let mut clear = 0;
+        for triangle in shape.mesh.triangles() {
+            let material =
+                &materials.materials()[materials.material_of(&triangle)];
+            match material.name.as_str() {
+                "PLA-black" => black += 1,
+                "TPU-clear" => clear += 1,
+                name => panic!("Unexpected material: {name}"),
+            }
+
+            assert_eq!(triangle.color, material.display_color);
+        }
+
+        // The top face of the cube is made up of two triangles.
+        assert_eq!(clear, 2);
+        assert_eq!(black, 10);
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_material() {
+        let shape = materialized_cube()
+            .with_material(fj::FaceSelector::All, "PETG-orange")
+            .into();
+
+        let err = ShapeProcessor::default()
+            .process(&shape)
+            .err()
+            .expect("Material is not defined");
+        assert_eq!(err.error_code(), "material");
+        assert_eq!(
+            err.path().map(ToString::to_string),
+            Some("assignments[0]".into())
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::{transform_faces, ErrorBudget, Tolerance},
+    objects::Face,
+    validation::{validate_intermediate, Validated, ValidationConfig},
+};
+use fj_math::{Aabb, Scalar, Transform, Vector};
+
+use crate::{Error, Shape};
+
+impl Shape for fj::Mirror {
+    type Brep = Vec<Face>;
+
+    fn compute_brep(
+        &self,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        error_budget: &mut ErrorBudget,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Validated<Self::Brep>, Error> {
+        let reflection =
+            make_reflection(self).ok_or(MirrorError::ZeroNormal)?;
+
+        let mut faces = self
+            .shape
+            .compute_brep(config, tolerance, error_budget, debug_info)
+            .map_err(|err| err.in_shape("shape"))?
+            .into_inner();
+
+        // The kernel reverses the cycles of faces that are transformed by a
+        // reflection, so they keep pointing outward. Like rigid transforms,
+        // reflections don't introduce any error.
+        transform_faces(&mut faces, &reflection);
+
+        Ok(validate_intermediate(faces, config)?)
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        let aabb = self.shape.bounding_volume();
+
+        // A shape that can't be reflected fails to compute. Until then, its
+        // bounding volume is as good a guess as any.
+        let reflection = match make_reflection(self) {
+            Some(reflection) => reflection,
+            None => return aabb,
+        };
+
+        // Unless the plane is axis-aligned, the reflected box is no longer
+        // aligned to the axes. All of its corners are required to bound it.
+        Aabb::<3>::from_points(
+            aabb.vertices()
+                .map(|vertex| reflection.transform_point(&vertex)),
+        )
+    }
+}
+
+fn make_reflection(mirror: &fj::Mirror) -> Option<Transform> {
+    let normal = Vector::from(mirror.plane_normal);
+    if normal.magnitude() == Scalar::ZERO {
+        return None;
+    }
+
+    Some(Transform::reflection(mirror.plane_origin, normal))
+}
+
+/// Error reflecting a shape
+#[derive(Debug, thiserror::Error)]
+pub enum MirrorError {
+    /// The normal of the mirror plane is zero
+    #[error("Mirror plane has a zero normal")]
+    ZeroNormal,
+}
+
+#[cfg(test)]
+mod tests {
+    use fj::syntax::Mirror as _;
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        shape_processor::ShapeProcessor, test_helpers::cube_at, Error,
+        Shape as _,
+    };
+
+    #[test]
+    fn mirror_cube() -> Result<(), Error> {
+        let mirror = cube_at([2., 0.], 1.).mirror([0., 0., 0.], [1., 0., 0.]);
+
+        let aabb = mirror.bounding_volume();
+        assert_eq!(aabb.min, Point::from([-3., 0., 0.]));
+        assert_eq!(aabb.max, Point::from([-2., 1., 1.]));
+
+        let processed = ShapeProcessor::default().process(&mirror.into())?;
+
+        // A solid that is inside-out has a negative volume.
+        let volume = processed.summary.volume;
+        assert!((volume - Scalar::ONE).abs() < Scalar::from_f64(1e-9));
+
+        for triangle in processed.mesh.triangles() {
+            for point in triangle.points {
+                assert!(point.x <= Scalar::from_f64(-2.));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn zero_normal() {
+        let mirror = cube_at([2., 0.], 1.).mirror([0., 0., 0.], [0., 0., 0.]);
+
+        let err = ShapeProcessor::default()
+            .process(&mirror.into())
+            .err()
+            .expect("Normal is zero");
+        assert_eq!(err.error_code(), "mirror");
+    }
+}
@@ -0,0 +1,216 @@
+use fj_interop::{
+    debug::DebugInfo,
+    part::{PartInfo, PartState},
+};
+use fj_kernel::{
+    algorithms::{ErrorBudget, Tolerance},
+    objects::Face,
+    validation::{validate_intermediate, Validated, ValidationConfig},
+};
+use fj_math::{Aabb, Point};
+
+use crate::{Error, Shape};
+
+impl Shape for fj::Part {
+    type Brep = Vec<Face>;
+
+    fn compute_brep(
+        &self,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        error_budget: &mut ErrorBudget,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Validated<Self::Brep>, Error> {
+        // Suppressed parts are skipped without evaluating their shape. Display-
+        // only parts are geometry like any other. They are only left out, when
+        // computing the physical properties.
+        match self.state() {
+            fj::PartState::Suppressed => {
+                Ok(validate_intermediate(Vec::new(), config)?)
+            }
+            fj::PartState::Active | fj::PartState::DisplayOnly => self
+                .shape()
+                .compute_brep(config, tolerance, error_budget, debug_info)
+                .map_err(|err| err.in_shape("shape")),
+        }
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        match self.state() {
+            fj::PartState::Suppressed => Aabb {
+                min: Point::origin(),
+                max: Point::origin(),
+            },
+            fj::PartState::Active | fj::PartState::DisplayOnly => {
+                self.shape().bounding_volume()
+            }
+        }
+    }
+}
+
+/// List all parts of a shape, including suppressed ones
+pub(crate) fn collect_parts(shape: &fj::Shape) -> Vec<PartInfo> {
+    let mut parts = Vec::new();
+    collect_parts_inner(shape, &mut Vec::new(), &mut parts);
+    parts
+}
+
+fn collect_parts_inner(
+    shape: &fj::Shape,
+    path: &mut Vec<String>,
+    parts: &mut Vec<PartInfo>,
+) {
+    let children: Vec<(&str, &fj::Shape)> = match shape {
+        fj::Shape::Annotated(shape) => vec![("shape", shape.shape())],
+        fj::Shape::Assembly(assembly) => {
+            // The names of instances are built from their index, so they are
+            // visited here instead of being returned as children.
+            for (i, instance) in assembly.instances().iter().enumerate() {
+                path.push(format!("instances[{i}]"));
+                collect_parts_inner(&instance.shape, path, parts);
+                path.pop();
+            }
+
+            Vec::new()
+        }
+        fj::Shape::Difference(difference) => {
+            vec![("a", &difference.a), ("b", &difference.b)]
+        }
+        fj::Shape::Fillet(shape) => vec![("shape", &shape.shape)],
+        fj::Shape::Group(group) => vec![("a", &group.a), ("b", &group.b)],
+        fj::Shape::Materialized(shape) => vec![("shape", shape.shape())],
+        fj::Shape::Mirror(shape) => vec![("shape", &shape.shape)],
+        fj::Shape::Part(part) => {
+            parts.push(PartInfo {
+                path: path.clone(),
+                label: part.label().map(Into::into),
+                state: match part.state() {
+                    fj::PartState::Active => PartState::Active,
+                    fj::PartState::Suppressed => PartState::Suppressed,
+                    fj::PartState::DisplayOnly => PartState::DisplayOnly,
+                },
+            });
+
+            vec![("shape", part.shape())]
+        }
+        fj::Shape::Sectioned(shape) => vec![("shape", shape.shape())],
+        fj::Shape::Shell(shape) => vec![("shape", &shape.shape)],
+        fj::Shape::Tolerance(shape) => vec![("shape", &shape.shape)],
+        fj::Shape::Transform(shape) => vec![("shape", &shape.shape)],
+        fj::Shape::ImportedMesh(_)
+        | fj::Shape::Revolve(_)
+        | fj::Shape::Shape2d(_)
+        | fj::Shape::Sweep(_) => Vec::new(),
+    };
+
+    for (name, child) in children {
+        path.push(name.into());
+        collect_parts_inner(child, path, parts);
+        path.pop();
+    }
+}
+
+/// Create a copy of the shape that only contains physical geometry
+///
+/// Returns `None`, if the shape doesn't contain any display-only parts, and
+/// can be used as-is.
+pub(crate) fn physical_shape(shape: &fj::Shape) -> Option<fj::Shape> {
+    let mut has_display_only = false;
+    shape.for_each_part(&mut |part| {
+        has_display_only |= part.state() == fj::PartState::DisplayOnly;
+    });
+
+    if !has_display_only {
+        return None;
+    }
+
+    let mut physical = shape.clone();
+    physical.for_each_part_mut(&mut |part| {
+        if part.state() == fj::PartState::DisplayOnly {
+            part.set_state(fj::PartState::Suppressed);
+        }
+    });
+
+    Some(physical)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj::syntax::{Group as _, Label as _, Sketch as _, Sweep as _};
+    use fj_interop::part::PartState;
+    use fj_math::{Point, Scalar};
+
+    use crate::{shape_processor::ShapeProcessor, Error};
+
+    fn cube(label: &str, x: f64) -> fj::Part {
+        let cube = [[0., 0.], [1., 0.], [1., 1.], [0., 1.]]
+            .sketch()
+            .sweep([0., 0., 1.]);
+        let cube: fj::Shape = fj::Transform {
+            shape: cube.into(),
+            axis: [0., 0., 1.],
+            angle: fj::Angle::from_rad(0.),
+            offset: [x, 0., 0.],
+        }
+        .into();
+
+        cube.label(label)
+    }
+
+    fn cubes() -> fj::Shape {
+        cube("left", 0.)
+            .group(&cube("middle", 2.))
+            .group(&cube("right", 4.))
+            .into()
+    }
+
+    #[test]
+    fn suppressed_parts() -> Result<(), Error> {
+        let mut shape = cubes();
+        assert_eq!(shape.set_part_state("right", fj::PartState::Suppressed), 1);
+
+        let processed = ShapeProcessor::default().process(&shape)?;
+
+        // Each cube is made up of 12 triangles.
+        assert_eq!(processed.mesh.triangles().count(), 24);
+        assert_eq!(processed.aabb.max, Point::from([3., 1., 1.]));
+
+        let parts: Vec<_> = processed
+            .parts
+            .iter()
+            .map(|part| (part.label.as_deref(), part.state, part.path.len()))
+            .collect();
+        assert_eq!(
+            parts,
+            [
+                (Some("left"), PartState::Active, 2),
+                (Some("middle"), PartState::Active, 2),
+                (Some("right"), PartState::Suppressed, 1),
+            ]
+        );
+
+        // Suppression can be undone between evaluations.
+        shape.set_part_state("right", fj::PartState::Active);
+        let processed = ShapeProcessor::default().process(&shape)?;
+        assert_eq!(processed.mesh.triangles().count(), 36);
+
+        Ok(())
+    }
+
+    #[test]
+    fn display_only_parts() -> Result<(), Error> {
+        let mut shape = cubes();
+        shape.set_part_state("middle", fj::PartState::DisplayOnly);
+
+        let processed = ShapeProcessor::default().process(&shape)?;
+
+        // The reference geometry is displayed, but not measured.
+        assert_eq!(processed.mesh.triangles().count(), 36);
+        assert!(
+            (processed.summary.volume - Scalar::from_f64(2.)).abs()
+                < Scalar::from_f64(1e-9)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,362 @@
+//! Partial rebuilds of shapes that changed only in parts
+//!
+//! When a model is changed, most of its operation tree often stays the same.
+//! A [`BrepCache`] keeps the boundary representations of the nodes of the
+//! tree from the previous evaluation, so only the nodes that changed, and
+//! the nodes that contain them, need to be computed again.
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    mem,
+};
+
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::{ErrorBudget, ErrorContribution, Tolerance},
+    objects::Face,
+    validation::{Validated, ValidationConfig},
+};
+
+use crate::{
+    group::merge_group, transform::apply_transform, Error, Shape as _,
+    ShapePath,
+};
+
+/// Boundary representations of shapes, kept between evaluations
+///
+/// Each node of an [`fj::Shape`] is identified by its parameters, including
+/// those of all the shapes it contains. If a node is unchanged since the
+/// previous evaluation, its boundary representation is reused. Otherwise, it
+/// is computed anew. Transforms and groups are computed from the boundary
+/// representations of their shapes, which might come from the cache. All
+/// other operations compute their shapes themselves.
+///
+/// Only the nodes of the previous evaluation are kept, and no more than the
+/// capacity of the cache. Changing the tolerance or the validation config
+/// clears the cache.
+///
+/// Errors that the operations of a node record in the [`ErrorBudget`] are
+/// recorded again, when the node is reused. Debug info is not.
+#[derive(Debug)]
+pub struct BrepCache {
+    capacity: usize,
+    settings: Option<(Tolerance, ValidationConfig)>,
+    previous: HashMap<u64, CacheEntry>,
+    current: HashMap<u64, CacheEntry>,
+    path: Vec<String>,
+    computed: Vec<ShapePath>,
+}
+
+impl BrepCache {
+    /// The capacity of a cache that is created using [`Default`]
+    pub const DEFAULT_CAPACITY: usize = 1024;
+
+    /// Create a cache that holds at most `capacity` nodes
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            settings: None,
+            previous: HashMap::new(),
+            current: HashMap::new(),
+            path: Vec::new(),
+            computed: Vec::new(),
+        }
+    }
+
+    /// Compute the boundary representation of a shape
+    ///
+    /// Starts a new evaluation. Nodes that are unchanged since the previous
+    /// one are taken from the cache.
+    pub fn compute_brep(
+        &mut self,
+        shape: &fj::Shape,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        error_budget: &mut ErrorBudget,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Validated<Vec<Face>>, Error> {
+        self.start_evaluation(config, tolerance);
+        self.compute_node(shape, config, tolerance, error_budget, debug_info)
+    }
+
+    /// Access the nodes that were computed during the last evaluation
+    ///
+    /// Nodes that were taken from the cache are not included. The paths are
+    /// relative to the shape that was evaluated.
+    pub fn computed(&self) -> &[ShapePath] {
+        &self.computed
+    }
+
+    /// Start a new evaluation
+    ///
+    /// The nodes of the current evaluation become the ones that can be
+    /// reused. Any nodes from before that are dropped.
+    pub(crate) fn start_evaluation(
+        &mut self,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+    ) {
+        let settings = Some((tolerance, *config));
+        if self.settings != settings {
+            self.settings = settings;
+            self.current.clear();
+        }
+
+        self.previous = mem::take(&mut self.current);
+        self.path.clear();
+        self.computed.clear();
+    }
+
+    /// Compute a node of the shape that is currently being evaluated
+    pub(crate) fn compute_node(
+        &mut self,
+        shape: &fj::Shape,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        error_budget: &mut ErrorBudget,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Validated<Vec<Face>>, Error> {
+        let key = node_key(shape);
+
+        if let Some(key) = key {
+            let entry = match self.current.get(&key) {
+                Some(entry) => Some(entry.clone()),
+                None => self.previous.remove(&key),
+            };
+
+            if let Some(entry) = entry {
+                for contribution in &entry.errors {
+                    error_budget.record(contribution.stage, contribution.error);
+                }
+
+                let shape = entry.shape.clone();
+                self.insert(key, entry);
+                return Ok(shape);
+            }
+        }
+
+        self.computed.push(ShapePath(self.path.clone()));
+        let num_errors = error_budget.contributions().count();
+
+        let faces = match shape {
+            fj::Shape::Group(group) => {
+                let a = self.compute_child(
+                    "a",
+                    &group.a,
+                    config,
+                    tolerance,
+                    error_budget,
+                    debug_info,
+                )?;
+                let b = self.compute_child(
+                    "b",
+                    &group.b,
+                    config,
+                    tolerance,
+                    error_budget,
+                    debug_info,
+                )?;
+
+                merge_group([a, b], config)?
+            }
+            fj::Shape::Transform(transform) => {
+                let shape = self.compute_child(
+                    "shape",
+                    &transform.shape,
+                    config,
+                    tolerance,
+                    error_budget,
+                    debug_info,
+                )?;
+
+                apply_transform(transform, shape, config)?
+            }
+            shape => shape.compute_brep(
+                config,
+                tolerance,
+                error_budget,
+                debug_info,
+            )?,
+        };
+
+        if let Some(key) = key {
+            let errors = error_budget
+                .contributions()
+                .skip(num_errors)
+                .copied()
+                .collect();
+            self.insert(
+                key,
+                CacheEntry {
+                    shape: faces.clone(),
+                    errors,
+                },
+            );
+        }
+
+        Ok(faces)
+    }
+
+    /// Continue with the node of the given name, within the current node
+    pub(crate) fn enter(&mut self, name: &str) {
+        self.path.push(name.into());
+    }
+
+    /// Return to the node that contains the current one
+    pub(crate) fn leave(&mut self) {
+        self.path.pop();
+    }
+
+    fn compute_child(
+        &mut self,
+        name: &str,
+        shape: &fj::Shape,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        error_budget: &mut ErrorBudget,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Validated<Vec<Face>>, Error> {
+        self.enter(name);
+        let result = self.compute_node(
+            shape,
+            config,
+            tolerance,
+            error_budget,
+            debug_info,
+        );
+        self.leave();
+
+        result.map_err(|err| err.in_shape(name))
+    }
+
+    fn insert(&mut self, key: u64, entry: CacheEntry) {
+        if self.current.len() < self.capacity || self.current.contains_key(&key)
+        {
+            self.current.insert(key, entry);
+        }
+    }
+}
+
+impl Default for BrepCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct CacheEntry {
+    shape: Validated<Vec<Face>>,
+    errors: Vec<ErrorContribution>,
+}
+
+/// Identify a node by its parameters, and those of the shapes it contains
+///
+/// Returns `None`, if the node can't be serialized. It is not cached then.
+fn node_key(shape: &fj::Shape) -> Option<u64> {
+    let serialized = serde_json::to_vec(shape).ok()?;
+
+    let mut hasher = DefaultHasher::new();
+    serialized.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use fj_kernel::algorithms::Tolerance;
+
+    use crate::{shape_processor::ShapeProcessor, Error};
+
+    use super::BrepCache;
+
+    #[test]
+    fn transform_of_unchanged_shape() -> Result<(), Error> {
+        let processor = ShapeProcessor {
+            tolerance: Some(Tolerance::from_scalar(0.01)?),
+            ..ShapeProcessor::default()
+        };
+        let mut cache = BrepCache::default();
+
+        let shape = transform(prism(48), [0., 0., 0.]);
+        processor.process_cached(&shape, &mut cache)?;
+        assert_eq!(cache.computed().len(), 2);
+
+        // Only the transform itself is computed again.
+        let shape = transform(prism(48), [1., 2., 3.]);
+        let warm = processor.process_cached(&shape, &mut cache)?;
+        assert_eq!(paths(&cache), [""]);
+
+        let cold = processor.process(&shape)?;
+        assert_eq!(warm.mesh.vertices().count(), cold.mesh.vertices().count());
+        assert!(warm
+            .mesh
+            .triangles()
+            .map(|triangle| triangle.points)
+            .eq(cold.mesh.triangles().map(|triangle| triangle.points)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn changed_sketch() -> Result<(), Error> {
+        let processor = ShapeProcessor {
+            tolerance: Some(Tolerance::from_scalar(0.01)?),
+            ..ShapeProcessor::default()
+        };
+        let mut cache = BrepCache::default();
+
+        let shape = |sides| {
+            let a = transform(prism(sides), [0., 0., 0.]);
+            let b = transform(prism(4), [10., 0., 0.]);
+            fj::Shape::from(fj::Group { a, b })
+        };
+
+        processor.process_cached(&shape(6), &mut cache)?;
+        assert_eq!(paths(&cache), ["a", "a/shape", "b", "b/shape"]);
+
+        processor.process_cached(&shape(6), &mut cache)?;
+        assert!(cache.computed().is_empty());
+
+        processor.process_cached(&shape(8), &mut cache)?;
+        assert_eq!(paths(&cache), ["a", "a/shape"]);
+
+        // Changing the tolerance invalidates everything.
+        let processor = ShapeProcessor {
+            tolerance: Some(Tolerance::from_scalar(0.1)?),
+            ..processor
+        };
+        processor.process_cached(&shape(8), &mut cache)?;
+        assert_eq!(cache.computed().len(), 4);
+
+        Ok(())
+    }
+
+    /// A prism with a regular polygon as its base, with `sides + 2` faces
+    fn prism(sides: usize) -> fj::Shape {
+        let points = (0..sides)
+            .map(|i| {
+                let angle = TAU * i as f64 / sides as f64;
+                [angle.cos(), angle.sin()]
+            })
+            .collect();
+        let sketch = fj::Sketch::from_points(points);
+
+        fj::Sweep::from_path(sketch.into(), [0., 0., 1.]).into()
+    }
+
+    fn transform(shape: fj::Shape, offset: [f64; 3]) -> fj::Shape {
+        fj::Transform {
+            shape,
+            axis: [0., 0., 1.],
+            angle: fj::Angle::from_rad(0.),
+            offset,
+        }
+        .into()
+    }
+
+    fn paths(cache: &BrepCache) -> Vec<String> {
+        cache.computed().iter().map(ToString::to_string).collect()
+    }
+}
@@ -0,0 +1,257 @@
+//! Reproduction files for processing issues
+//!
+//! If processing a shape fails or produces wrong results, a [`Repro`] captures
+//! everything that is needed to process it again: the shape itself, the
+//! settings of the [`ShapeProcessor`], and the parameters that were passed to
+//! the model. Since the shape is plain data, it can be replayed without the
+//! model's code.
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufReader, BufWriter},
+    path::Path,
+};
+
+use fj_interop::processed_shape::ProcessedShape;
+use fj_kernel::algorithms::{EffortBudget, InvalidTolerance, Tolerance};
+use serde::{Deserialize, Serialize};
+
+use crate::{shape_processor::ShapeProcessor, Error};
+
+/// The version of the repro format
+///
+/// Must be incremented, whenever the format changes in an incompatible way.
+/// This includes incompatible changes to the serialized form of [`fj::Shape`].
+pub const REPRO_FORMAT_VERSION: u32 = 1;
+
+/// Everything that is needed to reproduce the processing of a shape
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Repro {
+    /// The version of the repro format
+    pub format_version: u32,
+
+    /// The version of Fornjot that created the repro
+    pub fornjot_version: String,
+
+    /// The shape that was processed
+    pub shape: fj::Shape,
+
+    /// The parameters that were passed to the model
+    ///
+    /// These aren't required for replaying the repro, as the shape has already
+    /// been created from them. They are recorded, so the model's author can
+    /// reproduce the issue with the model's code, too.
+    pub parameters: BTreeMap<String, String>,
+
+    /// The tolerance that was configured, if any
+    ///
+    /// See [`ShapeProcessor::tolerance`].
+    pub tolerance: Option<f64>,
+
+    /// The limits of the effort budget
+    ///
+    /// See [`ShapeProcessor::effort_budget`]. Its deadline, and the time
+    /// budget, can't be reproduced, and are not recorded.
+    pub max_points_per_face: Option<usize>,
+
+    /// The limits of the effort budget
+    ///
+    /// See [`Repro::max_points_per_face`].
+    pub max_total_triangles: Option<usize>,
+}
+
+impl Repro {
+    /// Capture the processing of a shape
+    pub fn new(
+        processor: &ShapeProcessor,
+        shape: &fj::Shape,
+        parameters: impl IntoIterator<Item = (String, String)>,
+    ) -> Self {
+        Self {
+            format_version: REPRO_FORMAT_VERSION,
+            fornjot_version: env!("CARGO_PKG_VERSION").into(),
+            shape: shape.clone(),
+            parameters: parameters.into_iter().collect(),
+            tolerance: processor
+                .tolerance
+                .map(|tolerance| tolerance.inner().into_f64()),
+            max_points_per_face: processor.effort_budget.max_points_per_face,
+            max_total_triangles: processor.effort_budget.max_total_triangles,
+        }
+    }
+
+    /// Load a repro from a file
+    ///
+    /// Fails, if the repro was written in a format version other than
+    /// [`REPRO_FORMAT_VERSION`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ReproError> {
+        let file = BufReader::new(File::open(path)?);
+
+        // Check the version first, so a repro in another format results in a
+        // clear error, rather than some deserialization error.
+        let value: serde_json::Value = serde_json::from_reader(file)?;
+        let format_version = value
+            .get("format_version")
+            .and_then(serde_json::Value::as_u64);
+        if format_version != Some(u64::from(REPRO_FORMAT_VERSION)) {
+            return Err(ReproError::UnsupportedVersion {
+                format_version,
+                fornjot_version: value
+                    .get("fornjot_version")
+                    .and_then(serde_json::Value::as_str)
+                    .map(Into::into),
+            });
+        }
+
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Write the repro to a file
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ReproError> {
+        let file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+
+    /// Create a [`ShapeProcessor`] with the recorded settings
+    pub fn processor(&self) -> Result<ShapeProcessor, ReproError> {
+        let tolerance =
+            self.tolerance.map(Tolerance::from_scalar).transpose()?;
+
+        Ok(ShapeProcessor {
+            tolerance,
+            effort_budget: EffortBudget {
+                max_points_per_face: self.max_points_per_face,
+                max_total_triangles: self.max_total_triangles,
+                deadline: None,
+            },
+            ..ShapeProcessor::default()
+        })
+    }
+
+    /// Process the recorded shape again, with the recorded settings
+    pub fn replay(&self) -> Result<ProcessedShape, ReproError> {
+        let shape = self.processor()?.process(&self.shape)?;
+        Ok(shape)
+    }
+}
+
+/// Load a repro from a file and process its shape again
+///
+/// See [`Repro::load`] and [`Repro::replay`].
+pub fn replay_repro(
+    path: impl AsRef<Path>,
+) -> Result<ProcessedShape, ReproError> {
+    Repro::load(path)?.replay()
+}
+
+/// An error that can occur while saving, loading, or replaying a repro
+#[derive(Debug, thiserror::Error)]
+pub enum ReproError {
+    /// I/O error while reading or writing the repro
+    #[error("I/O error while accessing repro")]
+    Io(#[from] io::Error),
+
+    /// The repro could not be serialized or deserialized
+    #[error("Invalid repro")]
+    Format(#[from] serde_json::Error),
+
+    /// The repro was written in an unsupported format version
+    #[error(
+        "Unsupported repro format version {format_version:?} (written by \
+        Fornjot {fornjot_version:?}); expected version {REPRO_FORMAT_VERSION}"
+    )]
+    UnsupportedVersion {
+        /// The format version of the repro, if it has one
+        format_version: Option<u64>,
+
+        /// The version of Fornjot that wrote the repro, if known
+        fornjot_version: Option<String>,
+    },
+
+    /// The recorded tolerance is invalid
+    #[error("Invalid tolerance in repro")]
+    Tolerance(#[from] InvalidTolerance),
+
+    /// Processing the recorded shape failed
+    #[error("Error processing shape from repro")]
+    Processing(#[from] Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use fj::syntax::{Sketch as _, Sweep as _, Transform as _};
+    use fj_kernel::algorithms::Tolerance;
+
+    use crate::shape_processor::ShapeProcessor;
+
+    use super::{replay_repro, Repro, ReproError};
+
+    #[test]
+    fn save_and_replay() {
+        let shape: fj::Shape = [[0., 0.], [1., 0.], [1., 1.], [0., 1.]]
+            .sketch()
+            .sweep([0., 0., 1.])
+            .rotate([0., 0., 1.], fj::Angle::from_deg(30.))
+            .translate([1., 2., 3.])
+            .into();
+
+        let processor = ShapeProcessor {
+            tolerance: Some(
+                Tolerance::from_scalar(0.01).expect("Tolerance is positive"),
+            ),
+            ..ShapeProcessor::default()
+        };
+        let original = processor.process(&shape).expect("Shape is valid");
+
+        let path = temp_path("save_and_replay");
+        let parameters = [("size".to_string(), "1".to_string())];
+        Repro::new(&processor, &shape, parameters)
+            .save(&path)
+            .expect("Failed to save repro");
+
+        let repro = Repro::load(&path).expect("Failed to load repro");
+        assert_eq!(repro.shape, shape);
+        assert_eq!(repro.parameters.get("size").map(String::as_str), Some("1"));
+
+        let replayed = replay_repro(&path).expect("Failed to replay repro");
+        fs::remove_file(&path).expect("Failed to remove repro");
+
+        assert_eq!(
+            replayed.mesh.triangles().collect::<Vec<_>>(),
+            original.mesh.triangles().collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let path = temp_path("unsupported_version");
+        fs::write(
+            &path,
+            r#"{ "format_version": 0, "fornjot_version": "0.1.0" }"#,
+        )
+        .expect("Failed to write repro");
+
+        let result = Repro::load(&path);
+        fs::remove_file(&path).expect("Failed to remove repro");
+
+        assert!(matches!(
+            result,
+            Err(ReproError::UnsupportedVersion {
+                format_version: Some(0),
+                ..
+            })
+        ));
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "fj-operations-repro-{}-{}.json",
+            name,
+            std::process::id()
+        ))
+    }
+}
@@ -0,0 +1,71 @@
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::{revolve, ErrorBudget, Tolerance},
+    objects::Solid,
+    validation::{validate_intermediate, Validated, ValidationConfig},
+};
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use crate::{Error, Shape};
+
+impl Shape for fj::Revolve {
+    type Brep = Solid;
+
+    fn compute_brep(
+        &self,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        error_budget: &mut ErrorBudget,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Validated<Self::Brep>, Error> {
+        let sketch = self
+            .shape()
+            .compute_brep(config, tolerance, error_budget, debug_info)
+            .map_err(|err| err.in_shape("shape"))?;
+        let color = self.shape().color();
+
+        // An `fj::Angle` of zero stands for a full revolution.
+        let angle = if self.is_full() {
+            Scalar::PI * 2.
+        } else {
+            Scalar::from_f64(self.angle().rad())
+        };
+
+        let solid = revolve(
+            sketch.into_inner(),
+            self.axis_origin(),
+            self.axis_direction(),
+            angle,
+            tolerance,
+            color,
+        )?;
+        let solid = if config.auto_reorient {
+            solid.reoriented_outward()
+        } else {
+            solid
+        };
+
+        Ok(validate_intermediate(solid, config)?)
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        let aabb = self.shape().bounding_volume();
+
+        let origin = Point::from(self.axis_origin());
+        let direction = Vector::from(self.axis_direction()).normalize();
+
+        // Each corner of the bounding box stays on a circle around the axis.
+        // A cube around the center of that circle covers it.
+        let points = aabb.vertices().into_iter().flat_map(|vertex| {
+            let center = origin + direction * (vertex - origin).dot(&direction);
+            let radius = (vertex - center).magnitude();
+
+            [
+                center - Vector::from([radius; 3]),
+                center + Vector::from([radius; 3]),
+            ]
+        });
+
+        Aabb::<3>::from_points(points)
+    }
+}
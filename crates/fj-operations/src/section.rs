@@ -0,0 +1,279 @@
+use std::{collections::BTreeMap, iter, mem::size_of};
+
+use fj_interop::{
+    debug::DebugInfo,
+    memory::MemoryReport,
+    mesh::Mesh,
+    section::{Section, SectionFace, SectionView},
+};
+use fj_kernel::{
+    algorithms::{
+        clip_mesh, section_mesh, CycleApprox, ErrorBudget, SplitError,
+        Tolerance,
+    },
+    objects::{Cycle, Face, Surface},
+    validation::{Validated, ValidationConfig},
+};
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use crate::{Error, Shape};
+
+impl Shape for fj::Sectioned {
+    type Brep = Vec<Face>;
+
+    fn compute_brep(
+        &self,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        error_budget: &mut ErrorBudget,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Validated<Self::Brep>, Error> {
+        // Sections don't affect the geometry. They are evaluated separately,
+        // once the mesh of the shape is known.
+        self.shape()
+            .compute_brep(config, tolerance, error_budget, debug_info)
+            .map_err(|err| err.in_shape("shape"))
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        self.shape().bounding_volume()
+    }
+}
+
+/// Cut the mesh of a shape with the planes of its section presets
+///
+/// If `skip` is `true`, the sections are only listed, without cutting the
+/// mesh. Each section that is evaluated is recorded in `debug_info`.
+pub(crate) fn resolve_sections(
+    presets: &[fj::SectionPreset],
+    mesh: &Mesh<Point<3>>,
+    tolerance: Tolerance,
+    skip: bool,
+    debug_info: &mut DebugInfo,
+) -> Result<BTreeMap<String, Section>, Error> {
+    let mut sections = BTreeMap::new();
+
+    for (i, preset) in presets.iter().enumerate() {
+        let section =
+            resolve(preset, mesh, tolerance, skip).map_err(|err| {
+                Error::from(err).in_shape(format!("presets[{i}]"))
+            })?;
+
+        if section.view.is_some() {
+            debug_info.record_section();
+        }
+        sections.insert(preset.label.clone(), section);
+    }
+
+    Ok(sections)
+}
+
+/// Estimate the memory used by the evaluated sections
+///
+/// Includes the clipped meshes and the points of the section faces. Sections
+/// whose evaluation was skipped don't use any memory.
+pub(crate) fn sections_memory_report(
+    sections: &BTreeMap<String, Section>,
+) -> MemoryReport {
+    let mut report = MemoryReport::new();
+
+    for view in sections
+        .values()
+        .filter_map(|section| section.view.as_ref())
+    {
+        report.merge(&view.mesh.memory_report());
+
+        let points = view
+            .faces
+            .iter()
+            .flat_map(|face| iter::once(&face.exterior).chain(&face.interiors))
+            .map(Vec::len)
+            .sum::<usize>();
+        report.add("section points", points, points * size_of::<Point<3>>());
+    }
+
+    report
+}
+
+fn resolve(
+    preset: &fj::SectionPreset,
+    mesh: &Mesh<Point<3>>,
+    tolerance: Tolerance,
+    skip: bool,
+) -> Result<Section, SectionError> {
+    let origin = Point::from(preset.plane.origin);
+    let normal = Vector::from(preset.plane.normal);
+
+    if normal.magnitude() == Scalar::ZERO {
+        return Err(SectionError::ZeroNormal(preset.label.clone()));
+    }
+    let normal = normal.normalize();
+
+    let view = if skip {
+        None
+    } else {
+        let plane = plane(origin, normal);
+
+        let faces = section_mesh(mesh, &plane)?
+            .faces()
+            .map(|face| SectionFace {
+                exterior: face
+                    .exteriors()
+                    .flat_map(|cycle| points(cycle, tolerance))
+                    .collect(),
+                interiors: face
+                    .interiors()
+                    .map(|cycle| points(cycle, tolerance))
+                    .collect(),
+            })
+            .collect();
+        let mesh = clip_mesh(mesh, &plane, tolerance)?;
+
+        Some(SectionView { faces, mesh })
+    };
+
+    Ok(Section {
+        origin,
+        normal,
+        view,
+    })
+}
+
+/// Construct a plane whose normal points along `normal`
+fn plane(origin: Point<3>, normal: Vector<3>) -> Surface {
+    // Any vector that isn't parallel to the normal will do.
+    let reference = if normal.x.abs() < Scalar::from_f64(0.9) {
+        Vector::unit_x()
+    } else {
+        Vector::unit_y()
+    };
+
+    let u = normal.cross(&reference).normalize();
+    let v = normal.cross(&u);
+
+    Surface::plane_from_points([origin, origin + u, origin + v])
+}
+
+/// The points of a cycle of a section face, without repeating the first one
+fn points(cycle: &Cycle, tolerance: Tolerance) -> Vec<Point<3>> {
+    let mut points: Vec<_> = CycleApprox::new(cycle, tolerance)
+        .points
+        .into_iter()
+        .map(|point| *point.global_form())
+        .collect();
+
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+
+    points
+}
+
+/// An error that can occur while evaluating a section
+#[derive(Debug, thiserror::Error)]
+pub enum SectionError {
+    /// The normal of the section plane is zero
+    #[error("Section `{0}` has a zero normal")]
+    ZeroNormal(String),
+
+    /// The mesh of the shape could not be cut
+    #[error("Failed to cut shape for section")]
+    Split(#[from] SplitError),
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::memory::MemoryStage;
+    use fj_math::{Point, Scalar, Vector};
+
+    use crate::{shape_processor::ShapeProcessor, test_helpers::cube, Error};
+
+    fn sectioned_cube() -> fj::Shape {
+        fj::Sectioned::from_shape(cube(1.).into())
+            .with_preset(fj::SectionPreset::new(
+                "XY-mid",
+                fj::SectionPlane::new([0.5, 0.5, 0.5], [0., 0., 1.]),
+            ))
+            .into()
+    }
+
+    #[test]
+    fn mid_plane() -> Result<(), Error> {
+        let processed = ShapeProcessor::default().process(&sectioned_cube())?;
+
+        let section = &processed.sections["XY-mid"];
+        let view = section.view.as_ref().expect("Section was evaluated");
+
+        assert_eq!(view.faces.len(), 1);
+        let face = &view.faces[0];
+        assert!(face.interiors.is_empty());
+        assert!(face.exterior.iter().all(|point| is_close(point.z, 0.5)));
+
+        // The exterior runs counter-clockwise around the normal.
+        let area = face
+            .exterior
+            .iter()
+            .zip(face.exterior.iter().cycle().skip(1))
+            .map(|(a, b)| a.coords.cross(&b.coords))
+            .fold(Vector::from([0., 0., 0.]), |sum, area| sum + area)
+            .dot(&section.normal)
+            / 2.;
+        assert!(is_close(area, 1.));
+
+        let volume = fj_interop::physical_summary::PhysicalSummary::from_mesh(
+            &view.mesh,
+            Scalar::from_f64(1e-3),
+        )
+        .volume;
+        assert!(is_close(volume, 0.5));
+        assert!(view
+            .mesh
+            .vertices()
+            .all(|point: Point<3>| point.z <= Scalar::from_f64(0.5)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn skip_sections() -> Result<(), Error> {
+        let processor = ShapeProcessor {
+            skip_sections: true,
+            ..ShapeProcessor::default()
+        };
+        let processed = processor.process(&sectioned_cube())?;
+
+        assert_eq!(processed.debug_info.num_sections(), 0);
+        assert!(processed.sections["XY-mid"].view.is_none());
+
+        let processed = ShapeProcessor::default().process(&sectioned_cube())?;
+        assert_eq!(processed.debug_info.num_sections(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn memory_snapshots() -> Result<(), Error> {
+        let processed = ShapeProcessor::default().process(&sectioned_cube())?;
+
+        let stages: Vec<_> = processed
+            .memory
+            .iter()
+            .map(|snapshot| snapshot.stage)
+            .collect();
+        assert_eq!(stages, MemoryStage::ALL);
+
+        // Every stage produces data for the cube, including the section.
+        for snapshot in &processed.memory {
+            assert!(snapshot.report.total_bytes() > 0, "{:?}", snapshot.stage);
+        }
+
+        let mesh = &processed.memory[2].report;
+        assert_eq!(mesh, &processed.mesh.memory_report());
+
+        Ok(())
+    }
+
+    fn is_close(a: Scalar, b: f64) -> bool {
+        (a - Scalar::from_f64(b)).abs() < Scalar::from_f64(1e-9)
+    }
+}
@@ -1,23 +1,102 @@
 //! API for processing shapes
 
-use fj_interop::{debug::DebugInfo, processed_shape::ProcessedShape};
+use std::time::{Duration, Instant};
+
+use fj_interop::{
+    annotation::Annotation,
+    debug::DebugInfo,
+    material::Materials,
+    memory::{MemorySnapshot, MemoryStage},
+    mesh::Mesh,
+    physical_summary::PhysicalSummary,
+    processed_shape::ProcessedShape,
+};
 use fj_kernel::{
-    algorithms::{triangulate, InvalidTolerance, Tolerance},
-    validation::{ValidationConfig, ValidationError},
+    algorithms::{
+        triangulate, triangulate_instanced, triangulate_with_config,
+        ApproxStore, EffortBudget, ErrorBudget, Tolerance, TriangulationConfig,
+    },
+    iter::ObjectIters,
+    objects::Face,
+    validation::{
+        check_orientation, check_shells, validate_deferred,
+        validate_intermediate, ValidationConfig,
+    },
 };
-use fj_math::Scalar;
+use fj_math::{Aabb, Point, Scalar};
 
-use crate::Shape as _;
+use crate::{
+    annotation::resolve_annotations,
+    group::compute_parts,
+    material::{apply_display_colors, resolve_materials},
+    part::{collect_parts, physical_shape},
+    rebuild::BrepCache,
+    section::{resolve_sections, sections_memory_report},
+    sketch::sketch_warnings,
+    Shape as _,
+};
+
+pub use crate::Error;
+pub use fj_interop::{
+    debug::{CaptureLevel, DebugConfig},
+    memory::memory_table,
+};
 
 /// Processes an [`fj::Shape`] into a [`ProcessedShape`]
+#[derive(Default)]
 pub struct ShapeProcessor {
     /// The tolerance value used for creating the triangle mesh
     pub tolerance: Option<Tolerance>,
+
+    /// Configures how much debug info is recorded while processing
+    pub debug_config: DebugConfig,
+
+    /// Limits the effort spent on triangulating the shape
+    ///
+    /// Faces that would exceed it are triangulated with a coarser tolerance,
+    /// and a warning is emitted for each of them. Unlimited by default, which
+    /// is what export needs. Groups are always triangulated without limits.
+    pub effort_budget: EffortBudget,
+
+    /// The time that triangulating a shape may take
+    ///
+    /// Overrides the deadline of [`ShapeProcessor::effort_budget`], as the
+    /// deadline needs to be computed anew for each call to
+    /// [`ShapeProcessor::process`].
+    pub time_budget: Option<Duration>,
+
+    /// Skip the evaluation of sections
+    ///
+    /// The sections that the shape defines are still listed in the
+    /// [`ProcessedShape`], but without cutting the shape. Useful for fast
+    /// rebuilds, while the sections are not displayed.
+    pub skip_sections: bool,
 }
 
 impl ShapeProcessor {
     /// Process an [`fj::Shape`] into [`ProcessedShape`]
     pub fn process(&self, shape: &fj::Shape) -> Result<ProcessedShape, Error> {
+        self.process_inner(shape, None)
+    }
+
+    /// Process an [`fj::Shape`], reusing results of the previous evaluation
+    ///
+    /// Parts of the shape that haven't changed since the cache was last used
+    /// are taken from it, instead of being computed again. See [`BrepCache`]
+    /// for details.
+    pub fn process_cached(
+        &self,
+        shape: &fj::Shape,
+        cache: &mut BrepCache,
+    ) -> Result<ProcessedShape, Error> {
+        self.process_inner(shape, Some(cache))
+    }
+
+    fn process_inner(
+        &self,
+        shape: &fj::Shape,
+        mut cache: Option<&mut BrepCache>,
+    ) -> Result<ProcessedShape, Error> {
         let aabb = shape.bounding_volume();
 
         let tolerance = match self.tolerance {
@@ -38,28 +117,377 @@ impl ShapeProcessor {
             Some(user_defined_tolerance) => user_defined_tolerance,
         };
 
+        let span = fj_interop::span!(
+            INFO,
+            "process",
+            tolerance = tolerance.inner().into_f64(),
+            triangles = tracing::field::Empty,
+            warnings = tracing::field::Empty,
+        );
+
+        let mut effort_budget = self.effort_budget;
+        if let Some(time_budget) = self.time_budget {
+            effort_budget.deadline = Some(Instant::now() + time_budget);
+        }
+
         let config = ValidationConfig::default();
-        let mut debug_info = DebugInfo::new();
-        let shape = shape.compute_brep(&config, tolerance, &mut debug_info)?;
-        let mesh = triangulate(shape.into_inner(), tolerance, &mut debug_info);
+        let mut error_budget = ErrorBudget::new();
+        let mut debug_info = DebugInfo::with_config(self.debug_config);
+        let mut warnings = Vec::new();
+        let mut approx_store = ApproxStore::new();
+        let mut memory = Vec::new();
+
+        let parts = collect_parts(shape);
+        let physical = physical_shape(shape);
+
+        // Problems with sketches are easiest to fix in the sketch itself, so
+        // they are pointed out there, before they turn into problems with the
+        // shapes that are made from them.
+        warnings.extend(sketch_warnings(shape, &config, tolerance));
+
+        // Annotations, materials, and sections are carried alongside the
+        // geometry. They are resolved, once the shape is known.
+        let mut shape = shape;
+        let mut annotations = None;
+        let mut materialized = None;
+        let mut presets = None;
+        loop {
+            match shape {
+                fj::Shape::Annotated(annotated) => {
+                    annotations.get_or_insert(annotated.annotations());
+                    shape = annotated.shape();
+                }
+                fj::Shape::Materialized(inner) => {
+                    materialized.get_or_insert(&**inner);
+                    shape = inner.shape();
+                }
+                fj::Shape::Sectioned(sectioned) => {
+                    presets.get_or_insert(sectioned.presets());
+                    shape = sectioned.shape();
+                }
+                fj::Shape::Part(part)
+                    if part.state() != fj::PartState::Suppressed =>
+                {
+                    shape = part.shape();
+                }
+                _ => break,
+            }
+        }
+        let annotations = annotations.unwrap_or_default();
+        let presets = presets.unwrap_or_default();
+
+        if let Some(cache) = cache.as_deref_mut() {
+            cache.start_evaluation(&config, tolerance);
+        }
+
+        let (mesh, annotations, materials) = match shape {
+            // Groups often contain many identical parts. Those only need to be
+            // triangulated once.
+            fj::Shape::Group(group) => {
+                let parts = compute_parts(
+                    group,
+                    &config,
+                    tolerance,
+                    &mut error_budget,
+                    &mut debug_info,
+                    cache,
+                )?;
+                let parts = validate_intermediate(parts, &config)?;
+                let parts = validate_deferred(parts, &config)?;
+                memory.push(MemorySnapshot {
+                    stage: MemoryStage::Brep,
+                    report: parts.memory_report(),
+                });
+
+                for part in parts.iter() {
+                    warnings.extend(orientation_warnings(part, tolerance));
+                }
+
+                let (annotations, materials) =
+                    if annotations.is_empty() && materialized.is_none() {
+                        (Vec::new(), Materials::default())
+                    } else {
+                        let faces: Vec<_> =
+                            parts.iter().flatten().cloned().collect();
+                        resolve_attributes(annotations, materialized, &faces)?
+                    };
+
+                let mesh = triangulate_instanced(
+                    parts.into_inner(),
+                    tolerance,
+                    &error_budget,
+                    &mut approx_store,
+                    &mut debug_info,
+                )?
+                .to_mesh();
+
+                (mesh, annotations, materials)
+            }
+            shape => {
+                let is_sketch = matches!(shape, fj::Shape::Shape2d(_));
+
+                let shape = match cache {
+                    Some(cache) => cache.compute_node(
+                        shape,
+                        &config,
+                        tolerance,
+                        &mut error_budget,
+                        &mut debug_info,
+                    )?,
+                    None => shape.compute_brep(
+                        &config,
+                        tolerance,
+                        &mut error_budget,
+                        &mut debug_info,
+                    )?,
+                };
+                let shape = validate_deferred(shape, &config)?;
+                memory.push(MemorySnapshot {
+                    stage: MemoryStage::Brep,
+                    report: shape.memory_report(),
+                });
+
+                warnings.extend(orientation_warnings(&*shape, tolerance));
+
+                let (annotations, materials) =
+                    resolve_attributes(annotations, materialized, &shape)?;
+
+                let mut mesh = triangulate_with_config(
+                    shape.into_inner(),
+                    tolerance,
+                    &error_budget,
+                    &mut approx_store,
+                    &mut debug_info,
+                    &TriangulationConfig {
+                        effort: effort_budget,
+                        ..TriangulationConfig::default()
+                    },
+                )?;
+
+                for (face, achieved) in mesh.degraded_faces() {
+                    warnings.push(format!(
+                        "Face {face} was approximated with tolerance \
+                        {achieved} instead of {}, to stay within the effort \
+                        budget",
+                        tolerance.inner()
+                    ));
+                }
+
+                // Sketches are open surfaces, which can be seen from both
+                // sides.
+                mesh.set_double_sided(is_sketch);
+
+                (mesh, annotations, materials)
+            }
+        };
+
+        let mesh = apply_display_colors(mesh, &materials);
+
+        // The approximations are only needed for triangulation, but they stay
+        // around until processing is done.
+        memory.push(MemorySnapshot {
+            stage: MemoryStage::Approximation,
+            report: approx_store.memory_report(),
+        });
+        memory.push(MemorySnapshot {
+            stage: MemoryStage::Mesh,
+            report: mesh.memory_report(),
+        });
+
+        // Sections are cut from the finished mesh, so they show the shape as
+        // it is displayed.
+        let sections = resolve_sections(
+            presets,
+            &mesh,
+            tolerance,
+            self.skip_sections,
+            &mut debug_info,
+        )?;
+        memory.push(MemorySnapshot {
+            stage: MemoryStage::Sections,
+            report: sections_memory_report(&sections),
+        });
+
+        // Display-only parts are reference geometry. They are excluded from
+        // the physical properties, which requires evaluating the shape again
+        // without them.
+        let summary = match physical {
+            Some(physical) => {
+                let mesh = physical_mesh(&physical, &config, tolerance)?;
+                PhysicalSummary::from_mesh(&mesh, tolerance.inner())
+            }
+            None => PhysicalSummary::from_mesh(&mesh, tolerance.inner()),
+        };
+
+        fj_interop::record!(span, "triangles", mesh.triangle_count());
+        fj_interop::record!(span, "warnings", warnings.len());
+
+        // Warnings are reported to the caller, but mirroring them as events
+        // makes them show up in logs, in the context of the spans above.
+        #[cfg(feature = "tracing")]
+        for warning in &warnings {
+            tracing::warn!(%warning, "Warning while processing shape");
+        }
 
         Ok(ProcessedShape {
             aabb,
             mesh,
+            summary,
+            accumulated_error: error_budget.total(),
+            warnings,
+            annotations,
+            materials,
+            parts,
+            sections,
             debug_info,
+            memory,
         })
     }
 }
 
-/// A shape processing error
-#[allow(clippy::large_enum_variant)]
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    /// Error converting to shape
-    #[error("Error converting to shape")]
-    ToShape(#[from] ValidationError),
-
-    /// Model has zero size
-    #[error("Model has zero size")]
-    Extent(#[from] InvalidTolerance),
+fn resolve_attributes(
+    annotations: &[fj::Annotation],
+    materialized: Option<&fj::Materialized>,
+    faces: &[Face],
+) -> Result<(Vec<Annotation>, Materials), Error> {
+    let annotations = resolve_annotations(annotations, faces)?;
+    let materials = match materialized {
+        Some(materialized) => resolve_materials(materialized, faces)?,
+        None => Materials::default(),
+    };
+
+    Ok((annotations, materials))
+}
+
+fn physical_mesh(
+    shape: &fj::Shape,
+    config: &ValidationConfig,
+    tolerance: Tolerance,
+) -> Result<Mesh<Point<3>>, Error> {
+    let mut error_budget = ErrorBudget::new();
+    let mut debug_info = DebugInfo::with_config(DebugConfig {
+        level: CaptureLevel::Off,
+        ..DebugConfig::default()
+    });
+
+    let faces = shape.compute_brep(
+        config,
+        tolerance,
+        &mut error_budget,
+        &mut debug_info,
+    )?;
+    let mesh = triangulate(
+        faces.into_inner(),
+        tolerance,
+        &error_budget,
+        &mut ApproxStore::new(),
+        &mut debug_info,
+    )?;
+
+    Ok(mesh)
+}
+
+fn orientation_warnings<'r>(
+    shape: &'r impl ObjectIters<'r>,
+    tolerance: Tolerance,
+) -> Vec<String> {
+    let orientation = check_orientation(shape, tolerance)
+        .into_iter()
+        .map(|warning| warning.to_string());
+    let shells = check_shells(shape, tolerance)
+        .into_iter()
+        .map(|warning| warning.to_string());
+
+    orientation.chain(shells).collect()
+}
+
+/// The smallest tolerance [`tolerance_for_view`] returns, relative to the model
+///
+/// Relative to the diagonal of the model's bounding box.
+pub const MIN_RELATIVE_TOLERANCE: f64 = 1e-6;
+
+/// The largest tolerance [`tolerance_for_view`] returns, relative to the model
+///
+/// Relative to the diagonal of the model's bounding box.
+pub const MAX_RELATIVE_TOLERANCE: f64 = 0.1;
+
+/// Compute a tolerance that is suitable for viewing a model
+///
+/// Returns a tolerance that is half the size of a pixel, at the distance of
+/// the model from the camera. Triangulating the model with it results in a
+/// deviation that is not visible at the current zoom level.
+///
+/// `fov` is the vertical field of view of the camera. The tolerance is clamped
+/// to [`MIN_RELATIVE_TOLERANCE`] and [`MAX_RELATIVE_TOLERANCE`], so zooming in
+/// very close doesn't require an impossible precision, and zooming out very
+/// far doesn't degrade the model beyond recognition. If `aabb` is empty, the
+/// bounds are relative to a unit length instead.
+pub fn tolerance_for_view(
+    aabb: &Aabb<3>,
+    camera_distance: Scalar,
+    viewport_height_px: u32,
+    fov: fj::Angle,
+) -> Tolerance {
+    let view_height = camera_distance * 2. * (fov.rad() / 2.).tan();
+    let pixel_size = view_height / f64::from(viewport_height_px.max(1));
+
+    let mut diagonal = aabb.size().magnitude();
+    if diagonal == Scalar::ZERO {
+        diagonal = Scalar::ONE;
+    }
+
+    let tolerance = (pixel_size / 2.)
+        .max(diagonal * MIN_RELATIVE_TOLERANCE)
+        .min(diagonal * MAX_RELATIVE_TOLERANCE);
+
+    Tolerance::from_scalar(tolerance)
+        .expect("Tolerance is clamped to positive values")
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Aabb, Point, Scalar};
+
+    use super::{tolerance_for_view, ShapeProcessor, MIN_RELATIVE_TOLERANCE};
+
+    #[test]
+    fn tolerance_for_view_scales_with_pixel_size() {
+        let aabb = Aabb {
+            min: Point::from([0., 0., 0.]),
+            max: Point::from([10., 10., 10.]),
+        };
+        let fov = fj::Angle::from_deg(60.);
+
+        let tolerance = |distance: f64, height: u32| {
+            tolerance_for_view(&aabb, Scalar::from_f64(distance), height, fov)
+                .inner()
+                .into_f64()
+        };
+        let is_close = |a: f64, b: f64| (a - b).abs() < 1e-12;
+
+        let base = tolerance(50., 1000);
+        assert!(is_close(tolerance(50., 2000), base / 2.));
+        assert!(is_close(tolerance(100., 1000), base * 2.));
+
+        let diagonal = aabb.size().magnitude().into_f64();
+        assert!(is_close(
+            tolerance(1e-9, 1000),
+            diagonal * MIN_RELATIVE_TOLERANCE
+        ));
+    }
+
+    #[test]
+    fn sketch_warnings() -> Result<(), crate::Error> {
+        let sketch =
+            fj::Sketch::from_points(vec![[0., 0.], [10., 0.], [10., 0.05]]);
+
+        let processed = ShapeProcessor::default().process(&sketch.into())?;
+        assert!(processed.warnings.contains(
+            &"Sketch: corner of 0.3° at (0, 0); consider filleting or \
+            merging points"
+                .to_string()
+        ));
+
+        Ok(())
+    }
 }
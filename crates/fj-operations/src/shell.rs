@@ -0,0 +1,86 @@
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::{shell, ErrorBudget, Tolerance},
+    objects::Solid,
+    validation::{validate_intermediate, Validated, ValidationConfig},
+};
+use fj_math::Aabb;
+
+use crate::{material::face_selector, Error, Shape};
+
+impl Shape for fj::Shell {
+    type Brep = Solid;
+
+    fn compute_brep(
+        &self,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        error_budget: &mut ErrorBudget,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Validated<Self::Brep>, Error> {
+        let solid = Solid::from_faces(
+            self.shape
+                .compute_brep(config, tolerance, error_budget, debug_info)
+                .map_err(|err| err.in_shape("shape"))?
+                .into_inner(),
+        );
+
+        let solid = shell(solid, self.thickness, &face_selector(&self.open))?;
+
+        Ok(validate_intermediate(solid, config)?)
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        // Hollowing out a shape only ever removes material from its inside.
+        self.shape.bounding_volume()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj::syntax::Shell as _;
+    use fj_interop::debug::DebugInfo;
+    use fj_kernel::{
+        algorithms::{ErrorBudget, Tolerance},
+        validation::ValidationConfig,
+    };
+
+    use crate::{
+        shape_processor::ShapeProcessor, test_helpers::cube, Error, Shape as _,
+    };
+
+    #[test]
+    fn open_box() -> Result<(), Error> {
+        let shape: fj::Shape = cube(10.)
+            .shell(1.5, fj::FaceSelector::normal([0., 0., 1.]))
+            .into();
+
+        let faces = shape.compute_brep(
+            &ValidationConfig::default(),
+            Tolerance::from_scalar(0.001)?,
+            &mut ErrorBudget::new(),
+            &mut DebugInfo::new(),
+        )?;
+        assert_eq!(faces.len(), 5 + 5 + 4);
+
+        let processed = ShapeProcessor::default().process(&shape)?;
+        let expected = 10. * 10. * 10. - 7. * 7. * 8.5;
+        let volume = processed.summary.volume.into_f64();
+        assert!((volume - expected).abs() < expected * 1e-6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn thickness_too_large() {
+        let shape: fj::Shape = cube(10.)
+            .shell(5., fj::FaceSelector::normal([0., 0., 1.]))
+            .into();
+        let err = ShapeProcessor::default()
+            .process(&shape)
+            .err()
+            .expect("Thickness is too large");
+
+        assert_eq!(err.error_code(), "shell");
+    }
+}
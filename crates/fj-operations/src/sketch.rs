@@ -1,12 +1,14 @@
 use fj_interop::debug::DebugInfo;
 use fj_kernel::{
-    algorithms::Tolerance,
+    algorithms::{
+        analyze_sketch, ErrorBudget, SketchAnalysisThresholds, Tolerance,
+    },
     objects::{Cycle, Edge, Face, Sketch, Surface},
-    validation::{validate, Validated, ValidationConfig, ValidationError},
+    validation::{validate_intermediate, Validated, ValidationConfig},
 };
 use fj_math::{Aabb, Point, Scalar};
 
-use super::Shape;
+use crate::{Error, Shape};
 
 impl Shape for fj::Sketch {
     type Brep = Sketch;
@@ -15,8 +17,9 @@ impl Shape for fj::Sketch {
         &self,
         config: &ValidationConfig,
         _: Tolerance,
+        _: &mut ErrorBudget,
         _: &mut DebugInfo,
-    ) -> Result<Validated<Self::Brep>, ValidationError> {
+    ) -> Result<Validated<Self::Brep>, Error> {
         let surface = Surface::xy_plane();
 
         let face = match self.chain() {
@@ -42,7 +45,7 @@ impl Shape for fj::Sketch {
         };
 
         let sketch = Sketch::from_faces([face]);
-        validate(sketch, config)
+        Ok(validate_intermediate(sketch, config)?)
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
@@ -61,3 +64,108 @@ impl Shape for fj::Sketch {
         }
     }
 }
+
+/// Analyze all sketches within a shape, and describe what was found
+///
+/// See [`analyze_sketch`]. Sketches within suppressed parts are skipped, as
+/// are sketches that fail to compute. Those fail again, once the shape is
+/// computed, and are reported along with their path within the shape then.
+pub(crate) fn sketch_warnings(
+    shape: &fj::Shape,
+    config: &ValidationConfig,
+    tolerance: Tolerance,
+) -> Vec<String> {
+    let mut sketches = Vec::new();
+    collect_sketches(shape, &mut sketches);
+
+    let thresholds = SketchAnalysisThresholds::from_tolerance(tolerance);
+    let mut warnings = Vec::new();
+
+    for sketch in sketches {
+        let sketch = match sketch.compute_brep(
+            config,
+            tolerance,
+            &mut ErrorBudget::new(),
+            &mut DebugInfo::new(),
+        ) {
+            Ok(sketch) => sketch,
+            Err(_) => continue,
+        };
+
+        let report = analyze_sketch(&sketch, &thresholds);
+        warnings.extend(
+            report
+                .findings
+                .iter()
+                .map(|finding| format!("Sketch: {finding}")),
+        );
+    }
+
+    warnings
+}
+
+fn collect_sketches<'r>(
+    shape: &'r fj::Shape,
+    sketches: &mut Vec<&'r fj::Sketch>,
+) {
+    match shape {
+        fj::Shape::Annotated(shape) => {
+            collect_sketches(shape.shape(), sketches)
+        }
+        fj::Shape::Assembly(assembly) => {
+            for instance in assembly.instances() {
+                collect_sketches(&instance.shape, sketches);
+            }
+        }
+        fj::Shape::Difference(difference) => {
+            collect_sketches(&difference.a, sketches);
+            collect_sketches(&difference.b, sketches);
+        }
+        fj::Shape::Fillet(shape) => collect_sketches(&shape.shape, sketches),
+        fj::Shape::Group(group) => {
+            collect_sketches(&group.a, sketches);
+            collect_sketches(&group.b, sketches);
+        }
+        fj::Shape::ImportedMesh(_) => {}
+        fj::Shape::Materialized(shape) => {
+            collect_sketches(shape.shape(), sketches)
+        }
+        fj::Shape::Mirror(shape) => collect_sketches(&shape.shape, sketches),
+        fj::Shape::Part(part) => {
+            if part.state() != fj::PartState::Suppressed {
+                collect_sketches(part.shape(), sketches);
+            }
+        }
+        fj::Shape::Revolve(revolve) => {
+            collect_sketches_2d(revolve.shape(), sketches)
+        }
+        fj::Shape::Sectioned(shape) => {
+            collect_sketches(shape.shape(), sketches)
+        }
+        fj::Shape::Shape2d(shape) => collect_sketches_2d(shape, sketches),
+        fj::Shape::Shell(shape) => collect_sketches(&shape.shape, sketches),
+        fj::Shape::Sweep(sweep) => collect_sketches_2d(sweep.shape(), sketches),
+        fj::Shape::Tolerance(shape) => collect_sketches(&shape.shape, sketches),
+        fj::Shape::Transform(shape) => collect_sketches(&shape.shape, sketches),
+    }
+}
+
+fn collect_sketches_2d<'r>(
+    shape: &'r fj::Shape2d,
+    sketches: &mut Vec<&'r fj::Sketch>,
+) {
+    match shape {
+        fj::Shape2d::Difference(difference) => {
+            for shape in difference.shapes() {
+                collect_sketches_2d(shape, sketches);
+            }
+        }
+        fj::Shape2d::DifferenceAll(difference) => {
+            collect_sketches_2d(difference.base(), sketches);
+            for shape in difference.tools() {
+                collect_sketches_2d(shape, sketches);
+            }
+        }
+        fj::Shape2d::Sketch(sketch) => sketches.push(sketch),
+    }
+}
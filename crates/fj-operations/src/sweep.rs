@@ -1,12 +1,12 @@
 use fj_interop::debug::DebugInfo;
 use fj_kernel::{
-    algorithms::{sweep, Tolerance},
+    algorithms::{sweep_with_scale, ErrorBudget, ScaleProfile, Tolerance},
     objects::Solid,
-    validation::{validate, Validated, ValidationConfig, ValidationError},
+    validation::{validate_intermediate, Validated, ValidationConfig},
 };
-use fj_math::{Aabb, Vector};
+use fj_math::{Aabb, Scalar, Vector};
 
-use super::Shape;
+use crate::{Error, Shape};
 
 impl Shape for fj::Sweep {
     type Brep = Solid;
@@ -15,25 +15,81 @@ impl Shape for fj::Sweep {
         &self,
         config: &ValidationConfig,
         tolerance: Tolerance,
+        error_budget: &mut ErrorBudget,
         debug_info: &mut DebugInfo,
-    ) -> Result<Validated<Self::Brep>, ValidationError> {
-        let sketch =
-            self.shape().compute_brep(config, tolerance, debug_info)?;
+    ) -> Result<Validated<Self::Brep>, Error> {
+        let sketch = self
+            .shape()
+            .compute_brep(config, tolerance, error_budget, debug_info)
+            .map_err(|err| err.in_shape("shape"))?;
         let path = Vector::from(self.path());
         let color = self.shape().color();
 
-        let solid = sweep(sketch.into_inner(), path, tolerance, color);
-        validate(solid, config)
+        let scale_profile = scale_profile(self.scale_profile());
+
+        let solid = sweep_with_scale(
+            sketch.into_inner(),
+            path,
+            &scale_profile,
+            tolerance,
+            color,
+        )?;
+        let solid = if config.auto_reorient {
+            solid.reoriented_outward()
+        } else {
+            solid
+        };
+
+        Ok(validate_intermediate(solid, config)?)
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
-        self.shape()
-            .bounding_volume()
-            .merged(&Aabb::<3>::from_points(
-                self.shape()
-                    .bounding_volume()
-                    .vertices()
-                    .map(|v| v + self.path()),
-            ))
+        let aabb = self.shape().bounding_volume();
+
+        // The shape is scaled about its centroid, which can be anywhere within
+        // its bounding box. Scaling the bounding box about its center by
+        // `2 * scale - 1` covers every possible centroid.
+        let max_scale = match self.scale_profile() {
+            fj::ScaleProfile::Constant(scale) => *scale,
+            fj::ScaleProfile::Linear { start, end } => start.max(*end),
+            fj::ScaleProfile::PiecewiseLinear(pairs) => pairs
+                .iter()
+                .map(|&(_, scale)| scale)
+                .fold(f64::NEG_INFINITY, f64::max),
+        };
+        let aabb = if max_scale > 1. {
+            let center = aabb.center();
+            Aabb::<3>::from_points(aabb.vertices().map(|vertex| {
+                center + (vertex - center) * (2. * max_scale - 1.)
+            }))
+        } else {
+            aabb
+        };
+
+        aabb.merged(&Aabb::<3>::from_points(
+            aabb.vertices().map(|v| v + self.path()),
+        ))
+    }
+}
+
+fn scale_profile(scale_profile: &fj::ScaleProfile) -> ScaleProfile {
+    match scale_profile {
+        fj::ScaleProfile::Constant(scale) => {
+            ScaleProfile::Constant(Scalar::from_f64(*scale))
+        }
+        fj::ScaleProfile::Linear { start, end } => ScaleProfile::Linear {
+            start: Scalar::from_f64(*start),
+            end: Scalar::from_f64(*end),
+        },
+        fj::ScaleProfile::PiecewiseLinear(pairs) => {
+            ScaleProfile::PiecewiseLinear(
+                pairs
+                    .iter()
+                    .map(|&(t, scale)| {
+                        (Scalar::from_f64(t), Scalar::from_f64(scale))
+                    })
+                    .collect(),
+            )
+        }
     }
 }
@@ -0,0 +1,15 @@
+//! Fixtures that are shared by the tests of the shapes
+
+use fj::syntax::{Sketch as _, Sweep as _};
+
+/// A cube with the given edge length, with one corner at the origin
+pub fn cube(size: f64) -> fj::Sweep {
+    cube_at([0., 0.], size)
+}
+
+/// A cube with the given edge length, with its lowest corner at `[x, y, 0]`
+pub fn cube_at([x, y]: [f64; 2], size: f64) -> fj::Sweep {
+    [[x, y], [x + size, y], [x + size, y + size], [x, y + size]]
+        .sketch()
+        .sweep([0., 0., size])
+}
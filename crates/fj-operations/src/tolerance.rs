@@ -0,0 +1,109 @@
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::{ErrorBudget, Tolerance},
+    objects::Face,
+    validation::{validate_intermediate, Validated, ValidationConfig},
+};
+use fj_math::Aabb;
+
+use crate::{Error, Shape};
+
+impl Shape for fj::Tolerance {
+    type Brep = Vec<Face>;
+
+    fn compute_brep(
+        &self,
+        config: &ValidationConfig,
+        _: Tolerance,
+        error_budget: &mut ErrorBudget,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Validated<Self::Brep>, Error> {
+        let tolerance =
+            Tolerance::from_scalar(self.tolerance).map_err(Error::Tolerance)?;
+
+        let faces = self
+            .shape
+            .compute_brep(config, tolerance, error_budget, debug_info)
+            .map_err(|err| err.in_shape("shape"))?
+            .into_inner();
+
+        // The faces are approximated when the shape is triangulated, long
+        // after this. The tolerance has to stay with them until then. Faces
+        // that already have a tolerance come from an override further down,
+        // which takes precedence.
+        let faces = faces
+            .into_iter()
+            .map(|face| {
+                if face.triangles().is_some() || face.tolerance().is_some() {
+                    face
+                } else {
+                    face.with_tolerance(tolerance)
+                }
+            })
+            .collect();
+
+        Ok(validate_intermediate(faces, config)?)
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        self.shape.bounding_volume()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj::syntax::Tolerance as _;
+    use fj_kernel::algorithms::Tolerance;
+
+    use crate::{shape_processor::ShapeProcessor, Error, Shape as _};
+
+    fn circle() -> fj::Shape {
+        fj::Sketch::from_circle(fj::Circle::from_radius(10.)).into()
+    }
+
+    fn num_vertices(shape: &fj::Shape) -> Result<usize, Error> {
+        let processor = ShapeProcessor {
+            tolerance: Some(Tolerance::from_scalar(0.01)?),
+            ..ShapeProcessor::default()
+        };
+        let processed = processor.process(shape)?;
+
+        Ok(processed.mesh.vertices().count())
+    }
+
+    #[test]
+    fn coarse_override() -> Result<(), Error> {
+        let shape = circle();
+        let coarse: fj::Shape = shape.with_tolerance(1.).into();
+
+        // A circle with a radius of 10 needs 7 segments to meet a tolerance of
+        // 1.0.
+        assert_eq!(num_vertices(&coarse)?, 7);
+        assert!(num_vertices(&shape)? > 7);
+
+        assert_eq!(coarse.bounding_volume(), shape.bounding_volume());
+
+        Ok(())
+    }
+
+    #[test]
+    fn inner_override_takes_precedence() -> Result<(), Error> {
+        let shape: fj::Shape =
+            circle().with_tolerance(1.).with_tolerance(0.001).into();
+
+        assert_eq!(num_vertices(&shape)?, 7);
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_tolerance() {
+        let shape: fj::Shape = circle().with_tolerance(0.).into();
+        let err = ShapeProcessor::default()
+            .process(&shape)
+            .err()
+            .expect("Tolerance is invalid");
+
+        assert_eq!(err.error_code(), "tolerance");
+    }
+}
@@ -0,0 +1,111 @@
+//! Helpers for the instrumentation with the `tracing` crate
+//!
+//! Only available, if the `tracing` feature is enabled. The spans themselves
+//! are entered with the macros from `fj_interop::trace`.
+
+/// The kind of operation a shape is, for naming its span
+pub(crate) fn kind(shape: &fj::Shape) -> &'static str {
+    match shape {
+        fj::Shape::Annotated(_) => "annotated",
+        fj::Shape::Assembly(_) => "assembly",
+        fj::Shape::Difference(_) => "difference",
+        fj::Shape::Fillet(_) => "fillet",
+        fj::Shape::Group(_) => "group",
+        fj::Shape::ImportedMesh(_) => "imported_mesh",
+        fj::Shape::Materialized(_) => "materialized",
+        fj::Shape::Mirror(_) => "mirror",
+        fj::Shape::Part(_) => "part",
+        fj::Shape::Revolve(_) => "revolve",
+        fj::Shape::Sectioned(_) => "sectioned",
+        fj::Shape::Shape2d(_) => "shape_2d",
+        fj::Shape::Shell(_) => "shell",
+        fj::Shape::Sweep(_) => "sweep",
+        fj::Shape::Tolerance(_) => "tolerance",
+        fj::Shape::Transform(_) => "transform",
+    }
+}
+
+/// The label of a shape, if it has one
+pub(crate) fn label(shape: &fj::Shape) -> Option<&str> {
+    match shape {
+        fj::Shape::Part(part) => part.label(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use fj::syntax::{Sketch as _, Sweep as _};
+    use tracing::{span, Subscriber};
+    use tracing_subscriber::{
+        layer::{Context, SubscriberExt as _},
+        Layer, Registry,
+    };
+
+    use crate::{shape_processor::ShapeProcessor, Error};
+
+    #[test]
+    fn spans() -> Result<(), Error> {
+        let shape: fj::Shape = [[0., 0.], [1., 0.], [1., 1.], [0., 1.]]
+            .sketch()
+            .sweep([0., 0., 1.])
+            .into();
+
+        let capture = Capture::default();
+        let subscriber = Registry::default().with(capture.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            ShapeProcessor::default().process(&shape)
+        })?;
+
+        let spans = capture.0.lock().expect("Lock is not poisoned");
+        let has_span = |name: &str, fields: &[&str]| {
+            spans.iter().any(|(span, span_fields)| {
+                span == name
+                    && fields
+                        .iter()
+                        .all(|field| span_fields.iter().any(|f| f == field))
+            })
+        };
+
+        assert!(has_span("process", &["tolerance", "triangles", "warnings"]));
+        assert!(has_span("shape", &["kind", "label", "faces"]));
+        assert!(has_span("validate", &["faces"]));
+        assert!(has_span(
+            "triangulate",
+            &["faces", "tolerance", "triangles"]
+        ));
+        assert!(has_span("triangulate_face", &["index"]));
+
+        Ok(())
+    }
+
+    /// Captures the names and fields of all spans that are created
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<CapturedSpan>>>);
+
+    /// The name of a span, and the names of its fields
+    type CapturedSpan = (String, Vec<&'static str>);
+
+    impl<S: Subscriber> Layer<S> for Capture {
+        fn on_new_span(
+            &self,
+            attributes: &span::Attributes<'_>,
+            _: &span::Id,
+            _: Context<'_, S>,
+        ) {
+            let name = attributes.metadata().name().to_owned();
+            let fields = attributes
+                .fields()
+                .iter()
+                .map(|field| field.name())
+                .collect();
+
+            self.0
+                .lock()
+                .expect("Lock is not poisoned")
+                .push((name, fields));
+        }
+    }
+}
@@ -1,12 +1,12 @@
 use fj_interop::debug::DebugInfo;
 use fj_kernel::{
-    algorithms::{transform_faces, Tolerance},
+    algorithms::{transform_faces, ErrorBudget, Tolerance},
     objects::Face,
-    validation::{validate, Validated, ValidationConfig, ValidationError},
+    validation::{validate_intermediate, Validated, ValidationConfig},
 };
-use fj_math::{Aabb, Transform, Vector};
+use fj_math::{Aabb, Transform};
 
-use super::Shape;
+use crate::{Error, Shape};
 
 impl Shape for fj::Transform {
     type Brep = Vec<Face>;
@@ -15,16 +15,15 @@ impl Shape for fj::Transform {
         &self,
         config: &ValidationConfig,
         tolerance: Tolerance,
+        error_budget: &mut ErrorBudget,
         debug_info: &mut DebugInfo,
-    ) -> Result<Validated<Self::Brep>, ValidationError> {
-        let mut faces = self
+    ) -> Result<Validated<Self::Brep>, Error> {
+        let shape = self
             .shape
-            .compute_brep(config, tolerance, debug_info)?
-            .into_inner();
+            .compute_brep(config, tolerance, error_budget, debug_info)
+            .map_err(|err| err.in_shape("shape"))?;
 
-        transform_faces(&mut faces, &make_transform(self));
-
-        validate(faces, config)
+        apply_transform(self, shape, config)
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
@@ -32,8 +31,29 @@ impl Shape for fj::Transform {
     }
 }
 
+/// Apply a transform to the boundary representation of its shape
+///
+/// Also used to transform shapes that were computed earlier. See
+/// [`BrepCache`](crate::rebuild::BrepCache).
+pub(crate) fn apply_transform(
+    transform: &fj::Transform,
+    shape: Validated<Vec<Face>>,
+    config: &ValidationConfig,
+) -> Result<Validated<Vec<Face>>, Error> {
+    let mut faces = shape.into_inner();
+
+    // `fj::Transform` only supports rigid transforms (rotation and
+    // translation), which don't introduce any error. So there's nothing to
+    // record in the error budget here.
+    transform_faces(&mut faces, &make_transform(transform));
+
+    Ok(validate_intermediate(faces, config)?)
+}
+
 fn make_transform(transform: &fj::Transform) -> Transform {
-    let axis = Vector::from(transform.axis).normalize();
     Transform::translation(transform.offset)
-        * Transform::rotation(axis * transform.angle.rad())
+        * Transform::rotation_from_axis_angle(
+            transform.axis,
+            transform.angle.rad(),
+        )
 }
@@ -0,0 +1,371 @@
+use std::{collections::BTreeMap, fmt};
+
+use fj_interop::annotation::AnnotationKind;
+use fj_kernel::algorithms::FaceSelector;
+use fj_math::{Aabb, Point, Scalar};
+
+use crate::Evaluation;
+
+/// A failed assertion about an evaluated shape
+///
+/// The message contains the actual values, and if applicable, points that
+/// witness the failure.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssertionFailure {
+    /// The message that describes the failure
+    pub message: String,
+}
+
+impl AssertionFailure {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for AssertionFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for AssertionFailure {}
+
+/// Assert that the volume of a shape is near the expected value
+///
+/// See [`check_volume_near`].
+#[track_caller]
+pub fn assert_volume_near(
+    evaluation: &Evaluation,
+    expected: f64,
+    rel_tol: f64,
+) {
+    unwrap(check_volume_near(evaluation, expected, rel_tol));
+}
+
+/// Check that the volume of a shape is near the expected value
+///
+/// `rel_tol` is relative to `expected`. The volume is computed from the
+/// triangle mesh, so for curved shapes, `rel_tol` must account for the
+/// tolerance of the approximation.
+pub fn check_volume_near(
+    evaluation: &Evaluation,
+    expected: f64,
+    rel_tol: f64,
+) -> Result<(), AssertionFailure> {
+    let actual = evaluation.processed().summary.volume.into_f64();
+    let error = (actual - expected).abs();
+
+    if error <= expected.abs() * rel_tol {
+        return Ok(());
+    }
+
+    Err(AssertionFailure::new(format!(
+        "Volume is {actual}, expected {expected} (relative error {}, allowed \
+        {rel_tol})",
+        error / expected.abs()
+    )))
+}
+
+/// Assert that a shape fits within a bounding box
+///
+/// See [`check_fits_within`].
+#[track_caller]
+pub fn assert_fits_within(evaluation: &Evaluation, aabb: &Aabb<3>) {
+    unwrap(check_fits_within(evaluation, aabb));
+}
+
+/// Check that a shape fits within a bounding box
+///
+/// Every vertex of the triangle mesh must be within `aabb`. If not, the vertex
+/// that is furthest outside is reported as a witness.
+pub fn check_fits_within(
+    evaluation: &Evaluation,
+    aabb: &Aabb<3>,
+) -> Result<(), AssertionFailure> {
+    let processed = evaluation.processed();
+
+    let outside = processed
+        .mesh
+        .vertices()
+        .map(|vertex| (distance_outside(aabb, vertex), vertex))
+        .filter(|&(distance, _)| distance > Scalar::ZERO)
+        .max_by_key(|&(distance, _)| distance);
+
+    match outside {
+        None => Ok(()),
+        Some((distance, witness)) => Err(AssertionFailure::new(format!(
+            "Shape doesn't fit within {:?} to {:?}: its bounding box is {:?} \
+            to {:?}; vertex {:?} is {distance} outside",
+            aabb.min,
+            aabb.max,
+            processed.summary.aabb.min,
+            processed.summary.aabb.max,
+            witness,
+        ))),
+    }
+}
+
+/// Assert that the triangle mesh of a shape is watertight
+///
+/// See [`check_watertight`].
+#[track_caller]
+pub fn assert_watertight(evaluation: &Evaluation) {
+    unwrap(check_watertight(evaluation));
+}
+
+/// Check that the triangle mesh of a shape is watertight
+///
+/// Every edge of the mesh must be shared by exactly two triangles. If not, the
+/// number of open edges is reported, along with one of them as a witness.
+pub fn check_watertight(
+    evaluation: &Evaluation,
+) -> Result<(), AssertionFailure> {
+    let mut edges = BTreeMap::new();
+    for triangle in evaluation.processed().mesh.triangles() {
+        let [a, b, c] = triangle.points;
+        for [a, b] in [[a, b], [b, c], [c, a]] {
+            let edge = if a < b { [a, b] } else { [b, a] };
+            *edges.entry(edge).or_insert(0) += 1;
+        }
+    }
+
+    let mut open_edges = edges.into_iter().filter(|&(_, uses)| uses != 2);
+
+    match open_edges.next() {
+        None => Ok(()),
+        Some(([a, b], uses)) => Err(AssertionFailure::new(format!(
+            "Mesh is not watertight: {} open edges; for example, the edge from \
+            {a:?} to {b:?} is shared by {uses} triangles",
+            open_edges.count() + 1,
+        ))),
+    }
+}
+
+/// Assert the distance between two datum points of a shape
+///
+/// See [`check_distance_between`].
+#[track_caller]
+pub fn assert_distance_between(
+    evaluation: &Evaluation,
+    name_a: &str,
+    name_b: &str,
+    expected: f64,
+    tol: f64,
+) {
+    unwrap(check_distance_between(
+        evaluation, name_a, name_b, expected, tol,
+    ));
+}
+
+/// Check the distance between two datum points of a shape
+///
+/// Datum points are note annotations (see [`fj::Annotation::Note`]). They are
+/// identified by their text. `tol` is an absolute tolerance.
+pub fn check_distance_between(
+    evaluation: &Evaluation,
+    name_a: &str,
+    name_b: &str,
+    expected: f64,
+    tol: f64,
+) -> Result<(), AssertionFailure> {
+    let a = datum_point(evaluation, name_a)?;
+    let b = datum_point(evaluation, name_b)?;
+
+    let actual = Point::distance(&a, &b).into_f64();
+    if (actual - expected).abs() <= tol {
+        return Ok(());
+    }
+
+    Err(AssertionFailure::new(format!(
+        "Distance between `{name_a}` at {a:?} and `{name_b}` at {b:?} is \
+        {actual}, expected {expected} (tolerance {tol})"
+    )))
+}
+
+/// Assert the number of faces that a selector selects
+///
+/// See [`check_face_count`].
+#[track_caller]
+pub fn assert_face_count(
+    evaluation: &Evaluation,
+    selector: &FaceSelector,
+    count: usize,
+) {
+    unwrap(check_face_count(evaluation, selector, count));
+}
+
+/// Check the number of faces that a selector selects
+///
+/// If the number doesn't match, the indices of the selected faces are
+/// reported.
+pub fn check_face_count(
+    evaluation: &Evaluation,
+    selector: &FaceSelector,
+    count: usize,
+) -> Result<(), AssertionFailure> {
+    let selected: Vec<_> = evaluation
+        .faces()
+        .iter()
+        .enumerate()
+        .filter(|&(index, face)| selector.selects(index, face))
+        .map(|(index, _)| index)
+        .collect();
+
+    if selected.len() == count {
+        return Ok(());
+    }
+
+    Err(AssertionFailure::new(format!(
+        "Selector {selector:?} selects {} faces, expected {count}; selected \
+        faces: {selected:?}",
+        selected.len(),
+    )))
+}
+
+#[track_caller]
+fn unwrap(result: Result<(), AssertionFailure>) {
+    if let Err(failure) = result {
+        panic!("{failure}");
+    }
+}
+
+fn datum_point(
+    evaluation: &Evaluation,
+    name: &str,
+) -> Result<Point<3>, AssertionFailure> {
+    let notes = evaluation
+        .processed()
+        .annotations
+        .iter()
+        .filter(|annotation| annotation.kind == AnnotationKind::Note);
+
+    let mut names = Vec::new();
+    for note in notes {
+        if note.text == name {
+            return Ok(note.position);
+        }
+        names.push(note.text.as_str());
+    }
+
+    Err(AssertionFailure::new(format!(
+        "No datum point named `{name}`; available: {names:?}"
+    )))
+}
+
+fn distance_outside(aabb: &Aabb<3>, point: Point<3>) -> Scalar {
+    let mut distance = Scalar::ZERO;
+
+    for ((min, max), coord) in aabb
+        .min
+        .coords
+        .components
+        .into_iter()
+        .zip(aabb.max.coords.components)
+        .zip(point.coords.components)
+    {
+        let outside = (min - coord).max(coord - max).max(Scalar::ZERO);
+        distance = distance.max(outside);
+    }
+
+    distance
+}
+
+#[cfg(test)]
+mod tests {
+    use fj::syntax::*;
+    use fj_kernel::algorithms::FaceSelector;
+    use fj_math::{Aabb, Point};
+
+    use crate::{evaluate, Evaluation};
+
+    use super::{
+        check_distance_between, check_face_count, check_fits_within,
+        check_volume_near, check_watertight,
+    };
+
+    #[test]
+    fn volume_near() {
+        let evaluation = cube();
+
+        assert!(check_volume_near(&evaluation, 8., 1e-9).is_ok());
+
+        let actual = evaluation.processed().summary.volume;
+        let failure = check_volume_near(&evaluation, 9., 0.01).unwrap_err();
+        assert!(failure.message.contains(&format!("Volume is {actual}")));
+    }
+
+    #[test]
+    fn fits_within() {
+        let evaluation = cube();
+
+        let aabb = Aabb {
+            min: Point::from([0., 0., 0.]),
+            max: Point::from([2., 2., 2.]),
+        };
+        assert!(check_fits_within(&evaluation, &aabb).is_ok());
+
+        let aabb = Aabb {
+            min: Point::from([0., 0., 0.]),
+            max: Point::from([2., 2., 1.5]),
+        };
+        let failure = check_fits_within(&evaluation, &aabb).unwrap_err();
+        assert!(failure.message.contains("is 0.5 outside"));
+    }
+
+    #[test]
+    fn watertight() {
+        assert!(check_watertight(&cube()).is_ok());
+
+        let square = [[0., 0.], [1., 0.], [1., 1.], [0., 1.]].sketch();
+        let failure = check_watertight(&evaluate(square)).unwrap_err();
+        assert!(failure.message.contains("4 open edges"));
+    }
+
+    #[test]
+    fn distance_between() {
+        let evaluation = evaluate(cube_shape().annotate(vec![
+            fj::Annotation::Note {
+                anchor: [0., 0., 0.],
+                text: "a".into(),
+            },
+            fj::Annotation::Note {
+                anchor: [0., 0., 2.],
+                text: "b".into(),
+            },
+        ]));
+
+        assert!(check_distance_between(&evaluation, "a", "b", 2., 1e-9).is_ok());
+
+        let failure = check_distance_between(&evaluation, "a", "b", 3., 1e-9)
+            .unwrap_err();
+        assert!(failure.message.contains("is 2, expected 3"));
+
+        let failure = check_distance_between(&evaluation, "a", "c", 2., 1e-9)
+            .unwrap_err();
+        assert!(failure.message.contains("No datum point named `c`"));
+    }
+
+    #[test]
+    fn face_count() {
+        let evaluation = cube();
+
+        assert!(check_face_count(&evaluation, &FaceSelector::All, 6).is_ok());
+
+        let top = FaceSelector::normal([0., 0., 1.]);
+        let failure = check_face_count(&evaluation, &top, 2).unwrap_err();
+        assert!(failure.message.contains("selects 1 faces"));
+    }
+
+    fn cube() -> Evaluation {
+        evaluate(cube_shape())
+    }
+
+    fn cube_shape() -> fj::Shape {
+        [[0., 0.], [2., 0.], [2., 2.], [0., 2.]]
+            .sketch()
+            .sweep([0., 0., 2.])
+            .into()
+    }
+}
@@ -0,0 +1,83 @@
+use fj_interop::{debug::DebugInfo, processed_shape::ProcessedShape};
+use fj_kernel::{
+    algorithms::{ErrorBudget, Tolerance},
+    objects::Face,
+    validation::ValidationConfig,
+};
+use fj_operations::{shape_processor::ShapeProcessor, Error, Shape as _};
+
+/// A shape that has been evaluated for testing
+///
+/// Contains both the processed shape, as it would be displayed or exported, and
+/// the faces of its boundary representation.
+pub struct Evaluation {
+    processed: ProcessedShape,
+    faces: Vec<Face>,
+    tolerance: Tolerance,
+}
+
+impl Evaluation {
+    /// Access the processed shape
+    pub fn processed(&self) -> &ProcessedShape {
+        &self.processed
+    }
+
+    /// Access the faces of the shape's boundary representation
+    pub fn faces(&self) -> &[Face] {
+        &self.faces
+    }
+
+    /// Access the tolerance that the shape was evaluated with
+    pub fn tolerance(&self) -> Tolerance {
+        self.tolerance
+    }
+}
+
+/// Evaluate a shape, using the default tolerance
+///
+/// # Panics
+///
+/// Panics, if the shape can't be processed. Use [`try_evaluate`] to handle
+/// that case.
+#[track_caller]
+pub fn evaluate(shape: impl Into<fj::Shape>) -> Evaluation {
+    match try_evaluate(shape, None) {
+        Ok(evaluation) => evaluation,
+        Err(err) => panic!("Failed to evaluate shape: {err}"),
+    }
+}
+
+/// Evaluate a shape
+///
+/// If `tolerance` is `None`, a tolerance is derived from the size of the shape,
+/// as [`ShapeProcessor`] does.
+pub fn try_evaluate(
+    shape: impl Into<fj::Shape>,
+    tolerance: Option<Tolerance>,
+) -> Result<Evaluation, Error> {
+    let shape = shape.into();
+
+    let processor = ShapeProcessor {
+        tolerance,
+        ..ShapeProcessor::default()
+    };
+    let processed = processor.process(&shape)?;
+
+    // The processed shape records the tolerance that was actually used.
+    let tolerance = Tolerance::from_scalar(processed.summary.tolerance)?;
+
+    let faces = shape
+        .compute_brep(
+            &ValidationConfig::default(),
+            tolerance,
+            &mut ErrorBudget::new(),
+            &mut DebugInfo::new(),
+        )?
+        .into_inner();
+
+    Ok(Evaluation {
+        processed,
+        faces,
+        tolerance,
+    })
+}
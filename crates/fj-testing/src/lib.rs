@@ -0,0 +1,45 @@
+//! # Fornjot Testing Support
+//!
+//! This library is part of the [Fornjot] ecosystem. Fornjot is an open-source,
+//! code-first CAD application; and collection of libraries that make up the CAD
+//! application, but can be used independently.
+//!
+//! This library helps model authors to test their models with `cargo test`,
+//! without opening a window. Add it as a dev-dependency, [`evaluate`] the
+//! shape that the model creates, and check its geometric properties:
+//!
+//! ``` rust
+//! use fj::syntax::*;
+//!
+//! let cube = [[0., 0.], [1., 0.], [1., 1.], [0., 1.]]
+//!     .sketch()
+//!     .sweep([0., 0., 1.]);
+//!
+//! let evaluation = fj_testing::evaluate(cube);
+//! fj_testing::assert_volume_near(&evaluation, 1., 1e-9);
+//! fj_testing::assert_watertight(&evaluation);
+//! ```
+//!
+//! Each `assert_*` function panics with a message that contains the actual
+//! values, if the assertion fails. Each has a `check_*` counterpart that
+//! returns the failure instead.
+//!
+//! [Fornjot]: https://www.fornjot.app/
+
+#![warn(missing_docs)]
+
+mod assertions;
+mod evaluation;
+
+pub use fj_kernel::algorithms::FaceSelector;
+pub use fj_math::{Aabb, Point};
+
+pub use self::{
+    assertions::{
+        assert_distance_between, assert_face_count, assert_fits_within,
+        assert_volume_near, assert_watertight, check_distance_between,
+        check_face_count, check_fits_within, check_volume_near,
+        check_watertight, AssertionFailure,
+    },
+    evaluation::{evaluate, try_evaluate, Evaluation},
+};
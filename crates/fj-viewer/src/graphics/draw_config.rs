@@ -1,5 +1,9 @@
 //! High level configuration for graphics rendering
 
+use fj_interop::mesh::FaceColoring;
+
+use super::vertices::ColorBoundary;
+
 /// High level configuration for rendering the active model
 #[derive(Debug)]
 pub struct DrawConfig {
@@ -9,6 +13,16 @@ pub struct DrawConfig {
     pub draw_mesh: bool,
     /// Toggle for displaying model debug information
     pub draw_debug: bool,
+    /// Toggle for displaying the annotations of the model
+    pub draw_annotations: bool,
+    /// How colors are handled where faces of different color meet
+    ///
+    /// Takes effect the next time the geometry is updated.
+    pub color_boundary: ColorBoundary,
+    /// How faces are colored, to tell them apart while debugging
+    ///
+    /// Takes effect the next time the geometry is updated.
+    pub face_coloring: FaceColoring,
 }
 
 impl Default for DrawConfig {
@@ -17,6 +31,9 @@ impl Default for DrawConfig {
             draw_model: true,
             draw_mesh: false,
             draw_debug: false,
+            draw_annotations: true,
+            color_boundary: ColorBoundary::default(),
+            face_coloring: FaceColoring::default(),
         }
     }
 }
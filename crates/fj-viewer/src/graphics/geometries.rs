@@ -9,6 +9,7 @@ use super::vertices::{Vertex, Vertices};
 pub struct Geometries {
     pub mesh: Geometry,
     pub lines: Geometry,
+    pub annotations: Geometry,
     pub aabb: Aabb<3>,
 }
 
@@ -17,13 +18,24 @@ impl Geometries {
         device: &wgpu::Device,
         mesh: &Vertices,
         debug_info: &Vertices,
+        annotations: &Vertices,
         aabb: Aabb<3>,
     ) -> Self {
         let mesh = Geometry::new(device, mesh.vertices(), mesh.indices());
         let lines =
             Geometry::new(device, debug_info.vertices(), debug_info.indices());
+        let annotations = Geometry::new(
+            device,
+            annotations.vertices(),
+            annotations.indices(),
+        );
 
-        Self { mesh, lines, aabb }
+        Self {
+            mesh,
+            lines,
+            annotations,
+            aabb,
+        }
     }
 }
 
@@ -7,6 +7,7 @@ mod geometries;
 mod pipelines;
 mod renderer;
 mod shaders;
+mod simplify;
 mod transform;
 mod uniforms;
 mod vertices;
@@ -14,6 +15,8 @@ mod vertices;
 pub use self::{
     draw_config::DrawConfig,
     renderer::{DrawError, InitError, Renderer},
+    simplify::simplify_for_viewing,
+    vertices::ColorBoundary,
 };
 
 const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
@@ -1,5 +1,6 @@
 use std::{io, mem::size_of};
 
+use fj_interop::{annotation::Annotation, debug::DebugInfo, mesh::Mesh};
 use fj_math::{Aabb, Point};
 use thiserror::Error;
 use tracing::debug;
@@ -12,9 +13,15 @@ use crate::{
 };
 
 use super::{
-    config_ui::ConfigUi, draw_config::DrawConfig, drawables::Drawables,
-    geometries::Geometries, pipelines::Pipelines, transform::Transform,
-    uniforms::Uniforms, vertices::Vertices, DEPTH_FORMAT,
+    config_ui::ConfigUi,
+    draw_config::DrawConfig,
+    drawables::Drawables,
+    geometries::Geometries,
+    pipelines::Pipelines,
+    transform::Transform,
+    uniforms::Uniforms,
+    vertices::{ColorBoundary, Vertices},
+    DEPTH_FORMAT,
 };
 
 #[derive(Default)]
@@ -56,6 +63,12 @@ pub struct Renderer {
     geometries: Geometries,
     pipelines: Pipelines,
 
+    /// The text of the annotations, and where to display it
+    labels: Vec<(Point<3>, String)>,
+
+    /// Describes how much of the debug info is displayed
+    debug_summary: String,
+
     config_ui: ConfigUi,
 
     /// State required for integration with `egui`.
@@ -209,6 +222,7 @@ impl Renderer {
             &device,
             &Vertices::empty(),
             &Vertices::empty(),
+            &Vertices::empty(),
             Aabb {
                 min: Point::from([0.0, 0.0, 0.0]),
                 max: Point::from([0.0, 0.0, 0.0]),
@@ -251,6 +265,9 @@ impl Renderer {
             geometries,
             pipelines,
 
+            labels: Vec::new(),
+            debug_summary: String::new(),
+
             config_ui,
 
             egui: EguiState {
@@ -263,13 +280,45 @@ impl Renderer {
     }
 
     /// Updates the geometry of the model being rendered.
+    ///
+    /// # Arguments
+    /// - `annotations`: The annotations of the model. Their leader lines are
+    ///   rendered with the model, their text is displayed on top of it.
+    /// - `color_boundary`: Defines how colors are handled where faces of
+    ///   different color meet.
     pub fn update_geometry(
         &mut self,
-        mesh: Vertices,
-        lines: Vertices,
+        mesh: &Mesh<Point<3>>,
+        debug_info: &DebugInfo,
+        annotations: &[Annotation],
         aabb: Aabb<3>,
+        color_boundary: ColorBoundary,
     ) {
-        self.geometries = Geometries::new(&self.device, &mesh, &lines, aabb);
+        let mesh = Vertices::from_mesh(mesh, color_boundary);
+        let lines = Vertices::from(debug_info);
+
+        let mut leaders = Vertices::empty();
+        for annotation in annotations {
+            leaders.push_line_set(&annotation.leaders);
+        }
+
+        // Debug info might be sampled or incomplete. Make that visible, so the
+        // rendered checks are not mistaken for all of them.
+        let num_recorded = debug_info.triangle_edge_checks().count();
+        self.debug_summary = format!(
+            "Showing {} of {} checks ({} dropped)",
+            num_recorded,
+            debug_info.num_triangle_edge_checks(),
+            debug_info.num_dropped(),
+        );
+
+        self.labels = annotations
+            .iter()
+            .map(|annotation| (annotation.position, annotation.text.clone()))
+            .collect();
+
+        self.geometries =
+            Geometries::new(&self.device, &mesh, &lines, &leaders, aabb);
     }
 
     /// Resizes the render surface.
@@ -344,6 +393,14 @@ impl Renderer {
                 &self.bind_group,
             );
         }
+        if config.draw_annotations {
+            drawables.annotations.draw(
+                &mut encoder,
+                &color_view,
+                &self.depth_view,
+                &self.bind_group,
+            );
+        }
 
         if self.egui.options.show_original_ui {
             self.config_ui
@@ -396,6 +453,36 @@ impl Renderer {
         let egui_input = self.egui.winit_state.take_egui_input(window);
         self.egui.context.begin_frame(egui_input);
 
+        if config.draw_annotations {
+            let screen_rect = self.egui.context.input().screen_rect();
+            let painter = self.egui.context.layer_painter(egui::LayerId::new(
+                egui::Order::Background,
+                egui::Id::new("fj-annotations"),
+            ));
+
+            for (position, text) in &self.labels {
+                if let Some([x, y]) =
+                    uniforms.transform.project_point(*position)
+                {
+                    // Normalized device coordinates have their origin in the
+                    // center, with y pointing up.
+                    let pos = screen_rect.min
+                        + egui::vec2(
+                            (x + 1.) / 2. * screen_rect.width(),
+                            (1. - y) / 2. * screen_rect.height(),
+                        );
+
+                    painter.text(
+                        pos,
+                        egui::Align2::CENTER_BOTTOM,
+                        text,
+                        egui::FontId::default(),
+                        egui::Color32::BLACK,
+                    );
+                }
+            }
+        }
+
         fn get_bbox_size_text(aabb: &Aabb<3>) -> String {
             /* Render size of model bounding box */
             let bbsize = aabb.size().components;
@@ -418,6 +505,12 @@ impl Renderer {
                     .on_hover_text_at_pointer("Toggle with 2");
                 ui.checkbox(&mut config.draw_debug, "Render debug")
                     .on_hover_text_at_pointer("Toggle with 3");
+                if config.draw_debug {
+                    ui.label(self.debug_summary.as_str());
+                }
+                ui.checkbox(&mut config.draw_annotations, "Render annotations");
+                ui.label(format!("Face colors: {:?}", config.face_coloring))
+                    .on_hover_text_at_pointer("Change with 4");
                 ui.checkbox(
                     &mut self.egui.options.show_original_ui,
                     "Render original UI",
@@ -0,0 +1,373 @@
+use std::collections::BTreeSet;
+
+use fj::Angle;
+use fj_interop::{half_edge::HalfEdgeMesh, mesh::Mesh};
+use fj_math::{Point, Scalar, Vector};
+
+/// Simplify a mesh for display in the viewer
+///
+/// Greedily collapses edges of the mesh, as long as the normal of every
+/// affected triangle deviates from the normals of the original triangles it
+/// replaces by no more than `max_normal_deviation`. This reduces the number of
+/// triangles in curved regions that were tessellated more finely than
+/// necessary for display, while keeping the overall look of the model.
+///
+/// Vertices on mesh boundary edges (edges with only one triangle) and on face
+/// boundaries are never collapsed, so face outlines stay crisp. Since the mesh
+/// doesn't carry any information about faces, triangles of different colors
+/// are assumed to belong to different faces.
+///
+/// Since an edge collapse merges one vertex into another, the resulting mesh
+/// only contains vertices from the original mesh.
+///
+/// This is meant for viewer meshes only. The result does not honor the
+/// tolerance that the original mesh was created with and must not be used for
+/// export.
+pub fn simplify_for_viewing(
+    mesh: &Mesh<Point<3>>,
+    max_normal_deviation: Angle,
+) -> Mesh<Point<3>> {
+    let min_cos = Scalar::from_f64(max_normal_deviation.rad().cos());
+
+    let mut simplifier = Simplifier::new(mesh);
+    simplifier.lock_vertices();
+
+    loop {
+        let mut collapsed_any = false;
+
+        for u in 0..simplifier.locked.len() {
+            if simplifier.locked[u]
+                || simplifier.mesh.triangles_around(u).is_empty()
+            {
+                continue;
+            }
+
+            let neighbors: BTreeSet<_> =
+                simplifier.mesh.one_ring(u).into_iter().collect();
+            for v in neighbors {
+                if simplifier.try_collapse(u, v, min_cos) {
+                    collapsed_any = true;
+                    break;
+                }
+            }
+        }
+
+        if !collapsed_any {
+            break;
+        }
+    }
+
+    let mut simplified = simplifier.mesh.to_mesh();
+    simplified.set_double_sided(mesh.is_double_sided());
+
+    simplified
+}
+
+struct Simplifier {
+    mesh: HalfEdgeMesh,
+
+    /// The normals of all original triangles each triangle stands in for
+    normals: Vec<Vec<Vector<3>>>,
+
+    locked: Vec<bool>,
+}
+
+impl Simplifier {
+    fn new(mesh: &Mesh<Point<3>>) -> Self {
+        // Triangles in a mesh span an area, so they always have a normal. If
+        // that's not the case for some reason, we fall back to an empty list
+        // of normals, which will prevent any collapse that affects this
+        // triangle.
+        let normals = mesh
+            .triangles()
+            .map(|triangle| normal(triangle.points).into_iter().collect())
+            .collect();
+
+        let locked = vec![false; mesh.vertices().count()];
+
+        Self {
+            mesh: HalfEdgeMesh::from_mesh(mesh),
+            normals,
+            locked,
+        }
+    }
+
+    /// Lock all vertices that must not be collapsed
+    fn lock_vertices(&mut self) {
+        for vertex in self.mesh.vertices() {
+            // Vertex is on a mesh boundary, or on a non-manifold edge.
+            if self.mesh.is_boundary_vertex(vertex)
+                || !self.mesh.is_manifold_vertex(vertex)
+            {
+                self.locked[vertex] = true;
+            }
+        }
+
+        for half_edge in self.mesh.edges() {
+            let opposite = match self.mesh.opposite(half_edge) {
+                Some(opposite) => opposite,
+                None => continue,
+            };
+
+            let [a, b] = [half_edge, opposite].map(|half_edge| {
+                self.mesh
+                    .triangle(half_edge / 3)
+                    .expect("Half-edge belongs to a triangle")
+            });
+
+            // Edge is on a face boundary.
+            if a.color != b.color {
+                let vertices =
+                    [self.mesh.origin(half_edge), self.mesh.target(half_edge)];
+                for vertex in vertices {
+                    self.locked[vertex] = true;
+                }
+            }
+        }
+    }
+
+    /// Collapse vertex `u` into vertex `v`, if that is permissible
+    ///
+    /// Returns `true`, if the collapse has been performed.
+    fn try_collapse(&mut self, u: usize, v: usize, min_cos: Scalar) -> bool {
+        let half_edge = match self.mesh.half_edge(u, v) {
+            Some(half_edge) => half_edge,
+            // `u` and `v` don't share an edge.
+            None => return false,
+        };
+
+        let (removed, modified): (Vec<_>, Vec<_>) =
+            self.mesh.triangles_around(u).into_iter().partition(|&i| {
+                self.mesh
+                    .triangle_vertices(i)
+                    .expect("Triangle around vertex exists")
+                    .contains(&v)
+            });
+
+        // Each removed triangle is absorbed by the modified triangle that
+        // shares its other edge at `u`. That triangle stands in for it from now
+        // on, and must not deviate from its normals.
+        let opposite_vertices: Vec<_> = removed
+            .iter()
+            .map(|&i| {
+                self.mesh
+                    .triangle_vertices(i)
+                    .expect("Triangle around vertex exists")
+                    .into_iter()
+                    .find(|&vertex| vertex != u && vertex != v)
+            })
+            .collect();
+
+        let mut absorbed_normals = Vec::new();
+        for &i in &modified {
+            let vertices = self
+                .mesh
+                .triangle_vertices(i)
+                .expect("Triangle around vertex exists");
+
+            let absorbed: Vec<_> = removed
+                .iter()
+                .zip(&opposite_vertices)
+                .filter(|(_, opposite)| {
+                    opposite.map_or(false, |vertex| vertices.contains(&vertex))
+                })
+                .flat_map(|(&removed, _)| self.normals[removed].iter().copied())
+                .collect();
+
+            let points = vertices
+                .map(|vertex| if vertex == u { v } else { vertex })
+                .map(|vertex| self.mesh.point(vertex));
+
+            let normal = match normal(points) {
+                Some(normal) => normal,
+                None => return false,
+            };
+
+            let deviates = self.normals[i]
+                .iter()
+                .chain(&absorbed)
+                .any(|reference| normal.dot(reference) < min_cos);
+            if deviates {
+                return false;
+            }
+
+            absorbed_normals.push(absorbed);
+        }
+
+        // The half-edge mesh makes sure the collapse doesn't change the
+        // topology of the mesh.
+        if self.mesh.collapse_edge(half_edge).is_none() {
+            return false;
+        }
+
+        for (i, absorbed) in modified.into_iter().zip(absorbed_normals) {
+            self.normals[i].extend(absorbed);
+        }
+
+        true
+    }
+}
+
+fn normal(points: [Point<3>; 3]) -> Option<Vector<3>> {
+    let [a, b, c] = points;
+    let normal = (b - a).cross(&(c - a));
+
+    if normal.magnitude() == Scalar::ZERO {
+        return None;
+    }
+
+    Some(normal.normalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use fj::Angle;
+    use fj_interop::mesh::Mesh;
+    use fj_math::{Point, Scalar, Vector};
+
+    use super::simplify_for_viewing;
+
+    #[test]
+    fn cylinder() {
+        let radius = 1.;
+        let height = 2.;
+        let segments = 72;
+        let rings = 20;
+
+        let side = [255, 0, 0, 255];
+        let cap = [0, 0, 255, 255];
+
+        let point = |segment: usize, ring: usize| {
+            let angle = Scalar::TWO * Scalar::PI * (segment % segments) as f64
+                / segments as f64;
+            let (sin, cos) = angle.sin_cos();
+            let z = height * ring as f64 / rings as f64;
+
+            Point::from([cos * radius, sin * radius, Scalar::from(z)])
+        };
+
+        let mut mesh = Mesh::new();
+
+        for i in 0..segments {
+            for j in 0..rings {
+                let [a, b, c, d] = [
+                    point(i, j),
+                    point(i + 1, j),
+                    point(i + 1, j + 1),
+                    point(i, j + 1),
+                ];
+
+                mesh.push_triangle([a, b, c], side);
+                mesh.push_triangle([a, c, d], side);
+            }
+
+            // Caps
+            let bottom = Point::from([0., 0., 0.]);
+            let top = Point::from([0., 0., height]);
+            mesh.push_triangle([bottom, point(i + 1, 0), point(i, 0)], cap);
+            mesh.push_triangle(
+                [top, point(i, rings), point(i + 1, rings)],
+                cap,
+            );
+        }
+
+        let simplified = simplify_for_viewing(&mesh, Angle::from_deg(5.));
+
+        let num_original = mesh.triangles().count();
+        let num_simplified = simplified.triangles().count();
+        assert!(num_simplified * 2 <= num_original);
+
+        // The silhouette of the cylinder, viewed along any direction
+        // perpendicular to its axis, must not change by more than the
+        // tolerance the cylinder was tessellated with. The original edges
+        // deviate by exactly that much, so leave some room for rounding.
+        let tolerance = Scalar::from_f64(radius)
+            * (Scalar::ONE - (Scalar::PI / segments as f64).cos())
+            + Scalar::from_f64(1e-12);
+
+        for i in 0..360 {
+            let angle = Scalar::from_u64(i) / 180. * Scalar::PI;
+            let (sin, cos) = angle.sin_cos();
+            let direction = Vector::from([cos, sin, Scalar::ZERO]);
+
+            let extent = |mesh: &Mesh<Point<3>>| {
+                mesh.vertices()
+                    .map(|vertex| vertex.coords.dot(&direction))
+                    .max()
+                    .expect("Mesh has vertices")
+            };
+
+            let deviation = (extent(&mesh) - extent(&simplified)).abs();
+            assert!(deviation <= tolerance);
+        }
+
+        // The rims of the cylinder are its silhouette, when viewed along the
+        // axis. Every edge on them must still be within the tolerance of the
+        // original circle. The caps may be triangulated differently, so only
+        // the side has edges on the rims.
+        for triangle in simplified
+            .triangles()
+            .filter(|triangle| triangle.color == side)
+        {
+            let [a, b, c] = triangle.points;
+
+            for [a, b] in [[a, b], [b, c], [c, a]] {
+                let is_on_rim = a.z == b.z
+                    && (a.z == Scalar::ZERO || a.z == Scalar::from_f64(height))
+                    && a.coords.xy().magnitude() > Scalar::ZERO
+                    && b.coords.xy().magnitude() > Scalar::ZERO;
+                if !is_on_rim {
+                    continue;
+                }
+
+                let center = a.coords + (b - a) / Scalar::TWO;
+                let deviation =
+                    Scalar::from_f64(radius) - center.xy().magnitude();
+                assert!(deviation <= tolerance);
+            }
+        }
+    }
+
+    #[test]
+    fn cube() {
+        let mut mesh = Mesh::new();
+
+        let corners = [
+            [0., 0., 0.],
+            [1., 0., 0.],
+            [1., 1., 0.],
+            [0., 1., 0.],
+            [0., 0., 1.],
+            [1., 0., 1.],
+            [1., 1., 1.],
+            [0., 1., 1.],
+        ]
+        .map(Point::from);
+
+        // Every face has its own color, which makes every edge of the cube a
+        // face boundary.
+        let quads = [
+            ([0, 3, 2, 1], [255, 0, 0, 255]),   // bottom
+            ([4, 5, 6, 7], [0, 255, 0, 255]),   // top
+            ([0, 1, 5, 4], [0, 0, 255, 255]),   // front
+            ([2, 3, 7, 6], [255, 255, 0, 255]), // back
+            ([0, 4, 7, 3], [255, 0, 255, 255]), // left
+            ([1, 2, 6, 5], [0, 255, 255, 255]), // right
+        ];
+
+        for ([a, b, c, d], color) in quads {
+            let [a, b, c, d] = [a, b, c, d].map(|i| corners[i]);
+            mesh.push_triangle([a, b, c], color);
+            mesh.push_triangle([a, c, d], color);
+        }
+
+        for degrees in [1., 45., 89., 135., 179.] {
+            let simplified =
+                simplify_for_viewing(&mesh, Angle::from_deg(degrees));
+
+            let original: Vec<_> = mesh.triangles().collect();
+            let simplified: Vec<_> = simplified.triangles().collect();
+            assert_eq!(original, simplified);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use bytemuck::{Pod, Zeroable};
+use fj_math::Point;
 
 use crate::camera::Camera;
 
@@ -28,6 +29,24 @@ impl Transform {
         Self(transform.map(|scalar| scalar.into_f32()))
     }
 
+    /// Project a point into normalized device coordinates
+    ///
+    /// Returns `None`, if the point is behind the camera.
+    pub fn project_point(&self, point: Point<3>) -> Option<[f32; 2]> {
+        let [x, y, z] = point.coords.components.map(|c| c.into_f32());
+
+        // The matrix is stored in column-major order.
+        let m = &self.0;
+        let row = |r: usize| m[r] * x + m[4 + r] * y + m[8 + r] * z + m[12 + r];
+
+        let w = row(3);
+        if w <= 0. {
+            return None;
+        }
+
+        Some([row(0) / w, row(1) / w])
+    }
+
     /// Compute transform used for normals
     ///
     /// This method is only relevant for the graphics code. The returned
@@ -47,3 +66,26 @@ impl From<&fj_math::Transform> for Transform {
         Self(native.map(|val| val as f32))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Point;
+
+    use super::Transform;
+
+    #[test]
+    fn project_point() {
+        let transform =
+            Transform::from(&fj_math::Transform::translation([0., 0., -1.]));
+        assert_eq!(
+            transform.project_point(Point::from([0.5, -0.25, 0.])),
+            Some([0.5, -0.25])
+        );
+
+        // A perspective projection puts points behind the camera at negative
+        // `w`.
+        let mut behind = Transform::identity();
+        behind.0[15] = -1.;
+        assert_eq!(behind.project_point(Point::from([0., 0., 0.])), None);
+    }
+}
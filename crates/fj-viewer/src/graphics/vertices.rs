@@ -1,9 +1,12 @@
+use std::collections::HashMap;
+
 use bytemuck::{Pod, Zeroable};
 use fj_interop::{
     debug::DebugInfo,
-    mesh::{Index, Mesh},
+    gizmo::LineSet,
+    mesh::{Color, Index, Mesh, DEFAULT_CREASE_ANGLE},
 };
-use fj_math::{Point, Vector};
+use fj_math::{Point, Scalar, Vector};
 
 #[derive(Debug)]
 pub struct Vertices {
@@ -45,51 +48,74 @@ impl Vertices {
         self.indices.push(self.indices.len() as u32);
     }
 
-    pub fn push_cross(
-        &mut self,
-        position: Point<3>,
-        normal: [f32; 3],
-        color: [f32; 4],
-    ) {
-        let d = 0.05;
+    pub fn push_line_set(&mut self, lines: &LineSet) {
+        let normal = [0.; 3];
 
-        self.push_line(
-            [
-                position - Vector::from([d, 0., 0.]),
-                position + Vector::from([d, 0., 0.]),
-            ],
-            normal,
-            color,
-        );
-        self.push_line(
-            [
-                position - Vector::from([0., d, 0.]),
-                position + Vector::from([0., d, 0.]),
-            ],
-            normal,
-            color,
-        );
+        for (line, color) in lines.lines() {
+            let color = color.map(|v| f32::from(v) / 255.0);
+            self.push_line(line, normal, color);
+        }
     }
 }
 
-impl From<&Mesh<fj_math::Point<3>>> for Vertices {
-    fn from(mesh: &Mesh<fj_math::Point<3>>) -> Self {
+impl Vertices {
+    pub fn from_mesh(
+        mesh: &Mesh<Point<3>>,
+        color_boundary: ColorBoundary,
+    ) -> Self {
+        let blended_colors = match color_boundary {
+            ColorBoundary::Blend => blend_colors(mesh),
+            _ => HashMap::new(),
+        };
+
         let mut m = Mesh::new();
 
-        for triangle in mesh.triangles() {
+        // Faces that meet smoothly are shaded smoothly across the edge between
+        // them.
+        let vertex_normals =
+            mesh.vertex_normals(Scalar::from_f64(DEFAULT_CREASE_ANGLE));
+
+        for (triangle, [n_a, n_b, n_c]) in mesh.triangles().zip(vertex_normals)
+        {
             let [a, b, c] = triangle.points;
 
-            let normal = (b - a).cross(&(c - a)).normalize();
-            let color = triangle.color;
+            // The back side of a double-sided mesh is rendered using a copy
+            // of each triangle, that faces the other way.
+            let mut sides = vec![[(a, n_a), (b, n_b), (c, n_c)]];
+            if mesh.is_double_sided() {
+                sides.push([(a, -n_a), (c, -n_c), (b, -n_b)]);
+            }
+
+            for vertices in sides {
+                for (point, normal) in vertices {
+                    let (color, face) = match color_boundary {
+                        ColorBoundary::Hard => (triangle.color, None),
+                        ColorBoundary::SplitVertices => {
+                            (triangle.color, triangle.face)
+                        }
+                        ColorBoundary::Blend => (blended_colors[&point], None),
+                    };
+
+                    // Faces that were approximated more coarsely than
+                    // requested are tinted, so they don't pass for accurate.
+                    let is_degraded = triangle
+                        .face
+                        .map(|face| mesh.is_degraded(face))
+                        .unwrap_or(false);
+                    let color = if is_degraded {
+                        tint_degraded(color)
+                    } else {
+                        color
+                    };
 
-            m.push_vertex((a, normal, color));
-            m.push_vertex((b, normal, color));
-            m.push_vertex((c, normal, color));
+                    m.push_vertex((point, normal, color, face));
+                }
+            }
         }
 
         let vertices = m
             .vertices()
-            .map(|(vertex, normal, color)| Vertex {
+            .map(|(vertex, normal, color, _)| Vertex {
                 position: vertex.into(),
                 normal: normal.into(),
                 color: color.map(|v| f32::from(v) / 255.0),
@@ -102,15 +128,32 @@ impl From<&Mesh<fj_math::Point<3>>> for Vertices {
     }
 }
 
-impl From<&DebugInfo> for Vertices {
-    fn from(debug_info: &DebugInfo) -> Self {
+/// Mix a color with orange, to mark a face as degraded
+fn tint_degraded(color: Color) -> Color {
+    const TINT: Color = [255, 128, 0, 255];
+
+    let mut tinted = color;
+    for (channel, tint) in tinted.iter_mut().zip(TINT).take(3) {
+        *channel = ((u16::from(*channel) + u16::from(tint)) / 2) as u8;
+    }
+    tinted
+}
+
+impl From<&LineSet> for Vertices {
+    fn from(lines: &LineSet) -> Self {
         let mut self_ = Self::empty();
+        self_.push_line_set(lines);
+        self_
+    }
+}
 
-        for triangle_edge_check in &debug_info.triangle_edge_checks {
-            let normal = [0.; 3];
+impl From<&DebugInfo> for Vertices {
+    fn from(debug_info: &DebugInfo) -> Self {
+        let mut lines = LineSet::new();
 
-            let red = [1., 0., 0., 1.];
-            let green = [0., 1., 0., 1.];
+        for triangle_edge_check in debug_info.triangle_edge_checks() {
+            let red = [255, 0, 0, 255];
+            let green = [0, 255, 0, 255];
 
             let color = if triangle_edge_check.hits.len() % 2 == 0 {
                 red
@@ -118,20 +161,104 @@ impl From<&DebugInfo> for Vertices {
                 green
             };
 
-            self_.push_cross(triangle_edge_check.origin, normal, color);
+            push_cross(&mut lines, triangle_edge_check.origin, color);
 
             for &hit in &triangle_edge_check.hits {
                 let line = hit.points();
-                let color = [0., 0., 0., 1.];
+                let color = [0, 0, 0, 255];
 
-                self_.push_line(line, normal, color);
+                lines.push_line(line, color);
             }
         }
 
-        self_
+        Self::from(&lines)
+    }
+}
+
+fn push_cross(lines: &mut LineSet, position: Point<3>, color: Color) {
+    let d = 0.05;
+
+    lines.push_line(
+        [
+            position - Vector::from([d, 0., 0.]),
+            position + Vector::from([d, 0., 0.]),
+        ],
+        color,
+    );
+    lines.push_line(
+        [
+            position - Vector::from([0., d, 0.]),
+            position + Vector::from([0., d, 0.]),
+        ],
+        color,
+    );
+}
+
+/// Defines how colors are handled where faces of different color meet
+///
+/// Relevant when converting a triangle mesh into something that can be
+/// rendered.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ColorBoundary {
+    /// Every triangle has the color of its face
+    ///
+    /// Vertices are shared between triangles that agree in position, normal,
+    /// and color.
+    Hard,
+
+    /// Every triangle has the color of its face, and faces share no vertices
+    ///
+    /// This uses the face information of the triangle mesh, which means
+    /// vertices are duplicated exactly along the edges of the original shape.
+    SplitVertices,
+
+    /// Vertices that are shared between faces of different color get the
+    /// average of those colors
+    ///
+    /// This results in a soft transition along the edges between those faces.
+    Blend,
+}
+
+impl Default for ColorBoundary {
+    fn default() -> Self {
+        Self::Hard
     }
 }
 
+/// Compute the color of every vertex, blending colors between faces
+///
+/// Averages the distinct colors of all triangles that touch a vertex, so only
+/// vertices on boundaries between differently colored faces are affected.
+fn blend_colors(mesh: &Mesh<Point<3>>) -> HashMap<Point<3>, Color> {
+    let mut colors_by_point: HashMap<_, Vec<Color>> = HashMap::new();
+
+    for triangle in mesh.triangles() {
+        for point in triangle.points {
+            let colors = colors_by_point.entry(point).or_default();
+            if !colors.contains(&triangle.color) {
+                colors.push(triangle.color);
+            }
+        }
+    }
+
+    colors_by_point
+        .into_iter()
+        .map(|(point, colors)| {
+            let mut sum = [0u32; 4];
+            for color in &colors {
+                for (sum, channel) in sum.iter_mut().zip(color) {
+                    *sum += u32::from(*channel);
+                }
+            }
+
+            let n = colors.len() as u32;
+            let color = sum.map(|sum| ((sum + n / 2) / n) as u8);
+
+            (point, color)
+        })
+        .collect()
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
 #[repr(C)]
 pub struct Vertex {
@@ -139,3 +266,143 @@ pub struct Vertex {
     pub normal: [f32; 3],
     pub color: [f32; 4],
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use fj_interop::{
+        gizmo::axes,
+        mesh::{Color, Mesh},
+    };
+    use fj_math::Point;
+
+    use super::{ColorBoundary, Vertices};
+
+    const RED: Color = [255, 0, 0, 255];
+    const BLUE: Color = [0, 0, 255, 255];
+
+    #[test]
+    fn split_vertices() {
+        let mesh = two_color_box();
+        let vertices = Vertices::from_mesh(&mesh, ColorBoundary::SplitVertices);
+
+        // Every triangle has the uniform color of its face.
+        for (triangle, indices) in
+            mesh.triangles().zip(vertices.indices().chunks(3))
+        {
+            for &index in indices {
+                let vertex = vertices.vertices()[index as usize];
+                assert_eq!(vertex.color, to_f32(triangle.color));
+            }
+        }
+
+        // Every corner of the box is shared by exactly 3 faces, so it must be
+        // duplicated exactly 3 times.
+        let mut vertices_by_position: HashMap<_, usize> = HashMap::new();
+        for vertex in vertices.vertices() {
+            *vertices_by_position
+                .entry(vertex.position.map(f32::to_bits))
+                .or_default() += 1;
+        }
+        assert_eq!(vertices_by_position.len(), 8);
+        assert!(vertices_by_position.values().all(|&n| n == 3));
+    }
+
+    #[test]
+    fn blend() {
+        let mesh = two_color_box();
+        let vertices = Vertices::from_mesh(&mesh, ColorBoundary::Blend);
+
+        let top_corners: HashSet<_> = mesh
+            .triangles()
+            .filter(|triangle| triangle.color == BLUE)
+            .flat_map(|triangle| triangle.points)
+            .map(|point| to_f32_position(point).map(f32::to_bits))
+            .collect();
+
+        for vertex in vertices.vertices() {
+            if top_corners.contains(&vertex.position.map(f32::to_bits)) {
+                // Shared between the blue top and the red sides.
+                assert_ne!(vertex.color, to_f32(RED));
+                assert_ne!(vertex.color, to_f32(BLUE));
+            } else {
+                assert_eq!(vertex.color, to_f32(RED));
+            }
+        }
+    }
+
+    #[test]
+    fn double_sided() {
+        let mut mesh = Mesh::new();
+        mesh.push_triangle(
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]].map(Point::from),
+            RED,
+        );
+
+        let vertices = Vertices::from_mesh(&mesh, ColorBoundary::Hard);
+        assert_eq!(vertices.indices().len(), 3);
+
+        mesh.set_double_sided(true);
+        let vertices = Vertices::from_mesh(&mesh, ColorBoundary::Hard);
+        assert_eq!(vertices.indices().len(), 6);
+
+        let normals: Vec<_> = vertices
+            .indices()
+            .chunks(3)
+            .map(|triangle| vertices.vertices()[triangle[0] as usize].normal)
+            .collect();
+        assert_eq!(normals, [[0., 0., 1.], [0., 0., -1.]]);
+    }
+
+    /// A unit square, swept along the z-axis, with a top face of a different
+    /// color
+    #[test]
+    fn line_set() {
+        let lines = axes(1.);
+        let vertices = Vertices::from(&lines);
+
+        assert_eq!(vertices.indices().len(), lines.positions().len());
+        for (vertex, &position) in
+            vertices.vertices().iter().zip(lines.positions())
+        {
+            assert_eq!(vertex.position, to_f32_position(position));
+        }
+        for (line, &color) in vertices.vertices().chunks(2).zip(lines.colors())
+        {
+            assert!(line.iter().all(|vertex| vertex.color == to_f32(color)));
+        }
+    }
+
+    fn two_color_box() -> Mesh<Point<3>> {
+        let mut mesh = Mesh::new();
+
+        let bottom = [[0., 0., 0.], [1., 0., 0.], [1., 1., 0.], [0., 1., 0.]]
+            .map(Point::from);
+        let top = bottom.map(|point| point + [0., 0., 1.]);
+
+        let mut push_quad = |[a, b, c, d]: [Point<3>; 4], color, face| {
+            mesh.push_face_triangle([a, b, c], color, face);
+            mesh.push_face_triangle([a, c, d], color, face);
+        };
+
+        let [a, b, c, d] = bottom;
+        push_quad([a, d, c, b], RED, 0);
+        push_quad(top, BLUE, 1);
+
+        for i in 0..4 {
+            let j = (i + 1) % 4;
+            push_quad([bottom[i], bottom[j], top[j], top[i]], RED, 2 + i);
+        }
+
+        mesh
+    }
+
+    fn to_f32(color: Color) -> [f32; 4] {
+        color.map(|v| f32::from(v) / 255.0)
+    }
+
+    fn to_f32_position(point: Point<3>) -> [f32; 3] {
+        point.coords.components.map(|scalar| scalar.into_f32())
+    }
+}
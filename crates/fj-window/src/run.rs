@@ -6,7 +6,10 @@
 use std::error;
 
 use fj_host::Watcher;
-use fj_operations::shape_processor::ShapeProcessor;
+use fj_operations::{
+    rebuild::BrepCache,
+    shape_processor::{memory_table, ShapeProcessor},
+};
 use fj_viewer::{
     camera::Camera,
     graphics::{self, DrawConfig, Renderer},
@@ -14,7 +17,7 @@ use fj_viewer::{
     screen::{NormalizedPosition, Screen as _, Size},
 };
 use futures::executor::block_on;
-use tracing::{trace, warn};
+use tracing::{debug, trace, warn};
 use winit::{
     dpi::PhysicalPosition,
     event::{
@@ -46,23 +49,46 @@ pub fn run(
     let mut shape = None;
     let mut camera = None;
 
+    // Most changes to a model only affect parts of it. The rest can be taken
+    // from the previous evaluation.
+    let mut brep_cache = BrepCache::default();
+
     event_loop.run(move |event, _, control_flow| {
         trace!("Handling event: {:?}", event);
 
         if let Some(new_shape) = watcher.receive() {
-            match shape_processor.process(&new_shape) {
+            match shape_processor.process_cached(&new_shape, &mut brep_cache) {
                 Ok(new_shape) => {
+                    for warning in &new_shape.warnings {
+                        warn!("{}", warning);
+                    }
+                    debug!(
+                        "Estimated memory usage:\n{}",
+                        memory_table(&new_shape.memory)
+                    );
+
                     renderer.update_geometry(
-                        (&new_shape.mesh).into(),
-                        (&new_shape.debug_info).into(),
+                        &new_shape
+                            .mesh
+                            .with_face_coloring(draw_config.face_coloring),
+                        &new_shape.debug_info,
+                        &new_shape.annotations,
                         new_shape.aabb,
+                        draw_config.color_boundary,
                     );
 
                     if camera.is_none() {
                         camera = Some(Camera::new(&new_shape.aabb));
                     }
 
-                    shape = Some(new_shape);
+                    if let (Some(previous), Some(shape)) =
+                        (shape.replace(new_shape), &shape)
+                    {
+                        let diff = shape.summary.diff(&previous.summary);
+                        if !diff.is_unchanged() {
+                            println!("Model changed: {}", diff);
+                        }
+                    }
                 }
                 Err(err) => {
                     // Can be cleaned up, once `Report` is stable:
@@ -142,6 +168,24 @@ pub fn run(
                 VirtualKeyCode::Key3 => {
                     draw_config.draw_debug = !draw_config.draw_debug
                 }
+                VirtualKeyCode::Key4 => {
+                    draw_config.face_coloring =
+                        draw_config.face_coloring.next();
+
+                    // Only the colors change, so the mesh of the current
+                    // shape can be reused.
+                    if let Some(shape) = &shape {
+                        renderer.update_geometry(
+                            &shape
+                                .mesh
+                                .with_face_coloring(draw_config.face_coloring),
+                            &shape.debug_info,
+                            &shape.annotations,
+                            shape.aabb,
+                            draw_config.color_boundary,
+                        );
+                    }
+                }
                 _ => {}
             },
             Event::WindowEvent {
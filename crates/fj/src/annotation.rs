@@ -0,0 +1,112 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::Shape;
+
+/// A 3-dimensional shape with annotations
+///
+/// Annotations don't change the geometry of the shape. They are carried
+/// alongside it, and their measured values are resolved from the geometry, when
+/// the shape is evaluated.
+///
+/// # Limitations
+///
+/// Only the annotations of the shape that is being evaluated are resolved.
+/// Annotations of shapes that are nested within other shapes are ignored.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Annotated {
+    shape: Shape,
+    annotations: Vec<Annotation>,
+}
+
+impl Annotated {
+    /// Create an `Annotated` from a shape and its annotations
+    pub fn from_shape(shape: Shape, annotations: Vec<Annotation>) -> Self {
+        Self { shape, annotations }
+    }
+
+    /// Access the shape that is annotated
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    pub(crate) fn shape_mut(&mut self) -> &mut Shape {
+        &mut self.shape
+    }
+
+    /// Access the annotations of the shape
+    pub fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+}
+
+impl From<Annotated> for Shape {
+    fn from(shape: Annotated) -> Self {
+        Self::Annotated(Box::new(shape))
+    }
+}
+
+/// An annotation of a shape
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub enum Annotation {
+    /// The distance between two vertices
+    LinearDimension {
+        /// The vertex the dimension starts at
+        from: VertexRef,
+
+        /// The vertex the dimension ends at
+        to: VertexRef,
+
+        /// The offset of the dimension line from the vertices
+        offset_direction: [f64; 3],
+    },
+
+    /// The radius of a circular edge
+    RadiusDimension {
+        /// The edge whose radius is annotated
+        edge_ref: EdgeRef,
+    },
+
+    /// A note at a point
+    Note {
+        /// The point the note is attached to
+        anchor: [f64; 3],
+
+        /// The text of the note
+        text: String,
+    },
+}
+
+/// Refers to an edge of a shape
+///
+/// Edges are identified by their position within the evaluated shape: The index
+/// of the face, the index of the cycle within the face (the exterior cycle
+/// comes first), and the index of the edge within that cycle.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct EdgeRef {
+    /// The index of the face
+    pub face: usize,
+
+    /// The index of the cycle within the face
+    pub cycle: usize,
+
+    /// The index of the edge within the cycle
+    pub edge: usize,
+}
+
+/// Refers to a vertex of a shape
+///
+/// A vertex is identified by the edge it starts. See [`EdgeRef`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct VertexRef {
+    /// The edge that starts at the vertex
+    pub edge: EdgeRef,
+}
@@ -0,0 +1,52 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Shape, Transform};
+
+/// An assembly of transformed 3-dimensional shapes
+///
+/// Each instance of the assembly is a shape with its own transform. Unlike the
+/// shapes in a [`Group`](crate::Group), instances may touch or overlap. They
+/// are combined without a boolean union, so each instance keeps its faces and
+/// their colors.
+///
+/// Assemblies can be nested. The transform of an instance applies to all of
+/// it, including the instances of an assembly within it.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Assembly {
+    instances: Vec<Transform>,
+}
+
+impl Assembly {
+    /// Create an assembly without any instances
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an instance
+    ///
+    /// Use [`fj::syntax::Transform`] to create the instance from a shape.
+    ///
+    /// [`fj::syntax::Transform`]: crate::syntax::Transform
+    pub fn with_instance(mut self, instance: Transform) -> Self {
+        self.instances.push(instance);
+        self
+    }
+
+    /// Access the instances, in the order they were added
+    pub fn instances(&self) -> &[Transform] {
+        &self.instances
+    }
+
+    pub(crate) fn instances_mut(&mut self) -> &mut [Transform] {
+        &mut self.instances
+    }
+}
+
+impl From<Assembly> for Shape {
+    fn from(shape: Assembly) -> Self {
+        Self::Assembly(Box::new(shape))
+    }
+}
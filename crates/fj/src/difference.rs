@@ -0,0 +1,30 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::Shape;
+
+/// A difference between two 3-dimensional shapes
+///
+/// The volume of `b` is removed from `a`. If `b` is completely inside of `a`,
+/// the result has an internal void.
+///
+/// # Limitations
+///
+/// Faces of `a` and `b` must not lie in the same plane, and no vertex of one
+/// shape must lie in the plane of a face of the other.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Difference {
+    /// The shape that is subtracted from
+    pub a: Shape,
+
+    /// The shape that is subtracted
+    pub b: Shape,
+}
+
+impl From<Difference> for Shape {
+    fn from(shape: Difference) -> Self {
+        Self::Difference(Box::new(shape))
+    }
+}
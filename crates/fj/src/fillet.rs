@@ -0,0 +1,73 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::Shape;
+
+/// A 3-dimensional shape with rounded edges
+///
+/// The selected edges are replaced by fillets of the given radius.
+///
+/// # Limitations
+///
+/// Only straight edges between two planar faces can be rounded. Where
+/// fillets end, they must either end at a face that is perpendicular to them,
+/// or meet two other fillets at the corner of three faces, like at the corners
+/// of a cuboid. The radius must be small enough, that no edge of the trimmed
+/// faces vanishes.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Fillet {
+    /// The shape whose edges are rounded
+    pub shape: Shape,
+
+    /// The radius of the fillets
+    pub radius: f64,
+
+    /// The edges that are rounded
+    pub edges: FilletEdges,
+}
+
+impl Fillet {
+    /// Create a `Fillet` that rounds all edges of a shape
+    pub fn from_shape(shape: Shape, radius: f64) -> Self {
+        Self {
+            shape,
+            radius,
+            edges: FilletEdges::All,
+        }
+    }
+
+    /// Only round the edges that are within the given box
+    ///
+    /// An edge is within the box, if both of its vertices are. The box
+    /// includes its boundary.
+    pub fn within(mut self, min: [f64; 3], max: [f64; 3]) -> Self {
+        self.edges = FilletEdges::Within { min, max };
+        self
+    }
+}
+
+impl From<Fillet> for Shape {
+    fn from(shape: Fillet) -> Self {
+        Self::Fillet(Box::new(shape))
+    }
+}
+
+/// The edges that a [`Fillet`] rounds
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub enum FilletEdges {
+    /// Round all edges
+    All,
+
+    /// Round the edges whose vertices are within a box
+    Within {
+        /// The minimum corner of the box
+        min: [f64; 3],
+
+        /// The maximum corner of the box
+        max: [f64; 3],
+    },
+}
@@ -0,0 +1,41 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::Shape;
+
+/// A 3-dimensional shape that is imported from a mesh file
+///
+/// Binary and ASCII STL files are supported. The imported shape consists of a
+/// single face, which is defined by the triangles of the file.
+///
+/// # Limitations
+///
+/// The file is identified by its path. If the file changes, but the path
+/// doesn't, shapes that were cached from a previous evaluation are reused.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct ImportedMesh {
+    path: String,
+}
+
+impl ImportedMesh {
+    /// Create an `ImportedMesh` from the path to a mesh file
+    ///
+    /// Relative paths are resolved against the working directory of the
+    /// application that evaluates the shape.
+    pub fn from_path(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Access the path to the mesh file
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl From<ImportedMesh> for Shape {
+    fn from(shape: ImportedMesh) -> Self {
+        Self::ImportedMesh(shape)
+    }
+}
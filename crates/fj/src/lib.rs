@@ -18,16 +18,45 @@
 
 #![warn(missing_docs)]
 
+pub mod sketch;
 pub mod syntax;
 
 mod angle;
+mod annotation;
+mod assembly;
+mod difference;
+mod fillet;
 mod group;
+mod import;
+mod material;
+mod mirror;
+mod part;
+mod revolve;
+mod section;
 mod shape_2d;
+mod shell;
 mod sweep;
+mod tolerance;
 mod transform;
 
 pub use self::{
-    angle::*, group::Group, shape_2d::*, sweep::Sweep, transform::Transform,
+    angle::*,
+    annotation::{Annotated, Annotation, EdgeRef, VertexRef},
+    assembly::Assembly,
+    difference::Difference,
+    fillet::{Fillet, FilletEdges},
+    group::Group,
+    import::ImportedMesh,
+    material::{FaceSelector, Material, MaterialAssignment, Materialized},
+    mirror::Mirror,
+    part::{Part, PartState},
+    revolve::Revolve,
+    section::{SectionPlane, SectionPreset, Sectioned},
+    shape_2d::*,
+    shell::Shell,
+    sweep::{ScaleProfile, Sweep},
+    tolerance::Tolerance,
+    transform::Transform,
 };
 pub use fj_proc::*;
 #[cfg(feature = "serde")]
@@ -38,15 +67,51 @@ use serde::{Deserialize, Serialize};
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(C)]
 pub enum Shape {
+    /// A 3-dimensional shape with annotations
+    Annotated(Box<Annotated>),
+
+    /// An assembly of transformed 3-dimensional shapes
+    Assembly(Box<Assembly>),
+
+    /// A difference between two 3-dimensional shapes
+    Difference(Box<Difference>),
+
+    /// A 3-dimensional shape with rounded edges
+    Fillet(Box<Fillet>),
+
     /// A group of two 3-dimensional shapes
     Group(Box<Group>),
 
+    /// A 3-dimensional shape that is imported from a mesh file
+    ImportedMesh(ImportedMesh),
+
+    /// A 3-dimensional shape with materials assigned to its faces
+    Materialized(Box<Materialized>),
+
+    /// A 3-dimensional shape that is reflected across a plane
+    Mirror(Box<Mirror>),
+
+    /// A labeled part of a model, that can be suppressed
+    Part(Box<Part>),
+
+    /// A revolution of a 2-dimensional shape around an axis
+    Revolve(Revolve),
+
+    /// A 3-dimensional shape with named cross-sections
+    Sectioned(Box<Sectioned>),
+
     /// A 2D shape
     Shape2d(Shape2d),
 
+    /// A hollowed-out 3-dimensional shape
+    Shell(Box<Shell>),
+
     /// A sweep of 2-dimensional shape along the z-axis
     Sweep(Sweep),
 
+    /// A 3-dimensional shape that is approximated with its own tolerance
+    Tolerance(Box<Tolerance>),
+
     /// A transformed 3-dimensional shape
     Transform(Box<Transform>),
 }
@@ -0,0 +1,163 @@
+use std::collections::BTreeMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::Shape;
+
+/// A 3-dimensional shape with materials assigned to its faces
+///
+/// Materials are defined once, in the registry of the shape, and then assigned
+/// to faces by name. Faces that have no material assigned use the default
+/// material.
+///
+/// # Limitations
+///
+/// Only the materials of the shape that is being evaluated are resolved.
+/// Materials of shapes that are nested within other shapes are ignored.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Materialized {
+    shape: Shape,
+    materials: Vec<Material>,
+    assignments: Vec<MaterialAssignment>,
+}
+
+impl Materialized {
+    /// Create a `Materialized` from a shape and a registry of materials
+    pub fn from_shape(shape: Shape, materials: Vec<Material>) -> Self {
+        Self {
+            shape,
+            materials,
+            assignments: Vec::new(),
+        }
+    }
+
+    /// Assign a material to the selected faces
+    ///
+    /// `material` is the name of a material from the registry. If multiple
+    /// assignments select the same face, the last one wins.
+    pub fn with_material(
+        mut self,
+        selector: FaceSelector,
+        material: impl Into<String>,
+    ) -> Self {
+        self.assignments.push(MaterialAssignment {
+            selector,
+            material: material.into(),
+        });
+        self
+    }
+
+    /// Access the shape that materials are assigned to
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    pub(crate) fn shape_mut(&mut self) -> &mut Shape {
+        &mut self.shape
+    }
+
+    /// Access the registry of materials
+    pub fn materials(&self) -> &[Material] {
+        &self.materials
+    }
+
+    /// Access the material assignments, in the order they were made
+    pub fn assignments(&self) -> &[MaterialAssignment] {
+        &self.assignments
+    }
+}
+
+impl From<Materialized> for Shape {
+    fn from(shape: Materialized) -> Self {
+        Self::Materialized(Box::new(shape))
+    }
+}
+
+/// A named material
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Material {
+    /// The name of the material, for example "PLA-black"
+    pub name: String,
+
+    /// The color that is used to display faces of this material
+    pub display_color: [u8; 4],
+
+    /// Additional information about the material
+    ///
+    /// Passed through to exporters that support it.
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl Material {
+    /// Create a material without metadata
+    pub fn new(name: impl Into<String>, display_color: [u8; 4]) -> Self {
+        Self {
+            name: name.into(),
+            display_color,
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    /// Add an entry to the metadata of the material
+    pub fn with_metadata(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+}
+
+/// The assignment of a material to some faces of a shape
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct MaterialAssignment {
+    /// Selects the faces that the material is assigned to
+    pub selector: FaceSelector,
+
+    /// The name of the material
+    pub material: String,
+}
+
+/// Selects faces of a shape
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub enum FaceSelector {
+    /// Select all faces
+    All,
+
+    /// Select the faces at the given indices
+    ///
+    /// The indices refer to the order of the faces in the evaluated shape.
+    Indices(Vec<usize>),
+
+    /// Select the planar faces whose normal points in the given direction
+    Normal {
+        /// The direction the normal of a selected face points in
+        direction: [f64; 3],
+
+        /// The maximum angle between the normal and the direction, in radians
+        max_angle: f64,
+    },
+}
+
+impl FaceSelector {
+    /// Select the planar faces whose normal points exactly in `direction`
+    ///
+    /// Allows for a small angle between the two, to be robust against floating
+    /// point accuracy issues.
+    pub fn normal(direction: [f64; 3]) -> Self {
+        Self::Normal {
+            direction,
+            max_angle: 1e-6,
+        }
+    }
+}
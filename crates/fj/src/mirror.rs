@@ -0,0 +1,33 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::Shape;
+
+/// A 3-dimensional shape that is reflected across a plane
+///
+/// The plane goes through `plane_origin` and is perpendicular to
+/// `plane_normal`. The length of the normal doesn't matter, but it must not be
+/// zero.
+///
+/// The reflection of a solid is a solid again, with its faces still pointing
+/// outward. A shape on one side of the plane ends up on the other side, at the
+/// same distance.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Mirror {
+    /// The shape being reflected
+    pub shape: Shape,
+
+    /// A point on the plane
+    pub plane_origin: [f64; 3],
+
+    /// The normal of the plane
+    pub plane_normal: [f64; 3],
+}
+
+impl From<Mirror> for Shape {
+    fn from(shape: Mirror) -> Self {
+        Self::Mirror(Box::new(shape))
+    }
+}
@@ -0,0 +1,187 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::Shape;
+
+/// A part of a model, that can be labeled and suppressed
+///
+/// Wrapping a shape into a part doesn't change its geometry. It allows the host
+/// application to refer to the shape by its label, and to change its
+/// [`PartState`] between evaluations, without recompiling the model (see
+/// [`Shape::set_part_state`]).
+///
+/// Parts can be nested anywhere in the model, including the children of a
+/// [`Group`](crate::Group).
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Part {
+    shape: Shape,
+    label: Option<String>,
+    state: PartState,
+}
+
+impl Part {
+    /// Create an active, unlabeled `Part` from a shape
+    pub fn from_shape(shape: Shape) -> Self {
+        Self {
+            shape,
+            label: None,
+            state: PartState::default(),
+        }
+    }
+
+    /// Label the part
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Set the state of the part
+    pub fn with_state(mut self, state: PartState) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// Access the shape that makes up the part
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    /// Access the label of the part
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Access the state of the part
+    pub fn state(&self) -> PartState {
+        self.state
+    }
+
+    /// Change the state of the part
+    pub fn set_state(&mut self, state: PartState) {
+        self.state = state;
+    }
+}
+
+impl From<Part> for Shape {
+    fn from(shape: Part) -> Self {
+        Self::Part(Box::new(shape))
+    }
+}
+
+/// Determines how a [`Part`] is evaluated
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub enum PartState {
+    /// The part is evaluated as usual
+    #[default]
+    Active,
+
+    /// The part is skipped entirely
+    ///
+    /// It doesn't contribute to the geometry or the bounding volume of the
+    /// model. Its shape is not evaluated at all, so suppressing parts is a
+    /// cheap way to work on a region of a big model.
+    Suppressed,
+
+    /// The part is displayed, but is only reference geometry
+    ///
+    /// It is triangulated, but doesn't contribute to the physical properties
+    /// of the model.
+    DisplayOnly,
+}
+
+impl Shape {
+    /// Call `f` for every part within the shape
+    ///
+    /// Parts are visited depth-first, outer parts before the parts nested
+    /// within them. The shapes of suppressed parts are visited too.
+    pub fn for_each_part(&self, f: &mut impl FnMut(&Part)) {
+        match self {
+            Self::Annotated(shape) => shape.shape().for_each_part(f),
+            Self::Assembly(assembly) => {
+                for instance in assembly.instances() {
+                    instance.shape.for_each_part(f);
+                }
+            }
+            Self::Difference(difference) => {
+                difference.a.for_each_part(f);
+                difference.b.for_each_part(f);
+            }
+            Self::Fillet(shape) => shape.shape.for_each_part(f),
+            Self::Group(group) => {
+                group.a.for_each_part(f);
+                group.b.for_each_part(f);
+            }
+            Self::Materialized(shape) => shape.shape().for_each_part(f),
+            Self::Mirror(shape) => shape.shape.for_each_part(f),
+            Self::Part(part) => {
+                f(part);
+                part.shape.for_each_part(f);
+            }
+            Self::Sectioned(shape) => shape.shape().for_each_part(f),
+            Self::Shell(shape) => shape.shape.for_each_part(f),
+            Self::Tolerance(shape) => shape.shape.for_each_part(f),
+            Self::Transform(shape) => shape.shape.for_each_part(f),
+            Self::ImportedMesh(_)
+            | Self::Revolve(_)
+            | Self::Shape2d(_)
+            | Self::Sweep(_) => {}
+        }
+    }
+
+    /// Call `f` for every part within the shape, with mutable access
+    ///
+    /// See [`Shape::for_each_part`].
+    pub fn for_each_part_mut(&mut self, f: &mut impl FnMut(&mut Part)) {
+        match self {
+            Self::Annotated(shape) => shape.shape_mut().for_each_part_mut(f),
+            Self::Assembly(assembly) => {
+                for instance in assembly.instances_mut() {
+                    instance.shape.for_each_part_mut(f);
+                }
+            }
+            Self::Difference(difference) => {
+                difference.a.for_each_part_mut(f);
+                difference.b.for_each_part_mut(f);
+            }
+            Self::Fillet(shape) => shape.shape.for_each_part_mut(f),
+            Self::Group(group) => {
+                group.a.for_each_part_mut(f);
+                group.b.for_each_part_mut(f);
+            }
+            Self::Materialized(shape) => shape.shape_mut().for_each_part_mut(f),
+            Self::Mirror(shape) => shape.shape.for_each_part_mut(f),
+            Self::Part(part) => {
+                f(part);
+                part.shape.for_each_part_mut(f);
+            }
+            Self::Sectioned(shape) => shape.shape_mut().for_each_part_mut(f),
+            Self::Shell(shape) => shape.shape.for_each_part_mut(f),
+            Self::Tolerance(shape) => shape.shape.for_each_part_mut(f),
+            Self::Transform(shape) => shape.shape.for_each_part_mut(f),
+            Self::ImportedMesh(_)
+            | Self::Revolve(_)
+            | Self::Shape2d(_)
+            | Self::Sweep(_) => {}
+        }
+    }
+
+    /// Set the state of all parts with the given label
+    ///
+    /// Returns the number of parts whose state was set.
+    pub fn set_part_state(&mut self, label: &str, state: PartState) -> usize {
+        let mut count = 0;
+
+        self.for_each_part_mut(&mut |part| {
+            if part.label() == Some(label) {
+                part.set_state(state);
+                count += 1;
+            }
+        });
+
+        count
+    }
+}
@@ -0,0 +1,84 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Angle, Shape, Shape2d};
+
+/// A revolution of a 2-dimensional shape around an axis
+///
+/// The shape is rotated around the axis by the given angle, following the
+/// right-hand rule. Since an [`Angle`] is always between 0° (inclusive) and
+/// 360° (exclusive), an angle of 0° stands for a full revolution, which results
+/// in a solid without cap faces.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Revolve {
+    /// The 2-dimensional shape being revolved
+    shape: Shape2d,
+
+    /// A point on the axis of the revolution
+    axis_origin: [f64; 3],
+
+    /// The direction of the axis of the revolution
+    axis_direction: [f64; 3],
+
+    /// The angle of the revolution
+    angle: Angle,
+}
+
+impl Revolve {
+    /// Create a `Revolve` around the given axis
+    pub fn from_axis(
+        shape: Shape2d,
+        axis_origin: [f64; 3],
+        axis_direction: [f64; 3],
+        angle: Angle,
+    ) -> Self {
+        Self {
+            shape,
+            axis_origin,
+            axis_direction,
+            angle,
+        }
+    }
+
+    /// Create a full revolution around the given axis
+    pub fn full(
+        shape: Shape2d,
+        axis_origin: [f64; 3],
+        axis_direction: [f64; 3],
+    ) -> Self {
+        Self::from_axis(shape, axis_origin, axis_direction, Angle::from_rad(0.))
+    }
+
+    /// Access the shape being revolved
+    pub fn shape(&self) -> &Shape2d {
+        &self.shape
+    }
+
+    /// Access the point on the axis of the revolution
+    pub fn axis_origin(&self) -> [f64; 3] {
+        self.axis_origin
+    }
+
+    /// Access the direction of the axis of the revolution
+    pub fn axis_direction(&self) -> [f64; 3] {
+        self.axis_direction
+    }
+
+    /// Access the angle of the revolution
+    pub fn angle(&self) -> Angle {
+        self.angle
+    }
+
+    /// Determine whether this is a full revolution
+    pub fn is_full(&self) -> bool {
+        self.angle.rad() == 0.
+    }
+}
+
+impl From<Revolve> for Shape {
+    fn from(shape: Revolve) -> Self {
+        Self::Revolve(shape)
+    }
+}
@@ -0,0 +1,104 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::Shape;
+
+/// A 3-dimensional shape with named cross-sections
+///
+/// Each preset defines a plane that the shape is cut with, after it has been
+/// evaluated. The host application can then switch between the full shape and
+/// any of its sections, without evaluating the shape again.
+///
+/// # Limitations
+///
+/// Only the presets of the shape that is being evaluated are resolved. Presets
+/// of shapes that are nested within other shapes are ignored.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Sectioned {
+    shape: Shape,
+    presets: Vec<SectionPreset>,
+}
+
+impl Sectioned {
+    /// Create a `Sectioned` from a shape, without any presets
+    pub fn from_shape(shape: Shape) -> Self {
+        Self {
+            shape,
+            presets: Vec::new(),
+        }
+    }
+
+    /// Add a section preset
+    ///
+    /// If multiple presets have the same label, the last one wins.
+    pub fn with_preset(mut self, preset: SectionPreset) -> Self {
+        self.presets.push(preset);
+        self
+    }
+
+    /// Access the shape that is sectioned
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    pub(crate) fn shape_mut(&mut self) -> &mut Shape {
+        &mut self.shape
+    }
+
+    /// Access the section presets, in the order they were added
+    pub fn presets(&self) -> &[SectionPreset] {
+        &self.presets
+    }
+}
+
+impl From<Sectioned> for Shape {
+    fn from(shape: Sectioned) -> Self {
+        Self::Sectioned(Box::new(shape))
+    }
+}
+
+/// A named plane to cut a shape with
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct SectionPreset {
+    /// The label that the section is identified by
+    pub label: String,
+
+    /// The plane that the shape is cut with
+    pub plane: SectionPlane,
+}
+
+impl SectionPreset {
+    /// Create a section preset
+    pub fn new(label: impl Into<String>, plane: SectionPlane) -> Self {
+        Self {
+            label: label.into(),
+            plane,
+        }
+    }
+}
+
+/// A plane that a shape is cut with
+///
+/// The part of the shape that is on the side the normal points to is removed.
+/// The normal doesn't need to be normalized, but it must not be zero.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct SectionPlane {
+    /// A point on the plane
+    pub origin: [f64; 3],
+
+    /// The normal of the plane
+    pub normal: [f64; 3],
+}
+
+impl SectionPlane {
+    /// Create a plane from a point on it and its normal
+    pub fn new(origin: [f64; 3], normal: [f64; 3]) -> Self {
+        Self { origin, normal }
+    }
+}
@@ -13,6 +13,9 @@ pub enum Shape2d {
     /// A difference between two shapes
     Difference(Box<Difference2d>),
 
+    /// A difference between a shape and any number of other shapes
+    DifferenceAll(Box<DifferenceAll>),
+
     /// A sketch
     Sketch(Sketch),
 }
@@ -23,6 +26,7 @@ impl Shape2d {
         match &self {
             Shape2d::Sketch(s) => s.color(),
             Shape2d::Difference(d) => d.color(),
+            Shape2d::DifferenceAll(d) => d.color(),
         }
     }
 }
@@ -64,6 +68,54 @@ impl From<Difference2d> for Shape2d {
     }
 }
 
+/// A difference between a shape and any number of other shapes
+///
+/// Equivalent to nesting [`Difference2d`]s, subtracting one tool after the
+/// other from the base. But all tools are subtracted in a single step, without
+/// computing and validating any intermediate shapes. Prefer this over nesting,
+/// if many shapes are subtracted from the same base (like holes from a plate).
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct DifferenceAll {
+    base: Shape2d,
+    tools: Vec<Shape2d>,
+}
+
+impl DifferenceAll {
+    /// Create a `DifferenceAll` from a base shape and the shapes to subtract
+    pub fn from_shapes(base: Shape2d, tools: Vec<Shape2d>) -> Self {
+        Self { base, tools }
+    }
+
+    /// Get the rendering color of the larger object in RGBA
+    pub fn color(&self) -> [u8; 4] {
+        self.base.color()
+    }
+
+    /// Access the shape that the other shapes are subtracted from
+    pub fn base(&self) -> &Shape2d {
+        &self.base
+    }
+
+    /// Access the shapes that are subtracted from the base
+    pub fn tools(&self) -> &[Shape2d] {
+        &self.tools
+    }
+}
+
+impl From<DifferenceAll> for Shape {
+    fn from(shape: DifferenceAll) -> Self {
+        Self::Shape2d(shape.into())
+    }
+}
+
+impl From<DifferenceAll> for Shape2d {
+    fn from(shape: DifferenceAll) -> Self {
+        Self::DifferenceAll(Box::new(shape))
+    }
+}
+
 /// A sketch
 ///
 /// Sketches are currently limited to a single cycle of straight lines,
@@ -0,0 +1,58 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{FaceSelector, Shape};
+
+/// A hollowed-out 3-dimensional shape
+///
+/// All faces of the shape are offset inward by the thickness, leaving walls of
+/// that thickness. The selected faces are removed, which opens up the hollow
+/// shape on that side, like an enclosure without a lid.
+///
+/// # Limitations
+///
+/// Only shapes whose faces are planar polygons without holes can be hollowed
+/// out. Each vertex must be a corner of three faces, and removed faces must not
+/// share an edge. The thickness must be less than half of the smallest
+/// dimension of the shape.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Shell {
+    /// The shape that is hollowed out
+    pub shape: Shape,
+
+    /// The thickness of the walls
+    pub thickness: f64,
+
+    /// The faces that are removed
+    ///
+    /// Selecting faces by their normal refers to their outward normal.
+    pub open: FaceSelector,
+}
+
+impl Shell {
+    /// Create a `Shell` that removes the selected faces of a shape
+    pub fn from_shape(
+        shape: Shape,
+        thickness: f64,
+        open: FaceSelector,
+    ) -> Self {
+        Self {
+            shape,
+            thickness,
+            open,
+        }
+    }
+
+    /// Create a `Shell` that leaves a closed cavity within a shape
+    pub fn closed(shape: Shape, thickness: f64) -> Self {
+        Self::from_shape(shape, thickness, FaceSelector::Indices(Vec::new()))
+    }
+}
+
+impl From<Shell> for Shape {
+    fn from(shape: Shell) -> Self {
+        Self::Shell(Box::new(shape))
+    }
+}
@@ -0,0 +1,556 @@
+//! A minimal constraint solver for sketches
+//!
+//! Build a [`ConstraintSystem`] from points, some of whose coordinates are
+//! marked as free, and constraints between those points. [`ConstraintSystem::
+//! solve`] then finds values for the free coordinates that satisfy all
+//! constraints, and the resulting [`Solution`] can be turned into a
+//! [`Sketch`].
+//!
+//! The solver uses the Levenberg-Marquardt method with a numerical Jacobian.
+//! It finds a solution close to the initial positions of the points, so those
+//! should be a reasonable guess.
+
+use std::{error, fmt};
+
+use crate::Sketch;
+
+/// Identifies a point in a [`ConstraintSystem`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct PointId(usize);
+
+/// Identifies an arc in a [`ConstraintSystem`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct ArcId(usize);
+
+/// A line segment between two points of a [`ConstraintSystem`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Segment(pub PointId, pub PointId);
+
+/// A constraint in a [`ConstraintSystem`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Constraint {
+    /// The two points are at the same position
+    Coincident(PointId, PointId),
+
+    /// The segment is parallel to the x-axis
+    Horizontal(Segment),
+
+    /// The segment is parallel to the y-axis
+    Vertical(Segment),
+
+    /// The two points are the given distance apart
+    Distance(PointId, PointId, f64),
+
+    /// The arc has the given radius
+    Radius(ArcId, f64),
+}
+
+/// A system of points and the constraints between them
+#[derive(Clone, Debug, Default)]
+pub struct ConstraintSystem {
+    points: Vec<[f64; 2]>,
+
+    // For every coordinate of every point, the index of its variable, if the
+    // coordinate is free.
+    variables: Vec<[Option<usize>; 2]>,
+    num_variables: usize,
+
+    arcs: Vec<Arc>,
+    constraints: Vec<Constraint>,
+}
+
+impl ConstraintSystem {
+    /// Construct an empty instance of `ConstraintSystem`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a point to the system
+    ///
+    /// `free` marks the coordinates (x and y) that the solver may change. For
+    /// those, `position` is the initial guess.
+    pub fn add_point(
+        &mut self,
+        position: [f64; 2],
+        free: [bool; 2],
+    ) -> PointId {
+        let variables = free.map(|free| {
+            if free {
+                self.num_variables += 1;
+                Some(self.num_variables - 1)
+            } else {
+                None
+            }
+        });
+
+        self.points.push(position);
+        self.variables.push(variables);
+
+        PointId(self.points.len() - 1)
+    }
+
+    /// Add a circular arc to the system
+    ///
+    /// The arc runs from `start` to `end`, around `center`. The solver keeps
+    /// `start` and `end` at the same distance from `center`.
+    pub fn add_arc(
+        &mut self,
+        center: PointId,
+        start: PointId,
+        end: PointId,
+    ) -> ArcId {
+        self.arcs.push(Arc { center, start, end });
+        ArcId(self.arcs.len() - 1)
+    }
+
+    /// Add a constraint to the system
+    ///
+    /// Returns the index of the constraint, which is used to refer to it in
+    /// diagnostics.
+    pub fn add_constraint(&mut self, constraint: Constraint) -> usize {
+        self.constraints.push(constraint);
+        self.constraints.len() - 1
+    }
+
+    /// Solve the system
+    ///
+    /// Returns [`SolveError::Conflict`], if the constraints can't be satisfied
+    /// at the same time. If the system is under-constrained, a solution is
+    /// still returned, but [`Solution::degrees_of_freedom`] is non-zero.
+    pub fn solve(&self) -> Result<Solution, SolveError> {
+        let mut variables = vec![0.; self.num_variables];
+        for (position, point_variables) in
+            self.points.iter().zip(&self.variables)
+        {
+            for (coord, variable) in position.iter().zip(point_variables) {
+                if let Some(variable) = variable {
+                    variables[*variable] = *coord;
+                }
+            }
+        }
+
+        let variables = self.minimize(variables);
+
+        let residuals = self.residuals(&variables);
+        let mut conflicting: Vec<_> = residuals
+            .iter()
+            .filter(|residual| residual.value.abs() > RESIDUAL_TOLERANCE)
+            .filter_map(|residual| residual.constraint)
+            .collect();
+        conflicting.dedup();
+        if residuals
+            .iter()
+            .any(|residual| residual.value.abs() > RESIDUAL_TOLERANCE)
+        {
+            return Err(SolveError::Conflict {
+                constraints: conflicting,
+            });
+        }
+
+        let (rank, mut redundant_constraints) = self.analyze(&variables);
+        redundant_constraints.dedup();
+
+        let points = (0..self.points.len())
+            .map(|index| self.position(&variables, PointId(index)))
+            .collect();
+
+        Ok(Solution {
+            points,
+            degrees_of_freedom: self.num_variables - rank,
+            redundant_constraints,
+        })
+    }
+
+    fn position(&self, variables: &[f64], point: PointId) -> [f64; 2] {
+        let mut position = self.points[point.0];
+
+        for (coord, variable) in
+            position.iter_mut().zip(self.variables[point.0])
+        {
+            if let Some(variable) = variable {
+                *coord = variables[variable];
+            }
+        }
+
+        position
+    }
+
+    fn residuals(&self, variables: &[f64]) -> Vec<Residual> {
+        let position = |point| self.position(variables, point);
+        let distance = |a, b| {
+            let [a, b] = [position(a), position(b)];
+            (a[0] - b[0]).hypot(a[1] - b[1])
+        };
+
+        let mut residuals = Vec::new();
+
+        for arc in &self.arcs {
+            residuals.push(Residual {
+                constraint: None,
+                value: distance(arc.center, arc.end)
+                    - distance(arc.center, arc.start),
+            });
+        }
+
+        for (index, constraint) in self.constraints.iter().enumerate() {
+            let mut push = |value| {
+                residuals.push(Residual {
+                    constraint: Some(index),
+                    value,
+                })
+            };
+
+            match *constraint {
+                Constraint::Coincident(a, b) => {
+                    let [a, b] = [position(a), position(b)];
+                    push(a[0] - b[0]);
+                    push(a[1] - b[1]);
+                }
+                Constraint::Horizontal(Segment(a, b)) => {
+                    push(position(a)[1] - position(b)[1]);
+                }
+                Constraint::Vertical(Segment(a, b)) => {
+                    push(position(a)[0] - position(b)[0]);
+                }
+                Constraint::Distance(a, b, value) => {
+                    push(distance(a, b) - value);
+                }
+                Constraint::Radius(arc, value) => {
+                    let arc = &self.arcs[arc.0];
+                    push(distance(arc.center, arc.start) - value);
+                }
+            }
+        }
+
+        residuals
+    }
+
+    fn jacobian(&self, variables: &[f64]) -> Vec<Vec<f64>> {
+        let num_residuals = self.residuals(variables).len();
+        let mut jacobian = vec![vec![0.; self.num_variables]; num_residuals];
+
+        let mut variables = variables.to_vec();
+        for i in 0..self.num_variables {
+            let original = variables[i];
+            let step = JACOBIAN_STEP * original.abs().max(1.);
+
+            variables[i] = original + step;
+            let forward = self.residuals(&variables);
+            variables[i] = original - step;
+            let backward = self.residuals(&variables);
+            variables[i] = original;
+
+            for (row, (f, b)) in
+                jacobian.iter_mut().zip(forward.iter().zip(&backward))
+            {
+                row[i] = (f.value - b.value) / (2. * step);
+            }
+        }
+
+        jacobian
+    }
+
+    fn cost(&self, variables: &[f64]) -> f64 {
+        self.residuals(variables)
+            .iter()
+            .map(|residual| residual.value * residual.value)
+            .sum()
+    }
+
+    /// Minimize the sum of squared residuals, using Levenberg-Marquardt
+    fn minimize(&self, mut variables: Vec<f64>) -> Vec<f64> {
+        let n = self.num_variables;
+
+        let mut cost = self.cost(&variables);
+        let mut lambda = 1e-3;
+
+        for _ in 0..MAX_ITERATIONS {
+            if cost < CONVERGED_COST || lambda > MAX_DAMPING {
+                break;
+            }
+
+            let residuals = self.residuals(&variables);
+            let jacobian = self.jacobian(&variables);
+
+            // Set up the normal equations.
+            let mut jtj = vec![vec![0.; n]; n];
+            let mut jtr = vec![0.; n];
+            for (row, residual) in jacobian.iter().zip(&residuals) {
+                for p in 0..n {
+                    jtr[p] -= row[p] * residual.value;
+                    for q in 0..n {
+                        jtj[p][q] += row[p] * row[q];
+                    }
+                }
+            }
+
+            let mut damped = jtj;
+            for (p, row) in damped.iter_mut().enumerate() {
+                row[p] += lambda * (1. + row[p]);
+            }
+
+            let candidate = solve_linear(damped, jtr).map(|step| {
+                variables
+                    .iter()
+                    .zip(step)
+                    .map(|(variable, step)| variable + step)
+                    .collect::<Vec<_>>()
+            });
+
+            match candidate {
+                Some(candidate) if self.cost(&candidate) < cost => {
+                    cost = self.cost(&candidate);
+                    variables = candidate;
+                    lambda = (lambda / 10.).max(MIN_DAMPING);
+                }
+                _ => {
+                    lambda *= 10.;
+                }
+            }
+        }
+
+        variables
+    }
+
+    /// Compute the rank of the Jacobian and find redundant constraints
+    ///
+    /// A constraint is redundant, if none of its rows in the Jacobian are
+    /// linearly independent of the rows that came before.
+    fn analyze(&self, variables: &[f64]) -> (usize, Vec<usize>) {
+        let residuals = self.residuals(variables);
+        let jacobian = self.jacobian(variables);
+
+        let mut basis: Vec<Vec<f64>> = Vec::new();
+        let mut redundant = Vec::new();
+        let mut constraint_is_independent = false;
+
+        for (i, (row, residual)) in jacobian.iter().zip(&residuals).enumerate()
+        {
+            let length = norm(row);
+
+            let mut row = row.clone();
+            for b in &basis {
+                let projection = dot(&row, b);
+                for (r, b) in row.iter_mut().zip(b) {
+                    *r -= projection * b;
+                }
+            }
+
+            let remaining = norm(&row);
+            if remaining > RANK_TOLERANCE * length.max(1.) {
+                basis.push(row.iter().map(|r| r / remaining).collect());
+                constraint_is_independent = true;
+            }
+
+            // Once we've seen the last row of a constraint, we know whether it
+            // is redundant.
+            let is_last_row = residuals
+                .get(i + 1)
+                .map(|next| next.constraint != residual.constraint)
+                .unwrap_or(true);
+            if is_last_row {
+                if let Some(constraint) = residual.constraint {
+                    if !constraint_is_independent {
+                        redundant.push(constraint);
+                    }
+                }
+                constraint_is_independent = false;
+            }
+        }
+
+        (basis.len(), redundant)
+    }
+}
+
+/// A solution of a [`ConstraintSystem`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Solution {
+    points: Vec<[f64; 2]>,
+    degrees_of_freedom: usize,
+    redundant_constraints: Vec<usize>,
+}
+
+impl Solution {
+    /// Access the solved position of a point
+    pub fn point(&self, point: PointId) -> [f64; 2] {
+        self.points[point.0]
+    }
+
+    /// The number of degrees of freedom that the constraints leave open
+    ///
+    /// If this is non-zero, the system is under-constrained: The free
+    /// coordinates could still change without violating any constraints, and
+    /// the solution is just the one closest to the initial guess.
+    pub fn degrees_of_freedom(&self) -> usize {
+        self.degrees_of_freedom
+    }
+
+    /// The indices of constraints that are implied by the others
+    ///
+    /// These constraints don't conflict with the others, but they don't add
+    /// any information either.
+    pub fn redundant_constraints(&self) -> &[usize] {
+        &self.redundant_constraints
+    }
+
+    /// Create a sketch from the solved positions of the given points
+    pub fn to_sketch(&self, points: &[PointId]) -> Sketch {
+        Sketch::from_points(
+            points.iter().map(|&point| self.point(point)).collect(),
+        )
+    }
+}
+
+/// Error solving a [`ConstraintSystem`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SolveError {
+    /// The constraints can't be satisfied at the same time
+    Conflict {
+        /// The indices of the constraints that are violated by the best fit
+        /// the solver could find
+        constraints: Vec<usize>,
+    },
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Conflict { constraints } => {
+                write!(f, "Conflicting constraints: {:?}", constraints)
+            }
+        }
+    }
+}
+
+impl error::Error for SolveError {}
+
+#[derive(Clone, Copy, Debug)]
+struct Arc {
+    center: PointId,
+    start: PointId,
+    end: PointId,
+}
+
+struct Residual {
+    constraint: Option<usize>,
+    value: f64,
+}
+
+const MAX_ITERATIONS: usize = 200;
+const CONVERGED_COST: f64 = 1e-24;
+const MIN_DAMPING: f64 = 1e-12;
+const MAX_DAMPING: f64 = 1e12;
+const RESIDUAL_TOLERANCE: f64 = 1e-9;
+const RANK_TOLERANCE: f64 = 1e-6;
+const JACOBIAN_STEP: f64 = 1e-7;
+
+/// Solve a linear system using Gaussian elimination with partial pivoting
+///
+/// Returns `None`, if the system is singular.
+fn solve_linear(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+
+    for column in 0..n {
+        let pivot = (column..n).max_by(|&i, &j| {
+            a[i][column].abs().total_cmp(&a[j][column].abs())
+        })?;
+        if a[pivot][column].abs() < f64::MIN_POSITIVE {
+            return None;
+        }
+
+        a.swap(column, pivot);
+        b.swap(column, pivot);
+
+        for row in column + 1..n {
+            let factor = a[row][column] / a[column][column];
+            for k in column..n {
+                a[row][k] -= factor * a[column][k];
+            }
+            b[row] -= factor * b[column];
+        }
+    }
+
+    let mut x = vec![0.; n];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+
+    Some(x)
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+fn norm(a: &[f64]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Constraint, ConstraintSystem, PointId, Segment, SolveError};
+
+    /// A rectangle with one fixed corner, and the others roughly in place
+    fn rectangle() -> (ConstraintSystem, [PointId; 4]) {
+        let mut system = ConstraintSystem::new();
+
+        let a = system.add_point([0., 0.], [false, false]);
+        let b = system.add_point([3.8, 0.3], [true, true]);
+        let c = system.add_point([4.2, 2.7], [true, true]);
+        let d = system.add_point([0.3, 3.2], [true, true]);
+
+        system.add_constraint(Constraint::Horizontal(Segment(a, b)));
+        system.add_constraint(Constraint::Vertical(Segment(b, c)));
+        system.add_constraint(Constraint::Horizontal(Segment(c, d)));
+        system.add_constraint(Constraint::Vertical(Segment(d, a)));
+        system.add_constraint(Constraint::Distance(a, b, 4.));
+
+        (system, [a, b, c, d])
+    }
+
+    #[test]
+    fn rectangle_solves_exactly() -> Result<(), SolveError> {
+        let (mut system, [a, b, c, d]) = rectangle();
+        system.add_constraint(Constraint::Distance(b, c, 3.));
+
+        let solution = system.solve()?;
+
+        for (point, expected) in
+            [(a, [0., 0.]), (b, [4., 0.]), (c, [4., 3.]), (d, [0., 3.])]
+        {
+            let [x, y] = solution.point(point);
+            assert!((x - expected[0]).abs() < 1e-9);
+            assert!((y - expected[1]).abs() < 1e-9);
+        }
+
+        assert_eq!(solution.degrees_of_freedom(), 0);
+        assert!(solution.redundant_constraints().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn under_constrained() -> Result<(), SolveError> {
+        let (system, _) = rectangle();
+
+        let solution = system.solve()?;
+        assert_eq!(solution.degrees_of_freedom(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn conflict() {
+        let (mut system, [a, b, c, _]) = rectangle();
+        system.add_constraint(Constraint::Distance(b, c, 3.));
+        let conflicting = system.add_constraint(Constraint::Distance(a, c, 6.));
+
+        match system.solve() {
+            Err(SolveError::Conflict { constraints }) => {
+                assert!(constraints.contains(&conflicting));
+            }
+            result => panic!("Expected conflict, got {:?}", result),
+        }
+    }
+}
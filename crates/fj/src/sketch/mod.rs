@@ -0,0 +1,5 @@
+//! Tools for building sketches
+//!
+//! See [`Sketch`](crate::Sketch) for the sketch itself.
+
+pub mod constraints;
@@ -13,12 +13,25 @@ pub struct Sweep {
 
     /// The length and direction of the sweep
     path: [f64; 3],
+
+    /// The scale of the shape along the path
+    scale_profile: ScaleProfile,
 }
 
 impl Sweep {
     /// Create a `Sweep` along a straight path
     pub fn from_path(shape: Shape2d, path: [f64; 3]) -> Self {
-        Self { shape, path }
+        Self {
+            shape,
+            path,
+            scale_profile: ScaleProfile::default(),
+        }
+    }
+
+    /// Scale the shape along the path, according to the provided profile
+    pub fn with_scale_profile(mut self, scale_profile: ScaleProfile) -> Self {
+        self.scale_profile = scale_profile;
+        self
     }
 
     /// Access the shape being swept
@@ -30,6 +43,44 @@ impl Sweep {
     pub fn path(&self) -> [f64; 3] {
         self.path
     }
+
+    /// Access the scale profile of the sweep
+    pub fn scale_profile(&self) -> &ScaleProfile {
+        &self.scale_profile
+    }
+}
+
+/// The scale of a swept shape along the path of the sweep
+///
+/// The scale is defined in terms of the normalized path length `t`, which is
+/// `0.` at the start of the path and `1.` at its end. The shape is scaled
+/// about its centroid.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub enum ScaleProfile {
+    /// The same scale along the whole path
+    Constant(f64),
+
+    /// A scale that changes linearly from the start to the end of the path
+    Linear {
+        /// The scale at the start of the path
+        start: f64,
+
+        /// The scale at the end of the path
+        end: f64,
+    },
+
+    /// A scale that is interpolated linearly between `(t, scale)` pairs
+    ///
+    /// Before the first and after the last pair, the scale stays constant.
+    PiecewiseLinear(Vec<(f64, f64)>),
+}
+
+impl Default for ScaleProfile {
+    fn default() -> Self {
+        Self::Constant(1.)
+    }
 }
 
 impl From<Sweep> for Shape {
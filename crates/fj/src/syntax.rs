@@ -3,6 +3,28 @@
 //! This model defines extension traits, which provide convenient syntax for
 //! the various operations defined in this trait.
 
+/// Convenient syntax to create an [`fj::Annotated`]
+///
+/// [`fj::Annotated`]: crate::Annotated
+pub trait Annotate {
+    /// Annotate `self` with the provided annotations
+    fn annotate(&self, annotations: Vec<crate::Annotation>)
+        -> crate::Annotated;
+}
+
+impl<T> Annotate for T
+where
+    T: Clone + Into<crate::Shape>,
+{
+    fn annotate(
+        &self,
+        annotations: Vec<crate::Annotation>,
+    ) -> crate::Annotated {
+        let shape = self.clone().into();
+        crate::Annotated::from_shape(shape, annotations)
+    }
+}
+
 /// Convenient syntax to create an [`fj::Difference2d`]
 ///
 /// [`fj::Difference2d`]: crate::Difference2d
@@ -28,6 +50,28 @@ where
     }
 }
 
+/// Convenient syntax to create an [`fj::Fillet`]
+///
+/// [`fj::Fillet`]: crate::Fillet
+pub trait Fillet {
+    /// Round all edges of `self` with the given radius
+    ///
+    /// Use [`fj::Fillet::within`] to only round some of them.
+    ///
+    /// [`fj::Fillet::within`]: crate::Fillet::within
+    fn fillet(&self, radius: f64) -> crate::Fillet;
+}
+
+impl<T> Fillet for T
+where
+    T: Clone + Into<crate::Shape>,
+{
+    fn fillet(&self, radius: f64) -> crate::Fillet {
+        let shape = self.clone().into();
+        crate::Fillet::from_shape(shape, radius)
+    }
+}
+
 /// Convenient syntax to create an [`fj::Group`]
 ///
 /// [`fj::Group`]: crate::Group
@@ -53,6 +97,125 @@ where
     }
 }
 
+/// Convenient syntax to create an [`fj::Materialized`]
+///
+/// [`fj::Materialized`]: crate::Materialized
+pub trait Materialize {
+    /// Define the materials that can be assigned to the faces of `self`
+    fn with_materials(
+        &self,
+        materials: Vec<crate::Material>,
+    ) -> crate::Materialized;
+}
+
+impl<T> Materialize for T
+where
+    T: Clone + Into<crate::Shape>,
+{
+    fn with_materials(
+        &self,
+        materials: Vec<crate::Material>,
+    ) -> crate::Materialized {
+        let shape = self.clone().into();
+        crate::Materialized::from_shape(shape, materials)
+    }
+}
+
+/// Convenient syntax to create an [`fj::Part`]
+///
+/// [`fj::Part`]: crate::Part
+pub trait Label {
+    /// Create a part from `self`, with the provided label
+    fn label(&self, label: impl Into<String>) -> crate::Part;
+}
+
+impl<T> Label for T
+where
+    T: Clone + Into<crate::Shape>,
+{
+    fn label(&self, label: impl Into<String>) -> crate::Part {
+        let shape = self.clone().into();
+        crate::Part::from_shape(shape).with_label(label)
+    }
+}
+
+/// Convenient syntax to create an [`fj::Mirror`]
+///
+/// [`fj::Mirror`]: crate::Mirror
+pub trait Mirror {
+    /// Reflect `self` across the plane through `origin`, with the given
+    /// `normal`
+    fn mirror(&self, origin: [f64; 3], normal: [f64; 3]) -> crate::Mirror;
+}
+
+impl<T> Mirror for T
+where
+    T: Clone + Into<crate::Shape>,
+{
+    fn mirror(&self, origin: [f64; 3], normal: [f64; 3]) -> crate::Mirror {
+        let shape = self.clone().into();
+        crate::Mirror {
+            shape,
+            plane_origin: origin,
+            plane_normal: normal,
+        }
+    }
+}
+
+/// Convenient syntax to create an [`fj::Revolve`]
+///
+/// [`fj::Revolve`]: crate::Revolve
+pub trait Revolve {
+    /// Revolve `self` around an axis
+    ///
+    /// See [`fj::Revolve`] for how `angle` is interpreted.
+    ///
+    /// [`fj::Revolve`]: crate::Revolve
+    fn revolve(
+        &self,
+        axis_origin: [f64; 3],
+        axis_direction: [f64; 3],
+        angle: crate::Angle,
+    ) -> crate::Revolve;
+}
+
+impl<T> Revolve for T
+where
+    T: Clone + Into<crate::Shape2d>,
+{
+    fn revolve(
+        &self,
+        axis_origin: [f64; 3],
+        axis_direction: [f64; 3],
+        angle: crate::Angle,
+    ) -> crate::Revolve {
+        let shape = self.clone().into();
+        crate::Revolve::from_axis(shape, axis_origin, axis_direction, angle)
+    }
+}
+
+/// Convenient syntax to create an [`fj::Shell`]
+///
+/// [`fj::Shell`]: crate::Shell
+pub trait Shell {
+    /// Hollow out `self`, removing the selected faces
+    ///
+    /// Use [`fj::Shell::closed`] to leave a closed cavity instead.
+    ///
+    /// [`fj::Shell::closed`]: crate::Shell::closed
+    fn shell(&self, thickness: f64, open: crate::FaceSelector) -> crate::Shell;
+}
+
+impl<T> Shell for T
+where
+    T: Clone + Into<crate::Shape>,
+{
+    fn shell(&self, thickness: f64, open: crate::FaceSelector) -> crate::Shell {
+        let shape = self.clone().into();
+        crate::Shell::from_shape(shape, thickness, open)
+    }
+}
+
 /// Convenient syntax to create an [`fj::Sketch`]
 ///
 /// [`fj::Sketch`]: crate::Sketch
@@ -73,6 +236,31 @@ where
     }
 }
 
+/// Convenient syntax to create an [`fj::Difference`]
+///
+/// [`fj::Difference`]: crate::Difference
+pub trait Subtract {
+    /// Subtract `other` from `self`
+    fn subtract<Other>(&self, other: &Other) -> crate::Difference
+    where
+        Other: Clone + Into<crate::Shape>;
+}
+
+impl<T> Subtract for T
+where
+    T: Clone + Into<crate::Shape>,
+{
+    fn subtract<Other>(&self, other: &Other) -> crate::Difference
+    where
+        Other: Clone + Into<crate::Shape>,
+    {
+        let a = self.clone().into();
+        let b = other.clone().into();
+
+        crate::Difference { a, b }
+    }
+}
+
 /// Convenient syntax to create an [`fj::Sweep`]
 ///
 /// [`fj::Sweep`]: crate::Sweep
@@ -91,6 +279,27 @@ where
     }
 }
 
+/// Convenient syntax to create an [`fj::Tolerance`]
+///
+/// [`fj::Tolerance`]: crate::Tolerance
+pub trait Tolerance {
+    /// Approximate `self` with the given tolerance
+    ///
+    /// Overrides the tolerance that the rest of the model is approximated
+    /// with.
+    fn with_tolerance(&self, tolerance: f64) -> crate::Tolerance;
+}
+
+impl<T> Tolerance for T
+where
+    T: Clone + Into<crate::Shape>,
+{
+    fn with_tolerance(&self, tolerance: f64) -> crate::Tolerance {
+        let shape = self.clone().into();
+        crate::Tolerance::from_shape(shape, tolerance)
+    }
+}
+
 /// Convenient syntax to create an [`fj::Transform`]
 ///
 /// [`fj::Transform`]: crate::Transform
@@ -0,0 +1,39 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::Shape;
+
+/// A 3-dimensional shape that is approximated with its own tolerance
+///
+/// Curved geometry is approximated when a shape is turned into a triangle mesh.
+/// By default, the whole shape is approximated with the same tolerance. This
+/// overrides it for everything within the shape, unless it's overridden again
+/// further down. That allows approximating fine details more finely, without
+/// paying for it in large, simple parts of the model.
+///
+/// The tolerance is the maximum distance between the shape and its
+/// approximation. It must be positive.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Tolerance {
+    /// The shape that is approximated with the tolerance
+    pub shape: Shape,
+
+    /// The tolerance
+    pub tolerance: f64,
+}
+
+impl Tolerance {
+    /// Create a `Tolerance` from a shape and the tolerance to approximate it
+    /// with
+    pub fn from_shape(shape: Shape, tolerance: f64) -> Self {
+        Self { shape, tolerance }
+    }
+}
+
+impl From<Tolerance> for Shape {
+    fn from(shape: Tolerance) -> Self {
+        Self::Tolerance(Box::new(shape))
+    }
+}
@@ -16,3 +16,42 @@ pub fn model(
 
     cuboid.into()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use fj_testing::{Aabb, FaceSelector, Point};
+
+    #[test]
+    fn default_parameters() {
+        let cuboid = fj_testing::evaluate(super::model(&HashMap::new()));
+
+        fj_testing::assert_volume_near(&cuboid, 6., 1e-9);
+        fj_testing::assert_watertight(&cuboid);
+        fj_testing::assert_face_count(&cuboid, &FaceSelector::All, 6);
+        fj_testing::assert_fits_within(
+            &cuboid,
+            &Aabb {
+                min: Point::from([-1.5, -1., 0.]),
+                max: Point::from([1.5, 1., 1.]),
+            },
+        );
+    }
+
+    #[test]
+    fn custom_parameters() {
+        let args = HashMap::from([
+            ("x".to_string(), "4".to_string()),
+            ("z".to_string(), "0.5".to_string()),
+        ]);
+        let cuboid = fj_testing::evaluate(super::model(&args));
+
+        fj_testing::assert_volume_near(&cuboid, 4., 1e-9);
+        fj_testing::assert_face_count(
+            &cuboid,
+            &FaceSelector::normal([0., 0., 1.]),
+            1,
+        );
+    }
+}